@@ -1,4 +1,23 @@
 from .data_catalog import DataCatalog
-from .deltalake import PyDeltaTableError, RawDeltaTable, rust_core_version
+from .deltalake import (
+    CommitFailedError,
+    DeltaError,
+    DeltaFileExistsError,
+    DeltaFileNotFoundError,
+    DeltaNotImplementedError,
+    DeltaPermissionError,
+    DeltaProtocolError,
+    DeltaTimeoutError,
+    DeltaValueError,
+    PyDeltaTableError,
+    RawDeltaTable,
+    SchemaMismatchError,
+    TableNotFoundError,
+    TimestampBeforeFirstCommitError,
+    VersionNotInLogError,
+    rust_core_version,
+    set_io_runtime,
+)
 from .schema import DataType, Field, Schema
-from .table import DeltaTable, Metadata
+from .table import DeltaTable, Metadata, Protocol, VacuumMetrics
+from .writer import DeltaWriter, WriteMetrics, write_deltalake
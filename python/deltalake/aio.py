@@ -0,0 +1,113 @@
+"""
+An asyncio interface to Delta tables. Loading and updating a table run as Rust futures on a
+shared runtime and scans read the files on an executor, so the event loop is never blocked.
+"""
+import asyncio
+import functools
+from typing import Any, AsyncIterator, Iterator, List, Optional, Tuple, Union
+
+import pyarrow
+import pyarrow.fs as pa_fs
+
+from .deltalake import RawDeltaTable
+from .table import DeltaTable as _DeltaTable
+
+
+async def _await_abortable(awaitable_and_handle: Tuple[Any, Any]) -> Any:
+    awaitable, abort_handle = awaitable_and_handle
+    try:
+        return await awaitable
+    except asyncio.CancelledError:
+        # stop the Rust future as well
+        abort_handle.abort()
+        raise
+
+
+class DeltaTable(_DeltaTable):
+    """
+    A DeltaTable with asynchronous loading, updates and scans.
+
+    Create it with ``await DeltaTable.load(table_uri)``. All other methods of
+    :class:`deltalake.DeltaTable` are available and behave the same, including blocking IO,
+    so ``DeltaTable(table_uri)`` loads the table synchronously.
+    """
+
+    @classmethod
+    def from_table(cls, table: RawDeltaTable) -> "DeltaTable":
+        """
+        Wrap an already loaded RawDeltaTable.
+
+        :param table: the loaded table
+        """
+        delta_table = cls.__new__(cls)
+        delta_table._table = table
+        return delta_table
+
+    @classmethod
+    async def load(
+        cls,
+        table_uri: str,
+        version: Optional[int] = None,
+        case_sensitive: bool = False,
+    ) -> "DeltaTable":
+        """
+        Load the Delta Table from a path with an optional version.
+
+        :param table_uri: the path of the DeltaTable
+        :param version: version of the DeltaTable
+        :param case_sensitive: match column names in partition filters and projections case-sensitively
+        """
+        table = await _await_abortable(
+            RawDeltaTable.load_async(
+                table_uri, version=version, case_sensitive=case_sensitive
+            )
+        )
+        return cls.from_table(table)
+
+    async def update(self) -> None:
+        """
+        Update the DeltaTable to the latest version by incrementally applying newer versions.
+        Once the new commits were read, the update is applied even if the task is cancelled.
+        """
+        await _await_abortable(self._table.update_incremental_async())
+
+    async def to_pyarrow_batches(
+        self,
+        partitions: Optional[List[Tuple[str, str, Any]]] = None,
+        columns: Optional[List[str]] = None,
+        filesystem: Optional[Union[str, pa_fs.FileSystem]] = None,
+    ) -> AsyncIterator[pyarrow.RecordBatch]:
+        """
+        Scan the DeltaTable, yielding the RecordBatches of its files. The dataset is created and
+        each batch is read on the default executor of the event loop.
+
+        :param partitions: A list of partition filters, see help(DeltaTable.files_by_partitions) for filter syntax
+        :param columns: The columns to project. This can be a list of column names to include (order and duplicates will be preserved)
+        :param filesystem: A concrete implementation of the Pyarrow FileSystem or a fsspec-compatible interface. If None, the first file path will be used to determine the right FileSystem
+        """
+        loop = asyncio.get_event_loop()
+        batches = await loop.run_in_executor(
+            None,
+            functools.partial(
+                self._scan_batches,
+                partitions=partitions,
+                columns=columns,
+                filesystem=filesystem,
+            ),
+        )
+        while True:
+            batch = await loop.run_in_executor(None, next, batches, None)
+            if batch is None:
+                return
+            yield batch
+
+    def _scan_batches(
+        self,
+        partitions: Optional[List[Tuple[str, str, Any]]],
+        columns: Optional[List[str]],
+        filesystem: Optional[Union[str, pa_fs.FileSystem]],
+    ) -> Iterator[pyarrow.RecordBatch]:
+        if columns is not None:
+            columns = self._table.resolve_column_names(columns)
+        dataset = self.to_pyarrow_dataset(partitions=partitions, filesystem=filesystem)
+        return iter(dataset.to_batches(columns=columns))
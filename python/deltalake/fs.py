@@ -1,24 +1,229 @@
-from typing import Any, Dict, List, Optional
+import io
+import itertools
+import os
+import uuid
+from concurrent.futures import ThreadPoolExecutor
+from datetime import datetime, timezone
+from typing import Any, Dict, Iterator, List, Optional, Tuple, Union
 
 import pyarrow as pa
 from pyarrow.fs import FileInfo, FileSelector, FileSystemHandler, FileType
 
 from .deltalake import DeltaStorageFsBackend
 
+# Maximum number of requests in flight when fetching the metadata of many files
+MAX_CONCURRENT_REQUESTS = 32
+# Default maximum size of objects that can be appended to, as they are rewritten
+MAX_APPEND_SIZE = 100 * 1024 * 1024
+# Number of files listed per request when listing directories
+LIST_PAGE_SIZE = 1000
+# Infix of the temporary files written by write_atomic, followed by a UUID
+ATOMIC_WRITE_SUFFIX = ".tmp-"
+# Suffixes of the empty objects marking directories on object stores, appended to
+# the directory path, see the dir_markers option of DeltaStorageHandler
+DIR_MARKER_SUFFIX = "/"
+HADOOP_DIR_MARKER_SUFFIX = "_$folder$"
+# Sorts after every other character of a path, see _iter_file_infos
+MAX_PATH_CHAR = "\U0010ffff"
+
+# Object metadata from the storage: path, modification time in seconds, size, ETag
+# and version
+_ObjectTuple = Tuple[str, str, Optional[int], Optional[str], Optional[str]]
+
+
+class DeltaOutputStream(io.BytesIO):
+    """
+    Write-only stream that collects the written bytes in memory and uploads them to
+    the storage as a single object when closed. A stream that is garbage collected
+    without being closed is discarded instead of uploading partial data.
+    """
+
+    def __init__(
+        self, storage: DeltaStorageFsBackend, path: str, initial_bytes: bytes = b""
+    ) -> None:
+        super().__init__(initial_bytes)
+        self.seek(0, io.SEEK_END)
+        self._storage = storage
+        self._path = path
+        self._discarded = False
+
+    def readable(self) -> bool:
+        return False
+
+    def close(self) -> None:
+        if not self.closed and not self._discarded:
+            self._storage.put_obj(self._path, self.getvalue())
+        super().close()
+
+    def __del__(self) -> None:
+        self._discarded = True
+        super().__del__()
+
 
 class DeltaStorageHandler(FileSystemHandler):
     """
     DeltaStorageHander is a concrete implementations of a PyArrow FileSystemHandler.
+
+    :param table_uri: the URI of the table
+    :param max_append_size: the maximum size in bytes of an object opened with
+        open_append_stream, or 0 to disable appending
+    :param read_only: reject every operation that writes or deletes files with a
+        PermissionError
+    :param storage_options: options passed to the storage backend, e.g. credentials,
+        "timeout" to limit the duration of a request (e.g. "30s"), "max_retries"
+        and "retry_timeout" to configure the retries of requests failing with
+        transient errors or timing out, or "max_requests_per_second" and
+        "max_concurrent_requests" to make requests wait instead of exceeding these
+        limits. On S3 and GCS, "checksum" set to "md5" or "crc32c" (GCS only)
+        verifies the contents of objects. Unknown keys are logged, or
+        rejected with a ValueError if "unknown_options" is "error".
+    :param local_fast_path: for tables on the local filesystem, open files for
+        reading directly instead of going through the storage backend. Such reads
+        are not recorded in the metrics.
+    :param dir_markers: on object stores, mark directories with empty objects, so
+        that empty directories are visible: True for markers named like the
+        directory with a trailing "/", or "hadoop" for markers with the suffix
+        "_$folder$" written by Hadoop. create_dir writes markers, delete_dir
+        deletes directories with their markers and get_file_info reports marked
+        directories. By default, directories cannot be created or deleted.
     """
 
-    def __init__(self, table_uri: str) -> None:
-        self._storage = DeltaStorageFsBackend(table_uri)
+    def __init__(
+        self,
+        table_uri: str,
+        max_append_size: int = MAX_APPEND_SIZE,
+        read_only: bool = False,
+        storage_options: Optional[Dict[str, str]] = None,
+        local_fast_path: bool = True,
+        dir_markers: Union[bool, str] = False,
+    ) -> None:
+        if dir_markers not in (False, True, "hadoop"):
+            raise ValueError(
+                f'dir_markers must be True, False or "hadoop", found {dir_markers!r}'
+            )
+        self._storage = DeltaStorageFsBackend(table_uri, storage_options)
+        self._storage_options = storage_options
+        self._max_append_size = max_append_size
+        self._read_only = read_only
+        self._local_fast_path = local_fast_path
+        # metadata of files known from the Delta log, see file_infos_from_log
+        self._known_files: Dict[str, FileInfo] = {}
+        # normalized local table URIs are absolute paths without a scheme
+        self._is_local = "://" not in self.table_uri
+        self._dir_markers = dir_markers
+        self._dir_marker_suffix: Optional[str] = None
+        if dir_markers == "hadoop" and not self._is_local:
+            self._dir_marker_suffix = HADOOP_DIR_MARKER_SUFFIX
+        elif dir_markers and not self._is_local:
+            self._dir_marker_suffix = DIR_MARKER_SUFFIX
+
+    @property
+    def table_uri(self) -> str:
+        """
+        The normalized URI of the table the handler was created for.
+        """
+        return self._storage.table_uri()
 
     def __eq__(self, other: Any) -> bool:
-        return NotImplemented
+        if not isinstance(other, DeltaStorageHandler):
+            return NotImplemented
+        return self.table_uri == other.table_uri
+
+    def __hash__(self) -> int:
+        return hash(self.table_uri)
 
     def __ne__(self, other: Any) -> bool:
-        return NotImplemented
+        if not isinstance(other, DeltaStorageHandler):
+            return NotImplemented
+        return not self.__eq__(other)
+
+    def __reduce__(
+        self,
+    ) -> Tuple[
+        Any, Tuple[str, int, bool, Optional[Dict[str, str]], bool, Union[bool, str]]
+    ]:
+        # the storage backend cannot be pickled, so it is rebuilt from the table URI
+        # and options, e.g. when the handler is shipped to a Dask or Ray worker
+        return DeltaStorageHandler, (
+            self.table_uri,
+            self._max_append_size,
+            self._read_only,
+            self._storage_options,
+            self._local_fast_path,
+            self._dir_markers,
+        )
+
+    def __repr__(self) -> str:
+        return (
+            f"DeltaStorageHandler(table_uri={self.table_uri!r}, "
+            f"read_only={self._read_only})"
+        )
+
+    def _check_writable(self) -> None:
+        if self._read_only:
+            raise PermissionError(f"{self!r} is read-only")
+
+    def _dir_marker(self, path: str) -> str:
+        assert self._dir_marker_suffix is not None
+        return path.rstrip("/") + self._dir_marker_suffix
+
+    def _marked_directory(self, path: str) -> Optional[str]:
+        """Returns the directory marked by the object at path, if it is a marker."""
+        suffix = self._dir_marker_suffix
+        if suffix is None or not path.endswith(suffix):
+            return None
+        return path[: -len(suffix)]
+
+    def _local_path(self, path: str) -> Optional[str]:
+        if self._is_local and self._local_fast_path:
+            return self._storage.normalize_path(path)
+        return None
+
+    def get_metrics(self) -> Dict[str, Dict[str, float]]:
+        """
+        Get the number of calls, the transferred bytes and the total latency of each
+        storage operation since the handler was created or the metrics were reset.
+
+        A call retried after a transient error counts once.
+
+        :return: for each operation (head, get, list, put, rename and delete), a dict
+            with the "calls", "bytes" and "latency_seconds". If requests are rate
+            limited, "throttle" has the number of calls that waited for the limiter
+            and their total wait time, and "in_flight" the calls currently running.
+        """
+        metrics: Dict[str, Dict[str, float]] = {
+            name: {"calls": calls, "bytes": num_bytes, "latency_seconds": latency}
+            for name, calls, num_bytes, latency in self._storage.metrics()
+        }
+        stats = self._storage.rate_limit_stats()
+        if stats is not None:
+            throttled, wait_seconds, in_flight = stats
+            metrics["throttle"] = {
+                "calls": throttled,
+                "bytes": 0,
+                "latency_seconds": wait_seconds,
+                "in_flight": in_flight,
+            }
+        return metrics
+
+    def reset_metrics(self) -> None:
+        """
+        Set all metrics returned by get_metrics to zero, except the calls in flight.
+        """
+        self._storage.reset_metrics()
+
+    def is_safe_for_concurrent_writes(self) -> bool:
+        """
+        Whether several writers can safely commit to the table concurrently.
+
+        This is the case if the storage backend renames files without replacing
+        existing ones, e.g. on the local filesystem and GCS, and on S3 only if
+        "AWS_S3_LOCKING_PROVIDER" is "dynamodb", with the lock table given as
+        "DYNAMO_LOCK_TABLE_NAME". Read-only handlers are never safe for writes.
+
+        :return: True if concurrent commits cannot overwrite each other
+        """
+        return not self._read_only and self._storage.is_safe_for_concurrent_writes()
 
     def get_type_name(self) -> str:
         """
@@ -37,27 +242,287 @@ class DeltaStorageHandler(FileSystemHandler):
         """
         return self._storage.normalize_path(path)
 
+    def head(self, path: str) -> Dict[str, Any]:
+        """
+        Get the metadata of a file without pyarrow types.
+
+        :param path: the path of the file
+        :return: a dict with the path, the size in bytes (None if the storage does
+            not report it), the last modification time as an ISO 8601 string, the
+            ETag and the version of the object (None if the storage does not report
+            them)
+        """
+        [obj] = self._storage.head_objs([path], 1)
+        if obj is None:
+            raise FileNotFoundError(f"No such file: {path}")
+        return _object_dict(*obj)
+
+    def get_file_metadata(self, paths: List[str]) -> List[Optional[Dict[str, Any]]]:
+        """
+        Get the metadata of files without pyarrow types, including the ETags and
+        versions that FileInfo cannot carry, e.g. to detect changed files cheaply.
+
+        The metadata of the files is fetched concurrently.
+
+        :param paths: List of file paths
+        :return: a dict like those of head for every file, or None if the file does
+            not exist
+        """
+        objs = self._storage.head_objs(paths, MAX_CONCURRENT_REQUESTS)
+        return [None if obj is None else _object_dict(*obj) for obj in objs]
+
+    def exists(self, path: str) -> bool:
+        """
+        Check whether a file exists.
+
+        :param path: the path of the file
+        :return: whether the file exists
+        """
+        [obj] = self._storage.head_objs([path], 1)
+        return obj is not None
+
     def get_file_info(self, paths: List[str]) -> List[FileInfo]:
         """
         Get info for the given files.
 
+        The metadata of the files is fetched concurrently, except for files passed to
+        file_infos_from_log before. Files that do not exist have the type
+        FileType.NotFound, unless dir_markers is set and a directory marker exists
+        for them, which makes them a FileType.Directory.
+
         :param paths: List of file paths
         :return: list of file info objects
         """
+        unknown = [path for path in paths if path not in self._known_files]
+        objects = iter(self._storage.head_objs(unknown, MAX_CONCURRENT_REQUESTS))
         infos = []
         for path in paths:
-            path, secs = self._storage.head_obj(path)
-            infos.append(FileInfo(path, type=FileType.File, mtime=float(secs)))
+            if path in self._known_files:
+                infos.append(self._known_files[path])
+                continue
+            obj = next(objects)
+            if obj is None:
+                infos.append(FileInfo(path, type=FileType.NotFound))
+            else:
+                obj_path, secs, size, _, _ = obj
+                infos.append(
+                    FileInfo(obj_path, type=FileType.File, mtime=float(secs), size=size)
+                )
+        if self._dir_marker_suffix is not None:
+            missing = [info.path for info in infos if info.type == FileType.NotFound]
+            markers = self._storage.head_objs(
+                [self._dir_marker(path) for path in missing], MAX_CONCURRENT_REQUESTS
+            )
+            directories = {
+                path for path, marker in zip(missing, markers) if marker is not None
+            }
+            infos = [
+                FileInfo(info.path, type=FileType.Directory)
+                if info.type == FileType.NotFound and info.path in directories
+                else info
+                for info in infos
+            ]
         return infos
 
-    def get_file_info_selector(self, selector: FileSelector) -> List[FileInfo]:
+    def file_infos_from_log(
+        self, files: List[Tuple[str, Optional[int], Optional[int]]]
+    ) -> List[FileInfo]:
+        """
+        Get info for files whose metadata is known from the Delta log, e.g. from
+        RawDeltaTable.file_infos, without requests to the storage.
+
+        The metadata is remembered, so that get_file_info answers for these files
+        without requests as well, until they are written or deleted through this
+        handler. Files with missing metadata are fetched with get_file_info.
+
+        :param files: the path, size in bytes and modification time in milliseconds
+            since the Unix epoch of each file, with None for unknown values
+        :return: list of file info objects, in the order of files
+        """
+        for path, size, mtime_ms in files:
+            if size is not None and mtime_ms is not None:
+                self._known_files[path] = FileInfo(
+                    path, type=FileType.File, mtime=mtime_ms / 1000, size=size
+                )
+        return self.get_file_info([path for path, _, _ in files])
+
+    def _forget(self, *paths: str) -> None:
+        for path in paths:
+            self._known_files.pop(path, None)
+
+    def get_file_info_selector(
+        self, selector: FileSelector, max_results: Optional[int] = None
+    ) -> List[FileInfo]:
         """
         Get info for the files defined by FileSelector.
 
+        Directories are derived from the paths of the files they contain, so empty
+        directories are only found on the local filesystem.
+
         :param selector: FileSelector object
+        :param max_results: stop listing once this many files and directories are
+            found, or None to list all of them
         :return: list of file info objects
         """
-        raise NotImplementedError
+        base_dir = selector.base_dir.rstrip("/")
+        infos = list(
+            itertools.islice(
+                self._iter_file_infos(base_dir, selector.recursive), max_results
+            )
+        )
+
+        if not infos and not selector.allow_not_found:
+            if not (self._is_local and os.path.isdir(base_dir)) and not (
+                self._dir_marker_suffix is not None
+                and self.exists(self._dir_marker(base_dir))
+            ):
+                raise FileNotFoundError(f"No such directory: {selector.base_dir}")
+        return infos
+
+    def list_paths_iter(
+        self, base_dir: str, recursive: bool = True
+    ) -> Iterator[FileInfo]:
+        """
+        Iterate over the files and directories below a directory, like
+        get_file_info_selector with allow_not_found, without holding all of them in
+        memory.
+
+        On object stores, the files are listed one page of LIST_PAGE_SIZE at a time,
+        when the iteration reaches it. Errors of the storage are raised from the
+        iteration, after the entries listed before. Local directories are walked
+        completely before the first entry is returned.
+
+        :param base_dir: the directory to list
+        :param recursive: also list the contents of subdirectories
+        :return: an iterator of file info objects, sorted by path
+        """
+        return self._iter_file_infos(base_dir.rstrip("/"), recursive)
+
+    def _iter_file_infos(self, base_dir: str, recursive: bool) -> Iterator[FileInfo]:
+        if self._is_local:
+            yield from self._walk_local(base_dir, recursive)
+            return
+        directories = set()
+        start_after = ""
+        truncated = True
+        while truncated:
+            objs, truncated = self._list_after(base_dir, start_after, LIST_PAGE_SIZE)
+            for obj_path, secs, size, _, _ in objs:
+                start_after = obj_path
+                # markers are listed like an empty file in the directory they mark
+                marked = self._marked_directory(obj_path)
+                relative = obj_path if marked is None else marked + "/"
+                parts = relative[len(base_dir) + 1 :].split("/")
+                depth = len(parts) if recursive else 1
+                for i in range(1, min(depth, len(parts) - 1) + 1):
+                    directory = "/".join([base_dir, *parts[:i]])
+                    if directory not in directories:
+                        directories.add(directory)
+                        yield FileInfo(directory, type=FileType.Directory)
+                if marked is None and len(parts) <= depth:
+                    yield FileInfo(
+                        obj_path, type=FileType.File, mtime=float(secs), size=size
+                    )
+                elif not recursive and len(parts) > 1:
+                    # there is no delimited listing, so the listing continues after
+                    # the last possible path inside the subdirectory instead
+                    skip_to = f"{base_dir}/{parts[0]}/{MAX_PATH_CHAR}"
+                    if skip_to > obj_path:
+                        start_after = skip_to
+                        truncated = True
+                        break
+
+    def _walk_local(self, base_dir: str, recursive: bool) -> List[FileInfo]:
+        """
+        Lists a local directory, and if recursive its subdirectories breadth-first,
+        scanning up to MAX_CONCURRENT_REQUESTS directories of a level concurrently.
+        """
+
+        def scan(directory: str) -> List[Tuple[FileInfo, bool]]:
+            """Returns the entries of a directory and whether to descend into them."""
+            try:
+                with os.scandir(directory) as entries:
+                    scanned = []
+                    for entry in entries:
+                        if entry.is_dir():
+                            info = FileInfo(entry.path, type=FileType.Directory)
+                            scanned.append((info, not entry.is_symlink()))
+                        elif entry.is_file():
+                            stat = entry.stat()
+                            info = FileInfo(
+                                entry.path,
+                                type=FileType.File,
+                                mtime=stat.st_mtime,
+                                size=stat.st_size,
+                            )
+                            scanned.append((info, False))
+                    return scanned
+            except (FileNotFoundError, NotADirectoryError):
+                return []
+
+        infos = []
+        level = [base_dir]
+        with ThreadPoolExecutor(max_workers=MAX_CONCURRENT_REQUESTS) as executor:
+            while level:
+                entries = [
+                    entry for scanned in executor.map(scan, level) for entry in scanned
+                ]
+                infos.extend(info for info, _ in entries)
+                level = [
+                    info.path for info, descend in entries if recursive and descend
+                ]
+        return sorted(infos, key=lambda info: info.path)
+
+    def list_page(
+        self,
+        base_dir: str,
+        page_token: Optional[str] = None,
+        max_results: int = LIST_PAGE_SIZE,
+    ) -> Tuple[List[Dict[str, Any]], Optional[str]]:
+        """
+        List a page of the files below a directory, recursively, in lexicographic order
+        of their paths.
+
+        On S3, every page is listed from where the previous one ended. Other storage
+        lists all files below the directory for every page.
+
+        :param base_dir: the directory to list
+        :param page_token: the continuation token returned with the previous page, or
+            None for the first page
+        :param max_results: the maximum number of files in the page
+        :return: the files, as dicts like those of head, and the opaque continuation
+            token of the next page, or None if this is the last page
+        """
+        if max_results < 1:
+            raise ValueError("max_results must be positive")
+        objs, truncated = self._list_after(
+            base_dir.rstrip("/"), page_token or "", max_results
+        )
+        next_token = objs[-1][0] if truncated else None
+        return [_object_dict(*obj) for obj in objs], next_token
+
+    def _list_after(
+        self, base_dir: str, start_after: str, max_results: int
+    ) -> Tuple[List[_ObjectTuple], bool]:
+        if not self._is_local:
+            return self._storage.list_objs_after(
+                base_dir + "/", start_after, max_results
+            )
+        # local directories are listed one by one, so all files are sorted here
+        paths = sorted(
+            path
+            for root, _, names in os.walk(base_dir)
+            for path in (os.path.join(root, name) for name in names)
+            if path > start_after
+        )
+        objs = []
+        for path in paths[:max_results]:
+            try:
+                stat = os.stat(path)
+            except FileNotFoundError:
+                continue
+            objs.append((path, str(int(stat.st_mtime)), stat.st_size, None, None))
+        return objs, len(paths) > max_results
 
     def create_dir(self, path: str, *, recursive: bool = True) -> None:
         """
@@ -65,28 +530,185 @@ class DeltaStorageHandler(FileSystemHandler):
 
         This function succeeds if the directory already exists.
 
+        Object stores have no directories, so this requires dir_markers, and writes
+        the marker of the directory, and if recursive those of its parents below the
+        table root. Directories of local tables are created on the filesystem.
+
         :param path: The path of the new directory.
         :param recursive: Create nested directories as well.
         """
-        raise NotImplementedError
-
-    def delete_dir(self, path: str) -> None:
+        if not self._dir_markers:
+            raise NotImplementedError("Directories are only created with dir_markers")
+        self._check_writable()
+        if self._is_local:
+            directory = self._storage.normalize_path(path)
+            if recursive:
+                os.makedirs(directory, exist_ok=True)
+            elif not os.path.isdir(directory):
+                os.mkdir(directory)
+            return
+        directories = [path.rstrip("/")]
+        while recursive and directories[-1].startswith(self.table_uri + "/"):
+            parent = directories[-1].rsplit("/", 1)[0]
+            if parent == self.table_uri:
+                break
+            directories.append(parent)
+        for directory in directories:
+            self._storage.put_obj(self._dir_marker(directory), b"")
+
+    def _delete_marked_dir(
+        self, path: str, include_dir: bool, dry_run: bool
+    ) -> Optional[List[str]]:
+        if self._dir_marker_suffix is None:
+            raise NotImplementedError(
+                "Only directories of local tables, or with dir_markers, can be deleted"
+            )
+        if not dry_run:
+            self._check_writable()
+            self._known_files.clear()
+        directory = path.rstrip("/")
+        marker = self._dir_marker(directory)
+        paths = []
+        start_after = ""
+        truncated = True
+        while truncated:
+            objs, truncated = self._list_after(directory, start_after, LIST_PAGE_SIZE)
+            paths.extend(obj[0] for obj in objs if obj[0] != marker)
+            if objs:
+                start_after = objs[-1][0]
+        if include_dir:
+            paths.append(marker)
+        if dry_run:
+            return paths
+        errors = [
+            error
+            for error in self._storage.delete_objs(paths, True, MAX_CONCURRENT_REQUESTS)
+            if error is not None
+        ]
+        if errors:
+            raise OSError("; ".join(errors))
+        return None
+
+    def _delete_local_dir(
+        self,
+        path: str,
+        include_dir: bool,
+        follow_symlinks: bool,
+        dry_run: bool,
+    ) -> Optional[List[str]]:
+        if not self._is_local:
+            return self._delete_marked_dir(path, include_dir, dry_run)
+        if not dry_run:
+            self._check_writable()
+            self._known_files.clear()
+        root = os.path.realpath(self.table_uri)
+        root_device = os.stat(root).st_dev
+        directory = self._storage.normalize_path(path)
+
+        def escapes(entry_path: str) -> bool:
+            real = os.path.realpath(entry_path)
+            if real != root and not real.startswith(root + os.sep):
+                return True
+            try:
+                # mounted filesystems belong to someone else, even inside the table
+                return os.stat(real).st_dev != root_device
+            except FileNotFoundError:
+                # a dangling link, which is removed without touching anything else
+                return False
+
+        if escapes(directory):
+            raise PermissionError(f"{directory} is outside of the table root {root}")
+        directory_is_link = os.path.islink(directory)
+        if directory_is_link and not follow_symlinks:
+            raise PermissionError(f"{directory} is a symlink")
+
+        # (path, is_dir) in deletion order, children before their directory
+        planned: List[Tuple[str, bool]] = []
+        refused: List[str] = []
+        visited = {os.path.realpath(directory)}
+
+        def plan(current: str) -> bool:
+            """Plans the deletion of the contents and returns whether all go."""
+            complete = True
+            with os.scandir(current) as entries:
+                for entry in entries:
+                    is_link = entry.is_symlink()
+                    if is_link and not follow_symlinks:
+                        complete = False
+                        continue
+                    if escapes(entry.path):
+                        refused.append(entry.path)
+                        complete = False
+                        continue
+                    if entry.is_dir():
+                        real = os.path.realpath(entry.path)
+                        if real in visited:
+                            # a link back into a directory being deleted anyway
+                            planned.append((entry.path, False))
+                            continue
+                        visited.add(real)
+                        if plan(entry.path):
+                            # links are removed, their targets only emptied
+                            planned.append((entry.path, not is_link))
+                        else:
+                            complete = False
+                    else:
+                        planned.append((entry.path, False))
+            return complete
+
+        complete = plan(directory)
+        if refused:
+            raise PermissionError(
+                f"Refusing to delete entries outside of the table root {root}: "
+                + ", ".join(refused)
+            )
+        if include_dir and complete:
+            planned.append((directory, not directory_is_link))
+        if dry_run:
+            return [entry_path for entry_path, _ in planned]
+        for entry_path, is_dir in planned:
+            if is_dir:
+                os.rmdir(entry_path)
+            else:
+                os.unlink(entry_path)
+        return None
+
+    def delete_dir(
+        self, path: str, *, follow_symlinks: bool = False, dry_run: bool = False
+    ) -> Optional[List[str]]:
         """
         Delete a directory and its contents, recursively.
 
+        Only directories of local tables can be deleted, or with dir_markers the
+        files below a directory on object stores and its marker. Nothing outside of
+        the table root is deleted: the root and the directory are resolved to
+        canonical paths first, and entries that escape the root or lie on another
+        mounted filesystem are refused with a PermissionError listing them, before
+        anything is deleted.
+
         :param path: The path of the directory to be deleted.
+        :param follow_symlinks: Delete the contents of symlinked directories inside
+            the table root, and the links themselves. By default, symlinks and the
+            directories containing them are kept.
+        :param dry_run: Only return the paths that would be deleted.
+        :return: The paths that would be deleted if dry_run is set, otherwise None.
         """
-        raise NotImplementedError
+        return self._delete_local_dir(path, True, follow_symlinks, dry_run)
 
-    def delete_dir_contents(self, path: str) -> None:
+    def delete_dir_contents(
+        self, path: str, *, follow_symlinks: bool = False, dry_run: bool = False
+    ) -> Optional[List[str]]:
         """
         Delete a directory’s contents, recursively.
 
         Like delete_dir, but doesn’t delete the directory itself.
 
         :param path: The path of the directory to be deleted.
+        :param follow_symlinks: As for delete_dir.
+        :param dry_run: Only return the paths that would be deleted.
+        :return: The paths that would be deleted if dry_run is set, otherwise None.
         """
-        raise NotImplementedError
+        return self._delete_local_dir(path, False, follow_symlinks, dry_run)
 
     def delete_root_dir_contents(self) -> None:
         """
@@ -94,7 +716,7 @@ class DeltaStorageHandler(FileSystemHandler):
 
         Like delete_dir_contents, but for the root directory (path is empty or “/”)
         """
-        raise NotImplementedError
+        self._delete_local_dir(self.table_uri, False, False, False)
 
     def delete_file(self, path: str) -> None:
         """
@@ -102,20 +724,163 @@ class DeltaStorageHandler(FileSystemHandler):
 
         :param path: The path of the file to be deleted.
         """
-        raise NotImplementedError
+        self._check_writable()
+        self._forget(path)
+        self._storage.delete_obj(path)
+
+    def delete_files(
+        self,
+        paths: List[str],
+        ignore_not_found: bool = False,
+        max_concurrent_requests: int = 32,
+    ) -> List[Optional[str]]:
+        """
+        Delete many files with concurrent requests.
+
+        Failing deletions do not stop the others.
+
+        :param paths: The paths of the files to be deleted.
+        :param ignore_not_found: Treat files that do not exist as deleted.
+        :param max_concurrent_requests: Maximum number of requests in flight.
+        :return: For each path, the error message of its deletion, or None if it
+            was deleted.
+        """
+        self._check_writable()
+        self._forget(*paths)
+        return self._storage.delete_objs(
+            paths, ignore_not_found, max_concurrent_requests
+        )
 
-    def move(self, src: str, dest: str) -> None:
+    def move(self, src: str, dest: str, overwrite: bool = False) -> None:
         """
-        Move / rename a file or directory.
+        Move / rename a file.
 
-        If the destination exists: - if it is a non-empty directory, an error is returned - otherwise,
-        if it has the same type as the source, it is replaced - otherwise,
-        behavior is unspecified (implementation-dependent).
+        If the destination exists, FileExistsError is raised unless overwrite is set.
+        Moves without overwrite are atomic if the storage supports renames that fail
+        on existing destinations. With overwrite, the file is copied and then
+        deleted, which is not atomic.
 
-        :param src: The path of the file or the directory to be moved.
-        :param dest: The destination path where the file or directory is moved to.
+        :param src: The path of the file to be moved.
+        :param dest: The destination path where the file is moved to.
+        :param overwrite: Replace the destination if it exists.
         """
-        raise NotImplementedError
+        self._check_writable()
+        self._forget(src, dest)
+        if overwrite:
+            self._storage.put_obj(dest, self._storage.get_obj(src))
+            self._storage.delete_obj(src)
+        else:
+            self._storage.rename_obj_noreplace(src, dest)
+
+    def put_if_absent(self, path: str, data: bytes) -> bool:
+        """
+        Write a file only if it does not exist yet.
+
+        The data is uploaded to a temporary file next to the target, which is then
+        renamed without replacing an existing target, the same way Delta commits are
+        written. This is atomic on the local filesystem and GCS, and on S3 only if
+        DynamoDB locking is enabled, otherwise DeltaError is raised. Azure does not
+        support it.
+
+        Conditional puts on entity tags are not offered, as none of the storage
+        backends exposes them.
+
+        :param path: The path of the file to be written.
+        :param data: The content of the file.
+        :return: True if the file was written, False if it already existed.
+        """
+        self._check_writable()
+        self._forget(path)
+        tmp_path = f"{path}.{uuid.uuid4()}.tmp"
+        self._storage.put_obj(tmp_path, data)
+        try:
+            self._storage.rename_obj_noreplace(tmp_path, path)
+        except FileExistsError:
+            self._storage.delete_obj(tmp_path)
+            return False
+        except BaseException:
+            self._storage.delete_obj(tmp_path)
+            raise
+        return True
+
+    def write_atomic(self, path: str, data: bytes) -> bool:
+        """
+        Publish a file atomically, without replacing an existing one.
+
+        The data is written to a temporary file ``<path>.tmp-<uuid>`` next to the
+        target, which is then renamed without replacing an existing target. The
+        temporary file is deleted if the rename fails. A process killed between the
+        write and the rename leaves it behind, see cleanup_atomic_writes.
+
+        If the storage cannot rename without replacing, e.g. S3 without DynamoDB
+        locking, the data is written directly to the target if it does not exist
+        yet. Readers never see partial data, as single puts are atomic, but two
+        concurrent writers may both succeed.
+
+        :param path: The path of the file to be written.
+        :param data: The content of the file.
+        :return: True if the destination already existed, in which case it is left
+            unchanged.
+        """
+        self._check_writable()
+        self._forget(path)
+        if not self._storage.is_safe_for_concurrent_writes():
+            [existing] = self._storage.head_objs([path], 1)
+            if existing is not None:
+                return True
+            self._storage.put_obj(path, data)
+            return False
+
+        tmp_path = f"{path}{ATOMIC_WRITE_SUFFIX}{uuid.uuid4()}"
+        try:
+            self._storage.put_obj(tmp_path, data)
+            self._storage.rename_obj_noreplace(tmp_path, path)
+        except FileExistsError:
+            self._storage.delete_obj(tmp_path)
+            return True
+        except BaseException:
+            # the original error is more useful than one of the cleanup
+            self._storage.delete_objs([tmp_path], True, 1)
+            raise
+        return False
+
+    def cleanup_atomic_writes(
+        self, path: str, min_age_seconds: float = 3600
+    ) -> List[str]:
+        """
+        Delete the temporary files left behind by interrupted write_atomic calls.
+
+        :param path: The path of the target file passed to write_atomic.
+        :param min_age_seconds: Only delete temporary files at least this old, so
+            that writes in progress are not disturbed.
+        :return: The paths of the deleted temporary files.
+        """
+        self._check_writable()
+        if self._is_local:
+            parent = os.path.dirname(path)
+        else:
+            parent = path.rsplit("/", 1)[0]
+        prefix = f"{path}{ATOMIC_WRITE_SUFFIX}"
+        cutoff = datetime.now(timezone.utc).timestamp() - min_age_seconds
+        try:
+            objs = self._storage.list_objs(parent)
+        except FileNotFoundError:
+            return []
+        leftovers = [
+            obj_path
+            for obj_path, modified, *_ in objs
+            if obj_path.startswith(prefix) and int(modified) <= cutoff
+        ]
+        errors = [
+            error
+            for error in self._storage.delete_objs(
+                leftovers, True, MAX_CONCURRENT_REQUESTS
+            )
+            if error is not None
+        ]
+        if errors:
+            raise OSError("; ".join(errors))
+        return leftovers
 
     def copy_file(self, src: str, dest: str) -> None:
         """
@@ -136,6 +901,9 @@ class DeltaStorageHandler(FileSystemHandler):
         :param source: The source to open for reading.
         :return:  NativeFile
         """
+        local_path = self._local_path(path)
+        if local_path is not None:
+            return pa.OSFile(local_path, mode="r")
         raw = self._storage.get_obj(path)
         return pa.BufferReader(pa.py_buffer(raw))
 
@@ -146,6 +914,9 @@ class DeltaStorageHandler(FileSystemHandler):
         :param source: The source to open for reading.
         :return:  NativeFile
         """
+        local_path = self._local_path(path)
+        if local_path is not None:
+            return pa.memory_map(local_path, mode="r")
         raw = self._storage.get_obj(path)
         return pa.BufferReader(pa.py_buffer(raw))
 
@@ -157,11 +928,16 @@ class DeltaStorageHandler(FileSystemHandler):
 
         If the target already exists, existing data is truncated.
 
+        The written data is kept in memory and uploaded as a single object when the
+        stream is closed.
+
         :param path: The source to open for writing.
         :param metadata: If not None, a mapping of string keys to string values.
         :return:  NativeFile
         """
-        raise NotImplementedError
+        self._check_writable()
+        self._forget(path)
+        return pa.PythonFile(DeltaOutputStream(self._storage, path), mode="w")
 
     def open_append_stream(
         self, path: str, metadata: Optional[Dict[str, Any]] = None
@@ -171,8 +947,46 @@ class DeltaStorageHandler(FileSystemHandler):
 
         If the target doesn’t exist, a new empty file is created.
 
+        The storage backends cannot append to objects, so the existing content is
+        read into memory and the whole object is rewritten when the stream is
+        closed. This is not atomic: concurrent appends to the same object lose data.
+        Objects larger than max_append_size cannot be opened.
+
         :param path: The source to open for writing.
         :param metadata: If not None, a mapping of string keys to string values.
         :return:  NativeFile
         """
-        raise NotImplementedError
+        self._check_writable()
+        self._forget(path)
+        if self._max_append_size <= 0:
+            raise NotImplementedError("Appending is disabled for this filesystem")
+        [info] = self.get_file_info([path])
+        content = b""
+        if info.type != FileType.NotFound:
+            size = info.size
+            if size is None or size <= self._max_append_size:
+                content = self._storage.get_obj(path)
+                size = len(content)
+            if size > self._max_append_size:
+                raise OSError(
+                    f"Cannot append to {path}: its size of {size} bytes exceeds "
+                    f"max_append_size of {self._max_append_size} bytes"
+                )
+        return pa.PythonFile(DeltaOutputStream(self._storage, path, content), mode="w")
+
+
+def _object_dict(
+    path: str,
+    secs: str,
+    size: Optional[int],
+    e_tag: Optional[str],
+    version: Optional[str],
+) -> Dict[str, Any]:
+    last_modified = datetime.fromtimestamp(int(secs), timezone.utc)
+    return {
+        "path": path,
+        "size": size,
+        "last_modified": last_modified.isoformat(),
+        "e_tag": e_tag,
+        "version": version,
+    }
@@ -0,0 +1,235 @@
+"""
+An fsspec filesystem backed by the storage backends of deltalake, for tools that read
+files through fsspec rather than pyarrow, e.g. pandas.
+
+Importing this module registers it for the "delta" protocol::
+
+    >>> import fsspec
+    >>> import deltalake.fsspec
+    >>> fs = fsspec.filesystem("delta", table_uri="s3://bucket/table")
+    >>> fs.ls("s3://bucket/table/_delta_log")
+
+Paths are given like to DeltaStorageHandler, as URIs or local paths below the table,
+optionally prefixed with "delta://".
+"""
+import os
+from typing import Any, Dict, List, Optional, Union
+
+from fsspec import AbstractFileSystem, register_implementation
+from fsspec.spec import AbstractBufferedFile
+from pyarrow.fs import FileInfo, FileType
+
+from .fs import DeltaStorageHandler
+
+
+class DeltaFileSystem(AbstractFileSystem):
+    """
+    fsspec filesystem on the storage of a Delta table.
+
+    Directories are derived from the paths of the files they contain, except on the
+    local filesystem. Files are uploaded as a whole when written.
+
+    :param table_uri: the URI of the table
+    :param storage_options: options passed to the storage backend, see
+        DeltaStorageHandler
+    :param read_only: reject every operation that writes or deletes files with a
+        PermissionError
+    """
+
+    protocol = "delta"
+    root_marker = ""
+
+    def __init__(
+        self,
+        table_uri: str,
+        storage_options: Optional[Dict[str, str]] = None,
+        read_only: bool = False,
+        **kwargs: Any,
+    ) -> None:
+        super().__init__(**kwargs)
+        self.handler = DeltaStorageHandler(
+            table_uri, storage_options=storage_options, read_only=read_only
+        )
+
+    @classmethod
+    def _strip_protocol(cls, path: str) -> str:
+        if path.startswith("delta://"):
+            path = path[len("delta://") :]
+        return path.rstrip("/") or path
+
+    def ls(self, path: str, detail: bool = True, **kwargs: Any) -> List[Any]:
+        """
+        List the files and directories in a directory, or the file at path.
+
+        :param path: the directory to list
+        :param detail: return dicts with the "name", "size", "type" and "mtime" of
+            each entry instead of their names
+        :return: the entries, sorted by name
+        """
+        path = self._strip_protocol(path)
+        entries = [
+            _info_dict(info)
+            for info in self.handler.list_paths_iter(path, recursive=False)
+        ]
+        if not entries:
+            entry = self.info(path)
+            entries = [] if entry["type"] == "directory" else [entry]
+        return entries if detail else [entry["name"] for entry in entries]
+
+    def info(self, path: str, **kwargs: Any) -> Dict[str, Any]:
+        """
+        Get the details of a file or directory.
+
+        :param path: the path of the file or directory
+        :return: a dict with the "name", "size", "type" and "mtime"
+        """
+        path = self._strip_protocol(path)
+        if self.handler._is_local and os.path.isdir(path):
+            return _info_dict(FileInfo(path, type=FileType.Directory))
+        [info] = self.handler.get_file_info([path])
+        if info.type == FileType.NotFound:
+            if next(self.handler.list_paths_iter(path, recursive=False), None) is None:
+                raise FileNotFoundError(path)
+            info = FileInfo(path, type=FileType.Directory)
+        return _info_dict(info)
+
+    def cat_file(
+        self,
+        path: str,
+        start: Optional[int] = None,
+        end: Optional[int] = None,
+        **kwargs: Any,
+    ) -> bytes:
+        """
+        Read a file, or the bytes from start up to end of it. Only the range is
+        requested from the storage.
+
+        :param path: the path of the file
+        :param start: the first byte to read, counted from the end if negative
+        :param end: the byte to stop before, counted from the end if negative
+        :return: the content
+        """
+        path = self._strip_protocol(path)
+        if start is None and end is None:
+            return self.handler._storage.get_obj(path)
+        if (start is not None and start < 0) or (end is not None and end < 0):
+            size = self.size(path)
+            if start is not None and start < 0:
+                start = max(size + start, 0)
+            if end is not None and end < 0:
+                end = size + end
+        start = start or 0
+        if end is None:
+            end = self.size(path)
+        if end <= start:
+            return b""
+        return self.handler._storage.get_obj_range(path, start, end)
+
+    def pipe_file(self, path: str, value: bytes, **kwargs: Any) -> None:
+        """
+        Write a file with the given content, replacing it if it exists.
+
+        :param path: the path of the file
+        :param value: the content
+        """
+        path = self._strip_protocol(path)
+        self.handler._check_writable()
+        self.handler._forget(path)
+        self.handler._storage.put_obj(path, value)
+
+    def rm_file(self, path: str) -> None:
+        """
+        Delete a file.
+
+        :param path: the path of the file
+        """
+        self.handler.delete_file(self._strip_protocol(path))
+
+    def rm(
+        self,
+        path: Union[str, List[str]],
+        recursive: bool = False,
+        maxdepth: Optional[int] = None,
+    ) -> None:
+        """
+        Delete files, and with recursive directories with their contents.
+
+        :param path: the paths of the files or directories, which may be globs
+        :param recursive: delete the contents of directories
+        :param maxdepth: the depth up to which directories are deleted
+        """
+        paths = self.expand_path(path, recursive=recursive, maxdepth=maxdepth)
+        if self.handler._is_local:
+            directories = [entry for entry in paths if os.path.isdir(entry)]
+        else:
+            # prefixes of listed files, unless they are marked directories
+            infos = self.handler.get_file_info(paths)
+            directories = [info.path for info in infos if info.type != FileType.File]
+        directory_set = set(directories)
+        files = [entry for entry in paths if entry not in directory_set]
+        if directories and not recursive:
+            raise IsADirectoryError(directories[0])
+        errors = [
+            error for error in self.handler.delete_files(files) if error is not None
+        ]
+        if errors:
+            raise OSError("; ".join(errors))
+        if self.handler._is_local:
+            # children sort after their parents
+            for directory in sorted(directories, reverse=True):
+                if os.path.isdir(directory):
+                    os.rmdir(directory)
+        self.invalidate_cache()
+
+    def _open(
+        self,
+        path: str,
+        mode: str = "rb",
+        block_size: Optional[int] = None,
+        autocommit: bool = True,
+        cache_options: Optional[Dict[str, Any]] = None,
+        **kwargs: Any,
+    ) -> "DeltaFile":
+        return DeltaFile(
+            self,
+            self._strip_protocol(path),
+            mode=mode,
+            block_size=block_size or "default",
+            autocommit=autocommit,
+            cache_options=cache_options,
+            **kwargs,
+        )
+
+
+class DeltaFile(AbstractBufferedFile):
+    """
+    A file of a DeltaFileSystem. Reads request the blocks they need, writes are
+    uploaded as a single object when the file is closed.
+    """
+
+    def _fetch_range(self, start: int, end: int) -> bytes:
+        return self.fs.cat_file(self.path, start, end)
+
+    def _initiate_upload(self) -> None:
+        self.fs.handler._check_writable()
+
+    def _upload_chunk(self, final: bool = False) -> bool:
+        if not final:
+            # keep buffering, the storage backends cannot upload in parts
+            return False
+        self.fs.pipe_file(self.path, self.buffer.getvalue())
+        return True
+
+
+def _info_dict(info: FileInfo) -> Dict[str, Any]:
+    if info.type == FileType.Directory:
+        return {"name": info.path, "size": 0, "type": "directory"}
+    return {
+        "name": info.path,
+        "size": info.size,
+        "type": "file",
+        "mtime": info.mtime.timestamp() if info.mtime is not None else None,
+    }
+
+
+register_implementation(DeltaFileSystem.protocol, DeltaFileSystem, clobber=True)
@@ -4,6 +4,8 @@ from typing import Any, Dict, List, Optional
 
 import pyarrow
 
+from .deltalake import arrow_schema_json_from_delta
+
 # TODO: implement this module in Rust land to avoid JSON serialization
 # https://github.com/delta-io/delta-rs/issues/95
 
@@ -149,6 +151,32 @@ class Schema:
     def json(self) -> Dict[str, Any]:
         return self.json_value
 
+    def to_json(self) -> str:
+        """
+        Serialize the Schema to the json format used in the Delta log.
+
+        :return: the schema in json format
+        """
+        return json.dumps(self.json_value)
+
+    def to_pyarrow(self) -> pyarrow.Schema:
+        """
+        Convert the Schema to the PyArrow format, like DeltaTable.pyarrow_schema() does.
+
+        :return: the Schema in PyArrow format
+        """
+        return pyarrow_schema_from_json(arrow_schema_json_from_delta(self.to_json()))
+
+    @classmethod
+    def from_pyarrow(cls, schema: pyarrow.Schema) -> "Schema":
+        """
+        Generate a DeltaTable Schema from a Schema in PyArrow format.
+
+        :param schema: the Schema in PyArrow format
+        :return: the DeltaTable Schema
+        """
+        return delta_schema_from_pyarrow(schema)
+
     @classmethod
     def from_json(cls, json_data: str) -> "Schema":
         """
@@ -296,3 +324,83 @@ def pyarrow_schema_from_json(json_data: str) -> pyarrow.Schema:
     schema_json = json.loads(json_data)
     arrow_fields = [pyarrow_field_from_dict(field) for field in schema_json["fields"]]
     return pyarrow.schema(arrow_fields)
+
+
+def delta_datatype_from_pyarrow(data_type: pyarrow.DataType) -> Any:
+    """
+    Create a DataType in json format from a DataType in PyArrow format.
+
+    :param data_type: the DataType in PyArrow format
+    :return: the DataType in json format
+    """
+    primitive_types = [
+        (pyarrow.types.is_boolean, "boolean"),
+        (pyarrow.types.is_int8, "byte"),
+        (pyarrow.types.is_int16, "short"),
+        (pyarrow.types.is_int32, "integer"),
+        (pyarrow.types.is_int64, "long"),
+        (pyarrow.types.is_float32, "float"),
+        (pyarrow.types.is_float64, "double"),
+        (pyarrow.types.is_string, "string"),
+        (pyarrow.types.is_large_string, "string"),
+        (pyarrow.types.is_binary, "binary"),
+        (pyarrow.types.is_large_binary, "binary"),
+        (pyarrow.types.is_date32, "date"),
+        (pyarrow.types.is_timestamp, "timestamp"),
+    ]
+    for is_type, type_name in primitive_types:
+        if is_type(data_type):
+            return type_name
+    if pyarrow.types.is_decimal(data_type):
+        return f"decimal({data_type.precision},{data_type.scale})"
+    if pyarrow.types.is_list(data_type) or pyarrow.types.is_large_list(data_type):
+        return {
+            "type": "array",
+            "elementType": delta_datatype_from_pyarrow(data_type.value_type),
+            "containsNull": data_type.value_field.nullable,
+        }
+    if pyarrow.types.is_map(data_type):
+        return {
+            "type": "map",
+            "keyType": delta_datatype_from_pyarrow(data_type.key_type),
+            "valueType": delta_datatype_from_pyarrow(data_type.item_type),
+            "valueContainsNull": data_type.item_field.nullable,
+        }
+    if pyarrow.types.is_struct(data_type):
+        return {
+            "type": "struct",
+            "fields": [delta_field_from_pyarrow(field) for field in data_type],
+        }
+    raise TypeError(f"PyArrow type {data_type} is not supported in Delta tables")
+
+
+def delta_field_from_pyarrow(field: pyarrow.Field) -> Dict[str, Any]:
+    """
+    Create a Field in json format from a Field in PyArrow format.
+
+    :param field: the Field in PyArrow format
+    :return: the Field in json format
+    """
+    metadata = {
+        key.decode(): value.decode() for key, value in (field.metadata or {}).items()
+    }
+    return {
+        "name": field.name,
+        "type": delta_datatype_from_pyarrow(field.type),
+        "nullable": field.nullable,
+        "metadata": metadata,
+    }
+
+
+def delta_schema_from_pyarrow(schema: pyarrow.Schema) -> Schema:
+    """
+    Create a DeltaTable Schema from a Schema in PyArrow format.
+
+    :param schema: the Schema in PyArrow format
+    :return: the DeltaTable Schema
+    """
+    json_value = {
+        "type": "struct",
+        "fields": [delta_field_from_pyarrow(field) for field in schema],
+    }
+    return Schema.from_json(json.dumps(json_value))
@@ -1,83 +1,126 @@
+import html
 import json
 import warnings
 from dataclasses import dataclass
-from typing import TYPE_CHECKING, Any, Dict, List, Optional, Tuple, Union
+from datetime import date, datetime, timedelta, timezone
+from decimal import Decimal
+from typing import TYPE_CHECKING, Any, Callable, Dict, List, Optional, Tuple, Union
 from urllib.parse import urlparse
 
 import pyarrow
+import pyarrow.compute
 import pyarrow.fs as pa_fs
-from pyarrow.dataset import dataset, partitioning
+from pyarrow.dataset import (
+    Expression,
+    FileSystemDataset,
+    dataset,
+    field,
+    partitioning,
+    scalar,
+)
 
 if TYPE_CHECKING:
     import pandas
 
+    from .writer import DeltaWriter
+
 from .data_catalog import DataCatalog
 from .deltalake import RawDeltaTable
 from .fs import DeltaStorageHandler
-from .schema import Schema, pyarrow_schema_from_json
+from .schema import Schema, delta_schema_from_pyarrow, pyarrow_schema_from_json
 
+# Limits keeping the representation of tables with long URIs or many properties short
+_REPR_MAX_URI_LENGTH = 80
+_REPR_MAX_VALUE_LENGTH = 40
+_REPR_MAX_CONFIGURATION_ENTRIES = 10
+_REPR_HISTORY_ENTRIES = 5
 
-@dataclass(init=False)
+
+@dataclass
 class Metadata:
-    """Create a Metadata instance."""
+    """The metadata of a DeltaTable, as stored in the metaData action of the transaction log."""
 
-    def __init__(self, table: RawDeltaTable):
-        self._metadata = table.metadata()
+    id: str
+    """The unique identifier of the DeltaTable."""
+    name: Optional[str]
+    """The user-provided identifier of the DeltaTable."""
+    description: Optional[str]
+    """The user-provided description of the DeltaTable."""
+    partition_columns: List[str]
+    """The list of partition_columns of the DeltaTable."""
+    created_time: Optional[datetime]
+    """The time when this metadata action was created."""
+    configuration: Dict[str, Optional[str]]
+    """The DeltaTable properties."""
 
-    @property
-    def id(self) -> int:
-        """Return the unique identifier of the DeltaTable."""
-        return self._metadata.id
+    @classmethod
+    def _from_raw(cls, table: RawDeltaTable) -> "Metadata":
+        metadata = table.metadata()
+        created_time = None
+        if metadata.created_time is not None:
+            created_time = datetime.fromtimestamp(
+                metadata.created_time / 1000, timezone.utc
+            )
+        return cls(
+            id=metadata.id,
+            name=metadata.name,
+            description=metadata.description,
+            partition_columns=metadata.partition_columns,
+            created_time=created_time,
+            configuration=metadata.configuration,
+        )
 
-    @property
-    def name(self) -> str:
-        """Return the user-provided identifier of the DeltaTable."""
-        return self._metadata.name
+    def __str__(self) -> str:
+        return (
+            f"Metadata(id: {self.id}, name: {self.name}, "
+            f"description: {self.description}, partition_columns: {self.partition_columns}, "
+            f"created_time: {self.created_time}, configuration: {self.configuration})"
+        )
 
-    @property
-    def description(self) -> str:
-        """Return the user-provided description of the DeltaTable."""
-        return self._metadata.description
 
-    @property
-    def partition_columns(self) -> List[str]:
-        """Return an array containing the names of the partitioned columns of the DeltaTable."""
-        return self._metadata.partition_columns
+@dataclass
+class Protocol:
+    """The protocol versions a client needs to support to read or write a DeltaTable."""
 
-    @property
-    def created_time(self) -> int:
-        """
-        Return The time when this metadata action is created, in milliseconds since the Unix epoch of the DeltaTable.
-        """
-        return self._metadata.created_time
+    min_reader_version: int
+    min_writer_version: int
+    reader_features: Optional[List[str]] = None
+    """The reader features of the table, None for protocol versions without table features."""
+    writer_features: Optional[List[str]] = None
+    """The writer features of the table, None for protocol versions without table features."""
 
-    @property
-    def configuration(self) -> List[str]:
-        """Return the DeltaTable properties."""
-        return self._metadata.configuration
 
-    def __str__(self) -> str:
-        return (
-            f"Metadata(id: {self._metadata.id}, name: {self._metadata.name}, "
-            f"description: {self._metadata.description}, partition_columns: {self._metadata.partition_columns}, "
-            f"created_time: {self.created_time}, configuration: {self._metadata.configuration})"
-        )
+@dataclass
+class VacuumMetrics:
+    """The files deleted by a vacuum, or the files a dry run would delete."""
+
+    files_deleted: List[str]
+    num_files_deleted: int
+    bytes_reclaimed: int
+    num_referenced_files_skipped: int
 
 
 @dataclass(init=False)
 class DeltaTable:
     """Create a DeltaTable instance."""
 
-    def __init__(self, table_uri: str, version: Optional[int] = None):
+    def __init__(
+        self,
+        table_uri: str,
+        version: Optional[int] = None,
+        case_sensitive: bool = False,
+    ):
         """
         Create the Delta Table from a path with an optional version.
         Multiple StorageBackends are currently supported: AWS S3, Azure Data Lake Storage Gen2, Google Cloud Storage (GCS) and local URI.
 
         :param table_uri: the path of the DeltaTable
         :param version: version of the DeltaTable
+        :param case_sensitive: match column names in partition filters, predicates and projections case-sensitively. By default they are matched case-insensitively like Spark does, an error is raised if a name matches several columns.
         """
-        self._table = RawDeltaTable(table_uri, version=version)
-        self._metadata = Metadata(self._table)
+        self._table = RawDeltaTable(
+            table_uri, version=version, case_sensitive=case_sensitive
+        )
 
     @classmethod
     def from_data_catalog(
@@ -105,6 +148,45 @@ class DeltaTable:
         )
         return cls(table_uri=table_uri, version=version)
 
+    @classmethod
+    def create(
+        cls,
+        table_uri: str,
+        schema: Union[pyarrow.Schema, Schema],
+        partition_by: Optional[List[str]] = None,
+        mode: str = "error",
+        configuration: Optional[Dict[str, Optional[str]]] = None,
+        storage_options: Optional[Dict[str, str]] = None,
+    ) -> "DeltaTable":
+        """
+        Create an empty Delta Table at the given location.
+
+        :param table_uri: the path of the DeltaTable
+        :param schema: the schema of the table, either in PyArrow format or a DeltaTable Schema
+        :param partition_by: the columns to partition the table by, they must be part of the schema
+        :param mode: how to handle an existing table at the location. "error" raises, "ignore" returns the existing table unchanged and "overwrite" replaces its schema and removes all of its data.
+        :param configuration: the table properties, e.g. {"delta.appendOnly": "true"}
+        :param storage_options: options passed to the storage backend, e.g. credentials
+        :return: the created DeltaTable
+        """
+        if mode not in ("error", "ignore", "overwrite"):
+            raise ValueError(
+                f"Invalid mode {mode!r}, expected one of 'error', 'ignore' or 'overwrite'"
+            )
+        if isinstance(schema, pyarrow.Schema):
+            schema = delta_schema_from_pyarrow(schema)
+
+        table = cls.__new__(cls)
+        table._table = RawDeltaTable.create(
+            table_uri,
+            json.dumps(schema.json()),
+            partition_by or [],
+            mode,
+            configuration or {},
+            storage_options or {},
+        )
+        return table
+
     def version(self) -> int:
         """
         Get the version of the DeltaTable.
@@ -152,6 +234,30 @@ class DeltaTable:
                 "Only the type String is currently allowed inside the partition filters."
             )
 
+    def files_by_predicate(self, predicate: str) -> List[str]:
+        """
+        Get the files that may contain rows matching a predicate, according to their
+        partition values and the min/max values and null counts of their statistics.
+        Files without statistics for a column of the predicate are kept.
+
+        The predicate is a subset of SQL: comparisons of a column with a literal (``=``,
+        ``!=``, ``<>``, ``<``, ``<=``, ``>``, ``>=``), ``[NOT] IN (...)`` and
+        ``IS [NOT] NULL``, combined with ``AND``, ``OR``, ``NOT`` and parentheses.
+        Strings, dates and timestamps are quoted with single quotes, nested columns are
+        referred to by their dotted path and column names with special characters can be
+        quoted with backticks.
+
+        Examples:
+        "year = '2021' AND month IN ('11', '12')"
+        "event_time >= '2023-01-01 00:00:00' OR status IS NULL"
+        "NOT (`address`.zip < 10000)"
+
+        :param predicate: the predicate the rows have to match
+        :return: list of the .parquet files with an absolute URI that may contain
+            matching rows
+        """
+        return self._table.files_by_predicate(predicate)
+
     def file_paths(self) -> List[str]:
         """
         Get the list of files with an absolute path.
@@ -173,6 +279,25 @@ class DeltaTable:
         """
         return self._table.file_uris()
 
+    def get_add_actions(self, flatten: bool = False) -> pyarrow.RecordBatch:
+        """
+        Get the add actions of the files of the current version, with their statistics,
+        e.g. to prune files before reading them.
+
+        The batch has a row per file, with the columns ``path``, ``size_bytes``,
+        ``modification_time``, ``data_change``, ``partition_values``, ``num_records``,
+        and ``null_count``, ``min`` and ``max`` with the statistics of every column that
+        is not a partition column. Partition values and statistics have the types of
+        their columns, and are null if they are missing or cannot be parsed. There are
+        no statistics for binary columns and the elements of arrays and maps.
+
+        :param flatten: put the partition values and the statistics of every column in
+            a separate column named by its path, e.g. ``min.address.city``, instead of
+            nesting them in structs like the table schema
+        :return: the add actions, as a RecordBatch
+        """
+        return self._table.get_add_actions(flatten)
+
     def load_version(self, version: int) -> None:
         """
         Load a DeltaTable with a specified version.
@@ -181,18 +306,31 @@ class DeltaTable:
         """
         self._table.load_version(version)
 
-    def load_with_datetime(self, datetime_string: str) -> None:
+    def load_with_datetime(self, datetime_string: Union[str, datetime]) -> None:
         """
         Time travel Delta table to the latest version that's created at or before provided `datetime_string` argument.
-        The `datetime_string` argument should be an RFC 3339 and ISO 8601 date and time string.
+        The `datetime_string` argument should be an RFC 3339 and ISO 8601 date and time string, or a datetime.
+        Naive datetimes are interpreted as UTC.
+        The time a version was created at is the modification time of its commit file.
 
         Examples:
         `2018-01-26T18:30:09Z`
         `2018-12-19T16:39:57-08:00`
         `2018-01-26T18:30:09.453+00:00`
+        `datetime(2018, 1, 26, 18, 30, 9, tzinfo=timezone.utc)`
 
         :param datetime_string: the identifier of the datetime point of the DeltaTable to load
+        :raises TimestampBeforeFirstCommitError: if the datetime is before the earliest version in the log, the message names the time of the earliest version
         """
+        if isinstance(datetime_string, datetime):
+            if datetime_string.tzinfo is None:
+                warnings.warn(
+                    "Interpreting the naive datetime "
+                    f"{datetime_string.isoformat()} as UTC",
+                    stacklevel=2,
+                )
+                datetime_string = datetime_string.replace(tzinfo=timezone.utc)
+            datetime_string = datetime_string.isoformat()
         self._table.load_with_datetime(datetime_string)
 
     def schema(self) -> Schema:
@@ -209,7 +347,16 @@ class DeltaTable:
 
         :return: the current Metadata registered in the transaction log
         """
-        return self._metadata
+        return Metadata._from_raw(self._table)
+
+    def protocol(self) -> Protocol:
+        """
+        Get the current protocol versions of the DeltaTable.
+
+        :return: the current Protocol registered in the transaction log
+        """
+        min_reader_version, min_writer_version = self._table.protocol_versions()
+        return Protocol(min_reader_version, min_writer_version)
 
     def history(self, limit: Optional[int] = None) -> List[Dict[str, Any]]:
         """
@@ -224,21 +371,225 @@ class DeltaTable:
             for commit_info_raw in self._table.history(limit)
         ]
 
+    def create_checkpoint(self, max_actions_per_part: Optional[int] = None) -> None:
+        """
+        Write a checkpoint of the loaded version and point ``_delta_log/_last_checkpoint`` to it, so that
+        readers load the table from the checkpoint instead of replaying all earlier commits.
+
+        The checkpoint is written through the storage of the table and ``_last_checkpoint`` is only
+        updated once all of its parts are written, so concurrent readers are not affected.
+
+        :param max_actions_per_part: split checkpoints with more actions into a multi-part checkpoint with parts of at most this many actions, 1,000,000 by default
+        """
+        if max_actions_per_part is not None and max_actions_per_part < 1:
+            raise ValueError("max_actions_per_part should be at least 1.")
+        self._table.create_checkpoint(max_actions_per_part)
+
+    def cleanup_metadata(self) -> int:
+        """
+        Delete the commits older than ``delta.logRetentionDuration``, 30 days by default, which precede the
+        latest checkpoint not newer than the loaded version. Nothing is deleted if there is no checkpoint.
+
+        :return: the number of deleted commit files
+        """
+        return self._table.cleanup_metadata()
+
+    def create_writer(
+        self,
+        partition_by: Optional[List[str]] = None,
+        target_file_size: Optional[int] = None,
+    ) -> "DeltaWriter":
+        """
+        Create a writer streaming record batches to new files of the table, without holding all of
+        them in memory. The files are appended to the table in a single commit once the writer is closed.
+
+        Example:
+        ``with table.create_writer() as writer: writer.write_batch(batch)``
+
+        :param partition_by: the partition columns of the data, which must be the partition columns of the table
+        :param target_file_size: the size in bytes at which a file is uploaded and a new one is started, ``delta.targetFileSize`` of the table by default
+        :return: the writer, see :class:`deltalake.writer.DeltaWriter`
+        """
+        from .writer import DeltaWriter
+
+        partition_columns = self.metadata().partition_columns
+        if partition_by is not None and list(partition_by) != partition_columns:
+            raise ValueError(
+                f"The table is partitioned by {partition_columns}, not by {list(partition_by)}"
+            )
+        if target_file_size is not None and target_file_size < 1:
+            raise ValueError("target_file_size should be at least 1.")
+        return DeltaWriter(self._table.create_writer(target_file_size))
+
+    def get_actions_between(
+        self, start_version: int, end_version: int
+    ) -> List[Dict[str, Any]]:
+        """
+        Read the commits from start_version up to and including end_version, so that the files
+        added and removed by each version can be processed without loading those versions.
+
+        The add and remove actions and the commit info are returned as they are written in the
+        transaction log, e.g. an add action has the keys ``path``, ``partitionValues``, ``size``,
+        ``modificationTime`` and ``dataChange``.
+
+        :param start_version: the first version to read
+        :param end_version: the last version to read
+        :return: a dict with the keys ``version``, ``add``, ``remove`` and ``commit_info`` for each version
+        :raises VersionNotInLogError: if a commit in the range was already removed from the log, the message names the earliest available version
+        """
+        commits = []
+        for version, actions_raw in self._table.get_actions_between(
+            start_version, end_version
+        ):
+            commit: Dict[str, Any] = {
+                "version": version,
+                "add": [],
+                "remove": [],
+                "commit_info": None,
+            }
+            for action in map(json.loads, actions_raw):
+                if "add" in action:
+                    commit["add"].append(action["add"])
+                elif "remove" in action:
+                    commit["remove"].append(action["remove"])
+                elif "commitInfo" in action:
+                    commit["commit_info"] = action["commitInfo"]
+            commits.append(commit)
+        return commits
+
+    def generate_manifest(self) -> None:
+        """
+        Generate the symlink format manifests of the loaded version, which let Presto, Trino and Athena read the table.
+        A manifest listing the URIs of the data files is written to ``_symlink_format_manifest/<partition path>/manifest``
+        for each partition and manifests of partitions without data are deleted.
+
+        The manifests are regenerated after each commit if the table property
+        ``delta.compatibility.symlinkFormatManifest.enabled`` is ``"true"``.
+        """
+        self._table.generate_manifest()
+
+    def check_integrity(
+        self, max_concurrent_requests: int = 16, max_sample_size: int = 20
+    ) -> Dict[str, Any]:
+        """
+        Verify that the loaded version is healthy without modifying anything. The checks are
+        ``files_exist``, ``file_sizes_match``, ``unique_add_paths``, ``checkpoint_matches_log``,
+        ``protocol_readable`` and ``stats_parse``.
+
+        :param max_concurrent_requests: the maximum number of concurrent requests checking the data files
+        :param max_sample_size: the maximum number of offending paths reported per check
+        :return: a dict with the audited ``version``, ``healthy`` and the ``checks``, each a dict of the
+            ``check``, its ``status`` ("passed", "failed" or "skipped"), ``num_failures``, a ``sample`` of the
+            offending paths and an optional ``message``
+        """
+        report = json.loads(
+            self._table.check_integrity(max_concurrent_requests, max_sample_size)
+        )
+        report["healthy"] = all(
+            check["status"] != "failed" for check in report["checks"]
+        )
+        return report
+
+    def export_iceberg_metadata(self, target_metadata_dir: Optional[str] = None) -> str:
+        """
+        Export the loaded version as Apache Iceberg metadata (format version 1), which lets Iceberg readers read
+        the table without copying its data files. Each export adds a snapshot to the metadata of the previous
+        export in the target directory, and nothing is written if the loaded version was exported already.
+
+        This is experimental. Tables with column mapping in id mode, tables that may use deletion vectors and
+        data files without statistics are not supported.
+
+        :param target_metadata_dir: the directory of the Iceberg metadata, by default ``metadata`` in the table root
+        :return: the URI of the written metadata file
+        """
+        if target_metadata_dir is None:
+            target_metadata_dir = self._table.table_uri().rstrip("/") + "/metadata"
+        return self._table.export_iceberg_metadata(target_metadata_dir)
+
+    def detail(self) -> Dict[str, Any]:
+        """
+        Get the details of the DeltaTable like Spark's DESCRIBE DETAIL: format, id, name, description,
+        location, createdAt, lastModified, partitionColumns, numFiles, sizeInBytes, properties,
+        minReaderVersion, minWriterVersion, readerFeatures and writerFeatures.
+        The times are in milliseconds since the Unix epoch.
+
+        :return: a dict of the details of the loaded version
+        """
+        return json.loads(self._table.detail())
+
     def vacuum(
-        self, retention_hours: Optional[int] = None, dry_run: bool = True
+        self,
+        retention_hours: Optional[int] = None,
+        dry_run: bool = True,
+        progress_callback: Optional[Callable[[Dict[str, Any]], None]] = None,
+        cancellation_event: Optional[Any] = None,
+        enforce_retention_duration: bool = True,
+        confirm_unsafe_retention: bool = False,
+        max_concurrent_deletes: int = 16,
     ) -> List[str]:
         """
         Run the Vacuum command on the Delta Table: list and delete files no longer referenced by the Delta table and are older than the retention threshold.
 
-        :param retention_hours: the retention threshold in hours, if none then the value from `configuration.deletedFileRetentionDuration` is used or default of 1 week otherwise.
+        :param retention_hours: the retention threshold in hours, if none then the value from `delta.deletedFileRetentionDuration` is used or default of 1 week otherwise.
         :param dry_run: when activated, list only the files, delete otherwise
+        :param progress_callback: called after each deleted file with a dict of the phase ("delete_files"), the number of files done, the total number of files and the bytes deleted so far
+        :param cancellation_event: an object like threading.Event. Once it is set no further file is deleted and the vacuum raises. Files deleted until then stay deleted.
+        :param enforce_retention_duration: reject retention thresholds shorter than `delta.deletedFileRetentionDuration`. Disabling it requires confirm_unsafe_retention, since files that concurrent readers and writers still use may be deleted.
+        :param confirm_unsafe_retention: confirm that enforce_retention_duration is disabled on purpose
+        :param max_concurrent_deletes: the maximum number of files deleted at once
         :return: the list of files no longer referenced by the Delta Table and are older than the retention threshold.
         """
+        return self.vacuum_with_metrics(
+            retention_hours,
+            dry_run,
+            progress_callback,
+            cancellation_event,
+            enforce_retention_duration,
+            confirm_unsafe_retention,
+            max_concurrent_deletes,
+        ).files_deleted
+
+    def vacuum_with_metrics(
+        self,
+        retention_hours: Optional[int] = None,
+        dry_run: bool = True,
+        progress_callback: Optional[Callable[[Dict[str, Any]], None]] = None,
+        cancellation_event: Optional[Any] = None,
+        enforce_retention_duration: bool = True,
+        confirm_unsafe_retention: bool = False,
+        max_concurrent_deletes: int = 16,
+    ) -> VacuumMetrics:
+        """
+        Run the Vacuum command like :meth:`vacuum` and return what it deleted, or would delete in a dry run.
+
+        :return: the deleted files, the bytes reclaimed and the number of files kept because the table still references them
+        """
         if retention_hours:
             if retention_hours < 0:
                 raise ValueError("The retention periods should be positive.")
+        if not enforce_retention_duration and not confirm_unsafe_retention:
+            raise ValueError(
+                "Vacuum without enforce_retention_duration may delete files that "
+                "concurrent readers and writers still use, pass "
+                "confirm_unsafe_retention=True to run it anyway."
+            )
+        if max_concurrent_deletes < 1:
+            raise ValueError("max_concurrent_deletes should be at least 1.")
 
-        return self._table.vacuum(dry_run, retention_hours)
+        files, bytes_reclaimed, num_referenced_files_skipped = self._table.vacuum(
+            dry_run,
+            retention_hours,
+            progress_callback,
+            cancellation_event,
+            enforce_retention_duration,
+            max_concurrent_deletes,
+        )
+        return VacuumMetrics(
+            files_deleted=files,
+            num_files_deleted=len(files),
+            bytes_reclaimed=bytes_reclaimed,
+            num_referenced_files_skipped=num_referenced_files_skipped,
+        )
 
     def pyarrow_schema(self) -> pyarrow.Schema:
         """
@@ -252,18 +603,23 @@ class DeltaTable:
         self,
         partitions: Optional[List[Tuple[str, str, Any]]] = None,
         filesystem: Optional[Union[str, pa_fs.FileSystem]] = None,
+        filter: Optional[Union[Expression, str]] = None,
     ) -> pyarrow.dataset.Dataset:
         """
         Build a PyArrow Dataset using data from the DeltaTable.
 
         :param partitions: A list of partition filters, see help(DeltaTable.files_by_partitions) for filter syntax
         :param filesystem: A concrete implementation of the Pyarrow FileSystem or a fsspec-compatible interface. If None, the first file path will be used to determine the right FileSystem
+        :param filter: A PyArrow expression or a predicate, see help(DeltaTable.files_by_predicate) for its syntax, used to skip files. The partition values and statistics of the files are checked against it and files that cannot contain matching rows are left out. The rows of the dataset are not filtered, pass the expression to e.g. ``to_table(filter=...)`` as well, see help(DeltaTable.predicate_expression) to get the expression of a predicate. The column names of a PyArrow expression are matched by PyArrow and must be spelled exactly like in the table schema, the columns of a predicate are resolved like partition filters
         :return: the PyArrow dataset in PyArrow
         """
         if not partitions:
             file_paths = self._table.file_uris()
         else:
             file_paths = self._table.files_by_partitions(partitions)
+        if isinstance(filter, str):
+            matching_paths = set(self._table.files_by_predicate(filter))
+            file_paths = [path for path in file_paths if path in matching_paths]
 
         empty_delta_table = len(file_paths) == 0
         if empty_delta_table:
@@ -275,23 +631,80 @@ class DeltaTable:
 
         parsed = urlparse(file_paths[0])
         if not filesystem and parsed.netloc:
-            filesystem = pa_fs.PyFileSystem(
-                DeltaStorageHandler(self._table.table_uri())
-            )
+            handler = DeltaStorageHandler(self._table.table_uri())
+            # the sizes and modification times are known from the log, so pyarrow
+            # asking for them sends no requests
+            handler.file_infos_from_log(self._table.file_infos())
+            filesystem = pa_fs.PyFileSystem(handler)
 
-        return dataset(
+        files_dataset = dataset(
             file_paths,
             schema=self.pyarrow_schema(),
             format="parquet",
             filesystem=filesystem,
             partitioning=partitioning(flavor="hive"),
         )
+        if filter is None or isinstance(filter, str):
+            return files_dataset
+        return self._prune_dataset(files_dataset, file_paths, filter)
+
+    def _prune_dataset(
+        self,
+        files_dataset: FileSystemDataset,
+        file_paths: List[str],
+        filter: Expression,
+    ) -> FileSystemDataset:
+        """
+        Rebuild the fragments of the dataset with the partition values and statistics of their
+        files as guarantees, and keep the fragments pyarrow cannot rule out for the filter.
+        """
+        files = {
+            uri: (partition_values, stats)
+            for uri, partition_values, stats in self._table.files_with_stats()
+        }
+        schema = files_dataset.schema
+        fragments = []
+        # the fragments are in the order of the paths the dataset was created from
+        for fragment, file_path in zip(files_dataset.get_fragments(), file_paths):
+            partition_values, stats = files[file_path]
+            fragments.append(
+                files_dataset.format.make_fragment(
+                    fragment.path,
+                    files_dataset.filesystem,
+                    partition_expression=_file_guarantee(
+                        schema, partition_values, stats
+                    ),
+                )
+            )
+        candidates = FileSystemDataset(
+            fragments, schema, files_dataset.format, files_dataset.filesystem
+        )
+        return FileSystemDataset(
+            list(candidates.get_fragments(filter=filter)),
+            schema,
+            files_dataset.format,
+            files_dataset.filesystem,
+        )
+
+    def predicate_expression(self, predicate: str) -> Expression:
+        """
+        Get the PyArrow expression of a predicate, see help(DeltaTable.files_by_predicate) for
+        its syntax. Its columns are resolved to the names of the table schema like partition
+        filters and its values are converted to the types of the columns.
+
+        :param predicate: the predicate the rows have to match
+        :return: the PyArrow expression of the predicate
+        """
+        return _predicate_expression(
+            json.loads(self._table.resolve_predicate(predicate)), self.pyarrow_schema()
+        )
 
     def to_pyarrow_table(
         self,
         partitions: Optional[List[Tuple[str, str, Any]]] = None,
         columns: Optional[List[str]] = None,
         filesystem: Optional[Union[str, pa_fs.FileSystem]] = None,
+        filter: Optional[Union[Expression, str]] = None,
     ) -> pyarrow.Table:
         """
         Build a PyArrow Table using data from the DeltaTable.
@@ -299,17 +712,27 @@ class DeltaTable:
         :param partitions: A list of partition filters, see help(DeltaTable.files_by_partitions) for filter syntax
         :param columns: The columns to project. This can be a list of column names to include (order and duplicates will be preserved)
         :param filesystem: A concrete implementation of the Pyarrow FileSystem or a fsspec-compatible interface. If None, the first file path will be used to determine the right FileSystem
+        :param filter: A PyArrow expression or a predicate the rows have to match, see help(DeltaTable.to_pyarrow_dataset). Files that cannot contain matching rows according to their partition values and statistics are not read
         :return: the PyArrow table
         """
+        if columns is not None:
+            columns = self._table.resolve_column_names(columns)
+        row_filter = filter
+        if isinstance(filter, str):
+            row_filter = self.predicate_expression(filter)
         return self.to_pyarrow_dataset(
-            partitions=partitions, filesystem=filesystem
-        ).to_table(columns=columns)
+            partitions=partitions, filesystem=filesystem, filter=filter
+        ).to_table(columns=columns, filter=row_filter)
 
     def to_pandas(
         self,
         partitions: Optional[List[Tuple[str, str, Any]]] = None,
         columns: Optional[List[str]] = None,
         filesystem: Optional[Union[str, pa_fs.FileSystem]] = None,
+        filter: Optional[Union[Expression, str]] = None,
+        types_mapper: Optional[Callable[[pyarrow.DataType], Any]] = None,
+        categories: Optional[List[str]] = None,
+        arrow_dtypes: bool = False,
     ) -> "pandas.DataFrame":
         """
         Build a pandas dataframe using data from the DeltaTable.
@@ -317,15 +740,316 @@ class DeltaTable:
         :param partitions: A list of partition filters, see help(DeltaTable.files_by_partitions) for filter syntax
         :param columns: The columns to project. This can be a list of column names to include (order and duplicates will be preserved)
         :param filesystem: A concrete implementation of the Pyarrow FileSystem or a fsspec-compatible interface. If None, the first file path will be used to determine the right FileSystem
+        :param filter: A PyArrow expression or a predicate the rows have to match, see help(DeltaTable.to_pyarrow_table)
+        :param types_mapper: A function mapping a PyArrow DataType to a pandas ExtensionDtype, passed to ``pyarrow.Table.to_pandas``
+        :param categories: The columns to load as pandas Categorical, by default the partition columns. The categories of a partition column are all its values in the loaded version, other columns only get the values that were read. Pass an empty list to load them with their plain types
+        :param arrow_dtypes: Use the pandas.ArrowDtype of each column, requires pandas 2 or newer and cannot be combined with a types_mapper
         :return: a pandas dataframe
         """
-        return self.to_pyarrow_table(
-            partitions=partitions, columns=columns, filesystem=filesystem
-        ).to_pandas()
+        if arrow_dtypes:
+            import pandas
+
+            if types_mapper is not None:
+                raise ValueError("types_mapper cannot be combined with arrow_dtypes")
+            if not hasattr(pandas, "ArrowDtype"):
+                raise ValueError("arrow_dtypes requires pandas 2 or newer")
+            types_mapper = pandas.ArrowDtype
+
+        table = self.to_pyarrow_table(
+            partitions=partitions, columns=columns, filesystem=filesystem, filter=filter
+        )
+        partition_columns = self.metadata().partition_columns
+        if categories is None:
+            categories = partition_columns
+        else:
+            categories = self._table.resolve_column_names(categories)
+        for name in categories:
+            if name not in table.column_names:
+                continue
+            index = table.column_names.index(name)
+            column = table.column(index)
+            dictionary = None
+            if name in partition_columns:
+                dictionary = _partition_dictionary(
+                    self._table.partition_values(name), column.type
+                )
+            if dictionary is None:
+                column = column.dictionary_encode()
+            else:
+                column = pyarrow.chunked_array(
+                    [
+                        pyarrow.DictionaryArray.from_arrays(
+                            pyarrow.compute.index_in(chunk, value_set=dictionary),
+                            dictionary,
+                        )
+                        for chunk in column.chunks
+                    ],
+                    pyarrow.dictionary(pyarrow.int32(), dictionary.type),
+                )
+            table = table.set_column(index, name, column)
+        return table.to_pandas(types_mapper=types_mapper)
+
+    def __arrow_c_stream__(self, requested_schema: Optional[Any] = None) -> Any:
+        """
+        Export the rows of the table as an ArrowArrayStream PyCapsule, the Arrow PyCapsule
+        interface used by e.g. ``polars.from_arrow`` and DuckDB. The table is scanned while the
+        stream is consumed. Requires pyarrow 14 or newer.
 
-    def update_incremental(self) -> None:
+        :param requested_schema: an ArrowSchema PyCapsule, only its columns are scanned
+        :return: an ArrowArrayStream PyCapsule
+        """
+        if not hasattr(pyarrow.RecordBatchReader, "__arrow_c_stream__"):
+            raise NotImplementedError(
+                "Exporting a DeltaTable as an Arrow stream requires pyarrow 14 or newer"
+            )
+        columns = None
+        if requested_schema is not None:
+            schema = pyarrow.Schema._import_from_c_capsule(requested_schema)
+            columns = self._table.resolve_column_names(schema.names)
+        reader = self.to_pyarrow_dataset().scanner(columns=columns).to_reader()
+        if requested_schema is not None and reader.schema.equals(schema):
+            # the projection already is the requested schema, nothing to cast
+            requested_schema = None
+        return reader.__arrow_c_stream__(requested_schema)
+
+    def update_incremental(self, max_version: Optional[int] = None) -> int:
         """
         Updates the DeltaTable to the latest version by incrementally applying
         newer versions.
+
+        Only the commit files newer than the loaded version are read. If they were
+        cleaned up after a newer checkpoint was written, the state is loaded from the
+        checkpoint instead.
+
+        :param max_version: the version to update to at most, e.g. to process the new
+            versions one at a time
+        :return: the number of versions applied
         """
-        self._table.update_incremental()
+        return self._table.update_incremental(max_version)
+
+    def __repr__(self) -> str:
+        uri = _truncate(self._table.table_uri(), _REPR_MAX_URI_LENGTH)
+        size = _format_size(self._table.files_size())
+        return (
+            f"DeltaTable(uri={uri!r}, version={self.version()}, "
+            f"files={len(self.files())}, size={size}, "
+            f"partition_columns={self._table.metadata().partition_columns})"
+        )
+
+    def _repr_html_(self) -> str:
+        metadata = self._table.metadata()
+        reader_version, writer_version = self._table.protocol_versions()
+        protocol = f"reader version {reader_version}, writer version {writer_version}"
+        rows = [
+            _html_header("DeltaTable"),
+            _html_row("URI", _truncate(self._table.table_uri(), _REPR_MAX_URI_LENGTH)),
+            _html_row("Version", str(self.version())),
+            _html_row("Files", str(len(self.files()))),
+            _html_row("Size", _format_size(self._table.files_size())),
+            _html_row("Partition columns", ", ".join(metadata.partition_columns)),
+            _html_row("Name", metadata.name or ""),
+            _html_row("Description", metadata.description or ""),
+            _html_row("Protocol", protocol),
+        ]
+
+        configuration = sorted(metadata.configuration.items())
+        if configuration:
+            rows.append(_html_header("Configuration"))
+            for key, value in configuration[:_REPR_MAX_CONFIGURATION_ENTRIES]:
+                value = _truncate(str(value), _REPR_MAX_VALUE_LENGTH)
+                rows.append(_html_row(key, value))
+            if len(configuration) > _REPR_MAX_CONFIGURATION_ENTRIES:
+                hidden = len(configuration) - _REPR_MAX_CONFIGURATION_ENTRIES
+                rows.append(_html_row("...", f"{hidden} more"))
+
+        history = self.history(_REPR_HISTORY_ENTRIES)
+        if history:
+            rows.append(_html_header("Recent history"))
+            for commit_info in history:
+                timestamp = commit_info.get("timestamp")
+                rows.append(
+                    _html_row(
+                        _format_timestamp(timestamp) if timestamp is not None else "",
+                        str(commit_info.get("operation", "")),
+                    )
+                )
+
+        return "<table>\n" + "\n".join(rows) + "\n</table>"
+
+
+def _file_guarantee(
+    schema: pyarrow.Schema,
+    partition_values: Dict[str, Optional[str]],
+    stats_json: Optional[str],
+) -> Expression:
+    """
+    An expression true for all rows of a file, built from its partition values and statistics.
+    Values that cannot be converted to the type of their column are left out.
+    """
+    conditions = []
+    for name, value in partition_values.items():
+        if not value:
+            conditions.append(field(name).is_null())
+            continue
+        partition_value = _to_scalar(value, schema.field(name).type)
+        if partition_value is not None:
+            conditions.append(field(name) == partition_value)
+
+    if stats_json is not None:
+        stats = json.loads(stats_json)
+        min_values = stats.get("minValues", {})
+        max_values = stats.get("maxValues", {})
+        for name, null_count in stats.get("nullCount", {}).items():
+            # nested columns have a struct of counts
+            if not isinstance(null_count, int) or name not in schema.names:
+                continue
+            if null_count == stats.get("numRecords"):
+                conditions.append(field(name).is_null())
+            elif null_count == 0:
+                # the bounds are only a guarantee if every row has a value
+                conditions.append(field(name).is_valid())
+                column_type = schema.field(name).type
+                minimum = _to_scalar(min_values.get(name), column_type)
+                if minimum is not None:
+                    conditions.append(field(name) >= minimum)
+                maximum = _to_scalar(max_values.get(name), column_type)
+                if maximum is not None:
+                    conditions.append(field(name) <= maximum)
+
+    guarantee = scalar(True)
+    for condition in conditions:
+        guarantee = guarantee & condition
+    return guarantee
+
+
+def _predicate_expression(node: Dict[str, Any], schema: pyarrow.Schema) -> Expression:
+    """
+    The PyArrow expression of a predicate resolved by the table, see
+    ``DeltaTable::resolve_predicate`` of the Rust crate for its format.
+    """
+    op = node["op"]
+    if op in ("and", "or", "not"):
+        args = [_predicate_expression(arg, schema) for arg in node["args"]]
+        if op == "not":
+            return ~args[0]
+        if op == "and":
+            return args[0] & args[1]
+        return args[0] | args[1]
+
+    column = field(*node["column"])
+    if op == "is null":
+        return column.is_null()
+    if op == "is not null":
+        return column.is_valid()
+
+    data_type = schema.field(node["column"][0]).type
+    for name in node["column"][1:]:
+        data_type = data_type[data_type.get_field_index(name)].type
+    if op in ("in", "not in"):
+        # comparisons rather than isin, so that nulls match neither IN nor NOT IN
+        values = [_predicate_scalar(value, data_type) for value in node["values"]]
+        expression = column == values[0]
+        for value in values[1:]:
+            expression = expression | (column == value)
+        return ~expression if op == "not in" else expression
+
+    value = _predicate_scalar(node["value"], data_type)
+    if op == "=":
+        return column == value
+    if op == "!=":
+        return column != value
+    if op == "<":
+        return column < value
+    if op == "<=":
+        return column <= value
+    if op == ">":
+        return column > value
+    return column >= value
+
+
+def _predicate_scalar(value: Any, data_type: pyarrow.DataType) -> Expression:
+    """
+    A value of a resolved predicate as a scalar of the type of its column. Numbers that do not
+    fit the type of the column, like decimals compared with an integer column, are compared as
+    floats.
+    """
+    if pyarrow.types.is_integer(data_type) or pyarrow.types.is_decimal(data_type):
+        number = Decimal(value)
+        if pyarrow.types.is_integer(data_type) and number == number.to_integral_value():
+            return scalar(pyarrow.scalar(int(number), type=data_type))
+        if (
+            pyarrow.types.is_decimal(data_type)
+            and -number.as_tuple().exponent <= data_type.scale
+        ):
+            return scalar(pyarrow.scalar(number, type=data_type))
+        return scalar(float(number))
+    if pyarrow.types.is_floating(data_type):
+        return scalar(pyarrow.scalar(float(value), type=data_type))
+    if pyarrow.types.is_date32(data_type):
+        return scalar(
+            pyarrow.scalar(date(1970, 1, 1) + timedelta(days=value), type=data_type)
+        )
+    if pyarrow.types.is_timestamp(data_type):
+        return scalar(pyarrow.scalar(value, type=pyarrow.timestamp("us")))
+    return scalar(value)
+
+
+def _partition_dictionary(
+    values: List[str], data_type: pyarrow.DataType
+) -> Optional[pyarrow.Array]:
+    """
+    The sorted distinct partition values converted to the type of the column, or None if they
+    cannot be converted.
+    """
+    try:
+        dictionary = pyarrow.compute.unique(pyarrow.array(values).cast(data_type))
+    except (
+        pyarrow.ArrowInvalid,
+        pyarrow.ArrowNotImplementedError,
+        pyarrow.ArrowTypeError,
+    ):
+        return None
+    return dictionary.take(pyarrow.compute.sort_indices(dictionary))
+
+
+def _to_scalar(value: Any, data_type: pyarrow.DataType) -> Optional[pyarrow.Scalar]:
+    if value is None or isinstance(value, (dict, list)):
+        return None
+    try:
+        return pyarrow.array([value]).cast(data_type)[0]
+    except (
+        pyarrow.ArrowInvalid,
+        pyarrow.ArrowNotImplementedError,
+        pyarrow.ArrowTypeError,
+    ):
+        return None
+
+
+def _truncate(text: str, max_length: int) -> str:
+    if len(text) <= max_length:
+        return text
+    return text[: max_length - 3] + "..."
+
+
+def _format_size(size: int) -> str:
+    if size < 1024:
+        return f"{size} B"
+    value = float(size)
+    for unit in ["KiB", "MiB", "GiB", "TiB"]:
+        value /= 1024
+        if value < 1024:
+            break
+    return f"{value:.1f} {unit}"
+
+
+def _format_timestamp(timestamp_ms: int) -> str:
+    timestamp = datetime.fromtimestamp(timestamp_ms / 1000, timezone.utc)
+    return timestamp.strftime("%Y-%m-%d %H:%M:%S UTC")
+
+
+def _html_header(title: str) -> str:
+    return f'<tr><th colspan="2">{html.escape(title)}</th></tr>'
+
+
+def _html_row(key: str, value: str) -> str:
+    return f"<tr><td>{html.escape(key)}</td><td>{html.escape(value)}</td></tr>"
@@ -0,0 +1,212 @@
+import json
+from dataclasses import dataclass
+from typing import (
+    TYPE_CHECKING,
+    Any,
+    Callable,
+    Dict,
+    Iterable,
+    List,
+    Optional,
+    Type,
+    Union,
+)
+
+import pyarrow
+
+if TYPE_CHECKING:
+    import pandas
+
+from .deltalake import DeltaWriter as _DeltaWriter
+from .deltalake import create_deltalake_writer as _create_deltalake_writer
+from .schema import Schema, delta_schema_from_pyarrow
+from .table import DeltaTable
+
+
+@dataclass
+class WriteMetrics:
+    """The metrics of a write, which are also stored as the operationMetrics of its commit."""
+
+    version: int
+    num_added_files: int
+    num_removed_files: int
+    num_added_bytes: int
+    num_removed_bytes: int
+    num_output_rows: int
+    num_partitions: int
+    execution_time_ms: int
+
+
+def write_deltalake(
+    table_or_uri: Union[str, DeltaTable],
+    data: Union[
+        "pandas.DataFrame",
+        pyarrow.Table,
+        pyarrow.RecordBatch,
+        Iterable[pyarrow.RecordBatch],
+    ],
+    *,
+    schema: Optional[Union[pyarrow.Schema, Schema]] = None,
+    partition_by: Optional[List[str]] = None,
+    mode: str = "error",
+    predicate: Optional[str] = None,
+    schema_mode: Optional[str] = None,
+    configuration: Optional[Dict[str, Optional[str]]] = None,
+    custom_metadata: Optional[Dict[str, str]] = None,
+    storage_options: Optional[Dict[str, str]] = None,
+    post_commithook: Optional[Callable[[Dict[str, Any]], None]] = None,
+    strict_post_commithook: bool = False,
+    progress_callback: Optional[Callable[[Dict[str, Any]], None]] = None,
+    cancellation_event: Optional[Any] = None,
+) -> Optional[WriteMetrics]:
+    """
+    Write data to a Delta Table. The parquet files, their statistics and the commit are all written by the Rust engine.
+
+    If the table does not exist yet it is created with the schema of the data, the given partition columns and configuration.
+
+    :param table_or_uri: the path of the DeltaTable or a DeltaTable, which is updated to the new version after the write
+    :param data: the data to write. An iterable of RecordBatches requires the schema to be given, it is consumed one batch at a time and the batches are written as they arrive
+    :param schema: the schema of the data, only needed for an iterable of RecordBatches
+    :param partition_by: the columns to partition a new table by, ignored if the table exists
+    :param mode: how to handle existing data. "error" raises and "ignore" writes nothing if the table exists, "append" adds the data and "overwrite" replaces all data of the table.
+    :param predicate: with mode "overwrite", only replace the partitions matching this predicate, see help(DeltaTable.files_by_predicate) for its syntax. It may only refer to partition columns and all written rows have to match it
+    :param schema_mode: "merge" adds the columns of the data that are missing from the table schema, "overwrite" replaces the table schema with the schema of the data and requires mode "overwrite". By default the data has to match the table schema
+    :param configuration: the table properties of a new table, e.g. {"delta.appendOnly": "true"}
+    :param custom_metadata: entries added to the commitInfo of the commit
+    :param storage_options: options passed to the storage backend, e.g. credentials
+    :param post_commithook: called after the data was committed with a summary dict of the commit: its version, operation, operation_parameters, num_added_files and num_removed_files
+    :param strict_post_commithook: raise if the post_commithook fails. The data stays committed either way, by default a failure is only logged.
+    :param progress_callback: called after each written file with a dict of the phase ("write_files"), the number of files done, the total number of files and the bytes written so far
+    :param cancellation_event: an object like threading.Event. Once it is set the write stops before its next file and raises without committing. Files written until then are deleted again.
+    :return: the metrics of the write, or None if nothing was written in mode "ignore"
+    """
+    if mode not in ("error", "append", "overwrite", "ignore"):
+        raise ValueError(
+            f"Invalid mode {mode!r}, expected one of 'error', 'append', 'overwrite' or 'ignore'"
+        )
+    if schema_mode not in (None, "merge", "overwrite"):
+        raise ValueError(
+            f"Invalid schema_mode {schema_mode!r}, expected 'merge' or 'overwrite'"
+        )
+
+    if isinstance(table_or_uri, DeltaTable):
+        table: Optional[DeltaTable] = table_or_uri
+        table_uri = table_or_uri._table.table_uri()
+    else:
+        table = None
+        table_uri = str(table_or_uri)
+
+    if _is_pandas_dataframe(data):
+        data = pyarrow.Table.from_pandas(data, preserve_index=False)
+
+    batches: Iterable[pyarrow.RecordBatch]
+    if isinstance(data, pyarrow.Table):
+        schema = schema or data.schema
+        batches = data.to_batches()
+    elif isinstance(data, pyarrow.RecordBatch):
+        schema = schema or data.schema
+        batches = [data]
+    elif schema is None:
+        raise ValueError("The schema is required to write an iterable of RecordBatches")
+    else:
+        batches = data
+
+    if isinstance(schema, pyarrow.Schema):
+        schema = delta_schema_from_pyarrow(schema)
+
+    raw_writer = _create_deltalake_writer(
+        table_uri,
+        json.dumps(schema.json()),
+        partition_by or [],
+        mode,
+        predicate,
+        schema_mode,
+        configuration or {},
+        custom_metadata or {},
+        storage_options or {},
+        post_commithook,
+        strict_post_commithook,
+        progress_callback,
+        cancellation_event,
+    )
+    if raw_writer is None:
+        return None
+
+    writer = DeltaWriter(raw_writer)
+    try:
+        for batch in batches:
+            writer.write_batch(batch)
+    except BaseException:
+        writer.abort()
+        raise
+    metrics = writer.close()
+
+    if table is not None:
+        table.update_incremental()
+    return metrics
+
+
+class DeltaWriter:
+    """
+    Streams record batches to new files of a DeltaTable, created by :meth:`DeltaTable.create_writer`.
+
+    A file is kept open per partition and uploaded once it reaches the target file size. The files
+    are appended to the table in a single commit by :meth:`close`, until then readers do not see them.
+    If a write fails or the writer is aborted, the files uploaded so far are deleted again.
+
+    Used as a context manager the writer is closed on exit, or aborted if an exception was raised.
+    """
+
+    def __init__(self, writer: _DeltaWriter):
+        self._writer = writer
+
+    def write_batch(self, batch: Union[pyarrow.RecordBatch, pyarrow.Table]) -> None:
+        """
+        Write the rows of a batch to the open files of their partitions.
+
+        The columns are matched to the table schema by name. If the write fails, the writer is
+        aborted and cannot be used anymore.
+
+        :param batch: the rows to write, a RecordBatch or a Table
+        """
+        batches = batch.to_batches() if isinstance(batch, pyarrow.Table) else [batch]
+        for batch in batches:
+            self._writer.write_batch(batch)
+
+    def close(self) -> WriteMetrics:
+        """
+        Upload the remaining open files and append all written files to the table in a single commit.
+        The DeltaTable is updated to the committed version.
+
+        :return: the metrics of the write
+        """
+        return WriteMetrics(**self._writer.close())
+
+    def abort(self) -> None:
+        """
+        Delete the files uploaded so far without committing them, leaving the table unchanged.
+        Does nothing if the writer is already closed.
+        """
+        self._writer.abort()
+
+    def __enter__(self) -> "DeltaWriter":
+        return self
+
+    def __exit__(
+        self,
+        exc_type: Optional[Type[BaseException]],
+        exc_value: Optional[BaseException],
+        traceback: Any,
+    ) -> None:
+        if exc_type is None:
+            self.close()
+        else:
+            self.abort()
+
+
+def _is_pandas_dataframe(data: object) -> bool:
+    try:
+        import pandas
+    except ImportError:
+        return False
+    return isinstance(data, pandas.DataFrame)
@@ -3,51 +3,291 @@
 extern crate pyo3;
 
 use chrono::{DateTime, FixedOffset, Utc};
+use deltalake::action::SaveMode;
 use deltalake::arrow::datatypes::Schema as ArrowSchema;
+use deltalake::arrow::pyarrow::PyArrowConvert;
+use deltalake::arrow::record_batch::RecordBatch;
 use deltalake::partitions::PartitionFilter;
+use deltalake::predicate::Predicate;
 use deltalake::storage;
-use deltalake::{arrow, StorageBackend};
+use deltalake::writer::{StreamingRecordBatchWriter, WriteMetrics};
+use deltalake::{arrow, PostCommitHook, StorageBackend};
+use futures::{StreamExt, TryStreamExt};
 use pyo3::create_exception;
-use pyo3::exceptions::PyException;
+use pyo3::exceptions::{
+    PyException, PyFileExistsError, PyFileNotFoundError, PyNotImplementedError, PyPermissionError,
+    PyRuntimeError, PyTimeoutError, PyValueError,
+};
+use pyo3::once_cell::GILOnceCell;
 use pyo3::prelude::*;
-use pyo3::types::{PyBytes, PyTuple, PyType};
-use std::collections::HashMap;
+use pyo3::types::{IntoPyDict, PyBytes, PyDict, PyTuple, PyType};
+use std::collections::{BTreeSet, HashMap};
 use std::convert::TryFrom;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard, TryLockError};
+use std::time::Duration;
 
-create_exception!(deltalake, PyDeltaTableError, PyException);
+create_exception!(deltalake, DeltaError, PyException);
+create_exception!(deltalake, TableNotFoundError, DeltaError);
+create_exception!(deltalake, CommitFailedError, DeltaError);
+create_exception!(deltalake, DeltaProtocolError, DeltaError);
+create_exception!(deltalake, SchemaMismatchError, DeltaError);
+create_exception!(deltalake, TimestampBeforeFirstCommitError, DeltaError);
+create_exception!(deltalake, VersionNotInLogError, DeltaError);
 
-impl PyDeltaTableError {
+/// The exceptions of storage errors. Each is a `DeltaError` as well as the builtin exception
+/// Python code and pyarrow expect from file operations, e.g. `DeltaFileNotFoundError` derives
+/// from `DeltaError` and `FileNotFoundError`. `create_exception!` only takes a single base, so
+/// they are created with `type` on first use.
+struct StorageErrorTypes {
+    not_found: Py<PyType>,
+    already_exists: Py<PyType>,
+    permission_denied: Py<PyType>,
+    timed_out: Py<PyType>,
+    invalid_options: Py<PyType>,
+    not_supported: Py<PyType>,
+}
+
+static STORAGE_ERROR_TYPES: GILOnceCell<StorageErrorTypes> = GILOnceCell::new();
+
+impl StorageErrorTypes {
+    fn get(py: Python) -> &StorageErrorTypes {
+        STORAGE_ERROR_TYPES.get_or_init(py, || {
+            let new_type = |name: &str, base: &PyType| -> Py<PyType> {
+                let bases = PyTuple::new(py, &[py.get_type::<DeltaError>(), base]);
+                let dict = [("__module__", "deltalake")].into_py_dict(py);
+                let ty = py
+                    .get_type::<PyType>()
+                    .call1((name, bases, dict))
+                    .and_then(|ty| Ok(ty.downcast::<PyType>()?))
+                    .expect("exception types can be derived from DeltaError and OSError");
+                ty.into()
+            };
+            StorageErrorTypes {
+                not_found: new_type(
+                    "DeltaFileNotFoundError",
+                    py.get_type::<PyFileNotFoundError>(),
+                ),
+                already_exists: new_type(
+                    "DeltaFileExistsError",
+                    py.get_type::<PyFileExistsError>(),
+                ),
+                permission_denied: new_type(
+                    "DeltaPermissionError",
+                    py.get_type::<PyPermissionError>(),
+                ),
+                timed_out: new_type("DeltaTimeoutError", py.get_type::<PyTimeoutError>()),
+                invalid_options: new_type("DeltaValueError", py.get_type::<PyValueError>()),
+                not_supported: new_type(
+                    "DeltaNotImplementedError",
+                    py.get_type::<PyNotImplementedError>(),
+                ),
+            }
+        })
+    }
+
+    fn add_to_module(py: Python, m: &PyModule) -> PyResult<()> {
+        let types = StorageErrorTypes::get(py);
+        for ty in [
+            &types.not_found,
+            &types.already_exists,
+            &types.permission_denied,
+            &types.timed_out,
+            &types.invalid_options,
+            &types.not_supported,
+        ] {
+            let ty = ty.as_ref(py);
+            m.add(ty.name()?, ty)?;
+        }
+        Ok(())
+    }
+}
+
+/// Highest reader protocol version of the tables the bindings can read
+const MAX_SUPPORTED_READER_VERSION: i32 = 1;
+/// Highest writer protocol version of the tables the bindings can write to
+const MAX_SUPPORTED_WRITER_VERSION: i32 = 2;
+/// How often blocking calls check for signals like Ctrl-C
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+impl DeltaError {
     fn from_arrow(err: arrow::error::ArrowError) -> pyo3::PyErr {
-        PyDeltaTableError::new_err(err.to_string())
+        DeltaError::new_err(err.to_string())
     }
 
     fn from_data_catalog(err: deltalake::DataCatalogError) -> pyo3::PyErr {
-        PyDeltaTableError::new_err(err.to_string())
+        DeltaError::new_err(err.to_string())
     }
 
     fn from_raw(err: deltalake::DeltaTableError) -> pyo3::PyErr {
-        PyDeltaTableError::new_err(err.to_string())
+        match err {
+            deltalake::DeltaTableError::NotATable(_) => {
+                TableNotFoundError::new_err(err.to_string())
+            }
+            deltalake::DeltaTableError::VersionAlreadyExists(_) => {
+                CommitFailedError::new_err(err.to_string())
+            }
+            deltalake::DeltaTableError::SchemaMismatch { .. }
+            | deltalake::DeltaTableError::InvalidColumnNames { .. } => {
+                SchemaMismatchError::new_err(err.to_string())
+            }
+            deltalake::DeltaTableError::ColumnMappingNotSupported { .. } => {
+                DeltaProtocolError::new_err(err.to_string())
+            }
+            deltalake::DeltaTableError::TimestampBeforeFirstCommit { .. } => {
+                TimestampBeforeFirstCommitError::new_err(err.to_string())
+            }
+            deltalake::DeltaTableError::VersionNotInLog { .. } => {
+                VersionNotInLogError::new_err(err.to_string())
+            }
+            _ => DeltaError::new_err(err.to_string()),
+        }
+    }
+
+    fn from_checkpoint(err: deltalake::checkpoints::CheckpointError) -> pyo3::PyErr {
+        match err {
+            deltalake::checkpoints::CheckpointError::DeltaTable { source } => {
+                DeltaError::from_raw(source)
+            }
+            _ => DeltaError::new_err(err.to_string()),
+        }
     }
 
+    /// Raises the OSError subclasses Python code expects from file operations where possible,
+    /// see `StorageErrorTypes`.
     fn from_storage(err: deltalake::StorageError) -> pyo3::PyErr {
-        PyDeltaTableError::new_err(err.to_string())
+        Python::with_gil(|py| {
+            let types = StorageErrorTypes::get(py);
+            let ty = match &err {
+                deltalake::StorageError::NotFound => &types.not_found,
+                deltalake::StorageError::AlreadyExists(_) => &types.already_exists,
+                deltalake::StorageError::Io { source } => match source.kind() {
+                    std::io::ErrorKind::PermissionDenied => &types.permission_denied,
+                    std::io::ErrorKind::TimedOut => &types.timed_out,
+                    _ => return DeltaError::new_err(err.to_string()),
+                },
+                deltalake::StorageError::InvalidOptions(_) => &types.invalid_options,
+                deltalake::StorageError::NotSupported(_) => &types.not_supported,
+                _ => return DeltaError::new_err(err.to_string()),
+            };
+            PyErr::from_type(ty.as_ref(py), err.to_string())
+        })
     }
 
-    fn from_tokio(err: tokio::io::Error) -> pyo3::PyErr {
-        PyDeltaTableError::new_err(err.to_string())
+    fn from_poison<T>(err: PoisonError<T>) -> pyo3::PyErr {
+        DeltaError::new_err(err.to_string())
     }
 
     fn from_chrono(err: chrono::ParseError) -> pyo3::PyErr {
-        PyDeltaTableError::new_err(format!(
+        DeltaError::new_err(format!(
             "Parse date and time string failed: {}",
             err.to_string()
         ))
     }
 }
 
-#[inline]
-fn rt() -> PyResult<tokio::runtime::Runtime> {
-    tokio::runtime::Runtime::new().map_err(PyDeltaTableError::from_tokio)
+/// Whether the IO runtime was started, after which `set_io_runtime` can no longer configure it.
+static IO_RUNTIME_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Returns the runtime shared by all blocking calls and the awaitables of `deltalake.aio`,
+/// starting it on first use.
+fn io_runtime() -> &'static tokio::runtime::Runtime {
+    IO_RUNTIME_STARTED.store(true, Ordering::SeqCst);
+    pyo3_asyncio::tokio::get_runtime()
+}
+
+/// Configures the runtime running the IO of all tables and storage handlers: the number of
+/// worker threads, one per CPU by default, and the prefix of their names. The runtime is shared
+/// for the lifetime of the process, so this must be called before the first IO.
+#[pyfunction(threads = "None", thread_name = "\"deltalake-io\"")]
+fn set_io_runtime(threads: Option<usize>, thread_name: &str) -> PyResult<()> {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder.enable_all().thread_name(thread_name);
+    match threads {
+        Some(0) => return Err(PyValueError::new_err("threads must be positive")),
+        Some(threads) => {
+            builder.worker_threads(threads);
+        }
+        None => {}
+    }
+    if IO_RUNTIME_STARTED.load(Ordering::SeqCst) {
+        return Err(PyRuntimeError::new_err(
+            "The IO runtime is already running, set_io_runtime must be called before any IO",
+        ));
+    }
+    pyo3_asyncio::tokio::init(builder);
+    Ok(())
+}
+
+/// Runs a future to completion on the IO runtime. Must be called with the GIL released: the
+/// GIL is only taken every `SIGNAL_CHECK_INTERVAL` to run the Python signal handlers, and if
+/// one raises (e.g. KeyboardInterrupt on Ctrl-C) the future is dropped and the error returned.
+fn wait_for_future<F: Future>(future: F) -> PyResult<F::Output> {
+    // other Python threads would stall for the whole IO otherwise
+    debug_assert_eq!(
+        unsafe { pyo3::ffi::PyGILState_Check() },
+        0,
+        "wait_for_future must be called in py.allow_threads"
+    );
+    io_runtime().block_on(async {
+        tokio::pin!(future);
+        loop {
+            tokio::select! {
+                output = &mut future => return Ok(output),
+                _ = tokio::time::sleep(SIGNAL_CHECK_INTERVAL) => {
+                    Python::with_gil(|py| py.check_signals())?;
+                }
+            }
+        }
+    })
+}
+
+/// Releases the GIL to run `f`. The log level is synced first, so that the records the
+/// `deltalake` loggers would drop are filtered by `log::max_level` without taking the GIL.
+fn allow_threads<T, F>(py: Python, f: F) -> T
+where
+    F: Send + FnOnce() -> T,
+    T: Send,
+{
+    sync_log_level(py);
+    py.allow_threads(f)
+}
+
+/// Sets `log::max_level` to the lowest level enabled for the `deltalake` logger or one of its
+/// children. Keeps the current level if the logging module cannot be inspected.
+fn sync_log_level(py: Python) {
+    let level = || -> PyResult<i64> {
+        let logging = py.import("logging")?;
+        let mut level: i64 = logging
+            .call_method1("getLogger", ("deltalake",))?
+            .call_method0("getEffectiveLevel")?
+            .extract()?;
+        let loggers: &PyDict = logging
+            .getattr("Logger")?
+            .getattr("manager")?
+            .getattr("loggerDict")?
+            .downcast()?;
+        for (name, logger) in loggers {
+            // placeholders of loggers that were not created yet have no level
+            if name.extract::<&str>()?.starts_with("deltalake.")
+                && logger.hasattr("getEffectiveLevel")?
+            {
+                level = level.min(logger.call_method0("getEffectiveLevel")?.extract()?);
+            }
+        }
+        Ok(level)
+    };
+    if let Ok(level) = level() {
+        log::set_max_level(match level {
+            i64::MIN..=5 => log::LevelFilter::Trace,
+            6..=10 => log::LevelFilter::Debug,
+            11..=20 => log::LevelFilter::Info,
+            21..=30 => log::LevelFilter::Warn,
+            31..=40 => log::LevelFilter::Error,
+            _ => log::LevelFilter::Off,
+        });
+    }
 }
 
 #[derive(FromPyObject)]
@@ -58,7 +298,577 @@ enum PartitionFilterValue<'a> {
 
 #[pyclass]
 struct RawDeltaTable {
-    _table: deltalake::DeltaTable,
+    // Updates are loaded under the read lock and installed under the write lock, so readers
+    // always see a complete state while the GIL is released for IO.
+    _table: Arc<RwLock<deltalake::DeltaTable>>,
+    // Serializes updates, so that prepared updates are applied in order.
+    _update_lock: Arc<Mutex<()>>,
+}
+
+impl RawDeltaTable {
+    fn from_table(table: deltalake::DeltaTable) -> Self {
+        RawDeltaTable {
+            _table: Arc::new(RwLock::new(table)),
+            _update_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Read-locks the table while holding the GIL. The lock is waited for with the GIL released,
+    /// as the writer holding or waiting for it may need the GIL to check signals or to log.
+    fn table(&self, py: Python) -> PyResult<RwLockReadGuard<deltalake::DeltaTable>> {
+        loop {
+            match self._table.try_read() {
+                Ok(table) => return Ok(table),
+                Err(TryLockError::Poisoned(err)) => return Err(DeltaError::from_poison(err)),
+                Err(TryLockError::WouldBlock) => py.allow_threads(|| drop(self._table.read())),
+            }
+        }
+    }
+
+    /// Read-locks the table, must be called with the GIL released.
+    fn table_without_gil(&self) -> PyResult<RwLockReadGuard<deltalake::DeltaTable>> {
+        self._table.read().map_err(DeltaError::from_poison)
+    }
+
+    /// Loads an update with `prepare` while the table stays readable and installs it afterwards.
+    fn update_with<F>(&self, py: Python, prepare: F) -> PyResult<()>
+    where
+        F: FnOnce(&deltalake::DeltaTable) -> PyResult<deltalake::DeltaTableUpdate> + Send,
+    {
+        allow_threads(py, || {
+            apply_update_with(&self._table, &self._update_lock, prepare)
+        })
+    }
+}
+
+fn apply_update_with<F>(
+    table: &RwLock<deltalake::DeltaTable>,
+    update_lock: &Mutex<()>,
+    prepare: F,
+) -> PyResult<()>
+where
+    F: FnOnce(&deltalake::DeltaTable) -> PyResult<deltalake::DeltaTableUpdate>,
+{
+    let _guard = update_lock.lock().map_err(DeltaError::from_poison)?;
+    let update = prepare(&*table.read().map_err(DeltaError::from_poison)?)?;
+    let mut table = table.write().map_err(DeltaError::from_poison)?;
+    table.apply_update(update).map_err(DeltaError::from_raw)?;
+    check_reader_protocol(&table)
+}
+
+fn table_builder(
+    table_uri: &str,
+    version: Option<deltalake::DeltaDataTypeLong>,
+    case_sensitive: bool,
+) -> PyResult<deltalake::DeltaTableBuilder> {
+    let mut builder =
+        deltalake::DeltaTableBuilder::from_uri(table_uri).map_err(DeltaError::from_raw)?;
+    if let Some(version) = version {
+        builder = builder.with_version(version);
+    }
+    if case_sensitive {
+        builder = builder.with_column_resolution(deltalake::ColumnResolution::CaseSensitive);
+    }
+    Ok(builder)
+}
+
+/// Aborts the Rust future behind an awaitable, used when its asyncio task is cancelled.
+#[pyclass]
+struct AbortHandle {
+    handle: futures::future::AbortHandle,
+}
+
+#[pymethods]
+impl AbortHandle {
+    fn abort(&self) {
+        self.handle.abort()
+    }
+}
+
+/// Spawns `future` on the runtime shared with pyo3-asyncio and returns an awaitable of its result
+/// together with the handle to abort it.
+fn future_into_py<F>(py: Python, future: F) -> PyResult<(&PyAny, AbortHandle)>
+where
+    F: Future<Output = PyResult<PyObject>> + Send + 'static,
+{
+    let (future, handle) = futures::future::abortable(future);
+    io_runtime();
+    let awaitable = pyo3_asyncio::tokio::future_into_py(py, async move {
+        future
+            .await
+            .unwrap_or_else(|_| Err(DeltaError::new_err("The operation was cancelled")))
+    })?;
+    Ok((awaitable, AbortHandle { handle }))
+}
+
+fn check_reader_protocol(table: &deltalake::DeltaTable) -> PyResult<()> {
+    if table.get_min_reader_version() > MAX_SUPPORTED_READER_VERSION {
+        return Err(DeltaProtocolError::new_err(format!(
+            "The table requires reader version {}, but only versions up to {} are supported",
+            table.get_min_reader_version(),
+            MAX_SUPPORTED_READER_VERSION
+        )));
+    }
+    Ok(())
+}
+
+fn check_writer_protocol(table: &deltalake::DeltaTable) -> PyResult<()> {
+    if table.get_min_writer_version() > MAX_SUPPORTED_WRITER_VERSION {
+        return Err(DeltaProtocolError::new_err(format!(
+            "The table requires writer version {}, but only versions up to {} are supported",
+            table.get_min_writer_version(),
+            MAX_SUPPORTED_WRITER_VERSION
+        )));
+    }
+    Ok(())
+}
+
+fn new_table_metadata(
+    schema_json: &str,
+    partition_by: Vec<String>,
+    configuration: HashMap<String, Option<String>>,
+) -> PyResult<deltalake::DeltaTableMetaData> {
+    let schema: deltalake::Schema = serde_json::from_str(schema_json)
+        .map_err(|err| DeltaError::new_err(format!("Invalid schema: {}", err)))?;
+    for column in &partition_by {
+        match schema.get_fields().iter().find(|f| f.get_name() == column) {
+            Some(field) if matches!(field.get_type(), deltalake::SchemaDataType::primitive(_)) => {}
+            Some(_) => {
+                return Err(DeltaError::new_err(format!(
+                    "Partition column {} must be of a primitive type",
+                    column
+                )))
+            }
+            None => {
+                return Err(DeltaError::new_err(format!(
+                    "Partition column {} is not part of the schema",
+                    column
+                )))
+            }
+        }
+    }
+    Ok(deltalake::DeltaTableMetaData::new(
+        None,
+        None,
+        None,
+        schema,
+        partition_by,
+        configuration,
+    ))
+}
+
+/// Loads the table at the uri, or returns None if there is no Delta table yet
+async fn load_existing_table(
+    table_uri: &str,
+    storage_options: &HashMap<String, String>,
+) -> PyResult<Option<deltalake::DeltaTable>> {
+    let backend = storage::get_backend_for_uri_with_options(table_uri, storage_options.clone())
+        .map_err(DeltaError::from_storage)?;
+    let existing = deltalake::DeltaTableBuilder::from_uri(table_uri)
+        .map_err(DeltaError::from_raw)?
+        .with_storage_backend(backend)
+        .load()
+        .await;
+    match existing {
+        Ok(table) => Ok(Some(table)),
+        Err(deltalake::DeltaTableError::NotATable(_)) => Ok(None),
+        Err(err) => Err(DeltaError::from_raw(err)),
+    }
+}
+
+/// Returns the table at the uri without loading it, for a table that is yet to be created
+fn unloaded_table(
+    table_uri: &str,
+    storage_options: &HashMap<String, String>,
+) -> PyResult<deltalake::DeltaTable> {
+    let backend = storage::get_backend_for_uri_with_options(table_uri, storage_options.clone())
+        .map_err(DeltaError::from_storage)?;
+    deltalake::DeltaTable::new(table_uri, backend, deltalake::DeltaTableConfig::default())
+        .map_err(DeltaError::from_raw)
+}
+
+/// The protocol of the tables created by the bindings
+fn new_table_protocol() -> deltalake::action::Protocol {
+    deltalake::action::Protocol {
+        min_reader_version: 1,
+        min_writer_version: 2,
+    }
+}
+
+async fn create_new_table(
+    table_uri: &str,
+    metadata: deltalake::DeltaTableMetaData,
+    storage_options: &HashMap<String, String>,
+) -> PyResult<deltalake::DeltaTable> {
+    let mut table = unloaded_table(table_uri, storage_options)?;
+    let mut commit_info = serde_json::Map::new();
+    commit_info.insert("operation".to_string(), "CREATE TABLE".into());
+    table
+        .create(metadata, new_table_protocol(), Some(commit_info))
+        .await
+        .map_err(DeltaError::from_raw)?;
+    Ok(table)
+}
+
+/// Creates the table at `table_uri` with the given metadata. If a table already exists, the save
+/// `mode` decides whether that is an error, the existing table is returned unchanged or its
+/// schema is replaced and all of its files are removed.
+async fn create_table(
+    table_uri: &str,
+    mut metadata: deltalake::DeltaTableMetaData,
+    mode: &str,
+    storage_options: HashMap<String, String>,
+) -> PyResult<deltalake::DeltaTable> {
+    if !matches!(mode, "error" | "ignore" | "overwrite") {
+        return Err(DeltaError::new_err(format!(
+            "Invalid save mode {} for creating a table, expected one of error, ignore or overwrite",
+            mode
+        )));
+    }
+
+    let table_uri = storage::normalize_table_uri(table_uri).map_err(DeltaError::from_storage)?;
+    match load_existing_table(&table_uri, &storage_options).await? {
+        None => create_new_table(&table_uri, metadata, &storage_options).await,
+        Some(_) if mode == "error" => Err(DeltaError::new_err(format!(
+            "A Delta table already exists at {}",
+            table_uri
+        ))),
+        Some(table) if mode == "ignore" => Ok(table),
+        // overwrite: replace the schema and remove all files of the existing table
+        Some(mut table) => {
+            check_writer_protocol(&table)?;
+            metadata.id = table
+                .get_metadata()
+                .map_err(DeltaError::from_raw)?
+                .id
+                .clone();
+            let deletion_timestamp = Utc::now().timestamp_millis();
+            let mut actions: Vec<deltalake::action::Action> = table
+                .get_state()
+                .files()
+                .iter()
+                .map(|add| {
+                    deltalake::action::Action::remove(deltalake::action::Remove {
+                        path: add.path.clone(),
+                        deletion_timestamp: Some(deletion_timestamp),
+                        data_change: true,
+                        extended_file_metadata: Some(true),
+                        partition_values: Some(add.partition_values.clone()),
+                        size: Some(add.size),
+                        tags: add.tags.clone(),
+                    })
+                })
+                .collect();
+            actions.push(deltalake::action::Action::metaData(
+                deltalake::action::MetaData::try_from(metadata)
+                    .map_err(|err| DeltaError::new_err(err.to_string()))?,
+            ));
+            let mut commit_info = serde_json::Map::new();
+            commit_info.insert("operation".to_string(), "CREATE OR REPLACE TABLE".into());
+            actions.push(deltalake::action::Action::commitInfo(commit_info));
+
+            let mut transaction = table.create_transaction(None);
+            transaction.add_actions(actions);
+            transaction
+                .commit(None)
+                .await
+                .map_err(DeltaError::from_raw)?;
+            table.update().await.map_err(DeltaError::from_raw)?;
+            Ok(table)
+        }
+    }
+}
+
+/// Calls a Python callable with a summary dict of each commit
+struct PythonPostCommitHook {
+    callback: PyObject,
+}
+
+impl PostCommitHook for PythonPostCommitHook {
+    fn after_commit(
+        &self,
+        version: deltalake::DeltaDataTypeVersion,
+        _operation: Option<&deltalake::action::DeltaOperation>,
+        actions: &[deltalake::action::Action],
+    ) -> Result<(), deltalake::DeltaTableError> {
+        use deltalake::action::Action;
+
+        let commit_info = actions.iter().find_map(|action| match action {
+            Action::commitInfo(commit_info) => Some(commit_info),
+            _ => None,
+        });
+        let summary = serde_json::json!({
+            "version": version,
+            "operation": commit_info.and_then(|c| c.get("operation")),
+            "operation_parameters": commit_info.and_then(|c| c.get("operationParameters")),
+            "num_added_files": actions.iter().filter(|a| matches!(a, Action::add(_))).count(),
+            "num_removed_files": actions.iter().filter(|a| matches!(a, Action::remove(_))).count(),
+        });
+
+        Python::with_gil(|py| {
+            let summary = py
+                .import("json")?
+                .call_method1("loads", (summary.to_string(),))?;
+            self.callback.call1(py, (summary,))?;
+            Ok(())
+        })
+        .map_err(|err: PyErr| deltalake::DeltaTableError::Generic(err.to_string()))
+    }
+}
+
+/// Calls a Python callable with a dict of each progress event
+struct PythonProgressCallback {
+    callback: PyObject,
+}
+
+impl deltalake::ProgressCallback for PythonProgressCallback {
+    fn on_progress(&self, event: &deltalake::ProgressEvent) {
+        Python::with_gil(|py| {
+            let event = [
+                ("phase", event.phase.as_str().into_py(py)),
+                ("done", event.done.into_py(py)),
+                ("total", event.total.into_py(py)),
+                ("bytes", event.bytes.into_py(py)),
+            ]
+            .into_py_dict(py);
+            if let Err(err) = self.callback.call1(py, (event,)) {
+                log::warn!("Progress callback failed: {}", err);
+            }
+        })
+    }
+}
+
+/// Checks the `is_set()` method of a Python object like threading.Event
+struct PythonCancellationToken {
+    event: PyObject,
+}
+
+impl deltalake::CancellationToken for PythonCancellationToken {
+    fn is_cancelled(&self) -> bool {
+        Python::with_gil(|py| {
+            self.event
+                .call_method0(py, "is_set")
+                .and_then(|is_set| is_set.extract(py))
+                .unwrap_or_else(|err| {
+                    log::warn!("Checking the cancellation event failed: {}", err);
+                    false
+                })
+        })
+    }
+}
+
+fn operation_control(
+    progress_callback: Option<PyObject>,
+    cancellation_event: Option<PyObject>,
+) -> deltalake::OperationControl {
+    let mut control = deltalake::OperationControl::default();
+    if let Some(callback) = progress_callback {
+        control = control.with_progress_callback(Arc::new(PythonProgressCallback { callback }));
+    }
+    if let Some(event) = cancellation_event {
+        control = control.with_cancellation_token(Arc::new(PythonCancellationToken { event }));
+    }
+    control
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn create_deltalake_writer(
+    py: Python,
+    table_uri: &str,
+    schema_json: &str,
+    partition_by: Vec<String>,
+    mode: &str,
+    predicate: Option<&str>,
+    schema_mode: Option<&str>,
+    configuration: HashMap<String, Option<String>>,
+    custom_metadata: HashMap<String, String>,
+    storage_options: HashMap<String, String>,
+    post_commithook: Option<PyObject>,
+    strict_post_commithook: bool,
+    progress_callback: Option<PyObject>,
+    cancellation_event: Option<PyObject>,
+) -> PyResult<Option<DeltaWriter>> {
+    let mode = match mode {
+        "append" => SaveMode::Append,
+        "overwrite" => SaveMode::Overwrite,
+        "error" => SaveMode::ErrorIfExists,
+        "ignore" => SaveMode::Ignore,
+        _ => {
+            return Err(DeltaError::new_err(format!(
+                "Invalid save mode {}, expected one of append, overwrite, error or ignore",
+                mode
+            )))
+        }
+    };
+    let predicate: Option<Predicate> = predicate
+        .map(|predicate| predicate.parse().map_err(DeltaError::from_raw))
+        .transpose()?;
+    if predicate.is_some() && mode != SaveMode::Overwrite {
+        return Err(DeltaError::new_err(
+            "A predicate can only be used with save mode overwrite",
+        ));
+    }
+    match schema_mode {
+        None | Some("merge") => {}
+        Some("overwrite") if mode != SaveMode::Overwrite => {
+            return Err(DeltaError::new_err(
+                "Schema mode overwrite can only be used with save mode overwrite",
+            ))
+        }
+        Some("overwrite") if predicate.is_some() => {
+            return Err(DeltaError::new_err(
+                "Schema mode overwrite cannot be combined with a predicate",
+            ))
+        }
+        Some("overwrite") => {}
+        Some(schema_mode) => {
+            return Err(DeltaError::new_err(format!(
+                "Invalid schema mode {}, expected merge or overwrite",
+                schema_mode
+            )))
+        }
+    }
+    let metadata = new_table_metadata(schema_json, partition_by, configuration)?;
+    let commit_info = custom_metadata
+        .into_iter()
+        .map(|(key, value)| (key, serde_json::Value::String(value)))
+        .collect();
+    let mut transaction_options = deltalake::DeltaTransactionOptions::default();
+    if let Some(callback) = post_commithook {
+        transaction_options =
+            transaction_options.with_post_commit_hook(Arc::new(PythonPostCommitHook { callback }));
+    }
+    if strict_post_commithook {
+        transaction_options = transaction_options.with_strict_post_commit_hooks();
+    }
+    let control = operation_control(progress_callback, cancellation_event);
+
+    allow_threads(py, || {
+        wait_for_future(async {
+            let table_uri =
+                storage::normalize_table_uri(table_uri).map_err(DeltaError::from_storage)?;
+            let (table, writer) = match load_existing_table(&table_uri, &storage_options).await? {
+                // the table is created together with the data in a single commit
+                None => {
+                    let writer =
+                        StreamingRecordBatchWriter::for_new_table(metadata, new_table_protocol())
+                            .map_err(DeltaError::from_raw)?;
+                    (unloaded_table(&table_uri, &storage_options)?, writer)
+                }
+                Some(_) if mode == SaveMode::ErrorIfExists => {
+                    return Err(DeltaError::new_err(format!(
+                        "A Delta table already exists at {}",
+                        table_uri
+                    )))
+                }
+                Some(_) if mode == SaveMode::Ignore => return Ok(None),
+                Some(table) => {
+                    check_writer_protocol(&table)?;
+                    let mut writer = StreamingRecordBatchWriter::for_table(&table)
+                        .map_err(DeltaError::from_raw)?;
+                    let current = table.get_metadata().map_err(DeltaError::from_raw)?;
+                    if let Some(changed) = changed_metadata(current, metadata, schema_mode)? {
+                        writer = writer
+                            .with_metadata(changed)
+                            .map_err(DeltaError::from_raw)?;
+                    }
+                    if mode == SaveMode::Overwrite {
+                        writer = writer.with_overwrite(predicate);
+                    }
+                    (table, writer)
+                }
+            };
+            Ok(Some(DeltaWriter {
+                _table: Arc::new(RwLock::new(table)),
+                _update_lock: Arc::new(Mutex::new(())),
+                writer: Some(writer.with_control(control)),
+                commit_info: Some(commit_info),
+                transaction_options: Some(transaction_options),
+            }))
+        })?
+    })
+}
+
+/// The metadata of an existing table after a write with the given schema mode, or None if it
+/// stays the same. "merge" adds the columns of the written schema missing from the table,
+/// "overwrite" replaces the schema and, if they are given, the partition columns.
+fn changed_metadata(
+    current: &deltalake::DeltaTableMetaData,
+    written: deltalake::DeltaTableMetaData,
+    schema_mode: Option<&str>,
+) -> PyResult<Option<deltalake::DeltaTableMetaData>> {
+    let mut metadata = current.clone();
+    match schema_mode {
+        Some("merge") => {
+            let mut fields = current.schema.get_fields().clone();
+            let new_fields = written
+                .schema
+                .get_fields()
+                .iter()
+                .filter(|field| {
+                    !fields
+                        .iter()
+                        .any(|existing| existing.get_name() == field.get_name())
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if new_fields.is_empty() {
+                return Ok(None);
+            }
+            fields.extend(new_fields);
+            metadata.schema = deltalake::Schema::new(fields);
+        }
+        Some("overwrite") => {
+            if !written.partition_columns.is_empty() {
+                metadata.partition_columns = written.partition_columns;
+            }
+            if let Some(column) = metadata.partition_columns.iter().find(|column| {
+                !written
+                    .schema
+                    .get_fields()
+                    .iter()
+                    .any(|field| field.get_name() == *column)
+            }) {
+                return Err(DeltaError::new_err(format!(
+                    "Partition column {} is not part of the schema",
+                    column
+                )));
+            }
+            metadata.schema = written.schema;
+        }
+        _ => return Ok(None),
+    }
+    Ok(Some(metadata))
+}
+
+fn write_metrics_dict(metrics: WriteMetrics) -> HashMap<&'static str, i64> {
+    HashMap::from([
+        ("version", metrics.version),
+        ("num_added_files", metrics.num_added_files),
+        ("num_removed_files", metrics.num_removed_files),
+        ("num_added_bytes", metrics.num_added_bytes),
+        ("num_removed_bytes", metrics.num_removed_bytes),
+        ("num_output_rows", metrics.num_output_rows),
+        ("num_partitions", metrics.num_partitions),
+        ("execution_time_ms", metrics.execution_time_ms),
+    ])
+}
+
+fn arrow_schema_json(schema: &deltalake::Schema) -> PyResult<String> {
+    serde_json::to_string(
+        &<ArrowSchema as TryFrom<&deltalake::Schema>>::try_from(schema)
+            .map_err(DeltaError::from_arrow)?
+            .to_json(),
+    )
+    .map_err(|_| DeltaError::new_err("Got invalid table schema"))
+}
+
+#[pyfunction]
+fn arrow_schema_json_from_delta(schema_json: &str) -> PyResult<String> {
+    let schema: deltalake::Schema = serde_json::from_str(schema_json)
+        .map_err(|err| DeltaError::new_err(format!("Invalid schema: {}", err)))?;
+    arrow_schema_json(&schema)
 }
 
 #[pyclass]
@@ -80,49 +890,138 @@ struct RawDeltaTableMetaData {
 #[pymethods]
 impl RawDeltaTable {
     #[new]
-    fn new(table_uri: &str, version: Option<deltalake::DeltaDataTypeLong>) -> PyResult<Self> {
-        let table = match version {
-            None => rt()?.block_on(deltalake::open_table(table_uri)),
-            Some(version) => rt()?.block_on(deltalake::open_table_with_version(table_uri, version)),
-        }
-        .map_err(PyDeltaTableError::from_raw)?;
-        Ok(RawDeltaTable { _table: table })
+    #[args(version = "None", case_sensitive = "false")]
+    fn new(
+        py: Python,
+        table_uri: &str,
+        version: Option<deltalake::DeltaDataTypeLong>,
+        case_sensitive: bool,
+    ) -> PyResult<Self> {
+        let builder = table_builder(table_uri, version, case_sensitive)?;
+        let table = allow_threads(py, || {
+            wait_for_future(builder.load())?.map_err(DeltaError::from_raw)
+        })?;
+        check_reader_protocol(&table)?;
+        Ok(RawDeltaTable::from_table(table))
+    }
+
+    /// Loads the table without blocking, returns an awaitable of the table and its abort handle.
+    #[staticmethod]
+    #[args(version = "None", case_sensitive = "false")]
+    fn load_async<'py>(
+        py: Python<'py>,
+        table_uri: &str,
+        version: Option<deltalake::DeltaDataTypeLong>,
+        case_sensitive: bool,
+    ) -> PyResult<(&'py PyAny, AbortHandle)> {
+        let builder = table_builder(table_uri, version, case_sensitive)?;
+        future_into_py(py, async move {
+            let table = builder.load().await.map_err(DeltaError::from_raw)?;
+            check_reader_protocol(&table)?;
+            Python::with_gil(|py| Ok(Py::new(py, RawDeltaTable::from_table(table))?.into_py(py)))
+        })
+    }
+
+    #[classmethod]
+    fn create(
+        _cls: &PyType,
+        py: Python,
+        table_uri: &str,
+        schema_json: &str,
+        partition_by: Vec<String>,
+        mode: &str,
+        configuration: HashMap<String, Option<String>>,
+        storage_options: HashMap<String, String>,
+    ) -> PyResult<Self> {
+        let metadata = new_table_metadata(schema_json, partition_by, configuration)?;
+
+        let table = allow_threads(py, || {
+            wait_for_future(create_table(table_uri, metadata, mode, storage_options))?
+        })?;
+        Ok(RawDeltaTable::from_table(table))
     }
 
     #[classmethod]
     fn get_table_uri_from_data_catalog(
         _cls: &PyType,
+        py: Python,
         data_catalog: &str,
         database_name: &str,
         table_name: &str,
         data_catalog_id: Option<String>,
     ) -> PyResult<String> {
         let data_catalog = deltalake::data_catalog::get_data_catalog(data_catalog)
-            .map_err(PyDeltaTableError::from_data_catalog)?;
-        let table_uri = rt()?
-            .block_on(data_catalog.get_table_storage_location(
+            .map_err(DeltaError::from_data_catalog)?;
+        allow_threads(py, || {
+            wait_for_future(data_catalog.get_table_storage_location(
                 data_catalog_id,
                 database_name,
                 table_name,
-            ))
-            .map_err(PyDeltaTableError::from_data_catalog)?;
+            ))?
+            .map_err(DeltaError::from_data_catalog)
+        })
+    }
 
-        Ok(table_uri)
+    pub fn table_uri(&self, py: Python) -> PyResult<String> {
+        Ok(self.table(py)?.table_uri.clone())
     }
 
-    pub fn table_uri(&self) -> PyResult<&str> {
-        Ok(&self._table.table_uri)
+    /// Pickles the table as the arguments to load the same version again, the state itself is
+    /// re-read from the log when unpickling.
+    #[allow(clippy::type_complexity)]
+    fn __reduce__(
+        &self,
+        py: Python,
+    ) -> PyResult<(PyObject, (String, deltalake::DeltaDataTypeVersion, bool))> {
+        let table = self.table(py)?;
+        let case_sensitive =
+            table.config.column_resolution == deltalake::ColumnResolution::CaseSensitive;
+        Ok((
+            py.get_type::<RawDeltaTable>().into(),
+            (table.table_uri.clone(), table.version, case_sensitive),
+        ))
     }
 
-    pub fn version(&self) -> PyResult<i64> {
-        Ok(self._table.version)
+    pub fn version(&self, py: Python) -> PyResult<i64> {
+        Ok(self.table(py)?.version)
+    }
+
+    pub fn protocol_versions(&self, py: Python) -> PyResult<(i32, i32)> {
+        let table = self.table(py)?;
+        Ok((
+            table.get_min_reader_version(),
+            table.get_min_writer_version(),
+        ))
+    }
+
+    /// The distinct values of a partition column in the files of the loaded version, without
+    /// null values
+    pub fn partition_values(&self, py: Python, column: &str) -> PyResult<Vec<String>> {
+        let table = self.table(py)?;
+        let values: BTreeSet<&String> = table
+            .get_state()
+            .files()
+            .iter()
+            .filter_map(|add| add.partition_values.get(column)?.as_ref())
+            .filter(|value| !value.is_empty())
+            .collect();
+        Ok(values.into_iter().cloned().collect())
+    }
+
+    /// Total size in bytes of the data files of the loaded version
+    pub fn files_size(&self, py: Python) -> PyResult<deltalake::DeltaDataTypeLong> {
+        Ok(self
+            .table(py)?
+            .get_state()
+            .files()
+            .iter()
+            .map(|add| add.size)
+            .sum())
     }
 
-    pub fn metadata(&self) -> PyResult<RawDeltaTableMetaData> {
-        let metadata = self
-            ._table
-            .get_metadata()
-            .map_err(PyDeltaTableError::from_raw)?;
+    pub fn metadata(&self, py: Python) -> PyResult<RawDeltaTableMetaData> {
+        let table = self.table(py)?;
+        let metadata = table.get_metadata().map_err(DeltaError::from_raw)?;
         Ok(RawDeltaTableMetaData {
             id: metadata.id.clone(),
             name: metadata.name.clone(),
@@ -133,24 +1032,29 @@ impl RawDeltaTable {
         })
     }
 
-    pub fn load_version(&mut self, version: deltalake::DeltaDataTypeVersion) -> PyResult<()> {
-        rt()?
-            .block_on(self._table.load_version(version))
-            .map_err(PyDeltaTableError::from_raw)
+    pub fn load_version(
+        &self,
+        py: Python,
+        version: deltalake::DeltaDataTypeVersion,
+    ) -> PyResult<()> {
+        self.update_with(py, |table| {
+            wait_for_future(table.prepare_load_version(version))?.map_err(DeltaError::from_raw)
+        })
     }
 
-    pub fn load_with_datetime(&mut self, ds: &str) -> PyResult<()> {
+    pub fn load_with_datetime(&self, py: Python, ds: &str) -> PyResult<()> {
         let datetime = DateTime::<Utc>::from(
-            DateTime::<FixedOffset>::parse_from_rfc3339(ds)
-                .map_err(PyDeltaTableError::from_chrono)?,
+            DateTime::<FixedOffset>::parse_from_rfc3339(ds).map_err(DeltaError::from_chrono)?,
         );
-        rt()?
-            .block_on(self._table.load_with_datetime(datetime))
-            .map_err(PyDeltaTableError::from_raw)
+        self.update_with(py, |table| {
+            wait_for_future(table.prepare_load_with_datetime(datetime))?
+                .map_err(DeltaError::from_raw)
+        })
     }
 
     pub fn files_by_partitions(
         &self,
+        py: Python,
         partitions_filters: Vec<(&str, &str, PartitionFilterValue)>,
     ) -> PyResult<Vec<String>> {
         let partition_filters: Result<Vec<PartitionFilter<&str>>, deltalake::DeltaTableError> =
@@ -167,107 +1071,709 @@ impl RawDeltaTable {
                 .collect();
         match partition_filters {
             Ok(filters) => Ok(self
-                ._table
+                .table(py)?
                 .get_file_uris_by_partitions(&filters)
-                .map_err(PyDeltaTableError::from_raw)?),
-            Err(err) => Err(PyDeltaTableError::from_raw(err)),
+                .map_err(DeltaError::from_raw)?),
+            Err(err) => Err(DeltaError::from_raw(err)),
         }
     }
 
-    pub fn files(&self) -> PyResult<Vec<String>> {
+    pub fn files_by_predicate(&self, py: Python, predicate: &str) -> PyResult<Vec<String>> {
+        let predicate: Predicate = predicate.parse().map_err(DeltaError::from_raw)?;
+        self.table(py)?
+            .get_file_uris_by_predicate(&predicate)
+            .map_err(DeltaError::from_raw)
+    }
+
+    pub fn resolve_predicate(&self, py: Python, predicate: &str) -> PyResult<String> {
+        let predicate: Predicate = predicate.parse().map_err(DeltaError::from_raw)?;
+        let resolved = self
+            .table(py)?
+            .resolve_predicate(&predicate)
+            .map_err(DeltaError::from_raw)?;
+        Ok(resolved.to_string())
+    }
+
+    pub fn resolve_column_names(&self, py: Python, columns: Vec<&str>) -> PyResult<Vec<String>> {
+        let table = self.table(py)?;
+        columns
+            .into_iter()
+            .map(|name| {
+                table
+                    .resolve_column_name(name)
+                    .map_err(DeltaError::from_raw)
+            })
+            .collect()
+    }
+
+    pub fn files(&self, py: Python) -> PyResult<Vec<String>> {
         Ok(self
-            ._table
+            .table(py)?
             .get_files_iter()
             .map(|f| f.to_string())
             .collect())
     }
 
-    pub fn file_uris(&self) -> PyResult<Vec<String>> {
-        Ok(self._table.get_file_uris())
+    pub fn file_uris(&self, py: Python) -> PyResult<Vec<String>> {
+        Ok(self.table(py)?.get_file_uris())
     }
 
-    pub fn schema_json(&self) -> PyResult<String> {
-        let schema = self
-            ._table
-            .get_schema()
-            .map_err(PyDeltaTableError::from_raw)?;
-        serde_json::to_string(&schema)
-            .map_err(|_| PyDeltaTableError::new_err("Got invalid table schema"))
+    /// The URI, size in bytes and modification time in milliseconds of each file, as recorded in
+    /// the log.
+    pub fn file_infos(
+        &self,
+        py: Python,
+    ) -> PyResult<
+        Vec<(
+            String,
+            deltalake::DeltaDataTypeLong,
+            deltalake::DeltaDataTypeTimestamp,
+        )>,
+    > {
+        let table = self.table(py)?;
+        Ok(table
+            .get_file_uris()
+            .into_iter()
+            .zip(table.get_state().files())
+            .map(|(uri, add)| (uri, add.size, add.modification_time))
+            .collect())
+    }
+
+    /// The URI, partition values and statistics of each file, the statistics as JSON.
+    #[allow(clippy::type_complexity)]
+    pub fn files_with_stats(
+        &self,
+        py: Python,
+    ) -> PyResult<Vec<(String, HashMap<String, Option<String>>, Option<String>)>> {
+        let table = self.table(py)?;
+        table
+            .get_file_uris()
+            .into_iter()
+            .zip(table.get_state().files())
+            .zip(table.get_stats())
+            .map(|((uri, add), stats)| {
+                let stats = stats
+                    .map_err(DeltaError::from_raw)?
+                    .map(|stats| serde_json::to_string(&stats))
+                    .transpose()
+                    .map_err(|_| DeltaError::new_err("Got invalid file statistics"))?;
+                Ok((uri, add.partition_values.clone(), stats))
+            })
+            .collect()
+    }
+
+    /// The add actions of the files as a pyarrow RecordBatch, with their partition values and
+    /// statistics in the types of their columns.
+    pub fn get_add_actions(&self, py: Python, flatten: bool) -> PyResult<PyObject> {
+        let batch = self
+            .table(py)?
+            .get_state()
+            .add_actions_table(flatten)
+            .map_err(DeltaError::from_raw)?;
+        batch.to_pyarrow(py)
+    }
+
+    pub fn schema_json(&self, py: Python) -> PyResult<String> {
+        let table = self.table(py)?;
+        let schema = table.get_schema().map_err(DeltaError::from_raw)?;
+        serde_json::to_string(&schema).map_err(|_| DeltaError::new_err("Got invalid table schema"))
     }
 
     /// Run the Vacuum command on the Delta Table: list and delete files no longer referenced by the Delta table and are older than the retention threshold.
-    pub fn vacuum(&mut self, dry_run: bool, retention_hours: Option<u64>) -> PyResult<Vec<String>> {
-        rt()?
-            .block_on(self._table.vacuum(retention_hours, dry_run))
-            .map_err(PyDeltaTableError::from_raw)
+    /// Returns the deleted files, the bytes reclaimed and the number of referenced files skipped.
+    #[allow(clippy::too_many_arguments)]
+    pub fn vacuum(
+        &self,
+        py: Python,
+        dry_run: bool,
+        retention_hours: Option<u64>,
+        progress_callback: Option<PyObject>,
+        cancellation_event: Option<PyObject>,
+        enforce_retention_duration: bool,
+        max_concurrent_deletes: usize,
+    ) -> PyResult<(Vec<String>, i64, usize)> {
+        let control = operation_control(progress_callback, cancellation_event);
+        let options = deltalake::VacuumOptions {
+            retention_hours,
+            dry_run,
+            enforce_retention_duration,
+            max_concurrent_deletes,
+        };
+        allow_threads(py, || {
+            let table = self.table_without_gil()?;
+            let metrics = wait_for_future(table.vacuum_with_options(&options, &control))?
+                .map_err(DeltaError::from_raw)?;
+            Ok((
+                metrics.files_deleted,
+                metrics.bytes_reclaimed,
+                metrics.referenced_files_skipped,
+            ))
+        })
     }
 
     // Run the History command on the Delta Table: Returns provenance information, including the operation, user, and so on, for each write to a table.
-    pub fn history(&mut self, limit: Option<usize>) -> PyResult<Vec<String>> {
+    pub fn history(&self, py: Python, limit: Option<usize>) -> PyResult<Vec<String>> {
         let history = self
-            ._table
+            .table(py)?
             .history(limit)
-            .map_err(PyDeltaTableError::from_raw)?;
+            .map_err(DeltaError::from_raw)?;
         Ok(history
             .iter()
             .map(|c| serde_json::to_string(c).unwrap())
             .collect())
     }
 
-    pub fn arrow_schema_json(&self) -> PyResult<String> {
-        let schema = self
-            ._table
-            .get_schema()
-            .map_err(PyDeltaTableError::from_raw)?;
-        serde_json::to_string(
-            &<ArrowSchema as TryFrom<&deltalake::Schema>>::try_from(schema)
-                .map_err(PyDeltaTableError::from_arrow)?
-                .to_json(),
-        )
-        .map_err(|_| PyDeltaTableError::new_err("Got invalid table schema"))
+    /// Writes a checkpoint of the loaded version, split into parts of at most
+    /// `max_actions_per_part` actions.
+    #[args(max_actions_per_part = "None")]
+    pub fn create_checkpoint(
+        &self,
+        py: Python,
+        max_actions_per_part: Option<usize>,
+    ) -> PyResult<()> {
+        let mut config = deltalake::checkpoints::CheckpointConfig::default();
+        if let Some(max_actions_per_part) = max_actions_per_part {
+            config.max_actions_per_part = max_actions_per_part;
+        }
+        allow_threads(py, || {
+            let table = self.table_without_gil()?;
+            wait_for_future(deltalake::checkpoints::create_checkpoint_with_config(
+                &table, &config,
+            ))?
+            .map_err(DeltaError::from_checkpoint)
+        })
+    }
+
+    /// Deletes the expired commits before the latest checkpoint, returns the number deleted.
+    pub fn cleanup_metadata(&self, py: Python) -> PyResult<i32> {
+        allow_threads(py, || {
+            let table = self.table_without_gil()?;
+            wait_for_future(deltalake::checkpoints::cleanup_metadata(&table))?
+                .map_err(DeltaError::from_raw)
+        })
+    }
+
+    /// Returns a writer streaming record batches to new files of the table, which are appended
+    /// in a single commit once the writer is closed.
+    #[args(target_file_size = "None")]
+    pub fn create_writer(
+        &self,
+        py: Python,
+        target_file_size: Option<u64>,
+    ) -> PyResult<DeltaWriter> {
+        let table = self.table(py)?;
+        check_writer_protocol(&table)?;
+        let mut writer =
+            StreamingRecordBatchWriter::for_table(&table).map_err(DeltaError::from_raw)?;
+        if let Some(target_file_size) = target_file_size {
+            writer = writer.with_target_file_size(target_file_size);
+        }
+        Ok(DeltaWriter {
+            _table: self._table.clone(),
+            _update_lock: self._update_lock.clone(),
+            writer: Some(writer),
+            commit_info: None,
+            transaction_options: None,
+        })
+    }
+
+    /// Returns the version and the JSON encoded actions of each commit in the given range.
+    pub fn get_actions_between(
+        &self,
+        py: Python,
+        start_version: deltalake::DeltaDataTypeVersion,
+        end_version: deltalake::DeltaDataTypeVersion,
+    ) -> PyResult<Vec<(deltalake::DeltaDataTypeVersion, Vec<String>)>> {
+        allow_threads(py, || {
+            let table = self.table_without_gil()?;
+            let commits = wait_for_future(table.get_actions_between(start_version, end_version))?
+                .map_err(DeltaError::from_raw)?;
+            Ok(commits
+                .into_iter()
+                .map(|commit| {
+                    let actions = commit
+                        .actions
+                        .iter()
+                        .map(|action| serde_json::to_string(action).unwrap())
+                        .collect();
+                    (commit.version, actions)
+                })
+                .collect())
+        })
+    }
+
+    pub fn generate_manifest(&self, py: Python) -> PyResult<()> {
+        allow_threads(py, || {
+            let table = self.table_without_gil()?;
+            wait_for_future(table.generate_symlink_format_manifest())?.map_err(DeltaError::from_raw)
+        })
+    }
+
+    pub fn export_iceberg_metadata(
+        &self,
+        py: Python,
+        target_metadata_dir: &str,
+    ) -> PyResult<String> {
+        allow_threads(py, || {
+            let table = self.table_without_gil()?;
+            wait_for_future(deltalake::uniform::export_iceberg_metadata(
+                &table,
+                target_metadata_dir,
+            ))?
+            .map_err(|err| DeltaError::new_err(err.to_string()))
+        })
+    }
+
+    pub fn check_integrity(
+        &self,
+        py: Python,
+        max_concurrent_requests: Option<usize>,
+        max_sample_size: Option<usize>,
+    ) -> PyResult<String> {
+        let mut options = deltalake::audit::AuditOptions::default();
+        if let Some(max_concurrent_requests) = max_concurrent_requests {
+            options.max_concurrent_requests = max_concurrent_requests;
+        }
+        if let Some(max_sample_size) = max_sample_size {
+            options.max_sample_size = max_sample_size;
+        }
+        let report = allow_threads(py, || {
+            let table = self.table_without_gil()?;
+            wait_for_future(table.audit(&options))?.map_err(DeltaError::from_raw)
+        })?;
+        serde_json::to_string(&report).map_err(|err| DeltaError::new_err(err.to_string()))
+    }
+
+    pub fn detail(&self, py: Python) -> PyResult<String> {
+        let detail = self.table(py)?.get_detail().map_err(DeltaError::from_raw)?;
+        serde_json::to_string(&detail).map_err(|err| DeltaError::new_err(err.to_string()))
+    }
+
+    pub fn arrow_schema_json(&self, py: Python) -> PyResult<String> {
+        let table = self.table(py)?;
+        let schema = table.get_schema().map_err(DeltaError::from_raw)?;
+        arrow_schema_json(schema)
     }
 
-    pub fn update_incremental(&mut self) -> PyResult<()> {
-        rt()?
-            .block_on(self._table.update_incremental())
-            .map_err(PyDeltaTableError::from_raw)
+    /// Applies the commits newer than the loaded version, up to `max_version`, and returns the
+    /// number of versions applied.
+    #[args(max_version = "None")]
+    pub fn update_incremental(
+        &self,
+        py: Python,
+        max_version: Option<deltalake::DeltaDataTypeVersion>,
+    ) -> PyResult<deltalake::DeltaDataTypeVersion> {
+        let mut applied = 0;
+        self.update_with(py, |table| {
+            let update = wait_for_future(table.prepare_update_incremental_to(max_version))?
+                .map_err(DeltaError::from_raw)?;
+            applied = update.version() - table.version;
+            Ok(update)
+        })?;
+        Ok(applied)
+    }
+
+    /// Updates the table without blocking, returns an awaitable and its abort handle. Once the
+    /// new commits are read, the update is installed even if the awaitable was aborted.
+    pub fn update_incremental_async<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(&'py PyAny, AbortHandle)> {
+        let table = self._table.clone();
+        let update_lock = self._update_lock.clone();
+        future_into_py(py, async move {
+            // the table is only readable through a blocking lock
+            tokio::task::spawn_blocking(move || {
+                apply_update_with(&table, &update_lock, |table| {
+                    tokio::runtime::Handle::current()
+                        .block_on(table.prepare_update_incremental())
+                        .map_err(DeltaError::from_raw)
+                })
+            })
+            .await
+            .map_err(|err| DeltaError::new_err(err.to_string()))??;
+            Ok(Python::with_gil(|py| py.None()))
+        })
+    }
+}
+
+/// Streams record batches to a table, see [`StreamingRecordBatchWriter`]. Once closed or
+/// aborted the writer cannot be used anymore.
+#[pyclass]
+struct DeltaWriter {
+    _table: Arc<RwLock<deltalake::DeltaTable>>,
+    _update_lock: Arc<Mutex<()>>,
+    writer: Option<StreamingRecordBatchWriter>,
+    commit_info: Option<serde_json::Map<String, serde_json::Value>>,
+    transaction_options: Option<deltalake::DeltaTransactionOptions>,
+}
+
+impl DeltaWriter {
+    fn writer(&mut self) -> PyResult<&mut StreamingRecordBatchWriter> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| DeltaError::new_err("The writer is already closed"))
+    }
+}
+
+#[pymethods]
+impl DeltaWriter {
+    /// Writes a batch to the open files, uploading the files reaching the target size. If the
+    /// write fails, the uploaded files are deleted and the writer is closed.
+    pub fn write_batch(&mut self, py: Python, batch: &PyAny) -> PyResult<()> {
+        let batch = RecordBatch::from_pyarrow(batch)?;
+        let table = self._table.clone();
+        let writer = self.writer()?;
+        let result = allow_threads(py, || {
+            let table = table.read().map_err(DeltaError::from_poison)?;
+            wait_for_future(writer.write(&table, &batch))?.map_err(DeltaError::from_raw)
+        });
+        if result.is_err() {
+            if let Err(err) = self.abort(py) {
+                log::warn!("Deleting the uncommitted files failed: {}", err);
+            }
+        }
+        result
+    }
+
+    /// Appends the written files to the table in a single commit, returns the write metrics. If
+    /// the files are not committed, e.g. because the close was interrupted, they are deleted.
+    pub fn close(&mut self, py: Python) -> PyResult<HashMap<&'static str, i64>> {
+        let writer = self
+            .writer
+            .take()
+            .ok_or_else(|| DeltaError::new_err("The writer is already closed"))?;
+        let commit_info = self.commit_info.take();
+        let transaction_options = self.transaction_options.take();
+        allow_threads(py, || {
+            close_writer(
+                writer,
+                &self._table,
+                &self._update_lock,
+                commit_info,
+                transaction_options,
+            )
+            .map(write_metrics_dict)
+        })
     }
+
+    /// Deletes the files uploaded so far and closes the writer, leaving the table unchanged.
+    pub fn abort(&mut self, py: Python) -> PyResult<()> {
+        let mut writer = match self.writer.take() {
+            Some(writer) => writer,
+            None => return Ok(()),
+        };
+        allow_threads(py, || {
+            let table = self._table.read().map_err(DeltaError::from_poison)?;
+            wait_for_future(writer.abort(&table))?.map_err(DeltaError::from_raw)
+        })
+    }
+}
+
+/// Commits the files of `writer` with a clone of `table`, so that the table stays readable and
+/// updatable meanwhile, and installs the committed version afterwards. Uncommitted files are
+/// deleted on every error.
+fn close_writer(
+    mut writer: StreamingRecordBatchWriter,
+    table: &RwLock<deltalake::DeltaTable>,
+    update_lock: &Mutex<()>,
+    commit_info: Option<serde_json::Map<String, serde_json::Value>>,
+    transaction_options: Option<deltalake::DeltaTransactionOptions>,
+) -> PyResult<WriteMetrics> {
+    let mut commit_table = match table.read() {
+        Ok(table) => table.clone(),
+        Err(err) => {
+            let poisoned = DeltaError::new_err(err.to_string());
+            // the poisoned table still locates the files to delete
+            return Err(abort_writer(writer, &err.into_inner(), poisoned));
+        }
+    };
+    let committed = wait_for_future(writer.flush(&commit_table)).and_then(|flushed| {
+        flushed.map_err(DeltaError::from_raw)?;
+        // not interruptible, the files must not be deleted once the commit may have happened
+        Ok(io_runtime().block_on(writer.commit(
+            &mut commit_table,
+            commit_info,
+            transaction_options,
+        )))
+    });
+    let result = match committed {
+        Ok(Ok(metrics)) => Ok(metrics),
+        // the files were committed, only the post-commit hooks failed
+        Ok(Err(err @ deltalake::DeltaTableError::PostCommitHookFailed { .. })) => {
+            Err(DeltaError::from_raw(err))
+        }
+        Ok(Err(err)) => {
+            return Err(abort_writer(
+                writer,
+                &commit_table,
+                DeltaError::from_raw(err),
+            ))
+        }
+        Err(err) => return Err(abort_writer(writer, &commit_table, err)),
+    };
+    apply_update_with(table, update_lock, |table| {
+        wait_for_future(table.prepare_update())?.map_err(DeltaError::from_raw)
+    })?;
+    result
+}
+
+/// Deletes the uncommitted files of a writer that failed with `err`, returns `err`. Not
+/// interruptible, so that an interrupted close deletes the files too.
+fn abort_writer(
+    mut writer: StreamingRecordBatchWriter,
+    table: &deltalake::DeltaTable,
+    err: PyErr,
+) -> PyErr {
+    if let Err(abort_err) = io_runtime().block_on(writer.abort(table)) {
+        log::warn!("Deleting the uncommitted files failed: {}", abort_err);
+    }
+    err
+}
+
+/// The metadata of an object as passed to Python: the path, the modification time in seconds,
+/// the size, the ETag and the version.
+type ObjectTuple = (String, String, Option<i64>, Option<String>, Option<String>);
+
+fn object_tuple(obj: storage::ObjectMeta) -> ObjectTuple {
+    (
+        obj.path,
+        obj.modified.timestamp().to_string(),
+        obj.size,
+        obj.e_tag,
+        obj.version,
+    )
 }
 
 #[pyclass]
 pub struct DeltaStorageFsBackend {
     _storage: Box<dyn StorageBackend>,
+    _table_uri: String,
+    _metrics: Arc<storage::metrics::StorageMetrics>,
 }
 
 #[pymethods]
 impl DeltaStorageFsBackend {
     #[new]
-    fn new(table_uri: &str) -> PyResult<Self> {
-        let storage =
-            storage::get_backend_for_uri(table_uri).map_err(PyDeltaTableError::from_storage)?;
-        Ok(Self { _storage: storage })
+    #[args(storage_options = "None")]
+    fn new(table_uri: &str, storage_options: Option<HashMap<String, String>>) -> PyResult<Self> {
+        let table_uri =
+            storage::normalize_table_uri(table_uri).map_err(DeltaError::from_storage)?;
+        let storage = storage::get_backend_for_uri_with_options(
+            &table_uri,
+            storage_options.unwrap_or_default(),
+        )
+        .map_err(DeltaError::from_storage)?;
+        let storage = storage::metrics::MetricsStorageBackend::new(storage);
+        Ok(Self {
+            _metrics: storage.metrics(),
+            _storage: Box::new(storage),
+            _table_uri: table_uri,
+        })
+    }
+
+    /// Returns the name, number of calls, transferred bytes and total latency in seconds of each
+    /// storage operation.
+    fn metrics(&self) -> Vec<(&'static str, u64, u64, f64)> {
+        storage::metrics::StorageOperation::ALL
+            .iter()
+            .map(|operation| {
+                let metrics = self._metrics.get(*operation);
+                (
+                    operation.name(),
+                    metrics.calls,
+                    metrics.bytes,
+                    metrics.latency.as_secs_f64(),
+                )
+            })
+            .collect()
+    }
+
+    fn reset_metrics(&self) {
+        self._metrics.reset();
+        if let Some(stats) = self._storage.rate_limit_stats() {
+            stats.reset();
+        }
+    }
+
+    /// Returns the number of operations that waited for the rate limiter, their total wait time
+    /// in seconds and the number of operations in flight, or None if no limit is configured.
+    fn rate_limit_stats(&self) -> Option<(u64, f64, u64)> {
+        self._storage.rate_limit_stats().map(|stats| {
+            (
+                stats.throttled(),
+                stats.wait_time().as_secs_f64(),
+                stats.in_flight(),
+            )
+        })
+    }
+
+    fn is_safe_for_concurrent_writes(&self) -> bool {
+        self._storage.is_safe_for_concurrent_writes()
     }
 
+    fn table_uri(&self) -> PyResult<&str> {
+        Ok(&self._table_uri)
+    }
+
+    /// Normalizes the path like table URIs, so that paths of the handler and table URIs agree.
     fn normalize_path(&self, path: &str) -> PyResult<String> {
-        Ok(self._storage.trim_path(path))
+        let invalid_path = |err: &dyn std::fmt::Display| {
+            PyValueError::new_err(format!("Invalid path {:?}: {}", path, err))
+        };
+        let normalized = storage::normalize_table_uri(path).map_err(|err| invalid_path(&err))?;
+        storage::parse_uri(&normalized).map_err(|err| invalid_path(&err))?;
+        Ok(normalized)
     }
 
     fn head_obj<'py>(&mut self, py: Python<'py>, path: &str) -> PyResult<&'py PyTuple> {
-        let obj = rt()?
-            .block_on(self._storage.head_obj(path))
-            .map_err(PyDeltaTableError::from_storage)?;
+        let storage = &self._storage;
+        let obj = allow_threads(py, || {
+            wait_for_future(storage.head_obj(path))?.map_err(DeltaError::from_storage)
+        })?;
         Ok(PyTuple::new(
             py,
             &[obj.path, obj.modified.timestamp().to_string()],
         ))
     }
 
+    /// Fetches the metadata of the objects with up to `max_concurrent_requests` requests in
+    /// flight. Objects that do not exist are returned as None.
+    fn head_objs(
+        &mut self,
+        py: Python,
+        paths: Vec<String>,
+        max_concurrent_requests: usize,
+    ) -> PyResult<Vec<Option<ObjectTuple>>> {
+        let storage = &self._storage;
+        allow_threads(py, || {
+            wait_for_future(
+                futures::stream::iter(paths.iter().map(|path| async move {
+                    match storage.head_obj(path).await {
+                        Ok(obj) => Ok(Some(object_tuple(obj))),
+                        Err(deltalake::StorageError::NotFound) => Ok(None),
+                        Err(err) => Err(err),
+                    }
+                }))
+                .buffered(max_concurrent_requests.max(1))
+                .try_collect::<Vec<_>>(),
+            )?
+            .map_err(DeltaError::from_storage)
+        })
+    }
+
     fn get_obj<'py>(&mut self, py: Python<'py>, path: &str) -> PyResult<&'py PyBytes> {
-        let obj = rt()?
-            .block_on(self._storage.get_obj(path))
-            .map_err(PyDeltaTableError::from_storage)?;
+        let storage = &self._storage;
+        let obj = allow_threads(py, || {
+            wait_for_future(storage.get_obj(path))?.map_err(DeltaError::from_storage)
+        })?;
+        Ok(PyBytes::new(py, &obj))
+    }
+
+    /// Reads the bytes from `start` up to `end` of an object, fewer if it ends before.
+    fn get_obj_range<'py>(
+        &mut self,
+        py: Python<'py>,
+        path: &str,
+        start: usize,
+        end: usize,
+    ) -> PyResult<&'py PyBytes> {
+        let storage = &self._storage;
+        let obj = allow_threads(py, || {
+            wait_for_future(storage.get_range(path, start..end))?.map_err(DeltaError::from_storage)
+        })?;
         Ok(PyBytes::new(py, &obj))
     }
+
+    fn put_obj(&mut self, py: Python, path: &str, data: &[u8]) -> PyResult<()> {
+        let storage = &self._storage;
+        allow_threads(py, || {
+            wait_for_future(storage.put_obj(path, data))?.map_err(DeltaError::from_storage)
+        })
+    }
+
+    fn rename_obj_noreplace(&mut self, py: Python, src: &str, dst: &str) -> PyResult<()> {
+        let storage = &self._storage;
+        allow_threads(py, || {
+            wait_for_future(storage.rename_obj_noreplace(src, dst))?
+                .map_err(DeltaError::from_storage)
+        })
+    }
+
+    fn delete_obj(&mut self, py: Python, path: &str) -> PyResult<()> {
+        let storage = &self._storage;
+        allow_threads(py, || {
+            wait_for_future(storage.delete_obj(path))?.map_err(DeltaError::from_storage)
+        })
+    }
+
+    /// Lists the objects below `path`.
+    fn list_objs(&mut self, py: Python, path: &str) -> PyResult<Vec<ObjectTuple>> {
+        let storage = &self._storage;
+        allow_threads(py, || {
+            wait_for_future(async {
+                storage
+                    .list_objs(path)
+                    .await?
+                    .map_ok(object_tuple)
+                    .try_collect::<Vec<_>>()
+                    .await
+            })?
+            .map_err(DeltaError::from_storage)
+        })
+    }
+
+    /// Lists up to `max_results` objects below `path` whose paths sort after `start_after`, in
+    /// lexicographic order, and returns them with whether more objects follow.
+    fn list_objs_after(
+        &mut self,
+        py: Python,
+        path: &str,
+        start_after: &str,
+        max_results: usize,
+    ) -> PyResult<(Vec<ObjectTuple>, bool)> {
+        let storage = &self._storage;
+        allow_threads(py, || {
+            wait_for_future(async {
+                let mut objs = storage
+                    .list_objs_after(path, start_after)
+                    .await?
+                    .map_ok(object_tuple)
+                    .take(max_results + 1)
+                    .try_collect::<Vec<_>>()
+                    .await?;
+                let truncated = objs.len() > max_results;
+                objs.truncate(max_results);
+                Ok::<_, deltalake::StorageError>((objs, truncated))
+            })?
+            .map_err(DeltaError::from_storage)
+        })
+    }
+
+    /// Deletes the objects with up to `max_concurrent_requests` requests in flight and returns
+    /// the error message of each path, or None if it was deleted.
+    fn delete_objs(
+        &mut self,
+        py: Python,
+        paths: Vec<String>,
+        ignore_not_found: bool,
+        max_concurrent_requests: usize,
+    ) -> PyResult<Vec<Option<String>>> {
+        let storage = &self._storage;
+        allow_threads(py, || {
+            wait_for_future(
+                futures::stream::iter(paths.iter().map(|path| async move {
+                    match storage.delete_obj(path).await {
+                        Ok(()) => None,
+                        Err(deltalake::StorageError::NotFound) if ignore_not_found => None,
+                        Err(err) => Some(err.to_string()),
+                    }
+                }))
+                .buffered(max_concurrent_requests.max(1))
+                .collect::<Vec<_>>(),
+            )
+        })
+    }
 }
 
 #[pyfunction]
@@ -278,12 +1784,38 @@ fn rust_core_version() -> &'static str {
 #[pymodule]
 // module name need to match project name
 fn deltalake(py: Python, m: &PyModule) -> PyResult<()> {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    // Forward the log records of the Rust crates to the `logging` module, e.g. the records of
+    // `deltalake::delta` go to the "deltalake.delta" logger. Forwarding a record takes the GIL,
+    // so the levels disabled in Python are filtered out beforehand, see `sync_log_level`.
+    pyo3_log::Logger::new(py, pyo3_log::Caching::Loggers)?
+        .install()
+        .map_err(|err| DeltaError::new_err(err.to_string()))?;
+    sync_log_level(py);
 
     m.add_function(pyo3::wrap_pyfunction!(rust_core_version, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(set_io_runtime, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(create_deltalake_writer, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(arrow_schema_json_from_delta, m)?)?;
     m.add_class::<RawDeltaTable>()?;
     m.add_class::<RawDeltaTableMetaData>()?;
     m.add_class::<DeltaStorageFsBackend>()?;
-    m.add("PyDeltaTableError", py.get_type::<PyDeltaTableError>())?;
+    m.add_class::<AbortHandle>()?;
+    m.add_class::<DeltaWriter>()?;
+    m.add("DeltaError", py.get_type::<DeltaError>())?;
+    // kept for code written against earlier releases
+    m.add("PyDeltaTableError", py.get_type::<DeltaError>())?;
+    m.add("TableNotFoundError", py.get_type::<TableNotFoundError>())?;
+    m.add("CommitFailedError", py.get_type::<CommitFailedError>())?;
+    m.add("DeltaProtocolError", py.get_type::<DeltaProtocolError>())?;
+    m.add("SchemaMismatchError", py.get_type::<SchemaMismatchError>())?;
+    m.add(
+        "TimestampBeforeFirstCommitError",
+        py.get_type::<TimestampBeforeFirstCommitError>(),
+    )?;
+    m.add(
+        "VersionNotInLogError",
+        py.get_type::<VersionNotInLogError>(),
+    )?;
+    StorageErrorTypes::add_to_module(py, m)?;
     Ok(())
 }
@@ -1,6 +1,31 @@
-from typing import Any, Callable
+from typing import Any, Callable, Dict, Optional
 
 RawDeltaTable: Any
-PyDeltaTableError: Any
 rust_core_version: Callable[[], str]
+set_io_runtime: Callable[..., None]
+create_deltalake_writer: Callable[..., Optional["DeltaWriter"]]
 DeltaStorageFsBackend: Any
+
+def arrow_schema_json_from_delta(schema_json: str) -> str: ...
+
+class DeltaError(Exception): ...
+
+PyDeltaTableError = DeltaError
+
+class TableNotFoundError(DeltaError): ...
+class CommitFailedError(DeltaError): ...
+class DeltaProtocolError(DeltaError): ...
+class SchemaMismatchError(DeltaError): ...
+class TimestampBeforeFirstCommitError(DeltaError): ...
+class VersionNotInLogError(DeltaError): ...
+class DeltaFileNotFoundError(DeltaError, FileNotFoundError): ...
+class DeltaFileExistsError(DeltaError, FileExistsError): ...
+class DeltaPermissionError(DeltaError, PermissionError): ...
+class DeltaTimeoutError(DeltaError, TimeoutError): ...
+class DeltaValueError(DeltaError, ValueError): ...
+class DeltaNotImplementedError(DeltaError, NotImplementedError): ...
+
+class DeltaWriter:
+    def write_batch(self, batch: Any) -> None: ...
+    def close(self) -> Dict[str, int]: ...
+    def abort(self) -> None: ...
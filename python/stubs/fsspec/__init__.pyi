@@ -0,0 +1,7 @@
+from typing import Any
+
+class AbstractFileSystem:
+    def __init__(self, *args: Any, **kwargs: Any) -> None: ...
+    def __getattr__(self, name: str) -> Any: ...
+
+def register_implementation(name: str, cls: Any, clobber: bool = ...) -> None: ...
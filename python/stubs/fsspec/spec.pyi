@@ -0,0 +1,7 @@
+from typing import Any
+
+class AbstractBufferedFile:
+    fs: Any
+    path: str
+    buffer: Any
+    def __init__(self, *args: Any, **kwargs: Any) -> None: ...
@@ -0,0 +1,82 @@
+import asyncio
+import pathlib
+import threading
+
+import pyarrow as pa
+import pytest
+
+from deltalake import DeltaTable as SyncDeltaTable
+from deltalake import write_deltalake
+from deltalake.aio import DeltaTable
+
+
+async def scan(dt: DeltaTable) -> pa.Table:
+    return pa.Table.from_batches(
+        [batch async for batch in dt.to_pyarrow_batches(columns=["value"])]
+    )
+
+
+@pytest.mark.asyncio
+async def test_load_and_concurrent_scans():
+    table_path = "../rust/tests/data/delta-0.8.0-partitioned"
+    dt = await DeltaTable.load(table_path)
+    assert dt.version() == 0
+
+    first, second = await asyncio.gather(scan(dt), scan(dt))
+    expected = dt.to_pyarrow_table(columns=["value"])
+    assert first.num_rows == second.num_rows == expected.num_rows
+    assert sorted(first["value"].to_pylist()) == sorted(expected["value"].to_pylist())
+    assert sorted(second["value"].to_pylist()) == sorted(
+        expected["value"].to_pylist()
+    )
+
+
+@pytest.mark.asyncio
+async def test_load_version_and_update(tmp_path: pathlib.Path):
+    data = pa.table({"value": pa.array([1, 2, 3], pa.int64())})
+    write_deltalake(str(tmp_path), data)
+    write_deltalake(str(tmp_path), data, mode="append")
+
+    dt = await DeltaTable.load(str(tmp_path), version=0)
+    assert dt.version() == 0
+    await dt.update()
+    assert dt.version() == 1
+    assert (await scan(dt)).num_rows == 6
+
+
+@pytest.mark.asyncio
+async def test_cancel_load():
+    task = asyncio.ensure_future(
+        DeltaTable.load("../rust/tests/data/delta-0.8.0-partitioned")
+    )
+    await asyncio.sleep(0)
+    task.cancel()
+    with pytest.raises(asyncio.CancelledError):
+        await task
+
+
+@pytest.mark.asyncio
+async def test_scan_creates_dataset_off_the_event_loop(
+    monkeypatch: pytest.MonkeyPatch,
+):
+    dt = await DeltaTable.load("../rust/tests/data/delta-0.8.0-partitioned")
+    threads = []
+    to_pyarrow_dataset = dt.to_pyarrow_dataset
+
+    def recording_to_pyarrow_dataset(*args, **kwargs):
+        threads.append(threading.current_thread())
+        return to_pyarrow_dataset(*args, **kwargs)
+
+    monkeypatch.setattr(dt, "to_pyarrow_dataset", recording_to_pyarrow_dataset)
+    assert (await scan(dt)).num_rows == dt.to_pyarrow_table().num_rows
+    assert threads[0] is not threading.main_thread()
+
+
+def test_constructors():
+    table_path = "../rust/tests/data/delta-0.8.0-partitioned"
+    dt = DeltaTable(table_path, version=0)
+    assert isinstance(dt, SyncDeltaTable)
+    assert dt.version() == 0
+
+    wrapped = DeltaTable.from_table(SyncDeltaTable(table_path)._table)
+    assert wrapped.files() == dt.files()
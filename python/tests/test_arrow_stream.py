@@ -0,0 +1,56 @@
+import pyarrow as pa
+import pytest
+
+from deltalake import DeltaTable
+
+pytestmark = pytest.mark.skipif(
+    not hasattr(pa.RecordBatchReader, "_import_from_c_capsule"),
+    reason="requires pyarrow 14",
+)
+
+
+@pytest.fixture()
+def dt() -> DeltaTable:
+    return DeltaTable("../rust/tests/data/delta-0.8.0-partitioned")
+
+
+def sort_rows(table: pa.Table, columns):
+    return sorted(zip(*(table.column(name).to_pylist() for name in columns)))
+
+
+def test_stream_to_pyarrow(dt: DeltaTable):
+    reader = pa.RecordBatchReader._import_from_c_capsule(dt.__arrow_c_stream__())
+    table = reader.read_all()
+    expected = dt.to_pyarrow_table()
+    assert table.schema == expected.schema
+    assert sort_rows(table, table.column_names) == sort_rows(
+        expected, expected.column_names
+    )
+
+
+def test_stream_with_requested_schema(dt: DeltaTable):
+    requested = pa.schema(
+        [pa.field("value", pa.string()), pa.field("year", pa.string())]
+    )
+    reader = pa.RecordBatchReader._import_from_c_capsule(
+        dt.__arrow_c_stream__(requested.__arrow_c_schema__())
+    )
+    table = reader.read_all()
+    assert table.schema == requested
+    assert sort_rows(table, ["value", "year"]) == sort_rows(
+        dt.to_pyarrow_table(), ["value", "year"]
+    )
+
+
+def test_stream_to_polars(dt: DeltaTable):
+    pl = pytest.importorskip("polars")
+    df = pl.from_arrow(dt)
+    expected = dt.to_pyarrow_table()
+    assert sorted(df["value"].to_list()) == sorted(expected["value"].to_pylist())
+
+
+def test_stream_to_duckdb(dt: DeltaTable):
+    duckdb = pytest.importorskip("duckdb")
+    result = duckdb.sql("SELECT value FROM dt ORDER BY value").fetchall()
+    expected = dt.to_pyarrow_table()
+    assert [row[0] for row in result] == sorted(expected["value"].to_pylist())
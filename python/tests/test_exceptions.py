@@ -0,0 +1,75 @@
+import json
+import pathlib
+
+import pyarrow as pa
+import pytest
+
+from deltalake import (
+    CommitFailedError,
+    DeltaError,
+    DeltaProtocolError,
+    DeltaTable,
+    PyDeltaTableError,
+    SchemaMismatchError,
+    TableNotFoundError,
+    write_deltalake,
+)
+
+
+@pytest.fixture()
+def sample_data() -> pa.Table:
+    return pa.table({"id": pa.array([1, 2, 3], pa.int64())})
+
+
+def commit_protocol(table_path: pathlib.Path, version: int, reader: int, writer: int):
+    action = {"protocol": {"minReaderVersion": reader, "minWriterVersion": writer}}
+    (table_path / "_delta_log" / f"{version:020}.json").write_text(json.dumps(action))
+
+
+def test_exception_hierarchy():
+    assert PyDeltaTableError is DeltaError
+    for error in [
+        TableNotFoundError,
+        CommitFailedError,
+        DeltaProtocolError,
+        SchemaMismatchError,
+    ]:
+        assert issubclass(error, DeltaError)
+
+
+def test_table_not_found(tmp_path: pathlib.Path):
+    with pytest.raises(TableNotFoundError):
+        DeltaTable(str(tmp_path / "missing"))
+
+    # code catching the previous error type keeps working
+    with pytest.raises(PyDeltaTableError):
+        DeltaTable(str(tmp_path))
+
+
+def test_schema_mismatch(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data)
+
+    other_data = pa.table({"other": pa.array(["a"])})
+    with pytest.raises(SchemaMismatchError):
+        write_deltalake(str(tmp_path), other_data, mode="append")
+
+
+def test_unsupported_reader_protocol(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data)
+    dt = DeltaTable(str(tmp_path))
+    commit_protocol(tmp_path, 1, reader=3, writer=7)
+
+    with pytest.raises(DeltaProtocolError):
+        DeltaTable(str(tmp_path))
+    with pytest.raises(DeltaProtocolError):
+        dt.update_incremental()
+
+
+def test_unsupported_writer_protocol(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data)
+    commit_protocol(tmp_path, 1, reader=1, writer=7)
+
+    # the table can still be read
+    assert DeltaTable(str(tmp_path)).version() == 1
+    with pytest.raises(DeltaProtocolError):
+        write_deltalake(str(tmp_path), sample_data, mode="append")
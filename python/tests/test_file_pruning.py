@@ -0,0 +1,132 @@
+import pathlib
+
+import pyarrow as pa
+import pyarrow.dataset as ds
+import pytest
+
+from deltalake import DeltaError, DeltaTable, write_deltalake
+
+
+@pytest.fixture()
+def pruning_table(tmp_path: pathlib.Path) -> DeltaTable:
+    # one file per write: a partition with the value bounds 1..2, one with 10..20 and a
+    # null partition without values
+    for dates, values in [
+        (["2021-01-01", "2021-01-01"], [1, 2]),
+        (["2021-01-02", "2021-01-02"], [10, 20]),
+        ([None, None], [None, None]),
+    ]:
+        data = pa.table(
+            {
+                "date": pa.array(dates, pa.string()),
+                "value": pa.array(values, pa.int64()),
+            }
+        )
+        write_deltalake(str(tmp_path), data, partition_by=["date"], mode="append")
+    return DeltaTable(str(tmp_path))
+
+
+def sorted_values(table: pa.Table):
+    return sorted(table["value"].to_pylist(), key=lambda v: (v is None, v))
+
+
+@pytest.mark.parametrize(
+    "expression,expected_files,expected_values",
+    [
+        (ds.field("date") == "2021-01-01", 1, [1, 2]),
+        (ds.field("value") > 5, 1, [10, 20]),
+        ((ds.field("value") >= 2) & (ds.field("value") < 15), 2, [2, 10]),
+        (ds.field("date").isin(["2021-01-02"]), 1, [10, 20]),
+        (ds.field("value").is_null(), 1, [None, None]),
+        (ds.field("value") > 100, 0, []),
+    ],
+)
+def test_filter_prunes_files(
+    pruning_table: DeltaTable, expression, expected_files, expected_values
+):
+    dataset = pruning_table.to_pyarrow_dataset(filter=expression)
+    assert len(list(dataset.get_fragments())) == expected_files
+
+    table = pruning_table.to_pyarrow_table(filter=expression)
+    assert sorted_values(table) == expected_values
+    assert sorted_values(table) == sorted_values(
+        pruning_table.to_pyarrow_dataset().to_table(filter=expression)
+    )
+
+
+@pytest.mark.parametrize(
+    "predicate,expected_files",
+    [
+        ("date = '2021-01-01'", 1),
+        ("value > 5", 1),
+        ("value >= 2 AND value < 15", 2),
+        ("date IN ('2021-01-02')", 1),
+        ("value IS NULL", 1),
+        ("value > 100", 0),
+        ("NOT value > 100", 2),
+    ],
+)
+def test_predicate_prunes_files(pruning_table: DeltaTable, predicate, expected_files):
+    assert len(pruning_table.files_by_predicate(predicate)) == expected_files
+    dataset = pruning_table.to_pyarrow_dataset(filter=predicate)
+    assert len(list(dataset.get_fragments())) == expected_files
+
+
+@pytest.mark.parametrize(
+    "predicate,expected_values",
+    [
+        ("DATE = '2021-01-01'", [1, 2]),
+        ("Value >= 2 AND VALUE < 15", [2, 10]),
+        ("value > 1.5", [2, 10, 20]),
+        ("value IN (1, 20)", [1, 20]),
+        ("value NOT IN (1, 20)", [2, 10]),
+        ("NOT value = 1", [2, 10, 20]),
+        ("value IS NULL OR date IS NULL", [None, None]),
+    ],
+)
+def test_predicate_filters_rows(pruning_table: DeltaTable, predicate, expected_values):
+    table = pruning_table.to_pyarrow_table(filter=predicate)
+    assert sorted_values(table) == expected_values
+    expression = pruning_table.predicate_expression(predicate)
+    assert sorted_values(pruning_table.to_pyarrow_table(filter=expression)) == (
+        expected_values
+    )
+    df = pruning_table.to_pandas(filter=predicate)
+    assert len(df) == len(expected_values)
+
+
+def test_predicate_with_partition_filters(pruning_table: DeltaTable):
+    dataset = pruning_table.to_pyarrow_dataset(
+        partitions=[("date", "=", "2021-01-02")], filter="value < 5 OR value > 15"
+    )
+    assert sorted_values(dataset.to_table()) == [10, 20]
+    dataset = pruning_table.to_pyarrow_dataset(
+        partitions=[("date", "=", "2021-01-02")], filter="value < 5"
+    )
+    assert len(list(dataset.get_fragments())) == 0
+
+
+def test_invalid_predicate(pruning_table: DeltaTable):
+    with pytest.raises(DeltaError, match="Column missing not found"):
+        pruning_table.files_by_predicate("missing = 1")
+    with pytest.raises(DeltaError, match="Invalid predicate"):
+        pruning_table.files_by_predicate("value = 'ten'")
+    with pytest.raises(DeltaError, match="Column missing not found"):
+        pruning_table.predicate_expression("missing = 1")
+
+
+def test_filter_with_unsupported_expression(pruning_table: DeltaTable):
+    expression = ds.field("value").cast(pa.string()) == "10"
+    table = pruning_table.to_pyarrow_table(filter=expression)
+    assert sorted_values(table) == [10]
+
+
+def test_filter_with_partition_filters(pruning_table: DeltaTable):
+    table = pruning_table.to_pyarrow_table(
+        partitions=[("date", "=", "2021-01-02")], filter=ds.field("value") < 15
+    )
+    assert sorted_values(table) == [10]
+
+
+def test_no_filter_keeps_all_files(pruning_table: DeltaTable):
+    assert len(list(pruning_table.to_pyarrow_dataset().get_fragments())) == 3
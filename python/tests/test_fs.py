@@ -1,8 +1,24 @@
+import gc
+import http.server
+import os
+import pickle
+import socket
+import subprocess
+import sys
+import threading
+import time
+import uuid
+from concurrent.futures import ThreadPoolExecutor
+from datetime import datetime
+
 import pandas as pd
 import pyarrow as pa
+import pyarrow.dataset as ds
 import pyarrow.parquet as pq
 import pytest
+from pyarrow.fs import FileSelector, FileType, PyFileSystem
 
+import deltalake
 from deltalake import DeltaTable
 from deltalake.deltalake import DeltaStorageFsBackend
 from deltalake.fs import DeltaStorageHandler
@@ -13,6 +29,37 @@ def test_normalize_path():
     assert backend.normalize_path("s3://foo/bar") == "s3://foo/bar"
     assert backend.normalize_path("s3://foo/bar/") == "s3://foo/bar"
     assert backend.normalize_path("/foo/bar//") == "/foo/bar"
+    assert backend.normalize_path("file:///foo/bar/") == "/foo/bar"
+    assert backend.normalize_path("/foo/../bar/./baz") == "/bar/baz"
+    assert backend.normalize_path("S3://Foo/a%20b") == "s3://foo/a%20b"
+    assert backend.normalize_path("tests") == os.path.abspath("tests")
+    with pytest.raises(ValueError, match="unknown://foo"):
+        backend.normalize_path("unknown://foo/bar")
+
+
+def test_handler_equality_normalizes_uri():
+    table_path = "../rust/tests/data/simple_table"
+    absolute_path = os.path.abspath(table_path)
+    handler = DeltaStorageHandler(table_path)
+    for uri in [
+        absolute_path,
+        absolute_path + "/",
+        "file://" + absolute_path,
+        "../rust/tests/data/../data/simple_table",
+    ]:
+        assert handler == DeltaStorageHandler(uri)
+        assert hash(handler) == hash(DeltaStorageHandler(uri))
+    assert handler != DeltaStorageHandler("../rust/tests/data/delta-0.8.0")
+
+
+def test_handler_table_uri():
+    handler = DeltaStorageHandler("../rust/tests/data/simple_table/")
+    assert handler.table_uri == os.path.abspath("../rust/tests/data/simple_table")
+
+
+def test_table_uri_is_normalized():
+    dt = DeltaTable("../rust/tests/data/simple_table/")
+    assert dt._table.table_uri() == os.path.abspath("../rust/tests/data/simple_table")
 
 
 @pytest.mark.s3
@@ -38,3 +85,937 @@ def test_read_simple_table_from_remote(s3_localstack):
     table_path = "s3://deltars/simple"
     dt = DeltaTable(table_path)
     assert dt.to_pandas().equals(pd.DataFrame({"id": [5, 7, 9]}))
+
+
+def test_open_output_stream(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    path = str(tmp_path / "nested" / "data.bin")
+    data = os.urandom(100_000)
+
+    with handler.open_output_stream(path) as f:
+        # nothing is uploaded before the stream is closed
+        for offset in range(0, len(data), 3_000):
+            f.write(data[offset : offset + 3_000])
+        assert not os.path.exists(path)
+
+    with handler.open_input_stream(path) as f:
+        assert f.read() == data
+
+    with handler.open_output_stream(path) as f:
+        f.write(b"replaced")
+    with open(path, "rb") as f:
+        assert f.read() == b"replaced"
+
+
+def test_write_parquet_through_handler(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    path = str(tmp_path / "table.parquet")
+    table = pa.table({"id": list(range(1000)), "value": [str(i) for i in range(1000)]})
+
+    with handler.open_output_stream(path) as f:
+        pq.write_table(table, f, data_page_size=512)
+
+    assert pq.read_table(handler.open_input_file(path)) == table
+
+
+def test_output_stream_discarded_without_close(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    path = str(tmp_path / "data.bin")
+
+    f = handler.open_output_stream(path)
+    f.write(b"partial")
+    del f
+    gc.collect()
+    assert not os.path.exists(path)
+
+
+def test_delete_files(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    paths = [str(tmp_path / f"part-{i}.parquet") for i in range(50)]
+    for path in paths:
+        with open(path, "wb") as f:
+            f.write(b"data")
+
+    handler.delete_file(paths[0])
+    assert not os.path.exists(paths[0])
+
+    results = handler.delete_files(paths, max_concurrent_requests=4)
+    assert results[0] is not None
+    assert results[1:] == [None] * 49
+    assert os.listdir(tmp_path) == []
+
+    assert handler.delete_files(paths[:2], ignore_not_found=True) == [None, None]
+
+
+@pytest.mark.skipif(not hasattr(os, "symlink"), reason="requires symlinks")
+def test_delete_dir_stays_within_table_root(tmp_path):
+    table = tmp_path / "table"
+    other = tmp_path / "other_table"
+    partition = table / "part=1"
+    (partition / "nested").mkdir(parents=True)
+    other.mkdir()
+    (partition / "a.parquet").write_bytes(b"data")
+    (partition / "nested" / "b.parquet").write_bytes(b"data")
+    (other / "keep.parquet").write_bytes(b"data")
+    os.symlink(other, partition / "linked", target_is_directory=True)
+    handler = DeltaStorageHandler(str(table))
+
+    planned = handler.delete_dir(str(partition), dry_run=True)
+    assert sorted(planned) == [
+        str(partition / "a.parquet"),
+        str(partition / "nested"),
+        str(partition / "nested" / "b.parquet"),
+    ]
+    assert (partition / "a.parquet").exists()
+
+    # the symlink is skipped, so the partition directory is kept with it
+    handler.delete_dir(str(partition))
+    assert os.listdir(partition) == ["linked"]
+    assert os.listdir(other) == ["keep.parquet"]
+
+    with pytest.raises(PermissionError, match="linked"):
+        handler.delete_dir(str(partition), follow_symlinks=True)
+    assert os.listdir(other) == ["keep.parquet"]
+    with pytest.raises(PermissionError):
+        handler.delete_dir(str(other))
+    assert os.listdir(other) == ["keep.parquet"]
+
+
+@pytest.mark.skipif(not hasattr(os, "symlink"), reason="requires symlinks")
+def test_delete_dir_follows_symlinks_within_table_root(tmp_path):
+    partition = tmp_path / "part=1"
+    target = tmp_path / "part=2"
+    partition.mkdir()
+    target.mkdir()
+    (target / "c.parquet").write_bytes(b"data")
+    os.symlink(target, partition / "inner", target_is_directory=True)
+    os.symlink(partition, partition / "loop", target_is_directory=True)
+    handler = DeltaStorageHandler(str(tmp_path))
+
+    handler.delete_dir(str(partition), follow_symlinks=True)
+    # the link is removed and its target emptied, but the target stays
+    assert os.listdir(tmp_path) == ["part=2"]
+    assert os.listdir(target) == []
+
+    handler.delete_root_dir_contents()
+    assert os.listdir(tmp_path) == []
+    with pytest.raises(PermissionError):
+        DeltaStorageHandler(str(tmp_path), read_only=True).delete_dir_contents(
+            str(tmp_path)
+        )
+
+
+@pytest.mark.parametrize(
+    "dir_markers,suffix", [(True, "/"), ("hadoop", "_$folder$")], ids=["s3", "hadoop"]
+)
+def test_dir_markers(dir_markers, suffix):
+    root = f"memory://test_fs/dir_markers_{suffix.strip('/') or 'slash'}"
+    handler = DeltaStorageHandler(root, dir_markers=dir_markers)
+    handler.create_dir(f"{root}/empty/nested")
+    handler.create_dir(f"{root}/part=1", recursive=False)
+    with handler.open_output_stream(f"{root}/part=1/data.parquet") as f:
+        pq.write_table(pa.table({"x": [1, 2]}), f)
+
+    def objects():
+        return sorted(obj[0] for obj in handler._storage.list_objs(root))
+
+    assert objects() == sorted(
+        [
+            f"{root}/empty{suffix}",
+            f"{root}/empty/nested{suffix}",
+            f"{root}/part=1{suffix}",
+            f"{root}/part=1/data.parquet",
+        ]
+    )
+
+    [empty, missing] = handler.get_file_info([f"{root}/empty", f"{root}/missing"])
+    assert empty.type == FileType.Directory
+    assert missing.type == FileType.NotFound
+    assert handler.get_file_info_selector(FileSelector(f"{root}/empty/nested")) == []
+    infos = handler.get_file_info_selector(FileSelector(root, recursive=True))
+    assert [(info.path, info.type) for info in infos] == [
+        (f"{root}/empty", FileType.Directory),
+        (f"{root}/empty/nested", FileType.Directory),
+        (f"{root}/part=1", FileType.Directory),
+        (f"{root}/part=1/data.parquet", FileType.File),
+    ]
+
+    # dataset discovery finds neither files nor partitions in markers
+    dataset = ds.dataset(
+        root, filesystem=PyFileSystem(handler), format="parquet", partitioning="hive"
+    )
+    assert dataset.files == [f"{root}/part=1/data.parquet"]
+    assert dataset.to_table().num_rows == 2
+
+    assert handler.delete_dir(f"{root}/empty", dry_run=True) == [
+        f"{root}/empty/nested{suffix}",
+        f"{root}/empty{suffix}",
+    ]
+    handler.delete_dir(f"{root}/empty")
+    handler.delete_dir_contents(f"{root}/part=1")
+    assert objects() == [f"{root}/part=1{suffix}"]
+
+    with pytest.raises(ValueError, match="dir_markers"):
+        DeltaStorageHandler(root, dir_markers="gcs")
+
+
+def test_create_dir(tmp_path):
+    with pytest.raises(NotImplementedError):
+        DeltaStorageHandler(str(tmp_path)).create_dir(str(tmp_path / "a"))
+    handler = DeltaStorageHandler(str(tmp_path), dir_markers=True)
+    handler.create_dir(str(tmp_path / "a" / "b"))
+    assert (tmp_path / "a" / "b").is_dir()
+    # local directories need no markers
+    assert os.listdir(tmp_path / "a" / "b") == []
+
+
+def test_get_file_info(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    paths = [str(tmp_path / f"part-{i}.parquet") for i in range(3)]
+    for path in paths:
+        with open(path, "wb") as f:
+            f.write(b"data")
+    missing = str(tmp_path / "missing.parquet")
+
+    infos = handler.get_file_info(paths + [missing])
+    assert [info.path for info in infos] == paths + [missing]
+    assert [info.type for info in infos] == [FileType.File] * 3 + [FileType.NotFound]
+    mtime = os.path.getmtime(paths[0])
+    assert infos[0].mtime.timestamp() == pytest.approx(mtime, abs=1)
+
+
+def test_file_infos_from_log(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    dt = DeltaTable("../rust/tests/data/simple_table")
+    infos = handler.file_infos_from_log(dt._table.file_infos())
+    assert handler.get_metrics()["head"]["calls"] == 0
+    assert [info.path for info in infos] == dt.file_uris()
+    assert all(info.type == FileType.File and info.size > 0 for info in infos)
+
+    # known files are answered from memory, others are fetched
+    unknown = str(tmp_path / "data.bin")
+    with open(unknown, "wb") as f:
+        f.write(b"data")
+    infos = handler.file_infos_from_log(
+        [(infos[0].path, 10, 1000), (unknown, None, None)]
+    )
+    assert (infos[0].size, infos[0].mtime.timestamp()) == (10, 1.0)
+    assert infos[1].size == 4
+    assert handler.get_file_info([infos[0].path])[0].size == 10
+    assert handler.get_metrics()["head"]["calls"] == 1
+
+    # files deleted through the handler are forgotten
+    handler.file_infos_from_log([(unknown, 4, 0)])
+    handler.delete_file(unknown)
+    assert handler.get_file_info([unknown])[0].type == FileType.NotFound
+
+
+def test_open_append_stream(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    path = str(tmp_path / "log.txt")
+
+    with handler.open_append_stream(path) as f:
+        f.write(b"first\n")
+    with handler.open_append_stream(path) as f:
+        assert f.tell() == 6
+        f.write(b"second\n")
+    with open(path, "rb") as f:
+        assert f.read() == b"first\nsecond\n"
+
+    [info] = handler.get_file_info([path])
+    assert info.size == 13
+
+    with pytest.raises(OSError, match="max_append_size"):
+        DeltaStorageHandler(str(tmp_path), max_append_size=10).open_append_stream(path)
+    with pytest.raises(NotImplementedError):
+        DeltaStorageHandler(str(tmp_path), max_append_size=0).open_append_stream(path)
+
+
+def test_move(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    src = str(tmp_path / "src.bin")
+    dest = str(tmp_path / "dest.bin")
+    with open(src, "wb") as f:
+        f.write(b"src")
+
+    handler.move(src, dest)
+    assert not os.path.exists(src)
+    with open(dest, "rb") as f:
+        assert f.read() == b"src"
+
+    with open(src, "wb") as f:
+        f.write(b"new")
+    with pytest.raises(FileExistsError):
+        handler.move(src, dest)
+    assert os.path.exists(src)
+
+    handler.move(src, dest, overwrite=True)
+    assert not os.path.exists(src)
+    with open(dest, "rb") as f:
+        assert f.read() == b"new"
+
+
+def test_put_if_absent(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    path = str(tmp_path / "00000000000000000000.json")
+
+    assert handler.put_if_absent(path, b"first")
+    assert not handler.put_if_absent(path, b"second")
+    with open(path, "rb") as f:
+        assert f.read() == b"first"
+    assert os.listdir(tmp_path) == ["00000000000000000000.json"]
+
+
+class FailingStorage:
+    """Wraps a storage backend, replacing the given methods by failing ones."""
+
+    def __init__(self, storage, **overrides):
+        self._storage = storage
+        self._overrides = overrides
+
+    def __getattr__(self, name):
+        return self._overrides.get(name, getattr(self._storage, name))
+
+
+def test_write_atomic(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    path = str(tmp_path / "_last_checkpoint")
+
+    assert not handler.write_atomic(path, b"first")
+    assert handler.write_atomic(path, b"second")
+    with open(path, "rb") as f:
+        assert f.read() == b"first"
+    assert os.listdir(tmp_path) == ["_last_checkpoint"]
+
+
+def test_write_atomic_removes_temporary_file_on_failure(tmp_path):
+    def rename_obj_noreplace(src, dest):
+        raise ConnectionError("lost connection")
+
+    handler = DeltaStorageHandler(str(tmp_path))
+    handler._storage = FailingStorage(
+        handler._storage, rename_obj_noreplace=rename_obj_noreplace
+    )
+    path = str(tmp_path / "_last_checkpoint")
+
+    with pytest.raises(ConnectionError):
+        handler.write_atomic(path, b"data")
+    assert os.listdir(tmp_path) == []
+
+
+def test_write_atomic_without_rename():
+    def rename_obj_noreplace(src, dest):
+        raise AssertionError("renames are not safe")
+
+    handler = DeltaStorageHandler("memory://test_fs/write_atomic")
+    handler._storage = FailingStorage(
+        handler._storage,
+        is_safe_for_concurrent_writes=lambda: False,
+        rename_obj_noreplace=rename_obj_noreplace,
+    )
+    path = "memory://test_fs/write_atomic/_last_checkpoint"
+
+    assert not handler.write_atomic(path, b"first")
+    assert handler.write_atomic(path, b"second")
+    with handler.open_input_stream(path) as f:
+        assert f.read() == b"first"
+    assert [obj[0] for obj in handler._storage.list_objs(handler.table_uri)] == [path]
+
+
+def test_cleanup_atomic_writes():
+    handler = DeltaStorageHandler("memory://test_fs/cleanup_atomic_writes")
+    path = "memory://test_fs/cleanup_atomic_writes/_last_checkpoint"
+    assert not handler.write_atomic(path, b"data")
+    # a temporary file left behind by a writer killed before the rename
+    leftover = f"{path}.tmp-{uuid.uuid4()}"
+    with handler.open_output_stream(leftover) as f:
+        f.write(b"partial")
+
+    assert handler.cleanup_atomic_writes(path) == []
+    assert handler.cleanup_atomic_writes(path, min_age_seconds=0) == [leftover]
+    assert not handler.exists(leftover)
+    assert handler.exists(path)
+
+
+@pytest.mark.parametrize("memory", [False, True])
+def test_list_page(tmp_path, memory):
+    base_dir = "memory://test_fs/list_page" if memory else str(tmp_path)
+    handler = DeltaStorageHandler(base_dir)
+    for name in ["a", "b/c", "b/d/e", "b.txt"]:
+        with handler.open_output_stream(f"{base_dir}/{name}") as f:
+            f.write(b"data")
+
+    files, token = handler.list_page(base_dir, max_results=2)
+    assert [f["path"] for f in files] == [f"{base_dir}/a", f"{base_dir}/b.txt"]
+    assert files[0]["size"] == 4
+    assert token is not None
+    files, token = handler.list_page(base_dir, token, max_results=2)
+    assert [f["path"] for f in files] == [f"{base_dir}/b/c", f"{base_dir}/b/d/e"]
+    assert token is None
+
+
+def test_get_file_info_selector(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    for name in ["a", "b/c", "b/d/e"]:
+        (tmp_path / name).parent.mkdir(parents=True, exist_ok=True)
+        (tmp_path / name).write_bytes(b"data")
+
+    infos = handler.get_file_info_selector(FileSelector(str(tmp_path)))
+    assert [(info.path, info.type) for info in infos] == [
+        (str(tmp_path / "a"), FileType.File),
+        (str(tmp_path / "b"), FileType.Directory),
+    ]
+    infos = handler.get_file_info_selector(FileSelector(str(tmp_path), recursive=True))
+    assert len(infos) == 5
+    assert [info.path for info in infos if info.type == FileType.File] == [
+        str(tmp_path / "a"),
+        str(tmp_path / "b" / "c"),
+        str(tmp_path / "b" / "d" / "e"),
+    ]
+    infos = handler.get_file_info_selector(
+        FileSelector(str(tmp_path), recursive=True), max_results=2
+    )
+    assert len(infos) == 2
+
+    missing = str(tmp_path / "missing")
+    with pytest.raises(FileNotFoundError):
+        handler.get_file_info_selector(FileSelector(missing))
+    assert handler.get_file_info_selector(FileSelector(missing, True)) == []
+
+
+def test_list_paths_iter(monkeypatch):
+    root = "memory://test_fs/list_paths_iter"
+    handler = DeltaStorageHandler(root)
+    for name in ["a", "b/c", "b/d/e"]:
+        with handler.open_output_stream(f"{root}/{name}") as f:
+            f.write(b"data")
+
+    class FailingStorage:
+        def __init__(self, storage):
+            self.storage = storage
+            self.pages = 0
+
+        def __getattr__(self, name):
+            return getattr(self.storage, name)
+
+        def list_objs_after(self, *args):
+            self.pages += 1
+            if self.pages == 3:
+                raise OSError("listing failed")
+            return self.storage.list_objs_after(*args)
+
+    monkeypatch.setattr(deltalake.fs, "LIST_PAGE_SIZE", 1)
+    storage = FailingStorage(handler._storage)
+    handler._storage = storage
+
+    infos = handler.list_paths_iter(root, recursive=False)
+    assert storage.pages == 0
+    assert next(infos).path == f"{root}/a"
+    assert storage.pages == 1
+    assert next(infos).path == f"{root}/b"
+    with pytest.raises(OSError, match="listing failed"):
+        next(infos)
+
+    storage.pages = -10
+    infos = handler.list_paths_iter(root)
+    assert [(info.path, info.type) for info in infos] == [
+        (f"{root}/a", FileType.File),
+        (f"{root}/b", FileType.Directory),
+        (f"{root}/b/c", FileType.File),
+        (f"{root}/b/d", FileType.Directory),
+        (f"{root}/b/d/e", FileType.File),
+    ]
+
+
+def test_list_without_recursion_skips_subdirectories(monkeypatch):
+    root = "memory://test_fs/list_skips_subdirectories"
+    handler = DeltaStorageHandler(root)
+    for name in ["a", *(f"b/{i}" for i in range(5)), "b/c/d", "c"]:
+        with handler.open_output_stream(f"{root}/{name}") as f:
+            f.write(b"data")
+
+    pages = []
+    storage = handler._storage
+
+    class CountingStorage:
+        def __getattr__(self, name):
+            return getattr(storage, name)
+
+        def list_objs_after(self, *args):
+            pages.append(args)
+            return storage.list_objs_after(*args)
+
+    monkeypatch.setattr(deltalake.fs, "LIST_PAGE_SIZE", 1)
+    handler._storage = CountingStorage()
+    infos = handler.get_file_info_selector(FileSelector(root))
+    assert [(info.path, info.type) for info in infos] == [
+        (f"{root}/a", FileType.File),
+        (f"{root}/b", FileType.Directory),
+        (f"{root}/c", FileType.File),
+    ]
+    assert len(pages) == 3
+
+
+def test_get_file_info_selector_walks_local_directories(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    for name in ["b/c", "b.txt", "a/x/y/z"]:
+        (tmp_path / name).parent.mkdir(parents=True, exist_ok=True)
+        (tmp_path / name).write_bytes(b"data")
+    (tmp_path / "b" / "empty").mkdir()
+
+    infos = handler.get_file_info_selector(FileSelector(str(tmp_path), recursive=True))
+    assert [(info.path, info.type) for info in infos] == [
+        (str(tmp_path / "a"), FileType.Directory),
+        (str(tmp_path / "a" / "x"), FileType.Directory),
+        (str(tmp_path / "a" / "x" / "y"), FileType.Directory),
+        (str(tmp_path / "a" / "x" / "y" / "z"), FileType.File),
+        (str(tmp_path / "b"), FileType.Directory),
+        (str(tmp_path / "b.txt"), FileType.File),
+        (str(tmp_path / "b" / "c"), FileType.File),
+        (str(tmp_path / "b" / "empty"), FileType.Directory),
+    ]
+    assert infos[3].size == 4
+
+    infos = handler.get_file_info_selector(FileSelector(str(tmp_path / "a")))
+    assert [info.path for info in infos] == [str(tmp_path / "a" / "x")]
+
+
+def test_handlers_share_the_io_runtime(tmp_path):
+    def num_threads():
+        return len(os.listdir("/proc/self/task"))
+
+    if not os.path.isdir("/proc/self/task"):
+        pytest.skip("requires /proc")
+    path = str(tmp_path / "data.bin")
+    DeltaStorageHandler(str(tmp_path)).exists(path)
+    threads_before = num_threads()
+    for _ in range(1000):
+        handler = DeltaStorageHandler(str(tmp_path))
+        assert not handler.exists(path)
+        del handler
+    gc.collect()
+    assert num_threads() <= threads_before
+
+    with pytest.raises(RuntimeError, match="already running"):
+        deltalake.set_io_runtime(threads=2)
+    with pytest.raises(ValueError):
+        deltalake.set_io_runtime(threads=0)
+
+
+def test_set_io_runtime():
+    if not os.path.isdir("/proc/self/task"):
+        pytest.skip("requires /proc")
+    # the memory store does not start threads for blocking IO, which have the same names
+    script = """
+import os
+import deltalake
+from deltalake.fs import DeltaStorageHandler
+
+deltalake.set_io_runtime(threads=2, thread_name="test-io")
+DeltaStorageHandler("memory://test_io/table").exists("memory://test_io/table/data")
+names = [
+    open(f"/proc/self/task/{task}/comm").read().strip()
+    for task in os.listdir("/proc/self/task")
+]
+print(names.count("test-io"))
+"""
+    # the process must exit although the threads of the runtime are still running
+    result = subprocess.run(
+        [sys.executable, "-c", script],
+        capture_output=True,
+        check=True,
+        text=True,
+        timeout=60,
+    )
+    assert result.stdout.strip() == "2"
+
+
+def test_storage_errors_are_os_errors(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    with pytest.raises(FileNotFoundError):
+        handler.open_input_file(str(tmp_path / "missing.parquet"))
+    with pytest.raises(FileNotFoundError):
+        handler.delete_file(str(tmp_path / "missing.parquet"))
+
+
+def test_storage_errors_are_delta_errors(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path), local_fast_path=False)
+    with pytest.raises(deltalake.DeltaFileNotFoundError) as exc_info:
+        handler.open_input_file(str(tmp_path / "missing.parquet"))
+    assert isinstance(exc_info.value, deltalake.DeltaError)
+    assert isinstance(exc_info.value, FileNotFoundError)
+
+    (tmp_path / "src").write_bytes(b"src")
+    (tmp_path / "dest").write_bytes(b"dest")
+    with pytest.raises(deltalake.DeltaFileExistsError) as exc_info:
+        handler.move(str(tmp_path / "src"), str(tmp_path / "dest"))
+    assert isinstance(exc_info.value, deltalake.DeltaError)
+    assert isinstance(exc_info.value, FileExistsError)
+
+    with pytest.raises(deltalake.DeltaValueError) as exc_info:
+        DeltaStorageHandler(
+            str(tmp_path),
+            storage_options={"max_retires": "3", "unknown_options": "error"},
+        )
+    assert isinstance(exc_info.value, deltalake.DeltaError)
+    assert isinstance(exc_info.value, ValueError)
+
+    assert issubclass(deltalake.DeltaPermissionError, PermissionError)
+    assert issubclass(deltalake.DeltaTimeoutError, TimeoutError)
+    assert issubclass(deltalake.DeltaNotImplementedError, NotImplementedError)
+    for error in [
+        deltalake.DeltaPermissionError,
+        deltalake.DeltaTimeoutError,
+        deltalake.DeltaNotImplementedError,
+    ]:
+        assert issubclass(error, deltalake.DeltaError)
+        assert error.__module__ == "deltalake"
+
+
+@pytest.mark.skipif(
+    not hasattr(os, "geteuid") or os.geteuid() == 0,
+    reason="file permissions are not enforced for root",
+)
+def test_permission_error(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    path = tmp_path / "secret.parquet"
+    path.write_bytes(b"data")
+    path.chmod(0)
+    try:
+        with pytest.raises(PermissionError):
+            handler.open_input_file(str(path))
+    finally:
+        path.chmod(0o644)
+
+
+def test_head_and_exists(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path))
+    path = tmp_path / "data.bin"
+    path.write_bytes(b"data")
+
+    assert handler.exists(str(path))
+    assert not handler.exists(str(tmp_path / "missing.bin"))
+
+    head = handler.head(str(path))
+    assert head["path"] == str(path)
+    assert head["size"] == 4
+    mtime = datetime.fromisoformat(head["last_modified"]).timestamp()
+    assert mtime == pytest.approx(os.path.getmtime(path), abs=1)
+    with pytest.raises(FileNotFoundError):
+        handler.head(str(tmp_path / "missing.bin"))
+
+
+class VersionedObjectServer(http.server.BaseHTTPRequestHandler):
+    """Serves the metadata of versioned objects, except those named missing."""
+
+    def do_HEAD(self):
+        if self.path.endswith("/missing"):
+            self.send_response(404)
+            self.end_headers()
+            return
+        self.send_response(200)
+        self.send_header("Content-Length", "4")
+        self.send_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+        self.send_header("ETag", '"etag"')
+        self.send_header("x-amz-version-id", "v1")
+        self.end_headers()
+
+    def log_message(self, *args):
+        pass
+
+
+def test_get_file_metadata(tmp_path):
+    path = tmp_path / "data.bin"
+    path.write_bytes(b"data")
+    handler = DeltaStorageHandler(str(tmp_path))
+    [local, missing] = handler.get_file_metadata([str(path), str(tmp_path / "x")])
+    assert local == handler.head(str(path))
+    assert local["e_tag"] is None and local["version"] is None
+    assert missing is None
+
+    server = http.server.ThreadingHTTPServer(("127.0.0.1", 0), VersionedObjectServer)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    try:
+        handler = DeltaStorageHandler(
+            "s3://bucket/table",
+            storage_options={
+                "AWS_ENDPOINT_URL": "http://127.0.0.1:%d" % server.server_address[1],
+                "AWS_REGION": "us-east-1",
+                "AWS_SKIP_SIGNATURE": "true",
+            },
+        )
+        [obj, missing] = handler.get_file_metadata(
+            ["s3://bucket/table/data.bin", "s3://bucket/table/missing"]
+        )
+    finally:
+        server.shutdown()
+        server.server_close()
+    assert obj == {
+        "path": "s3://bucket/table/data.bin",
+        "size": 4,
+        "last_modified": "2015-10-21T07:28:00+00:00",
+        "e_tag": '"etag"',
+        "version": "v1",
+    }
+    assert missing is None
+
+
+def test_read_only_handler(tmp_path):
+    path = tmp_path / "data.bin"
+    path.write_bytes(b"data")
+    handler = DeltaStorageHandler(str(tmp_path), read_only=True)
+    assert "read_only=True" in repr(handler)
+
+    with pytest.raises(PermissionError):
+        handler.delete_file(str(path))
+    with pytest.raises(PermissionError):
+        handler.delete_files([str(path)])
+    with pytest.raises(PermissionError):
+        handler.move(str(path), str(tmp_path / "moved.bin"))
+    with pytest.raises(PermissionError):
+        handler.open_output_stream(str(path))
+    with pytest.raises(PermissionError):
+        handler.open_append_stream(str(path))
+    assert path.read_bytes() == b"data"
+
+    with handler.open_input_stream(str(path)) as f:
+        assert f.read() == b"data"
+
+
+def test_metrics(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path), local_fast_path=False)
+    path = str(tmp_path / "data.bin")
+    with handler.open_output_stream(path) as f:
+        f.write(b"x" * 100)
+    for _ in range(3):
+        with handler.open_input_file(path) as f:
+            f.read()
+    handler.get_file_info([path, str(tmp_path / "missing.bin")])
+
+    metrics = handler.get_metrics()
+    assert metrics["put"]["calls"] == 1
+    assert metrics["put"]["bytes"] == 100
+    assert metrics["get"]["calls"] == 3
+    assert metrics["get"]["bytes"] == 300
+    assert metrics["head"]["calls"] == 2
+    assert metrics["delete"]["calls"] == 0
+    assert metrics["get"]["latency_seconds"] > 0
+
+    handler.reset_metrics()
+    assert all(m["calls"] == 0 for m in handler.get_metrics().values())
+
+
+@pytest.mark.parametrize("local_fast_path", [True, False])
+def test_local_fast_path(tmp_path, local_fast_path):
+    handler = DeltaStorageHandler(str(tmp_path), local_fast_path=local_fast_path)
+    path = tmp_path / "data.bin"
+    path.write_bytes(b"0123456789")
+
+    with handler.open_input_file(str(path)) as f:
+        assert f.size() == 10
+        f.seek(4)
+        assert f.read(3) == b"456"
+    with handler.open_input_stream(str(path)) as f:
+        assert f.read() == b"0123456789"
+
+    get_calls = handler.get_metrics()["get"]["calls"]
+    assert get_calls == (0 if local_fast_path else 2)
+
+
+def test_rate_limited_requests(tmp_path):
+    handler = DeltaStorageHandler(str(tmp_path), local_fast_path=False)
+    assert "throttle" not in handler.get_metrics()
+
+    handler = DeltaStorageHandler(
+        str(tmp_path),
+        local_fast_path=False,
+        storage_options={
+            "max_requests_per_second": "10",
+            "max_concurrent_requests": "2",
+        },
+    )
+    paths = [str(tmp_path / f"part-{i}.parquet") for i in range(15)]
+    for path in paths:
+        with open(path, "wb") as f:
+            f.write(b"data")
+
+    start = time.monotonic()
+    infos = handler.get_file_info(paths)
+    # a burst of 10 requests, then 5 more at 10 per second
+    assert time.monotonic() - start >= 0.4
+    assert all(info.type == FileType.File for info in infos)
+    throttle = handler.get_metrics()["throttle"]
+    assert throttle["calls"] >= 5
+    assert throttle["latency_seconds"] > 0
+    assert throttle["in_flight"] == 0
+
+    handler.reset_metrics()
+    assert handler.get_metrics()["throttle"]["calls"] == 0
+
+
+def test_unknown_storage_options(tmp_path):
+    DeltaStorageHandler(str(tmp_path), storage_options={"max_retires": "3"})
+    with pytest.raises(ValueError, match="did you mean max_retries"):
+        DeltaStorageHandler(
+            str(tmp_path),
+            storage_options={"max_retires": "3", "unknown_options": "error"},
+        )
+
+
+def test_request_timeout():
+    # connections to a listener that never accepts are established, but never answered
+    with socket.socket() as listener:
+        listener.bind(("127.0.0.1", 0))
+        listener.listen()
+        handler = DeltaStorageHandler(
+            "s3://bucket/table",
+            storage_options={
+                "AWS_ENDPOINT_URL": "http://127.0.0.1:%d" % listener.getsockname()[1],
+                "AWS_REGION": "us-east-1",
+                "AWS_SKIP_SIGNATURE": "true",
+                "timeout": "1s",
+                "max_retries": "0",
+            },
+        )
+        start = time.monotonic()
+        with pytest.raises(TimeoutError):
+            handler.get_file_info(["s3://bucket/table/_delta_log/0.json"])
+        assert time.monotonic() - start < 3
+
+
+class SlowObjectServer(http.server.BaseHTTPRequestHandler):
+    """Serves every object after a delay, keeping reads in flight."""
+
+    def do_GET(self):
+        time.sleep(1)
+        self.send_response(200)
+        self.send_header("Content-Length", "4")
+        self.end_headers()
+        self.wfile.write(b"data")
+
+    def log_message(self, *args):
+        pass
+
+
+def test_reads_release_the_gil():
+    server = http.server.ThreadingHTTPServer(("127.0.0.1", 0), SlowObjectServer)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    handler = DeltaStorageHandler(
+        "s3://bucket/table",
+        storage_options={
+            "AWS_ENDPOINT_URL": "http://127.0.0.1:%d" % server.server_address[1],
+            "AWS_REGION": "us-east-1",
+            "AWS_SKIP_SIGNATURE": "true",
+        },
+    )
+    counter = 0
+    done = threading.Event()
+
+    def count():
+        nonlocal counter
+        while not done.is_set():
+            counter += 1
+            time.sleep(0.01)
+
+    counting = threading.Thread(target=count)
+    counting.start()
+    try:
+        before = counter
+        with handler.open_input_file("s3://bucket/table/data.bin") as f:
+            assert f.read() == b"data"
+        # the counter only advances if the read released the GIL
+        assert counter - before > 10
+    finally:
+        done.set()
+        counting.join()
+        server.shutdown()
+        server.server_close()
+
+
+def test_proxy_options_survive_pickling():
+    options = {
+        "proxy_url": "http://proxy.invalid:3128",
+        "proxy_ca_certificate": "../rust/tests/data/certs/proxy-ca.pem",
+    }
+    uri = "https://example.invalid/table"
+    handler = DeltaStorageHandler(uri, storage_options=options)
+    unpickled = pickle.loads(pickle.dumps(handler))
+    assert unpickled._storage_options == options
+
+    with pytest.raises(ValueError, match="proxy_url"):
+        DeltaStorageHandler(uri, storage_options={"proxy_url": "nope://"})
+
+
+def test_is_safe_for_concurrent_writes(tmp_path, monkeypatch):
+    assert DeltaStorageHandler(str(tmp_path)).is_safe_for_concurrent_writes()
+    read_only = DeltaStorageHandler(str(tmp_path), read_only=True)
+    assert not read_only.is_safe_for_concurrent_writes()
+
+    monkeypatch.delenv("AWS_S3_LOCKING_PROVIDER", raising=False)
+    monkeypatch.delenv("DYNAMO_LOCK_TABLE_NAME", raising=False)
+    options = {"AWS_REGION": "us-east-1", "AWS_SKIP_SIGNATURE": "true"}
+    s3 = DeltaStorageHandler("s3://bucket/table", storage_options=options)
+    assert not s3.is_safe_for_concurrent_writes()
+
+    options["AWS_S3_LOCKING_PROVIDER"] = "dynamodb"
+    with pytest.raises(ValueError, match="DYNAMO_LOCK_TABLE_NAME must be set"):
+        DeltaStorageHandler("s3://bucket/table", storage_options=options)
+
+    options["DYNAMO_LOCK_TABLE_NAME"] = "delta_locks"
+    locked = DeltaStorageHandler("s3://bucket/table", storage_options=options)
+    assert locked.is_safe_for_concurrent_writes()
+
+
+def test_s3_endpoint_validation():
+    options = {"aws_region": "us-east-1", "aws_skip_signature": "true"}
+    handler = DeltaStorageHandler(
+        "s3://bucket/table",
+        storage_options={**options, "aws_endpoint_url": "http://localhost:9000"},
+    )
+    assert handler.table_uri == "s3://bucket/table"
+    with pytest.raises(ValueError, match='found scheme "ftp" and host "minio:9000"'):
+        DeltaStorageHandler(
+            "s3://bucket/table",
+            storage_options={**options, "aws_endpoint_url": "ftp://minio:9000"},
+        )
+
+
+@pytest.mark.s3
+@pytest.mark.integration
+@pytest.mark.timeout(timeout=30, method="thread")
+def test_concurrent_commits_with_dynamodb_lock(s3_localstack):
+    table_path = "s3://deltars/concurrent_put_if_absent"
+    options = {
+        "AWS_S3_LOCKING_PROVIDER": "dynamodb",
+        "DYNAMO_LOCK_TABLE_NAME": "test_table",
+        "DYNAMO_LOCK_PARTITION_KEY_VALUE": "concurrent_put_if_absent",
+        "DYNAMO_LOCK_REFRESH_PERIOD_MILLIS": "100",
+        "DYNAMO_LOCK_ADDITIONAL_TIME_TO_WAIT_MILLIS": "100",
+    }
+    handlers = [
+        DeltaStorageHandler(table_path, storage_options=options) for _ in range(2)
+    ]
+    assert all(handler.is_safe_for_concurrent_writes() for handler in handlers)
+    commit = f"{table_path}/_delta_log/{uuid.uuid4()}.json"
+
+    with ThreadPoolExecutor(max_workers=2) as executor:
+        results = list(
+            executor.map(
+                lambda args: args[0].put_if_absent(commit, args[1]),
+                [(handler, b"{}" * (i + 1)) for i, handler in enumerate(handlers)],
+            )
+        )
+    assert sorted(results) == [False, True]
+    handlers[0].delete_file(commit)
+
+
+def test_memory_store_is_shared_between_handlers():
+    writer = DeltaStorageHandler("memory://test_fs/table")
+    reader = DeltaStorageHandler("memory://test_fs/table")
+    path = "memory://test_fs/table/data.bin"
+    with writer.open_output_stream(path) as f:
+        f.write(b"data")
+    assert writer.put_if_absent("memory://test_fs/table/0.json", b"{}")
+    assert not reader.put_if_absent("memory://test_fs/table/0.json", b"{}")
+    with reader.open_input_file(path) as f:
+        assert f.read() == b"data"
@@ -0,0 +1,99 @@
+import os
+
+import pandas as pd
+import pyarrow as pa
+import pytest
+
+from deltalake import DeltaTable, write_deltalake
+
+fsspec = pytest.importorskip("fsspec")
+from deltalake.fsspec import DeltaFileSystem  # noqa: E402
+
+
+@pytest.fixture()
+def table_path(tmp_path) -> str:
+    data = pa.table({"id": pa.array(range(10), pa.int64()), "part": ["a", "b"] * 5})
+    write_deltalake(str(tmp_path), data, partition_by=["part"])
+    return str(tmp_path)
+
+
+def test_registered_protocol(table_path):
+    fs = fsspec.filesystem("delta", table_uri=table_path)
+    assert isinstance(fs, DeltaFileSystem)
+    assert fs.exists(f"delta://{table_path}/_delta_log")
+
+
+def test_ls_and_info(table_path):
+    fs = DeltaFileSystem(table_path)
+    entries = fs.ls(table_path)
+    assert [(entry["name"], entry["type"]) for entry in entries] == [
+        (os.path.join(table_path, "_delta_log"), "directory"),
+        (os.path.join(table_path, "part=a"), "directory"),
+        (os.path.join(table_path, "part=b"), "directory"),
+    ]
+    assert fs.ls(table_path, detail=False) == [entry["name"] for entry in entries]
+
+    commit = os.path.join(table_path, "_delta_log", "00000000000000000000.json")
+    info = fs.info(commit)
+    assert info["type"] == "file"
+    assert info["size"] == os.path.getsize(commit)
+    assert fs.ls(commit) == [info]
+    assert fs.info(os.path.join(table_path, "part=a"))["type"] == "directory"
+
+    with pytest.raises(FileNotFoundError):
+        fs.info(os.path.join(table_path, "missing"))
+    with pytest.raises(FileNotFoundError):
+        fs.ls(os.path.join(table_path, "missing"))
+
+
+def test_cat_file_ranges(table_path):
+    fs = DeltaFileSystem(table_path)
+    path = os.path.join(table_path, "data.bin")
+    fs.pipe_file(path, b"0123456789")
+
+    assert fs.cat_file(path) == b"0123456789"
+    assert fs.cat_file(path, 2, 5) == b"234"
+    assert fs.cat_file(path, 8) == b"89"
+    assert fs.cat_file(path, -3) == b"789"
+    assert fs.cat_file(path, 1, -7) == b"12"
+    assert fs.cat_file(path, 5, 100) == b"56789"
+    assert fs.cat_file(path, 5, 2) == b""
+
+    with fs.open(path, "rb", block_size=4) as f:
+        f.seek(3)
+        assert f.read(4) == b"3456"
+
+    with fs.open(path, "wb") as f:
+        f.write(b"new ")
+        f.write(b"content")
+    assert fs.cat_file(path) == b"new content"
+
+
+def test_rm(table_path):
+    fs = DeltaFileSystem(table_path)
+    part = os.path.join(table_path, "part=a")
+    [file] = fs.ls(part, detail=False)
+
+    with pytest.raises(IsADirectoryError):
+        fs.rm(part)
+    fs.rm(file)
+    assert not os.path.exists(file)
+    assert fs.ls(part) == []
+
+    fs.pipe_file(os.path.join(part, "nested", "file"), b"data")
+    fs.rm(part, recursive=True)
+    assert not os.path.exists(part)
+
+    read_only = DeltaFileSystem(table_path, read_only=True)
+    with pytest.raises(PermissionError):
+        read_only.pipe_file(os.path.join(table_path, "other"), b"data")
+    with pytest.raises(PermissionError):
+        read_only.rm(os.path.join(table_path, "part=b"), recursive=True)
+
+
+def test_read_parquet_through_fsspec(table_path):
+    fs = DeltaFileSystem(table_path)
+    [file] = [uri for uri in DeltaTable(table_path).file_uris() if "part=b" in uri]
+    with fs.open(file, "rb") as f:
+        df = pd.read_parquet(f)
+    assert sorted(df["id"]) == [1, 3, 5, 7, 9]
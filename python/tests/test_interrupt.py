@@ -0,0 +1,67 @@
+import _thread
+import socket
+import threading
+import time
+
+import pytest
+
+from deltalake import DeltaTable
+from deltalake.fs import DeltaStorageHandler
+
+
+@pytest.fixture()
+def hanging_endpoint():
+    """An HTTP endpoint which accepts connections but never responds."""
+    server = socket.socket()
+    server.bind(("127.0.0.1", 0))
+    server.listen()
+    connections = []
+    closed = threading.Event()
+
+    def accept():
+        while not closed.is_set():
+            try:
+                connections.append(server.accept()[0])
+            except OSError:
+                return
+
+    threading.Thread(target=accept, daemon=True).start()
+    yield "http://127.0.0.1:{}".format(server.getsockname()[1])
+
+    closed.set()
+    server.close()
+    for connection in connections:
+        connection.close()
+
+
+@pytest.fixture()
+def hanging_s3(monkeypatch, hanging_endpoint):
+    monkeypatch.setenv("AWS_ENDPOINT_URL", hanging_endpoint)
+    monkeypatch.setenv("AWS_REGION", "us-east-1")
+    monkeypatch.setenv("AWS_ACCESS_KEY_ID", "deltalake")
+    monkeypatch.setenv("AWS_SECRET_ACCESS_KEY", "weloverust")
+
+
+def assert_interrupted(call):
+    # simulates Ctrl-C while the call waits for the store
+    timer = threading.Timer(0.5, _thread.interrupt_main)
+    timer.start()
+    start = time.monotonic()
+    try:
+        with pytest.raises(KeyboardInterrupt):
+            call()
+    finally:
+        timer.cancel()
+
+    assert time.monotonic() - start < 5
+
+
+def test_keyboard_interrupt_stops_load(hanging_s3):
+    assert_interrupted(lambda: DeltaTable("s3://deltars/simple"))
+
+
+def test_keyboard_interrupt_stops_handler_calls(hanging_s3):
+    handler = DeltaStorageHandler("s3://deltars/simple")
+
+    assert_interrupted(lambda: handler.get_file_info(["s3://deltars/simple/a"]))
+    assert_interrupted(lambda: handler.write_atomic("s3://deltars/simple/a", b"a"))
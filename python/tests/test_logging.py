@@ -0,0 +1,105 @@
+import logging
+import pathlib
+import threading
+
+import pyarrow as pa
+import pytest
+
+from deltalake import DeltaTable, write_deltalake
+
+
+@pytest.fixture()
+def sample_data() -> pa.Table:
+    return pa.table({"id": pa.array([1, 2, 3], pa.int64())})
+
+
+def deltalake_messages(caplog: pytest.LogCaptureFixture, level: int):
+    return [
+        record.getMessage()
+        for record in caplog.records
+        if record.name.startswith("deltalake") and record.levelno == level
+    ]
+
+
+def test_commit_logs(
+    tmp_path: pathlib.Path, sample_data: pa.Table, caplog: pytest.LogCaptureFixture
+):
+    caplog.set_level(logging.DEBUG, logger="deltalake")
+    write_deltalake(str(tmp_path), sample_data)
+    write_deltalake(str(tmp_path), sample_data, mode="append")
+
+    assert any(
+        message.startswith("Committed version 0 of")
+        for message in deltalake_messages(caplog, logging.INFO)
+    )
+    assert any(
+        message.startswith("Committed version 1 of")
+        for message in deltalake_messages(caplog, logging.INFO)
+    )
+    assert any(
+        message.startswith("Committing version 1") and "(attempt 1)" in message
+        for message in deltalake_messages(caplog, logging.DEBUG)
+    )
+
+
+def test_debug_logs_are_filtered(
+    tmp_path: pathlib.Path, sample_data: pa.Table, caplog: pytest.LogCaptureFixture
+):
+    caplog.set_level(logging.INFO, logger="deltalake")
+    write_deltalake(str(tmp_path), sample_data)
+    DeltaTable(str(tmp_path))
+
+    assert deltalake_messages(caplog, logging.DEBUG) == []
+    assert len(deltalake_messages(caplog, logging.INFO)) == 1
+
+
+def test_load_fallback_logs(
+    tmp_path: pathlib.Path, sample_data: pa.Table, caplog: pytest.LogCaptureFixture
+):
+    write_deltalake(str(tmp_path), sample_data)
+    (tmp_path / "_delta_log" / "_last_checkpoint").write_text("{not json")
+
+    caplog.set_level(logging.DEBUG, logger="deltalake")
+    assert DeltaTable(str(tmp_path)).version() == 0
+
+    assert any(
+        message.startswith("Failed to parse _last_checkpoint")
+        and "listing the log instead" in message
+        for message in deltalake_messages(caplog, logging.WARNING)
+    )
+    assert any(
+        message.startswith("Reading commits")
+        for message in deltalake_messages(caplog, logging.DEBUG)
+    )
+
+
+def test_debug_logs_while_reading_from_another_thread(
+    tmp_path: pathlib.Path, sample_data: pa.Table, caplog: pytest.LogCaptureFixture
+):
+    caplog.set_level(logging.DEBUG, logger="deltalake")
+    write_deltalake(str(tmp_path), sample_data)
+    dt = DeltaTable(str(tmp_path))
+    done = threading.Event()
+    versions = []
+
+    def read_versions():
+        while not done.is_set():
+            versions.append(dt.version())
+
+    reader = threading.Thread(target=read_versions)
+    reader.start()
+    try:
+        for _ in range(5):
+            write_deltalake(str(tmp_path), sample_data, mode="append")
+            dt.update_incremental()
+    finally:
+        done.set()
+        reader.join(timeout=10)
+    assert not reader.is_alive()
+
+    assert dt.version() == 5
+    assert versions == sorted(versions)
+    assert any(
+        message.startswith("Reading commits")
+        for message in deltalake_messages(caplog, logging.DEBUG)
+    )
@@ -0,0 +1,55 @@
+import multiprocessing
+import pathlib
+import pickle
+from typing import List, Tuple
+
+import pyarrow as pa
+import pytest
+
+from deltalake import DeltaError, DeltaTable, write_deltalake
+from deltalake.fs import DeltaStorageHandler
+
+
+def version_and_files(table: DeltaTable) -> Tuple[int, List[str]]:
+    return table.version(), table.files()
+
+
+def test_pickle_round_trip():
+    dt = DeltaTable("../rust/tests/data/simple_table", version=2)
+    unpickled = pickle.loads(pickle.dumps(dt))
+    assert version_and_files(unpickled) == version_and_files(dt)
+    assert unpickled._table.table_uri() == dt._table.table_uri()
+
+
+def test_pickle_keeps_case_sensitivity():
+    dt = DeltaTable("../rust/tests/data/delta-0.8.0-partitioned", case_sensitive=True)
+    unpickled = pickle.loads(pickle.dumps(dt))
+    assert unpickled.to_pyarrow_table(columns=["value"]).num_columns == 1
+    with pytest.raises(DeltaError):
+        unpickled.to_pyarrow_table(columns=["VALUE"])
+
+
+def test_pickle_to_spawned_process(tmp_path: pathlib.Path):
+    data = pa.table({"id": pa.array([1, 2, 3], pa.int64())})
+    write_deltalake(str(tmp_path), data)
+    write_deltalake(str(tmp_path), data, mode="append")
+    dt = DeltaTable(str(tmp_path), version=1)
+    # the worker loads the pinned version, not the latest one
+    write_deltalake(str(tmp_path), data, mode="append")
+
+    with multiprocessing.get_context("spawn").Pool(1) as pool:
+        assert pool.apply(version_and_files, (dt,)) == version_and_files(dt)
+
+
+def test_pickle_storage_handler():
+    handler = DeltaStorageHandler(
+        "../rust/tests/data/simple_table/", max_append_size=0, read_only=True
+    )
+    unpickled = pickle.loads(pickle.dumps(handler))
+    assert unpickled == handler
+    assert unpickled._max_append_size == 0
+    assert repr(unpickled) == repr(handler)
+
+    dt = DeltaTable("../rust/tests/data/simple_table")
+    paths = dt.file_uris()
+    assert unpickled.get_file_info(paths) == handler.get_file_info(paths)
@@ -0,0 +1,54 @@
+import pathlib
+
+import pyarrow as pa
+
+from deltalake import DeltaTable
+
+
+def test_repr():
+    dt = DeltaTable("../rust/tests/data/delta-0.8.0-partitioned")
+    assert repr(dt) == (
+        "DeltaTable(uri='../rust/tests/data/delta-0.8.0-partitioned', version=0, "
+        "files=6, size=2.4 KiB, partition_columns=['year', 'month', 'day'])"
+    )
+
+
+def test_repr_html():
+    dt = DeltaTable("../rust/tests/data/delta-0.8.0-partitioned")
+    assert dt._repr_html_() == "\n".join(
+        [
+            "<table>",
+            '<tr><th colspan="2">DeltaTable</th></tr>',
+            "<tr><td>URI</td><td>../rust/tests/data/delta-0.8.0-partitioned</td></tr>",
+            "<tr><td>Version</td><td>0</td></tr>",
+            "<tr><td>Files</td><td>6</td></tr>",
+            "<tr><td>Size</td><td>2.4 KiB</td></tr>",
+            "<tr><td>Partition columns</td><td>year, month, day</td></tr>",
+            "<tr><td>Name</td><td></td></tr>",
+            "<tr><td>Description</td><td></td></tr>",
+            "<tr><td>Protocol</td><td>reader version 1, writer version 2</td></tr>",
+            '<tr><th colspan="2">Recent history</th></tr>',
+            "<tr><td>2021-03-12 13:27:26 UTC</td><td>WRITE</td></tr>",
+            "</table>",
+        ]
+    )
+
+
+def test_repr_truncates_long_values(tmp_path: pathlib.Path):
+    table_path = tmp_path / ("a" * 100)
+    configuration = {f"key{i:02}": "x" * 50 for i in range(12)}
+    dt = DeltaTable.create(
+        str(table_path),
+        pa.schema([pa.field("id", pa.int64())]),
+        configuration=configuration,
+    )
+
+    uri = repr(dt).split("'")[1]
+    assert len(uri) == 80
+    assert uri.endswith("aaa...")
+
+    html = dt._repr_html_()
+    assert "<tr><td>key09</td><td>" + "x" * 37 + "...</td></tr>" in html
+    assert "key10" not in html
+    assert "<tr><td>...</td><td>2 more</td></tr>" in html
+    assert "CREATE TABLE" in html
@@ -324,3 +324,30 @@ def test_schema_pyarrow_types():
     )
     assert pyarrow_field.metadata == metadata
     assert pyarrow_field.nullable is False
+
+
+def test_schema_round_trips():
+    pyarrow_schema = pyarrow.schema(
+        [
+            pyarrow.field("id", pyarrow.int64(), nullable=False),
+            pyarrow.field("name", pyarrow.string()),
+            pyarrow.field("price", pyarrow.decimal128(10, 2)),
+            pyarrow.field("updated", pyarrow.timestamp("us")),
+            pyarrow.field(
+                "tags", pyarrow.list_(pyarrow.field("element", pyarrow.string()))
+            ),
+            pyarrow.field(
+                "location",
+                pyarrow.struct([pyarrow.field("lat", pyarrow.float64())]),
+            ),
+        ]
+    )
+    schema = Schema.from_pyarrow(pyarrow_schema)
+
+    assert Schema.from_json(schema.to_json()) == schema
+    assert schema.to_pyarrow() == pyarrow_schema
+    assert Schema.from_pyarrow(schema.to_pyarrow()) == schema
+
+    dt = DeltaTable("../rust/tests/data/delta-0.8.0-partitioned")
+    assert Schema.from_json(dt.schema().to_json()) == dt.schema()
+    assert dt.schema().to_pyarrow() == dt.pyarrow_schema()
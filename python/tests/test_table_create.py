@@ -0,0 +1,88 @@
+import pathlib
+
+import pyarrow as pa
+import pytest
+
+from deltalake import DeltaTable, PyDeltaTableError, Schema
+from deltalake.schema import delta_schema_from_pyarrow
+
+
+@pytest.fixture()
+def sample_schema() -> pa.Schema:
+    return pa.schema(
+        [
+            pa.field("id", pa.int64(), nullable=False),
+            pa.field("name", pa.string()),
+            pa.field("price", pa.decimal128(10, 2)),
+            pa.field("tags", pa.list_(pa.string())),
+            pa.field("date", pa.string()),
+        ]
+    )
+
+
+def test_create_table(tmp_path: pathlib.Path, sample_schema: pa.Schema):
+    dt = DeltaTable.create(
+        str(tmp_path),
+        sample_schema,
+        partition_by=["date"],
+        configuration={"delta.appendOnly": "true"},
+    )
+
+    assert dt.version() == 0
+    assert dt.files() == []
+
+    # The metadata survives reopening the table
+    dt = DeltaTable(str(tmp_path))
+    assert dt.version() == 0
+    assert dt.metadata().partition_columns == ["date"]
+    assert dt.metadata().configuration == {"delta.appendOnly": "true"}
+    assert dt.schema().json() == delta_schema_from_pyarrow(sample_schema).json()
+    assert dt.to_pyarrow_table().num_rows == 0
+
+
+def test_create_table_from_delta_schema(tmp_path: pathlib.Path):
+    table_path = "../rust/tests/data/delta-0.8.0-partitioned"
+    schema = DeltaTable(table_path).schema()
+    assert isinstance(schema, Schema)
+
+    dt = DeltaTable.create(str(tmp_path), schema, partition_by=["year"])
+
+    assert dt.schema().json() == schema.json()
+    assert dt.metadata().partition_columns == ["year"]
+
+
+def test_create_table_modes(tmp_path: pathlib.Path, sample_schema: pa.Schema):
+    DeltaTable.create(str(tmp_path), sample_schema)
+
+    with pytest.raises(PyDeltaTableError, match="already exists"):
+        DeltaTable.create(str(tmp_path), sample_schema)
+
+    other_schema = pa.schema([pa.field("value", pa.float64())])
+    dt = DeltaTable.create(str(tmp_path), other_schema, mode="ignore")
+    assert dt.version() == 0
+    assert dt.schema().json() == delta_schema_from_pyarrow(sample_schema).json()
+
+    dt = DeltaTable.create(str(tmp_path), other_schema, mode="overwrite")
+    assert dt.version() == 1
+    assert dt.schema().json() == delta_schema_from_pyarrow(other_schema).json()
+
+    with pytest.raises(ValueError):
+        DeltaTable.create(str(tmp_path), other_schema, mode="append")
+
+
+def test_create_table_invalid_partition_columns(
+    tmp_path: pathlib.Path, sample_schema: pa.Schema
+):
+    with pytest.raises(PyDeltaTableError, match="not part of the schema"):
+        DeltaTable.create(str(tmp_path), sample_schema, partition_by=["missing"])
+    with pytest.raises(PyDeltaTableError, match="primitive type"):
+        DeltaTable.create(str(tmp_path), sample_schema, partition_by=["tags"])
+
+    # Nothing was written for the rejected tables
+    assert not (tmp_path / "_delta_log").exists()
+
+
+def test_create_table_unsupported_type(tmp_path: pathlib.Path):
+    schema = pa.schema([pa.field("duration", pa.duration("s"))])
+    with pytest.raises(TypeError):
+        DeltaTable.create(str(tmp_path), schema)
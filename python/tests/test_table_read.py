@@ -1,11 +1,24 @@
+import json
 import os
-from threading import Barrier, Thread
+import pathlib
+import shutil
+from datetime import date, datetime, timedelta, timezone
+from decimal import Decimal
+from threading import Barrier, Event, Thread
 
 import pandas as pd
+import pyarrow as pa
 import pytest
 from pyarrow.fs import LocalFileSystem
 
-from deltalake import DeltaTable, Metadata
+from deltalake import (
+    DeltaError,
+    DeltaTable,
+    Metadata,
+    Protocol,
+    TimestampBeforeFirstCommitError,
+    VersionNotInLogError,
+)
 
 
 def test_read_simple_table_to_dict():
@@ -35,13 +48,30 @@ def test_load_with_datetime():
 
     table_path = "../rust/tests/data/simple_table"
     dt = DeltaTable(table_path)
-    dt.load_with_datetime("2020-05-01T00:47:31-07:00")
+    with pytest.raises(
+        TimestampBeforeFirstCommitError,
+        match=r"version 0 was committed at 2020-05-02T05:47:31\+00:00",
+    ):
+        dt.load_with_datetime("2020-05-01T00:47:31-07:00")
+    assert dt.version() == 4
+    dt.load_with_datetime("2020-05-01T22:47:31-07:00")
     assert dt.version() == 0
     dt.load_with_datetime("2020-05-02T22:47:31-07:00")
     assert dt.version() == 1
     dt.load_with_datetime("2020-05-25T22:47:31-07:00")
     assert dt.version() == 4
 
+    dt.load_with_datetime(
+        datetime(2020, 5, 3, 22, 47, 31, tzinfo=timezone(timedelta(hours=-7)))
+    )
+    assert dt.version() == 2
+    with pytest.warns(UserWarning, match="as UTC"):
+        dt.load_with_datetime(datetime(2020, 5, 4, 5, 47, 31))
+    assert dt.version() == 2
+    with pytest.warns(UserWarning, match="as UTC"):
+        dt.load_with_datetime(datetime(2020, 5, 4, 5, 47, 30))
+    assert dt.version() == 1
+
 
 def test_load_with_datetime_bad_format():
     table_path = "../rust/tests/data/simple_table"
@@ -70,8 +100,23 @@ def test_read_simple_table_update_incremental():
     table_path = "../rust/tests/data/simple_table"
     dt = DeltaTable(table_path, version=0)
     assert dt.to_pyarrow_dataset().to_table().to_pydict() == {"id": [0, 1, 2, 3, 4]}
-    dt.update_incremental()
+    assert dt.update_incremental() == 4
     assert dt.to_pyarrow_dataset().to_table().to_pydict() == {"id": [5, 7, 9]}
+    assert dt.update_incremental() == 0
+
+
+def test_read_simple_table_update_incremental_with_max_version():
+    table_path = "../rust/tests/data/simple_table"
+    dt = DeltaTable(table_path, version=0)
+    assert dt.update_incremental(max_version=2) == 2
+    assert dt.version() == 2
+    assert dt.to_pyarrow_dataset().to_table().to_pydict() == {
+        "id": [5, 7, 9, 5, 6, 7, 8, 9]
+    }
+    assert dt.update_incremental(max_version=1) == 0
+    assert dt.version() == 2
+    assert dt.update_incremental() == 2
+    assert dt.version() == 4
 
 
 def test_read_partitioned_table_to_dict():
@@ -100,6 +145,33 @@ def test_read_partitioned_table_with_partitions_filters_to_dict():
     assert dt.to_pyarrow_dataset(partitions).to_table().to_pydict() == expected
 
 
+def test_read_partitioned_table_with_mixed_case_names():
+    table_path = "../rust/tests/data/delta-0.8.0-partitioned"
+    dt = DeltaTable(table_path)
+    expected = {
+        "value": ["6", "7", "5", "4"],
+        "day": ["20", "20", "4", "5"],
+    }
+
+    table = dt.to_pyarrow_table(
+        partitions=[("Year", "=", "2021")], columns=["VALUE", "Day"]
+    )
+    assert table.to_pydict() == expected
+
+    table = dt.to_pyarrow_table(
+        columns=["VALUE", "Day"], filter="YEAR = '2021' AND Day IN ('20', '4', '5')"
+    )
+    assert sorted(table["value"].to_pylist()) == sorted(expected["value"])
+
+    dt = DeltaTable(table_path, case_sensitive=True)
+    with pytest.raises(DeltaError, match="Invalid partition filter"):
+        dt.files_by_partitions([("Year", "=", "2021")])
+    with pytest.raises(DeltaError, match="Column VALUE not found"):
+        dt.to_pyarrow_table(columns=["VALUE"])
+    with pytest.raises(DeltaError, match="Column Year not found"):
+        dt.to_pyarrow_table(filter="Year = '2021'")
+
+
 def test_read_empty_delta_table_after_delete():
     table_path = "../rust/tests/data/delta-0.8-empty"
     dt = DeltaTable(table_path)
@@ -148,6 +220,60 @@ def test_vacuum_dry_run_simple_table():
     )
 
 
+def test_vacuum_with_metrics(tmp_path):
+    log_dir = _create_log(tmp_path)
+    sizes = {"removed-1.parquet": 10, "removed-2.parquet": 20, "active.parquet": 40}
+    for version, (path, size) in enumerate(sizes.items(), start=1):
+        (tmp_path / path).write_bytes(b"0" * size)
+        add = {
+            "path": path,
+            "partitionValues": {},
+            "size": size,
+            "modificationTime": 1587968586000,
+            "dataChange": True,
+        }
+        _write_commit(log_dir, version, [{"add": add}])
+    removes = [
+        {"remove": {"path": path, "deletionTimestamp": 0, "dataChange": True}}
+        for path in ["removed-1.parquet", "removed-2.parquet"]
+    ]
+    _write_commit(log_dir, 4, removes)
+    dt = DeltaTable(str(tmp_path))
+
+    metrics = dt.vacuum_with_metrics()
+    assert sorted(os.path.basename(f) for f in metrics.files_deleted) == [
+        "removed-1.parquet",
+        "removed-2.parquet",
+    ]
+    assert metrics.num_files_deleted == 2
+    assert metrics.bytes_reclaimed == 30
+    assert metrics.num_referenced_files_skipped == 1
+    assert (tmp_path / "removed-1.parquet").exists()
+
+    with pytest.raises(ValueError, match="confirm_unsafe_retention"):
+        dt.vacuum(0, enforce_retention_duration=False)
+    assert (
+        len(
+            dt.vacuum(
+                0, enforce_retention_duration=False, confirm_unsafe_retention=True
+            )
+        )
+        == 2
+    )
+
+    events = []
+    metrics = dt.vacuum_with_metrics(
+        dry_run=False, progress_callback=events.append, max_concurrent_deletes=2
+    )
+    assert metrics.num_files_deleted == 2
+    assert not (tmp_path / "removed-1.parquet").exists()
+    assert not (tmp_path / "removed-2.parquet").exists()
+    assert (tmp_path / "active.parquet").exists()
+    assert [event["done"] for event in events] == [1, 2]
+    assert events[-1]["phase"] == "delete_files"
+    assert events[-1]["bytes"] == 30
+
+
 def test_read_partitioned_table_metadata():
     table_path = "../rust/tests/data/delta-0.8.0-partitioned"
     dt = DeltaTable(table_path)
@@ -156,8 +282,55 @@ def test_read_partitioned_table_metadata():
     assert metadata.name is None
     assert metadata.description is None
     assert metadata.partition_columns == ["year", "month", "day"]
-    assert metadata.created_time == 1615555644515
+    assert metadata.created_time == datetime(
+        2021, 3, 12, 13, 27, 24, 515000, tzinfo=timezone.utc
+    )
     assert metadata.configuration == {}
+    assert metadata == dt.metadata()
+    assert metadata == Metadata(
+        id="fe5a3c11-30d4-4dd7-b115-a1c121e66a4e",
+        name=None,
+        description=None,
+        partition_columns=["year", "month", "day"],
+        created_time=metadata.created_time,
+        configuration={},
+    )
+
+
+def test_read_table_protocol():
+    table_path = "../rust/tests/data/delta-0.8.0-partitioned"
+    dt = DeltaTable(table_path)
+    protocol = dt.protocol()
+    assert protocol == Protocol(min_reader_version=1, min_writer_version=2)
+    assert protocol.reader_features is None
+    assert repr(protocol) == (
+        "Protocol(min_reader_version=1, min_writer_version=2, "
+        "reader_features=None, writer_features=None)"
+    )
+
+
+def test_detail_simple_table():
+    table_path = "../rust/tests/data/simple_table"
+    dt = DeltaTable(table_path)
+    detail = dt.detail()
+
+    assert detail.pop("location") == dt._table.table_uri()
+    assert detail.pop("lastModified") is not None
+    assert detail == {
+        "format": "delta",
+        "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+        "name": None,
+        "description": None,
+        "createdAt": 1587968585495,
+        "partitionColumns": [],
+        "numFiles": 5,
+        "sizeInBytes": 1811,
+        "properties": {},
+        "minReaderVersion": 1,
+        "minWriterVersion": 2,
+        "readerFeatures": [],
+        "writerFeatures": ["appendOnly", "invariants"],
+    }
 
 
 def test_history_partitioned_table_metadata():
@@ -183,6 +356,45 @@ def test_history_partitioned_table_metadata():
     }
 
 
+def test_get_actions_between():
+    table_path = "../rust/tests/data/simple_table"
+    dt = DeltaTable(table_path)
+    commits = dt.get_actions_between(3, 4)
+
+    assert [commit["version"] for commit in commits] == [3, 4]
+    assert [(len(c["add"]), len(c["remove"])) for c in commits] == [(2, 2), (1, 2)]
+    assert commits[0]["commit_info"]["operation"] == "UPDATE"
+    assert commits[1]["commit_info"]["operation"] == "DELETE"
+    add = commits[1]["add"][0]
+    assert add["path"].endswith(".snappy.parquet")
+    assert add["partitionValues"] == {}
+    assert add["size"] > 0
+    assert add["dataChange"] is True
+    assert "modificationTime" in add
+    remove = commits[1]["remove"][0]
+    assert remove["dataChange"] is True
+    assert "deletionTimestamp" in remove
+
+    with pytest.raises(DeltaError, match="Invalid table version: 5"):
+        dt.get_actions_between(4, 5)
+
+
+def test_get_actions_between_after_log_cleanup(tmp_path):
+    log_dir = tmp_path / "_delta_log"
+    shutil.copytree(
+        "../rust/tests/data/simple_table_with_checkpoint/_delta_log", log_dir
+    )
+    for version in range(5):
+        (log_dir / f"{version:020}.json").unlink()
+    dt = DeltaTable(str(tmp_path))
+
+    with pytest.raises(
+        VersionNotInLogError, match="the earliest available version is 5"
+    ):
+        dt.get_actions_between(3, 10)
+    assert len(dt.get_actions_between(5, 10)) == 6
+
+
 def test_get_files_partitioned_table():
     table_path = "../rust/tests/data/delta-0.8.0-partitioned"
     dt = DeltaTable(table_path)
@@ -248,12 +460,95 @@ def test_get_files_partitioned_table():
     )
 
 
+def test_get_add_actions():
+    dt = DeltaTable("../rust/tests/data/table_with_typed_stats")
+
+    actions = dt.get_add_actions()
+    assert actions.schema.names == [
+        "path",
+        "size_bytes",
+        "modification_time",
+        "data_change",
+        "partition_values",
+        "num_records",
+        "null_count",
+        "min",
+        "max",
+    ]
+    assert actions["size_bytes"].to_pylist() == [1200, 1300, 1400]
+    assert actions["partition_values"].to_pylist() == [
+        {"date": date(2021, 1, 1)},
+        {"date": date(2021, 1, 2)},
+        {"date": None},
+    ]
+    # the second file has truncated statistics and the third none
+    assert actions["num_records"].to_pylist() == [2, None, None]
+    assert actions["min"][0].as_py() == {
+        "id": 1,
+        "ts": datetime(2021, 1, 1),
+        "amount": Decimal("1.00"),
+        "address": {"city": "Berlin", "zip": 10115},
+    }
+    assert actions["max"].type.field("amount").type == pa.decimal128(10, 2)
+
+    flattened = dt.get_add_actions(flatten=True)
+    assert "null_count.address.zip" in flattened.schema.names
+    assert flattened["null_count.address.zip"].to_pylist() == [1, None, None]
+    assert flattened["max.amount"].to_pylist() == [Decimal("12.35"), None, None]
+
+
 def test_delta_table_to_pandas():
     table_path = "../rust/tests/data/simple_table"
     dt = DeltaTable(table_path)
     assert dt.to_pandas().equals(pd.DataFrame({"id": [5, 7, 9]}))
 
 
+def test_delta_table_to_pandas_with_categories():
+    table_path = "../rust/tests/data/delta-0.8.0-partitioned"
+    dt = DeltaTable(table_path)
+    df = dt.to_pandas()
+    plain = dt.to_pandas(categories=[])
+
+    for name in ["year", "month", "day"]:
+        assert df[name].dtype == "category"
+        assert plain[name].dtype == object
+    assert df["value"].dtype == object
+    assert list(df["year"].cat.categories) == ["2020", "2021"]
+    assert list(df["month"].cat.categories) == ["1", "12", "2", "4"]
+    assert df.astype(object).equals(plain)
+
+    # the categories are all partition values of the version, not just the ones read
+    df = dt.to_pandas(partitions=[("year", "=", "2021")])
+    assert set(df["year"]) == {"2021"}
+    assert list(df["year"].cat.categories) == ["2020", "2021"]
+
+    df = dt.to_pandas(categories=["VALUE"])
+    assert df["value"].dtype == "category"
+    assert df["year"].dtype == object
+    assert df.astype(object).equals(plain)
+
+
+def test_delta_table_to_pandas_with_types_mapper():
+    table_path = "../rust/tests/data/delta-0.8.0-partitioned"
+    dt = DeltaTable(table_path)
+    df = dt.to_pandas(types_mapper={pa.string(): pd.StringDtype()}.get, categories=[])
+    assert df["value"].dtype == pd.StringDtype()
+    assert df["year"].dtype == pd.StringDtype()
+    assert df.astype(object).equals(dt.to_pandas(categories=[]))
+
+
+@pytest.mark.skipif(not hasattr(pd, "ArrowDtype"), reason="requires pandas 2")
+def test_delta_table_to_pandas_with_arrow_dtypes():
+    table_path = "../rust/tests/data/simple_table"
+    dt = DeltaTable(table_path)
+    df = dt.to_pandas(arrow_dtypes=True)
+    assert df["id"].dtype == pd.ArrowDtype(pa.int64())
+    assert df["id"].tolist() == [5, 7, 9]
+
+    with pytest.raises(ValueError):
+        dt.to_pandas(arrow_dtypes=True, types_mapper=pd.ArrowDtype)
+
+
 def test_delta_table_with_filesystem():
     table_path = "../rust/tests/data/simple_table"
     dt = DeltaTable(table_path)
@@ -352,3 +647,223 @@ def test_read_multiple_tables_from_s3_multi_threaded(s3_localstack):
         t.start()
     for t in threads:
         t.join()
+
+
+def _write_commit(log_dir, version, actions):
+    path = os.path.join(log_dir, f"{version:020}.json")
+    tmp_path = path + ".tmp"
+    with open(tmp_path, "w") as f:
+        f.write("\n".join(json.dumps(action) for action in actions))
+    os.rename(tmp_path, path)
+
+
+def _create_log(table_path) -> str:
+    """Writes the first commit of a table with a long column and returns the log dir."""
+    log_dir = os.path.join(table_path, "_delta_log")
+    os.makedirs(log_dir)
+    schema = {
+        "type": "struct",
+        "fields": [{"name": "id", "type": "long", "nullable": True, "metadata": {}}],
+    }
+    _write_commit(
+        log_dir,
+        0,
+        [
+            {"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}},
+            {
+                "metaData": {
+                    "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": json.dumps(schema),
+                    "partitionColumns": [],
+                    "configuration": {},
+                    "createdTime": 1587968585495,
+                }
+            },
+        ],
+    )
+    return log_dir
+
+
+def _add_file(log_dir, version):
+    add = {
+        "path": f"part-{version:05}.parquet",
+        "partitionValues": {},
+        "size": 100,
+        "modificationTime": 1587968586000,
+        "dataChange": True,
+    }
+    _write_commit(log_dir, version, [{"add": add}])
+
+
+@pytest.mark.timeout(timeout=60, method="thread")
+def test_readers_see_consistent_snapshot_during_updates(tmp_path):
+    log_dir = _create_log(tmp_path)
+    dt = DeltaTable(str(tmp_path))
+    num_versions = 100
+    done = Event()
+
+    def write():
+        try:
+            for version in range(1, num_versions + 1):
+                _add_file(log_dir, version)
+                dt.update_incremental()
+        finally:
+            done.set()
+
+    def read():
+        while not done.is_set():
+            files = dt.files()
+            version = dt.version()
+            # every version adds exactly one file, so a complete snapshot always holds the
+            # files of versions 1 to n
+            assert sorted(files) == [
+                f"part-{v:05}.parquet" for v in range(1, len(files) + 1)
+            ]
+            assert version >= len(files)
+
+    threads = [ExcPassThroughThread(target=write)] + [
+        ExcPassThroughThread(target=read) for _ in range(4)
+    ]
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+
+    assert dt.version() == num_versions
+    assert len(dt.files()) == num_versions
+
+
+@pytest.mark.timeout(timeout=60, method="thread")
+def test_history_and_time_travel_do_not_block_readers(tmp_path):
+    log_dir = _create_log(tmp_path)
+    for version in range(1, 21):
+        _add_file(log_dir, version)
+    dt = DeltaTable(str(tmp_path))
+    done = Event()
+
+    def travel():
+        try:
+            for _ in range(20):
+                dt.load_with_datetime(datetime.now(timezone.utc))
+                dt.history()
+        finally:
+            done.set()
+
+    def read_log():
+        # holds the table readable while waiting for IO, which retakes the GIL
+        while not done.is_set():
+            dt.get_actions_between(1, 20)
+
+    def read():
+        while not done.is_set():
+            assert len(dt.files()) == dt.version()
+
+    threads = [
+        ExcPassThroughThread(target=travel),
+        ExcPassThroughThread(target=read_log),
+    ] + [ExcPassThroughThread(target=read) for _ in range(2)]
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+
+    assert dt.version() == 20
+
+
+def test_create_checkpoint_and_cleanup_metadata(tmp_path):
+    log_dir = _create_log(tmp_path)
+    for version in range(1, 51):
+        _add_file(log_dir, version)
+    dt = DeltaTable(str(tmp_path))
+    dt.create_checkpoint()
+
+    assert os.path.exists(os.path.join(log_dir, f"{50:020}.checkpoint.parquet"))
+    with open(os.path.join(log_dir, "_last_checkpoint")) as f:
+        assert json.load(f)["version"] == 50
+
+    # the commits before the checkpoint are not read anymore
+    for version in range(50):
+        with open(os.path.join(log_dir, f"{version:020}.json"), "w") as f:
+            f.write("not a commit")
+    reloaded = DeltaTable(str(tmp_path))
+    assert reloaded.version() == 50
+    assert sorted(reloaded.files()) == sorted(dt.files())
+
+    assert dt.cleanup_metadata() == 0
+    expired = (datetime.now() - timedelta(days=31)).timestamp()
+    for version in range(51):
+        os.utime(os.path.join(log_dir, f"{version:020}.json"), (expired, expired))
+    assert dt.cleanup_metadata() == 50
+    assert sorted(os.listdir(log_dir)) == [
+        f"{50:020}.checkpoint.parquet",
+        f"{50:020}.json",
+        "_last_checkpoint",
+    ]
+    assert DeltaTable(str(tmp_path)).version() == 50
+
+
+def test_create_multi_part_checkpoint(tmp_path):
+    log_dir = _create_log(tmp_path)
+    for version in range(1, 51):
+        _add_file(log_dir, version)
+    dt = DeltaTable(str(tmp_path))
+    dt.create_checkpoint(max_actions_per_part=20)
+
+    # protocol, metadata and 50 add actions
+    assert sorted(f for f in os.listdir(log_dir) if "checkpoint." in f) == [
+        f"{50:020}.checkpoint.{part:010}.{3:010}.parquet" for part in range(1, 4)
+    ]
+    assert sorted(DeltaTable(str(tmp_path)).files()) == sorted(dt.files())
+
+    with pytest.raises(ValueError):
+        dt.create_checkpoint(max_actions_per_part=0)
+
+
+@pytest.mark.timeout(timeout=60, method="thread")
+def test_update_incremental_one_version_at_a_time(tmp_path):
+    log_dir = _create_log(tmp_path)
+    dt = DeltaTable(str(tmp_path))
+    num_versions = 20
+
+    def write():
+        for version in range(1, num_versions + 1):
+            _add_file(log_dir, version)
+
+    writer = ExcPassThroughThread(target=write)
+    writer.start()
+    processed = []
+    while dt.version() < num_versions:
+        applied = dt.update_incremental(max_version=dt.version() + 1)
+        assert applied in (0, 1)
+        if applied:
+            processed.append(dt.version())
+            assert sorted(dt.files()) == [
+                f"part-{v:05}.parquet" for v in range(1, dt.version() + 1)
+            ]
+    writer.join()
+
+    assert processed == list(range(1, num_versions + 1))
+    assert dt.update_incremental() == 0
+
+
+def test_check_integrity(tmp_path: pathlib.Path):
+    table_path = tmp_path / "simple_table"
+    shutil.copytree("../rust/tests/data/simple_table", table_path)
+    dt = DeltaTable(str(table_path))
+    report = dt.check_integrity()
+    assert report["healthy"]
+    assert report["version"] == dt.version()
+
+    removed = dt.files()[0]
+    (table_path / removed).unlink()
+    report = dt.check_integrity(max_sample_size=1)
+    assert not report["healthy"]
+    files_exist = next(c for c in report["checks"] if c["check"] == "files_exist")
+    assert files_exist == {
+        "check": "files_exist",
+        "status": "failed",
+        "num_failures": 1,
+        "sample": [removed],
+        "message": None,
+    }
@@ -0,0 +1,474 @@
+import json
+import pathlib
+import threading
+from typing import Dict, List
+
+import pyarrow as pa
+import pytest
+
+from deltalake import (
+    DeltaError,
+    DeltaTable,
+    PyDeltaTableError,
+    SchemaMismatchError,
+    WriteMetrics,
+    write_deltalake,
+)
+
+
+@pytest.fixture()
+def sample_data() -> pa.Table:
+    return pa.table(
+        {
+            "id": pa.array([1, 2, 3], pa.int64()),
+            "value": pa.array([1.5, None, -2.0], pa.float64()),
+            "date": pa.array(["2021-01-01", "2021-01-01", "2021-01-02"]),
+        }
+    )
+
+
+def test_write_new_table(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(
+        str(tmp_path),
+        sample_data,
+        partition_by=["date"],
+        custom_metadata={"userName": "test"},
+    )
+
+    dt = DeltaTable(str(tmp_path))
+    assert dt.version() == 0
+    assert dt.metadata().partition_columns == ["date"]
+    assert len(dt.files()) == 2
+    df = dt.to_pandas(categories=[]).sort_values("id").reset_index(drop=True)
+    assert df.equals(sample_data.to_pandas())
+
+    commit = json.loads(
+        (tmp_path / "_delta_log" / "00000000000000000000.json")
+        .read_text()
+        .splitlines()[0]
+    )["commitInfo"]
+    assert commit["operation"] == "WRITE"
+    assert commit["userName"] == "test"
+
+
+def test_write_modes(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data)
+
+    with pytest.raises(PyDeltaTableError, match="already exists"):
+        write_deltalake(str(tmp_path), sample_data)
+
+    dt = DeltaTable(str(tmp_path))
+    write_deltalake(dt, sample_data, mode="ignore")
+    assert dt.version() == 0
+
+    write_deltalake(dt, sample_data, mode="append")
+    assert dt.version() == 1
+    assert dt.to_pyarrow_table().num_rows == 6
+
+    write_deltalake(dt, sample_data.slice(0, 1), mode="overwrite")
+    assert dt.version() == 2
+    assert dt.to_pyarrow_table().num_rows == 1
+
+    with pytest.raises(ValueError):
+        write_deltalake(dt, sample_data, mode="merge")
+
+
+def test_write_record_batches(tmp_path: pathlib.Path, sample_data: pa.Table):
+    batches = sample_data.to_batches(max_chunksize=1)
+
+    with pytest.raises(ValueError, match="schema is required"):
+        write_deltalake(str(tmp_path), iter(batches))
+
+    write_deltalake(str(tmp_path), iter(batches), schema=sample_data.schema)
+    assert DeltaTable(str(tmp_path)).to_pyarrow_table().num_rows == 3
+
+
+def test_write_pandas(tmp_path: pathlib.Path, sample_data: pa.Table):
+    df = sample_data.to_pandas()
+    write_deltalake(str(tmp_path), df)
+    assert DeltaTable(str(tmp_path)).to_pandas().equals(df)
+
+
+def test_write_mismatched_schema(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data)
+
+    other_data = pa.table({"other": pa.array(["a"])})
+    with pytest.raises(PyDeltaTableError, match="not part of the table schema"):
+        write_deltalake(str(tmp_path), other_data, mode="append")
+    assert DeltaTable(str(tmp_path)).version() == 0
+
+
+@pytest.mark.parametrize("name", ["col name", "a,b"])
+def test_write_invalid_column_names(tmp_path: pathlib.Path, name: str):
+    data = pa.table({"id": pa.array([1, 2]), name: pa.array(["a", "b"])})
+    with pytest.raises(SchemaMismatchError, match="please rename the columns"):
+        write_deltalake(str(tmp_path), data)
+    assert list(tmp_path.iterdir()) == []
+
+
+def test_write_unicode_column_names(tmp_path: pathlib.Path):
+    data = pa.table({"größe": pa.array([1, 2]), "名前": pa.array(["a", "b"])})
+    write_deltalake(str(tmp_path), data)
+    write_deltalake(str(tmp_path), data, mode="append")
+
+    dt = DeltaTable(str(tmp_path))
+    assert dt.protocol().min_reader_version == 1
+    assert dt.to_pyarrow_table().to_pydict() == {
+        "größe": [1, 2, 1, 2],
+        "名前": ["a", "b", "a", "b"],
+    }
+
+
+def test_write_iterable_streams_batches(tmp_path: pathlib.Path, sample_data: pa.Table):
+    consumed = []
+
+    def batches():
+        for batch in sample_data.to_batches(max_chunksize=1):
+            consumed.append(batch)
+            yield batch
+
+    write_deltalake(str(tmp_path), sample_data, partition_by=["date"])
+    write_deltalake(str(tmp_path), batches(), schema=sample_data.schema, mode="append")
+    assert len(consumed) == 3
+    assert DeltaTable(str(tmp_path)).to_pyarrow_table().num_rows == 6
+
+    def failing_batches():
+        yield from sample_data.to_batches()
+        raise RuntimeError("source failed")
+
+    files_before = sorted(tmp_path.glob("date=*/*.parquet"))
+    with pytest.raises(RuntimeError, match="source failed"):
+        write_deltalake(
+            str(tmp_path), failing_batches(), schema=sample_data.schema, mode="append"
+        )
+    assert sorted(tmp_path.glob("date=*/*.parquet")) == files_before
+    assert DeltaTable(str(tmp_path)).version() == 1
+
+
+def test_write_overwrite_with_predicate(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data, partition_by=["date"])
+    dt = DeltaTable(str(tmp_path))
+
+    replacement = pa.table(
+        {
+            "id": pa.array([4], pa.int64()),
+            "value": pa.array([4.0], pa.float64()),
+            "date": pa.array(["2021-01-01"]),
+        }
+    )
+    metrics = write_deltalake(
+        dt, replacement, mode="overwrite", predicate="DATE = '2021-01-01'"
+    )
+    assert metrics is not None
+    assert metrics.num_removed_files == 1
+    assert sorted(dt.to_pyarrow_table()["id"].to_pylist()) == [3, 4]
+    assert dt.history(1)[0]["operationParameters"]["predicate"] == "DATE = '2021-01-01'"
+
+    with pytest.raises(DeltaError, match="does not match the predicate"):
+        write_deltalake(
+            dt, replacement, mode="overwrite", predicate="date = '2021-01-02'"
+        )
+    with pytest.raises(DeltaError, match="not a partition column"):
+        write_deltalake(dt, replacement, mode="overwrite", predicate="id = 4")
+    with pytest.raises(DeltaError, match="save mode overwrite"):
+        write_deltalake(dt, replacement, mode="append", predicate="id = 4")
+    assert dt.version() == 1
+    assert len(list(tmp_path.glob("date=*/*.parquet"))) == 3
+
+
+def test_write_schema_modes(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data)
+    dt = DeltaTable(str(tmp_path))
+
+    extended = sample_data.append_column("comment", pa.array(["a", "b", "c"]))
+    with pytest.raises(DeltaError, match="not part of the table schema"):
+        write_deltalake(dt, extended, mode="append")
+    write_deltalake(dt, extended, mode="append", schema_mode="merge")
+    assert dt.schema().to_pyarrow().names == ["id", "value", "date", "comment"]
+    assert dt.to_pyarrow_table()["comment"].to_pylist().count(None) == 3
+
+    replacement = pa.table({"name": pa.array(["x"])})
+    with pytest.raises(DeltaError, match="save mode overwrite"):
+        write_deltalake(dt, replacement, mode="append", schema_mode="overwrite")
+    write_deltalake(dt, replacement, mode="overwrite", schema_mode="overwrite")
+    assert dt.schema().to_pyarrow().names == ["name"]
+    assert dt.to_pyarrow_table().to_pydict() == {"name": ["x"]}
+
+    with pytest.raises(ValueError, match="schema_mode"):
+        write_deltalake(dt, replacement, mode="append", schema_mode="evolve")
+
+
+def test_write_post_commithook(tmp_path: pathlib.Path, sample_data: pa.Table):
+    summaries = []
+    write_deltalake(
+        str(tmp_path),
+        sample_data,
+        partition_by=["date"],
+        post_commithook=summaries.append,
+    )
+    write_deltalake(
+        str(tmp_path), sample_data, mode="overwrite", post_commithook=summaries.append
+    )
+
+    assert summaries == [
+        {
+            "version": 0,
+            "operation": "WRITE",
+            "operation_parameters": {
+                "mode": "ErrorIfExists",
+                "partitionBy": '["date"]',
+            },
+            "num_added_files": 2,
+            "num_removed_files": 0,
+        },
+        {
+            "version": 1,
+            "operation": "WRITE",
+            "operation_parameters": {"mode": "Overwrite", "partitionBy": '["date"]'},
+            "num_added_files": 2,
+            "num_removed_files": 2,
+        },
+    ]
+
+
+def test_write_failing_post_commithook(tmp_path: pathlib.Path, sample_data: pa.Table):
+    def failing_hook(summary):
+        raise RuntimeError("hook failed")
+
+    # the failure is only logged
+    write_deltalake(str(tmp_path), sample_data, post_commithook=failing_hook)
+    assert DeltaTable(str(tmp_path)).version() == 0
+
+    with pytest.raises(DeltaError, match="hook failed"):
+        write_deltalake(
+            str(tmp_path),
+            sample_data,
+            mode="append",
+            post_commithook=failing_hook,
+            strict_post_commithook=True,
+        )
+    # the data stays committed
+    assert DeltaTable(str(tmp_path)).version() == 1
+
+
+def test_write_progress_callback(tmp_path: pathlib.Path, sample_data: pa.Table):
+    events = []
+    write_deltalake(
+        str(tmp_path),
+        sample_data,
+        partition_by=["date"],
+        progress_callback=events.append,
+    )
+
+    assert [(e["phase"], e["done"], e["total"]) for e in events] == [
+        ("write_files", 1, 2),
+        ("write_files", 2, 2),
+    ]
+    assert events[0]["bytes"] < events[1]["bytes"]
+
+
+def test_write_cancelled(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data, partition_by=["date"])
+
+    cancelled = threading.Event()
+    events = []
+
+    def cancel_after_first_file(event):
+        events.append(event)
+        cancelled.set()
+
+    with pytest.raises(DeltaError, match="cancelled"):
+        write_deltalake(
+            str(tmp_path),
+            sample_data,
+            mode="append",
+            progress_callback=cancel_after_first_file,
+            cancellation_event=cancelled,
+        )
+
+    assert len(events) == 1
+    assert events[0]["phase"] == "write_files"
+    assert DeltaTable(str(tmp_path)).version() == 0
+    # the file uploaded before the cancellation is deleted again
+    assert len(list(tmp_path.glob("date=*/*.parquet"))) == 2
+
+
+def test_detail_after_append(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data, partition_by=["date"])
+    dt = DeltaTable(str(tmp_path))
+    before = dt.detail()
+    assert before["partitionColumns"] == ["date"]
+    assert before["numFiles"] == 2
+
+    metrics = write_deltalake(dt, sample_data, mode="append")
+    after = dt.detail()
+    assert after["numFiles"] == 4
+    assert after["sizeInBytes"] == before["sizeInBytes"] + metrics.num_added_bytes
+    assert after["lastModified"] >= before["lastModified"]
+
+
+def test_generate_manifest(tmp_path: pathlib.Path, sample_data: pa.Table):
+    def manifests() -> Dict[str, List[str]]:
+        root = tmp_path / "_symlink_format_manifest"
+        return {
+            str(path.parent.relative_to(root)): sorted(path.read_text().splitlines())
+            for path in root.glob("*/manifest")
+        }
+
+    def partition_files(dt: DeltaTable, partition: str) -> List[str]:
+        return sorted(
+            uri for uri in dt.file_uris() if "/date={}/".format(partition) in uri
+        )
+
+    write_deltalake(str(tmp_path), sample_data, partition_by=["date"])
+    dt = DeltaTable(str(tmp_path))
+    dt.generate_manifest()
+    assert manifests() == {
+        "date=2021-01-01": partition_files(dt, "2021-01-01"),
+        "date=2021-01-02": partition_files(dt, "2021-01-02"),
+    }
+
+
+def test_manifest_updated_after_write(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(
+        str(tmp_path),
+        sample_data,
+        partition_by=["date"],
+        configuration={"delta.compatibility.symlinkFormatManifest.enabled": "true"},
+    )
+    dt = DeltaTable(str(tmp_path))
+    write_deltalake(dt, sample_data.slice(0, 1), mode="append")
+    root = tmp_path / "_symlink_format_manifest"
+    assert len((root / "date=2021-01-01" / "manifest").read_text().splitlines()) == 2
+    assert len((root / "date=2021-01-02" / "manifest").read_text().splitlines()) == 1
+
+    # only the data of the first date remains
+    write_deltalake(dt, sample_data.slice(0, 2), mode="overwrite")
+    assert sorted(
+        (root / "date=2021-01-01" / "manifest").read_text().splitlines()
+    ) == sorted(dt.file_uris())
+    assert not (root / "date=2021-01-02" / "manifest").exists()
+
+
+def test_write_metrics(tmp_path: pathlib.Path, sample_data: pa.Table):
+    def committed_metrics(metrics: WriteMetrics) -> Dict[str, str]:
+        return {
+            "numFiles": str(metrics.num_added_files),
+            "numRemovedFiles": str(metrics.num_removed_files),
+            "numOutputBytes": str(metrics.num_added_bytes),
+            "numRemovedBytes": str(metrics.num_removed_bytes),
+            "numOutputRows": str(metrics.num_output_rows),
+            "numPartitions": str(metrics.num_partitions),
+            "executionTimeMs": str(metrics.execution_time_ms),
+        }
+
+    metrics = write_deltalake(str(tmp_path), sample_data, partition_by=["date"])
+    dt = DeltaTable(str(tmp_path))
+    assert metrics is not None
+    assert metrics.version == 0
+    assert metrics.num_added_files == 2
+    assert metrics.num_removed_files == 0
+    assert metrics.num_output_rows == 3
+    assert metrics.num_partitions == 2
+    assert metrics.num_added_bytes == sum(
+        (tmp_path / path).stat().st_size for path in dt.files()
+    )
+    assert dt.history(1)[0]["operationMetrics"] == committed_metrics(metrics)
+
+    added_bytes = metrics.num_added_bytes
+    metrics = write_deltalake(dt, sample_data.slice(0, 1), mode="overwrite")
+    assert metrics is not None
+    assert metrics.version == 1
+    assert metrics.num_added_files == 1
+    assert metrics.num_removed_files == 2
+    assert metrics.num_removed_bytes == added_bytes
+    assert metrics.num_output_rows == 1
+    assert dt.history(1)[0]["operationMetrics"] == committed_metrics(metrics)
+
+    assert write_deltalake(dt, sample_data, mode="ignore") is None
+
+
+def test_export_iceberg_metadata(tmp_path: pathlib.Path, sample_data: pa.Table):
+    write_deltalake(str(tmp_path), sample_data, partition_by=["date"])
+    dt = DeltaTable(str(tmp_path))
+    metadata_uri = dt.export_iceberg_metadata()
+    assert metadata_uri.endswith("metadata/v1.metadata.json")
+    assert (tmp_path / "metadata" / "version-hint.text").read_text() == "1"
+
+    metadata = json.loads(pathlib.Path(metadata_uri).read_text())
+    assert metadata["properties"]["delta.version"] == str(dt.version())
+    assert [field["name"] for field in metadata["schema"]["fields"]] == [
+        "id",
+        "value",
+        "date",
+    ]
+    assert metadata["snapshots"][0]["summary"]["total-records"] == "3"
+
+    pyiceberg = pytest.importorskip("pyiceberg.table")
+    table = pyiceberg.StaticTable.from_metadata(metadata_uri)
+    assert table.scan().to_arrow().sort_by("id").column("id").to_pylist() == [1, 2, 3]
+
+
+def test_streaming_writer(tmp_path: pathlib.Path, sample_data: pa.Table):
+    dt = DeltaTable.create(str(tmp_path), sample_data.schema, partition_by=["date"])
+
+    with pytest.raises(ValueError, match="partitioned by"):
+        dt.create_writer(partition_by=["id"])
+
+    # every batch fills its file, so each batch of a partition is uploaded as a new file
+    with dt.create_writer(partition_by=["date"], target_file_size=1) as writer:
+        for batch in sample_data.to_batches(max_chunksize=1):
+            writer.write_batch(batch)
+        assert dt.version() == 0
+        assert len(list(tmp_path.glob("date=*/*.parquet"))) == 3
+
+    assert dt.version() == 1
+    assert len(dt.files()) == 3
+    assert dt.history(1)[0]["operation"] == "WRITE"
+    assert sorted(dt.to_pyarrow_table()["id"].to_pylist()) == [1, 2, 3]
+
+    writer = dt.create_writer()
+    writer.write_batch(sample_data)
+    metrics = writer.close()
+    assert metrics.version == 2
+    assert metrics.num_added_files == 2
+    assert metrics.num_output_rows == 3
+    assert metrics.num_partitions == 2
+    with pytest.raises(DeltaError, match="closed"):
+        writer.write_batch(sample_data)
+
+
+def test_streaming_writer_error_deletes_files(
+    tmp_path: pathlib.Path, sample_data: pa.Table
+):
+    dt = DeltaTable.create(str(tmp_path), sample_data.schema, partition_by=["date"])
+
+    with pytest.raises(RuntimeError, match="source failed"):
+        with dt.create_writer(target_file_size=1) as writer:
+            writer.write_batch(sample_data)
+            assert len(list(tmp_path.glob("date=*/*.parquet"))) == 2
+            raise RuntimeError("source failed")
+    assert not list(tmp_path.glob("date=*/*.parquet"))
+
+    writer = dt.create_writer(target_file_size=1)
+    writer.write_batch(sample_data)
+    with pytest.raises(DeltaError):
+        writer.write_batch(pa.table({"other": pa.array([1])}))
+    assert not list(tmp_path.glob("date=*/*.parquet"))
+    with pytest.raises(DeltaError, match="closed"):
+        writer.close()
+    assert dt.version() == 0
+
+
+def test_streaming_writer_commits_after_concurrent_write(
+    tmp_path: pathlib.Path, sample_data: pa.Table
+):
+    dt = DeltaTable.create(str(tmp_path), sample_data.schema, partition_by=["date"])
+    writer = dt.create_writer()
+    writer.write_batch(sample_data)
+    write_deltalake(str(tmp_path), sample_data, mode="append")
+
+    # the commit retries on a copy of the table, which is updated once committed
+    assert writer.close().version == 2
+    assert dt.version() == 2
+    assert len(dt.files()) == 4
@@ -80,6 +80,10 @@ impl ColumnValueStat {
     }
 
     /// Returns the serde_json representation of the ColumnValueStat.
+    ///
+    /// Numbers that have no exact JSON representation are kept as strings: `"NaN"`,
+    /// `"Infinity"` and `"-Infinity"`, integers overflowing a 64 bit integer and numbers that
+    /// would change their value when parsed as a double (e.g. high precision decimals).
     pub fn as_value(&self) -> Option<&Value> {
         match self {
             ColumnValueStat::Value(v) => Some(v),
@@ -116,6 +120,113 @@ impl ColumnCountStat {
     }
 }
 
+/// Rewrites a stats JSON document so that it can be parsed by serde_json without losing
+/// information. Writers may emit the non-standard tokens `NaN`, `Infinity` and `-Infinity` as well
+/// as numbers which do not fit into an `i64`, `u64` or `f64`. These are turned into JSON strings
+/// holding the original text.
+fn quote_inexact_numbers(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    let mut chars = json.char_indices().peekable();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    while let Some((start, c)) = chars.next() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        let rest = &json[start..];
+        let token = ["NaN", "Infinity", "-Infinity"]
+            .iter()
+            .find(|t| rest.starts_with(*t));
+        if let Some(token) = token {
+            out.push('"');
+            out.push_str(token);
+            out.push('"');
+            // skip the remaining characters of the token
+            for _ in 1..token.len() {
+                chars.next();
+            }
+            continue;
+        }
+
+        if c == '-' || c.is_ascii_digit() {
+            let mut end = start + c.len_utf8();
+            while let Some((i, n)) = chars.peek() {
+                if n.is_ascii_digit() || matches!(n, '.' | 'e' | 'E' | '+' | '-') {
+                    end = *i + n.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let number = &json[start..end];
+            if is_exact_json_number(number) {
+                out.push_str(number);
+            } else {
+                out.push('"');
+                out.push_str(number);
+                out.push('"');
+            }
+            continue;
+        }
+
+        if c == '"' {
+            in_string = true;
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+fn is_exact_json_number(number: &str) -> bool {
+    if !number.contains(|c| matches!(c, '.' | 'e' | 'E')) {
+        return number.parse::<i64>().is_ok() || number.parse::<u64>().is_ok();
+    }
+
+    // A number is only kept as a number if it survives the round trip through a double, so
+    // that high precision decimals keep all of their digits.
+    match number.parse::<f64>() {
+        Ok(f) if f.is_finite() => normalized_number(number) == normalized_number(&f.to_string()),
+        _ => false,
+    }
+}
+
+/// Significant digits, sign and exponent of a number literal, such that literals denoting the
+/// same value compare equal.
+fn normalized_number(number: &str) -> Option<(bool, String, i64)> {
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, number),
+    };
+    let (mantissa, exponent) = match number.find(|c| c == 'e' || c == 'E') {
+        Some(i) => (&number[..i], number[i + 1..].parse::<i64>().ok()?),
+        None => (number, 0),
+    };
+    let (int_part, frac_part) = match mantissa.find('.') {
+        Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+        None => (mantissa, ""),
+    };
+    let digits = [int_part, frac_part].concat();
+    let leading_zeros = digits.len() - digits.trim_start_matches('0').len();
+    let significant = digits.trim_matches('0');
+    if significant.is_empty() {
+        return Some((false, String::new(), 0));
+    }
+    // Exponent of the first significant digit.
+    let exponent = exponent + int_part.len() as i64 - leading_zeros as i64;
+    Some((negative, significant.to_string(), exponent))
+}
+
 /// Statistics associated with Add actions contained in the Delta log.
 #[derive(Serialize, Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
@@ -293,10 +404,12 @@ impl Add {
 
     /// Returns the serde_json representation of stats contained in the action if present.
     /// Since stats are defined as optional in the protocol, this may be None.
+    /// Values that cannot be represented as JSON numbers without loss, e.g. `NaN` or integers
+    /// exceeding 64 bits, are returned as strings. See [`ColumnValueStat::as_value`].
     pub fn get_stats(&self) -> Result<Option<Stats>, serde_json::error::Error> {
-        self.stats
-            .as_ref()
-            .map_or(Ok(None), |s| serde_json::from_str(s))
+        self.stats.as_ref().map_or(Ok(None), |s| {
+            serde_json::from_str(&quote_inexact_numbers(s))
+        })
     }
 
     /// Returns the composite HashMap representation of stats contained in the action if present.
@@ -847,7 +960,7 @@ impl Action {
 
 /// Operation performed when creating a new log entry with one or more actions.
 /// This is a key element of the `CommitInfo` action.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum DeltaOperation {
     /// Represents a Delta `Write` operation.
     /// Write operations will typically only include `Add` actions.
@@ -872,7 +985,7 @@ pub enum DeltaOperation {
 }
 
 /// The SaveMode used when performing a DeltaOperation
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SaveMode {
     /// Files will be appended to the target location.
     Append,
@@ -885,7 +998,7 @@ pub enum SaveMode {
 }
 
 /// The OutputMode used in streaming operations.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputMode {
     /// Only new rows will be written when new data is available.
     Append,
@@ -982,4 +1095,67 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_load_table_stats_with_inexact_numbers() {
+        let action = Add {
+            stats: Some(
+                r#"{"numRecords":3,"minValues":{"f":NaN,"g":-Infinity,"i":-9223372036854775808,"s":"NaN 1e400","d":0.1},"maxValues":{"f":NaN,"g":Infinity,"i":123456789012345678901234,"s":"x\"Infinity","d":1e400,"p":12345678901234567890.123,"q":1234567890123456.7,"r":0.30000000000000004}}"#
+                    .to_string(),
+            ),
+            ..Default::default()
+        };
+
+        let stats = action.get_stats().unwrap().unwrap();
+
+        assert_eq!(stats.num_records, 3);
+        assert_eq!(
+            stats.min_values["f"].as_value().unwrap(),
+            &serde_json::json!("NaN")
+        );
+        assert_eq!(
+            stats.min_values["g"].as_value().unwrap(),
+            &serde_json::json!("-Infinity")
+        );
+        assert_eq!(
+            stats.min_values["i"].as_value().unwrap(),
+            &serde_json::json!(i64::MIN)
+        );
+        assert_eq!(
+            stats.min_values["s"].as_value().unwrap(),
+            &serde_json::json!("NaN 1e400")
+        );
+        assert_eq!(
+            stats.min_values["d"].as_value().unwrap(),
+            &serde_json::json!(0.1)
+        );
+        assert_eq!(
+            stats.max_values["g"].as_value().unwrap(),
+            &serde_json::json!("Infinity")
+        );
+        assert_eq!(
+            stats.max_values["i"].as_value().unwrap(),
+            &serde_json::json!("123456789012345678901234")
+        );
+        assert_eq!(
+            stats.max_values["s"].as_value().unwrap(),
+            &serde_json::json!("x\"Infinity")
+        );
+        assert_eq!(
+            stats.max_values["d"].as_value().unwrap(),
+            &serde_json::json!("1e400")
+        );
+        assert_eq!(
+            stats.max_values["p"].as_value().unwrap(),
+            &serde_json::json!("12345678901234567890.123")
+        );
+        assert_eq!(
+            stats.max_values["q"].as_value().unwrap(),
+            &serde_json::json!("1234567890123456.7")
+        );
+        assert_eq!(
+            stats.max_values["r"].as_value().unwrap(),
+            &serde_json::json!(0.30000000000000004)
+        );
+    }
 }
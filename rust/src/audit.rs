@@ -0,0 +1,348 @@
+//! Read-only verification that the loaded state of a delta table is consistent with its log and
+//! its storage.
+
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::storage::StorageError;
+use super::table_state::DeltaTableState;
+use super::{ApplyLogError, DeltaDataTypeVersion, DeltaTable, DeltaTableError};
+
+/// The highest reader version of the protocol this crate can read
+pub const MAX_SUPPORTED_READER_VERSION: i32 = 2;
+
+/// Options of [`audit`]
+#[derive(Debug, Clone)]
+pub struct AuditOptions {
+    /// Maximum number of concurrent requests checking the data files in the storage
+    pub max_concurrent_requests: usize,
+    /// Maximum number of offending paths reported per check
+    pub max_sample_size: usize,
+}
+
+impl Default for AuditOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 16,
+            max_sample_size: 20,
+        }
+    }
+}
+
+/// A check of [`audit`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditCheck {
+    /// Every active data file exists in the storage.
+    FilesExist,
+    /// The size of every active data file matches the size recorded in its add action.
+    FileSizesMatch,
+    /// No two active add actions reference the same path.
+    UniqueAddPaths,
+    /// The latest checkpoint holds the same state as replaying the log up to its version.
+    CheckpointMatchesLog,
+    /// The protocol of the table can be read by this crate.
+    ProtocolReadable,
+    /// The statistics of every active data file can be parsed.
+    StatsParse,
+}
+
+/// Outcome of an [`AuditCheck`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// No problem was found.
+    Passed,
+    /// At least one problem was found.
+    Failed,
+    /// The check could not be done, e.g. because the table has no checkpoint.
+    Skipped,
+}
+
+/// Result of an [`AuditCheck`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditCheckResult {
+    /// The check
+    pub check: AuditCheck,
+    /// The outcome of the check
+    pub status: AuditStatus,
+    /// Number of problems found
+    pub num_failures: usize,
+    /// Paths of the offending files, at most [`AuditOptions::max_sample_size`] of them
+    pub sample: Vec<String>,
+    /// Description of the outcome if the paths alone do not explain it
+    pub message: Option<String>,
+}
+
+impl AuditCheckResult {
+    fn skipped(check: AuditCheck, message: String) -> Self {
+        Self {
+            check,
+            status: AuditStatus::Skipped,
+            num_failures: 0,
+            sample: vec![],
+            message: Some(message),
+        }
+    }
+}
+
+/// Report of [`audit`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditReport {
+    /// The audited version of the table
+    pub version: DeltaDataTypeVersion,
+    /// The results of all checks
+    pub checks: Vec<AuditCheckResult>,
+}
+
+impl AuditReport {
+    /// Whether no check failed
+    pub fn is_healthy(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|result| result.status != AuditStatus::Failed)
+    }
+
+    /// Returns the result of the given check
+    pub fn get(&self, check: AuditCheck) -> Option<&AuditCheckResult> {
+        self.checks.iter().find(|result| result.check == check)
+    }
+}
+
+/// Collects the offending paths of a check, keeping a bounded sample.
+struct Failures {
+    check: AuditCheck,
+    count: usize,
+    sample: Vec<String>,
+    max_sample_size: usize,
+}
+
+impl Failures {
+    fn new(check: AuditCheck, options: &AuditOptions) -> Self {
+        Self {
+            check,
+            count: 0,
+            sample: vec![],
+            max_sample_size: options.max_sample_size,
+        }
+    }
+
+    fn push(&mut self, path: &str) {
+        self.count += 1;
+        if self.sample.len() < self.max_sample_size {
+            self.sample.push(path.to_string());
+        }
+    }
+
+    fn into_result(self, message: Option<String>) -> AuditCheckResult {
+        AuditCheckResult {
+            check: self.check,
+            status: if self.count == 0 && message.is_none() {
+                AuditStatus::Passed
+            } else {
+                AuditStatus::Failed
+            },
+            num_failures: self.count.max(message.is_some() as usize),
+            sample: self.sample,
+            message,
+        }
+    }
+}
+
+/// Verifies that the loaded state of `table` is healthy without modifying anything: every active
+/// data file exists with the recorded size, no path is added twice, the latest checkpoint matches
+/// the replayed log, the protocol is readable and the statistics parse. Errors of the storage
+/// other than missing files abort the audit.
+pub async fn audit(
+    table: &DeltaTable,
+    options: &AuditOptions,
+) -> Result<AuditReport, DeltaTableError> {
+    let files = table.get_state().files();
+
+    let mut missing = Failures::new(AuditCheck::FilesExist, options);
+    let mut wrong_sizes = Failures::new(AuditCheck::FileSizesMatch, options);
+    let mut num_sizes_checked = 0;
+    let mut heads = futures::stream::iter(files.iter().map(|add| {
+        let uri = table.storage.join_path(&table.table_uri, &add.path);
+        async move { (add, table.storage.head_obj(&uri).await) }
+    }))
+    .buffer_unordered(options.max_concurrent_requests.max(1));
+    while let Some((add, result)) = heads.next().await {
+        match result {
+            Ok(meta) => {
+                if let Some(size) = meta.size {
+                    num_sizes_checked += 1;
+                    if size != add.size {
+                        wrong_sizes.push(&add.path);
+                    }
+                }
+            }
+            Err(StorageError::NotFound) => missing.push(&add.path),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    drop(heads);
+
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    for add in files {
+        *occurrences.entry(add.path.as_str()).or_default() += 1;
+    }
+    let mut duplicates = Failures::new(AuditCheck::UniqueAddPaths, options);
+    let mut duplicate_paths = occurrences
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(path, _)| path)
+        .collect::<Vec<_>>();
+    duplicate_paths.sort_unstable();
+    duplicate_paths
+        .into_iter()
+        .for_each(|path| duplicates.push(path));
+
+    let mut invalid_stats = Failures::new(AuditCheck::StatsParse, options);
+    for add in files {
+        if add.get_stats().is_err() {
+            invalid_stats.push(&add.path);
+        }
+    }
+
+    let reader_version = table.get_min_reader_version();
+    let protocol_message = if reader_version > MAX_SUPPORTED_READER_VERSION {
+        Some(format!(
+            "The table requires reader version {}, but at most {} is supported",
+            reader_version, MAX_SUPPORTED_READER_VERSION
+        ))
+    } else {
+        None
+    };
+
+    let mut checks = vec![missing.into_result(None)];
+    checks.push(if num_sizes_checked == 0 && !files.is_empty() {
+        AuditCheckResult::skipped(
+            AuditCheck::FileSizesMatch,
+            "The storage backend does not report object sizes".to_string(),
+        )
+    } else {
+        wrong_sizes.into_result(None)
+    });
+    checks.push(duplicates.into_result(None));
+    checks.push(check_checkpoint(table, options).await?);
+    checks.push(Failures::new(AuditCheck::ProtocolReadable, options).into_result(protocol_message));
+    checks.push(invalid_stats.into_result(None));
+
+    Ok(AuditReport {
+        version: table.version,
+        checks,
+    })
+}
+
+/// Compares the state of the latest checkpoint up to the loaded version with the state of
+/// replaying the commits up to the checkpoint's version.
+async fn check_checkpoint(
+    table: &DeltaTable,
+    options: &AuditOptions,
+) -> Result<AuditCheckResult, DeltaTableError> {
+    let check = AuditCheck::CheckpointMatchesLog;
+    let check_point = match table
+        .find_latest_check_point_for_version(table.version)
+        .await?
+    {
+        Some(check_point) => check_point,
+        None => {
+            return Ok(AuditCheckResult::skipped(
+                check,
+                "The table has no checkpoint".to_string(),
+            ))
+        }
+    };
+
+    let failures = Failures::new(check, options);
+    let checkpoint_state = match DeltaTableState::from_checkpoint(table, &check_point, true).await {
+        Ok(state) => state,
+        Err(err) => {
+            return Ok(failures.into_result(Some(format!(
+                "The checkpoint of version {} cannot be read: {}",
+                check_point.version, err
+            ))))
+        }
+    };
+
+    let mut replayed_state = DeltaTableState::default();
+    for version in 0..=check_point.version {
+        match DeltaTableState::from_commit(table, version).await {
+            Ok(state) => replayed_state.merge(state, true),
+            Err(ApplyLogError::EndOfLog) => {
+                return Ok(AuditCheckResult::skipped(
+                    check,
+                    format!(
+                        "The commit of version {} was cleaned up, so the log cannot be replayed \
+                         up to the checkpoint of version {}",
+                        version, check_point.version
+                    ),
+                ))
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(compare_states(
+        failures,
+        check_point.version,
+        &checkpoint_state,
+        &replayed_state,
+    ))
+}
+
+fn compare_states(
+    mut failures: Failures,
+    version: DeltaDataTypeVersion,
+    checkpoint_state: &DeltaTableState,
+    replayed_state: &DeltaTableState,
+) -> AuditCheckResult {
+    let sizes = |state: &DeltaTableState| {
+        state
+            .files()
+            .iter()
+            .map(|add| (add.path.clone(), add.size))
+            .collect::<HashMap<_, _>>()
+    };
+    let checkpoint_files = sizes(checkpoint_state);
+    let replayed_files = sizes(replayed_state);
+    let mut differing_paths = checkpoint_files
+        .iter()
+        .filter(|(path, size)| replayed_files.get(*path) != Some(size))
+        .chain(
+            replayed_files
+                .iter()
+                .filter(|(path, _)| !checkpoint_files.contains_key(*path)),
+        )
+        .map(|(path, _)| path.as_str())
+        .collect::<Vec<_>>();
+    differing_paths.sort_unstable();
+    differing_paths
+        .into_iter()
+        .for_each(|path| failures.push(path));
+
+    let mut differences = vec![];
+    if checkpoint_state.min_reader_version() != replayed_state.min_reader_version()
+        || checkpoint_state.min_writer_version() != replayed_state.min_writer_version()
+    {
+        differences.push("protocol");
+    }
+    if checkpoint_state.current_metadata() != replayed_state.current_metadata() {
+        differences.push("metadata");
+    }
+    if failures.count > 0 {
+        differences.push("files");
+    }
+    let message = if differences.is_empty() {
+        None
+    } else {
+        Some(format!(
+            "The {} of the checkpoint of version {} differ from the log",
+            differences.join(", "),
+            version
+        ))
+    };
+    failures.into_result(message)
+}
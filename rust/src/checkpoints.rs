@@ -1,6 +1,6 @@
 //! Implementation for writing delta checkpoints.
 
-use arrow::datatypes::Schema as ArrowSchema;
+use arrow::datatypes::{Schema as ArrowSchema, SchemaRef};
 use arrow::error::ArrowError;
 use arrow::json::reader::Decoder;
 use chrono::Datelike;
@@ -8,12 +8,11 @@ use chrono::Duration;
 use chrono::Utc;
 use chrono::MIN_DATETIME;
 use futures::StreamExt;
-use lazy_static::lazy_static;
 use log::*;
 use parquet::arrow::ArrowWriter;
 use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
 use parquet::file::writer::InMemoryWriteableCursor;
-use regex::Regex;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -27,7 +26,7 @@ use super::schema::*;
 use super::storage::{ObjectMeta, StorageBackend, StorageError};
 use super::table_state::DeltaTableState;
 use super::writer::time_utils;
-use super::{CheckPoint, DeltaTableError};
+use super::{CheckPoint, DeltaLogFile, DeltaTableError};
 use crate::DeltaTable;
 
 /// Error returned when there is an error during creating a checkpoint.
@@ -80,6 +79,42 @@ pub enum CheckpointError {
 /// The record batch size for checkpoint parquet file
 pub const CHECKPOINT_RECORD_BATCH_SIZE: usize = 5000;
 
+/// The default maximum number of actions written to a single checkpoint file
+pub const CHECKPOINT_MAX_ACTIONS_PER_PART: usize = 1_000_000;
+
+/// The default maximum estimated size in bytes of the actions written to a single checkpoint file
+pub const CHECKPOINT_MAX_PART_SIZE: usize = 128 * 1024 * 1024;
+
+/// Estimated size in bytes of an action besides its paths, statistics and partition values
+const ACTION_SIZE_OVERHEAD: usize = 64;
+
+/// Configuration options for writing checkpoints
+#[derive(Debug, Clone)]
+pub struct CheckpointConfig {
+    /// Number of actions decoded into a single record batch. Each batch is written as its own
+    /// parquet row group.
+    pub record_batch_size: usize,
+    /// Maximum number of actions written to a single checkpoint file. Checkpoints with more
+    /// actions are split into a multi-part checkpoint.
+    pub max_actions_per_part: usize,
+    /// Maximum size in bytes of the actions written to a single checkpoint file, estimated from
+    /// the lengths of their paths, statistics and partition values. Every part is buffered in
+    /// memory before it is uploaded, so this caps the memory needed to write a checkpoint of a
+    /// table with large statistics or partition values. A single larger action still gets a part
+    /// of its own.
+    pub max_part_size: usize,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            record_batch_size: CHECKPOINT_RECORD_BATCH_SIZE,
+            max_actions_per_part: CHECKPOINT_MAX_ACTIONS_PER_PART,
+            max_part_size: CHECKPOINT_MAX_PART_SIZE,
+        }
+    }
+}
+
 impl From<CheckpointError> for ArrowError {
     fn from(error: CheckpointError) -> Self {
         ArrowError::from_external_error(Box::new(error))
@@ -88,24 +123,42 @@ impl From<CheckpointError> for ArrowError {
 
 /// Creates checkpoint at `table.version` for given `table`.
 pub async fn create_checkpoint(table: &DeltaTable) -> Result<(), CheckpointError> {
+    create_checkpoint_with_config(table, &CheckpointConfig::default()).await
+}
+
+/// Creates checkpoint at `table.version` for given `table` using the given `config`.
+pub async fn create_checkpoint_with_config(
+    table: &DeltaTable,
+    config: &CheckpointConfig,
+) -> Result<(), CheckpointError> {
     create_checkpoint_for(
         table.version,
         table.get_state(),
         table.storage.as_ref(),
         &table.table_uri,
+        config,
     )
     .await?;
 
     Ok(())
 }
 
-/// Delete expires log files before given version from table. The table log retention is based on
-/// the `logRetentionDuration` property of the Delta Table, 30 days by default.
+/// Delete expires log files before the latest checkpoint not newer than `table.version`, so the
+/// table can still be loaded from that checkpoint. Nothing is deleted without a checkpoint. The
+/// table log retention is based on the `delta.logRetentionDuration` property of the Delta Table,
+/// 30 days by default.
 pub async fn cleanup_metadata(table: &DeltaTable) -> Result<i32, DeltaTableError> {
+    let check_point = match table
+        .find_latest_check_point_for_version(table.version)
+        .await?
+    {
+        Some(check_point) => check_point,
+        None => return Ok(0),
+    };
     let log_retention_timestamp =
         Utc::now().timestamp_millis() - table.get_state().log_retention_millis();
     cleanup_expired_logs_for(
-        table.version + 1,
+        check_point.version(),
         table.storage.as_ref(),
         log_retention_timestamp,
         &table.table_uri,
@@ -115,7 +168,7 @@ pub async fn cleanup_metadata(table: &DeltaTable) -> Result<i32, DeltaTableError
 
 /// Loads table from given `table_uri` at given `version` and creates checkpoint for it.
 /// The `cleanup` param decides whether to run metadata cleanup of obsolete logs.
-/// If it's empty then the table's `enableExpiredLogCleanup` is used.
+/// If it's empty then the table's `delta.enableExpiredLogCleanup` is used.
 pub async fn create_checkpoint_from_table_uri_and_cleanup(
     table_uri: &str,
     version: DeltaDataTypeVersion,
@@ -126,7 +179,8 @@ pub async fn create_checkpoint_from_table_uri_and_cleanup(
         version,
         table.get_state(),
         table.storage.as_ref(),
-        table_uri,
+        &table.table_uri,
+        &CheckpointConfig::default(),
     )
     .await?;
 
@@ -146,26 +200,142 @@ async fn create_checkpoint_for(
     state: &DeltaTableState,
     storage: &dyn StorageBackend,
     table_uri: &str,
+    config: &CheckpointConfig,
 ) -> Result<(), CheckpointError> {
-    // TODO: checkpoints _can_ be multi-part... haven't actually found a good reference for
-    // an appropriate split point yet though so only writing a single part currently.
-    // See https://github.com/delta-io/delta-rs/issues/288
-
     let delta_log_uri = storage.join_path(table_uri, "_delta_log");
     let last_checkpoint_uri = storage.join_path(&delta_log_uri, "_last_checkpoint");
 
-    debug!("Writing parquet bytes to checkpoint buffer.");
-    let parquet_bytes = parquet_bytes_from_state(state)?;
+    let current_metadata = state
+        .current_metadata()
+        .ok_or(CheckpointError::MissingMetaData)?;
 
-    let size = parquet_bytes.len() as i64;
+    let partition_col_data_types = current_metadata.get_partition_col_data_types();
 
-    let checkpoint = CheckPoint::new(version, size, None);
+    // Collect a map of paths that require special stats conversion.
+    let mut stats_conversions: Vec<(SchemaPath, SchemaDataType)> = Vec::new();
+    collect_stats_conversions(&mut stats_conversions, current_metadata.schema.get_fields());
 
-    let file_name = format!("{:020}.checkpoint.parquet", version);
-    let checkpoint_uri = storage.join_path(&delta_log_uri, &file_name);
+    // Only references are collected here, the tombstones are cloned one at a time while being
+    // written. Collecting also guarantees the action count below matches the actions written.
+    let tombstones = state.unexpired_tombstones().collect::<Vec<_>>();
 
-    debug!("Writing checkpoint to {:?}.", checkpoint_uri);
-    storage.put_obj(&checkpoint_uri, &parquet_bytes).await?;
+    // if any, tombstones do not include extended file metadata, we must omit the extended metadata fields from the remove schema
+    // See https://github.com/delta-io/delta/blob/master/PROTOCOL.md#add-file-and-remove-file
+    //
+    // DBR version 8.x and greater have different behaviors of reading the parquet file depending
+    // on the `extended_file_metadata` flag, hence this is safer to set `extended_file_metadata=false`
+    // and omit metadata columns if at least one remove action has `extended_file_metadata=false`.
+    // We've added the additional check on `size.is_some` because in delta-spark the primitive long type
+    // is used, hence we want to omit possible errors when `extended_file_metadata=true`, but `size=null`
+    let use_extended_remove_schema = tombstones
+        .iter()
+        .all(|r| r.extended_file_metadata == Some(true) && r.size.is_some());
+
+    let metadata = action::MetaData::try_from(current_metadata.clone())?;
+
+    // The parts are cut before encoding anything, so that the number of parts is known for the
+    // file names. The sizes are estimated in the order the actions are written below.
+    let action_sizes = [
+        ACTION_SIZE_OVERHEAD,
+        ACTION_SIZE_OVERHEAD + metadata.schema_string.len(),
+    ]
+    .into_iter()
+    .chain(
+        state
+            .app_transaction_version()
+            .keys()
+            .map(|app_id| ACTION_SIZE_OVERHEAD + app_id.len()),
+    )
+    .chain(tombstones.iter().map(|r| estimated_remove_size(r)))
+    .chain(state.files().iter().map(estimated_add_size));
+    let part_lengths = part_lengths(
+        action_sizes,
+        config.max_actions_per_part.max(1),
+        config.max_part_size.max(1),
+    );
+    let num_parts = part_lengths.len();
+
+    // protocol
+    let mut actions = std::iter::once(action::Action::protocol(action::Protocol {
+        min_reader_version: state.min_reader_version(),
+        min_writer_version: state.min_writer_version(),
+    }))
+    // metaData
+    .chain(std::iter::once(action::Action::metaData(metadata)))
+    // txns
+    .chain(
+        state
+            .app_transaction_version()
+            .iter()
+            .map(|(app_id, version)| {
+                action::Action::txn(action::Txn {
+                    app_id: app_id.clone(),
+                    version: *version,
+                    last_updated: None,
+                })
+            }),
+    )
+    // removes
+    .chain(tombstones.iter().map(|r| {
+        let mut r = (*r).clone();
+
+        // If use_extended_remove_schema=false for some of the tombstones, then it should be for each.
+        // As a "new writer", we should always set `extendedFileMetadata` when writing, and include/ignore the other three fields accordingly.
+        // https://github.com/delta-io/delta/blob/fb0452c2fb142310211c6d3604eefb767bb4a134/core/src/main/scala/org/apache/spark/sql/delta/actions/actions.scala#L311-L314
+        if !use_extended_remove_schema || None == r.extended_file_metadata {
+            r.extended_file_metadata = Some(false);
+        }
+
+        action::Action::remove(r)
+    }))
+    .map(|a| serde_json::to_value(a).map_err(ArrowError::from))
+    // adds
+    .chain(state.files().iter().map(|f| {
+        checkpoint_add_from_state(f, partition_col_data_types.as_slice(), &stats_conversions)
+    }));
+
+    // Create the arrow schema that represents the Checkpoint parquet file.
+    let arrow_schema = delta_log_schema_for_table(
+        <ArrowSchema as TryFrom<&Schema>>::try_from(&current_metadata.schema)?,
+        current_metadata.partition_columns.as_slice(),
+        use_extended_remove_schema,
+    );
+
+    // Each part is buffered in memory and uploaded before the next one is encoded, so the peak
+    // memory is bounded by the size of a single part rather than the size of the table state.
+    let mut size = 0;
+    for (part, part_length) in (1..=num_parts).zip(part_lengths) {
+        let file_name = if num_parts == 1 {
+            format!("{:020}.checkpoint.parquet", version)
+        } else {
+            format!(
+                "{:020}.checkpoint.{:010}.{:010}.parquet",
+                version, part, num_parts
+            )
+        };
+        let checkpoint_uri = storage.join_path(&delta_log_uri, &file_name);
+
+        debug!(
+            "Writing part {} of {} to checkpoint buffer.",
+            part, num_parts
+        );
+        let parquet_bytes = parquet_bytes_from_actions(
+            &mut actions.by_ref().take(part_length),
+            arrow_schema.clone(),
+            config.record_batch_size,
+        )?;
+        size += parquet_bytes.len() as i64;
+
+        debug!("Writing checkpoint to {:?}.", checkpoint_uri);
+        storage.put_obj(&checkpoint_uri, &parquet_bytes).await?;
+    }
+
+    let parts = if num_parts > 1 {
+        Some(num_parts as u32)
+    } else {
+        None
+    };
+    let checkpoint = CheckPoint::new(version, size, parts);
 
     let last_checkpoint_content: Value = serde_json::to_value(&checkpoint)?;
     let last_checkpoint_content = serde_json::to_string(&last_checkpoint_content)?;
@@ -217,11 +387,6 @@ async fn cleanup_expired_logs_for(
     log_retention_timestamp: i64,
     table_uri: &str,
 ) -> Result<i32, DeltaTableError> {
-    lazy_static! {
-        static ref DELTA_LOG_REGEX: Regex =
-            Regex::new(r#"^*[/\\]_delta_log[/\\](\d{20})\.(json|checkpoint)*$"#).unwrap();
-    }
-
     let mut deleted_log_num = 0;
 
     // Get file objects from table.
@@ -233,9 +398,7 @@ async fn cleanup_expired_logs_for(
 
         let ts = obj_meta.modified.timestamp_millis();
 
-        if let Some(captures) = DELTA_LOG_REGEX.captures(&obj_meta.path) {
-            let log_ver_str = captures.get(1).unwrap().as_str();
-            let log_ver: DeltaDataTypeVersion = log_ver_str.parse().unwrap();
+        if let Some(DeltaLogFile::Commit(log_ver)) = DeltaLogFile::from_path(&obj_meta.path) {
             if log_ver < until_version && ts <= log_retention_timestamp {
                 candidates.push((log_ver, obj_meta));
             }
@@ -250,6 +413,9 @@ async fn cleanup_expired_logs_for(
         ObjectMeta {
             path: String::new(),
             modified: MIN_DATETIME,
+            size: None,
+            e_tag: None,
+            version: None,
         },
     );
     let file_needs_time_adjustment =
@@ -291,8 +457,8 @@ async fn cleanup_expired_logs_for(
             let updated = (
                 current_file.0,
                 ObjectMeta {
-                    path: current_file.1.path.clone(),
                     modified: last_file.1.modified.add(Duration::seconds(1)),
+                    ..current_file.1.clone()
                 },
             );
             maybe_delete_files.push(updated);
@@ -319,91 +485,77 @@ async fn cleanup_expired_logs_for(
     }
 }
 
-fn parquet_bytes_from_state(state: &DeltaTableState) -> Result<Vec<u8>, CheckpointError> {
-    let current_metadata = state
-        .current_metadata()
-        .ok_or(CheckpointError::MissingMetaData)?;
-
-    let partition_col_data_types = current_metadata.get_partition_col_data_types();
-
-    // Collect a map of paths that require special stats conversion.
-    let mut stats_conversions: Vec<(SchemaPath, SchemaDataType)> = Vec::new();
-    collect_stats_conversions(&mut stats_conversions, current_metadata.schema.get_fields());
-
-    let mut tombstones = state.unexpired_tombstones().cloned().collect::<Vec<_>>();
-
-    // if any, tombstones do not include extended file metadata, we must omit the extended metadata fields from the remove schema
-    // See https://github.com/delta-io/delta/blob/master/PROTOCOL.md#add-file-and-remove-file
-    //
-    // DBR version 8.x and greater have different behaviors of reading the parquet file depending
-    // on the `extended_file_metadata` flag, hence this is safer to set `extended_file_metadata=false`
-    // and omit metadata columns if at least one remove action has `extended_file_metadata=false`.
-    // We've added the additional check on `size.is_some` because in delta-spark the primitive long type
-    // is used, hence we want to omit possible errors when `extended_file_metadata=true`, but `size=null`
-    let use_extended_remove_schema = tombstones
-        .iter()
-        .all(|r| r.extended_file_metadata == Some(true) && r.size.is_some());
-
-    // If use_extended_remove_schema=false for some of the tombstones, then it should be for each.
-    if !use_extended_remove_schema {
-        for remove in tombstones.iter_mut() {
-            remove.extended_file_metadata = Some(false);
+/// Splits the actions into parts of at most `max_actions` actions and `max_bytes` estimated bytes,
+/// returns the number of actions of each part.
+fn part_lengths<I>(action_sizes: I, max_actions: usize, max_bytes: usize) -> Vec<usize>
+where
+    I: Iterator<Item = usize>,
+{
+    let mut parts = Vec::new();
+    let (mut actions, mut bytes) = (0, 0);
+    for size in action_sizes {
+        if actions > 0 && (actions == max_actions || bytes + size > max_bytes) {
+            parts.push(actions);
+            actions = 0;
+            bytes = 0;
         }
+        actions += 1;
+        bytes += size;
+    }
+    if actions > 0 {
+        parts.push(actions);
     }
+    parts
+}
 
-    // protocol
-    let mut jsons = std::iter::once(action::Action::protocol(action::Protocol {
-        min_reader_version: state.min_reader_version(),
-        min_writer_version: state.min_writer_version(),
-    }))
-    // metaData
-    .chain(std::iter::once(action::Action::metaData(
-        action::MetaData::try_from(current_metadata.clone())?,
-    )))
-    // txns
-    .chain(
-        state
-            .app_transaction_version()
-            .iter()
-            .map(|(app_id, version)| {
-                action::Action::txn(action::Txn {
-                    app_id: app_id.clone(),
-                    version: *version,
-                    last_updated: None,
-                })
-            }),
-    )
-    // removes
-    .chain(tombstones.iter().map(|r| {
-        let mut r = (*r).clone();
+fn estimated_partition_values_size(values: &HashMap<String, Option<String>>) -> usize {
+    values
+        .iter()
+        .map(|(key, value)| key.len() + value.as_ref().map_or(0, String::len))
+        .sum()
+}
 
-        // As a "new writer", we should always set `extendedFileMetadata` when writing, and include/ignore the other three fields accordingly.
-        // https://github.com/delta-io/delta/blob/fb0452c2fb142310211c6d3604eefb767bb4a134/core/src/main/scala/org/apache/spark/sql/delta/actions/actions.scala#L311-L314
-        if None == r.extended_file_metadata {
-            r.extended_file_metadata = Some(false);
-        }
+fn estimated_add_size(add: &action::Add) -> usize {
+    ACTION_SIZE_OVERHEAD
+        + add.path.len()
+        + add.stats.as_ref().map_or(0, String::len)
+        + estimated_partition_values_size(&add.partition_values)
+        + add.tags.as_ref().map_or(0, estimated_partition_values_size)
+}
 
-        action::Action::remove(r)
-    }))
-    .map(|a| serde_json::to_value(a).map_err(ArrowError::from))
-    // adds
-    .chain(state.files().iter().map(|f| {
-        checkpoint_add_from_state(f, partition_col_data_types.as_slice(), &stats_conversions)
-    }));
+fn estimated_remove_size(remove: &action::Remove) -> usize {
+    ACTION_SIZE_OVERHEAD
+        + remove.path.len()
+        + remove
+            .partition_values
+            .as_ref()
+            .map_or(0, estimated_partition_values_size)
+        + remove
+            .tags
+            .as_ref()
+            .map_or(0, estimated_partition_values_size)
+}
 
-    // Create the arrow schema that represents the Checkpoint parquet file.
-    let arrow_schema = delta_log_schema_for_table(
-        <ArrowSchema as TryFrom<&Schema>>::try_from(&current_metadata.schema)?,
-        current_metadata.partition_columns.as_slice(),
-        use_extended_remove_schema,
-    );
+fn parquet_bytes_from_actions<I>(
+    actions: &mut I,
+    arrow_schema: SchemaRef,
+    record_batch_size: usize,
+) -> Result<Vec<u8>, CheckpointError>
+where
+    I: Iterator<Item = Result<Value, ArrowError>>,
+{
+    // Flush a row group for every decoded batch instead of buffering up to the default row group
+    // size inside the writer.
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(record_batch_size)
+        .build();
 
-    debug!("Writing to checkpoint parquet buffer...");
     // Write the Checkpoint parquet file.
     let writeable_cursor = InMemoryWriteableCursor::default();
-    let mut writer = ArrowWriter::try_new(writeable_cursor.clone(), arrow_schema.clone(), None)?;
-    let decoder = Decoder::new(arrow_schema, CHECKPOINT_RECORD_BATCH_SIZE, None);
-    while let Some(batch) = decoder.next_batch(&mut jsons)? {
+    let mut writer =
+        ArrowWriter::try_new(writeable_cursor.clone(), arrow_schema.clone(), Some(props))?;
+    let decoder = Decoder::new(arrow_schema, record_batch_size, None);
+    while let Some(batch) = decoder.next_batch(actions)? {
         writer.write(&batch)?;
     }
     let _ = writer.close()?;
@@ -540,7 +692,7 @@ fn collect_field_conversion(
 ) {
     match field.get_type() {
         SchemaDataType::primitive(type_name) => {
-            if let "timestamp" = type_name.as_str() {
+            if let "timestamp" | "float" | "double" = type_name.as_str() {
                 let mut key_path = current_path.clone();
                 key_path.push(field.get_name().to_owned());
                 all_paths.push((key_path, field.get_type().to_owned()));
@@ -579,6 +731,23 @@ fn apply_stats_conversion(
                     }
                 }
             }
+            SchemaDataType::primitive(type_name)
+                if type_name == "float" || type_name == "double" =>
+            {
+                // NaN and infinite bounds are kept as strings in the stats. They have no JSON
+                // number representation and do not bound the column, so they are written as null.
+                let v = context.get_mut(&path[0]);
+
+                if let Some(v) = v {
+                    if let Some(s) = v.as_str() {
+                        *v = s
+                            .parse::<f64>()
+                            .ok()
+                            .and_then(serde_json::Number::from_f64)
+                            .map_or(Value::Null, Value::Number);
+                    }
+                }
+            }
             _ => { /* noop */ }
         }
     } else {
@@ -596,6 +765,18 @@ mod tests {
     use std::time::Duration;
     use uuid::Uuid;
 
+    #[test]
+    fn part_lengths_test() {
+        let sizes = vec![10, 10, 50, 10, 100, 10, 10];
+        assert_eq!(vec![7], part_lengths(sizes.clone().into_iter(), 10, 1000));
+        assert_eq!(
+            vec![3, 3, 1],
+            part_lengths(sizes.clone().into_iter(), 3, 1000)
+        );
+        // an action larger than the budget gets a part of its own
+        assert_eq!(vec![3, 1, 1, 2], part_lengths(sizes.into_iter(), 10, 70));
+    }
+
     #[test]
     fn typed_partition_value_from_string_test() {
         let string_value: Value = "Hello World!".into();
@@ -722,6 +903,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn apply_stats_conversion_non_finite_test() {
+        let mut values = serde_json::json!({
+            "nan": "NaN",
+            "inf": "Infinity",
+            "neg_inf": "-Infinity",
+            "number": 1.5,
+        });
+        let values = values.as_object_mut().unwrap();
+        let double = SchemaDataType::primitive("double".to_string());
+
+        for name in ["nan", "inf", "neg_inf", "number"].iter() {
+            apply_stats_conversion(values, &[name.to_string()], &double);
+        }
+
+        assert_eq!(Value::Null, values["nan"]);
+        assert_eq!(Value::Null, values["inf"]);
+        assert_eq!(Value::Null, values["neg_inf"]);
+        assert_eq!(serde_json::json!(1.5), values["number"]);
+    }
+
     #[test]
     fn apply_stats_conversion_test() {
         let mut stats = STATS_JSON.clone();
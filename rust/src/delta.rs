@@ -4,7 +4,7 @@
 //
 
 use arrow::error::ArrowError;
-use chrono::{DateTime, FixedOffset, Utc};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
 use futures::StreamExt;
 use lazy_static::lazy_static;
 use log::*;
@@ -12,9 +12,12 @@ use parquet::errors::ParquetError;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{cmp::Ordering, collections::HashSet};
 use uuid::Uuid;
@@ -23,12 +26,16 @@ use crate::action::Stats;
 
 use super::action;
 use super::action::{Action, DeltaOperation};
+use super::audit::{self, AuditOptions, AuditReport};
+use super::manifest;
 use super::partitions::{DeltaTablePartition, PartitionFilter};
+use super::predicate::Predicate;
 use super::schema::*;
 use super::storage;
 use super::storage::{parse_uri, StorageBackend, StorageError, UriError};
 use super::table_state::DeltaTableState;
-use crate::delta_config::DeltaConfigError;
+use crate::delta_config;
+use crate::delta_config::{DeltaConfigError, TableProperties};
 
 /// Metadata for a checkpoint file
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
@@ -52,6 +59,11 @@ impl CheckPoint {
             parts,
         }
     }
+
+    /// The table version the checkpoint holds the state of.
+    pub(crate) fn version(&self) -> DeltaDataTypeVersion {
+        self.version
+    }
 }
 
 impl PartialEq for CheckPoint {
@@ -62,6 +74,98 @@ impl PartialEq for CheckPoint {
 
 impl Eq for CheckPoint {}
 
+/// Classification of an object found in the `_delta_log` directory.
+///
+/// Only files following the naming rules of the Delta protocol are classified. Everything else,
+/// e.g. temporary files left behind by crashed writers (`*.json.tmp`), hadoop `.crc` sidecars or
+/// files written by other engines, is not part of the log and must be ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaLogFile {
+    /// A commit file named `<version>.json`.
+    Commit(DeltaDataTypeVersion),
+    /// A single part checkpoint named `<version>.checkpoint.parquet`.
+    Checkpoint(DeltaDataTypeVersion),
+    /// One part of a multi part checkpoint named `<version>.checkpoint.<part>.<parts>.parquet`.
+    CheckpointPart {
+        /// Table version of the checkpoint.
+        version: DeltaDataTypeVersion,
+        /// One-based index of this part.
+        part: u32,
+        /// Total number of parts of the checkpoint.
+        parts: u32,
+    },
+    /// A compacted range of commits named `<start>.<end>.compacted.json`.
+    CompactedCommit {
+        /// First version contained in the compacted file.
+        start: DeltaDataTypeVersion,
+        /// Last version contained in the compacted file.
+        end: DeltaDataTypeVersion,
+    },
+    /// A version checksum file named `<version>.crc`.
+    Checksum(DeltaDataTypeVersion),
+}
+
+impl DeltaLogFile {
+    /// Classifies the object at `path` by its file name. Returns `None` for files that are not
+    /// part of the Delta log.
+    pub fn from_path(path: &str) -> Option<Self> {
+        lazy_static! {
+            static ref COMMIT_REGEX: Regex = Regex::new(r#"^(\d{20})\.json$"#).unwrap();
+            static ref CHECKPOINT_REGEX: Regex =
+                Regex::new(r#"^(\d{20})\.checkpoint\.parquet$"#).unwrap();
+            static ref CHECKPOINT_PARTS_REGEX: Regex =
+                Regex::new(r#"^(\d{20})\.checkpoint\.(\d{10})\.(\d{10})\.parquet$"#).unwrap();
+            static ref COMPACTED_REGEX: Regex =
+                Regex::new(r#"^(\d{20})\.(\d{20})\.compacted\.json$"#).unwrap();
+            static ref CHECKSUM_REGEX: Regex = Regex::new(r#"^(\d{20})\.crc$"#).unwrap();
+        }
+
+        let file_name = path.rsplit(|c| c == '/' || c == '\\').next()?;
+        let parse = |captures: &regex::Captures, i: usize| -> Option<i64> {
+            captures.get(i)?.as_str().parse().ok()
+        };
+
+        let log_file = if let Some(c) = COMMIT_REGEX.captures(file_name) {
+            DeltaLogFile::Commit(parse(&c, 1)?)
+        } else if let Some(c) = CHECKPOINT_REGEX.captures(file_name) {
+            DeltaLogFile::Checkpoint(parse(&c, 1)?)
+        } else if let Some(c) = CHECKPOINT_PARTS_REGEX.captures(file_name) {
+            let (part, parts) = (parse(&c, 2)? as u32, parse(&c, 3)? as u32);
+            if part == 0 || part > parts {
+                debug!("Ignoring checkpoint part with invalid numbering: {}", path);
+                return None;
+            }
+            DeltaLogFile::CheckpointPart {
+                version: parse(&c, 1)?,
+                part,
+                parts,
+            }
+        } else if let Some(c) = COMPACTED_REGEX.captures(file_name) {
+            DeltaLogFile::CompactedCommit {
+                start: parse(&c, 1)?,
+                end: parse(&c, 2)?,
+            }
+        } else if let Some(c) = CHECKSUM_REGEX.captures(file_name) {
+            DeltaLogFile::Checksum(parse(&c, 1)?)
+        } else {
+            debug!("Ignoring unrecognized file in delta log: {}", path);
+            return None;
+        };
+
+        Some(log_file)
+    }
+
+    /// The table version this file belongs to. For compacted commits this is the last version
+    /// contained in the file.
+    pub fn version(&self) -> DeltaDataTypeVersion {
+        match self {
+            DeltaLogFile::Commit(v) | DeltaLogFile::Checkpoint(v) | DeltaLogFile::Checksum(v) => *v,
+            DeltaLogFile::CheckpointPart { version, .. } => *version,
+            DeltaLogFile::CompactedCommit { end, .. } => *end,
+        }
+    }
+}
+
 /// Delta Table specific error
 #[derive(thiserror::Error, Debug)]
 pub enum DeltaTableError {
@@ -173,14 +277,108 @@ pub enum DeltaTableError {
         /// The invalid partition filter used.
         partition_filter: String,
     },
+    /// Error returned when a predicate is malformed or does not match the schema of the table.
+    #[error("Invalid predicate {}: {}.", .predicate, .msg)]
+    InvalidPredicate {
+        /// The invalid predicate.
+        predicate: String,
+        /// What is wrong with the predicate.
+        msg: String,
+    },
+    /// Error returned when column names contain characters that can only be stored with column
+    /// mapping enabled.
+    #[error(
+        "Found invalid character(s) among ' ,;{{}}()\\n\\t=' in the column names {:?}. These names require column mapping, which deltalake can neither read nor write yet, please rename the columns.",
+        .names
+    )]
+    InvalidColumnNames {
+        /// The dotted paths of the invalid column names.
+        names: Vec<String>,
+    },
+    /// Error returned when data files are written to a table with column mapping, which requires
+    /// the parquet columns to be named by their physical names.
+    #[error(
+        "Writing data files to tables with column mapping mode '{}' is not supported.",
+        .mode
+    )]
+    ColumnMappingNotSupported {
+        /// The column mapping mode of the table.
+        mode: String,
+    },
+    /// Error returned when a column name does not match any column of the table schema.
+    #[error("Column {} not found in the table schema.", .name)]
+    ColumnNotFound {
+        /// The column name used.
+        name: String,
+    },
+    /// Error returned when a column name matches several columns that differ only by case.
+    #[error("Column {} is ambiguous, it could be any of {:?}.", .name, .candidates)]
+    AmbiguousColumn {
+        /// The column name used.
+        name: String,
+        /// The columns of the table matching the name.
+        candidates: Vec<String>,
+    },
     /// Error returned when Vacuum retention period is below the safe threshold
     #[error(
         "Invalid retention period, retention for Vacuum must be greater than 1 week (168 hours)"
     )]
     InvalidVacuumRetentionPeriod,
+    /// Error returned when time travelling to a timestamp before the earliest commit still in
+    /// the log.
+    #[error(
+        "No version of the table exists at {}, the earliest available version {} was committed at {}.",
+        .timestamp.to_rfc3339(),
+        .earliest_version,
+        .earliest_timestamp.to_rfc3339()
+    )]
+    TimestampBeforeFirstCommit {
+        /// The requested timestamp.
+        timestamp: DateTime<Utc>,
+        /// The earliest version whose commit is in the log.
+        earliest_version: DeltaDataTypeVersion,
+        /// The time the earliest version was committed at.
+        earliest_timestamp: DateTime<Utc>,
+    },
+    /// Error returned when the commit of a version was already removed from the log, e.g. by the
+    /// cleanup of expired log files.
+    #[error(
+        "Commit of version {} is no longer in the log, the earliest available version is {}",
+        .version,
+        .earliest_version
+    )]
+    VersionNotInLog {
+        /// The requested version.
+        version: DeltaDataTypeVersion,
+        /// The earliest version whose commit is in the log.
+        earliest_version: DeltaDataTypeVersion,
+    },
     /// Error returned when transaction is failed to be committed because given version already exists.
     #[error("Delta transaction failed, version {0} already exists.")]
     VersionAlreadyExists(DeltaDataTypeVersion),
+    /// Error returned when a post-commit hook fails and strict post-commit hooks are enabled.
+    /// The commit itself succeeded.
+    #[error("Post-commit hook failed after committing version {}: {}", .version, .msg)]
+    PostCommitHookFailed {
+        /// The committed version
+        version: DeltaDataTypeVersion,
+        /// Error message of the hook
+        msg: String,
+    },
+    /// Error returned when an operation was cancelled through its cancellation token. Nothing
+    /// was committed.
+    #[error("The operation was cancelled")]
+    Cancelled,
+    /// Error returned when a table property has a malformed value.
+    #[error("Invalid table property: {}", .source)]
+    Config {
+        /// The source DeltaConfigError.
+        #[from]
+        source: DeltaConfigError,
+    },
+    /// Error returned when a commit removes data from a table with `delta.appendOnly` set.
+    #[error("Cannot remove data from the append-only table {0}")]
+    AppendOnly(String),
     /// Generic Delta Table error
     #[error("Generic DeltaTable error: {0}")]
     Generic(String),
@@ -268,6 +466,63 @@ impl fmt::Display for DeltaTableMetaData {
     }
 }
 
+/// The details of a table as shown by Spark's `DESCRIBE DETAIL`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaTableDetail {
+    /// Format of the table, always "delta"
+    pub format: String,
+    /// Unique identifier of the table
+    pub id: Guid,
+    /// User-provided name of the table
+    pub name: Option<String>,
+    /// User-provided description of the table
+    pub description: Option<String>,
+    /// URI of the table root
+    pub location: String,
+    /// Creation time of the table, in milliseconds since the Unix epoch
+    pub created_at: Option<DeltaDataTypeTimestamp>,
+    /// Time of the latest commit, in milliseconds since the Unix epoch
+    pub last_modified: Option<DeltaDataTypeTimestamp>,
+    /// Names of the partition columns
+    pub partition_columns: Vec<String>,
+    /// Number of files in the latest snapshot
+    pub num_files: usize,
+    /// Size of the files in the latest snapshot in bytes
+    pub size_in_bytes: DeltaDataTypeLong,
+    /// Table properties
+    pub properties: HashMap<String, Option<String>>,
+    /// Minimum version of the reader protocol
+    pub min_reader_version: i32,
+    /// Minimum version of the writer protocol
+    pub min_writer_version: i32,
+    /// Features needed to read the table, implied by the minimum reader version
+    pub reader_features: Vec<String>,
+    /// Features needed to write to the table, implied by the minimum writer version
+    pub writer_features: Vec<String>,
+}
+
+/// Table features enabled by each legacy reader protocol version
+const READER_VERSION_FEATURES: [(i32, &str); 1] = [(2, "columnMapping")];
+/// Table features enabled by each legacy writer protocol version
+const WRITER_VERSION_FEATURES: [(i32, &str); 7] = [
+    (2, "appendOnly"),
+    (2, "invariants"),
+    (3, "checkConstraints"),
+    (4, "changeDataFeed"),
+    (4, "generatedColumns"),
+    (5, "columnMapping"),
+    (6, "identityColumns"),
+];
+
+fn features_of_version(features: &[(i32, &str)], version: i32) -> Vec<String> {
+    features
+        .iter()
+        .filter(|(min_version, _)| *min_version <= version)
+        .map(|(_, feature)| feature.to_string())
+        .collect()
+}
+
 impl TryFrom<action::MetaData> for DeltaTableMetaData {
     type Error = serde_json::error::Error;
 
@@ -420,7 +675,7 @@ impl Default for DeltaVersion {
 }
 
 /// configuration options for delta table
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DeltaTableConfig {
     /// indicates whether our use case requires tracking tombstones.
     /// read-only applications never require tombstones. Tombstones
@@ -428,12 +683,25 @@ pub struct DeltaTableConfig {
     /// may want to skip them.
     /// defaults to true as a safe default.
     pub require_tombstones: bool,
+    /// indicates whether an unreadable `_last_checkpoint` or checkpoint file fails the load.
+    /// By default the table falls back to an earlier checkpoint or to replaying the log and
+    /// records a [`DeltaTableHealthWarning`].
+    pub strict_checkpoints: bool,
+    /// how column names given in partition filters and projections are matched against the
+    /// table schema. Defaults to case-insensitive matching like Spark.
+    pub column_resolution: ColumnResolution,
+    /// indicates whether committing a malformed value of a table property fails the commit
+    /// with [`DeltaTableError::Config`] instead of logging a warning. defaults to false.
+    pub strict_table_properties: bool,
 }
 
 impl Default for DeltaTableConfig {
     fn default() -> Self {
         Self {
             require_tombstones: true,
+            strict_checkpoints: false,
+            column_resolution: ColumnResolution::default(),
+            strict_table_properties: false,
         }
     }
 }
@@ -451,18 +719,32 @@ pub struct DeltaTableLoadOptions {
     /// may want to skip them.
     /// defaults to true as a safe default.
     pub require_tombstones: bool,
+    /// fail the load on an unreadable checkpoint instead of falling back to replaying the log.
+    /// defaults to false.
+    pub strict_checkpoints: bool,
+    /// policy for matching column names against the table schema.
+    /// defaults to case-insensitive.
+    pub column_resolution: ColumnResolution,
+    /// fail commits setting malformed table properties instead of logging a warning.
+    /// defaults to false.
+    pub strict_table_properties: bool,
     /// specify the version we are going to load: a time stamp, a version, or just the newest
     /// available version
     pub version: DeltaVersion,
 }
 
 impl DeltaTableLoadOptions {
-    /// create default table load options for a table uri
+    /// create default table load options for a table uri. The uri is normalized with
+    /// [storage::normalize_table_uri] so that all spellings of a location load the same table.
     pub fn new(table_uri: &str) -> Result<Self, DeltaTableError> {
+        let table_uri = storage::normalize_table_uri(table_uri)?;
         Ok(Self {
-            table_uri: table_uri.to_string(),
-            storage_backend: storage::get_backend_for_uri(table_uri)?,
+            storage_backend: storage::get_backend_for_uri(&table_uri)?,
+            table_uri,
             require_tombstones: true,
+            strict_checkpoints: false,
+            column_resolution: ColumnResolution::default(),
+            strict_table_properties: false,
             version: DeltaVersion::default(),
         })
     }
@@ -488,6 +770,27 @@ impl DeltaTableBuilder {
         self
     }
 
+    /// fail the load when `_last_checkpoint` or a checkpoint file cannot be read, instead of
+    /// falling back to an earlier checkpoint or a full log replay.
+    pub fn with_strict_checkpoints(mut self) -> Self {
+        self.options.strict_checkpoints = true;
+        self
+    }
+
+    /// set the policy for matching column names in partition filters and projections against
+    /// the table schema.
+    pub fn with_column_resolution(mut self, column_resolution: ColumnResolution) -> Self {
+        self.options.column_resolution = column_resolution;
+        self
+    }
+
+    /// fail commits that set a malformed value of a table property, instead of logging a
+    /// warning and using the default of the property.
+    pub fn with_strict_table_properties(mut self) -> Self {
+        self.options.strict_table_properties = true;
+        self
+    }
+
     /// TODO
     pub fn with_version(mut self, version: DeltaDataTypeVersion) -> Self {
         self.options.version = DeltaVersion::Version(version);
@@ -517,6 +820,9 @@ impl DeltaTableBuilder {
     pub async fn load(self) -> Result<DeltaTable, DeltaTableError> {
         let config = DeltaTableConfig {
             require_tombstones: self.options.require_tombstones,
+            strict_checkpoints: self.options.strict_checkpoints,
+            column_resolution: self.options.column_resolution,
+            strict_table_properties: self.options.strict_table_properties,
         };
 
         let mut table = DeltaTable::new(
@@ -535,7 +841,83 @@ impl DeltaTableBuilder {
     }
 }
 
-/// In memory representation of a Delta Table
+/// The actions of a single commit, as returned by [`DeltaTable::get_actions_between`].
+#[derive(Debug, Clone)]
+pub struct CommitActions {
+    /// The version of the commit.
+    pub version: DeltaDataTypeVersion,
+    /// The actions in the order they appear in the commit file.
+    pub actions: Vec<Action>,
+}
+
+/// Table state loaded from the transaction log that has not been installed into a
+/// [`DeltaTable`] yet.
+///
+/// Updates are prepared through a shared reference, so the table can still be read while the log
+/// is being loaded, and are installed in a single step with [`DeltaTable::apply_update`].
+#[derive(Debug)]
+pub struct DeltaTableUpdate {
+    version: DeltaDataTypeVersion,
+    last_check_point: Option<CheckPoint>,
+    kind: DeltaTableUpdateKind,
+    warnings: Vec<DeltaTableHealthWarning>,
+    // modification times of commit files looked up while preparing the update
+    version_timestamps: HashMap<DeltaDataTypeVersion, i64>,
+}
+
+#[derive(Debug)]
+enum DeltaTableUpdateKind {
+    /// The current state with the commits following `base_version` applied, None if there were
+    /// no new commits.
+    Incremental {
+        base_version: DeltaDataTypeVersion,
+        state: Option<DeltaTableState>,
+    },
+    /// A complete state replacing the current state.
+    Snapshot(DeltaTableState),
+}
+
+impl DeltaTableUpdate {
+    /// The version the table is at once the update is applied.
+    pub fn version(&self) -> DeltaDataTypeVersion {
+        self.version
+    }
+}
+
+/// Problem with the transaction log that was detected and worked around while loading a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaTableHealthWarning {
+    /// The `_last_checkpoint` file could not be parsed. The latest checkpoint was looked up by
+    /// listing the log instead.
+    CorruptLastCheckpoint {
+        /// Description of the parse error.
+        error: String,
+    },
+    /// The checkpoint of the given version could not be read. The state was loaded from an
+    /// earlier checkpoint or by replaying the log from the first commit.
+    CorruptCheckpoint {
+        /// Version of the unreadable checkpoint.
+        version: DeltaDataTypeVersion,
+        /// Description of the read error.
+        error: String,
+    },
+}
+
+fn is_corrupt_checkpoint_error(err: &DeltaTableError) -> bool {
+    matches!(
+        err,
+        DeltaTableError::ParquetError { .. }
+            | DeltaTableError::InvalidAction { .. }
+            | DeltaTableError::ApplyLog { .. }
+            | DeltaTableError::StorageError {
+                source: StorageError::NotFound
+            }
+    )
+}
+
+/// In memory representation of a Delta Table. A clone copies the loaded state and shares the
+/// storage backend, e.g. to commit without borrowing the original table mutably.
+#[derive(Clone)]
 pub struct DeltaTable {
     /// The version of the table as of the most recent loaded Delta log entry.
     pub version: DeltaDataTypeVersion,
@@ -548,11 +930,13 @@ pub struct DeltaTable {
 
     // metadata
     // application_transactions
-    pub(crate) storage: Box<dyn StorageBackend>,
+    pub(crate) storage: Arc<dyn StorageBackend>,
 
     last_check_point: Option<CheckPoint>,
     log_uri: String,
+    /// The modification times of commit files in milliseconds since the epoch.
     version_timestamp: HashMap<DeltaDataTypeVersion, i64>,
+    health_warnings: Vec<DeltaTableHealthWarning>,
 }
 
 impl DeltaTable {
@@ -596,81 +980,68 @@ impl DeltaTable {
         Ok(serde_json::from_slice(&data)?)
     }
 
-    async fn find_latest_check_point_for_version(
+    pub(crate) async fn find_latest_check_point_for_version(
         &self,
         version: DeltaDataTypeVersion,
     ) -> Result<Option<CheckPoint>, DeltaTableError> {
-        lazy_static! {
-            static ref CHECKPOINT_REGEX: Regex =
-                Regex::new(r#"^*[/\\]_delta_log[/\\](\d{20})\.checkpoint\.parquet$"#).unwrap();
-            static ref CHECKPOINT_PARTS_REGEX: Regex = Regex::new(
-                r#"^*[/\\]_delta_log[/\\](\d{20})\.checkpoint\.\d{10}\.(\d{10})\.parquet$"#
-            )
-            .unwrap();
-        }
-
         let mut cp: Option<CheckPoint> = None;
         let mut stream = self.storage.list_objs(&self.log_uri).await?;
 
         while let Some(obj_meta) = stream.next().await {
             // Exit early if any objects can't be listed.
             let obj_meta = obj_meta?;
-            if let Some(captures) = CHECKPOINT_REGEX.captures(&obj_meta.path) {
-                let curr_ver_str = captures.get(1).unwrap().as_str();
-                let curr_ver: DeltaDataTypeVersion = curr_ver_str.parse().unwrap();
-                if curr_ver > version {
-                    // skip checkpoints newer than max version
-                    continue;
-                }
-                if cp.is_none() || curr_ver > cp.unwrap().version {
-                    cp = Some(CheckPoint {
-                        version: curr_ver,
-                        size: 0,
-                        parts: None,
-                    });
-                }
+            let (curr_ver, parts) = match DeltaLogFile::from_path(&obj_meta.path) {
+                Some(DeltaLogFile::Checkpoint(v)) => (v, None),
+                Some(DeltaLogFile::CheckpointPart { version, parts, .. }) => (version, Some(parts)),
+                _ => continue,
+            };
+            if curr_ver > version {
+                // skip checkpoints newer than max version
                 continue;
             }
-
-            if let Some(captures) = CHECKPOINT_PARTS_REGEX.captures(&obj_meta.path) {
-                let curr_ver_str = captures.get(1).unwrap().as_str();
-                let curr_ver: DeltaDataTypeVersion = curr_ver_str.parse().unwrap();
-                if curr_ver > version {
-                    // skip checkpoints newer than max version
-                    continue;
-                }
-                if cp.is_none() || curr_ver > cp.unwrap().version {
-                    let parts_str = captures.get(2).unwrap().as_str();
-                    let parts = parts_str.parse().unwrap();
-                    cp = Some(CheckPoint {
-                        version: curr_ver,
-                        size: 0,
-                        parts: Some(parts),
-                    });
-                }
-                continue;
+            if cp.is_none() || curr_ver > cp.unwrap().version {
+                cp = Some(CheckPoint {
+                    version: curr_ver,
+                    size: 0,
+                    parts,
+                });
             }
         }
 
         Ok(cp)
     }
 
-    async fn apply_log(&mut self, version: DeltaDataTypeVersion) -> Result<(), ApplyLogError> {
-        let new_state = DeltaTableState::from_commit(self, version).await?;
-        self.state.merge(new_state, self.config.require_tombstones);
-
-        Ok(())
-    }
-
-    async fn restore_checkpoint(&mut self, check_point: CheckPoint) -> Result<(), DeltaTableError> {
-        self.state =
-            DeltaTableState::from_checkpoint(self, &check_point, self.config.require_tombstones)
-                .await?;
-
-        Ok(())
+    /// Applies the commits from `version` up to the end of the log, or up to `max_version`, to
+    /// `state` one at a time, so that a file removed and added again by later commits is only
+    /// kept once. A borrowed state is only cloned once there is a commit to apply. Returns the
+    /// last version applied.
+    async fn apply_commits_from(
+        &self,
+        state: &mut Cow<'_, DeltaTableState>,
+        mut version: DeltaDataTypeVersion,
+        max_version: DeltaDataTypeVersion,
+    ) -> Result<DeltaDataTypeVersion, ApplyLogError> {
+        while version <= max_version {
+            match DeltaTableState::from_commit(self, version).await {
+                Ok(new_state) => {
+                    state
+                        .to_mut()
+                        .merge(new_state, self.config.require_tombstones);
+                    version += 1;
+                }
+                Err(ApplyLogError::EndOfLog) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(version - 1)
     }
 
-    async fn get_latest_version(&mut self) -> Result<DeltaDataTypeVersion, DeltaTableError> {
+    /// Returns the latest version in the log. The modification times of the commit files are
+    /// added to `timestamps`.
+    async fn get_latest_version(
+        &self,
+        timestamps: &mut HashMap<DeltaDataTypeVersion, i64>,
+    ) -> Result<DeltaDataTypeVersion, DeltaTableError> {
         let mut version = match self.get_last_checkpoint().await {
             Ok(last_check_point) => last_check_point.version + 1,
             Err(LoadCheckpointError::NotFound) => {
@@ -691,8 +1062,7 @@ impl DeltaTable {
             {
                 Ok(meta) => {
                     // also cache timestamp for version
-                    self.version_timestamp
-                        .insert(version, meta.modified.timestamp());
+                    timestamps.insert(version, meta.modified.timestamp_millis());
                     version += 1;
                 }
                 Err(e) => {
@@ -728,52 +1098,28 @@ impl DeltaTable {
     /// Updates the DeltaTable to the most recent state committed to the transaction log by
     /// loading the last checkpoint and incrementally applying each version since.
     pub async fn update(&mut self) -> Result<(), DeltaTableError> {
-        match self.get_last_checkpoint().await {
-            Ok(last_check_point) => {
-                if Some(last_check_point) == self.last_check_point {
-                    self.update_incremental().await
-                } else {
-                    self.last_check_point = Some(last_check_point);
-                    self.restore_checkpoint(last_check_point).await?;
-                    self.version = last_check_point.version;
-                    self.update_incremental().await
-                }
-            }
-            Err(LoadCheckpointError::NotFound) => self.update_incremental().await,
-            Err(e) => Err(DeltaTableError::LoadCheckpoint { source: e }),
-        }
+        let update = self.prepare_update().await?;
+        self.apply_update(update)
     }
 
     /// Updates the DeltaTable to the latest version by incrementally applying newer versions.
     /// It assumes that the table is already updated to the current version `self.version`.
     pub async fn update_incremental(&mut self) -> Result<(), DeltaTableError> {
-        self.version += 1;
+        self.update_incremental_to(None).await?;
+        Ok(())
+    }
 
-        loop {
-            match self.apply_log(self.version).await {
-                Ok(_) => {
-                    self.version += 1;
-                }
-                Err(e) => {
-                    match e {
-                        ApplyLogError::EndOfLog => {
-                            self.version -= 1;
-                            if self.version == -1 {
-                                let err = format!(
-                                    "No snapshot or version 0 found, perhaps {} is an empty dir?",
-                                    self.table_uri
-                                );
-                                return Err(DeltaTableError::NotATable(err));
-                            }
-                        }
-                        _ => {
-                            return Err(DeltaTableError::from(e));
-                        }
-                    }
-                    return Ok(());
-                }
-            }
-        }
+    /// Updates the DeltaTable like [`DeltaTable::update_incremental`], but to at most
+    /// `max_version`, so that versions can be processed one at a time. Returns the number of
+    /// versions applied.
+    pub async fn update_incremental_to(
+        &mut self,
+        max_version: Option<DeltaDataTypeVersion>,
+    ) -> Result<DeltaDataTypeVersion, DeltaTableError> {
+        let base_version = self.version;
+        let update = self.prepare_update_incremental_to(max_version).await?;
+        self.apply_update(update)?;
+        Ok(self.version - base_version)
     }
 
     /// Loads the DeltaTable state for the given version.
@@ -781,6 +1127,223 @@ impl DeltaTable {
         &mut self,
         version: DeltaDataTypeVersion,
     ) -> Result<(), DeltaTableError> {
+        let update = self.prepare_load_version(version).await?;
+        self.apply_update(update)
+    }
+
+    /// Loads the most recent state like [`DeltaTable::update`] does, without modifying the table.
+    /// The returned update is installed with [`DeltaTable::apply_update`].
+    pub async fn prepare_update(&self) -> Result<DeltaTableUpdate, DeltaTableError> {
+        let mut warnings = Vec::new();
+        let last_check_point = match self.get_last_checkpoint().await {
+            Ok(last_check_point) => Some(last_check_point),
+            Err(LoadCheckpointError::NotFound) => None,
+            Err(LoadCheckpointError::InvalidJson { source }) if !self.config.strict_checkpoints => {
+                warn!(
+                    "Failed to parse _last_checkpoint of {}, listing the log instead: {}",
+                    self.table_uri, source
+                );
+                warnings.push(DeltaTableHealthWarning::CorruptLastCheckpoint {
+                    error: source.to_string(),
+                });
+                if self.state.current_metadata().is_some() {
+                    // a state is loaded already, so newer commits can simply be applied to it.
+                    let mut update = self.prepare_update_incremental().await?;
+                    update.warnings = warnings;
+                    return Ok(update);
+                }
+                self.find_latest_check_point_for_version(DeltaDataTypeVersion::MAX)
+                    .await?
+            }
+            Err(e) => return Err(DeltaTableError::LoadCheckpoint { source: e }),
+        };
+
+        match last_check_point {
+            Some(last_check_point) if Some(last_check_point) != self.last_check_point => {
+                let (state, state_version) = self
+                    .load_checkpoint_with_fallback(Some(last_check_point), &mut warnings)
+                    .await?;
+                let mut state = Cow::Owned(state);
+                let version = self
+                    .apply_commits_from(&mut state, state_version + 1, DeltaDataTypeVersion::MAX)
+                    .await?;
+                if version == -1 {
+                    let err = format!(
+                        "No snapshot or version 0 found, perhaps {} is an empty dir?",
+                        self.table_uri
+                    );
+                    return Err(DeltaTableError::NotATable(err));
+                }
+
+                Ok(DeltaTableUpdate {
+                    version,
+                    last_check_point: Some(last_check_point),
+                    kind: DeltaTableUpdateKind::Snapshot(state.into_owned()),
+                    warnings,
+                    version_timestamps: HashMap::new(),
+                })
+            }
+            _ => {
+                let mut update = self.prepare_update_incremental().await?;
+                update.warnings = warnings;
+                Ok(update)
+            }
+        }
+    }
+
+    /// Loads the state of `check_point`. If the checkpoint cannot be read, earlier checkpoints
+    /// are tried and finally an empty state is returned to replay the log from the first commit,
+    /// unless `strict_checkpoints` is set. Returns the state together with its version.
+    async fn load_checkpoint_with_fallback(
+        &self,
+        check_point: Option<CheckPoint>,
+        warnings: &mut Vec<DeltaTableHealthWarning>,
+    ) -> Result<(DeltaTableState, DeltaDataTypeVersion), DeltaTableError> {
+        let mut candidate = check_point;
+        while let Some(check_point) = candidate {
+            match DeltaTableState::from_checkpoint(
+                self,
+                &check_point,
+                self.config.require_tombstones,
+            )
+            .await
+            {
+                Ok(state) => return Ok((state, check_point.version)),
+                Err(e) if !self.config.strict_checkpoints && is_corrupt_checkpoint_error(&e) => {
+                    warn!(
+                        "Failed to read checkpoint {} of {}, falling back to an earlier state: {}",
+                        check_point.version, self.table_uri, e
+                    );
+                    warnings.push(DeltaTableHealthWarning::CorruptCheckpoint {
+                        version: check_point.version,
+                        error: e.to_string(),
+                    });
+                    candidate = if check_point.version > 0 {
+                        self.find_latest_check_point_for_version(check_point.version - 1)
+                            .await?
+                    } else {
+                        None
+                    };
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok((DeltaTableState::default(), -1))
+    }
+
+    /// Loads all versions newer than `self.version` like [`DeltaTable::update_incremental`] does,
+    /// without modifying the table. The returned update is installed with
+    /// [`DeltaTable::apply_update`].
+    pub async fn prepare_update_incremental(&self) -> Result<DeltaTableUpdate, DeltaTableError> {
+        self.prepare_update_incremental_to(None).await
+    }
+
+    /// Loads the versions newer than `self.version` up to `max_version` like
+    /// [`DeltaTable::update_incremental_to`] does, without modifying the table.
+    ///
+    /// Only the new commit files are read. If the commit following `self.version` was cleaned
+    /// up after a newer checkpoint was written, the state is loaded from that checkpoint instead.
+    pub async fn prepare_update_incremental_to(
+        &self,
+        max_version: Option<DeltaDataTypeVersion>,
+    ) -> Result<DeltaTableUpdate, DeltaTableError> {
+        let max_version = max_version.unwrap_or(DeltaDataTypeVersion::MAX);
+        debug!(
+            "Reading commits of {} from version {}.",
+            self.table_uri,
+            self.version + 1
+        );
+        let mut state = Cow::Borrowed(&self.state);
+        let version = self
+            .apply_commits_from(&mut state, self.version + 1, max_version)
+            .await?;
+        if version == -1 {
+            let err = format!(
+                "No snapshot or version 0 found, perhaps {} is an empty dir?",
+                self.table_uri
+            );
+            return Err(DeltaTableError::NotATable(err));
+        }
+        if version == self.version && version < max_version {
+            if let Some(update) = self
+                .prepare_update_from_newer_checkpoint(max_version)
+                .await?
+            {
+                return Ok(update);
+            }
+        }
+
+        Ok(DeltaTableUpdate {
+            version,
+            last_check_point: None,
+            kind: DeltaTableUpdateKind::Incremental {
+                base_version: self.version,
+                state: match state {
+                    Cow::Owned(state) => Some(state),
+                    Cow::Borrowed(_) => None,
+                },
+            },
+            warnings: Vec::new(),
+            version_timestamps: HashMap::new(),
+        })
+    }
+
+    /// Loads the state from a checkpoint newer than `self.version` and not newer than
+    /// `max_version`, for when the commit following `self.version` is missing. Returns None if
+    /// there is no such checkpoint, then the table simply has no newer versions.
+    async fn prepare_update_from_newer_checkpoint(
+        &self,
+        max_version: DeltaDataTypeVersion,
+    ) -> Result<Option<DeltaTableUpdate>, DeltaTableError> {
+        // reading _last_checkpoint is cheap, the log is only listed if it is past max_version
+        let mut check_point = match self.get_last_checkpoint().await {
+            Ok(check_point) if check_point.version > self.version => check_point,
+            _ => return Ok(None),
+        };
+        if check_point.version > max_version {
+            check_point = match self
+                .find_latest_check_point_for_version(max_version)
+                .await?
+            {
+                Some(check_point) if check_point.version > self.version => check_point,
+                _ => return Err(DeltaTableError::InvalidVersion(self.version + 1)),
+            };
+        }
+        debug!(
+            "Commit {} of {} is missing, loading checkpoint {} instead.",
+            self.version + 1,
+            self.table_uri,
+            check_point.version
+        );
+
+        let mut warnings = Vec::new();
+        let (state, state_version) = self
+            .load_checkpoint_with_fallback(Some(check_point), &mut warnings)
+            .await?;
+        if state_version <= self.version {
+            return Err(DeltaTableError::InvalidVersion(self.version + 1));
+        }
+        let mut state = Cow::Owned(state);
+        let version = self
+            .apply_commits_from(&mut state, state_version + 1, max_version)
+            .await?;
+
+        Ok(Some(DeltaTableUpdate {
+            version: version.max(state_version),
+            last_check_point: Some(check_point),
+            kind: DeltaTableUpdateKind::Snapshot(state.into_owned()),
+            warnings,
+            version_timestamps: HashMap::new(),
+        }))
+    }
+
+    /// Loads the state of the given version like [`DeltaTable::load_version`] does, without
+    /// modifying the table. The returned update is installed with [`DeltaTable::apply_update`].
+    pub async fn prepare_load_version(
+        &self,
+        version: DeltaDataTypeVersion,
+    ) -> Result<DeltaTableUpdate, DeltaTableError> {
         // check if version is valid
         let commit_uri = self.commit_uri_from_version(version);
         match self.storage.head_obj(&commit_uri).await {
@@ -792,45 +1355,87 @@ impl DeltaTable {
                 return Err(DeltaTableError::from(e));
             }
         }
-        self.version = version;
 
-        let mut next_version;
         // 1. find latest checkpoint below version
-        match self.find_latest_check_point_for_version(version).await? {
-            Some(check_point) => {
-                self.restore_checkpoint(check_point).await?;
-                next_version = check_point.version + 1;
-            }
-            None => {
-                // no checkpoint found, clear table state and start from the beginning
-                self.state = DeltaTableState::default();
-                next_version = 0;
-            }
-        }
+        let mut warnings = Vec::new();
+        let check_point = self.find_latest_check_point_for_version(version).await?;
+        let (mut state, state_version) = self
+            .load_checkpoint_with_fallback(check_point, &mut warnings)
+            .await?;
+        let mut next_version = state_version + 1;
 
         // 2. apply all logs starting from checkpoint
-        while next_version <= self.version {
-            self.apply_log(next_version).await?;
+        while next_version <= version {
+            let new_state = DeltaTableState::from_commit(self, next_version).await?;
+            state.merge(new_state, self.config.require_tombstones);
             next_version += 1;
         }
 
+        Ok(DeltaTableUpdate {
+            version,
+            last_check_point: None,
+            kind: DeltaTableUpdateKind::Snapshot(state),
+            warnings,
+            version_timestamps: HashMap::new(),
+        })
+    }
+
+    /// Installs an update loaded by one of the `prepare_*` methods. The version and the state of
+    /// the table are replaced together, so the table is never observed with a new version but a
+    /// partially applied state.
+    pub fn apply_update(&mut self, update: DeltaTableUpdate) -> Result<(), DeltaTableError> {
+        match update.kind {
+            DeltaTableUpdateKind::Incremental {
+                base_version,
+                state,
+            } => {
+                if base_version != self.version {
+                    return Err(DeltaTableError::Generic(format!(
+                        "Cannot apply update prepared at version {} to table at version {}",
+                        base_version, self.version
+                    )));
+                }
+                if let Some(state) = state {
+                    self.state = state;
+                }
+            }
+            DeltaTableUpdateKind::Snapshot(state) => self.state = state,
+        }
+        if update.last_check_point.is_some() {
+            self.last_check_point = update.last_check_point;
+        }
+        for warning in update.warnings {
+            if !self.health_warnings.contains(&warning) {
+                self.health_warnings.push(warning);
+            }
+        }
+        self.version_timestamp.extend(update.version_timestamps);
+        self.version = update.version;
+
         Ok(())
     }
 
+    /// Returns the modification time of the commit file of `version`, looked up in the timestamps
+    /// cached by the table or `timestamps` first. New lookups are added to `timestamps`.
     async fn get_version_timestamp(
-        &mut self,
+        &self,
         version: DeltaDataTypeVersion,
+        timestamps: &mut HashMap<DeltaDataTypeVersion, i64>,
     ) -> Result<i64, DeltaTableError> {
-        match self.version_timestamp.get(&version) {
+        match self
+            .version_timestamp
+            .get(&version)
+            .or_else(|| timestamps.get(&version))
+        {
             Some(ts) => Ok(*ts),
             None => {
                 let meta = self
                     .storage
                     .head_obj(&self.commit_uri_from_version(version))
                     .await?;
-                let ts = meta.modified.timestamp();
+                let ts = meta.modified.timestamp_millis();
                 // also cache timestamp for version
-                self.version_timestamp.insert(version, ts);
+                timestamps.insert(version, ts);
 
                 Ok(ts)
             }
@@ -838,9 +1443,9 @@ impl DeltaTable {
     }
 
     /// Returns provenance information, including the operation, user, and so on, for each write to a table.
-    /// The table history retention is based on the `logRetentionDuration` property of the Delta Table, 30 days by default.
+    /// The table history retention is based on the `delta.logRetentionDuration` property of the Delta Table, 30 days by default.
     pub fn history(
-        &mut self,
+        &self,
         limit: Option<usize>,
     ) -> Result<Vec<Map<String, Value>>, DeltaTableError> {
         let commit_infos_list = self.state.commit_infos().iter().rev().map(Map::clone);
@@ -850,6 +1455,48 @@ impl DeltaTable {
         }
     }
 
+    /// Reads the commits from `start_version` up to and including `end_version` and returns
+    /// their actions, so that the files added and removed by each version can be processed
+    /// without loading the table state of those versions.
+    ///
+    /// Returns [`DeltaTableError::VersionNotInLog`] if a commit in the range was already cleaned
+    /// up and [`DeltaTableError::InvalidVersion`] if `end_version` does not exist yet.
+    pub async fn get_actions_between(
+        &self,
+        start_version: DeltaDataTypeVersion,
+        end_version: DeltaDataTypeVersion,
+    ) -> Result<Vec<CommitActions>, DeltaTableError> {
+        if start_version < 0 {
+            return Err(DeltaTableError::InvalidVersion(start_version));
+        }
+        let mut commits = Vec::new();
+        for version in start_version..=end_version {
+            let commit_uri = self.commit_uri_from_version(version);
+            let commit_log_bytes = match self.storage.get_obj(&commit_uri).await {
+                Ok(bytes) => bytes,
+                Err(StorageError::NotFound) => {
+                    let (earliest_version, _) = self.find_earliest_commit().await?;
+                    return Err(if version < earliest_version {
+                        DeltaTableError::VersionNotInLog {
+                            version,
+                            earliest_version,
+                        }
+                    } else {
+                        DeltaTableError::InvalidVersion(version)
+                    });
+                }
+                Err(e) => return Err(DeltaTableError::from(e)),
+            };
+            let actions = commit_log_bytes
+                .split(|byte| *byte == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(serde_json::from_slice)
+                .collect::<Result<_, _>>()?;
+            commits.push(CommitActions { version, actions });
+        }
+        Ok(commits)
+    }
+
     /// Returns the file list tracked in current table state filtered by provided
     /// `PartitionFilter`s.
     pub fn get_files_by_partitions(
@@ -860,12 +1507,24 @@ impl DeltaTable {
             .state
             .current_metadata()
             .ok_or(DeltaTableError::NoMetadata)?;
-        if !filters
-            .iter()
-            .all(|f| current_metadata.partition_columns.contains(&f.key.into()))
-        {
-            return Err(DeltaTableError::InvalidPartitionFilter {
-                partition_filter: format!("{:?}", filters),
+        let mut resolved_filters = Vec::with_capacity(filters.len());
+        for filter in filters {
+            let key = self
+                .config
+                .column_resolution
+                .resolve(
+                    filter.key,
+                    current_metadata
+                        .partition_columns
+                        .iter()
+                        .map(String::as_str),
+                )?
+                .ok_or_else(|| DeltaTableError::InvalidPartitionFilter {
+                    partition_filter: format!("{:?}", filters),
+                })?;
+            resolved_filters.push(PartitionFilter {
+                key,
+                value: filter.value.clone(),
             });
         }
 
@@ -884,7 +1543,7 @@ impl DeltaTable {
                     .iter()
                     .map(|p| DeltaTablePartition::from_partition_value(p, ""))
                     .collect::<Vec<DeltaTablePartition>>();
-                filters
+                resolved_filters
                     .iter()
                     .all(|filter| filter.match_partitions(&partitions, &partition_col_data_types))
             })
@@ -918,6 +1577,49 @@ impl DeltaTable {
             .collect())
     }
 
+    /// Returns the paths of the files that may contain rows matching the predicate, according
+    /// to their partition values and the min/max values and null counts of their statistics.
+    /// Files without statistics for a column of the predicate are kept. Column names are matched
+    /// according to the table's [`ColumnResolution`] policy.
+    pub fn get_files_by_predicate(
+        &self,
+        predicate: &Predicate,
+    ) -> Result<Vec<String>, DeltaTableError> {
+        Ok(predicate
+            .prune(&self.state, self.config.column_resolution)?
+            .into_iter()
+            .map(|add| add.path.clone())
+            .collect())
+    }
+
+    /// Returns the URIs of the files that may contain rows matching the predicate, see
+    /// [`DeltaTable::get_files_by_predicate`].
+    pub fn get_file_uris_by_predicate(
+        &self,
+        predicate: &Predicate,
+    ) -> Result<Vec<String>, DeltaTableError> {
+        let files = self.get_files_by_predicate(predicate)?;
+        Ok(files
+            .iter()
+            .map(|fname| self.storage.join_path(&self.table_uri, fname))
+            .collect())
+    }
+
+    /// Returns the predicate as JSON, with its columns resolved according to the table's
+    /// [`ColumnResolution`] policy and its literals converted to the types of the columns, for
+    /// engines filtering the rows of the table. Predicates are objects with an `"op"`:
+    /// `"and"`, `"or"` and `"not"` have their operands in `"args"`, comparisons (`"="`, `"!="`,
+    /// `"<"`, `"<="`, `">"`, `">="`) have a `"column"` path and a `"value"`, `"in"` and
+    /// `"not in"` a `"column"` and `"values"`, `"is null"` and `"is not null"` a `"column"`.
+    /// Numbers of integer and decimal columns are strings with all their digits, dates are days
+    /// and timestamps microseconds since the epoch.
+    pub fn resolve_predicate(
+        &self,
+        predicate: &Predicate,
+    ) -> Result<serde_json::Value, DeltaTableError> {
+        predicate.to_resolved_json(&self.state, self.config.column_resolution)
+    }
+
     /// Return a refernece to all active "add" actions present in the loaded state
     pub fn get_active_add_actions(&self) -> &Vec<action::Add> {
         self.state.files()
@@ -971,6 +1673,12 @@ impl DeltaTable {
             .collect()
     }
 
+    /// Returns the problems with the transaction log that were worked around while loading the
+    /// table, e.g. unreadable checkpoints.
+    pub fn get_health_warnings(&self) -> &[DeltaTableHealthWarning] {
+        &self.health_warnings
+    }
+
     /// Returns the currently loaded state snapshot.
     pub fn get_state(&self) -> &DeltaTableState {
         &self.state
@@ -1005,16 +1713,61 @@ impl DeltaTable {
         self.state.min_writer_version()
     }
 
+    /// Returns the details of the loaded state like Spark's `DESCRIBE DETAIL`. The last
+    /// modification time is the time of the current version's commit file if it was listed
+    /// while loading and the timestamp of the latest commitInfo otherwise.
+    pub fn get_detail(&self) -> Result<DeltaTableDetail, DeltaTableError> {
+        let metadata = self.get_metadata()?;
+        let last_modified = self
+            .version_timestamp
+            .get(&self.version)
+            .copied()
+            .or_else(|| {
+                self.state
+                    .commit_infos()
+                    .last()
+                    .and_then(|commit_info| commit_info.get("timestamp"))
+                    .and_then(Value::as_i64)
+            });
+        let files = self.state.files();
+
+        Ok(DeltaTableDetail {
+            format: "delta".to_string(),
+            id: metadata.id.clone(),
+            name: metadata.name.clone(),
+            description: metadata.description.clone(),
+            location: self.table_uri.clone(),
+            created_at: metadata.created_time,
+            last_modified,
+            partition_columns: metadata.partition_columns.clone(),
+            num_files: files.len(),
+            size_in_bytes: files.iter().map(|add| add.size).sum(),
+            properties: metadata.configuration.clone(),
+            min_reader_version: self.get_min_reader_version(),
+            min_writer_version: self.get_min_writer_version(),
+            reader_features: features_of_version(
+                &READER_VERSION_FEATURES,
+                self.get_min_reader_version(),
+            ),
+            writer_features: features_of_version(
+                &WRITER_VERSION_FEATURES,
+                self.get_min_writer_version(),
+            ),
+        })
+    }
+
     /// List files no longer referenced by a Delta table and are older than the retention threshold.
     fn get_stale_files(
         &self,
         retention_hours: Option<u64>,
+        enforce_retention_duration: bool,
     ) -> Result<HashSet<&str>, DeltaTableError> {
         let retention_millis = retention_hours
             .map(|hours| 3600000 * hours as i64)
             .unwrap_or_else(|| self.state.tombstone_retention_millis());
 
-        if retention_millis < self.state.tombstone_retention_millis() {
+        if enforce_retention_duration && retention_millis < self.state.tombstone_retention_millis()
+        {
             return Err(DeltaTableError::InvalidVacuumRetentionPeriod);
         }
 
@@ -1037,10 +1790,19 @@ impl DeltaTable {
     /// Names of the form partitionCol=[value] are partition directories, and should be
     /// deleted even if they'd normally be hidden. The _db_index directory contains (bloom filter)
     /// indexes and these must be deleted when the data they are tied to is deleted.
+    /// The log, change data and manifest directories are never touched, even if a partition
+    /// column name is a prefix of theirs.
     fn is_hidden_directory(&self, path_name: &str) -> Result<bool, DeltaTableError> {
+        let is_protected = VACUUM_PROTECTED_DIRECTORIES.iter().any(|dir| {
+            path_name.strip_prefix(dir).map_or(false, |rest| {
+                rest.is_empty() || rest.starts_with(['/', '\\'])
+            })
+        });
+        if is_protected {
+            return Ok(true);
+        }
         Ok((path_name.starts_with('.') || path_name.starts_with('_'))
             && !path_name.starts_with("_delta_index")
-            && !path_name.starts_with("_change_data")
             && !self
                 .state
                 .current_metadata()
@@ -1055,17 +1817,50 @@ impl DeltaTable {
     /// and uncommitted files can still be in use by concurrent readers or writers to the table.
     /// If vacuum cleans up active files, concurrent readers can fail or, worse, tables can be
     /// corrupted when vacuum deletes files that have not yet been committed.
-    /// If `retention_hours` is not set then the `configuration.deletedFileRetentionDuration` of
+    /// If `retention_hours` is not set then the `delta.deletedFileRetentionDuration` of
     /// delta table is used or if that's missing too, then the default value of 7 days otherwise.
     pub async fn vacuum(
         &mut self,
         retention_hours: Option<u64>,
         dry_run: bool,
     ) -> Result<Vec<String>, DeltaTableError> {
-        let expired_tombstones = self.get_stale_files(retention_hours)?;
+        self.vacuum_with_control(retention_hours, dry_run, &OperationControl::default())
+            .await
+    }
+
+    /// Run the vacuum like [`DeltaTable::vacuum`], reporting the deleted files to the progress
+    /// callback of `control`, see [`DeltaTable::vacuum_with_options`].
+    pub async fn vacuum_with_control(
+        &mut self,
+        retention_hours: Option<u64>,
+        dry_run: bool,
+        control: &OperationControl,
+    ) -> Result<Vec<String>, DeltaTableError> {
+        let options = VacuumOptions {
+            retention_hours,
+            dry_run,
+            ..Default::default()
+        };
+        let metrics = self.vacuum_with_options(&options, control).await?;
+        Ok(metrics.files_deleted)
+    }
+
+    /// Run the vacuum like [`DeltaTable::vacuum`] with the given options and return what it
+    /// deleted. Up to `max_concurrent_deletes` files are deleted at once and each one is
+    /// reported to the progress callback of `control`. The cancellation token is checked before
+    /// each deletion, so a cancelled vacuum may have deleted some of the files.
+    pub async fn vacuum_with_options(
+        &self,
+        options: &VacuumOptions,
+        control: &OperationControl,
+    ) -> Result<VacuumMetrics, DeltaTableError> {
+        let expired_tombstones =
+            self.get_stale_files(options.retention_hours, options.enforce_retention_duration)?;
         let valid_files = self.get_file_set();
 
         let mut files_to_delete = vec![];
+        let mut bytes_to_delete = 0;
+        let mut referenced_files_skipped = 0;
         let mut all_files = self.storage.list_objs(&self.table_uri).await?;
 
         // TODO: table_path is currently only used in vacuum, consider precalcualte it during table
@@ -1079,28 +1874,109 @@ impl DeltaTable {
             // object key without `s3://` and bucket name.
             let rel_path = extract_rel_path(&table_path, &obj_meta.path)?;
 
-            if valid_files.contains(rel_path) // file is still being tracked in table
-                || !expired_tombstones.contains(rel_path) // file is not an expired tombstone
+            if valid_files.contains(rel_path) {
+                // file is still being tracked in table
+                referenced_files_skipped += 1;
+                continue;
+            }
+            if !expired_tombstones.contains(rel_path) // file is not an expired tombstone
                 || self.is_hidden_directory(rel_path)?
             {
                 continue;
             }
 
-            files_to_delete.push(obj_meta.path);
+            bytes_to_delete += obj_meta.size.unwrap_or(0);
+            files_to_delete.push((obj_meta.path, obj_meta.size.unwrap_or(0)));
         }
+        debug!(
+            "Vacuum found {} files to delete in {}.",
+            files_to_delete.len(),
+            self.table_uri
+        );
 
-        if dry_run {
-            return Ok(files_to_delete);
+        let metrics = VacuumMetrics {
+            files_deleted: files_to_delete
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect(),
+            bytes_reclaimed: bytes_to_delete,
+            referenced_files_skipped,
+        };
+        if options.dry_run {
+            return Ok(metrics);
         }
 
-        let paths = &files_to_delete
-            .iter()
-            .map(|rel_path| self.storage.join_path(&self.table_uri, rel_path))
-            .collect::<Vec<_>>();
-        match self.storage.delete_objs(paths).await {
-            Ok(_) => Ok(files_to_delete),
-            Err(err) => Err(DeltaTableError::StorageError { source: err }),
+        let total = files_to_delete.len();
+        let mut deletes = futures::stream::iter(files_to_delete.iter().map(|(path, size)| {
+            let uri = self.storage.join_path(&self.table_uri, path);
+            async move {
+                control.check_cancelled()?;
+                match self.storage.delete_obj(&uri).await {
+                    Ok(()) | Err(StorageError::NotFound) => Ok(*size),
+                    Err(source) => Err(DeltaTableError::StorageError { source }),
+                }
+            }
+        }))
+        .buffer_unordered(options.max_concurrent_deletes.max(1));
+        let mut deleted = 0;
+        let mut bytes_deleted = 0;
+        while let Some(size) = deletes.next().await {
+            bytes_deleted += size?;
+            deleted += 1;
+            control.report(ProgressEvent {
+                phase: ProgressPhase::DeleteFiles,
+                done: deleted,
+                total,
+                bytes: Some(bytes_deleted),
+            });
         }
+        info!(
+            "Vacuum deleted {} files with {} bytes from {}.",
+            total, bytes_deleted, self.table_uri
+        );
+        Ok(metrics)
+    }
+
+    /// Verifies that the loaded version is consistent with the log and the storage without
+    /// modifying anything, see [`audit::audit`].
+    pub async fn audit(&self, options: &AuditOptions) -> Result<AuditReport, DeltaTableError> {
+        audit::audit(self, options).await
+    }
+
+    /// Writes the symlink format manifests of the loaded version, see
+    /// [`manifest::generate_symlink_format_manifest`].
+    pub async fn generate_symlink_format_manifest(&self) -> Result<(), DeltaTableError> {
+        manifest::generate_symlink_format_manifest(self).await
+    }
+
+    /// Sets table properties like `ALTER TABLE SET TBLPROPERTIES` by committing the current
+    /// metadata with `properties` merged into its configuration. Malformed values of the
+    /// properties defined by the protocol are logged, or rejected if the table was loaded with
+    /// strict table properties. Returns the committed version.
+    pub async fn set_tbl_properties(
+        &mut self,
+        properties: HashMap<String, Option<String>>,
+    ) -> Result<DeltaDataTypeVersion, DeltaTableError> {
+        let mut metadata = self.get_metadata()?.clone();
+        let mut commit_info = Map::new();
+        commit_info.insert(
+            "operation".to_string(),
+            Value::String("SET TBLPROPERTIES".to_string()),
+        );
+        commit_info.insert(
+            "operationParameters".to_string(),
+            serde_json::json!({ "properties": serde_json::to_string(&properties)? }),
+        );
+        metadata.configuration.extend(properties);
+
+        let mut transaction = self.create_transaction(None);
+        transaction.add_actions(vec![
+            Action::commitInfo(commit_info),
+            Action::metaData(action::MetaData::try_from(metadata)?),
+        ]);
+        let version = transaction.commit(None).await?;
+        self.update().await?;
+        Ok(version)
     }
 
     /// Return table schema parsed from transaction log. Return None if table hasn't been loaded or
@@ -1115,6 +1991,19 @@ impl DeltaTable {
         self.schema().ok_or(DeltaTableError::NoSchema)
     }
 
+    /// Resolve a user supplied column name to the name of a top level column of the table schema
+    /// according to the table's [`ColumnResolution`] policy.
+    pub fn resolve_column_name(&self, name: &str) -> Result<String, DeltaTableError> {
+        let schema = self.get_schema()?;
+        self.config
+            .column_resolution
+            .resolve(name, schema.get_fields().iter().map(|f| f.get_name()))?
+            .map(str::to_string)
+            .ok_or_else(|| DeltaTableError::ColumnNotFound {
+                name: name.to_string(),
+            })
+    }
+
     /// Return the tables configurations that are encapsulated in the DeltaTableStates currentMetaData field
     pub fn get_configurations(&self) -> Result<&HashMap<String, Option<String>>, DeltaTableError> {
         Ok(self
@@ -1134,6 +2023,75 @@ impl DeltaTable {
         DeltaTransaction::new(self, options)
     }
 
+    /// Uploads a data file of the table and returns its add action, without committing it. The
+    /// file is written to the directory of its partition values, ordered like the partition
+    /// columns of the table.
+    pub(crate) async fn write_data_file(
+        &self,
+        bytes: &[u8],
+        partition_values: Vec<(String, Option<String>)>,
+        stats: Option<String>,
+    ) -> Result<action::Add, DeltaTableError> {
+        let partitions = if partition_values.is_empty() {
+            None
+        } else {
+            Some(
+                partition_values
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = value
+                            .clone()
+                            .unwrap_or_else(|| NULL_PARTITION_VALUE_DATA_PATH.to_string());
+                        (key.clone(), value)
+                    })
+                    .collect(),
+            )
+        };
+
+        let path = self.generate_parquet_filename(partitions);
+        let parquet_uri = self.storage.join_path(&self.table_uri, &path);
+
+        debug!("Writing a parquet file to {}", &parquet_uri);
+        self.storage.put_obj(&parquet_uri, bytes).await?;
+
+        // Determine the modification timestamp to include in the add action - milliseconds since epoch
+        // Err should be impossible in this case since `SystemTime::now()` is always greater than `UNIX_EPOCH`
+        let modification_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let modification_time = modification_time.as_millis() as i64;
+
+        Ok(action::Add {
+            path,
+            partition_values: partition_values.into_iter().collect(),
+            modification_time,
+            size: bytes.len() as i64,
+            partition_values_parsed: None,
+            data_change: true,
+            stats,
+            stats_parsed: None,
+            tags: None,
+        })
+    }
+
+    fn generate_parquet_filename(&self, partitions: Option<Vec<(String, String)>>) -> String {
+        /*
+         * The specific file naming for parquet is not well documented including the preceding five
+         * zeros and the trailing c000 string
+         *
+         */
+        let mut path_parts = vec![];
+
+        if let Some(partitions) = partitions {
+            for partition in partitions {
+                path_parts.push(format!("{}={}", partition.0, partition.1));
+            }
+        }
+
+        path_parts.push(format!("part-00000-{}-c000.snappy.parquet", Uuid::new_v4()));
+
+        self.storage
+            .join_paths(&path_parts.iter().map(|s| s.as_str()).collect::<Vec<&str>>())
+    }
+
     /// Tries to commit a prepared commit file. Returns `DeltaTableError::VersionAlreadyExists`
     /// if the given `version` already exists. The caller should handle the retry logic itself.
     /// This is low-level transaction API. If user does not want to maintain the commit loop then
@@ -1161,6 +2119,9 @@ impl DeltaTable {
 
     /// Create a new Delta Table struct without loading any data from backing storage.
     ///
+    /// The uri is normalized with [storage::normalize_table_uri] like the uris of tables loaded
+    /// with [`DeltaTableBuilder`].
+    ///
     /// NOTE: This is for advanced users. If you don't know why you need to use this method, please
     /// call one of the `open_table` helper methods instead.
     pub fn new(
@@ -1168,17 +2129,18 @@ impl DeltaTable {
         storage_backend: Box<dyn StorageBackend>,
         config: DeltaTableConfig,
     ) -> Result<Self, DeltaTableError> {
-        let table_uri = storage_backend.trim_path(table_uri);
+        let table_uri = storage_backend.trim_path(&storage::normalize_table_uri(table_uri)?);
         let log_uri_normalized = storage_backend.join_path(&table_uri, "_delta_log");
         Ok(Self {
             version: 0,
             state: DeltaTableState::default(),
-            storage: storage_backend,
+            storage: storage_backend.into(),
             table_uri,
             config,
             last_check_point: None,
             log_uri: log_uri_normalized,
             version_timestamp: HashMap::new(),
+            health_warnings: Vec::new(),
         })
     }
 
@@ -1221,43 +2183,83 @@ impl DeltaTable {
     }
 
     /// Time travel Delta table to the latest version that's created at or before provided
-    /// `datetime` argument.
+    /// `datetime` argument, according to the modification times of the commit files.
     ///
-    /// Internally, this methods performs a binary search on all Delta transaction logs.
+    /// Internally, this methods performs a binary search on all Delta transaction logs. Returns
+    /// [`DeltaTableError::TimestampBeforeFirstCommit`] if `datetime` is before the earliest
+    /// commit still in the log.
     pub async fn load_with_datetime(
         &mut self,
         datetime: DateTime<Utc>,
     ) -> Result<(), DeltaTableError> {
-        let mut min_version = 0;
-        let mut max_version = self.get_latest_version().await?;
-        let mut version = min_version;
-        let target_ts = datetime.timestamp();
-
-        // binary search
-        while min_version <= max_version {
-            let pivot = (max_version + min_version) / 2;
-            version = pivot;
-            let pts = self.get_version_timestamp(pivot).await?;
-
-            match pts.cmp(&target_ts) {
-                Ordering::Equal => {
-                    break;
-                }
-                Ordering::Less => {
-                    min_version = pivot + 1;
-                }
-                Ordering::Greater => {
-                    max_version = pivot - 1;
-                    version = max_version
-                }
+        let update = self.prepare_load_with_datetime(datetime).await?;
+        self.apply_update(update)
+    }
+
+    /// Loads the state of the version at `datetime` like [`DeltaTable::load_with_datetime`]
+    /// does, without modifying the table. The returned update is installed with
+    /// [`DeltaTable::apply_update`].
+    pub async fn prepare_load_with_datetime(
+        &self,
+        datetime: DateTime<Utc>,
+    ) -> Result<DeltaTableUpdate, DeltaTableError> {
+        let target_ts = datetime.timestamp_millis();
+        let mut timestamps = HashMap::new();
+        let mut max_version = self.get_latest_version(&mut timestamps).await?;
+        let (mut min_version, earliest_ts) = self.find_earliest_commit().await?;
+        timestamps.insert(min_version, earliest_ts);
+        if earliest_ts > target_ts {
+            return Err(DeltaTableError::TimestampBeforeFirstCommit {
+                timestamp: datetime,
+                earliest_version: min_version,
+                earliest_timestamp: Utc.timestamp_millis(earliest_ts),
+            });
+        }
+
+        // binary search for the latest version committed at or before the target, the version
+        // at min_version always is
+        while min_version < max_version {
+            let pivot = min_version + (max_version - min_version + 1) / 2;
+            if self.get_version_timestamp(pivot, &mut timestamps).await? <= target_ts {
+                min_version = pivot;
+            } else {
+                max_version = pivot - 1;
             }
         }
 
-        if version < 0 {
-            version = 0;
+        let mut update = self.prepare_load_version(min_version).await?;
+        update.version_timestamps = timestamps;
+        Ok(update)
+    }
+
+    /// Returns the version and modification time in milliseconds of the earliest commit file.
+    async fn find_earliest_commit(&self) -> Result<(DeltaDataTypeVersion, i64), DeltaTableError> {
+        match self
+            .storage
+            .head_obj(&self.commit_uri_from_version(0))
+            .await
+        {
+            Ok(meta) => return Ok((0, meta.modified.timestamp_millis())),
+            Err(StorageError::NotFound) => {}
+            Err(e) => return Err(DeltaTableError::from(e)),
         }
 
-        self.load_version(version).await
+        let mut earliest: Option<(DeltaDataTypeVersion, i64)> = None;
+        let mut stream = self.storage.list_objs(&self.log_uri).await?;
+        while let Some(obj_meta) = stream.next().await {
+            let obj_meta = obj_meta?;
+            if let Some(DeltaLogFile::Commit(version)) = DeltaLogFile::from_path(&obj_meta.path) {
+                if earliest.map_or(true, |(earliest, _)| version < earliest) {
+                    earliest = Some((version, obj_meta.modified.timestamp_millis()));
+                }
+            }
+        }
+        earliest.ok_or_else(|| {
+            DeltaTableError::NotATable(format!(
+                "No commit found in {}, perhaps it is an empty dir?",
+                self.log_uri
+            ))
+        })
     }
 }
 
@@ -1291,11 +2293,168 @@ impl std::fmt::Debug for DeltaTable {
 
 const DEFAULT_DELTA_MAX_RETRY_COMMIT_ATTEMPTS: u32 = 10_000_000;
 
+/// Directories of the table a vacuum never deletes files from
+const VACUUM_PROTECTED_DIRECTORIES: [&str; 3] =
+    ["_delta_log", "_change_data", "_symlink_format_manifest"];
+
+/// Options of [`DeltaTable::vacuum_with_options`]
+#[derive(Debug, Clone)]
+pub struct VacuumOptions {
+    /// The retention threshold in hours, the `delta.deletedFileRetentionDuration` of the table
+    /// if not set
+    pub retention_hours: Option<u64>,
+    /// Only list the files that would be deleted, without deleting them
+    pub dry_run: bool,
+    /// Reject retention thresholds shorter than the `delta.deletedFileRetentionDuration` of the
+    /// table. Files that concurrent readers and writers still use may be deleted otherwise.
+    pub enforce_retention_duration: bool,
+    /// Maximum number of files deleted at once
+    pub max_concurrent_deletes: usize,
+}
+
+impl Default for VacuumOptions {
+    fn default() -> Self {
+        Self {
+            retention_hours: None,
+            dry_run: false,
+            enforce_retention_duration: true,
+            max_concurrent_deletes: 16,
+        }
+    }
+}
+
+/// The files deleted by a vacuum
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VacuumMetrics {
+    /// The deleted files, or the files that would be deleted in a dry run
+    pub files_deleted: Vec<String>,
+    /// Total size of the deleted files in bytes, as far as the storage reports their sizes
+    pub bytes_reclaimed: DeltaDataTypeLong,
+    /// Number of files in the table directory that were kept because the loaded version still
+    /// references them
+    pub referenced_files_skipped: usize,
+}
+
+/// Directory name used for null partition values in the data file paths
+pub const NULL_PARTITION_VALUE_DATA_PATH: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// A hook invoked after a transaction was committed, e.g. to invalidate caches or to notify
+/// consumers of the table
+pub trait PostCommitHook: Send + Sync {
+    /// Called with the committed version, operation and actions. A failure does not undo the
+    /// commit, it is logged unless strict post-commit hooks are enabled for the transaction.
+    fn after_commit(
+        &self,
+        version: DeltaDataTypeVersion,
+        operation: Option<&DeltaOperation>,
+        actions: &[Action],
+    ) -> Result<(), DeltaTableError>;
+}
+
+/// The phase of a long running operation reported in a [`ProgressEvent`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressPhase {
+    /// Writing the data files of a write
+    WriteFiles,
+    /// Deleting the unreferenced files of a vacuum
+    DeleteFiles,
+}
+
+impl ProgressPhase {
+    /// The name of the phase, e.g. `write_files`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProgressPhase::WriteFiles => "write_files",
+            ProgressPhase::DeleteFiles => "delete_files",
+        }
+    }
+}
+
+/// The progress of a long running operation within one of its phases
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProgressEvent {
+    /// The phase the operation is in
+    pub phase: ProgressPhase,
+    /// Number of items of the phase that are done, e.g. files written
+    pub done: usize,
+    /// Total number of items of the phase
+    pub total: usize,
+    /// Number of bytes processed in the phase so far, if the phase tracks sizes
+    pub bytes: Option<DeltaDataTypeLong>,
+}
+
+/// Receives the progress of long running operations
+pub trait ProgressCallback: Send + Sync {
+    /// Called each time an item of an operation phase is done
+    fn on_progress(&self, event: &ProgressEvent);
+}
+
+/// Signals that a long running operation should stop. Operations check the token between their
+/// steps and return [`DeltaTableError::Cancelled`] before committing anything.
+pub trait CancellationToken: Send + Sync {
+    /// Whether the operation should stop
+    fn is_cancelled(&self) -> bool;
+}
+
+impl CancellationToken for AtomicBool {
+    fn is_cancelled(&self) -> bool {
+        self.load(atomic::Ordering::SeqCst)
+    }
+}
+
+/// Progress reporting and cancellation of long running operations like writes and vacuum
+#[derive(Clone, Default)]
+pub struct OperationControl {
+    progress_callback: Option<Arc<dyn ProgressCallback>>,
+    cancellation_token: Option<Arc<dyn CancellationToken>>,
+}
+
+impl OperationControl {
+    /// Report the progress of the operation to the callback
+    pub fn with_progress_callback(mut self, callback: Arc<dyn ProgressCallback>) -> Self {
+        self.progress_callback = Some(callback);
+        self
+    }
+
+    /// Stop the operation once the token is cancelled
+    pub fn with_cancellation_token(mut self, token: Arc<dyn CancellationToken>) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Report progress to the callback, if any
+    pub fn report(&self, event: ProgressEvent) {
+        if let Some(callback) = &self.progress_callback {
+            callback.on_progress(&event);
+        }
+    }
+
+    /// Returns [`DeltaTableError::Cancelled`] if the operation was cancelled
+    pub fn check_cancelled(&self) -> Result<(), DeltaTableError> {
+        match &self.cancellation_token {
+            Some(token) if token.is_cancelled() => Err(DeltaTableError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for OperationControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OperationControl")
+            .field("progress_callback", &self.progress_callback.is_some())
+            .field("cancellation_token", &self.cancellation_token.is_some())
+            .finish()
+    }
+}
+
 /// Options for customizing behavior of a `DeltaTransaction`
-#[derive(Debug)]
 pub struct DeltaTransactionOptions {
     /// number of retry attempts allowed when committing a transaction
     max_retry_commit_attempts: u32,
+    /// hooks invoked in order after a successful commit
+    post_commit_hooks: Vec<Arc<dyn PostCommitHook>>,
+    /// whether a failing post-commit hook fails the commit call
+    strict_post_commit_hooks: bool,
 }
 
 impl DeltaTransactionOptions {
@@ -1303,18 +2462,45 @@ impl DeltaTransactionOptions {
     pub fn new(max_retry_commit_attempts: u32) -> Self {
         Self {
             max_retry_commit_attempts,
+            ..Default::default()
         }
     }
+
+    /// Register a hook invoked after the transaction is committed. Hooks run in the order they
+    /// were registered.
+    pub fn with_post_commit_hook(mut self, hook: Arc<dyn PostCommitHook>) -> Self {
+        self.post_commit_hooks.push(hook);
+        self
+    }
+
+    /// Return [`DeltaTableError::PostCommitHookFailed`] from the commit when a post-commit hook
+    /// fails, instead of only logging the failure. The remaining hooks are skipped.
+    pub fn with_strict_post_commit_hooks(mut self) -> Self {
+        self.strict_post_commit_hooks = true;
+        self
+    }
 }
 
 impl Default for DeltaTransactionOptions {
     fn default() -> Self {
         Self {
             max_retry_commit_attempts: DEFAULT_DELTA_MAX_RETRY_COMMIT_ATTEMPTS,
+            post_commit_hooks: Vec::new(),
+            strict_post_commit_hooks: false,
         }
     }
 }
 
+impl fmt::Debug for DeltaTransactionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeltaTransactionOptions")
+            .field("max_retry_commit_attempts", &self.max_retry_commit_attempts)
+            .field("post_commit_hooks", &self.post_commit_hooks.len())
+            .field("strict_post_commit_hooks", &self.strict_post_commit_hooks)
+            .finish()
+    }
+}
+
 /// Object representing a delta transaction.
 /// Clients that do not need to mutate action content in case a transaction conflict is encountered
 /// may use the `commit` method and rely on optimistic concurrency to determine the
@@ -1370,71 +2556,48 @@ impl<'a> DeltaTransaction<'a> {
         bytes: &[u8],
         partitions: Option<Vec<(String, String)>>,
     ) -> Result<(), DeltaTableError> {
-        let mut partition_values = HashMap::new();
-        if let Some(partitions) = &partitions {
-            for (key, value) in partitions {
-                partition_values.insert(key.clone(), Some(value.clone()));
-            }
-        }
+        let partition_values = partitions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, value)| (key, Some(value)))
+            .collect();
 
-        let path = self.generate_parquet_filename(partitions);
-        let parquet_uri = self
-            .delta_table
-            .storage
-            .join_path(&self.delta_table.table_uri, &path);
+        self.add_file_with_stats(bytes, partition_values, None)
+            .await
+    }
 
-        debug!("Writing a parquet file to {}", &parquet_uri);
-        self.delta_table
-            .storage
-            .put_obj(&parquet_uri, bytes)
+    /// Create a new add action like [`DeltaTransaction::add_file`] for a file with the given
+    /// statistics in JSON format
+    ///
+    /// partition_values are ordered like the partition columns of the table, files with a null
+    /// partition value are written to the `__HIVE_DEFAULT_PARTITION__` directory
+    pub async fn add_file_with_stats(
+        &mut self,
+        bytes: &[u8],
+        partition_values: Vec<(String, Option<String>)>,
+        stats: Option<String>,
+    ) -> Result<(), DeltaTableError> {
+        let add = self
+            .delta_table
+            .write_data_file(bytes, partition_values, stats)
             .await?;
-
-        // Determine the modification timestamp to include in the add action - milliseconds since epoch
-        // Err should be impossible in this case since `SystemTime::now()` is always greater than `UNIX_EPOCH`
-        let modification_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        let modification_time = modification_time.as_millis() as i64;
-
-        self.actions.push(Action::add(action::Add {
-            path,
-            partition_values,
-            modification_time,
-            size: bytes.len() as i64,
-            partition_values_parsed: None,
-            data_change: true,
-            stats: None,
-            stats_parsed: None,
-            tags: None,
-        }));
+        self.actions.push(Action::add(add));
 
         Ok(())
     }
 
-    fn generate_parquet_filename(&self, partitions: Option<Vec<(String, String)>>) -> String {
-        /*
-         * The specific file naming for parquet is not well documented including the preceding five
-         * zeros and the trailing c000 string
-         *
-         */
-        let mut path_parts = vec![];
-
-        if let Some(partitions) = partitions {
-            for partition in partitions {
-                path_parts.push(format!("{}={}", partition.0, partition.1));
-            }
-        }
-
-        path_parts.push(format!("part-00000-{}-c000.snappy.parquet", Uuid::new_v4()));
-
-        self.delta_table
-            .storage
-            .join_paths(&path_parts.iter().map(|s| s.as_str()).collect::<Vec<&str>>())
+    /// The actions added to this transaction so far
+    pub(crate) fn actions(&self) -> &[Action] {
+        &self.actions
     }
 
     /// Commits the given actions to the delta log.
     /// This method will retry the transaction commit based on the value of `max_retry_commit_attempts` set in `DeltaTransactionOptions`.
+    ///
+    /// The post-commit hooks of the transaction run after the commit succeeded.
     pub async fn commit(
         &mut self,
-        _operation: Option<DeltaOperation>,
+        operation: Option<DeltaOperation>,
     ) -> Result<DeltaDataTypeVersion, DeltaTableError> {
         // TODO: stubbing `operation` parameter (which will be necessary for writing the CommitInfo action), but leaving it unused for now.
         // `CommitInfo` is a fairly dynamic data structure so we should work out the data structure approach separately.
@@ -1454,14 +2617,104 @@ impl<'a> DeltaTransaction<'a> {
         //     IsolationLevel::Serializable
         // };
 
-        let prepared_commit = self.prepare_commit(_operation).await?;
+        let prepared_commit = self.prepare_commit(operation.clone()).await?;
 
         // try to commit in a loop in case other writers write the next version first
         let version = self.try_commit_loop(&prepared_commit).await?;
 
+        self.update_symlink_format_manifest(version).await?;
+        self.run_post_commit_hooks(version, operation.as_ref())?;
+
+        Ok(version)
+    }
+
+    /// Commits the given actions as exactly `version` without retrying, e.g. as version 0 to
+    /// create a table together with its first files. Returns
+    /// [`DeltaTableError::VersionAlreadyExists`] if another writer committed the version first.
+    ///
+    /// The post-commit hooks of the transaction run after the commit succeeded.
+    pub async fn commit_version(
+        &mut self,
+        version: DeltaDataTypeVersion,
+        operation: Option<DeltaOperation>,
+    ) -> Result<DeltaDataTypeVersion, DeltaTableError> {
+        let prepared_commit = self.prepare_commit(operation.clone()).await?;
+        match self
+            .delta_table
+            .try_commit_transaction(&prepared_commit, version)
+            .await
+        {
+            Ok(_) => info!(
+                "Committed version {} of {}.",
+                version, self.delta_table.table_uri
+            ),
+            Err(err @ DeltaTableError::VersionAlreadyExists(_)) => {
+                if let Err(delete_err) = self
+                    .delta_table
+                    .storage
+                    .delete_obj(&prepared_commit.uri)
+                    .await
+                {
+                    debug!("Failed to delete the prepared commit: {}", delete_err);
+                }
+                return Err(err);
+            }
+            Err(err) => return Err(err),
+        }
+
+        self.update_symlink_format_manifest(version).await?;
+        self.run_post_commit_hooks(version, operation.as_ref())?;
+
         Ok(version)
     }
 
+    /// Regenerates the symlink format manifests if the table enables them. A failure is handled
+    /// like a failing post-commit hook.
+    async fn update_symlink_format_manifest(
+        &self,
+        version: DeltaDataTypeVersion,
+    ) -> Result<(), DeltaTableError> {
+        let properties = TableProperties::from_metadata(self.delta_table.get_metadata()?);
+        if !properties.symlink_format_manifest_enabled {
+            return Ok(());
+        }
+        if let Err(err) = self.delta_table.generate_symlink_format_manifest().await {
+            if self.options.strict_post_commit_hooks {
+                return Err(DeltaTableError::PostCommitHookFailed {
+                    version,
+                    msg: err.to_string(),
+                });
+            }
+            warn!(
+                "Updating the symlink format manifests failed after committing version {}: {}",
+                version, err
+            );
+        }
+        Ok(())
+    }
+
+    fn run_post_commit_hooks(
+        &self,
+        version: DeltaDataTypeVersion,
+        operation: Option<&DeltaOperation>,
+    ) -> Result<(), DeltaTableError> {
+        for hook in &self.options.post_commit_hooks {
+            if let Err(err) = hook.after_commit(version, operation, &self.actions) {
+                if self.options.strict_post_commit_hooks {
+                    return Err(DeltaTableError::PostCommitHookFailed {
+                        version,
+                        msg: err.to_string(),
+                    });
+                }
+                warn!(
+                    "Post-commit hook failed after committing version {}: {}",
+                    version, err
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Low-level transaction API. Creates a temporary commit file. Once created,
     /// the transaction object could be dropped and the actual commit could be executed
     /// with `DeltaTable.try_commit_transaction`.
@@ -1473,7 +2726,9 @@ impl<'a> DeltaTransaction<'a> {
 
         // TODO: create a CommitInfo action and prepend it to actions.
         // Serialize all actions that are part of this log entry.
-        let log_entry = log_entry_from_actions(&self.actions)?;
+        let actions = self.actions_with_valid_column_names()?;
+        self.validate_table_properties(&actions)?;
+        let log_entry = log_entry_from_actions(&actions)?;
 
         let file_name = format!("_commit_{}.json.tmp", token);
         let uri = self
@@ -1489,6 +2744,92 @@ impl<'a> DeltaTransaction<'a> {
         Ok(PreparedCommit { uri })
     }
 
+    /// Validates the column names of schemas committed with this transaction. When columns of a
+    /// schema with column mapping need ids, the metadata is updated and the protocol upgraded
+    /// accordingly.
+    fn actions_with_valid_column_names(&self) -> Result<Cow<[Action]>, DeltaTableError> {
+        if !self
+            .actions
+            .iter()
+            .any(|a| matches!(a, Action::metaData(_)))
+        {
+            return Ok(Cow::Borrowed(&self.actions));
+        }
+
+        let mut actions = self.actions.clone();
+        let mut upgrade_protocol = false;
+        for action in actions.iter_mut() {
+            if let Action::metaData(metadata) = action {
+                let mut table_metadata = DeltaTableMetaData::try_from(metadata.clone())?;
+                if validate_column_names(&mut table_metadata)? {
+                    *metadata = action::MetaData::try_from(table_metadata)?;
+                    upgrade_protocol = true;
+                }
+            }
+        }
+
+        if upgrade_protocol {
+            let protocol = actions.iter_mut().find_map(|a| match a {
+                Action::protocol(p) => Some(p),
+                _ => None,
+            });
+            match protocol {
+                Some(protocol) => upgrade_protocol_for_column_mapping(protocol),
+                None => {
+                    let mut protocol = action::Protocol {
+                        min_reader_version: self.delta_table.state.min_reader_version(),
+                        min_writer_version: self.delta_table.state.min_writer_version(),
+                    };
+                    upgrade_protocol_for_column_mapping(&mut protocol);
+                    actions.push(Action::protocol(protocol));
+                }
+            }
+        }
+
+        Ok(Cow::Owned(actions))
+    }
+
+    /// Checks the table properties set by the metadata of this transaction, i.e. those differing
+    /// from the current metadata. Malformed values are logged, or rejected if the table has
+    /// `strict_table_properties` set. Commits removing data from an append-only table fail.
+    fn validate_table_properties(&self, actions: &[Action]) -> Result<(), DeltaTableError> {
+        let current_metadata = self.delta_table.state.current_metadata();
+        for action in actions {
+            if let Action::metaData(metadata) = action {
+                for error in TableProperties::validate(&metadata.configuration) {
+                    if let DeltaConfigError::InvalidProperty { key, .. } = &error {
+                        let unchanged = current_metadata.map_or(false, |current| {
+                            current.configuration.get(key) == metadata.configuration.get(key)
+                        });
+                        if unchanged {
+                            continue;
+                        }
+                    }
+                    if self.delta_table.config.strict_table_properties {
+                        return Err(error.into());
+                    }
+                    warn!(
+                        "Committing a malformed table property to {}, its default is used: {}",
+                        self.delta_table.table_uri, error
+                    );
+                }
+            }
+        }
+
+        let append_only = current_metadata.map_or(false, |metadata| {
+            TableProperties::from_metadata(metadata).append_only
+        });
+        let removes_data = actions
+            .iter()
+            .any(|action| matches!(action, Action::remove(remove) if remove.data_change));
+        if append_only && removes_data {
+            return Err(DeltaTableError::AppendOnly(
+                self.delta_table.table_uri.clone(),
+            ));
+        }
+        Ok(())
+    }
+
     async fn try_commit_loop(
         &mut self,
         commit: &PreparedCommit,
@@ -1498,6 +2839,12 @@ impl<'a> DeltaTransaction<'a> {
             self.delta_table.update().await?;
 
             let version = self.delta_table.version + 1;
+            debug!(
+                "Committing version {} of {} (attempt {}).",
+                version,
+                self.delta_table.table_uri,
+                attempt_number + 1
+            );
 
             match self
                 .delta_table
@@ -1505,6 +2852,7 @@ impl<'a> DeltaTransaction<'a> {
                 .await
             {
                 Ok(v) => {
+                    info!("Committed version {} of {}.", v, self.delta_table.table_uri);
                     return Ok(v);
                 }
                 Err(e) => {
@@ -1536,10 +2884,48 @@ pub struct PreparedCommit {
     uri: String,
 }
 
+/// Checks the column names of a schema that is about to be committed. Names with characters
+/// parquet cannot store are only allowed with column mapping, which the readers and writers of
+/// this crate do not support, so they are rejected unless the metadata enables column mapping
+/// itself. With column mapping enabled, ids and physical names are assigned to the columns that
+/// have none yet, e.g. columns added by a schema change. Returns whether the metadata was changed.
+fn validate_column_names(metadata: &mut DeltaTableMetaData) -> Result<bool, DeltaTableError> {
+    let properties = TableProperties::from_metadata(metadata);
+    if properties.column_mapping_mode == "none" {
+        let names = metadata.schema.invalid_column_names();
+        if names.is_empty() {
+            return Ok(false);
+        }
+        return Err(DeltaTableError::InvalidColumnNames { names });
+    }
+
+    let max_column_id = metadata
+        .schema
+        .assign_column_mapping(properties.column_mapping_max_column_id);
+    if max_column_id == properties.column_mapping_max_column_id {
+        return Ok(false);
+    }
+    metadata.configuration.insert(
+        delta_config::COLUMN_MAPPING_MAX_COLUMN_ID.key.clone(),
+        Some(max_column_id.to_string()),
+    );
+    Ok(true)
+}
+
+/// Column mapping requires reader version 2 and writer version 5.
+fn upgrade_protocol_for_column_mapping(protocol: &mut action::Protocol) {
+    protocol.min_reader_version = protocol.min_reader_version.max(2);
+    protocol.min_writer_version = protocol.min_writer_version.max(5);
+}
+
 fn log_entry_from_actions(actions: &[Action]) -> Result<String, serde_json::Error> {
     let mut jsons = Vec::<String>::new();
 
-    for action in actions {
+    // the commitInfo is written first, so it can be read without parsing the whole entry
+    let (commit_infos, others): (Vec<&Action>, Vec<&Action>) = actions
+        .iter()
+        .partition(|action| matches!(action, Action::commitInfo(_)));
+    for action in commit_infos.into_iter().chain(others) {
         let json = serde_json::to_string(action)?;
         jsons.push(json);
     }
@@ -1625,21 +3011,64 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn classify_delta_log_files() {
+        assert_eq!(
+            DeltaLogFile::from_path("table/_delta_log/00000000000000000010.json"),
+            Some(DeltaLogFile::Commit(10))
+        );
+        assert_eq!(
+            DeltaLogFile::from_path("table\\_delta_log\\00000000000000000010.checkpoint.parquet"),
+            Some(DeltaLogFile::Checkpoint(10))
+        );
+        assert_eq!(
+            DeltaLogFile::from_path(
+                "_delta_log/00000000000000000010.checkpoint.0000000002.0000000003.parquet"
+            ),
+            Some(DeltaLogFile::CheckpointPart {
+                version: 10,
+                part: 2,
+                parts: 3
+            })
+        );
+        assert_eq!(
+            DeltaLogFile::from_path(
+                "_delta_log/00000000000000000004.00000000000000000007.compacted.json"
+            ),
+            Some(DeltaLogFile::CompactedCommit { start: 4, end: 7 })
+        );
+        assert_eq!(
+            DeltaLogFile::from_path("_delta_log/00000000000000000005.crc"),
+            Some(DeltaLogFile::Checksum(5))
+        );
+
+        for junk in [
+            "_delta_log/00000000000000000011.json.tmp",
+            "_delta_log/.00000000000000000011.json.tmp",
+            "_delta_log/_commit_3f1c6b2e-9a4e-4b8a-8f0e-2d6c1a7e5b90.json.tmp",
+            "_delta_log/.00000000000000000010.checkpoint.parquet.crc",
+            "_delta_log/00000000000000000010.checkpoint.parquet.tmp",
+            "_delta_log/00000000000000000010.checkpoint.0000000000.0000000003.parquet",
+            "_delta_log/00000000000000000010.checkpoint.0000000004.0000000003.parquet",
+            "_delta_log/0000000000000000001.json",
+            "_delta_log/_last_checkpoint",
+            "_delta_log/README.txt",
+        ]
+        .iter()
+        {
+            assert_eq!(DeltaLogFile::from_path(junk), None, "{}", junk);
+        }
+    }
+
     #[tokio::test]
     async fn parquet_filename() {
-        let mut table = open_table("./tests/data/simple_table").await.unwrap();
-
-        let txn = DeltaTransaction {
-            delta_table: &mut table,
-            actions: vec![],
-            options: DeltaTransactionOptions::default(),
-        };
+        let table = open_table("./tests/data/simple_table").await.unwrap();
 
         let partitions = vec![
             (String::from("col1"), String::from("a")),
             (String::from("col2"), String::from("b")),
         ];
-        let parquet_filename = txn.generate_parquet_filename(Some(partitions));
+        let parquet_filename = table.generate_parquet_filename(Some(partitions));
         if cfg!(windows) {
             assert!(parquet_filename.contains("col1=a\\col2=b\\part-00000-"));
         } else {
@@ -1764,4 +3193,181 @@ mod tests {
         assert!(current_metadata.partition_columns.is_empty());
         assert!(current_metadata.configuration.is_empty());
     }
+
+    fn metadata_with_columns(names: &[&str]) -> DeltaTableMetaData {
+        let fields = names
+            .iter()
+            .map(|name| {
+                SchemaField::new(
+                    name.to_string(),
+                    SchemaDataType::primitive("string".to_string()),
+                    true,
+                    HashMap::new(),
+                )
+            })
+            .collect();
+        DeltaTableMetaData::new(
+            None,
+            None,
+            None,
+            Schema::new(fields),
+            vec![],
+            HashMap::new(),
+        )
+    }
+
+    fn new_table() -> (tempdir::TempDir, DeltaTable) {
+        let tmp_dir = tempdir::TempDir::new("create_table_column_names_test").unwrap();
+        let path = tmp_dir.path().join("test_create");
+        let backend = Box::new(storage::file::FileStorageBackend::new(
+            tmp_dir.path().to_str().unwrap(),
+        ));
+        let dt =
+            DeltaTable::new(path.to_str().unwrap(), backend, DeltaTableConfig::default()).unwrap();
+        (tmp_dir, dt)
+    }
+
+    #[tokio::test]
+    async fn test_create_delta_table_rejects_invalid_column_names() {
+        let protocol = action::Protocol {
+            min_reader_version: 1,
+            min_writer_version: 2,
+        };
+
+        for name in ["col name", "a,b"] {
+            let (_tmp_dir, mut dt) = new_table();
+            let delta_md = metadata_with_columns(&["id", name]);
+            let result = dt.create(delta_md, protocol.clone(), None).await;
+            match result {
+                Err(DeltaTableError::InvalidColumnNames { names }) => {
+                    assert_eq!(names, vec![name.to_string()])
+                }
+                other => panic!("expected invalid column names error, got {:?}", other),
+            }
+            assert!(!Path::new(&dt.table_uri)
+                .join("_delta_log/00000000000000000000.json")
+                .exists());
+        }
+
+        // Unicode letters are valid parquet column names
+        let (_tmp_dir, mut dt) = new_table();
+        let delta_md = metadata_with_columns(&["größe", "名前"]);
+        dt.create(delta_md.clone(), protocol, None).await.unwrap();
+        assert_eq!(dt.get_metadata().unwrap().schema, delta_md.schema);
+        assert_eq!(dt.get_min_writer_version(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_delta_table_with_column_mapping() {
+        let protocol = action::Protocol {
+            min_reader_version: 1,
+            min_writer_version: 2,
+        };
+        let (_tmp_dir, mut dt) = new_table();
+        let mut delta_md = metadata_with_columns(&["col name", "a,b", "größe"]);
+        delta_md.configuration.insert(
+            "delta.columnMapping.mode".to_string(),
+            Some("name".to_string()),
+        );
+        dt.create(delta_md, protocol, None).await.unwrap();
+
+        assert_eq!(dt.get_min_reader_version(), 2);
+        assert_eq!(dt.get_min_writer_version(), 5);
+
+        let metadata = dt.get_metadata().unwrap();
+        assert_eq!(
+            metadata.configuration.get("delta.columnMapping.mode"),
+            Some(&Some("name".to_string()))
+        );
+        assert_eq!(
+            metadata
+                .configuration
+                .get("delta.columnMapping.maxColumnId"),
+            Some(&Some("3".to_string()))
+        );
+
+        let mut physical_names = HashSet::new();
+        for (i, field) in metadata.schema.get_fields().iter().enumerate() {
+            let field_metadata = field.get_metadata();
+            assert_eq!(
+                field_metadata.get(COLUMN_MAPPING_ID_KEY),
+                Some(&(i + 1).to_string())
+            );
+            let physical_name = &field_metadata[COLUMN_MAPPING_PHYSICAL_NAME_KEY];
+            assert!(physical_name.starts_with("col-"));
+            assert!(!physical_name.contains(INVALID_COLUMN_NAME_CHARS));
+            physical_names.insert(physical_name.clone());
+        }
+        assert_eq!(physical_names.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_commit_metadata_with_invalid_column_names() {
+        let protocol = action::Protocol {
+            min_reader_version: 1,
+            min_writer_version: 2,
+        };
+        let (_tmp_dir, mut dt) = new_table();
+        dt.create(metadata_with_columns(&["id"]), protocol.clone(), None)
+            .await
+            .unwrap();
+
+        // Evolving the schema with a column that requires column mapping is rejected
+        let mut new_md = metadata_with_columns(&["id", "new column"]);
+        new_md.id = dt.get_metadata().unwrap().id.clone();
+        let mut transaction = dt.create_transaction(None);
+        transaction.add_actions(vec![Action::metaData(
+            action::MetaData::try_from(new_md).unwrap(),
+        )]);
+        assert!(matches!(
+            transaction.commit(None).await,
+            Err(DeltaTableError::InvalidColumnNames { names }) if names == vec!["new column"]
+        ));
+        dt.update().await.unwrap();
+        assert_eq!(dt.version, 0);
+        assert_eq!(dt.get_min_writer_version(), 2);
+
+        let (_tmp_dir, mut dt) = new_table();
+        let mut delta_md = metadata_with_columns(&["id", "new column"]);
+        delta_md.configuration.insert(
+            "delta.columnMapping.mode".to_string(),
+            Some("name".to_string()),
+        );
+        dt.create(delta_md, protocol, None).await.unwrap();
+        assert_eq!(dt.get_min_reader_version(), 2);
+        assert_eq!(dt.get_min_writer_version(), 5);
+
+        // Columns added to a table that uses column mapping get ids
+        let mut new_md = dt.get_metadata().unwrap().clone();
+        let mut fields = new_md.schema.get_fields().clone();
+        fields.push(SchemaField::new(
+            "third".to_string(),
+            SchemaDataType::primitive("string".to_string()),
+            true,
+            HashMap::new(),
+        ));
+        new_md.schema = Schema::new(fields);
+        let mut transaction = dt.create_transaction(None);
+        transaction.add_actions(vec![Action::metaData(
+            action::MetaData::try_from(new_md).unwrap(),
+        )]);
+        transaction.commit(None).await.unwrap();
+        dt.update().await.unwrap();
+
+        let metadata = dt.get_metadata().unwrap();
+        assert_eq!(
+            metadata
+                .configuration
+                .get("delta.columnMapping.maxColumnId"),
+            Some(&Some("3".to_string()))
+        );
+        let third = &metadata.schema.get_fields()[2];
+        assert_eq!(
+            third.get_metadata().get(COLUMN_MAPPING_ID_KEY),
+            Some(&"3".to_string())
+        );
+        assert!(third
+            .get_metadata()
+            .contains_key(COLUMN_MAPPING_PHYSICAL_NAME_KEY));
+    }
 }
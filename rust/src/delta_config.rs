@@ -2,11 +2,16 @@
 
 use crate::{DeltaDataTypeInt, DeltaDataTypeLong, DeltaTableMetaData};
 use lazy_static::lazy_static;
+use log::*;
+use std::collections::HashMap;
 use std::time::Duration;
 
 lazy_static! {
+    /// Whether the table only allows appending data. Commits removing data files are rejected.
+    pub static ref APPEND_ONLY: DeltaConfig = DeltaConfig::new("delta.appendOnly", "false");
+
     /// How often to checkpoint the delta log.
-    pub static ref CHECKPOINT_INTERVAL: DeltaConfig = DeltaConfig::new("checkpointInterval", "10");
+    pub static ref CHECKPOINT_INTERVAL: DeltaConfig = DeltaConfig::new("delta.checkpointInterval", "10");
 
     /// The shortest duration we have to keep logically deleted data files around before deleting
     /// them physically.
@@ -17,15 +22,32 @@ lazy_static! {
     ///  doesn't stop longer than this value. Otherwise, the query may not be able to restart as it
     ///  still needs to read old files.
     pub static ref TOMBSTONE_RETENTION: DeltaConfig =
-        DeltaConfig::new("deletedFileRetentionDuration", "interval 1 week");
+        DeltaConfig::new("delta.deletedFileRetentionDuration", "interval 1 week");
 
     /// The shortest duration we have to keep delta files around before deleting them. We can only
     /// delete delta files that are before a compaction. We may keep files beyond this duration until
     /// the next calendar day.
-    pub static ref LOG_RETENTION: DeltaConfig = DeltaConfig::new("logRetentionDuration", "interval 30 day");
+    pub static ref LOG_RETENTION: DeltaConfig = DeltaConfig::new("delta.logRetentionDuration", "interval 30 day");
 
     /// Whether to clean up expired checkpoints and delta logs.
-    pub static ref ENABLE_EXPIRED_LOG_CLEANUP: DeltaConfig = DeltaConfig::new("enableExpiredLogCleanup", "true");
+    pub static ref ENABLE_EXPIRED_LOG_CLEANUP: DeltaConfig = DeltaConfig::new("delta.enableExpiredLogCleanup", "true");
+
+    /// The target size of data files in bytes.
+    pub static ref TARGET_FILE_SIZE: DeltaConfig = DeltaConfig::new("delta.targetFileSize", "104857600");
+
+    /// Whether the changes of rows are recorded in the change data feed.
+    pub static ref ENABLE_CHANGE_DATA_FEED: DeltaConfig = DeltaConfig::new("delta.enableChangeDataFeed", "false");
+
+    /// How logical column names are mapped to the column names of the data files: `none`, `name`
+    /// or `id`.
+    pub static ref COLUMN_MAPPING_MODE: DeltaConfig = DeltaConfig::new("delta.columnMapping.mode", "none");
+
+    /// The highest column id assigned by column mapping.
+    pub static ref COLUMN_MAPPING_MAX_COLUMN_ID: DeltaConfig = DeltaConfig::new("delta.columnMapping.maxColumnId", "0");
+
+    /// Whether to update the symlink format manifests after each commit.
+    pub static ref SYMLINK_FORMAT_MANIFEST_ENABLED: DeltaConfig =
+        DeltaConfig::new("delta.compatibility.symlinkFormatManifest.enabled", "false");
 }
 
 /// Delta configuration error
@@ -34,6 +56,130 @@ pub enum DeltaConfigError {
     /// Error returned when configuration validation failed.
     #[error("Validation failed - {0}")]
     Validation(String),
+    /// Error returned when a table property has a malformed value.
+    #[error("Invalid value of table property {key} - {msg}")]
+    InvalidProperty {
+        /// The key of the property
+        key: String,
+        /// Why the value is invalid
+        msg: String,
+    },
+}
+
+/// The typed values of the table properties defined by the protocol, read from
+/// `metadata.configuration` with the defaults of the protocol for missing properties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableProperties {
+    /// `delta.appendOnly`
+    pub append_only: bool,
+    /// `delta.checkpointInterval`, the number of commits between checkpoints
+    pub checkpoint_interval: DeltaDataTypeInt,
+    /// `delta.deletedFileRetentionDuration`
+    pub deleted_file_retention_duration: Duration,
+    /// `delta.logRetentionDuration`
+    pub log_retention_duration: Duration,
+    /// `delta.enableExpiredLogCleanup`
+    pub enable_expired_log_cleanup: bool,
+    /// `delta.targetFileSize` in bytes
+    pub target_file_size: DeltaDataTypeLong,
+    /// `delta.enableChangeDataFeed`
+    pub enable_change_data_feed: bool,
+    /// `delta.columnMapping.mode`: `none`, `name` or `id`
+    pub column_mapping_mode: String,
+    /// `delta.columnMapping.maxColumnId`
+    pub column_mapping_max_column_id: DeltaDataTypeLong,
+    /// `delta.compatibility.symlinkFormatManifest.enabled`
+    pub symlink_format_manifest_enabled: bool,
+}
+
+impl TableProperties {
+    /// Reads the properties of `metadata`. Malformed values are logged and replaced by their
+    /// defaults.
+    pub fn from_metadata(metadata: &DeltaTableMetaData) -> Self {
+        Self::from_configuration(&metadata.configuration)
+    }
+
+    /// Reads the properties of a table configuration. Malformed values are logged and replaced
+    /// by their defaults.
+    pub fn from_configuration(configuration: &HashMap<String, Option<String>>) -> Self {
+        let (properties, errors) = Self::parse(configuration);
+        for error in errors {
+            warn!("Using the default instead: {}", error);
+        }
+        properties
+    }
+
+    /// Reads the properties of a table configuration, failing on the first malformed value.
+    pub fn try_from_configuration(
+        configuration: &HashMap<String, Option<String>>,
+    ) -> Result<Self, DeltaConfigError> {
+        let (properties, errors) = Self::parse(configuration);
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(properties),
+        }
+    }
+
+    /// Returns a [`DeltaConfigError::InvalidProperty`] for each malformed value of a table
+    /// configuration.
+    pub fn validate(configuration: &HashMap<String, Option<String>>) -> Vec<DeltaConfigError> {
+        Self::parse(configuration).1
+    }
+
+    fn parse(configuration: &HashMap<String, Option<String>>) -> (Self, Vec<DeltaConfigError>) {
+        let mut errors = vec![];
+        let properties = Self {
+            append_only: APPEND_ONLY.parse(configuration, parse_bool, &mut errors),
+            checkpoint_interval: CHECKPOINT_INTERVAL.parse(
+                configuration,
+                |v| Ok(parse_positive_int(v)? as DeltaDataTypeInt),
+                &mut errors,
+            ),
+            deleted_file_retention_duration: TOMBSTONE_RETENTION.parse(
+                configuration,
+                parse_interval,
+                &mut errors,
+            ),
+            log_retention_duration: LOG_RETENTION.parse(configuration, parse_interval, &mut errors),
+            enable_expired_log_cleanup: ENABLE_EXPIRED_LOG_CLEANUP.parse(
+                configuration,
+                parse_bool,
+                &mut errors,
+            ),
+            target_file_size: TARGET_FILE_SIZE.parse(
+                configuration,
+                parse_positive_int,
+                &mut errors,
+            ),
+            enable_change_data_feed: ENABLE_CHANGE_DATA_FEED.parse(
+                configuration,
+                parse_bool,
+                &mut errors,
+            ),
+            column_mapping_mode: COLUMN_MAPPING_MODE.parse(
+                configuration,
+                parse_column_mapping_mode,
+                &mut errors,
+            ),
+            column_mapping_max_column_id: COLUMN_MAPPING_MAX_COLUMN_ID.parse(
+                configuration,
+                parse_int,
+                &mut errors,
+            ),
+            symlink_format_manifest_enabled: SYMLINK_FORMAT_MANIFEST_ENABLED.parse(
+                configuration,
+                parse_bool,
+                &mut errors,
+            ),
+        };
+        (properties, errors)
+    }
+}
+
+impl Default for TableProperties {
+    fn default() -> Self {
+        Self::from_configuration(&HashMap::new())
+    }
 }
 
 /// Delta table's `metadata.configuration` entry.
@@ -93,13 +239,47 @@ impl DeltaConfig {
         parse_bool(&self.get_raw_from_metadata(metadata))
     }
 
-    fn get_raw_from_metadata(&self, metadata: &DeltaTableMetaData) -> String {
-        metadata
-            .configuration
+    /// Returns the raw value from `metadata.configuration` for `self.key`.
+    /// If it's missing in metadata then the `self.default` is used.
+    pub fn get_raw_from_metadata(&self, metadata: &DeltaTableMetaData) -> String {
+        self.get_raw(&metadata.configuration).to_string()
+    }
+
+    /// Returns the raw value of `self.key` in `configuration`. Older versions of this crate read
+    /// and wrote the keys without the `delta.` prefix, so these are used as a fallback.
+    fn get_raw<'a>(&'a self, configuration: &'a HashMap<String, Option<String>>) -> &'a str {
+        configuration
             .get(&self.key)
+            .or_else(|| {
+                self.key
+                    .strip_prefix("delta.")
+                    .and_then(|legacy_key| configuration.get(legacy_key))
+            })
             .and_then(|opt| opt.as_deref())
             .unwrap_or_else(|| self.default.as_str())
-            .to_string()
+    }
+
+    /// Parses the value of `self.key` in `configuration`. A malformed value is recorded in
+    /// `errors` and the default is returned instead.
+    fn parse<T>(
+        &self,
+        configuration: &HashMap<String, Option<String>>,
+        parse: impl Fn(&str) -> Result<T, DeltaConfigError>,
+        errors: &mut Vec<DeltaConfigError>,
+    ) -> T {
+        match parse(self.get_raw(configuration)) {
+            Ok(value) => value,
+            Err(error) => {
+                errors.push(DeltaConfigError::InvalidProperty {
+                    key: self.key.clone(),
+                    msg: match error {
+                        DeltaConfigError::Validation(msg) => msg,
+                        error => error.to_string(),
+                    },
+                });
+                parse(&self.default).expect("the defaults of table properties are valid")
+            }
+        }
     }
 }
 
@@ -108,14 +288,17 @@ const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
 const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
 const SECONDS_PER_WEEK: u64 = 7 * SECONDS_PER_DAY;
 
+/// Parses the `interval <number> <unit>` grammar of durations. Like in Spark, the keyword and the
+/// unit are case-insensitive and units may be plural.
 fn parse_interval(value: &str) -> Result<Duration, DeltaConfigError> {
     let not_an_interval =
         || DeltaConfigError::Validation(format!("'{}' is not an interval", value));
 
-    if !value.starts_with("interval ") {
+    let lowercase = value.to_lowercase();
+    if !lowercase.starts_with("interval ") {
         return Err(not_an_interval());
     }
-    let mut it = value.split_whitespace();
+    let mut it = lowercase.split_whitespace();
     let _ = it.next(); // skip "interval"
     let number = parse_int(it.next().ok_or_else(not_an_interval)?)?;
     if number < 0 {
@@ -126,7 +309,12 @@ fn parse_interval(value: &str) -> Result<Duration, DeltaConfigError> {
     }
     let number = number as u64;
 
-    let duration = match it.next().ok_or_else(not_an_interval)? {
+    let unit = it.next().ok_or_else(not_an_interval)?;
+    if it.next().is_some() {
+        return Err(not_an_interval());
+    }
+
+    let duration = match unit.strip_suffix('s').unwrap_or(unit) {
         "nanosecond" => Duration::from_nanos(number),
         "microsecond" => Duration::from_micros(number),
         "millisecond" => Duration::from_millis(number),
@@ -135,7 +323,7 @@ fn parse_interval(value: &str) -> Result<Duration, DeltaConfigError> {
         "hour" => Duration::from_secs(number * SECONDS_PER_HOUR),
         "day" => Duration::from_secs(number * SECONDS_PER_DAY),
         "week" => Duration::from_secs(number * SECONDS_PER_WEEK),
-        unit => {
+        _ => {
             return Err(DeltaConfigError::Validation(format!(
                 "Unknown unit '{}'",
                 unit
@@ -152,6 +340,27 @@ fn parse_int(value: &str) -> Result<i64, DeltaConfigError> {
     })
 }
 
+fn parse_positive_int(value: &str) -> Result<i64, DeltaConfigError> {
+    let number = parse_int(value)?;
+    if number <= 0 {
+        return Err(DeltaConfigError::Validation(format!(
+            "'{}' is not a positive integer",
+            value
+        )));
+    }
+    Ok(number)
+}
+
+fn parse_column_mapping_mode(value: &str) -> Result<String, DeltaConfigError> {
+    match value {
+        "none" | "name" | "id" => Ok(value.to_string()),
+        _ => Err(DeltaConfigError::Validation(format!(
+            "Unknown column mapping mode '{}'",
+            value
+        ))),
+    }
+}
+
 fn parse_bool(value: &str) -> Result<bool, DeltaConfigError> {
     value.parse().map_err(|e| {
         DeltaConfigError::Validation(format!("Cannot parse '{}' as bool: {}", value, e))
@@ -216,6 +425,134 @@ mod tests {
         )
     }
 
+    #[test]
+    fn table_properties_defaults_test() {
+        let properties = TableProperties::from_metadata(&dummy_metadata());
+        assert_eq!(
+            properties,
+            TableProperties {
+                append_only: false,
+                checkpoint_interval: 10,
+                deleted_file_retention_duration: Duration::from_secs(SECONDS_PER_WEEK),
+                log_retention_duration: Duration::from_secs(30 * SECONDS_PER_DAY),
+                enable_expired_log_cleanup: true,
+                target_file_size: 104857600,
+                enable_change_data_feed: false,
+                column_mapping_mode: "none".to_string(),
+                column_mapping_max_column_id: 0,
+                symlink_format_manifest_enabled: false,
+            }
+        );
+        assert_eq!(properties, TableProperties::default());
+    }
+
+    #[test]
+    fn table_properties_from_configuration_test() {
+        let configuration: HashMap<String, Option<String>> = [
+            ("delta.appendOnly", "true"),
+            ("delta.checkpointInterval", "5"),
+            ("delta.deletedFileRetentionDuration", "interval 2 days"),
+            ("delta.logRetentionDuration", "INTERVAL 12 Hours"),
+            ("delta.enableExpiredLogCleanup", "false"),
+            ("delta.targetFileSize", "1048576"),
+            ("delta.enableChangeDataFeed", "true"),
+            ("delta.columnMapping.mode", "name"),
+            ("delta.columnMapping.maxColumnId", "7"),
+            ("delta.compatibility.symlinkFormatManifest.enabled", "true"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), Some(v.to_string())))
+        .collect();
+
+        assert!(TableProperties::validate(&configuration).is_empty());
+        assert_eq!(
+            TableProperties::try_from_configuration(&configuration).unwrap(),
+            TableProperties {
+                append_only: true,
+                checkpoint_interval: 5,
+                deleted_file_retention_duration: Duration::from_secs(2 * SECONDS_PER_DAY),
+                log_retention_duration: Duration::from_secs(12 * SECONDS_PER_HOUR),
+                enable_expired_log_cleanup: false,
+                target_file_size: 1048576,
+                enable_change_data_feed: true,
+                column_mapping_mode: "name".to_string(),
+                column_mapping_max_column_id: 7,
+                symlink_format_manifest_enabled: true,
+            }
+        );
+    }
+
+    #[test]
+    fn table_properties_legacy_keys_test() {
+        let mut md = dummy_metadata();
+        md.configuration.insert(
+            "deletedFileRetentionDuration".to_string(),
+            Some("interval 3 day".to_string()),
+        );
+        assert_eq!(
+            TableProperties::from_metadata(&md).deleted_file_retention_duration,
+            Duration::from_secs(3 * SECONDS_PER_DAY)
+        );
+
+        // the prefixed key takes precedence
+        md.configuration.insert(
+            TOMBSTONE_RETENTION.key.to_string(),
+            Some("interval 4 day".to_string()),
+        );
+        assert_eq!(
+            TableProperties::from_metadata(&md).deleted_file_retention_duration,
+            Duration::from_secs(4 * SECONDS_PER_DAY)
+        );
+    }
+
+    #[test]
+    fn table_properties_malformed_values_test() {
+        let configuration: HashMap<String, Option<String>> = [
+            ("delta.appendOnly", "yes"),
+            ("delta.checkpointInterval", "0"),
+            ("delta.logRetentionDuration", "30 days"),
+            ("delta.columnMapping.mode", "position"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), Some(v.to_string())))
+        .collect();
+
+        let mut keys = TableProperties::validate(&configuration)
+            .into_iter()
+            .map(|error| match error {
+                DeltaConfigError::InvalidProperty { key, .. } => key,
+                error => panic!("unexpected error {:?}", error),
+            })
+            .collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "delta.appendOnly",
+                "delta.checkpointInterval",
+                "delta.columnMapping.mode",
+                "delta.logRetentionDuration",
+            ]
+        );
+
+        assert_eq!(
+            TableProperties::try_from_configuration(&configuration)
+                .err()
+                .unwrap(),
+            DeltaConfigError::InvalidProperty {
+                key: "delta.appendOnly".to_string(),
+                msg: "Cannot parse 'yes' as bool: provided string was not `true` or `false`"
+                    .to_string(),
+            }
+        );
+
+        // malformed values fall back to the defaults
+        assert_eq!(
+            TableProperties::from_configuration(&configuration),
+            TableProperties::default()
+        );
+    }
+
     #[test]
     fn parse_interval_test() {
         assert_eq!(
@@ -257,6 +594,16 @@ mod tests {
             parse_interval("interval 123 week").unwrap(),
             Duration::from_secs(123 * 604800)
         );
+
+        assert_eq!(
+            parse_interval("interval 2 weeks").unwrap(),
+            Duration::from_secs(2 * 604800)
+        );
+
+        assert_eq!(
+            parse_interval("Interval 1 DAY").unwrap(),
+            Duration::from_secs(86400)
+        );
     }
 
     #[test]
@@ -276,6 +623,11 @@ mod tests {
             DeltaConfigError::Validation("'interval 2' is not an interval".to_string())
         );
 
+        assert_eq!(
+            parse_interval("interval 2 day extra").err().unwrap(),
+            DeltaConfigError::Validation("'interval 2 day extra' is not an interval".to_string())
+        );
+
         assert_eq!(
             parse_interval("interval 2 years").err().unwrap(),
             DeltaConfigError::Validation("Unknown unit 'years'".to_string())
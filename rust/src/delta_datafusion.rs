@@ -21,6 +21,7 @@
 //! ```
 
 use std::any::Any;
+use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
@@ -39,6 +40,7 @@ use datafusion::scalar::ScalarValue;
 use crate::action;
 use crate::delta;
 use crate::schema;
+use crate::writer::decimal_utils::decimal_from_stats_value;
 
 impl delta::DeltaTable {
     /// Return statistics for Datafusion Table
@@ -92,6 +94,7 @@ impl delta::DeltaTable {
                                     max_value: new_stats
                                         .max_values
                                         .get(field.get_name())
+                                        .filter(|_| !is_decimal_field(field))
                                         .and_then(|x| {
                                             let old_stats = stats.clone();
                                             let max_value = to_scalar_value(x.as_value()?);
@@ -115,6 +118,7 @@ impl delta::DeltaTable {
                                     min_value: new_stats
                                         .min_values
                                         .get(field.get_name())
+                                        .filter(|_| !is_decimal_field(field))
                                         .and_then(|x| {
                                             let old_stats = stats.clone();
                                             let min_value = to_scalar_value(x.as_value()?);
@@ -144,6 +148,7 @@ impl delta::DeltaTable {
                 },
             )
             .unwrap_or_default();
+        let unbounded_columns = self.unbounded_stats_columns();
         // Convert column max/min scalar values to correct types based on arrow types.
         Statistics {
             is_exact: true,
@@ -161,6 +166,26 @@ impl delta::DeltaTable {
                             .unwrap()
                             .data_type()
                             .clone();
+                        if let ArrowDataType::Decimal(precision, scale) = dt {
+                            let (min, max) =
+                                self.decimal_stats_bounds(field.get_name(), precision, scale);
+                            return ColumnStatistics {
+                                null_count: col_states.null_count,
+                                max_value: max
+                                    .map(|v| ScalarValue::Decimal128(Some(v), precision, scale)),
+                                min_value: min
+                                    .map(|v| ScalarValue::Decimal128(Some(v), precision, scale)),
+                                distinct_count: col_states.distinct_count,
+                            };
+                        }
+                        if unbounded_columns.contains(field.get_name()) {
+                            return ColumnStatistics {
+                                null_count: col_states.null_count,
+                                max_value: None,
+                                min_value: None,
+                                distinct_count: col_states.distinct_count,
+                            };
+                        }
                         ColumnStatistics {
                             null_count: col_states.null_count,
                             max_value: col_states
@@ -180,6 +205,67 @@ impl delta::DeltaTable {
     }
 }
 
+impl delta::DeltaTable {
+    /// Names of the columns for which at least one file has a min or max value that cannot be
+    /// compared as a number. This includes `NaN`, `Infinity` and numbers exceeding the precision
+    /// of a double, which are kept as strings in the stats. Such a value does not bound the
+    /// column, so the table wide bounds are unknown.
+    fn unbounded_stats_columns(&self) -> HashSet<String> {
+        let mut columns = HashSet::new();
+        for add in self.get_active_add_actions() {
+            if let Ok(Some(stats)) = add.get_stats() {
+                for (name, stat) in stats.min_values.iter().chain(stats.max_values.iter()) {
+                    if stat.as_value().map_or(false, |v| v.is_string()) {
+                        columns.insert(name.clone());
+                    }
+                }
+            }
+        }
+        columns
+    }
+
+    /// Table wide bounds of a decimal column as unscaled values. The min and max values of each
+    /// file are parsed exactly at the column's scale, rounding mins down and maxes up, so that
+    /// values beyond the precision of a double never exclude rows of a file. The bounds are
+    /// unknown if any file lacks a parseable min or max value.
+    fn decimal_stats_bounds(
+        &self,
+        name: &str,
+        precision: usize,
+        scale: usize,
+    ) -> (Option<i128>, Option<i128>) {
+        let mut bounds: Option<(i128, i128)> = None;
+        for add in self.get_active_add_actions() {
+            let stats = match add.get_stats() {
+                Ok(Some(stats)) => stats,
+                _ => return (None, None),
+            };
+            let file_min = stats
+                .min_values
+                .get(name)
+                .and_then(|v| v.as_value())
+                .and_then(|v| decimal_from_stats_value(v, precision, scale, false));
+            let file_max = stats
+                .max_values
+                .get(name)
+                .and_then(|v| v.as_value())
+                .and_then(|v| decimal_from_stats_value(v, precision, scale, true));
+            bounds = match (file_min, file_max, bounds) {
+                (Some(file_min), Some(file_max), Some((min, max))) => {
+                    Some((min.min(file_min), max.max(file_max)))
+                }
+                (Some(file_min), Some(file_max), None) => Some((file_min, file_max)),
+                _ => return (None, None),
+            };
+        }
+        (bounds.map(|b| b.0), bounds.map(|b| b.1))
+    }
+}
+
+fn is_decimal_field(field: &schema::SchemaField) -> bool {
+    matches!(field.get_type(), schema::SchemaDataType::primitive(t) if t.starts_with("decimal"))
+}
+
 // TODO: uncomment this when datafusion supports per partitioned file stats
 // fn add_action_df_stats(add: &action::Add, schema: &schema::Schema) -> Statistics {
 //     if let Ok(Some(statistics)) = add.get_stats() {
@@ -314,10 +400,6 @@ fn correct_scalar_value_type(
             let raw_value = f64::try_from(value).unwrap();
             Some(ScalarValue::from(raw_value))
         }
-        ArrowDataType::Decimal(_, _) => {
-            let raw_value = f64::try_from(value).unwrap();
-            Some(ScalarValue::from(raw_value))
-        }
         ArrowDataType::Date32 => {
             let raw_value = i64::try_from(value).unwrap() as i32;
             Some(ScalarValue::Date32(Some(raw_value)))
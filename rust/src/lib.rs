@@ -88,15 +88,20 @@ extern crate serde_json;
 extern crate thiserror;
 
 pub mod action;
+pub mod audit;
 pub mod checkpoints;
 pub mod data_catalog;
 mod delta;
 pub mod delta_arrow;
 pub mod delta_config;
+pub mod manifest;
 pub mod partitions;
+pub mod predicate;
 pub mod schema;
 pub mod storage;
 mod table_state;
+mod table_state_arrow;
+pub mod uniform;
 pub mod writer;
 
 #[cfg(feature = "datafusion-ext")]
@@ -110,8 +115,8 @@ pub use self::delta::*;
 pub use self::partitions::*;
 pub use self::schema::*;
 pub use self::storage::{
-    get_backend_for_uri, get_backend_for_uri_with_options, parse_uri, StorageBackend, StorageError,
-    Uri, UriError,
+    get_backend_for_uri, get_backend_for_uri_with_options, normalize_table_uri, parse_uri,
+    StorageBackend, StorageError, Uri, UriError,
 };
 
 #[cfg(feature = "s3")]
@@ -0,0 +1,103 @@
+//! Generation of symlink format manifests, which let Presto, Trino and Athena read the current
+//! snapshot of a delta table.
+
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use log::*;
+use std::collections::BTreeMap;
+
+use super::storage::{StorageBackend, StorageError};
+use super::{DeltaTable, DeltaTableError, NULL_PARTITION_VALUE_DATA_PATH};
+
+/// Directory below the table root holding the manifests
+pub const MANIFEST_DIR: &str = "_symlink_format_manifest";
+
+/// Name of the manifest file in each partition directory
+pub const MANIFEST_FILE: &str = "manifest";
+
+/// Writes a manifest of the current snapshot of `table` for each of its partitions, listing the
+/// absolute URIs of the partition's data files, one per line. Manifests of partitions that no
+/// longer have any files are deleted.
+pub async fn generate_symlink_format_manifest(table: &DeltaTable) -> Result<(), DeltaTableError> {
+    let partition_columns = &table.get_metadata()?.partition_columns;
+    let storage = table.storage.as_ref();
+    let manifest_root = storage.join_path(&table.table_uri, MANIFEST_DIR);
+
+    let mut manifests: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for add in table.get_state().files() {
+        let mut path = vec![manifest_root.as_str()];
+        let partition_dirs = partition_columns
+            .iter()
+            .map(|column| {
+                let value = add
+                    .partition_values
+                    .get(column)
+                    .cloned()
+                    .flatten()
+                    .unwrap_or_else(|| NULL_PARTITION_VALUE_DATA_PATH.to_string());
+                format!("{}={}", column, value)
+            })
+            .collect::<Vec<_>>();
+        path.extend(partition_dirs.iter().map(String::as_str));
+        path.push(MANIFEST_FILE);
+        manifests
+            .entry(storage.join_paths(&path))
+            .or_default()
+            .push(storage.join_path(&table.table_uri, &add.path));
+    }
+
+    for (manifest_uri, files) in &manifests {
+        let mut content = files.join("\n");
+        content.push('\n');
+        storage.put_obj(manifest_uri, content.as_bytes()).await?;
+    }
+
+    let stale_manifests = list_manifests(storage, manifest_root)
+        .await?
+        .into_iter()
+        .filter(|path| !manifests.contains_key(path))
+        .collect::<Vec<_>>();
+    storage.delete_objs(&stale_manifests).await?;
+
+    debug!(
+        "Wrote {} and deleted {} symlink format manifests of {} at version {}.",
+        manifests.len(),
+        stale_manifests.len(),
+        table.table_uri,
+        table.version
+    );
+    Ok(())
+}
+
+/// Lists the manifests below `dir`. Backends with hierarchical listings return the partition
+/// directories, which are listed in turn.
+fn list_manifests(
+    storage: &dyn StorageBackend,
+    dir: String,
+) -> BoxFuture<'_, Result<Vec<String>, StorageError>> {
+    Box::pin(async move {
+        let mut objects = match storage.list_objs(&dir).await {
+            Ok(objects) => objects,
+            Err(StorageError::NotFound) => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+
+        let mut manifests = vec![];
+        let mut partition_dirs = vec![];
+        while let Some(object) = objects.next().await {
+            let path = object?.path;
+            let name = path.rsplit('/').next().unwrap_or_default();
+            if name == MANIFEST_FILE {
+                manifests.push(path);
+            } else if name.contains('=') && path.trim_end_matches('/') != dir {
+                partition_dirs.push(path);
+            }
+        }
+        drop(objects);
+
+        for partition_dir in partition_dirs {
+            manifests.extend(list_manifests(storage, partition_dir).await?);
+        }
+        Ok(manifests)
+    })
+}
@@ -0,0 +1,1218 @@
+//! Simple predicates on the columns of a table, like `date >= '2021-01-01' AND status = 'ok'`,
+//! and the pruning of the files of a table with them.
+//!
+//! Predicates are parsed from a small subset of SQL: comparisons of a column with a literal
+//! (`=`, `!=`, `<>`, `<`, `<=`, `>`, `>=`), `[NOT] IN (...)`, `IS [NOT] NULL`, combined with
+//! `AND`, `OR`, `NOT` and parentheses. Nested columns are referred to by their dotted path and
+//! names with special characters can be quoted with backticks, e.g. `` `my col`.city ``.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{json, Number, Value};
+
+use super::action::{Add, Stats};
+use super::schema::{ColumnResolution, SchemaDataType, SchemaField};
+use super::table_state::DeltaTableState;
+use super::table_state_arrow::{
+    count_stat, days_since_epoch, stats_name, timestamp_micros, value_stat,
+};
+use super::writer::decimal_utils;
+use super::DeltaTableError;
+
+/// The largest precision of decimals, values of numeric columns are compared as decimals.
+const MAX_DECIMAL_PRECISION: usize = 38;
+
+/// A predicate on the columns of a table.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Predicate {
+    /// Both predicates hold.
+    And(Box<Predicate>, Box<Predicate>),
+    /// Any of the predicates holds.
+    Or(Box<Predicate>, Box<Predicate>),
+    /// The predicate does not hold.
+    Not(Box<Predicate>),
+    /// The column compares to the value, which is never the case for nulls.
+    Comparison {
+        /// The path of the column, a single name for top level columns.
+        column: Vec<String>,
+        /// The comparison operator.
+        op: ComparisonOperator,
+        /// The value the column is compared to.
+        value: Literal,
+    },
+    /// The column is one of the values, or none of them if `negated` is set.
+    In {
+        /// The path of the column, a single name for top level columns.
+        column: Vec<String>,
+        /// The values of the list.
+        values: Vec<Literal>,
+        /// Whether the predicate is `NOT IN`.
+        negated: bool,
+    },
+    /// The column is null, or not null if `negated` is set.
+    IsNull {
+        /// The path of the column, a single name for top level columns.
+        column: Vec<String>,
+        /// Whether the predicate is `IS NOT NULL`.
+        negated: bool,
+    },
+}
+
+/// The operator of a comparison predicate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComparisonOperator {
+    /// `=`
+    Equal,
+    /// `!=` or `<>`
+    NotEqual,
+    /// `<`
+    LessThan,
+    /// `<=`
+    LessThanOrEqual,
+    /// `>`
+    GreaterThan,
+    /// `>=`
+    GreaterThanOrEqual,
+}
+
+impl ComparisonOperator {
+    fn negate(self) -> Self {
+        match self {
+            ComparisonOperator::Equal => ComparisonOperator::NotEqual,
+            ComparisonOperator::NotEqual => ComparisonOperator::Equal,
+            ComparisonOperator::LessThan => ComparisonOperator::GreaterThanOrEqual,
+            ComparisonOperator::LessThanOrEqual => ComparisonOperator::GreaterThan,
+            ComparisonOperator::GreaterThan => ComparisonOperator::LessThanOrEqual,
+            ComparisonOperator::GreaterThanOrEqual => ComparisonOperator::LessThan,
+        }
+    }
+
+    /// The operator comparing the operands the other way around, e.g. `>` for `<`.
+    fn flip(self) -> Self {
+        match self {
+            ComparisonOperator::LessThan => ComparisonOperator::GreaterThan,
+            ComparisonOperator::LessThanOrEqual => ComparisonOperator::GreaterThanOrEqual,
+            ComparisonOperator::GreaterThan => ComparisonOperator::LessThan,
+            ComparisonOperator::GreaterThanOrEqual => ComparisonOperator::LessThanOrEqual,
+            op => op,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            ComparisonOperator::Equal => "=",
+            ComparisonOperator::NotEqual => "!=",
+            ComparisonOperator::LessThan => "<",
+            ComparisonOperator::LessThanOrEqual => "<=",
+            ComparisonOperator::GreaterThan => ">",
+            ComparisonOperator::GreaterThanOrEqual => ">=",
+        }
+    }
+}
+
+/// A literal value of a predicate, converted to the type of the column it is compared to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Literal {
+    /// `TRUE` or `FALSE`.
+    Boolean(bool),
+    /// A number, as written in the predicate.
+    Number(String),
+    /// A string quoted with single quotes, also used for dates and timestamps.
+    String(String),
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Boolean(value) => write!(f, "{}", value),
+            Literal::Number(value) => write!(f, "{}", value),
+            Literal::String(value) => write!(f, "'{}'", value.replace('\'', "''")),
+        }
+    }
+}
+
+impl Literal {
+    fn to_json(&self) -> Value {
+        match self {
+            Literal::Boolean(value) => Value::Bool(*value),
+            Literal::Number(value) | Literal::String(value) => Value::String(value.clone()),
+        }
+    }
+}
+
+impl FromStr for Predicate {
+    type Err = DeltaTableError;
+
+    /// Parses a predicate, returns a [`DeltaTableError::InvalidPredicate`] if it is malformed.
+    fn from_str(predicate: &str) -> Result<Self, DeltaTableError> {
+        let invalid = |msg: String| DeltaTableError::InvalidPredicate {
+            predicate: predicate.to_string(),
+            msg,
+        };
+        let mut parser = Parser {
+            tokens: tokenize(predicate).map_err(invalid)?,
+            position: 0,
+        };
+        let parsed = parser.or().map_err(invalid)?;
+        match parser.next() {
+            None => Ok(parsed),
+            Some(token) => Err(invalid(format!("unexpected {}", token))),
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    /// Formats the predicate so that it parses to the same predicate again.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Predicate::And(left, right) => write!(f, "({} AND {})", left, right),
+            Predicate::Or(left, right) => write!(f, "({} OR {})", left, right),
+            Predicate::Not(predicate) => write!(f, "NOT {}", predicate),
+            Predicate::Comparison { column, op, value } => {
+                write!(f, "{} {} {}", ColumnPath(column), op.as_str(), value)
+            }
+            Predicate::In {
+                column,
+                values,
+                negated,
+            } => {
+                let values = values.iter().map(Literal::to_string).collect::<Vec<_>>();
+                let not = if *negated { "NOT " } else { "" };
+                write!(
+                    f,
+                    "{} {}IN ({})",
+                    ColumnPath(column),
+                    not,
+                    values.join(", ")
+                )
+            }
+            Predicate::IsNull { column, negated } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{} IS {}NULL", ColumnPath(column), not)
+            }
+        }
+    }
+}
+
+/// Formats the path of a column, quoting the names that would not parse as names.
+struct ColumnPath<'a>(&'a [String]);
+
+impl fmt::Display for ColumnPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, name) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            let plain = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                && !is_reserved(name)
+                && !name.eq_ignore_ascii_case("true")
+                && !name.eq_ignore_ascii_case("false");
+            if plain {
+                write!(f, "{}", name)?;
+            } else {
+                write!(f, "{}", Token::QuotedName(name.clone()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Predicate {
+    /// Returns the add actions of the files of the state that may contain rows matching the
+    /// predicate according to their partition values and statistics. Files without statistics
+    /// for a column of the predicate are kept.
+    pub(crate) fn prune<'a>(
+        &self,
+        state: &'a DeltaTableState,
+        column_resolution: ColumnResolution,
+    ) -> Result<Vec<&'a Add>, DeltaTableError> {
+        let metadata = state
+            .current_metadata()
+            .ok_or(DeltaTableError::NoMetadata)?;
+        let table = TableColumns {
+            fields: metadata.schema.get_fields(),
+            partition_columns: &metadata.partition_columns,
+            column_resolution,
+        };
+        let condition = self.resolve(&table, false)?;
+        Ok(state
+            .files()
+            .iter()
+            .filter(|add| {
+                let stats = add.get_stats().ok().flatten();
+                condition.may_match(add, stats.as_ref())
+            })
+            .collect())
+    }
+
+    /// Returns the add actions of the files of the state whose partition values match the
+    /// predicate, e.g. the files replaced by an overwrite of the matching partitions. Fails if the
+    /// predicate refers to columns that are not partition columns.
+    pub(crate) fn partition_files<'a>(
+        &self,
+        state: &'a DeltaTableState,
+        column_resolution: ColumnResolution,
+    ) -> Result<Vec<&'a Add>, DeltaTableError> {
+        let condition = self.partition_condition(state, column_resolution)?;
+        Ok(state
+            .files()
+            .iter()
+            .filter(|add| condition.may_match(add, None))
+            .collect())
+    }
+
+    /// Whether rows with the given partition values match the predicate, which may only refer to
+    /// partition columns like for [`Predicate::partition_files`].
+    pub(crate) fn matches_partition_values(
+        &self,
+        state: &DeltaTableState,
+        column_resolution: ColumnResolution,
+        partition_values: &HashMap<String, Option<String>>,
+    ) -> Result<bool, DeltaTableError> {
+        let condition = self.partition_condition(state, column_resolution)?;
+        let add = Add {
+            partition_values: partition_values.clone(),
+            ..Default::default()
+        };
+        Ok(condition.may_match(&add, None))
+    }
+
+    fn partition_condition<'a>(
+        &self,
+        state: &'a DeltaTableState,
+        column_resolution: ColumnResolution,
+    ) -> Result<Condition<'a>, DeltaTableError> {
+        let metadata = state
+            .current_metadata()
+            .ok_or(DeltaTableError::NoMetadata)?;
+        let table = TableColumns {
+            fields: metadata.schema.get_fields(),
+            partition_columns: &metadata.partition_columns,
+            column_resolution,
+        };
+        let condition = self.resolve(&table, false)?;
+        match condition.data_column() {
+            Some(column) => Err(DeltaTableError::InvalidPredicate {
+                predicate: self.to_string(),
+                msg: format!("{} is not a partition column", column.name),
+            }),
+            None => Ok(condition),
+        }
+    }
+
+    /// Returns the predicate as JSON with its columns resolved and its literals converted to the
+    /// types of the columns like [`Predicate::prune`] does, see
+    /// [`crate::DeltaTable::resolve_predicate`] for the format.
+    pub(crate) fn to_resolved_json(
+        &self,
+        state: &DeltaTableState,
+        column_resolution: ColumnResolution,
+    ) -> Result<Value, DeltaTableError> {
+        let metadata = state
+            .current_metadata()
+            .ok_or(DeltaTableError::NoMetadata)?;
+        let table = TableColumns {
+            fields: metadata.schema.get_fields(),
+            partition_columns: &metadata.partition_columns,
+            column_resolution,
+        };
+        self.resolved_json(&table)
+    }
+
+    fn resolved_json(&self, table: &TableColumns) -> Result<Value, DeltaTableError> {
+        Ok(match self {
+            Predicate::And(left, right) | Predicate::Or(left, right) => json!({
+                "op": if matches!(self, Predicate::And(..)) { "and" } else { "or" },
+                "args": [left.resolved_json(table)?, right.resolved_json(table)?],
+            }),
+            Predicate::Not(predicate) => json!({
+                "op": "not",
+                "args": [predicate.resolved_json(table)?],
+            }),
+            Predicate::Comparison { column, op, value } => {
+                let column = table.resolve(column)?;
+                json!({
+                    "op": op.as_str(),
+                    "column": column.path,
+                    "value": column.scalar_json(value)?,
+                })
+            }
+            Predicate::In {
+                column,
+                values,
+                negated,
+            } => {
+                let column = table.resolve(column)?;
+                json!({
+                    "op": if *negated { "not in" } else { "in" },
+                    "column": column.path,
+                    "values": values
+                        .iter()
+                        .map(|value| column.scalar_json(value))
+                        .collect::<Result<Vec<_>, _>>()?,
+                })
+            }
+            Predicate::IsNull { column, negated } => json!({
+                "op": if *negated { "is not null" } else { "is null" },
+                "column": table.resolve(column)?.path,
+            }),
+        })
+    }
+
+    /// Resolves the columns of the predicate and converts its literals to their types. `NOT` is
+    /// pushed down to the leaves, which is negated if `negated` is set.
+    fn resolve<'a>(
+        &self,
+        table: &TableColumns<'a>,
+        negated: bool,
+    ) -> Result<Condition<'a>, DeltaTableError> {
+        Ok(match self {
+            Predicate::And(left, right) | Predicate::Or(left, right) => {
+                let left = Box::new(left.resolve(table, negated)?);
+                let right = Box::new(right.resolve(table, negated)?);
+                if matches!(self, Predicate::And(..)) != negated {
+                    Condition::And(left, right)
+                } else {
+                    Condition::Or(left, right)
+                }
+            }
+            Predicate::Not(predicate) => predicate.resolve(table, !negated)?,
+            Predicate::Comparison { column, op, value } => {
+                let column = table.resolve(column)?;
+                let value = column.scalar(value)?;
+                let op = if negated { op.negate() } else { *op };
+                Condition::Comparison { column, op, value }
+            }
+            Predicate::In {
+                column,
+                values,
+                negated: not_in,
+            } => {
+                let column = table.resolve(column)?;
+                let values = values
+                    .iter()
+                    .map(|value| column.scalar(value))
+                    .collect::<Result<Option<Vec<_>>, _>>()?;
+                Condition::In {
+                    column,
+                    values,
+                    negated: *not_in != negated,
+                }
+            }
+            Predicate::IsNull {
+                column,
+                negated: not_null,
+            } => Condition::IsNull {
+                column: table.resolve(column)?,
+                negated: *not_null != negated,
+            },
+        })
+    }
+}
+
+struct TableColumns<'a> {
+    fields: &'a [SchemaField],
+    partition_columns: &'a [String],
+    column_resolution: ColumnResolution,
+}
+
+impl<'a> TableColumns<'a> {
+    fn resolve(&self, path: &[String]) -> Result<Column<'a>, DeltaTableError> {
+        let not_found = || DeltaTableError::ColumnNotFound {
+            name: path.join("."),
+        };
+        let mut fields = self.fields;
+        let mut resolved_names = Vec::with_capacity(path.len());
+        let mut stats_path = Vec::with_capacity(path.len());
+        let mut data_type = None;
+        for name in path {
+            if data_type.is_some() {
+                fields = match data_type {
+                    Some(SchemaDataType::r#struct(nested)) => nested.get_fields().as_slice(),
+                    _ => return Err(not_found()),
+                };
+            }
+            let resolved = self
+                .column_resolution
+                .resolve(name, fields.iter().map(|field| field.get_name()))?
+                .ok_or_else(not_found)?;
+            let field = fields
+                .iter()
+                .find(|field| field.get_name() == resolved)
+                .ok_or_else(not_found)?;
+            resolved_names.push(resolved);
+            stats_path.push(stats_name(field));
+            data_type = Some(field.get_type());
+        }
+        let data_type = data_type.ok_or_else(not_found)?;
+        let partition = match resolved_names.as_slice() {
+            [name] => self.partition_columns.iter().any(|column| column == name),
+            _ => false,
+        };
+        Ok(Column {
+            name: path.join("."),
+            path: resolved_names,
+            data_type,
+            stats_path,
+            partition,
+        })
+    }
+}
+
+/// A resolved column of a predicate.
+struct Column<'a> {
+    name: String,
+    /// The names of the path of the column as they are spelled in the schema.
+    path: Vec<&'a str>,
+    data_type: &'a SchemaDataType,
+    /// The path of the column in statistics, or the key in the partition values.
+    stats_path: Vec<&'a str>,
+    partition: bool,
+}
+
+impl Column<'_> {
+    /// Converts a literal to the type of the column, returns None if the files cannot be pruned
+    /// by the values of the column, e.g. for binary columns.
+    fn scalar(&self, literal: &Literal) -> Result<Option<Scalar>, DeltaTableError> {
+        let primitive = match self.data_type {
+            SchemaDataType::primitive(primitive) => primitive.as_str(),
+            _ => return Ok(None),
+        };
+        let scalar = match (primitive, literal) {
+            (
+                "long" | "integer" | "short" | "byte",
+                Literal::Number(value) | Literal::String(value),
+            ) => decimal_scalar(value, 0),
+            (decimal, Literal::Number(value) | Literal::String(value))
+                if decimal.starts_with("decimal") =>
+            {
+                let scale = decimal
+                    .trim_end_matches(')')
+                    .rsplit(',')
+                    .next()
+                    .and_then(|scale| scale.trim().parse().ok());
+                match scale {
+                    Some(scale) => decimal_scalar(value, scale),
+                    None => return Ok(None),
+                }
+            }
+            ("float" | "double", Literal::Number(value) | Literal::String(value)) => {
+                value.parse().ok().map(Scalar::Float)
+            }
+            ("string", Literal::String(value)) => Some(Scalar::String(value.clone())),
+            ("boolean", Literal::Boolean(value)) => Some(Scalar::Boolean(*value)),
+            ("date", Literal::String(value)) => days_since_epoch(value).map(Scalar::Date),
+            ("timestamp", Literal::String(value)) => timestamp_micros(value).map(Scalar::Timestamp),
+            ("binary", _) => return Ok(None),
+            _ => None,
+        };
+        match scalar {
+            Some(scalar) => Ok(Some(scalar)),
+            None => Err(DeltaTableError::InvalidPredicate {
+                predicate: format!("{} {}", self.name, literal),
+                msg: format!(
+                    "cannot compare the column {} of type {} with {}",
+                    self.name, primitive, literal
+                ),
+            }),
+        }
+    }
+
+    /// Converts a literal to the type of the column as JSON, the literal itself if it cannot be
+    /// converted, see [`Column::scalar`].
+    fn scalar_json(&self, literal: &Literal) -> Result<Value, DeltaTableError> {
+        Ok(match self.scalar(literal)? {
+            Some(scalar) => scalar.to_json(),
+            None => literal.to_json(),
+        })
+    }
+}
+
+/// Converts a number to a decimal with at least `scale` digits and all the digits of the number.
+fn decimal_scalar(value: &str, scale: usize) -> Option<Scalar> {
+    let value = value.trim();
+    let (mantissa, exponent) = match value.find(|c| c == 'e' || c == 'E') {
+        Some(i) => (&value[..i], value[i + 1..].parse::<i64>().ok()?),
+        None => (value, 0),
+    };
+    let digits = mantissa.find('.').map_or(0, |i| mantissa.len() - i - 1) as i64;
+    let scale = scale.max(usize::try_from(digits - exponent).unwrap_or(0));
+    let unscaled =
+        decimal_utils::decimal_from_stats_string(value, MAX_DECIMAL_PRECISION, scale, false)?;
+    Some(Scalar::Decimal(unscaled, scale))
+}
+
+/// Which value a statistic or partition value is of the values of a column in a file.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    /// A partition value, the value of all rows.
+    Exact,
+    /// A minimum value.
+    Lower,
+    /// A maximum value.
+    Upper,
+}
+
+/// A typed value to compare the values of a column with.
+#[derive(Clone, Debug, PartialEq)]
+enum Scalar {
+    Boolean(bool),
+    /// An unscaled decimal and its scale, numbers of integer and decimal columns are compared
+    /// as decimals so that no precision is lost.
+    Decimal(i128, usize),
+    Float(f64),
+    String(String),
+    /// Days since the epoch.
+    Date(i32),
+    /// Microseconds since the epoch.
+    Timestamp(i64),
+}
+
+impl Scalar {
+    /// Parses a statistic or partition value into a scalar of the same type as `self`, returns
+    /// None if it does not match the type.
+    fn parse_like(&self, value: &Value, bound: Bound) -> Option<Scalar> {
+        match self {
+            Scalar::Boolean(_) => value
+                .as_bool()
+                .or_else(|| value.as_str()?.parse().ok())
+                .map(Scalar::Boolean),
+            Scalar::Decimal(_, scale) => decimal_utils::decimal_from_stats_value(
+                value,
+                MAX_DECIMAL_PRECISION,
+                *scale,
+                bound == Bound::Upper,
+            )
+            .map(|unscaled| Scalar::Decimal(unscaled, *scale)),
+            Scalar::Float(_) => value
+                .as_f64()
+                .or_else(|| value.as_str()?.parse().ok())
+                .map(Scalar::Float),
+            Scalar::String(_) => value
+                .as_str()
+                .map(|value| Scalar::String(value.to_string())),
+            Scalar::Date(_) => days_since_epoch(value.as_str()?).map(Scalar::Date),
+            Scalar::Timestamp(_) => {
+                let micros = timestamp_micros(value.as_str()?)?;
+                // statistics of timestamps are truncated to milliseconds
+                Some(Scalar::Timestamp(if bound == Bound::Upper {
+                    micros + 999
+                } else {
+                    micros
+                }))
+            }
+        }
+    }
+
+    /// The value as JSON, decimals and non-finite floats as strings, dates as days and
+    /// timestamps as microseconds since the epoch.
+    fn to_json(&self) -> Value {
+        match self {
+            Scalar::Boolean(value) => Value::Bool(*value),
+            Scalar::Decimal(unscaled, scale) => {
+                Value::String(decimal_utils::decimal_to_stats_string(*unscaled, *scale))
+            }
+            Scalar::Float(value) => Number::from_f64(*value)
+                .map_or_else(|| Value::String(value.to_string()), Value::Number),
+            Scalar::String(value) => Value::String(value.clone()),
+            Scalar::Date(days) => Value::from(*days),
+            Scalar::Timestamp(micros) => Value::from(*micros),
+        }
+    }
+
+    fn compare(&self, other: &Scalar) -> Option<Ordering> {
+        match (self, other) {
+            (Scalar::Boolean(a), Scalar::Boolean(b)) => a.partial_cmp(b),
+            (Scalar::Decimal(a, a_scale), Scalar::Decimal(b, b_scale)) if a_scale == b_scale => {
+                a.partial_cmp(b)
+            }
+            (Scalar::Float(a), Scalar::Float(b)) => a.partial_cmp(b),
+            (Scalar::String(a), Scalar::String(b)) => a.partial_cmp(b),
+            (Scalar::Date(a), Scalar::Date(b)) => a.partial_cmp(b),
+            (Scalar::Timestamp(a), Scalar::Timestamp(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+/// A predicate with resolved columns, typed values and `NOT` pushed down to the leaves.
+enum Condition<'a> {
+    And(Box<Condition<'a>>, Box<Condition<'a>>),
+    Or(Box<Condition<'a>>, Box<Condition<'a>>),
+    /// The value is None if the column cannot be pruned by.
+    Comparison {
+        column: Column<'a>,
+        op: ComparisonOperator,
+        value: Option<Scalar>,
+    },
+    In {
+        column: Column<'a>,
+        values: Option<Vec<Scalar>>,
+        negated: bool,
+    },
+    IsNull {
+        column: Column<'a>,
+        negated: bool,
+    },
+}
+
+impl Condition<'_> {
+    /// The first column of the condition that is not a partition column.
+    fn data_column(&self) -> Option<&Column> {
+        match self {
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.data_column().or_else(|| right.data_column())
+            }
+            Condition::Comparison { column, .. }
+            | Condition::In { column, .. }
+            | Condition::IsNull { column, .. } => Some(column).filter(|column| !column.partition),
+        }
+    }
+
+    /// Whether the file may contain rows for which the condition holds.
+    fn may_match(&self, add: &Add, stats: Option<&Stats>) -> bool {
+        match self {
+            Condition::And(left, right) => {
+                left.may_match(add, stats) && right.may_match(add, stats)
+            }
+            Condition::Or(left, right) => left.may_match(add, stats) || right.may_match(add, stats),
+            Condition::Comparison { column, op, value } => match value {
+                Some(value) => ColumnValues::of(column, add, stats).may_compare(*op, value),
+                None => true,
+            },
+            Condition::In {
+                column,
+                values,
+                negated,
+            } => match values {
+                Some(values) => {
+                    let values_of_file = ColumnValues::of(column, add, stats);
+                    if *negated {
+                        values.iter().all(|value| {
+                            values_of_file.may_compare(ComparisonOperator::NotEqual, value)
+                        })
+                    } else {
+                        values.iter().any(|value| {
+                            values_of_file.may_compare(ComparisonOperator::Equal, value)
+                        })
+                    }
+                }
+                None => true,
+            },
+            Condition::IsNull { column, negated } => match ColumnValues::of(column, add, stats) {
+                ColumnValues::Partition(value) => value.is_none() != *negated,
+                ColumnValues::Stats {
+                    num_records,
+                    null_count: Some(null_count),
+                    ..
+                } => {
+                    if *negated {
+                        null_count < num_records
+                    } else {
+                        null_count > 0
+                    }
+                }
+                _ => true,
+            },
+        }
+    }
+}
+
+/// What is known about the values of a column in a file.
+enum ColumnValues<'a> {
+    /// The partition value, None for nulls.
+    Partition(Option<&'a str>),
+    Stats {
+        num_records: i64,
+        null_count: Option<i64>,
+        min: Option<&'a Value>,
+        max: Option<&'a Value>,
+    },
+    /// The file has no statistics.
+    Unknown,
+}
+
+impl<'a> ColumnValues<'a> {
+    fn of(column: &Column, add: &'a Add, stats: Option<&'a Stats>) -> Self {
+        if column.partition {
+            return ColumnValues::Partition(
+                add.partition_values
+                    .get(column.stats_path[0])
+                    .and_then(Option::as_deref)
+                    .filter(|value| !value.is_empty()),
+            );
+        }
+        match stats {
+            Some(stats) => ColumnValues::Stats {
+                num_records: stats.num_records,
+                null_count: count_stat(&stats.null_count, &column.stats_path),
+                min: value_stat(&stats.min_values, &column.stats_path),
+                max: value_stat(&stats.max_values, &column.stats_path),
+            },
+            None => ColumnValues::Unknown,
+        }
+    }
+
+    /// Whether some of the values may compare to `value` with `op`.
+    fn may_compare(&self, op: ComparisonOperator, value: &Scalar) -> bool {
+        let (min, max) = match self {
+            ColumnValues::Partition(None) => return false,
+            ColumnValues::Partition(Some(partition_value)) => {
+                let partition_value =
+                    value.parse_like(&Value::String(partition_value.to_string()), Bound::Exact);
+                (partition_value.clone(), partition_value)
+            }
+            // comparisons never hold for nulls
+            ColumnValues::Stats {
+                num_records,
+                null_count: Some(null_count),
+                ..
+            } if null_count == num_records => return false,
+            ColumnValues::Stats { min, max, .. } => (
+                min.and_then(|min| value.parse_like(min, Bound::Lower)),
+                max.and_then(|max| value.parse_like(max, Bound::Upper)),
+            ),
+            ColumnValues::Unknown => return true,
+        };
+        // missing bounds and values that cannot be compared, like NaN, do not rule anything out
+        let holds = |bound: &Option<Scalar>, test: fn(Ordering) -> bool| {
+            bound
+                .as_ref()
+                .and_then(|bound| bound.compare(value))
+                .map_or(true, test)
+        };
+        match op {
+            ComparisonOperator::Equal => {
+                holds(&min, Ordering::is_le) && holds(&max, Ordering::is_ge)
+            }
+            ComparisonOperator::NotEqual => !matches!(
+                (
+                    min.as_ref().and_then(|min| min.compare(value)),
+                    max.as_ref().and_then(|max| max.compare(value))
+                ),
+                (Some(Ordering::Equal), Some(Ordering::Equal))
+            ),
+            ComparisonOperator::LessThan => holds(&min, Ordering::is_lt),
+            ComparisonOperator::LessThanOrEqual => holds(&min, Ordering::is_le),
+            ComparisonOperator::GreaterThan => holds(&max, Ordering::is_gt),
+            ComparisonOperator::GreaterThanOrEqual => holds(&max, Ordering::is_ge),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// A name, keywords are names too.
+    Name(String),
+    /// A name quoted with backticks.
+    QuotedName(String),
+    Literal(Literal),
+    Operator(ComparisonOperator),
+    LeftParenthesis,
+    RightParenthesis,
+    Comma,
+    Dot,
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Name(name) if name.eq_ignore_ascii_case(keyword))
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "{}", name),
+            Token::QuotedName(name) => write!(f, "`{}`", name.replace('`', "``")),
+            Token::Literal(literal) => write!(f, "{}", literal),
+            Token::Operator(op) => write!(f, "{}", op.as_str()),
+            Token::LeftParenthesis => write!(f, "("),
+            Token::RightParenthesis => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Dot => write!(f, "."),
+        }
+    }
+}
+
+fn tokenize(predicate: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = predicate.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LeftParenthesis,
+            ')' => Token::RightParenthesis,
+            ',' => Token::Comma,
+            '.' if !matches!(chars.peek(), Some((_, next)) if next.is_ascii_digit()) => Token::Dot,
+            '=' => {
+                chars.next_if(|(_, next)| *next == '=');
+                Token::Operator(ComparisonOperator::Equal)
+            }
+            '!' if chars.next_if(|(_, next)| *next == '=').is_some() => {
+                Token::Operator(ComparisonOperator::NotEqual)
+            }
+            '<' => {
+                if chars.next_if(|(_, next)| *next == '=').is_some() {
+                    Token::Operator(ComparisonOperator::LessThanOrEqual)
+                } else if chars.next_if(|(_, next)| *next == '>').is_some() {
+                    Token::Operator(ComparisonOperator::NotEqual)
+                } else {
+                    Token::Operator(ComparisonOperator::LessThan)
+                }
+            }
+            '>' => {
+                if chars.next_if(|(_, next)| *next == '=').is_some() {
+                    Token::Operator(ComparisonOperator::GreaterThanOrEqual)
+                } else {
+                    Token::Operator(ComparisonOperator::GreaterThan)
+                }
+            }
+            '\'' | '`' => {
+                // the quote is escaped by doubling it
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, next)) if next == c => {
+                            if chars.next_if(|(_, next)| *next == c).is_none() {
+                                break;
+                            }
+                            value.push(c);
+                        }
+                        Some((_, next)) => value.push(next),
+                        None => return Err(format!("unterminated {} at {}", c, start)),
+                    }
+                }
+                if c == '\'' {
+                    Token::Literal(Literal::String(value))
+                } else {
+                    Token::QuotedName(value)
+                }
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                let mut end = start + c.len_utf8();
+                let mut previous = c;
+                while let Some((i, next)) = chars.next_if(|&(_, next)| {
+                    next.is_ascii_alphanumeric()
+                        || next == '.'
+                        || ((next == '-' || next == '+') && (previous == 'e' || previous == 'E'))
+                }) {
+                    end = i + next.len_utf8();
+                    previous = next;
+                }
+                let number = &predicate[start..end];
+                if decimal_utils::decimal_from_stats_string(number, MAX_DECIMAL_PRECISION, 0, false)
+                    .is_none()
+                    && number.parse::<f64>().is_err()
+                {
+                    return Err(format!("invalid number {}", number));
+                }
+                Token::Literal(Literal::Number(number.to_string()))
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, next)) =
+                    chars.next_if(|(_, next)| next.is_alphanumeric() || *next == '_')
+                {
+                    end = i + next.len_utf8();
+                }
+                let name = &predicate[start..end];
+                if name.eq_ignore_ascii_case("true") {
+                    Token::Literal(Literal::Boolean(true))
+                } else if name.eq_ignore_ascii_case("false") {
+                    Token::Literal(Literal::Boolean(false))
+                } else {
+                    Token::Name(name.to_string())
+                }
+            }
+            c => return Err(format!("unexpected character {} at {}", c, start)),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser of predicates, `AND` binds tighter than `OR`.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let matched = matches!(self.peek(), Some(token) if token.is_keyword(keyword));
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected {}, got {}", expected, token)),
+            None => Err(format!("expected {}", expected)),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        if self.next_if_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(format!("expected {}", keyword.to_uppercase()))
+        }
+    }
+
+    fn or(&mut self) -> Result<Predicate, String> {
+        let mut predicate = self.and()?;
+        while self.next_if_keyword("or") {
+            predicate = Predicate::Or(Box::new(predicate), Box::new(self.and()?));
+        }
+        Ok(predicate)
+    }
+
+    fn and(&mut self) -> Result<Predicate, String> {
+        let mut predicate = self.not()?;
+        while self.next_if_keyword("and") {
+            predicate = Predicate::And(Box::new(predicate), Box::new(self.not()?));
+        }
+        Ok(predicate)
+    }
+
+    fn not(&mut self) -> Result<Predicate, String> {
+        if self.next_if_keyword("not") {
+            return Ok(Predicate::Not(Box::new(self.not()?)));
+        }
+        if self.peek() == Some(&Token::LeftParenthesis) {
+            self.position += 1;
+            let predicate = self.or()?;
+            self.expect(Token::RightParenthesis)?;
+            return Ok(predicate);
+        }
+        self.leaf()
+    }
+
+    fn leaf(&mut self) -> Result<Predicate, String> {
+        // comparisons may also be written with the literal first, like `1 < x`
+        if let Some(Token::Literal(value)) = self.peek().cloned() {
+            self.position += 1;
+            let op = match self.next() {
+                Some(Token::Operator(op)) => op.flip(),
+                Some(token) => return Err(format!("expected a comparison, got {}", token)),
+                None => return Err("expected a comparison".to_string()),
+            };
+            let column = self.column()?;
+            return Ok(Predicate::Comparison { column, op, value });
+        }
+
+        let column = self.column()?;
+        match self.next() {
+            Some(Token::Operator(op)) => {
+                let value = self.literal()?;
+                Ok(Predicate::Comparison { column, op, value })
+            }
+            Some(token) if token.is_keyword("is") => {
+                let negated = self.next_if_keyword("not");
+                self.expect_keyword("null")?;
+                Ok(Predicate::IsNull { column, negated })
+            }
+            Some(token) if token.is_keyword("in") || token.is_keyword("not") => {
+                let negated = token.is_keyword("not");
+                if negated {
+                    self.expect_keyword("in")?;
+                }
+                self.expect(Token::LeftParenthesis)?;
+                let mut values = vec![self.literal()?];
+                while self.peek() == Some(&Token::Comma) {
+                    self.position += 1;
+                    values.push(self.literal()?);
+                }
+                self.expect(Token::RightParenthesis)?;
+                Ok(Predicate::In {
+                    column,
+                    values,
+                    negated,
+                })
+            }
+            Some(token) => Err(format!(
+                "expected a comparison after {}, got {}",
+                column.join("."),
+                token
+            )),
+            None => Err(format!("expected a comparison after {}", column.join("."))),
+        }
+    }
+
+    fn column(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            match self.next() {
+                Some(Token::Name(name)) if !is_reserved(&name) => path.push(name),
+                Some(Token::QuotedName(name)) => path.push(name),
+                Some(token) => return Err(format!("expected a column, got {}", token)),
+                None => return Err("expected a column".to_string()),
+            }
+            if self.peek() != Some(&Token::Dot) {
+                return Ok(path);
+            }
+            self.position += 1;
+        }
+    }
+
+    fn literal(&mut self) -> Result<Literal, String> {
+        match self.next() {
+            Some(Token::Literal(literal)) => Ok(literal),
+            Some(token) if token.is_keyword("null") => {
+                Err("comparisons with NULL never hold, use IS NULL".to_string())
+            }
+            Some(token) => Err(format!("expected a literal, got {}", token)),
+            None => Err("expected a literal".to_string()),
+        }
+    }
+}
+
+fn is_reserved(name: &str) -> bool {
+    ["and", "or", "not", "in", "is", "null"]
+        .iter()
+        .any(|keyword| name.eq_ignore_ascii_case(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(path: &[&str]) -> Vec<String> {
+        path.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_comparisons() {
+        assert_eq!(
+            "x >= 1.5".parse::<Predicate>().unwrap(),
+            Predicate::Comparison {
+                column: column(&["x"]),
+                op: ComparisonOperator::GreaterThanOrEqual,
+                value: Literal::Number("1.5".to_string()),
+            }
+        );
+        assert_eq!(
+            "'2021-01-01' < `my col`.date".parse::<Predicate>().unwrap(),
+            Predicate::Comparison {
+                column: column(&["my col", "date"]),
+                op: ComparisonOperator::GreaterThan,
+                value: Literal::String("2021-01-01".to_string()),
+            }
+        );
+        assert_eq!(
+            "name <> 'it''s'".parse::<Predicate>().unwrap(),
+            Predicate::Comparison {
+                column: column(&["name"]),
+                op: ComparisonOperator::NotEqual,
+                value: Literal::String("it's".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_precedence() {
+        let x_is_null = Predicate::IsNull {
+            column: column(&["x"]),
+            negated: false,
+        };
+        let y_in = Predicate::In {
+            column: column(&["y"]),
+            values: vec![
+                Literal::Number("1".to_string()),
+                Literal::Number("-2".to_string()),
+            ],
+            negated: true,
+        };
+        let z_true = Predicate::Comparison {
+            column: column(&["z"]),
+            op: ComparisonOperator::Equal,
+            value: Literal::Boolean(true),
+        };
+        assert_eq!(
+            "x IS NULL or y NOT IN (1, -2) AND NOT z == TRUE"
+                .parse::<Predicate>()
+                .unwrap(),
+            Predicate::Or(
+                Box::new(x_is_null.clone()),
+                Box::new(Predicate::And(
+                    Box::new(y_in.clone()),
+                    Box::new(Predicate::Not(Box::new(z_true)))
+                ))
+            )
+        );
+        assert_eq!(
+            "(x is null OR y not in (1,-2)) AND z is not null"
+                .parse::<Predicate>()
+                .unwrap(),
+            Predicate::And(
+                Box::new(Predicate::Or(Box::new(x_is_null), Box::new(y_in))),
+                Box::new(Predicate::IsNull {
+                    column: column(&["z"]),
+                    negated: true,
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn display_parses_to_the_same_predicate() {
+        for predicate in [
+            "x >= 1.5",
+            "`my col`.date > '2021-01-01' OR `and` = 'it''s'",
+            "NOT (x IS NULL OR y NOT IN (1, -2)) AND `true` = TRUE",
+            "größe IS NOT NULL AND `a``b` IN ('a', 'b')",
+        ] {
+            let parsed = predicate.parse::<Predicate>().unwrap();
+            assert_eq!(
+                parsed.to_string().parse::<Predicate>().unwrap(),
+                parsed,
+                "{}",
+                parsed
+            );
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for predicate in [
+            "",
+            "x",
+            "x = ",
+            "x = y",
+            "x = NULL",
+            "x = 'a",
+            "(x = 1",
+            "x = 1 y = 2",
+            "x in ()",
+            "and = 1",
+            "x = 1..2",
+        ] {
+            assert!(
+                matches!(
+                    predicate.parse::<Predicate>(),
+                    Err(DeltaTableError::InvalidPredicate { .. })
+                ),
+                "{} should be invalid",
+                predicate
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Delta Table schema implementation.
 #![allow(non_snake_case, non_camel_case_types)]
 
+use crate::DeltaTableError;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -20,6 +21,15 @@ static STRUCT_TAG: &str = "struct";
 static ARRAY_TAG: &str = "array";
 static MAP_TAG: &str = "map";
 
+/// Characters that parquet does not support in column names. Columns named with them can only be
+/// stored with column mapping enabled.
+pub const INVALID_COLUMN_NAME_CHARS: &[char] =
+    &[' ', ',', ';', '{', '}', '(', ')', '\n', '\t', '='];
+/// Field metadata key of the column id assigned by column mapping.
+pub const COLUMN_MAPPING_ID_KEY: &str = "delta.columnMapping.id";
+/// Field metadata key of the physical name assigned by column mapping.
+pub const COLUMN_MAPPING_PHYSICAL_NAME_KEY: &str = "delta.columnMapping.physicalName";
+
 /// Represents a struct field defined in the Delta table schema.
 // https://github.com/delta-io/delta/blob/master/PROTOCOL.md#Schema-Serialization-Format
 #[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
@@ -42,6 +52,117 @@ impl SchemaTypeStruct {
     pub fn get_fields(&self) -> &Vec<SchemaField> {
         &self.fields
     }
+
+    /// Returns the dotted paths of all (possibly nested) columns whose name contains one of the
+    /// [`INVALID_COLUMN_NAME_CHARS`].
+    pub fn invalid_column_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        collect_invalid_column_names(self, "", &mut names);
+        names
+    }
+
+    /// Assigns a column id and a physical name for column mapping to every (possibly nested)
+    /// column that does not have one yet. Ids are allocated after `max_column_id`, the new
+    /// maximum column id is returned.
+    pub fn assign_column_mapping(&mut self, max_column_id: i64) -> i64 {
+        let mut max_column_id = max_column_id;
+        for field in self.fields.iter_mut() {
+            if !field.metadata.contains_key(COLUMN_MAPPING_ID_KEY) {
+                max_column_id += 1;
+                field
+                    .metadata
+                    .insert(COLUMN_MAPPING_ID_KEY.to_string(), max_column_id.to_string());
+                field.metadata.insert(
+                    COLUMN_MAPPING_PHYSICAL_NAME_KEY.to_string(),
+                    format!("col-{}", uuid::Uuid::new_v4()),
+                );
+            }
+            max_column_id = assign_nested_column_mapping(&mut field.r#type, max_column_id);
+        }
+        max_column_id
+    }
+}
+
+fn collect_invalid_column_names(schema: &SchemaTypeStruct, prefix: &str, names: &mut Vec<String>) {
+    for field in schema.get_fields() {
+        let path = format!("{}{}", prefix, field.get_name());
+        if field.get_name().contains(INVALID_COLUMN_NAME_CHARS) {
+            names.push(path.clone());
+        }
+        for nested in nested_structs(field.get_type()) {
+            collect_invalid_column_names(nested, &format!("{}.", path), names);
+        }
+    }
+}
+
+fn nested_structs(data_type: &SchemaDataType) -> Vec<&SchemaTypeStruct> {
+    match data_type {
+        SchemaDataType::primitive(_) => vec![],
+        SchemaDataType::r#struct(nested) => vec![nested],
+        SchemaDataType::array(array) => nested_structs(&array.elementType),
+        SchemaDataType::map(map) => {
+            let mut structs = nested_structs(&map.keyType);
+            structs.extend(nested_structs(&map.valueType));
+            structs
+        }
+    }
+}
+
+fn assign_nested_column_mapping(data_type: &mut SchemaDataType, max_column_id: i64) -> i64 {
+    match data_type {
+        SchemaDataType::primitive(_) => max_column_id,
+        SchemaDataType::r#struct(nested) => nested.assign_column_mapping(max_column_id),
+        SchemaDataType::array(array) => {
+            assign_nested_column_mapping(&mut array.elementType, max_column_id)
+        }
+        SchemaDataType::map(map) => {
+            let max_column_id = assign_nested_column_mapping(&mut map.keyType, max_column_id);
+            assign_nested_column_mapping(&mut map.valueType, max_column_id)
+        }
+    }
+}
+
+/// Policy for matching user supplied column names against the column names of a table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnResolution {
+    /// Column names must match exactly.
+    CaseSensitive,
+    /// Column names match regardless of case, like Spark with `spark.sql.caseSensitive=false`.
+    CaseInsensitive,
+}
+
+impl Default for ColumnResolution {
+    fn default() -> Self {
+        ColumnResolution::CaseInsensitive
+    }
+}
+
+impl ColumnResolution {
+    /// Resolve `name` against the given column names and return the matching column as it is
+    /// spelled in the table, or `None` if no column matches. Fails with
+    /// [`DeltaTableError::AmbiguousColumn`] if several columns match case-insensitively.
+    pub fn resolve<'a, I>(&self, name: &str, columns: I) -> Result<Option<&'a str>, DeltaTableError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        match self {
+            ColumnResolution::CaseSensitive => Ok(columns.into_iter().find(|c| *c == name)),
+            ColumnResolution::CaseInsensitive => {
+                let name_lower = name.to_lowercase();
+                let matches: Vec<&str> = columns
+                    .into_iter()
+                    .filter(|c| c.to_lowercase() == name_lower)
+                    .collect();
+                if matches.len() > 1 {
+                    return Err(DeltaTableError::AmbiguousColumn {
+                        name: name.to_string(),
+                        candidates: matches.into_iter().map(str::to_string).collect(),
+                    });
+                }
+                Ok(matches.into_iter().next())
+            }
+        }
+    }
 }
 
 /// Describes a specific field of the Delta table schema.
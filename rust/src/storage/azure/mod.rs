@@ -2,9 +2,10 @@
 //!
 //! This module is gated behind the "azure" feature.
 //!
-//! There are several authentication options available. Either via the environment:
+//! There are several authentication options available. Either via the storage options or the
+//! environment, see [azure_storage_options]. In order of precedence:
 //! a) `AZURE_STORAGE_CONNECTION_STRING`
-//! b) `AZURE_STORAGE_ACCOUNT` and `AZURE_STORAGE_SAS`
+//! b) `AZURE_STORAGE_ACCOUNT` and `AZURE_STORAGE_SAS` (or `AZURE_STORAGE_SAS_TOKEN`)
 //! c) `AZURE_STORAGE_ACCOUNT` and `AZURE_STORAGE_KEY`
 //!
 //! Alternatively, the default credential from the azure_identity crate is used,
@@ -31,9 +32,44 @@ use std::fmt::Debug;
 use std::{fmt, pin::Pin};
 mod client;
 use super::{parse_uri, ObjectMeta, StorageBackend, StorageError, UriError};
+use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
 
+/// Storage option keys to use when creating an [AdlsGen2Backend].
+/// The same key should be used whether passing a key in the hashmap or setting it as an environment variable.
+pub mod azure_storage_options {
+    /// Connection string of the storage account, which takes precedence over all other options.
+    pub const AZURE_STORAGE_CONNECTION_STRING: &str = "AZURE_STORAGE_CONNECTION_STRING";
+    /// The storage account name.
+    pub const AZURE_STORAGE_ACCOUNT: &str = "AZURE_STORAGE_ACCOUNT";
+    /// A shared access signature. A leading `?` of a full SAS query string is removed.
+    pub const AZURE_STORAGE_SAS: &str = "AZURE_STORAGE_SAS";
+    /// Alias of [AZURE_STORAGE_SAS].
+    pub const AZURE_STORAGE_SAS_TOKEN: &str = "AZURE_STORAGE_SAS_TOKEN";
+    /// The storage account access key.
+    pub const AZURE_STORAGE_KEY: &str = "AZURE_STORAGE_KEY";
+
+    /// The list of option keys owned by the Azure module.
+    pub const AZURE_OPTS: &[&str] = &[
+        AZURE_STORAGE_CONNECTION_STRING,
+        AZURE_STORAGE_ACCOUNT,
+        AZURE_STORAGE_SAS,
+        AZURE_STORAGE_SAS_TOKEN,
+        AZURE_STORAGE_KEY,
+    ];
+}
+
+/// Returns the value of `key` in `options`, falling back to the environment variable.
+fn option(options: &HashMap<String, String>, key: &str) -> Option<String> {
+    options.get(key).cloned().or_else(|| env::var(key).ok())
+}
+
+/// Turns a SAS query string like `?sv=...&sig=...` into the token expected by the client.
+fn normalize_sas(sas: &str) -> &str {
+    sas.trim().trim_start_matches('?')
+}
+
 /// An object on an Azure Data Lake Storage Gen2 account.
 #[derive(Debug, PartialEq)]
 pub struct AdlsGen2Object<'a> {
@@ -70,8 +106,10 @@ impl std::fmt::Debug for AzureContainerClient {
 }
 
 impl AzureContainerClient {
-    pub fn new(container: &str) -> Result<Self, StorageError> {
-        if let Ok(connection_string) = env::var("AZURE_STORAGE_CONNECTION_STRING") {
+    pub fn new(container: &str, options: &HashMap<String, String>) -> Result<Self, StorageError> {
+        use azure_storage_options::*;
+
+        if let Some(connection_string) = option(options, AZURE_STORAGE_CONNECTION_STRING) {
             let inner = StaticClientProvider::new_with_connection_string(
                 connection_string.clone(),
                 container.to_string(),
@@ -84,13 +122,18 @@ impl AzureContainerClient {
             });
         }
 
-        let account = env::var("AZURE_STORAGE_ACCOUNT").map_err(|_| {
+        let account = option(options, AZURE_STORAGE_ACCOUNT).ok_or_else(|| {
             StorageError::AzureConfig("AZURE_STORAGE_ACCOUNT must be set".to_string())
         })?;
 
-        if let Ok(sas) = env::var("AZURE_STORAGE_SAS") {
-            let inner =
-                StaticClientProvider::new_with_sas(sas, account.clone(), container.to_string())?;
+        let sas =
+            option(options, AZURE_STORAGE_SAS).or_else(|| option(options, AZURE_STORAGE_SAS_TOKEN));
+        if let Some(sas) = sas {
+            let inner = StaticClientProvider::new_with_sas(
+                normalize_sas(&sas).to_string(),
+                account.clone(),
+                container.to_string(),
+            )?;
             return Ok(Self {
                 account,
                 container: container.to_string(),
@@ -98,7 +141,7 @@ impl AzureContainerClient {
             });
         }
 
-        if let Ok(key) = env::var("AZURE_STORAGE_KEY") {
+        if let Some(key) = option(options, AZURE_STORAGE_KEY) {
             let inner = StaticClientProvider::new_with_access_key(
                 key,
                 account.clone(),
@@ -141,7 +184,7 @@ impl AdlsGen2Backend {
     ///
     /// There are several authentication options available. Either via the environment:
     /// a) `AZURE_STORAGE_CONNECTION_STRING`
-    /// b) `AZURE_STORAGE_ACCOUNT` and `AZURE_STORAGE_SAS`
+    /// b) `AZURE_STORAGE_ACCOUNT` and `AZURE_STORAGE_SAS` (or `AZURE_STORAGE_SAS_TOKEN`)
     /// c) `AZURE_STORAGE_ACCOUNT` and `AZURE_STORAGE_KEY`
     ///
     /// Alternatively, the default credential from the azure_identity crate is used,
@@ -157,9 +200,19 @@ impl AdlsGen2Backend {
     /// and will panic if this is not set. This also implies that the backend is
     /// only valid for a single Storage Account.
     pub fn new(container: &str) -> Result<Self, StorageError> {
+        Self::new_from_options(container, &HashMap::new())
+    }
+
+    /// Create a new [`AdlsGen2Backend`] configured by the [azure_storage_options] keys of
+    /// `options`. Options missing from the map are read from the environment, as in
+    /// [`AdlsGen2Backend::new`].
+    pub fn new_from_options(
+        container: &str,
+        options: &HashMap<String, String>,
+    ) -> Result<Self, StorageError> {
         Ok(Self {
             container: container.to_string(),
-            client: AzureContainerClient::new(container)?,
+            client: AzureContainerClient::new(container, options)?,
         })
     }
 
@@ -206,6 +259,9 @@ impl StorageBackend for AdlsGen2Backend {
         Ok(ObjectMeta {
             path: path.to_string(),
             modified,
+            size: Some(properties.blob.properties.content_length as i64),
+            e_tag: Some(properties.blob.properties.etag.to_string()),
+            version: None,
         })
     }
 
@@ -259,6 +315,9 @@ impl StorageBackend for AdlsGen2Backend {
                 Ok(ObjectMeta {
                     path: object.to_string(),
                     modified: blob.properties.last_modified,
+                    size: Some(blob.properties.content_length as i64),
+                    e_tag: Some(blob.properties.etag.to_string()),
+                    version: None,
                 })
             })
             .collect::<Vec<Result<ObjectMeta, StorageError>>>();
@@ -278,6 +337,10 @@ impl StorageBackend for AdlsGen2Backend {
         unimplemented!("rename_obj_noreplace not implemented for azure");
     }
 
+    fn is_safe_for_concurrent_writes(&self) -> bool {
+        false
+    }
+
     async fn delete_obj(&self, _path: &str) -> Result<(), StorageError> {
         unimplemented!("delete_obj not implemented for azure");
     }
@@ -300,4 +363,12 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn normalize_sas_query_string() {
+        let token = "sv=2020-08-04&ss=b&sig=abc%3D";
+        assert_eq!(normalize_sas(token), token);
+        assert_eq!(normalize_sas(&format!("?{}", token)), token);
+        assert_eq!(normalize_sas(&format!(" ?{}\n", token)), token);
+    }
 }
@@ -0,0 +1,117 @@
+//! Integrity checks of object contents, configured with the [checksum_options::CHECKSUM] storage
+//! option, to detect objects corrupted in transit.
+//!
+//! The S3 backend only supports MD5: writes send the `Content-MD5` header, so that S3 rejects
+//! corrupted uploads, and reads are verified against the ETag, which is the MD5 of the object
+//! unless it was uploaded in parts or encrypted with SSE-KMS or SSE-C. The GCS backend verifies
+//! reads against the `x-goog-hash` header with either algorithm.
+//!
+//! Reads not matching the checksum fail with [StorageError::ChecksumMismatch], which the
+//! retrying backend treats as transient, so that the object is fetched again.
+
+use super::StorageError;
+
+/// Storage option keys to use when configuring checksums.
+pub mod checksum_options {
+    /// The algorithm verifying object contents: `md5`, `crc32c` or `off`, the default.
+    pub const CHECKSUM: &str = "checksum";
+}
+
+/// An algorithm computing the checksums of object contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    /// MD5, as sent in `Content-MD5` headers.
+    Md5,
+    /// CRC32C, as used by GCS.
+    Crc32c,
+}
+
+impl ChecksumAlgorithm {
+    /// Parses the value of the [checksum_options::CHECKSUM] option, which is `None` if checksums
+    /// are off. The error message names the option.
+    pub fn parse(value: &str) -> Result<Option<Self>, String> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "off" => Ok(None),
+            "md5" => Ok(Some(Self::Md5)),
+            "crc32c" => Ok(Some(Self::Crc32c)),
+            other => Err(format!(
+                "{} must be one of md5, crc32c or off, found {:?}",
+                checksum_options::CHECKSUM,
+                other
+            )),
+        }
+    }
+
+    /// The name of the algorithm, as in the `x-goog-hash` header.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Md5 => "md5",
+            Self::Crc32c => "crc32c",
+        }
+    }
+
+    /// Computes the checksum of `data`. CRC32C checksums are big-endian.
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Md5 => md5::compute(data).0.to_vec(),
+            Self::Crc32c => crc32c::crc32c(data).to_be_bytes().to_vec(),
+        }
+    }
+
+    /// Checks `data` read from `path` against the `expected` checksum.
+    pub fn verify(&self, path: &str, expected: &[u8], data: &[u8]) -> Result<(), StorageError> {
+        let actual = self.digest(data);
+        if actual == expected {
+            return Ok(());
+        }
+        Err(StorageError::ChecksumMismatch {
+            path: path.to_string(),
+            algorithm: self.name(),
+            expected: to_hex(expected),
+            actual: to_hex(&actual),
+        })
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_option() {
+        assert_eq!(
+            ChecksumAlgorithm::parse("MD5"),
+            Ok(Some(ChecksumAlgorithm::Md5))
+        );
+        assert_eq!(
+            ChecksumAlgorithm::parse("crc32c"),
+            Ok(Some(ChecksumAlgorithm::Crc32c))
+        );
+        assert_eq!(ChecksumAlgorithm::parse("off"), Ok(None));
+        assert!(ChecksumAlgorithm::parse("sha1").is_err());
+    }
+
+    #[test]
+    fn verify_contents() {
+        let md5 = ChecksumAlgorithm::Md5;
+        assert_eq!(
+            to_hex(&md5.digest(b"data")),
+            "8d777f385d3dfec8815d20f7496026dc"
+        );
+        let crc32c = ChecksumAlgorithm::Crc32c;
+        assert_eq!(to_hex(&crc32c.digest(b"123456789")), "e3069283");
+
+        let expected = md5.digest(b"data");
+        assert!(md5.verify("path", &expected, b"data").is_ok());
+        match md5.verify("path", &expected, b"dbta") {
+            Err(StorageError::ChecksumMismatch {
+                path, algorithm, ..
+            }) => assert_eq!((path.as_str(), algorithm), ("path", "md5")),
+            other => panic!("expected a checksum mismatch, got {:?}", other),
+        }
+    }
+}
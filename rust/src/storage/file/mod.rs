@@ -2,13 +2,15 @@
 //!
 //! The local file storage backend is multi-writer safe.
 
+use std::io::SeekFrom;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 
 use chrono::DateTime;
 use futures::{Stream, TryStreamExt};
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio_stream::wrappers::ReadDirStream;
 
 use super::{ObjectMeta, StorageBackend, StorageError};
@@ -75,6 +77,9 @@ impl StorageBackend for FileStorageBackend {
         Ok(ObjectMeta {
             path: path.to_string(),
             modified: DateTime::from(attr.modified().unwrap()),
+            size: Some(attr.len() as i64),
+            e_tag: None,
+            version: None,
         })
     }
 
@@ -82,6 +87,14 @@ impl StorageBackend for FileStorageBackend {
         fs::read(path).await.map_err(StorageError::from)
     }
 
+    async fn get_range(&self, path: &str, range: Range<usize>) -> Result<Vec<u8>, StorageError> {
+        let mut file = fs::File::open(path).await?;
+        file.seek(SeekFrom::Start(range.start as u64)).await?;
+        let mut buf = Vec::new();
+        file.take(range.len() as u64).read_to_end(&mut buf).await?;
+        Ok(buf)
+    }
+
     async fn list_objs<'a>(
         &'a self,
         path: &'a str,
@@ -92,9 +105,11 @@ impl StorageBackend for FileStorageBackend {
         let readdir = ReadDirStream::new(fs::read_dir(path).await?);
 
         Ok(Box::pin(readdir.err_into().and_then(|entry| async move {
+            let attr = entry.metadata().await.unwrap();
             Ok(ObjectMeta {
                 path: String::from(entry.path().to_str().unwrap()),
-                modified: DateTime::from(entry.metadata().await.unwrap().modified().unwrap()),
+                modified: DateTime::from(attr.modified().unwrap()),
+                size: Some(attr.len() as i64),
             })
         })))
     }
@@ -104,6 +119,11 @@ impl StorageBackend for FileStorageBackend {
             fs::create_dir_all(parent).await?;
         }
         let tmp_path = &format!("{}_{}", path, Uuid::new_v4().to_string());
+        // deletes the temp file if the rename fails or the put is cancelled
+        let mut tmp_file = TempFile {
+            path: tmp_path,
+            persisted: false,
+        };
         let mut f = fs::OpenOptions::new()
             .create(true)
             .truncate(true)
@@ -116,14 +136,9 @@ impl StorageBackend for FileStorageBackend {
         drop(f);
 
         // as temp path is transparent to end user, we could use syscall directly here
-        match fs::rename(tmp_path, path).await {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                // If rename failed, clean up the temp file.
-                self.delete_obj(tmp_path).await?;
-                Err(StorageError::from(e))
-            }
-        }
+        fs::rename(tmp_path, path).await?;
+        tmp_file.persisted = true;
+        Ok(())
     }
 
     async fn rename_obj_noreplace(&self, src: &str, dst: &str) -> Result<(), StorageError> {
@@ -135,6 +150,21 @@ impl StorageBackend for FileStorageBackend {
     }
 }
 
+/// A temporary file which is deleted when dropped, unless it was persisted.
+struct TempFile<'a> {
+    path: &'a str,
+    persisted: bool,
+}
+
+impl Drop for TempFile<'_> {
+    fn drop(&mut self) {
+        if !self.persisted {
+            // the put may be dropped outside of a runtime, so this can't be async
+            let _ = std::fs::remove_file(self.path);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::parse_uri;
@@ -165,6 +195,43 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn get_range() {
+        let tmp_dir = tempdir::TempDir::new("range_test").unwrap();
+        let backend = FileStorageBackend::new(tmp_dir.path().to_str().unwrap());
+        let path = tmp_dir.path().join("obj");
+        let path = path.to_str().unwrap();
+        backend.put_obj(path, b"hello world").await.unwrap();
+
+        assert_eq!(backend.get_range(path, 6..11).await.unwrap(), b"world");
+        assert_eq!(backend.get_range(path, 6..100).await.unwrap(), b"world");
+        assert!(backend.get_range(path, 20..30).await.unwrap().is_empty());
+        assert!(matches!(
+            backend.get_range(&format!("{}.missing", path), 0..1).await,
+            Err(StorageError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancelled_put_removes_temp_file() {
+        let tmp_dir = tempdir::TempDir::new("cancel_test").unwrap();
+        let backend = FileStorageBackend::new(tmp_dir.path().to_str().unwrap());
+        let path = tmp_dir.path().join("obj");
+        let data = vec![0u8; 64 * 1024 * 1024];
+
+        let temp_files = || std::fs::read_dir(tmp_dir.path()).unwrap().count();
+        let mut put = Box::pin(backend.put_obj(path.to_str().unwrap(), &data));
+        // cancel the put once it started writing the temp file
+        while temp_files() == 0 {
+            assert!(futures::poll!(put.as_mut()).is_pending());
+            tokio::task::yield_now().await;
+        }
+        drop(put);
+
+        assert_eq!(temp_files(), 0);
+        assert!(!path.exists());
+    }
+
     #[tokio::test]
     async fn delete_obj() {
         let tmp_dir = tempdir::TempDir::new("delete_test").unwrap();
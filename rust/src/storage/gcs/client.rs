@@ -1,10 +1,12 @@
 use super::{util, GCSClientError, GCSObject};
+use crate::storage::checksum::ChecksumAlgorithm;
 use futures::Stream;
 use std::convert::{TryFrom, TryInto};
 use std::path::PathBuf;
 /// Google Cloud Storage http client
 use std::sync::Arc;
 use tame_gcs::objects::{self, Object};
+use tame_gcs::ApiResponse;
 use tame_oauth::gcp as oauth;
 
 use log::debug;
@@ -14,10 +16,14 @@ use log::debug;
 pub struct GCSStorageBackend {
     /// The reqwest client used for handling http requests
     pub client: reqwest::Client,
-    /// The path to the path to the credentials file
-    pub cred_path: PathBuf,
+    /// The path to the credentials file, unless the credentials were passed inline
+    pub cred_path: Option<PathBuf>,
     /// The handle to our oauth token
     pub auth: Arc<oauth::ServiceAccountAccess>,
+    /// The project billed for requests to requester-pays buckets
+    pub user_project: Option<String>,
+    /// The algorithm verifying downloaded objects
+    pub checksum: Option<ChecksumAlgorithm>,
 }
 
 impl std::fmt::Debug for GCSStorageBackend {
@@ -29,20 +35,29 @@ impl std::fmt::Debug for GCSStorageBackend {
 impl TryFrom<PathBuf> for GCSStorageBackend {
     type Error = GCSClientError;
     fn try_from(cred_path: PathBuf) -> Result<Self, Self::Error> {
-        let client = reqwest::Client::builder().build()?;
         let cred_contents = std::fs::read_to_string(&cred_path)?;
+        let mut backend = Self::from_service_account_key(cred_contents)?;
+        backend.cred_path = Some(cred_path);
+        Ok(backend)
+    }
+}
+
+impl GCSStorageBackend {
+    /// Creates a backend authenticating with the JSON contents of a service account key
+    pub fn from_service_account_key(cred_contents: String) -> Result<Self, GCSClientError> {
+        let client = reqwest::Client::builder().build()?;
         let svc_account_info = oauth::ServiceAccountInfo::deserialize(cred_contents)?;
         let svc_account_access = oauth::ServiceAccountAccess::new(svc_account_info)?;
 
         Ok(Self {
             client,
-            cred_path,
+            cred_path: None,
             auth: std::sync::Arc::new(svc_account_access),
+            user_project: None,
+            checksum: None,
         })
     }
-}
 
-impl GCSStorageBackend {
     pub async fn metadata<'a>(
         &self,
         path: GCSObject<'_>,
@@ -57,14 +72,29 @@ impl GCSStorageBackend {
         Ok(response.metadata)
     }
 
-    pub async fn download<'a>(&self, path: GCSObject<'_>) -> Result<bytes::Bytes, GCSClientError> {
+    /// Downloads the object and returns it with the checksums of its `x-goog-hash` header
+    pub async fn download<'a>(
+        &self,
+        path: GCSObject<'_>,
+    ) -> Result<(bytes::Bytes, Option<String>), GCSClientError> {
         let download_request = Object::download(&path, None)?;
 
-        let response = util::execute::<_, objects::DownloadObjectResponse>(self, download_request)
-            .await
+        let response = util::send(self, download_request).await?;
+        let hashes = response
+            .headers()
+            .get_all("x-goog-hash")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        let response = objects::DownloadObjectResponse::try_from_parts(response)
+            .map_err(GCSClientError::from)
             .map_err(util::check_object_not_found)?;
 
-        Ok(response.consume())
+        Ok((
+            response.consume(),
+            Some(hashes).filter(|hashes| !hashes.is_empty()),
+        ))
     }
 
     pub fn list<'a>(
@@ -1,8 +1,7 @@
 //! Google Cloud Storage backend.
 //!
-//! This module is gated behind the "gcs" feature. Its usage also requires
-//! the `SERVICE_ACCOUNT` environment variables to be set to the path of
-//! credentials with permission to read from the bucket.
+//! This module is gated behind the "gcs" feature. Its usage also requires a service account key
+//! with permission to read from the bucket, passed as described in [gcs_storage_options].
 
 mod client;
 mod error;
@@ -15,24 +14,101 @@ pub(crate) use error::GCSClientError;
 pub(crate) use object::GCSObject;
 
 use futures::Stream;
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::pin::Pin;
 
 use log::debug;
 
+use super::checksum::ChecksumAlgorithm;
+use super::client::ClientOptions;
 use super::{parse_uri, ObjectMeta, StorageBackend, StorageError};
 
+/// Storage option keys to use when creating a GCS storage backend.
+/// The same key should be used whether passing a key in the hashmap or setting it as an environment variable.
+/// The first credential option that is set, in the order below, provides the credentials.
+pub mod gcs_storage_options {
+    /// The JSON contents of a service account key.
+    pub const GOOGLE_SERVICE_ACCOUNT_KEY: &str = "GOOGLE_SERVICE_ACCOUNT_KEY";
+    /// The path to a service account key file.
+    pub const GOOGLE_SERVICE_ACCOUNT: &str = "GOOGLE_SERVICE_ACCOUNT";
+    /// The path to a service account key file, kept for backwards compatibility.
+    pub const SERVICE_ACCOUNT: &str = "SERVICE_ACCOUNT";
+    /// The path to the application default credentials, which must be a service account key.
+    pub const GOOGLE_APPLICATION_CREDENTIALS: &str = "GOOGLE_APPLICATION_CREDENTIALS";
+    /// The project billed for the requests, sent as the `userProject` parameter. Required to
+    /// access requester-pays buckets.
+    pub const GOOGLE_USER_PROJECT: &str = "GOOGLE_USER_PROJECT";
+    /// Set to `md5` or `crc32c` to verify downloaded objects against their checksum. Objects
+    /// composed of others only have a CRC32C checksum.
+    pub use crate::storage::checksum::checksum_options::CHECKSUM;
+
+    /// The option keys of credential files, in the order they are looked up.
+    pub(crate) const CREDENTIAL_PATH_OPTS: &[&str] = &[
+        GOOGLE_SERVICE_ACCOUNT,
+        SERVICE_ACCOUNT,
+        GOOGLE_APPLICATION_CREDENTIALS,
+    ];
+
+    /// The list of option keys owned by the GCS module.
+    pub const GCS_OPTS: &[&str] = &[
+        GOOGLE_SERVICE_ACCOUNT_KEY,
+        GOOGLE_SERVICE_ACCOUNT,
+        SERVICE_ACCOUNT,
+        GOOGLE_APPLICATION_CREDENTIALS,
+        GOOGLE_USER_PROJECT,
+        CHECKSUM,
+    ];
+}
+
 impl GCSStorageBackend {
     pub(crate) fn new() -> Result<Self, StorageError> {
-        let cred_path = std::env::var("SERVICE_ACCOUNT")
-            .map(std::path::PathBuf::from)
-            .map_err(|_err| {
-                StorageError::GCSConfig(
-                    "SERVICE_ACCOUNT environment variable must be set".to_string(),
-                )
-            })?;
-
-        Ok(cred_path.try_into()?)
+        Self::new_from_options(&HashMap::new())
+    }
+
+    /// Creates a backend configured by the [gcs_storage_options] keys of `options`. Options
+    /// missing from the map are read from the environment.
+    ///
+    /// Malformed credentials fail here rather than at the first request.
+    pub(crate) fn new_from_options(
+        options: &HashMap<String, String>,
+    ) -> Result<Self, StorageError> {
+        use gcs_storage_options::*;
+        let option = |key: &str| {
+            options
+                .get(key)
+                .cloned()
+                .or_else(|| std::env::var(key).ok())
+        };
+
+        let mut backend: Self = if let Some(key) = option(GOOGLE_SERVICE_ACCOUNT_KEY) {
+            // the error never contains the key itself
+            Self::from_service_account_key(key).map_err(|err| {
+                StorageError::GCSConfig(format!(
+                    "{} is not a valid service account key: {}",
+                    GOOGLE_SERVICE_ACCOUNT_KEY, err
+                ))
+            })?
+        } else {
+            let cred_path = CREDENTIAL_PATH_OPTS
+                .iter()
+                .find_map(|key| option(*key))
+                .map(std::path::PathBuf::from)
+                .ok_or_else(|| {
+                    StorageError::GCSConfig(format!(
+                        "One of {}, {} must be set",
+                        GOOGLE_SERVICE_ACCOUNT_KEY,
+                        CREDENTIAL_PATH_OPTS.join(", ")
+                    ))
+                })?;
+            cred_path.try_into()?
+        };
+        backend.client = ClientOptions::from_map(options).reqwest_client()?;
+        backend.user_project = option(GOOGLE_USER_PROJECT);
+        backend.checksum = option(CHECKSUM)
+            .map_or(Ok(None), |value| ChecksumAlgorithm::parse(&value))
+            .map_err(StorageError::InvalidOptions)?;
+        Ok(backend)
     }
 }
 
@@ -41,6 +117,9 @@ impl From<tame_gcs::objects::Metadata> for ObjectMeta {
         ObjectMeta {
             path: metadata.name.unwrap(),
             modified: metadata.updated.unwrap(),
+            size: metadata.size.map(|size| size as i64),
+            e_tag: metadata.etag,
+            version: metadata.generation.map(|generation| generation.to_string()),
         }
     }
 }
@@ -59,10 +138,14 @@ impl StorageBackend for GCSStorageBackend {
     async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
         debug!("getting object at: {}", path);
         let obj_uri = parse_uri(path)?.into_gcs_object()?;
-        match self.download(obj_uri).await {
+        let (data, hashes) = match self.download(obj_uri).await {
             Err(GCSClientError::NotFound) => return Err(StorageError::NotFound),
-            res => Ok(res?.to_vec()),
+            res => res?,
+        };
+        if let Some(algorithm) = self.checksum {
+            verify_goog_hash(path, algorithm, hashes.as_deref(), &data)?;
         }
+        Ok(data.to_vec())
     }
 
     /// Return a list of objects by `path` prefix in an async stream.
@@ -114,3 +197,70 @@ impl StorageBackend for GCSStorageBackend {
         Ok(self.delete(uri).await?)
     }
 }
+
+/// Verifies `data` against the checksum of `algorithm` in the `x-goog-hash` header, e.g.
+/// `crc32c=rth90Q==,md5=jXd/OF09/siBXSD3SWAm3A==`. Data without such a checksum is not verified.
+fn verify_goog_hash(
+    path: &str,
+    algorithm: ChecksumAlgorithm,
+    hashes: Option<&str>,
+    data: &[u8],
+) -> Result<(), StorageError> {
+    let expected = hashes
+        .into_iter()
+        .flat_map(|hashes| hashes.split(','))
+        .filter_map(|hash| hash.trim().split_once('='))
+        .find(|(name, _)| *name == algorithm.name())
+        .and_then(|(_, value)| base64::decode(value).ok());
+    match expected {
+        Some(expected) => algorithm.verify(path, &expected, data),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_inline_service_account_key() {
+        let secret = r#"{"type": "service_account", "private_key": "not-a-key"}"#;
+        let options = [(
+            gcs_storage_options::GOOGLE_SERVICE_ACCOUNT_KEY.to_string(),
+            secret.to_string(),
+        )]
+        .into_iter()
+        .collect();
+        match GCSStorageBackend::new_from_options(&options) {
+            Err(StorageError::GCSConfig(message)) => {
+                assert!(message.starts_with("GOOGLE_SERVICE_ACCOUNT_KEY is not a valid"));
+                assert!(!message.contains("not-a-key"));
+            }
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn verify_checksums() {
+        let hashes = Some("crc32c=rth90Q==,md5=jXd/OF09/siBXSD3SWAm3A==");
+        for algorithm in [ChecksumAlgorithm::Md5, ChecksumAlgorithm::Crc32c] {
+            assert!(verify_goog_hash("gs://bucket/obj", algorithm, hashes, b"data").is_ok());
+            assert!(matches!(
+                verify_goog_hash("gs://bucket/obj", algorithm, hashes, b"dbta"),
+                Err(StorageError::ChecksumMismatch { .. })
+            ));
+        }
+        // composite objects have no MD5
+        let composite = Some("crc32c=rth90Q==");
+        assert!(verify_goog_hash(
+            "gs://bucket/obj",
+            ChecksumAlgorithm::Md5,
+            composite,
+            b"dbta"
+        )
+        .is_ok());
+        assert!(
+            verify_goog_hash("gs://bucket/obj", ChecksumAlgorithm::Crc32c, None, b"dbta").is_ok()
+        );
+    }
+}
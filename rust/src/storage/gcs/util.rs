@@ -106,11 +106,24 @@ async fn convert_response(
 /// Executes a GCS request via a reqwest client and returns the parsed response/API error
 pub async fn execute<B, R>(
     ctx: &GCSStorageBackend,
-    mut req: http::Request<B>,
+    req: http::Request<B>,
 ) -> Result<R, GCSClientError>
 where
     R: tame_gcs::ApiResponse<bytes::Bytes>,
     B: std::io::Read + Send + 'static,
+{
+    let response = send(ctx, req).await?;
+    Ok(R::try_from_parts(response)?)
+}
+
+/// Sends a GCS request via a reqwest client and returns the unparsed response, e.g. to read
+/// headers the parsed responses drop
+pub async fn send<B>(
+    ctx: &GCSStorageBackend,
+    mut req: http::Request<B>,
+) -> Result<http::Response<bytes::Bytes>, GCSClientError>
+where
+    B: std::io::Read + Send + 'static,
 {
     // First, get our oauth token, which can mean we have to do an additional
     // request if we've never retrieved one yet, or the one we are using has expired
@@ -121,11 +134,20 @@ where
     req.headers_mut()
         .insert(http::header::AUTHORIZATION, token.try_into()?);
 
-    let request = convert_request(req, &ctx.client).await?;
+    let mut request = convert_request(req, &ctx.client).await?;
+    if let Some(user_project) = &ctx.user_project {
+        add_user_project(&mut request, user_project);
+    }
     let response = ctx.client.execute(request).await?;
-    let response = convert_response(response).await?;
+    convert_response(response).await
+}
 
-    Ok(R::try_from_parts(response)?)
+/// Bills the request to `user_project`, as required by requester-pays buckets.
+fn add_user_project(request: &mut reqwest::Request, user_project: &str) {
+    request
+        .url_mut()
+        .query_pairs_mut()
+        .append_pair("userProject", user_project);
 }
 
 use http::status::StatusCode;
@@ -148,3 +170,27 @@ pub fn check_precondition_status(err: GCSClientError) -> GCSClientError {
         err => err,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_project_parameter() {
+        let client = reqwest::Client::new();
+        for (url, expected) in [
+            (
+                "https://storage.googleapis.com/storage/v1/b/bucket/o/key",
+                "https://storage.googleapis.com/storage/v1/b/bucket/o/key?userProject=my-project",
+            ),
+            (
+                "https://storage.googleapis.com/storage/v1/b/bucket/o?prefix=table",
+                "https://storage.googleapis.com/storage/v1/b/bucket/o?prefix=table&userProject=my-project",
+            ),
+        ] {
+            let mut request = client.get(url).build().unwrap();
+            add_user_project(&mut request, "my-project");
+            assert_eq!(request.url().as_str(), expected);
+        }
+    }
+}
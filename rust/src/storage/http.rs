@@ -0,0 +1,349 @@
+//! A read-only storage backend for tables served by plain HTTP servers, e.g. static hosting or
+//! artifact servers, addressed as `http://<host>/<path>` or `https://<host>/<path>`.
+//!
+//! This module is gated behind the "http" feature.
+//!
+//! Objects are read with GET requests and their metadata with HEAD requests. HTTP has no
+//! standard way to list a directory, so listing, which is needed e.g. to load a table at an older
+//! version, requires a manifest of the served objects given with
+//! [http_options::HTTP_LIST_MANIFEST]. Writes fail with [StorageError::NotSupported].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{Stream, StreamExt};
+use reqwest::header::{HeaderMap, CONTENT_LENGTH, ETAG, LAST_MODIFIED};
+use reqwest::{Method, StatusCode, Url};
+
+use super::client::ClientOptions;
+use super::{parse_uri, ObjectMeta, StorageBackend, StorageError, UriError};
+
+/// Storage option keys to use when creating a [HttpStorageBackend].
+/// The same key should be used whether passing a key in the hashmap or setting it as an environment variable.
+pub mod http_options {
+    /// The URL of a text file listing the served objects, one path per line relative to the URL
+    /// of the manifest. Empty lines and lines starting with `#` are skipped.
+    pub const HTTP_LIST_MANIFEST: &str = "HTTP_LIST_MANIFEST";
+
+    /// The list of option keys owned by the HTTP module.
+    pub const HTTP_OPTS: &[&str] = &[HTTP_LIST_MANIFEST];
+}
+
+/// An object served over HTTP(S).
+#[derive(Debug, PartialEq)]
+pub struct HttpObject<'a> {
+    /// Whether the object is served over HTTPS.
+    pub secure: bool,
+    /// The host and optional port of the server.
+    pub authority: &'a str,
+    /// The path of the object on the server, without the leading separator.
+    pub path: &'a str,
+}
+
+impl<'a> fmt::Display for HttpObject<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scheme = if self.secure { "https" } else { "http" };
+        write!(f, "{}://{}/{}", scheme, self.authority, self.path)
+    }
+}
+
+impl<'a> HttpObject<'a> {
+    /// Parses the part of an HTTP(S) URI following the scheme.
+    pub(crate) fn parse(secure: bool, rest: &'a str) -> Result<Self, UriError> {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if authority.is_empty() {
+            return Err(UriError::MissingHttpHost);
+        }
+        Ok(Self {
+            secure,
+            authority,
+            path,
+        })
+    }
+}
+
+/// A read-only storage backend for objects served over HTTP(S).
+///
+/// Objects without a `Last-Modified` header are reported as modified at the Unix epoch.
+#[derive(Debug)]
+pub struct HttpStorageBackend {
+    client: reqwest::Client,
+    list_manifest: Option<Url>,
+}
+
+impl HttpStorageBackend {
+    /// Creates a new [`HttpStorageBackend`] configured by the environment.
+    pub fn new() -> Result<Self, StorageError> {
+        Self::new_from_options(&HashMap::new())
+    }
+
+    /// Creates a new [`HttpStorageBackend`] configured by the [http_options] keys of `options`.
+    /// Options missing from the map are read from the environment.
+    pub fn new_from_options(options: &HashMap<String, String>) -> Result<Self, StorageError> {
+        let list_manifest = options
+            .get(http_options::HTTP_LIST_MANIFEST)
+            .cloned()
+            .or_else(|| std::env::var(http_options::HTTP_LIST_MANIFEST).ok())
+            .map(|manifest| {
+                Url::parse(&manifest).map_err(|_| {
+                    StorageError::InvalidOptions(format!(
+                        "{} is not a valid URL",
+                        http_options::HTTP_LIST_MANIFEST
+                    ))
+                })
+            })
+            .transpose()?;
+        let client = ClientOptions::from_map(options).reqwest_client()?;
+        Ok(Self {
+            client,
+            list_manifest,
+        })
+    }
+
+    /// Sends the request and maps HTTP error statuses to storage errors.
+    async fn send(&self, method: Method, url: &str) -> Result<reqwest::Response, StorageError> {
+        let response = self.client.request(method, url).send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        Err(match status {
+            StatusCode::NOT_FOUND | StatusCode::GONE => StorageError::NotFound,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => StorageError::Io {
+                source: std::io::Error::new(
+                    std::io::ErrorKind::PermissionDenied,
+                    format!("{} {}", status, url),
+                ),
+            },
+            _ => StorageError::Http(format!("{} {}", status, url)),
+        })
+    }
+}
+
+fn object_meta(path: &str, headers: &HeaderMap) -> ObjectMeta {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    ObjectMeta {
+        path: path.to_string(),
+        modified: header(LAST_MODIFIED)
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+            .map(|modified| modified.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.timestamp(0, 0)),
+        size: header(CONTENT_LENGTH).and_then(|value| value.parse().ok()),
+        e_tag: header(ETAG).map(str::to_string),
+        version: None,
+    }
+}
+
+fn read_only(path: &str) -> StorageError {
+    StorageError::NotSupported(format!("cannot modify {}, HTTP storage is read-only", path))
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for HttpStorageBackend {
+    async fn head_obj(&self, path: &str) -> Result<ObjectMeta, StorageError> {
+        let obj = parse_uri(path)?.into_http_object()?;
+        let response = self.send(Method::HEAD, &obj.to_string()).await?;
+        Ok(object_meta(path, response.headers()))
+    }
+
+    async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let obj = parse_uri(path)?.into_http_object()?;
+        let response = self.send(Method::GET, &obj.to_string()).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn list_objs<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        let obj = parse_uri(path)?.into_http_object()?;
+        let manifest = self.list_manifest.as_ref().ok_or_else(|| {
+            StorageError::NotSupported(format!(
+                "cannot list {} without the {} option",
+                obj,
+                http_options::HTTP_LIST_MANIFEST
+            ))
+        })?;
+
+        let prefix = format!("{}/", obj.to_string().trim_end_matches('/'));
+        let body = self
+            .send(Method::GET, manifest.as_str())
+            .await?
+            .text()
+            .await?;
+        let mut urls = Vec::new();
+        for line in body.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let url = manifest.join(line).map_err(|err| {
+                StorageError::Http(format!("Invalid entry {:?} in {}: {}", line, manifest, err))
+            })?;
+            if url.as_str().starts_with(&prefix) {
+                urls.push(String::from(url));
+            }
+        }
+
+        Ok(Box::pin(
+            futures::stream::iter(urls).then(move |url| async move { self.head_obj(&url).await }),
+        ))
+    }
+
+    fn is_safe_for_concurrent_writes(&self) -> bool {
+        false
+    }
+
+    async fn put_obj(&self, path: &str, _obj_bytes: &[u8]) -> Result<(), StorageError> {
+        Err(read_only(path))
+    }
+
+    async fn rename_obj_noreplace(&self, _src: &str, dst: &str) -> Result<(), StorageError> {
+        Err(read_only(dst))
+    }
+
+    async fn delete_obj(&self, path: &str) -> Result<(), StorageError> {
+        Err(read_only(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+    use std::convert::Infallible;
+
+    const MODIFIED: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+
+    async fn serve(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+        let body = match request.uri().path() {
+            "/table/manifest.txt" => {
+                "# objects\n_delta_log/00000000000000000000.json\npart-0.parquet\n"
+            }
+            "/table/_delta_log/00000000000000000000.json" => "commit",
+            "/table/part-0.parquet" => "data",
+            _ => {
+                return Ok(Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::empty())
+                    .unwrap())
+            }
+        };
+        Ok(Response::builder()
+            .header(LAST_MODIFIED, MODIFIED)
+            .header(CONTENT_LENGTH, body.len())
+            .body(Body::from(body))
+            .unwrap())
+    }
+
+    /// Starts a server for the objects of [serve] and returns its base URL.
+    fn start_server() -> String {
+        let make_service = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(serve)) });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let url = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+        url
+    }
+
+    #[test]
+    fn parse_http_uri() {
+        let obj = parse_uri("https://example.com:8443/tables/t")
+            .unwrap()
+            .into_http_object()
+            .unwrap();
+        assert_eq!(
+            obj,
+            HttpObject {
+                secure: true,
+                authority: "example.com:8443",
+                path: "tables/t",
+            }
+        );
+        assert_eq!(obj.to_string(), "https://example.com:8443/tables/t");
+        assert!(parse_uri("http:///table").is_err());
+    }
+
+    #[tokio::test]
+    async fn read_objects() {
+        let base = start_server();
+        let backend = HttpStorageBackend::new_from_options(&HashMap::new()).unwrap();
+
+        let path = format!("{}/table/part-0.parquet", base);
+        assert_eq!(backend.get_obj(&path).await.unwrap(), b"data");
+        let meta = backend.head_obj(&path).await.unwrap();
+        assert_eq!(meta.size, Some(4));
+        assert_eq!(meta.modified, Utc.ymd(2015, 10, 21).and_hms(7, 28, 0));
+
+        let missing = format!("{}/table/missing", base);
+        assert!(matches!(
+            backend.get_obj(&missing).await,
+            Err(StorageError::NotFound)
+        ));
+        assert!(matches!(
+            backend.put_obj(&path, b"data").await,
+            Err(StorageError::NotSupported(_))
+        ));
+        assert!(matches!(
+            backend.list_objs(&format!("{}/table", base)).await,
+            Err(StorageError::NotSupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn list_from_manifest() {
+        let base = start_server();
+        let options = [(
+            http_options::HTTP_LIST_MANIFEST.to_string(),
+            format!("{}/table/manifest.txt", base),
+        )]
+        .iter()
+        .cloned()
+        .collect();
+        let backend = HttpStorageBackend::new_from_options(&options).unwrap();
+
+        let log_dir = format!("{}/table/_delta_log", base);
+        let listed: Vec<_> = backend
+            .list_objs(&log_dir)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(
+            listed[0].path,
+            format!("{}/00000000000000000000.json", log_dir)
+        );
+        assert_eq!(listed[0].size, Some(6));
+    }
+
+    #[tokio::test]
+    async fn times_out_unresponsive_servers() {
+        // connections to a listener that never accepts are established, but never answered
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let path = format!(
+            "http://{}/table/part-0.parquet",
+            listener.local_addr().unwrap()
+        );
+        let options = [("timeout", "1s"), ("max_retries", "0")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let backend = super::super::get_backend_for_uri_with_options(&path, options).unwrap();
+
+        let start = std::time::Instant::now();
+        match backend.get_obj(&path).await {
+            Err(StorageError::Io { source }) => {
+                assert_eq!(source.kind(), std::io::ErrorKind::TimedOut)
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+        assert!(start.elapsed() < std::time::Duration::from_secs(3));
+    }
+}
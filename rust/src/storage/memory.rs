@@ -0,0 +1,287 @@
+//! In-memory storage backend for tests and ephemeral tables, addressed as
+//! `memory://<store>/<path>`.
+//!
+//! Objects are kept in named stores that live until the end of the process or until they are
+//! [cleared](clear). All backends of a process share the stores, so a table written through one
+//! backend can be read through another one with the same store name. Store names are case
+//! insensitive.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock};
+
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use lazy_static::lazy_static;
+
+use super::{parse_uri, ObjectMeta, StorageBackend, StorageError};
+
+/// An object in an in-memory store.
+#[derive(Debug, PartialEq)]
+pub struct MemoryObject<'a> {
+    /// The name of the store.
+    pub store: &'a str,
+    /// The path of the object within the store.
+    pub path: &'a str,
+}
+
+impl<'a> fmt::Display for MemoryObject<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "memory://{}/{}", self.store, self.path)
+    }
+}
+
+#[derive(Debug, Default)]
+struct MemoryStore {
+    objects: RwLock<BTreeMap<String, (Vec<u8>, DateTime<Utc>)>>,
+}
+
+lazy_static! {
+    static ref STORES: Mutex<HashMap<String, Arc<MemoryStore>>> = Mutex::new(HashMap::new());
+}
+
+fn store(name: &str) -> Arc<MemoryStore> {
+    STORES
+        .lock()
+        .unwrap()
+        .entry(name.to_lowercase())
+        .or_default()
+        .clone()
+}
+
+/// Returns the contents of all objects in the store `name` by path, e.g. to assert on the
+/// files written to a table.
+pub fn snapshot(name: &str) -> BTreeMap<String, Vec<u8>> {
+    store(name)
+        .objects
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(path, (data, _))| (path.clone(), data.clone()))
+        .collect()
+}
+
+/// Removes the store `name` with all its objects.
+pub fn clear(name: &str) {
+    STORES.lock().unwrap().remove(&name.to_lowercase());
+}
+
+/// A storage backend keeping objects in the process memory.
+///
+/// Listings return all objects below a path, like object stores do.
+#[derive(Debug, Default)]
+pub struct MemoryStorageBackend {}
+
+impl MemoryStorageBackend {
+    /// Creates a new backend for the in-memory stores.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for MemoryStorageBackend {
+    async fn head_obj(&self, path: &str) -> Result<ObjectMeta, StorageError> {
+        let obj = parse_uri(path)?.into_memory_object()?;
+        let store = store(obj.store);
+        let objects = store.objects.read().unwrap();
+        let (data, modified) = objects.get(obj.path).ok_or(StorageError::NotFound)?;
+        Ok(ObjectMeta {
+            path: path.to_string(),
+            modified: *modified,
+            size: Some(data.len() as i64),
+            e_tag: None,
+            version: None,
+        })
+    }
+
+    async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let obj = parse_uri(path)?.into_memory_object()?;
+        let store = store(obj.store);
+        let objects = store.objects.read().unwrap();
+        let (data, _) = objects.get(obj.path).ok_or(StorageError::NotFound)?;
+        Ok(data.clone())
+    }
+
+    async fn list_objs<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        let obj = parse_uri(path)?.into_memory_object()?;
+        let prefix = match obj.path.trim_end_matches('/') {
+            "" => String::new(),
+            dir => format!("{}/", dir),
+        };
+        let store = store(obj.store);
+        let objects = store.objects.read().unwrap();
+        let metas: Vec<_> = objects
+            .range(prefix.clone()..)
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, (data, modified))| {
+                Ok(ObjectMeta {
+                    path: MemoryObject {
+                        store: obj.store,
+                        path: key,
+                    }
+                    .to_string(),
+                    modified: *modified,
+                    size: Some(data.len() as i64),
+                    e_tag: None,
+                    version: None,
+                })
+            })
+            .collect();
+        Ok(Box::pin(futures::stream::iter(metas)))
+    }
+
+    async fn put_obj(&self, path: &str, obj_bytes: &[u8]) -> Result<(), StorageError> {
+        let obj = parse_uri(path)?.into_memory_object()?;
+        store(obj.store)
+            .objects
+            .write()
+            .unwrap()
+            .insert(obj.path.to_string(), (obj_bytes.to_vec(), Utc::now()));
+        Ok(())
+    }
+
+    async fn rename_obj_noreplace(&self, src: &str, dst: &str) -> Result<(), StorageError> {
+        let src_obj = parse_uri(src)?.into_memory_object()?;
+        let dst_obj = parse_uri(dst)?.into_memory_object()?;
+        if !src_obj.store.eq_ignore_ascii_case(dst_obj.store) {
+            return Err(StorageError::Generic(format!(
+                "Cannot rename {} to a different store: {}",
+                src, dst
+            )));
+        }
+        let store = store(src_obj.store);
+        let mut objects = store.objects.write().unwrap();
+        if objects.contains_key(dst_obj.path) {
+            return Err(StorageError::AlreadyExists(dst.to_string()));
+        }
+        let (data, _) = objects.remove(src_obj.path).ok_or(StorageError::NotFound)?;
+        objects.insert(dst_obj.path.to_string(), (data, Utc::now()));
+        Ok(())
+    }
+
+    async fn delete_obj(&self, path: &str) -> Result<(), StorageError> {
+        let obj = parse_uri(path)?.into_memory_object()?;
+        store(obj.store)
+            .objects
+            .write()
+            .unwrap()
+            .remove(obj.path)
+            .map(|_| ())
+            .ok_or(StorageError::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+
+    #[tokio::test]
+    async fn shared_between_backends() {
+        let writer = MemoryStorageBackend::new();
+        let reader = MemoryStorageBackend::new();
+        writer
+            .put_obj("memory://shared/table/_delta_log/0.json", b"commit")
+            .await
+            .unwrap();
+        writer
+            .put_obj("memory://shared/table/part-0.parquet", b"data")
+            .await
+            .unwrap();
+        writer
+            .put_obj("memory://shared/table2/part-0.parquet", b"other")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            reader
+                .get_obj("memory://SHARED/table/part-0.parquet")
+                .await
+                .unwrap(),
+            b"data"
+        );
+        let listed: Vec<_> = reader
+            .list_objs("memory://shared/table")
+            .await
+            .unwrap()
+            .map_ok(|meta| meta.path)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            listed,
+            vec![
+                "memory://shared/table/_delta_log/0.json",
+                "memory://shared/table/part-0.parquet",
+            ]
+        );
+        assert!(matches!(
+            reader.get_obj("memory://other/table/part-0.parquet").await,
+            Err(StorageError::NotFound)
+        ));
+
+        let contents = snapshot("shared");
+        assert_eq!(contents.len(), 3);
+        assert_eq!(contents["table/part-0.parquet"], b"data");
+        clear("shared");
+        assert!(snapshot("shared").is_empty());
+    }
+
+    #[tokio::test]
+    async fn list_after() {
+        let backend = MemoryStorageBackend::new();
+        for name in ["c", "a", "b/1", "b/2"] {
+            let path = format!("memory://list_after/table/{}", name);
+            backend.put_obj(&path, b"data").await.unwrap();
+        }
+
+        let listed: Vec<_> = backend
+            .list_objs_after("memory://list_after/table", "memory://list_after/table/b/1")
+            .await
+            .unwrap()
+            .map_ok(|meta| meta.path)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            listed,
+            vec![
+                "memory://list_after/table/b/2",
+                "memory://list_after/table/c",
+            ]
+        );
+        clear("list_after");
+    }
+
+    #[tokio::test]
+    async fn rename_noreplace() {
+        let backend = MemoryStorageBackend::new();
+        backend.put_obj("memory://rename/a", b"a").await.unwrap();
+        backend.put_obj("memory://rename/b", b"b").await.unwrap();
+
+        assert!(matches!(
+            backend
+                .rename_obj_noreplace("memory://rename/a", "memory://rename/b")
+                .await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        backend
+            .rename_obj_noreplace("memory://rename/a", "memory://rename/c")
+            .await
+            .unwrap();
+        assert_eq!(backend.get_obj("memory://rename/c").await.unwrap(), b"a");
+        assert!(matches!(
+            backend.delete_obj("memory://rename/a").await,
+            Err(StorageError::NotFound)
+        ));
+        clear("rename");
+    }
+}
@@ -0,0 +1,277 @@
+//! Storage backend decorator counting the requests and transferred bytes of each operation.
+
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::Stream;
+
+use super::rate_limit::RateLimitStats;
+use super::{ObjectMeta, StorageBackend, StorageError};
+
+/// An operation of a [StorageBackend]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StorageOperation {
+    /// [StorageBackend::head_obj]
+    Head,
+    /// [StorageBackend::get_obj]
+    Get,
+    /// [StorageBackend::list_objs]
+    List,
+    /// [StorageBackend::put_obj]
+    Put,
+    /// [StorageBackend::rename_obj_noreplace]
+    Rename,
+    /// [StorageBackend::delete_obj] and [StorageBackend::delete_objs]
+    Delete,
+}
+
+impl StorageOperation {
+    /// All operations
+    pub const ALL: [StorageOperation; 6] = [
+        StorageOperation::Head,
+        StorageOperation::Get,
+        StorageOperation::List,
+        StorageOperation::Put,
+        StorageOperation::Rename,
+        StorageOperation::Delete,
+    ];
+
+    /// Lowercase name of the operation
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageOperation::Head => "head",
+            StorageOperation::Get => "get",
+            StorageOperation::List => "list",
+            StorageOperation::Put => "put",
+            StorageOperation::Rename => "rename",
+            StorageOperation::Delete => "delete",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Metrics of one [StorageOperation]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OperationMetrics {
+    /// Number of calls, including failed ones
+    pub calls: u64,
+    /// Number of bytes read or written. Listings and deletes transfer no bytes.
+    pub bytes: u64,
+    /// Total time spent in the calls
+    pub latency: Duration,
+}
+
+#[derive(Debug, Default)]
+struct OperationCounters {
+    calls: AtomicU64,
+    bytes: AtomicU64,
+    latency_nanos: AtomicU64,
+}
+
+/// Counters shared by a [MetricsStorageBackend] and its readers
+#[derive(Debug, Default)]
+pub struct StorageMetrics {
+    counters: [OperationCounters; 6],
+}
+
+impl StorageMetrics {
+    fn record(&self, operation: StorageOperation, bytes: usize, latency: Duration) {
+        let counters = &self.counters[operation.index()];
+        counters.calls.fetch_add(1, Ordering::Relaxed);
+        counters.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        counters
+            .latency_nanos
+            .fetch_add(latency.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the metrics of `operation` since creation or the last reset.
+    pub fn get(&self, operation: StorageOperation) -> OperationMetrics {
+        let counters = &self.counters[operation.index()];
+        OperationMetrics {
+            calls: counters.calls.load(Ordering::Relaxed),
+            bytes: counters.bytes.load(Ordering::Relaxed),
+            latency: Duration::from_nanos(counters.latency_nanos.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Sets all counters to zero.
+    pub fn reset(&self) {
+        for counters in &self.counters {
+            counters.calls.store(0, Ordering::Relaxed);
+            counters.bytes.store(0, Ordering::Relaxed);
+            counters.latency_nanos.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Wraps a storage backend and records the number of calls, transferred bytes and latency of
+/// each of its operations in [StorageMetrics].
+#[derive(Debug)]
+pub struct MetricsStorageBackend {
+    inner: Box<dyn StorageBackend>,
+    metrics: Arc<StorageMetrics>,
+}
+
+impl MetricsStorageBackend {
+    /// Creates a backend recording the operations of `inner`.
+    pub fn new(inner: Box<dyn StorageBackend>) -> Self {
+        Self {
+            inner,
+            metrics: Arc::new(StorageMetrics::default()),
+        }
+    }
+
+    /// Returns the metrics, which stay readable after the backend is moved into a table.
+    pub fn metrics(&self) -> Arc<StorageMetrics> {
+        self.metrics.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for MetricsStorageBackend {
+    fn join_path(&self, path: &str, path_to_join: &str) -> String {
+        self.inner.join_path(path, path_to_join)
+    }
+
+    fn join_paths(&self, paths: &[&str]) -> String {
+        self.inner.join_paths(paths)
+    }
+
+    fn trim_path(&self, path: &str) -> String {
+        self.inner.trim_path(path)
+    }
+
+    fn is_safe_for_concurrent_writes(&self) -> bool {
+        self.inner.is_safe_for_concurrent_writes()
+    }
+
+    fn rate_limit_stats(&self) -> Option<Arc<RateLimitStats>> {
+        self.inner.rate_limit_stats()
+    }
+
+    async fn head_obj(&self, path: &str) -> Result<ObjectMeta, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.head_obj(path).await;
+        self.metrics
+            .record(StorageOperation::Head, 0, start.elapsed());
+        result
+    }
+
+    async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_obj(path).await;
+        let bytes = result.as_ref().map(|obj| obj.len()).unwrap_or(0);
+        self.metrics
+            .record(StorageOperation::Get, bytes, start.elapsed());
+        result
+    }
+
+    async fn get_range(&self, path: &str, range: Range<usize>) -> Result<Vec<u8>, StorageError> {
+        let start = Instant::now();
+        let result = self.inner.get_range(path, range).await;
+        let bytes = result.as_ref().map(|obj| obj.len()).unwrap_or(0);
+        self.metrics
+            .record(StorageOperation::Get, bytes, start.elapsed());
+        result
+    }
+
+    async fn list_objs<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        // only the time to start the listing is recorded, consuming the stream is not
+        let start = Instant::now();
+        let result = self.inner.list_objs(path).await;
+        self.metrics
+            .record(StorageOperation::List, 0, start.elapsed());
+        result
+    }
+
+    async fn list_objs_after<'a>(
+        &'a self,
+        path: &'a str,
+        start_after: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        let start = Instant::now();
+        let result = self.inner.list_objs_after(path, start_after).await;
+        self.metrics
+            .record(StorageOperation::List, 0, start.elapsed());
+        result
+    }
+
+    async fn put_obj(&self, path: &str, obj_bytes: &[u8]) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.put_obj(path, obj_bytes).await;
+        self.metrics
+            .record(StorageOperation::Put, obj_bytes.len(), start.elapsed());
+        result
+    }
+
+    async fn rename_obj_noreplace(&self, src: &str, dst: &str) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.rename_obj_noreplace(src, dst).await;
+        self.metrics
+            .record(StorageOperation::Rename, 0, start.elapsed());
+        result
+    }
+
+    async fn delete_obj(&self, path: &str) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.delete_obj(path).await;
+        self.metrics
+            .record(StorageOperation::Delete, 0, start.elapsed());
+        result
+    }
+
+    async fn delete_objs(&self, paths: &[String]) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.inner.delete_objs(paths).await;
+        self.metrics
+            .record(StorageOperation::Delete, 0, start.elapsed());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::file::FileStorageBackend;
+    use super::*;
+
+    #[tokio::test]
+    async fn records_calls_and_bytes() {
+        let tmp_dir = tempdir::TempDir::new("metrics_test").unwrap();
+        let root = tmp_dir.path().to_str().unwrap();
+        let backend = MetricsStorageBackend::new(Box::new(FileStorageBackend::new(root)));
+        let metrics = backend.metrics();
+        let path = backend.join_path(root, "data");
+
+        backend.put_obj(&path, b"hello").await.unwrap();
+        backend.get_obj(&path).await.unwrap();
+        backend.get_obj(&path).await.unwrap();
+        assert!(backend.get_obj(&format!("{}_missing", path)).await.is_err());
+
+        let get = metrics.get(StorageOperation::Get);
+        assert_eq!(get.calls, 3);
+        assert_eq!(get.bytes, 10);
+        assert_eq!(metrics.get(StorageOperation::Put).bytes, 5);
+        assert_eq!(metrics.get(StorageOperation::Head).calls, 0);
+
+        metrics.reset();
+        assert_eq!(
+            metrics.get(StorageOperation::Get),
+            OperationMetrics::default()
+        );
+    }
+}
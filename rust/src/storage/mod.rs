@@ -1,10 +1,12 @@
 //! Object storage backend abstraction layer for Delta Table transaction logs and data
 
 use std::fmt::Debug;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
 use std::pin::Pin;
 
 use chrono::{DateTime, Utc};
-use futures::Stream;
+use futures::{Stream, TryStreamExt};
 
 #[cfg(feature = "azure")]
 use azure_core::{Error as AzureError, HttpError as AzureHttpError};
@@ -18,11 +20,23 @@ use self::s3::S3StorageOptions;
 
 #[cfg(feature = "azure")]
 pub mod azure;
+#[cfg(any(feature = "gcs", feature = "s3", feature = "s3-rustls"))]
+pub mod checksum;
+pub mod client;
 pub mod file;
 #[cfg(any(feature = "gcs"))]
 pub mod gcs;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod memory;
+pub mod metrics;
+pub mod options;
+pub mod rate_limit;
+pub mod retry;
 #[cfg(any(feature = "s3", feature = "s3-rustls"))]
 pub mod s3;
+#[cfg(feature = "webhdfs")]
+pub mod webhdfs;
 
 /// Error enum that represents an invalid URI.
 #[derive(thiserror::Error, Debug, PartialEq)]
@@ -33,6 +47,9 @@ pub enum UriError {
     /// Error returned when a local file system path is expected, but the URI is not a local file system path.
     #[error("Expected local path URI, found: {0}")]
     ExpectedSLocalPathUri(String),
+    /// Error returned when an in-memory store URI is expected, but the URI is not a memory URI.
+    #[error("Expected memory URI, found: {0}")]
+    ExpectedMemoryUri(String),
 
     /// Error returned when the URI is expected to be an object storage path, but does not include a bucket part.
     #[cfg(any(feature = "gcs", feature = "s3", feature = "s3-rustls"))]
@@ -75,6 +92,23 @@ pub enum UriError {
     #[cfg(feature = "azure")]
     #[error("Object URI missing path")]
     MissingObjectPath,
+    /// Error returned when a WebHDFS URI is expected, but the URI is not a WebHDFS URI.
+    #[cfg(feature = "webhdfs")]
+    #[error("Expected WebHDFS URI, found: {0}")]
+    ExpectedWebHdfsUri(String),
+    /// Error returned when a WebHDFS URI does not include the namenode.
+    #[cfg(feature = "webhdfs")]
+    #[error("WebHDFS URI missing namenode")]
+    MissingWebHdfsNamenode,
+    /// Error returned when an HTTP(S) URI is expected, but the URI is not an HTTP(S) URI.
+    #[cfg(feature = "http")]
+    #[error("Expected HTTP URI, found: {0}")]
+    ExpectedHttpUri(String),
+    /// Error returned when an HTTP(S) URI does not include the host.
+    #[cfg(feature = "http")]
+    #[error("HTTP URI missing host")]
+    MissingHttpHost,
+
     /// Error returned when container in an Azure URI doesn't match the expected value
     #[cfg(feature = "azure")]
     #[error("Container mismatch, expected: {expected}, got: {got}")]
@@ -91,6 +125,8 @@ pub enum UriError {
 pub enum Uri<'a> {
     /// URI for local file system backend.
     LocalPath(&'a str),
+    /// URI for in-memory backend.
+    Memory(memory::MemoryObject<'a>),
     /// URI for S3 backend.
     #[cfg(any(feature = "s3", feature = "s3-rustls"))]
     S3Object(s3::S3Object<'a>),
@@ -100,6 +136,12 @@ pub enum Uri<'a> {
     /// URI for GCS backend
     #[cfg(feature = "gcs")]
     GCSObject(gcs::GCSObject<'a>),
+    /// URI for WebHDFS backend
+    #[cfg(feature = "webhdfs")]
+    WebHdfsObject(webhdfs::WebHdfsObject<'a>),
+    /// URI for the read-only HTTP(S) backend
+    #[cfg(feature = "http")]
+    HttpObject(http::HttpObject<'a>),
 }
 
 impl<'a> Uri<'a> {
@@ -113,7 +155,12 @@ impl<'a> Uri<'a> {
             Uri::AdlsGen2Object(x) => Err(UriError::ExpectedS3Uri(x.to_string())),
             #[cfg(feature = "gcs")]
             Uri::GCSObject(x) => Err(UriError::ExpectedS3Uri(x.to_string())),
+            #[cfg(feature = "webhdfs")]
+            Uri::WebHdfsObject(x) => Err(UriError::ExpectedS3Uri(x.to_string())),
+            #[cfg(feature = "http")]
+            Uri::HttpObject(x) => Err(UriError::ExpectedS3Uri(x.to_string())),
             Uri::LocalPath(x) => Err(UriError::ExpectedS3Uri(x.to_string())),
+            Uri::Memory(x) => Err(UriError::ExpectedS3Uri(x.to_string())),
         }
     }
 
@@ -127,7 +174,12 @@ impl<'a> Uri<'a> {
             Uri::S3Object(x) => Err(UriError::ExpectedAzureUri(x.to_string())),
             #[cfg(feature = "gcs")]
             Uri::GCSObject(x) => Err(UriError::ExpectedAzureUri(x.to_string())),
+            #[cfg(feature = "webhdfs")]
+            Uri::WebHdfsObject(x) => Err(UriError::ExpectedAzureUri(x.to_string())),
+            #[cfg(feature = "http")]
+            Uri::HttpObject(x) => Err(UriError::ExpectedAzureUri(x.to_string())),
             Uri::LocalPath(x) => Err(UriError::ExpectedAzureUri(x.to_string())),
+            Uri::Memory(x) => Err(UriError::ExpectedAzureUri(x.to_string())),
         }
     }
 
@@ -141,7 +193,50 @@ impl<'a> Uri<'a> {
             Uri::S3Object(x) => Err(UriError::ExpectedGCSUri(x.to_string())),
             #[cfg(feature = "azure")]
             Uri::AdlsGen2Object(x) => Err(UriError::ExpectedGCSUri(x.to_string())),
+            #[cfg(feature = "webhdfs")]
+            Uri::WebHdfsObject(x) => Err(UriError::ExpectedGCSUri(x.to_string())),
+            #[cfg(feature = "http")]
+            Uri::HttpObject(x) => Err(UriError::ExpectedGCSUri(x.to_string())),
             Uri::LocalPath(x) => Err(UriError::ExpectedGCSUri(x.to_string())),
+            Uri::Memory(x) => Err(UriError::ExpectedGCSUri(x.to_string())),
+        }
+    }
+
+    /// Converts the URI to a WebHdfsObject. Returns UriError if the URI is not valid for the
+    /// WebHDFS backend.
+    #[cfg(feature = "webhdfs")]
+    pub fn into_webhdfs_object(self) -> Result<webhdfs::WebHdfsObject<'a>, UriError> {
+        match self {
+            Uri::WebHdfsObject(x) => Ok(x),
+            #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+            Uri::S3Object(x) => Err(UriError::ExpectedWebHdfsUri(x.to_string())),
+            #[cfg(feature = "azure")]
+            Uri::AdlsGen2Object(x) => Err(UriError::ExpectedWebHdfsUri(x.to_string())),
+            #[cfg(feature = "gcs")]
+            Uri::GCSObject(x) => Err(UriError::ExpectedWebHdfsUri(x.to_string())),
+            Uri::LocalPath(x) => Err(UriError::ExpectedWebHdfsUri(x.to_string())),
+            Uri::Memory(x) => Err(UriError::ExpectedWebHdfsUri(x.to_string())),
+            #[cfg(feature = "http")]
+            Uri::HttpObject(x) => Err(UriError::ExpectedWebHdfsUri(x.to_string())),
+        }
+    }
+
+    /// Converts the URI to an HttpObject. Returns UriError if the URI is not valid for the HTTP
+    /// backend.
+    #[cfg(feature = "http")]
+    pub fn into_http_object(self) -> Result<http::HttpObject<'a>, UriError> {
+        match self {
+            Uri::HttpObject(x) => Ok(x),
+            #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+            Uri::S3Object(x) => Err(UriError::ExpectedHttpUri(x.to_string())),
+            #[cfg(feature = "azure")]
+            Uri::AdlsGen2Object(x) => Err(UriError::ExpectedHttpUri(x.to_string())),
+            #[cfg(feature = "gcs")]
+            Uri::GCSObject(x) => Err(UriError::ExpectedHttpUri(x.to_string())),
+            #[cfg(feature = "webhdfs")]
+            Uri::WebHdfsObject(x) => Err(UriError::ExpectedHttpUri(x.to_string())),
+            Uri::LocalPath(x) => Err(UriError::ExpectedHttpUri(x.to_string())),
+            Uri::Memory(x) => Err(UriError::ExpectedHttpUri(x.to_string())),
         }
     }
 
@@ -150,12 +245,36 @@ impl<'a> Uri<'a> {
     pub fn into_localpath(self) -> Result<&'a str, UriError> {
         match self {
             Uri::LocalPath(x) => Ok(x),
+            Uri::Memory(x) => Err(UriError::ExpectedSLocalPathUri(x.to_string())),
             #[cfg(any(feature = "s3", feature = "s3-rustls"))]
             Uri::S3Object(x) => Err(UriError::ExpectedSLocalPathUri(format!("{}", x))),
             #[cfg(feature = "azure")]
             Uri::AdlsGen2Object(x) => Err(UriError::ExpectedSLocalPathUri(format!("{}", x))),
             #[cfg(feature = "gcs")]
             Uri::GCSObject(x) => Err(UriError::ExpectedSLocalPathUri(format!("{}", x))),
+            #[cfg(feature = "webhdfs")]
+            Uri::WebHdfsObject(x) => Err(UriError::ExpectedSLocalPathUri(format!("{}", x))),
+            #[cfg(feature = "http")]
+            Uri::HttpObject(x) => Err(UriError::ExpectedSLocalPathUri(format!("{}", x))),
+        }
+    }
+
+    /// Converts the URI to a MemoryObject. Returns UriError if the URI is not valid for the
+    /// in-memory backend.
+    pub fn into_memory_object(self) -> Result<memory::MemoryObject<'a>, UriError> {
+        match self {
+            Uri::Memory(x) => Ok(x),
+            #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+            Uri::S3Object(x) => Err(UriError::ExpectedMemoryUri(x.to_string())),
+            #[cfg(feature = "azure")]
+            Uri::AdlsGen2Object(x) => Err(UriError::ExpectedMemoryUri(x.to_string())),
+            #[cfg(feature = "gcs")]
+            Uri::GCSObject(x) => Err(UriError::ExpectedMemoryUri(x.to_string())),
+            #[cfg(feature = "webhdfs")]
+            Uri::WebHdfsObject(x) => Err(UriError::ExpectedMemoryUri(x.to_string())),
+            #[cfg(feature = "http")]
+            Uri::HttpObject(x) => Err(UriError::ExpectedMemoryUri(x.to_string())),
+            Uri::LocalPath(x) => Err(UriError::ExpectedMemoryUri(x.to_string())),
         }
     }
 
@@ -164,12 +283,17 @@ impl<'a> Uri<'a> {
     pub fn path(&self) -> String {
         match self {
             Uri::LocalPath(x) => x.to_string(),
+            Uri::Memory(x) => x.path.to_string(),
             #[cfg(any(feature = "s3", feature = "s3-rustls"))]
             Uri::S3Object(x) => x.key.to_string(),
             #[cfg(feature = "azure")]
             Uri::AdlsGen2Object(x) => x.path.to_string(),
             #[cfg(feature = "gcs")]
             Uri::GCSObject(x) => x.path.to_string(),
+            #[cfg(feature = "webhdfs")]
+            Uri::WebHdfsObject(x) => x.path.to_string(),
+            #[cfg(feature = "http")]
+            Uri::HttpObject(x) => x.path.to_string(),
         }
     }
 }
@@ -234,6 +358,28 @@ pub fn parse_uri<'a>(path: &'a str) -> Result<Uri<'a>, UriError> {
         }
 
         "file" => Ok(Uri::LocalPath(parts[1])),
+        "memory" => {
+            let (store, path) = parts[1].split_once('/').unwrap_or((parts[1], ""));
+            Ok(Uri::Memory(memory::MemoryObject { store, path }))
+        }
+        "webhdfs" | "swebhdfs" => {
+            cfg_if::cfg_if! {
+                if #[cfg(feature = "webhdfs")] {
+                    Ok(Uri::WebHdfsObject(webhdfs::WebHdfsObject::parse(parts[0] == "swebhdfs", parts[1])?))
+                } else {
+                    Err(UriError::InvalidScheme(String::from(parts[0])))
+                }
+            }
+        }
+        "http" | "https" => {
+            cfg_if::cfg_if! {
+                if #[cfg(feature = "http")] {
+                    Ok(Uri::HttpObject(http::HttpObject::parse(parts[0] == "https", parts[1])?))
+                } else {
+                    Err(UriError::InvalidScheme(String::from(parts[0])))
+                }
+            }
+        }
         "abfss" => {
             cfg_if::cfg_if! {
                 if #[cfg(feature = "azure")] {
@@ -255,6 +401,67 @@ pub fn parse_uri<'a>(path: &'a str) -> Result<Uri<'a>, UriError> {
     }
 }
 
+/// Returns the canonical form of a table URI so that every spelling of the same location compares
+/// equal.
+///
+/// * scheme and host/bucket are lowercased and trailing separators are removed.
+/// * `file://` URIs and relative paths are resolved to absolute local paths, with `.` and `..`
+///   components removed. Windows drive letter paths (`C:\foo`, `file:///C:/foo`) are kept as is.
+pub fn normalize_table_uri(table_uri: &str) -> Result<String, StorageError> {
+    let (scheme, rest) = match table_uri.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme.to_lowercase()), rest),
+        None => (None, table_uri),
+    };
+
+    match scheme.as_deref() {
+        None | Some("file") => normalize_local_path(rest),
+        Some(scheme) => {
+            let rest = rest.trim_end_matches('/');
+            Ok(match rest.split_once('/') {
+                Some((host, path)) => format!("{}://{}/{}", scheme, host.to_lowercase(), path),
+                None => format!("{}://{}", scheme, rest.to_lowercase()),
+            })
+        }
+    }
+}
+
+fn normalize_local_path(path: &str) -> Result<String, StorageError> {
+    // `file:///C:/foo` leaves a separator in front of the drive letter
+    let path = match path.strip_prefix('/') {
+        Some(p) if cfg!(windows) && is_windows_drive_path(p) => p,
+        _ => path,
+    };
+
+    let path = Path::new(path);
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c.as_os_str()),
+        }
+    }
+
+    normalized
+        .into_os_string()
+        .into_string()
+        .map_err(|p| StorageError::Generic(format!("Table path is not valid unicode: {:?}", p)))
+}
+
+#[inline]
+fn is_windows_drive_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
 /// Error enum returned when storage backend interaction fails.
 #[derive(thiserror::Error, Debug)]
 pub enum StorageError {
@@ -279,6 +486,26 @@ pub enum StorageError {
     /// Wraps a generic storage backend error. The wrapped string contains the details.
     #[error("Generic error: {0}")]
     Generic(String),
+    /// Error returned when the storage options are invalid. The message names the offending
+    /// option keys, but never their values.
+    #[error("Invalid storage options: {0}")]
+    InvalidOptions(String),
+    /// The operation is not supported by the storage backend, e.g. writes to a read-only backend.
+    #[error("Operation not supported: {0}")]
+    NotSupported(String),
+    /// The contents of an object read from the storage do not match the checksum reported by the
+    /// storage, e.g. because they were corrupted in transit. Reading the object again may succeed.
+    #[error("Checksum mismatch for {path}: expected {algorithm} {expected}, found {actual}")]
+    ChecksumMismatch {
+        /// The path of the object.
+        path: String,
+        /// The name of the checksum algorithm.
+        algorithm: &'static str,
+        /// The checksum reported by the storage, hex encoded.
+        expected: String,
+        /// The checksum of the contents read, hex encoded.
+        actual: String,
+    },
 
     /// Error representing an S3 GET failure.
     #[cfg(any(feature = "s3", feature = "s3-rustls"))]
@@ -402,6 +629,16 @@ pub enum StorageError {
     #[error("GCS config error: {0}")]
     GCSConfig(String),
 
+    /// WebHDFS error
+    #[cfg(feature = "webhdfs")]
+    #[error("WebHDFS error: {0}")]
+    WebHdfs(String),
+
+    /// HTTP request error of the backends built on reqwest
+    #[cfg(any(feature = "gcs", feature = "webhdfs", feature = "http"))]
+    #[error("HTTP error: {0}")]
+    Http(String),
+
     /// GCS client error
     #[cfg(feature = "gcs")]
     #[error("GCS error: {source}")]
@@ -430,6 +667,23 @@ impl StorageError {
     }
 }
 
+#[cfg(any(feature = "gcs", feature = "webhdfs", feature = "http"))]
+impl From<reqwest::Error> for StorageError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            StorageError::Io {
+                source: std::io::Error::new(std::io::ErrorKind::TimedOut, err),
+            }
+        } else if err.is_connect() {
+            StorageError::Io {
+                source: std::io::Error::new(std::io::ErrorKind::ConnectionRefused, err),
+            }
+        } else {
+            StorageError::Http(err.to_string())
+        }
+    }
+}
+
 impl From<std::io::Error> for StorageError {
     fn from(error: std::io::Error) -> Self {
         match error.kind() {
@@ -480,6 +734,14 @@ pub struct ObjectMeta {
     // The timestamp of a commit comes from the remote storage `lastModifiedTime`, and can be
     // adjusted for clock skew.
     pub modified: DateTime<Utc>,
+    /// The size of the object in bytes, if the storage backend reports it.
+    pub size: Option<i64>,
+    /// The entity tag of the object, which changes whenever its content does, if the storage
+    /// backend reports it.
+    pub e_tag: Option<String>,
+    /// The version of the object, if the storage backend versions objects, e.g. the version ID
+    /// on S3 or the generation on GCS.
+    pub version: Option<String>,
 }
 
 impl Clone for ObjectMeta {
@@ -487,6 +749,9 @@ impl Clone for ObjectMeta {
         Self {
             path: self.path.clone(),
             modified: self.modified,
+            size: self.size,
+            e_tag: self.e_tag.clone(),
+            version: self.version.clone(),
         }
     }
 }
@@ -525,6 +790,17 @@ pub trait StorageBackend: Send + Sync + Debug {
     /// Fetch object content
     async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError>;
 
+    /// Fetch the bytes of `range` of the object content. The range is cut off at the end of the
+    /// object, so it may return fewer bytes than requested.
+    ///
+    /// The default implementation fetches the whole object. Backends that can read a part of an
+    /// object override it.
+    async fn get_range(&self, path: &str, range: Range<usize>) -> Result<Vec<u8>, StorageError> {
+        let obj = self.get_obj(path).await?;
+        let end = range.end.min(obj.len());
+        Ok(obj[range.start.min(end)..end].to_vec())
+    }
+
     /// Return a list of objects by `path` prefix in an async stream.
     async fn list_objs<'a>(
         &'a self,
@@ -534,6 +810,25 @@ pub trait StorageBackend: Send + Sync + Debug {
         StorageError,
     >;
 
+    /// Return the objects by `path` prefix whose paths sort after `start_after`, in lexicographic
+    /// order of their paths, e.g. to resume a listing where a previous one stopped.
+    ///
+    /// The default implementation lists and sorts all objects by `path` prefix. Backends whose
+    /// listings can start at an offset override it.
+    async fn list_objs_after<'a>(
+        &'a self,
+        path: &'a str,
+        start_after: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        let mut objs: Vec<ObjectMeta> = self.list_objs(path).await?.try_collect().await?;
+        objs.retain(|obj| obj.path.as_str() > start_after);
+        objs.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Box::pin(futures::stream::iter(objs.into_iter().map(Ok))))
+    }
+
     /// Create new object with `obj_bytes` as content.
     ///
     /// Implementation note:
@@ -552,6 +847,18 @@ pub trait StorageBackend: Send + Sync + Debug {
     /// [StorageError::AlreadyExists] error.
     async fn rename_obj_noreplace(&self, src: &str, dst: &str) -> Result<(), StorageError>;
 
+    /// Returns whether several writers can commit to a table of this backend concurrently,
+    /// i.e. whether [StorageBackend::rename_obj_noreplace] never replaces an existing object.
+    fn is_safe_for_concurrent_writes(&self) -> bool {
+        true
+    }
+
+    /// Returns the statistics of the [rate_limit::RateLimitStorageBackend] limiting the operations
+    /// of this backend, if any.
+    fn rate_limit_stats(&self) -> Option<std::sync::Arc<rate_limit::RateLimitStats>> {
+        None
+    }
+
     /// Deletes object by `path`.
     async fn delete_obj(&self, path: &str) -> Result<(), StorageError>;
 
@@ -572,33 +879,120 @@ pub trait StorageBackend: Send + Sync + Debug {
 pub fn get_backend_for_uri(uri: &str) -> Result<Box<dyn StorageBackend>, StorageError> {
     match parse_uri(uri)? {
         Uri::LocalPath(root) => Ok(Box::new(file::FileStorageBackend::new(root))),
+        Uri::Memory(_) => Ok(Box::new(memory::MemoryStorageBackend::new())),
         #[cfg(any(feature = "s3", feature = "s3-rustls"))]
         Uri::S3Object(_) => Ok(Box::new(s3::S3StorageBackend::new()?)),
         #[cfg(feature = "azure")]
         Uri::AdlsGen2Object(obj) => Ok(Box::new(azure::AdlsGen2Backend::new(obj.file_system)?)),
         #[cfg(feature = "gcs")]
         Uri::GCSObject(_) => Ok(Box::new(gcs::GCSStorageBackend::new()?)),
+        #[cfg(feature = "webhdfs")]
+        Uri::WebHdfsObject(_) => Ok(Box::new(webhdfs::WebHdfsBackend::new()?)),
+        #[cfg(feature = "http")]
+        Uri::HttpObject(_) => Ok(Box::new(http::HttpStorageBackend::new()?)),
     }
 }
 
 /// Returns a StorageBackend appropriate for the protocol and configured with the given options
 /// Options must be passed as a hashmap. Hashmap keys correspond to env variables that are used if options are not set.
 ///
-/// Currently, S3 is the only backend that accepts options.
+/// Currently, the local file system is the only backend that accepts no options.
 /// Options may be passed in the HashMap or set as environment variables.
 ///
 /// [S3StorageOptions] describes the available options for the S3 backend.
+/// [azure::azure_storage_options] describes the available options for the Azure backend.
+/// [gcs::gcs_storage_options] describes the available options for the GCS backend.
+/// [webhdfs::webhdfs_options] describes the available options for the WebHDFS backend.
+/// [http::http_options] describes the available options for the read-only HTTP backend.
+/// [client::client_options] describes the connection options of the GCS, WebHDFS and HTTP
+/// backends.
 /// [s3::dynamodb_lock::DynamoDbLockClient] describes additional options for the atomic rename client.
+///
+/// Operations of all backends failing with transient errors or exceeding a timeout are retried
+/// as described by [retry::retry_options], and can be rate limited as described by
+/// [rate_limit::rate_limit_options].
+///
+/// Option keys not recognized by the backend are logged, or rejected if the
+/// [options::UNKNOWN_OPTIONS] option is `error`. See [options::validate_options].
 pub fn get_backend_for_uri_with_options(
     uri: &str,
-    // NOTE: prefixing options with "_" to avoid deny warnings error since usage is conditional on s3 and the only usage is with s3 so far
-    _options: std::collections::HashMap<String, String>,
+    options: std::collections::HashMap<String, String>,
 ) -> Result<Box<dyn StorageBackend>, StorageError> {
-    match parse_uri(uri)? {
+    options::validate_options(&parse_uri(uri)?, &options)?;
+    let retry_config = retry::RetryConfig::from_map(&options);
+    let rate_limit_config = rate_limit::RateLimitConfig::from_map(&options);
+    let backend: Box<dyn StorageBackend> = match parse_uri(uri)? {
         #[cfg(any(feature = "s3", feature = "s3-rustls"))]
-        Uri::S3Object(_) => Ok(Box::new(s3::S3StorageBackend::new_from_options(
-            S3StorageOptions::from_map(_options),
-        )?)),
-        _ => get_backend_for_uri(uri),
+        Uri::S3Object(_) => Box::new(s3::S3StorageBackend::new_from_options(
+            S3StorageOptions::from_map(options),
+        )?),
+        #[cfg(feature = "gcs")]
+        Uri::GCSObject(_) => Box::new(gcs::GCSStorageBackend::new_from_options(&options)?),
+        #[cfg(feature = "webhdfs")]
+        Uri::WebHdfsObject(_) => Box::new(webhdfs::WebHdfsBackend::new_from_options(&options)?),
+        #[cfg(feature = "http")]
+        Uri::HttpObject(_) => Box::new(http::HttpStorageBackend::new_from_options(&options)?),
+        #[cfg(feature = "azure")]
+        Uri::AdlsGen2Object(obj) => Box::new(azure::AdlsGen2Backend::new_from_options(
+            obj.file_system,
+            &options,
+        )?),
+        _ => get_backend_for_uri(uri)?,
+    };
+    let backend = Box::new(retry::RetryStorageBackend::new(backend, retry_config));
+    Ok(if rate_limit_config.is_enabled() {
+        Box::new(rate_limit::RateLimitStorageBackend::new(
+            backend,
+            rate_limit_config,
+        ))
+    } else {
+        backend
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_object_store_uri() {
+        for table_uri in [
+            "s3://bucket/table",
+            "s3://bucket/table/",
+            "s3://bucket/table//",
+            "S3://Bucket/table",
+        ] {
+            assert_eq!(normalize_table_uri(table_uri).unwrap(), "s3://bucket/table");
+        }
+        assert_eq!(normalize_table_uri("gs://bucket/").unwrap(), "gs://bucket");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn normalize_local_uri() {
+        let cwd = std::env::current_dir().unwrap();
+        let expected = cwd.join("tests/data/simple_table");
+        let expected = expected.to_str().unwrap();
+        let absolute = format!("{}/", expected);
+        let file_uri = format!("file://{}", expected);
+        for table_uri in [
+            "tests/data/simple_table",
+            "./tests/data/simple_table",
+            "./tests/data/simple_table/",
+            "tests/data/../data/./simple_table",
+            absolute.as_str(),
+            file_uri.as_str(),
+        ] {
+            assert_eq!(normalize_table_uri(table_uri).unwrap(), expected);
+        }
+        assert_eq!(normalize_table_uri("/").unwrap(), "/");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn normalize_windows_drive_uri() {
+        for table_uri in ["C:\\foo\\bar", "C:\\foo\\bar\\", "file:///C:/foo/bar"] {
+            assert_eq!(normalize_table_uri(table_uri).unwrap(), "C:\\foo\\bar");
+        }
     }
 }
@@ -0,0 +1,310 @@
+//! Validation of the storage options passed to [super::get_backend_for_uri_with_options].
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use log::warn;
+
+use super::{rate_limit::rate_limit_options, retry::retry_options, StorageError, Uri};
+
+/// Storage option key selecting how unknown option keys are handled: `ignore`, `warn` (the
+/// default) or `error`.
+pub const UNKNOWN_OPTIONS: &str = "unknown_options";
+
+/// How [validate_options] handles option keys that no backend recognizes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnknownOptionsPolicy {
+    /// Unknown keys are silently ignored.
+    Ignore,
+    /// Unknown keys are logged as warnings.
+    Warn,
+    /// Unknown keys fail with [StorageError::InvalidOptions].
+    Error,
+}
+
+impl Default for UnknownOptionsPolicy {
+    fn default() -> Self {
+        UnknownOptionsPolicy::Warn
+    }
+}
+
+impl std::str::FromStr for UnknownOptionsPolicy {
+    type Err = StorageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ignore" => Ok(UnknownOptionsPolicy::Ignore),
+            "warn" => Ok(UnknownOptionsPolicy::Warn),
+            "error" => Ok(UnknownOptionsPolicy::Error),
+            _ => Err(StorageError::InvalidOptions(format!(
+                "{} must be one of ignore, warn or error, found {:?}",
+                UNKNOWN_OPTIONS, s
+            ))),
+        }
+    }
+}
+
+/// Options accepted by every backend.
+const COMMON_OPTIONS: &[&str] = &[
+    UNKNOWN_OPTIONS,
+    retry_options::MAX_RETRIES,
+    retry_options::RETRY_TIMEOUT,
+    retry_options::TIMEOUT,
+    rate_limit_options::MAX_REQUESTS_PER_SECOND,
+    rate_limit_options::MAX_CONCURRENT_REQUESTS,
+];
+
+/// Returns the option keys the backend of `uri` recognizes in addition to the common ones.
+fn backend_options(uri: &Uri) -> Vec<&'static str> {
+    match uri {
+        #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+        Uri::S3Object(_) => [
+            super::s3::s3_storage_options::S3_OPTS,
+            super::s3::dynamodb_lock::dynamo_lock_options::DYNAMO_LOCK_OPTS,
+        ]
+        .concat(),
+        #[cfg(feature = "azure")]
+        Uri::AdlsGen2Object(_) => super::azure::azure_storage_options::AZURE_OPTS.to_vec(),
+        #[cfg(feature = "gcs")]
+        Uri::GCSObject(_) => [
+            super::gcs::gcs_storage_options::GCS_OPTS,
+            super::client::client_options::CLIENT_OPTS,
+        ]
+        .concat(),
+        #[cfg(feature = "webhdfs")]
+        Uri::WebHdfsObject(_) => [
+            super::webhdfs::webhdfs_options::WEBHDFS_OPTS,
+            super::client::client_options::CLIENT_OPTS,
+        ]
+        .concat(),
+        #[cfg(feature = "http")]
+        Uri::HttpObject(_) => [
+            super::http::http_options::HTTP_OPTS,
+            super::client::client_options::CLIENT_OPTS,
+        ]
+        .concat(),
+        Uri::LocalPath(_) | Uri::Memory(_) => vec![],
+    }
+}
+
+/// Checks the keys of `options` against the options recognized by the backend of `uri`.
+///
+/// Unknown keys are handled according to the [UNKNOWN_OPTIONS] option, with a suggestion if they
+/// are close to a known key. Error messages only contain option keys, never their values, as
+/// these may be secrets.
+pub fn validate_options(uri: &Uri, options: &HashMap<String, String>) -> Result<(), StorageError> {
+    let policy = match options.get(UNKNOWN_OPTIONS) {
+        Some(policy) => policy.parse()?,
+        None => UnknownOptionsPolicy::default(),
+    };
+    if policy == UnknownOptionsPolicy::Ignore {
+        return Ok(());
+    }
+
+    let mut known = COMMON_OPTIONS.to_vec();
+    known.extend(backend_options(uri));
+    // the S3 backend matches option keys case insensitively
+    #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+    let ignore_case = matches!(uri, Uri::S3Object(_));
+    #[cfg(not(any(feature = "s3", feature = "s3-rustls")))]
+    let ignore_case = false;
+
+    let mut unknown: Vec<String> = options
+        .keys()
+        .filter(|key| {
+            !known.iter().any(|known| {
+                *known == key.as_str() || ignore_case && known.eq_ignore_ascii_case(key)
+            })
+        })
+        .map(|key| match suggest(key, &known) {
+            Some(suggestion) => format!("{} (did you mean {}?)", key, suggestion),
+            None => key.clone(),
+        })
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    unknown.sort();
+
+    let message = format!(
+        "unknown storage options for {}: {}",
+        backend_name(uri),
+        unknown.join(", ")
+    );
+    match policy {
+        UnknownOptionsPolicy::Error => Err(StorageError::InvalidOptions(message)),
+        _ => {
+            warn!("Ignoring {}", message);
+            Ok(())
+        }
+    }
+}
+
+fn backend_name(uri: &Uri) -> &'static str {
+    match uri {
+        Uri::LocalPath(_) => "the local file system",
+        Uri::Memory(_) => "in-memory stores",
+        #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+        Uri::S3Object(_) => "S3",
+        #[cfg(feature = "azure")]
+        Uri::AdlsGen2Object(_) => "Azure",
+        #[cfg(feature = "gcs")]
+        Uri::GCSObject(_) => "GCS",
+        #[cfg(feature = "webhdfs")]
+        Uri::WebHdfsObject(_) => "WebHDFS",
+        #[cfg(feature = "http")]
+        Uri::HttpObject(_) => "HTTP",
+    }
+}
+
+/// Parses the option `key` with `parse`. Malformed values are logged and ignored.
+pub(crate) fn parse_option<T>(
+    options: &HashMap<String, String>,
+    key: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    let value = options.get(key)?;
+    let parsed = parse(value);
+    if parsed.is_none() {
+        warn!("Ignoring invalid storage option {}: {}", key, value);
+    }
+    parsed
+}
+
+/// Parses a duration like `30s`, `500ms`, `2m` or `1h`. Numbers without a unit are seconds.
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let unit_start = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or_else(|| value.len());
+    let (number, unit) = value.split_at(unit_start);
+    let number: f64 = number.parse().ok()?;
+    let seconds = match unit.trim() {
+        "" | "s" | "sec" | "secs" | "seconds" => number,
+        "ms" => number / 1000.0,
+        "m" | "min" | "mins" | "minutes" => number * 60.0,
+        "h" | "hour" | "hours" => number * 3600.0,
+        _ => return None,
+    };
+    Some(Duration::from_secs_f64(seconds))
+}
+
+/// Returns the known key closest to `key`, if it is close enough to be a misspelling.
+fn suggest<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    let key = key.to_lowercase();
+    known
+        .iter()
+        .map(|candidate| (edit_distance(&key, &candidate.to_lowercase()), *candidate))
+        .filter(|(distance, _)| *distance <= 3.max(key.len() / 5))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_uri;
+    use super::*;
+
+    fn options(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("max_retries", "max_retries"), 0);
+        assert_eq!(edit_distance("max_retires", "max_retries"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1.5 m"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("soon"), None);
+        assert_eq!(parse_duration("5 fortnights"), None);
+    }
+
+    #[test]
+    fn local_options() {
+        let uri = parse_uri("./tests/data/simple_table").unwrap();
+        assert!(validate_options(&uri, &options(&[("max_retries", "3")])).is_ok());
+
+        // unknown keys only warn by default
+        assert!(validate_options(&uri, &options(&[("max_retires", "3")])).is_ok());
+
+        let result = validate_options(
+            &uri,
+            &options(&[("max_retires", "secret"), (UNKNOWN_OPTIONS, "error")]),
+        );
+        match result {
+            Err(StorageError::InvalidOptions(message)) => {
+                assert!(message.contains("max_retires (did you mean max_retries?)"));
+                assert!(!message.contains("secret"));
+            }
+            other => panic!("expected invalid options, got {:?}", other),
+        }
+
+        let ignored = options(&[("anything", "1"), (UNKNOWN_OPTIONS, "ignore")]);
+        assert!(validate_options(&uri, &ignored).is_ok());
+        assert!(validate_options(&uri, &options(&[(UNKNOWN_OPTIONS, "panic")])).is_err());
+    }
+
+    #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+    #[test]
+    fn s3_options() {
+        let uri = parse_uri("s3://bucket/table").unwrap();
+        let valid = options(&[
+            ("AWS_REGION", "us-east-1"),
+            ("aws_endpoint_url", "http://localhost:9000"),
+            ("DYNAMO_LOCK_TABLE_NAME", "locks"),
+            (UNKNOWN_OPTIONS, "error"),
+        ]);
+        assert!(validate_options(&uri, &valid).is_ok());
+
+        let misspelled = options(&[("AWS_REGOIN", "us-east-1"), (UNKNOWN_OPTIONS, "error")]);
+        match validate_options(&uri, &misspelled) {
+            Err(StorageError::InvalidOptions(message)) => {
+                assert!(message.contains("AWS_REGOIN (did you mean AWS_REGION?)"))
+            }
+            other => panic!("expected invalid options, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "gcs")]
+    #[test]
+    fn gcs_options() {
+        let uri = parse_uri("gs://bucket/table").unwrap();
+        let result = validate_options(
+            &uri,
+            &options(&[("AWS_REGION", "us-east-1"), (UNKNOWN_OPTIONS, "error")]),
+        );
+        match result {
+            Err(StorageError::InvalidOptions(message)) => {
+                assert_eq!(message, "unknown storage options for GCS: AWS_REGION")
+            }
+            other => panic!("expected invalid options, got {:?}", other),
+        }
+    }
+}
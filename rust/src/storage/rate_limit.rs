@@ -0,0 +1,391 @@
+//! Storage backend decorator limiting the rate and concurrency of operations, e.g. to stay below
+//! the per-prefix request limits of S3 when scanning large tables.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::Stream;
+use tokio::sync::Semaphore;
+
+use super::options::parse_option;
+use super::{ObjectMeta, StorageBackend, StorageError};
+
+/// Storage option keys to use when creating a [RateLimitConfig].
+pub mod rate_limit_options {
+    /// The maximum number of operations started per second, e.g. `100`. Short bursts of up to
+    /// one second worth of operations are allowed.
+    pub const MAX_REQUESTS_PER_SECOND: &str = "max_requests_per_second";
+    /// The maximum number of operations in flight.
+    pub const MAX_CONCURRENT_REQUESTS: &str = "max_concurrent_requests";
+}
+
+/// Configures how [RateLimitStorageBackend] limits operations.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RateLimitConfig {
+    /// The maximum number of operations started per second. Unlimited if `None`.
+    pub max_requests_per_second: Option<f64>,
+    /// The maximum number of operations in flight. Unlimited if `None`.
+    pub max_concurrent_requests: Option<usize>,
+}
+
+impl RateLimitConfig {
+    /// Creates a rate limit config from the [rate_limit_options] keys of the storage options.
+    /// Missing, malformed or non-positive options leave the operations unlimited.
+    pub fn from_map(options: &HashMap<String, String>) -> Self {
+        use rate_limit_options::*;
+        Self {
+            max_requests_per_second: parse_option(options, MAX_REQUESTS_PER_SECOND, |value| {
+                value.parse().ok().filter(|rate: &f64| *rate > 0.0)
+            }),
+            max_concurrent_requests: parse_option(options, MAX_CONCURRENT_REQUESTS, |value| {
+                value.parse().ok().filter(|max: &usize| *max > 0)
+            }),
+        }
+    }
+
+    /// Returns whether any limit is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.max_requests_per_second.is_some() || self.max_concurrent_requests.is_some()
+    }
+}
+
+/// Statistics of a [RateLimitStorageBackend], shared with its readers.
+#[derive(Debug, Default)]
+pub struct RateLimitStats {
+    throttled: AtomicU64,
+    wait_nanos: AtomicU64,
+    in_flight: AtomicU64,
+}
+
+impl RateLimitStats {
+    /// Number of operations that had to wait since creation or the last reset.
+    pub fn throttled(&self) -> u64 {
+        self.throttled.load(Ordering::Relaxed)
+    }
+
+    /// Total time operations waited since creation or the last reset.
+    pub fn wait_time(&self) -> Duration {
+        Duration::from_nanos(self.wait_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Number of operations currently in flight.
+    pub fn in_flight(&self) -> u64 {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Sets the number of throttled operations and the wait time to zero.
+    pub fn reset(&self) {
+        self.throttled.store(0, Ordering::Relaxed);
+        self.wait_nanos.store(0, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    /// The available tokens and when they were last refilled.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(rate: f64) -> Self {
+        let capacity = rate.max(1.0);
+        Self {
+            rate,
+            capacity,
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Takes a token, waiting until one is available. Returns whether it had to wait.
+    async fn acquire(&self) -> bool {
+        let mut waited = false;
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let (tokens, refilled) = *state;
+                let now = Instant::now();
+                let tokens = (tokens + now.duration_since(refilled).as_secs_f64() * self.rate)
+                    .min(self.capacity);
+                if tokens >= 1.0 {
+                    *state = (tokens - 1.0, now);
+                    return waited;
+                }
+                *state = (tokens, now);
+                Duration::from_secs_f64((1.0 - tokens) / self.rate)
+            };
+            waited = true;
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Wraps a storage backend and limits the rate and concurrency of its operations as configured
+/// by [RateLimitConfig]. Operations exceeding the limits wait instead of failing.
+///
+/// Each operation counts once, including its retries when wrapping a
+/// [super::retry::RetryStorageBackend], and holds its concurrency permit until it completes.
+/// Listings are limited when they start; fetching further pages of the stream is not.
+#[derive(Debug)]
+pub struct RateLimitStorageBackend {
+    inner: Box<dyn StorageBackend>,
+    bucket: Option<TokenBucket>,
+    semaphore: Option<Semaphore>,
+    stats: Arc<RateLimitStats>,
+}
+
+impl RateLimitStorageBackend {
+    /// Creates a backend limiting the operations of `inner` as configured by `config`.
+    pub fn new(inner: Box<dyn StorageBackend>, config: RateLimitConfig) -> Self {
+        Self {
+            inner,
+            bucket: config.max_requests_per_second.map(TokenBucket::new),
+            semaphore: config.max_concurrent_requests.map(Semaphore::new),
+            stats: Arc::new(RateLimitStats::default()),
+        }
+    }
+
+    /// Returns the statistics, which stay readable after the backend is moved into a table.
+    pub fn stats(&self) -> Arc<RateLimitStats> {
+        self.stats.clone()
+    }
+
+    async fn limit<T>(&self, operation: impl Future<Output = T>) -> T {
+        let start = Instant::now();
+        let mut waited = false;
+        let _permit = match &self.semaphore {
+            Some(semaphore) => Some(match semaphore.try_acquire() {
+                Ok(permit) => permit,
+                Err(_) => {
+                    waited = true;
+                    // the semaphore is never closed
+                    semaphore.acquire().await.unwrap()
+                }
+            }),
+            None => None,
+        };
+        if let Some(bucket) = &self.bucket {
+            waited |= bucket.acquire().await;
+        }
+        if waited {
+            self.stats.throttled.fetch_add(1, Ordering::Relaxed);
+            self.stats
+                .wait_nanos
+                .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        }
+
+        self.stats.in_flight.fetch_add(1, Ordering::Relaxed);
+        let result = operation.await;
+        self.stats.in_flight.fetch_sub(1, Ordering::Relaxed);
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for RateLimitStorageBackend {
+    fn join_path(&self, path: &str, path_to_join: &str) -> String {
+        self.inner.join_path(path, path_to_join)
+    }
+
+    fn join_paths(&self, paths: &[&str]) -> String {
+        self.inner.join_paths(paths)
+    }
+
+    fn trim_path(&self, path: &str) -> String {
+        self.inner.trim_path(path)
+    }
+
+    fn is_safe_for_concurrent_writes(&self) -> bool {
+        self.inner.is_safe_for_concurrent_writes()
+    }
+
+    fn rate_limit_stats(&self) -> Option<Arc<RateLimitStats>> {
+        Some(self.stats())
+    }
+
+    async fn head_obj(&self, path: &str) -> Result<ObjectMeta, StorageError> {
+        self.limit(self.inner.head_obj(path)).await
+    }
+
+    async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        self.limit(self.inner.get_obj(path)).await
+    }
+
+    async fn get_range(&self, path: &str, range: Range<usize>) -> Result<Vec<u8>, StorageError> {
+        self.limit(self.inner.get_range(path, range)).await
+    }
+
+    async fn list_objs<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        self.limit(self.inner.list_objs(path)).await
+    }
+
+    async fn list_objs_after<'a>(
+        &'a self,
+        path: &'a str,
+        start_after: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        self.limit(self.inner.list_objs_after(path, start_after))
+            .await
+    }
+
+    async fn put_obj(&self, path: &str, obj_bytes: &[u8]) -> Result<(), StorageError> {
+        self.limit(self.inner.put_obj(path, obj_bytes)).await
+    }
+
+    async fn rename_obj_noreplace(&self, src: &str, dst: &str) -> Result<(), StorageError> {
+        self.limit(self.inner.rename_obj_noreplace(src, dst)).await
+    }
+
+    async fn delete_obj(&self, path: &str) -> Result<(), StorageError> {
+        self.limit(self.inner.delete_obj(path)).await
+    }
+
+    async fn delete_objs(&self, paths: &[String]) -> Result<(), StorageError> {
+        self.limit(self.inner.delete_objs(paths)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Records the maximum number of concurrent GET requests, each taking 10ms.
+    #[derive(Debug, Default)]
+    struct CountingBackend {
+        in_flight: AtomicUsize,
+        max_in_flight: Arc<AtomicUsize>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl StorageBackend for CountingBackend {
+        async fn head_obj(&self, _path: &str) -> Result<ObjectMeta, StorageError> {
+            unimplemented!()
+        }
+
+        async fn get_obj(&self, _path: &str) -> Result<Vec<u8>, StorageError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(b"data".to_vec())
+        }
+
+        async fn list_objs<'a>(
+            &'a self,
+            _path: &'a str,
+        ) -> Result<
+            Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+            StorageError,
+        > {
+            unimplemented!()
+        }
+
+        async fn put_obj(&self, _path: &str, _obj_bytes: &[u8]) -> Result<(), StorageError> {
+            unimplemented!()
+        }
+
+        async fn rename_obj_noreplace(&self, _src: &str, _dst: &str) -> Result<(), StorageError> {
+            unimplemented!()
+        }
+
+        async fn delete_obj(&self, _path: &str) -> Result<(), StorageError> {
+            unimplemented!()
+        }
+    }
+
+    /// Returns a limited backend, its maximum number of concurrent requests and its calls.
+    fn counting_backend(
+        config: RateLimitConfig,
+    ) -> (RateLimitStorageBackend, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let counting = CountingBackend::default();
+        let max_in_flight = counting.max_in_flight.clone();
+        let calls = counting.calls.clone();
+        (
+            RateLimitStorageBackend::new(Box::new(counting), config),
+            max_in_flight,
+            calls,
+        )
+    }
+
+    async fn get_concurrently(backend: &RateLimitStorageBackend, requests: usize) {
+        futures::stream::iter((0..requests).map(|i| backend.get_obj(&format!("part-{}", i))))
+            .buffer_unordered(requests)
+            .for_each(|result| async move {
+                assert_eq!(result.unwrap(), b"data");
+            })
+            .await;
+    }
+
+    #[test]
+    fn config_from_map() {
+        let options = [
+            (rate_limit_options::MAX_REQUESTS_PER_SECOND, "12.5"),
+            (rate_limit_options::MAX_CONCURRENT_REQUESTS, "0"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let config = RateLimitConfig::from_map(&options);
+        assert_eq!(
+            config,
+            RateLimitConfig {
+                max_requests_per_second: Some(12.5),
+                max_concurrent_requests: None,
+            }
+        );
+        assert!(config.is_enabled());
+        assert!(!RateLimitConfig::from_map(&HashMap::new()).is_enabled());
+    }
+
+    #[tokio::test]
+    async fn limits_concurrency() {
+        let (backend, max_in_flight, calls) = counting_backend(RateLimitConfig {
+            max_concurrent_requests: Some(8),
+            ..Default::default()
+        });
+        get_concurrently(&backend, 100).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 100);
+        assert!(max_in_flight.load(Ordering::SeqCst) <= 8);
+        let stats = backend.rate_limit_stats().unwrap();
+        assert!(stats.throttled() >= 92);
+        assert!(stats.wait_time() > Duration::ZERO);
+        assert_eq!(stats.in_flight(), 0);
+        stats.reset();
+        assert_eq!(stats.throttled(), 0);
+    }
+
+    #[tokio::test]
+    async fn limits_rate() {
+        let (backend, _, calls) = counting_backend(RateLimitConfig {
+            max_requests_per_second: Some(20.0),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        // a burst of 20, then 10 more at 20 per second
+        get_concurrently(&backend, 30).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 30);
+        assert!(start.elapsed() >= Duration::from_millis(450));
+        assert_eq!(backend.stats().throttled(), 10);
+    }
+}
@@ -0,0 +1,451 @@
+//! Storage backend decorator retrying operations that failed with transient errors.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::Stream;
+use log::warn;
+
+use super::options::{parse_duration, parse_option};
+use super::rate_limit::RateLimitStats;
+use super::{ObjectMeta, StorageBackend, StorageError};
+
+/// Storage option keys to use when creating a [RetryConfig].
+pub mod retry_options {
+    /// The maximum number of retries of an operation.
+    pub const MAX_RETRIES: &str = "max_retries";
+    /// The time after the first attempt of an operation after which it is no longer retried,
+    /// e.g. `3m`. Numbers without a unit are seconds.
+    pub const RETRY_TIMEOUT: &str = "retry_timeout";
+    /// The maximum duration of a single attempt of an operation, e.g. `30s`. Attempts taking
+    /// longer fail with a timeout, which is retried.
+    pub const TIMEOUT: &str = "timeout";
+}
+
+/// Configures how [RetryStorageBackend] retries operations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryConfig {
+    /// The maximum number of retries of an operation. 0 disables retries.
+    pub max_retries: usize,
+    /// The backoff before the first retry. It doubles with every retry.
+    pub initial_backoff: Duration,
+    /// The maximum backoff between two attempts.
+    pub max_backoff: Duration,
+    /// No retry is started once this time has passed since the first attempt.
+    pub retry_timeout: Duration,
+    /// Attempts taking longer than this fail with [std::io::ErrorKind::TimedOut]. No timeout
+    /// if `None`.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(15),
+            retry_timeout: Duration::from_secs(180),
+            timeout: None,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Creates a retry config from the [retry_options] keys of the storage options. Missing or
+    /// malformed options keep their default.
+    pub fn from_map(options: &HashMap<String, String>) -> Self {
+        let mut config = Self::default();
+        if let Some(max_retries) = parse_option(options, retry_options::MAX_RETRIES, |value| {
+            value.parse().ok()
+        }) {
+            config.max_retries = max_retries;
+        }
+        if let Some(retry_timeout) =
+            parse_option(options, retry_options::RETRY_TIMEOUT, parse_duration)
+        {
+            config.retry_timeout = retry_timeout;
+        }
+        config.timeout = parse_option(options, retry_options::TIMEOUT, parse_duration);
+        config
+    }
+}
+
+/// Returns whether an operation failing with `err` may succeed when it is retried, e.g. after a
+/// connection reset or a 503 response. Missing objects, conflicts and permission errors are never
+/// retried, except for requests rejected because their temporary credentials expired, which the
+/// refreshing credential providers renew before the retry. Reads failing checksum verification
+/// are retried to fetch the object again.
+pub fn is_transient(err: &StorageError) -> bool {
+    match err {
+        StorageError::Io { source } => matches!(
+            source.kind(),
+            std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::Interrupted
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::BrokenPipe
+                | std::io::ErrorKind::UnexpectedEof
+        ),
+        StorageError::ChecksumMismatch { .. } => true,
+        #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+        StorageError::S3Get { source } => is_transient_rusoto(source),
+        #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+        StorageError::S3Head { source } => is_transient_rusoto(source),
+        #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+        StorageError::S3List { source } => is_transient_rusoto(source),
+        #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+        StorageError::S3Put { source } => is_transient_rusoto(source),
+        #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+        StorageError::S3Delete { source } => is_transient_rusoto(source),
+        #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+        StorageError::S3BatchDelete { source } => is_transient_rusoto(source),
+        _ => false,
+    }
+}
+
+#[cfg(any(feature = "s3", feature = "s3-rustls"))]
+fn is_transient_rusoto<E>(err: &rusoto_core::RusotoError<E>) -> bool {
+    match err {
+        rusoto_core::RusotoError::HttpDispatch(_) => true,
+        rusoto_core::RusotoError::Unknown(response) => {
+            response.status.is_server_error()
+                || response.status == 429
+                || String::from_utf8_lossy(&response.body).contains("<Code>ExpiredToken</Code>")
+        }
+        _ => false,
+    }
+}
+
+/// Wraps a storage backend and retries its operations with exponential backoff when they fail
+/// with [transient](is_transient) errors.
+///
+/// Renames are not retried: a rename that succeeded but whose response was lost would fail its
+/// retry with [StorageError::AlreadyExists], which commits treat as a conflict. They still fail
+/// once they exceed the configured timeout.
+#[derive(Debug)]
+pub struct RetryStorageBackend {
+    inner: Box<dyn StorageBackend>,
+    config: RetryConfig,
+}
+
+impl RetryStorageBackend {
+    /// Creates a backend retrying the operations of `inner` as configured by `config`.
+    pub fn new(inner: Box<dyn StorageBackend>, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// Runs a single attempt of an operation, failing it if it exceeds the configured timeout.
+    async fn attempt<T>(
+        &self,
+        operation: &str,
+        path: &str,
+        attempt: impl Future<Output = Result<T, StorageError>>,
+    ) -> Result<T, StorageError> {
+        match self.config.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, attempt)
+                .await
+                .unwrap_or_else(|_| {
+                    Err(StorageError::Io {
+                        source: std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            format!("{} of {} timed out after {:?}", operation, path, timeout),
+                        ),
+                    })
+                }),
+            None => attempt.await,
+        }
+    }
+
+    async fn retry<T, F, Fut>(&self, operation: &str, path: &str, f: F) -> Result<T, StorageError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, StorageError>>,
+    {
+        let start = Instant::now();
+        let mut backoff = self.config.initial_backoff;
+        let mut retries = 0;
+        loop {
+            match self.attempt(operation, path, f()).await {
+                Err(err)
+                    if retries < self.config.max_retries
+                        && is_transient(&err)
+                        && start.elapsed() + backoff < self.config.retry_timeout =>
+                {
+                    warn!(
+                        "{} of {} failed with {}, retrying in {:?}",
+                        operation, path, err, backoff
+                    );
+                }
+                result => return result,
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.config.max_backoff);
+            retries += 1;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for RetryStorageBackend {
+    fn join_path(&self, path: &str, path_to_join: &str) -> String {
+        self.inner.join_path(path, path_to_join)
+    }
+
+    fn join_paths(&self, paths: &[&str]) -> String {
+        self.inner.join_paths(paths)
+    }
+
+    fn trim_path(&self, path: &str) -> String {
+        self.inner.trim_path(path)
+    }
+
+    fn is_safe_for_concurrent_writes(&self) -> bool {
+        self.inner.is_safe_for_concurrent_writes()
+    }
+
+    fn rate_limit_stats(&self) -> Option<Arc<RateLimitStats>> {
+        self.inner.rate_limit_stats()
+    }
+
+    async fn head_obj(&self, path: &str) -> Result<ObjectMeta, StorageError> {
+        self.retry("HEAD", path, || self.inner.head_obj(path)).await
+    }
+
+    async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        self.retry("GET", path, || self.inner.get_obj(path)).await
+    }
+
+    async fn get_range(&self, path: &str, range: Range<usize>) -> Result<Vec<u8>, StorageError> {
+        self.retry("GET", path, || self.inner.get_range(path, range.clone()))
+            .await
+    }
+
+    async fn list_objs<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        // only starting the listing is retried, as retrying a partially consumed stream would
+        // repeat its objects
+        self.retry("LIST", path, || self.inner.list_objs(path))
+            .await
+    }
+
+    async fn list_objs_after<'a>(
+        &'a self,
+        path: &'a str,
+        start_after: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        self.retry("LIST", path, || {
+            self.inner.list_objs_after(path, start_after)
+        })
+        .await
+    }
+
+    async fn put_obj(&self, path: &str, obj_bytes: &[u8]) -> Result<(), StorageError> {
+        self.retry("PUT", path, || self.inner.put_obj(path, obj_bytes))
+            .await
+    }
+
+    async fn rename_obj_noreplace(&self, src: &str, dst: &str) -> Result<(), StorageError> {
+        self.attempt("RENAME", src, self.inner.rename_obj_noreplace(src, dst))
+            .await
+    }
+
+    async fn delete_obj(&self, path: &str) -> Result<(), StorageError> {
+        self.retry("DELETE", path, || self.inner.delete_obj(path))
+            .await
+    }
+
+    async fn delete_objs(&self, paths: &[String]) -> Result<(), StorageError> {
+        let description = format!("{} objects", paths.len());
+        self.retry("DELETE", &description, || self.inner.delete_objs(paths))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Fails the first `failures` GET requests with `kind`, or never completes them if `kind`
+    /// is `None`.
+    #[derive(Debug)]
+    struct FlakyBackend {
+        failures: usize,
+        kind: Option<std::io::ErrorKind>,
+        attempts: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl StorageBackend for FlakyBackend {
+        async fn head_obj(&self, _path: &str) -> Result<ObjectMeta, StorageError> {
+            unimplemented!()
+        }
+
+        async fn get_obj(&self, _path: &str) -> Result<Vec<u8>, StorageError> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                match self.kind {
+                    Some(kind) => Err(std::io::Error::from(kind).into()),
+                    None => futures::future::pending().await,
+                }
+            } else {
+                Ok(b"data".to_vec())
+            }
+        }
+
+        async fn list_objs<'a>(
+            &'a self,
+            _path: &'a str,
+        ) -> Result<
+            Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+            StorageError,
+        > {
+            unimplemented!()
+        }
+
+        async fn put_obj(&self, _path: &str, _obj_bytes: &[u8]) -> Result<(), StorageError> {
+            unimplemented!()
+        }
+
+        async fn rename_obj_noreplace(&self, _src: &str, _dst: &str) -> Result<(), StorageError> {
+            unimplemented!()
+        }
+
+        async fn delete_obj(&self, _path: &str) -> Result<(), StorageError> {
+            unimplemented!()
+        }
+    }
+
+    /// Returns a retrying backend whose first `failures` GET requests fail with `kind`, and the
+    /// counter of its GET requests.
+    fn flaky_backend(
+        failures: usize,
+        kind: Option<std::io::ErrorKind>,
+        max_retries: usize,
+    ) -> (RetryStorageBackend, Arc<AtomicUsize>) {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let flaky = FlakyBackend {
+            failures,
+            kind,
+            attempts: attempts.clone(),
+        };
+        let config = RetryConfig {
+            max_retries,
+            initial_backoff: Duration::from_millis(1),
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        (RetryStorageBackend::new(Box::new(flaky), config), attempts)
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors() {
+        let (backend, attempts) = flaky_backend(2, Some(std::io::ErrorKind::ConnectionReset), 3);
+        assert_eq!(backend.get_obj("path").await.unwrap(), b"data");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (backend, attempts) = flaky_backend(5, Some(std::io::ErrorKind::TimedOut), 3);
+        assert!(matches!(
+            backend.get_obj("path").await,
+            Err(StorageError::Io { .. })
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn retries_hanging_attempts() {
+        let (backend, attempts) = flaky_backend(1, None, 3);
+        assert_eq!(backend.get_obj("path").await.unwrap(), b"data");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        let (backend, _) = flaky_backend(5, None, 0);
+        match backend.get_obj("path").await {
+            Err(StorageError::Io { source }) => {
+                assert_eq!(source.kind(), std::io::ErrorKind::TimedOut)
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_permanent_errors() {
+        let (backend, attempts) = flaky_backend(1, Some(std::io::ErrorKind::NotFound), 3);
+        assert!(matches!(
+            backend.get_obj("path").await,
+            Err(StorageError::NotFound)
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        let (backend, attempts) = flaky_backend(1, Some(std::io::ErrorKind::PermissionDenied), 3);
+        assert!(backend.get_obj("path").await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn retries_checksum_mismatches() {
+        assert!(is_transient(&StorageError::ChecksumMismatch {
+            path: "path".to_string(),
+            algorithm: "md5",
+            expected: "00".to_string(),
+            actual: "01".to_string(),
+        }));
+    }
+
+    #[cfg(any(feature = "s3", feature = "s3-rustls"))]
+    #[test]
+    fn retries_expired_credentials() {
+        let response = |status: u16, body: &'static str| {
+            rusoto_core::RusotoError::<rusoto_s3::GetObjectError>::Unknown(
+                rusoto_core::request::BufferedHttpResponse {
+                    status: std::convert::TryFrom::try_from(status).unwrap(),
+                    body: bytes::Bytes::from_static(body.as_bytes()),
+                    headers: Default::default(),
+                },
+            )
+        };
+        let expired = "<Error><Code>ExpiredToken</Code></Error>";
+        let denied = "<Error><Code>AccessDenied</Code></Error>";
+        assert!(is_transient(&StorageError::S3Get {
+            source: response(400, expired)
+        }));
+        assert!(!is_transient(&StorageError::S3Get {
+            source: response(403, denied)
+        }));
+        assert!(is_transient(&StorageError::S3Get {
+            source: response(503, "")
+        }));
+    }
+
+    #[test]
+    fn config_from_options() {
+        let options = [
+            (retry_options::MAX_RETRIES.to_string(), "2".to_string()),
+            (
+                retry_options::RETRY_TIMEOUT.to_string(),
+                "often".to_string(),
+            ),
+            (retry_options::TIMEOUT.to_string(), "30s".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let config = RetryConfig::from_map(&options);
+        assert_eq!(config.max_retries, 2);
+        assert_eq!(config.retry_timeout, RetryConfig::default().retry_timeout);
+        assert_eq!(config.timeout, Some(Duration::from_secs(30)));
+    }
+}
@@ -17,6 +17,7 @@ pub mod dynamo_lock_options {
     /// This should be the same for all writers writing against the same S3 table.
     pub const DYNAMO_LOCK_PARTITION_KEY_VALUE: &str = "DYNAMO_LOCK_PARTITION_KEY_VALUE";
     /// The DynamoDb table where locks are stored. Must be the same between clients that require the same lock.
+    /// Required by S3 storage backends using the dynamodb locking provider.
     pub const DYNAMO_LOCK_TABLE_NAME: &str = "DYNAMO_LOCK_TABLE_NAME";
     /// Name of the task that owns the DynamoDb lock. If not provided, defaults to a UUID that represents the process performing the write.
     pub const DYNAMO_LOCK_OWNER_NAME: &str = "DYNAMO_LOCK_OWNER_NAME";
@@ -30,6 +31,16 @@ pub mod dynamo_lock_options {
     /// If not provided, defaults to 1000 millis.
     pub const DYNAMO_LOCK_ADDITIONAL_TIME_TO_WAIT_MILLIS: &str =
         "DYNAMO_LOCK_ADDITIONAL_TIME_TO_WAIT_MILLIS";
+
+    /// The list of option keys owned by the DynamoDb lock module.
+    pub const DYNAMO_LOCK_OPTS: &[&str] = &[
+        DYNAMO_LOCK_PARTITION_KEY_VALUE,
+        DYNAMO_LOCK_TABLE_NAME,
+        DYNAMO_LOCK_OWNER_NAME,
+        DYNAMO_LOCK_LEASE_DURATION,
+        DYNAMO_LOCK_REFRESH_PERIOD_MILLIS,
+        DYNAMO_LOCK_ADDITIONAL_TIME_TO_WAIT_MILLIS,
+    ];
 }
 
 /// Configuration options for [`DynamoDbLockClient`].
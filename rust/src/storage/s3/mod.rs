@@ -2,6 +2,7 @@
 
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::ops::Range;
 use std::{fmt, pin::Pin};
 
 use chrono::{DateTime, FixedOffset, Utc};
@@ -18,9 +19,11 @@ use rusoto_sts::{StsAssumeRoleSessionCredentialsProvider, StsClient, WebIdentity
 use serde::{Deserialize, Serialize};
 use tokio::io::AsyncReadExt;
 
+use super::checksum::ChecksumAlgorithm;
 use super::{parse_uri, ObjectMeta, StorageBackend, StorageError};
 use rusoto_core::credential::{
     AwsCredentials, CredentialsError, DefaultCredentialsProvider, ProvideAwsCredentials,
+    StaticProvider,
 };
 use std::time::Duration;
 use uuid::Uuid;
@@ -30,14 +33,24 @@ pub mod dynamodb_lock;
 /// Storage option keys to use when creating [crate::storage::s3::S3StorageOptions].
 /// The same key should be used whether passing a key in the hashmap or setting it as an environment variable.
 /// Provided keys may include configuration for the S3 backend and also the optional DynamoDb lock used for atomic rename.
+/// Keys passed in the hashmap are case insensitive, e.g. `aws_endpoint_url` is the same as
+/// [AWS_ENDPOINT_URL].
 pub mod s3_storage_options {
-    /// Custom S3 endpoint.
+    /// Custom S3 endpoint, e.g. of MinIO or localstack. Must be an `http://` or `https://` URL,
+    /// or a host without scheme, which uses HTTPS.
     pub const AWS_ENDPOINT_URL: &str = "AWS_ENDPOINT_URL";
+    /// Accepted for compatibility with other clients. Plain HTTP endpoints are always allowed.
+    pub const AWS_ALLOW_HTTP: &str = "AWS_ALLOW_HTTP";
+    /// Requests always use path-style addressing (`<endpoint>/<bucket>/<key>`), as required by
+    /// MinIO and localstack. Setting this to `true` fails, as virtual hosted-style requests are
+    /// not supported.
+    pub const AWS_VIRTUAL_HOSTED_STYLE_REQUEST: &str = "AWS_VIRTUAL_HOSTED_STYLE_REQUEST";
     /// The AWS region.
     pub const AWS_REGION: &str = "AWS_REGION";
     /// Locking provider to use for safe atomic rename.
-    /// `dynamodb` is currently the only supported locking provider.
-    /// If not set, safe atomic rename is not available.
+    /// `dynamodb` is currently the only supported locking provider, and requires
+    /// `DYNAMO_LOCK_TABLE_NAME` to be set.
+    /// If not set or `none`, safe atomic rename is not available.
     pub const AWS_S3_LOCKING_PROVIDER: &str = "AWS_S3_LOCKING_PROVIDER";
     /// The role to assume for S3 writes.
     pub const AWS_S3_ASSUME_ROLE_ARN: &str = "AWS_S3_ASSUME_ROLE_ARN";
@@ -66,12 +79,40 @@ pub mod s3_storage_options {
     /// NOTE: web identity related options are set in the environment when creating an instance of [crate::storage::s3::S3StorageOptions].
     /// See also https://docs.rs/rusoto_sts/0.47.0/rusoto_sts/struct.WebIdentityProvider.html#method.from_k8s_env.
     pub const AWS_ROLE_SESSION_NAME: &str = "AWS_ROLE_SESSION_NAME";
+    /// The profile of the shared config and credentials files to load credentials from.
+    /// NOTE: the profile is set in the environment when creating an instance of [crate::storage::s3::S3StorageOptions],
+    /// as the default credentials provider only reads it from there.
+    pub const AWS_PROFILE: &str = "AWS_PROFILE";
+    /// Send unsigned requests without looking up credentials, e.g. to read public buckets.
+    /// Enabled by `true`.
+    pub const AWS_SKIP_SIGNATURE: &str = "AWS_SKIP_SIGNATURE";
+    /// Alias of [AWS_SKIP_SIGNATURE].
+    pub const ANONYMOUS: &str = "anonymous";
+    /// Set to `requester` to access requester-pays buckets, which bill the requests and data
+    /// transfer to the requester instead of the bucket owner.
+    pub const AWS_REQUEST_PAYER: &str = "AWS_REQUEST_PAYER";
+    /// Alias of [AWS_REQUEST_PAYER].
+    pub const REQUEST_PAYER: &str = "request_payer";
+    /// The server-side encryption of written objects: `aes256` for keys managed by S3, `kms`
+    /// for keys managed by KMS, or `c` for keys provided by the client. If not set, the default
+    /// encryption of the bucket applies.
+    pub const AWS_SSE_TYPE: &str = "AWS_SSE_TYPE";
+    /// The id or ARN of the KMS key used with `kms` encryption. The AWS managed key of S3 is
+    /// used if not set.
+    pub const AWS_SSE_KMS_KEY_ID: &str = "AWS_SSE_KMS_KEY_ID";
+    /// The base64 encoded 256-bit key used with `c` encryption. It is sent with every read and
+    /// write, as S3 does not store it.
+    pub const AWS_SSE_CUSTOMER_KEY: &str = "AWS_SSE_CUSTOMER_KEY";
+    /// Set to `md5` to verify the contents of objects. S3 does not support `crc32c`.
+    pub use crate::storage::checksum::checksum_options::CHECKSUM;
 
     /// The list of option keys owned by the S3 module.
     /// Option keys not contained in this list will be added to the `extra_opts` field of [crate::storage::s3::S3StorageOptions].
     /// `extra_opts` are passed to [crate::storage::s3::dynamodb_lock::DynamoDbOptions] to configure the lock client.
     pub const S3_OPTS: &[&str] = &[
         AWS_ENDPOINT_URL,
+        AWS_ALLOW_HTTP,
+        AWS_VIRTUAL_HOSTED_STYLE_REQUEST,
         AWS_REGION,
         AWS_S3_LOCKING_PROVIDER,
         AWS_S3_ASSUME_ROLE_ARN,
@@ -79,9 +120,18 @@ pub mod s3_storage_options {
         AWS_WEB_IDENTITY_TOKEN_FILE,
         AWS_ROLE_ARN,
         AWS_ROLE_SESSION_NAME,
+        AWS_PROFILE,
         AWS_S3_POOL_IDLE_TIMEOUT_SECONDS,
         AWS_STS_POOL_IDLE_TIMEOUT_SECONDS,
         AWS_S3_GET_INTERNAL_SERVER_ERROR_RETRIES,
+        AWS_SKIP_SIGNATURE,
+        ANONYMOUS,
+        AWS_REQUEST_PAYER,
+        REQUEST_PAYER,
+        AWS_SSE_TYPE,
+        AWS_SSE_KMS_KEY_ID,
+        AWS_SSE_CUSTOMER_KEY,
+        CHECKSUM,
     ];
 }
 
@@ -99,12 +149,18 @@ pub struct S3StorageOptions {
     s3_pool_idle_timeout: Duration,
     sts_pool_idle_timeout: Duration,
     s3_get_internal_server_error_retries: usize,
+    skip_signature: bool,
+    virtual_hosted_style_request: bool,
+    requester_pays: bool,
+    server_side_encryption: Result<Option<ServerSideEncryption>, String>,
+    checksum: Result<Option<ChecksumAlgorithm>, String>,
     extra_opts: HashMap<String, String>,
 }
 
 impl S3StorageOptions {
     /// Creates an instance of S3StorageOptions from the given HashMap.
     pub fn from_map(options: HashMap<String, String>) -> S3StorageOptions {
+        let options = Self::normalize_keys(options);
         let extra_opts = options
             .iter()
             .filter(|(k, _)| !s3_storage_options::S3_OPTS.contains(&k.as_str()))
@@ -130,6 +186,8 @@ impl S3StorageOptions {
         Self::ensure_env_var(&options, s3_storage_options::AWS_WEB_IDENTITY_TOKEN_FILE);
         Self::ensure_env_var(&options, s3_storage_options::AWS_ROLE_ARN);
         Self::ensure_env_var(&options, s3_storage_options::AWS_ROLE_SESSION_NAME);
+        // same for the profile picked up by the default credentials provider
+        Self::ensure_env_var(&options, s3_storage_options::AWS_PROFILE);
 
         let s3_pool_idle_timeout = Self::u64_or_default(
             &options,
@@ -165,10 +223,140 @@ impl S3StorageOptions {
             s3_pool_idle_timeout: Duration::from_secs(s3_pool_idle_timeout),
             sts_pool_idle_timeout: Duration::from_secs(sts_pool_idle_timeout),
             s3_get_internal_server_error_retries,
+            skip_signature: Self::bool_option(&options, s3_storage_options::AWS_SKIP_SIGNATURE)
+                || Self::bool_option(&options, s3_storage_options::ANONYMOUS),
+            virtual_hosted_style_request: Self::bool_option(
+                &options,
+                s3_storage_options::AWS_VIRTUAL_HOSTED_STYLE_REQUEST,
+            ),
+            requester_pays: Self::str_option(&options, s3_storage_options::AWS_REQUEST_PAYER)
+                .or_else(|| Self::str_option(&options, s3_storage_options::REQUEST_PAYER))
+                .map(|v| v.eq_ignore_ascii_case("requester") || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            server_side_encryption: Self::sse_option(&options),
+            checksum: Self::checksum_option(&options),
             extra_opts,
         }
     }
 
+    /// Parses the server-side encryption options. The error message never contains the key.
+    fn sse_option(
+        options: &HashMap<String, String>,
+    ) -> Result<Option<ServerSideEncryption>, String> {
+        use s3_storage_options::*;
+        let sse_type = Self::str_option(options, AWS_SSE_TYPE).map(|t| t.trim().to_lowercase());
+        let kms_key_id = Self::str_option(options, AWS_SSE_KMS_KEY_ID);
+        let customer_key = Self::str_option(options, AWS_SSE_CUSTOMER_KEY);
+
+        let sse = match sse_type.as_deref() {
+            None | Some("") => None,
+            Some("aes256") => Some(ServerSideEncryption::Aes256),
+            Some("kms") | Some("aws:kms") => Some(ServerSideEncryption::Kms {
+                key_id: kms_key_id.clone(),
+            }),
+            Some("c") | Some("sse-c") => {
+                let key = customer_key.as_deref().ok_or_else(|| {
+                    format!(
+                        "{} must be set when {} is c",
+                        AWS_SSE_CUSTOMER_KEY, AWS_SSE_TYPE
+                    )
+                })?;
+                Some(ServerSideEncryption::Customer(CustomerKey::parse(key)?))
+            }
+            Some(other) => {
+                return Err(format!(
+                    "unsupported {} {:?}, expected aes256, kms or c",
+                    AWS_SSE_TYPE, other
+                ))
+            }
+        };
+        // a key that is silently ignored would leave objects encrypted differently than intended
+        if kms_key_id.is_some() && !matches!(sse, Some(ServerSideEncryption::Kms { .. })) {
+            return Err(format!(
+                "{} requires {} kms",
+                AWS_SSE_KMS_KEY_ID, AWS_SSE_TYPE
+            ));
+        }
+        if customer_key.is_some() && !matches!(sse, Some(ServerSideEncryption::Customer(_))) {
+            return Err(format!(
+                "{} requires {} c",
+                AWS_SSE_CUSTOMER_KEY, AWS_SSE_TYPE
+            ));
+        }
+        Ok(sse)
+    }
+
+    /// Parses the checksum option. S3 only reports MD5 checksums, as ETags.
+    fn checksum_option(
+        options: &HashMap<String, String>,
+    ) -> Result<Option<ChecksumAlgorithm>, String> {
+        let value = Self::str_option(options, s3_storage_options::CHECKSUM).unwrap_or_default();
+        match ChecksumAlgorithm::parse(&value)? {
+            Some(ChecksumAlgorithm::Crc32c) => Err(format!(
+                "{} crc32c is not supported by S3, use md5",
+                s3_storage_options::CHECKSUM
+            )),
+            checksum => Ok(checksum),
+        }
+    }
+
+    /// The `x-amz-request-payer` header of all requests.
+    fn request_payer(&self) -> Option<String> {
+        self.requester_pays.then(|| "requester".to_string())
+    }
+
+    /// The algorithm verifying object contents, if enabled and the options are valid.
+    fn checksum(&self) -> Option<ChecksumAlgorithm> {
+        self.checksum.as_ref().ok().copied().flatten()
+    }
+
+    /// The server-side encryption applied to requests, if the options are valid.
+    fn sse(&self) -> Option<&ServerSideEncryption> {
+        self.server_side_encryption
+            .as_ref()
+            .ok()
+            .and_then(Option::as_ref)
+    }
+
+    /// Replaces the keys of known options by their canonical spelling, e.g. `aws_region` by
+    /// `AWS_REGION`.
+    fn normalize_keys(options: HashMap<String, String>) -> HashMap<String, String> {
+        use dynamodb_lock::dynamo_lock_options::DYNAMO_LOCK_OPTS;
+        options
+            .into_iter()
+            .map(|(key, value)| {
+                let canonical = s3_storage_options::S3_OPTS
+                    .iter()
+                    .chain(DYNAMO_LOCK_OPTS)
+                    .find(|known| known.eq_ignore_ascii_case(&key));
+                match canonical {
+                    Some(canonical) => (canonical.to_string(), value),
+                    None => (key, value),
+                }
+            })
+            .collect()
+    }
+
+    /// Checks the options that would only fail at the first request otherwise.
+    fn validate(&self) -> Result<(), StorageError> {
+        if self.virtual_hosted_style_request {
+            return Err(StorageError::InvalidOptions(format!(
+                "{} is not supported, S3 requests always use path-style addressing",
+                s3_storage_options::AWS_VIRTUAL_HOSTED_STYLE_REQUEST
+            )));
+        }
+        if let Some(endpoint) = &self._endpoint_url {
+            validate_endpoint(endpoint)?;
+        }
+        if let Err(message) = &self.server_side_encryption {
+            return Err(StorageError::InvalidOptions(message.clone()));
+        }
+        if let Err(message) = &self.checksum {
+            return Err(StorageError::InvalidOptions(message.clone()));
+        }
+        Ok(())
+    }
+
     fn str_or_default(map: &HashMap<String, String>, key: &str, default: String) -> String {
         map.get(key)
             .map(|v| v.to_owned())
@@ -186,6 +374,12 @@ impl S3StorageOptions {
             .unwrap_or(default)
     }
 
+    fn bool_option(map: &HashMap<String, String>, key: &str) -> bool {
+        Self::str_option(map, key)
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+    }
+
     fn ensure_env_var(map: &HashMap<String, String>, key: &str) {
         if let Some(val) = Self::str_option(map, key) {
             std::env::set_var(key, val);
@@ -193,6 +387,138 @@ impl S3StorageOptions {
     }
 }
 
+/// Checks that `endpoint` is an HTTP(S) URL with a host, or a host without scheme.
+fn validate_endpoint(endpoint: &str) -> Result<(), StorageError> {
+    let (scheme, rest) = endpoint.split_once("://").unwrap_or(("https", endpoint));
+    let host = rest.split('/').next().unwrap_or_default();
+    let valid_host =
+        !host.is_empty() && !host.starts_with(':') && !host.contains(char::is_whitespace);
+    if (scheme == "http" || scheme == "https") && valid_host {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidOptions(format!(
+            "{} must be an http(s) URL, found scheme {:?} and host {:?}",
+            s3_storage_options::AWS_ENDPOINT_URL,
+            scheme,
+            host
+        )))
+    }
+}
+
+/// The server-side encryption of objects, configured with [s3_storage_options::AWS_SSE_TYPE].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerSideEncryption {
+    /// SSE-S3, with keys managed by S3.
+    Aes256,
+    /// SSE-KMS, with the given KMS key or the AWS managed key of S3 if `None`.
+    Kms {
+        /// The id or ARN of the KMS key.
+        key_id: Option<String>,
+    },
+    /// SSE-C, with a key provided by the client on every request.
+    Customer(CustomerKey),
+}
+
+/// A client provided SSE-C key. The key is not shown by [Debug].
+#[derive(Clone, PartialEq)]
+pub struct CustomerKey {
+    key: String,
+    key_md5: String,
+}
+
+impl CustomerKey {
+    const ALGORITHM: &'static str = "AES256";
+
+    fn parse(key: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "{} must be a base64 encoded 256-bit key",
+                s3_storage_options::AWS_SSE_CUSTOMER_KEY
+            )
+        };
+        let bytes = base64::decode(key.trim()).map_err(|_| invalid())?;
+        if bytes.len() != 32 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            key: base64::encode(&bytes),
+            key_md5: base64::encode(md5::compute(&bytes).0),
+        })
+    }
+
+    /// The algorithm, key and key MD5 headers of requests.
+    fn headers(&self) -> (Option<String>, Option<String>, Option<String>) {
+        (
+            Some(Self::ALGORITHM.to_string()),
+            Some(self.key.clone()),
+            Some(self.key_md5.clone()),
+        )
+    }
+}
+
+impl Debug for CustomerKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CustomerKey(<redacted>)")
+    }
+}
+
+impl ServerSideEncryption {
+    fn apply_to_put(&self, request: &mut PutObjectRequest) {
+        match self {
+            Self::Aes256 => request.server_side_encryption = Some("AES256".to_string()),
+            Self::Kms { key_id } => {
+                request.server_side_encryption = Some("aws:kms".to_string());
+                request.ssekms_key_id = key_id.clone();
+            }
+            Self::Customer(key) => {
+                let (algorithm, key, key_md5) = key.headers();
+                request.sse_customer_algorithm = algorithm;
+                request.sse_customer_key = key;
+                request.sse_customer_key_md5 = key_md5;
+            }
+        }
+    }
+
+    /// Encrypts the copy like a put, and decrypts the SSE-C encrypted source.
+    fn apply_to_copy(&self, request: &mut CopyObjectRequest) {
+        match self {
+            Self::Aes256 => request.server_side_encryption = Some("AES256".to_string()),
+            Self::Kms { key_id } => {
+                request.server_side_encryption = Some("aws:kms".to_string());
+                request.ssekms_key_id = key_id.clone();
+            }
+            Self::Customer(key) => {
+                let (algorithm, key, key_md5) = key.headers();
+                request.sse_customer_algorithm = algorithm.clone();
+                request.sse_customer_key = key.clone();
+                request.sse_customer_key_md5 = key_md5.clone();
+                request.copy_source_sse_customer_algorithm = algorithm;
+                request.copy_source_sse_customer_key = key;
+                request.copy_source_sse_customer_key_md5 = key_md5;
+            }
+        }
+    }
+
+    /// Only SSE-C requires headers on reads.
+    fn apply_to_get(&self, request: &mut GetObjectRequest) {
+        if let Self::Customer(key) = self {
+            let (algorithm, key, key_md5) = key.headers();
+            request.sse_customer_algorithm = algorithm;
+            request.sse_customer_key = key;
+            request.sse_customer_key_md5 = key_md5;
+        }
+    }
+
+    fn apply_to_head(&self, request: &mut HeadObjectRequest) {
+        if let Self::Customer(key) = self {
+            let (algorithm, key, key_md5) = key.headers();
+            request.sse_customer_algorithm = algorithm;
+            request.sse_customer_key = key;
+            request.sse_customer_key_md5 = key_md5;
+        }
+    }
+}
+
 impl Default for S3StorageOptions {
     /// Creates an instance of S3StorageOptions from environment variables.
     fn default() -> S3StorageOptions {
@@ -309,7 +635,11 @@ fn get_web_identity_provider() -> Result<AutoRefreshingProvider<WebIdentityProvi
 fn create_s3_client(options: &S3StorageOptions) -> Result<S3Client, StorageError> {
     let http_client = create_http_client(options.s3_pool_idle_timeout)?;
     let region = options.region.clone();
-    if options.use_web_identity {
+    if options.skip_signature {
+        // rusoto does not sign requests with empty credentials
+        let provider = StaticProvider::new_minimal(String::new(), String::new());
+        Ok(S3Client::new_with(http_client, provider, region))
+    } else if options.use_web_identity {
         let provider = get_web_identity_provider()?;
         Ok(S3Client::new_with(http_client, provider, region))
     } else if let Some(assume_role_arn) = &options.assume_role_arn {
@@ -368,6 +698,9 @@ fn try_object_meta_from(bucket: &str, obj: rusoto_s3::Object) -> Result<ObjectMe
     Ok(ObjectMeta {
         path: format!("s3://{}/{}", bucket, key),
         modified: parse_obj_last_modified_time(&obj.last_modified)?,
+        size: obj.size,
+        e_tag: obj.e_tag,
+        version: None,
     })
 }
 
@@ -397,6 +730,7 @@ impl S3StorageBackend {
     /// Creates a new S3StorageBackend.
     pub fn new() -> Result<Self, StorageError> {
         let options = S3StorageOptions::default();
+        options.validate()?;
         let client = create_s3_client(&options)?;
         let lock_client = try_create_lock_client(&options)?;
 
@@ -411,6 +745,7 @@ impl S3StorageBackend {
     ///
     /// Options are described in
     pub fn new_from_options(options: S3StorageOptions) -> Result<Self, StorageError> {
+        options.validate()?;
         let client = create_s3_client(&options)?;
         let lock_client = try_create_lock_client(&options)?;
 
@@ -448,91 +783,35 @@ impl S3StorageBackend {
         let src = parse_uri(src)?.into_s3object()?;
         let dst = parse_uri(dst)?.into_s3object()?;
 
-        self.client
-            .copy_object(CopyObjectRequest {
-                bucket: dst.bucket.to_string(),
-                key: dst.key.to_string(),
-                copy_source: format!("{}/{}", src.bucket, src.key),
-                ..Default::default()
-            })
-            .await?;
+        let mut copy_req = CopyObjectRequest {
+            bucket: dst.bucket.to_string(),
+            key: dst.key.to_string(),
+            copy_source: format!("{}/{}", src.bucket, src.key),
+            request_payer: self.options.request_payer(),
+            ..Default::default()
+        };
+        if let Some(sse) = self.options.sse() {
+            sse.apply_to_copy(&mut copy_req);
+        }
+        self.client.copy_object(copy_req).await?;
 
         self.client
             .delete_object(DeleteObjectRequest {
                 bucket: src.bucket.to_string(),
                 key: src.key.to_string(),
+                request_payer: self.options.request_payer(),
                 ..Default::default()
             })
             .await?;
 
         Ok(())
     }
-}
 
-impl Default for S3StorageBackend {
-    fn default() -> Self {
-        Self::new().unwrap()
-    }
-}
-
-impl std::fmt::Debug for S3StorageBackend {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(fmt, "S3StorageBackend")
-    }
-}
-
-#[async_trait::async_trait]
-impl StorageBackend for S3StorageBackend {
-    async fn head_obj(&self, path: &str) -> Result<ObjectMeta, StorageError> {
-        let uri = parse_uri(path)?.into_s3object()?;
-
-        let result = self
-            .client
-            .head_object(HeadObjectRequest {
-                bucket: uri.bucket.to_string(),
-                key: uri.key.to_string(),
-                ..Default::default()
-            })
-            .await?;
-
-        Ok(ObjectMeta {
-            path: path.to_string(),
-            modified: parse_head_obj_last_modified_time(&result.last_modified)?,
-        })
-    }
-
-    async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
-        debug!("fetching s3 object: {}...", path);
-
-        let uri = parse_uri(path)?.into_s3object()?;
-        let result = get_object_with_retries(
-            &self.client,
-            uri.bucket,
-            uri.key,
-            self.options.s3_get_internal_server_error_retries,
-        )
-        .await?;
-
-        debug!("streaming data from {}...", path);
-        let mut buf = Vec::new();
-        let stream = result
-            .body
-            .ok_or_else(|| StorageError::S3MissingObjectBody(path.to_string()))?;
-        stream
-            .into_async_read()
-            .read_to_end(&mut buf)
-            .await
-            .map_err(|e| {
-                StorageError::S3Generic(format!("Failed to read object content: {}", e))
-            })?;
-
-        debug!("s3 object fetched: {}", path);
-        Ok(buf)
-    }
-
-    async fn list_objs<'a>(
+    /// Lists the objects by `path` prefix whose keys sort after `start_after`, if given.
+    fn list_objs_from<'a>(
         &'a self,
         path: &'a str,
+        start_after: Option<String>,
     ) -> Result<
         Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
         StorageError,
@@ -556,12 +835,16 @@ impl StorageBackend for S3StorageBackend {
             continuation_token: ContinuationToken,
             bucket: String,
             key: String,
+            start_after: Option<String>,
+            request_payer: Option<String>,
         }
         let ctx = ListContext {
             obj_iter: Vec::new().into_iter(),
             continuation_token: ContinuationToken::Value(None),
             bucket: uri.bucket.to_string(),
             key: uri.key.to_string(),
+            start_after,
+            request_payer: self.options.request_payer(),
             client: self.client.clone(),
         };
 
@@ -577,6 +860,8 @@ impl StorageBackend for S3StorageBackend {
                             bucket: ctx.bucket.clone(),
                             prefix: Some(ctx.key.clone()),
                             continuation_token: v.clone(),
+                            start_after: ctx.start_after.clone(),
+                            request_payer: ctx.request_payer.clone(),
                             ..Default::default()
                         };
                         let result = match ctx.client.list_objects_v2(list_req).await {
@@ -600,23 +885,182 @@ impl StorageBackend for S3StorageBackend {
 
         Ok(Box::pin(futures::stream::unfold(ctx, next_meta)))
     }
+}
+
+impl Default for S3StorageBackend {
+    fn default() -> Self {
+        Self::new().unwrap()
+    }
+}
+
+impl std::fmt::Debug for S3StorageBackend {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(fmt, "S3StorageBackend")
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for S3StorageBackend {
+    async fn head_obj(&self, path: &str) -> Result<ObjectMeta, StorageError> {
+        let uri = parse_uri(path)?.into_s3object()?;
+
+        let mut head_req = HeadObjectRequest {
+            bucket: uri.bucket.to_string(),
+            key: uri.key.to_string(),
+            request_payer: self.options.request_payer(),
+            ..Default::default()
+        };
+        if let Some(sse) = self.options.sse() {
+            sse.apply_to_head(&mut head_req);
+        }
+        let result = self.client.head_object(head_req).await?;
+
+        Ok(ObjectMeta {
+            path: path.to_string(),
+            modified: parse_head_obj_last_modified_time(&result.last_modified)?,
+            size: result.content_length,
+            e_tag: result.e_tag,
+            version: result.version_id,
+        })
+    }
+
+    async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        debug!("fetching s3 object: {}...", path);
+
+        let uri = parse_uri(path)?.into_s3object()?;
+        let result = get_object_with_retries(
+            &self.client,
+            uri.bucket,
+            uri.key,
+            None,
+            self.options.request_payer(),
+            self.options.sse(),
+            self.options.s3_get_internal_server_error_retries,
+        )
+        .await?;
+
+        debug!("streaming data from {}...", path);
+        let mut buf = Vec::new();
+        let stream = result
+            .body
+            .ok_or_else(|| StorageError::S3MissingObjectBody(path.to_string()))?;
+        stream
+            .into_async_read()
+            .read_to_end(&mut buf)
+            .await
+            .map_err(|e| {
+                StorageError::S3Generic(format!("Failed to read object content: {}", e))
+            })?;
+
+        if self.options.checksum() == Some(ChecksumAlgorithm::Md5) {
+            // the ETag is not the MD5 of objects encrypted with keys other than those of S3
+            let plain_etag = matches!(
+                self.options.sse(),
+                None | Some(ServerSideEncryption::Aes256)
+            );
+            if let Some(expected) = result
+                .e_tag
+                .as_deref()
+                .filter(|_| plain_etag)
+                .and_then(md5_etag)
+            {
+                ChecksumAlgorithm::Md5.verify(path, &expected, &buf)?;
+            }
+        }
+
+        debug!("s3 object fetched: {}", path);
+        Ok(buf)
+    }
+
+    /// Ranged reads are not verified against the checksum, which covers the whole object.
+    async fn get_range(&self, path: &str, range: Range<usize>) -> Result<Vec<u8>, StorageError> {
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+        debug!("fetching bytes {:?} of s3 object: {}...", range, path);
+
+        let uri = parse_uri(path)?.into_s3object()?;
+        let result = get_object_with_retries(
+            &self.client,
+            uri.bucket,
+            uri.key,
+            Some(format!("bytes={}-{}", range.start, range.end - 1)),
+            self.options.request_payer(),
+            self.options.sse(),
+            self.options.s3_get_internal_server_error_retries,
+        )
+        .await;
+        let result = match result {
+            // the range starts after the end of the object
+            Err(RusotoError::Unknown(response)) if response.status.as_u16() == 416 => {
+                return Ok(Vec::new())
+            }
+            result => result?,
+        };
+
+        let mut buf = Vec::new();
+        result
+            .body
+            .ok_or_else(|| StorageError::S3MissingObjectBody(path.to_string()))?
+            .into_async_read()
+            .read_to_end(&mut buf)
+            .await
+            .map_err(|e| {
+                StorageError::S3Generic(format!("Failed to read object content: {}", e))
+            })?;
+        Ok(buf)
+    }
+
+    async fn list_objs<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        self.list_objs_from(path, None)
+    }
+
+    /// S3 lists objects in lexicographic order and starts listings after the given key.
+    async fn list_objs_after<'a>(
+        &'a self,
+        path: &'a str,
+        start_after: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        let start_after = parse_uri(start_after)?.into_s3object()?;
+        self.list_objs_from(path, Some(start_after.key.to_string()))
+    }
 
     async fn put_obj(&self, path: &str, obj_bytes: &[u8]) -> Result<(), StorageError> {
         debug!("put s3 object: {}...", path);
 
         let uri = parse_uri(path)?.into_s3object()?;
-        let put_req = PutObjectRequest {
+        let mut put_req = PutObjectRequest {
             bucket: uri.bucket.to_string(),
             key: uri.key.to_string(),
             body: Some(obj_bytes.to_vec().into()),
+            request_payer: self.options.request_payer(),
+            content_md5: (self.options.checksum() == Some(ChecksumAlgorithm::Md5))
+                .then(|| base64::encode(md5::compute(obj_bytes).0)),
             ..Default::default()
         };
+        if let Some(sse) = self.options.sse() {
+            sse.apply_to_put(&mut put_req);
+        }
 
         self.client.put_object(put_req).await?;
 
         Ok(())
     }
 
+    /// S3 has no atomic rename, so concurrent writes are only safe with a lock client.
+    fn is_safe_for_concurrent_writes(&self) -> bool {
+        self.lock_client.is_some()
+    }
+
     async fn rename_obj_noreplace(&self, src: &str, dst: &str) -> Result<(), StorageError> {
         debug!("rename s3 object: {} -> {}...", src, dst);
 
@@ -641,6 +1085,7 @@ impl StorageBackend for S3StorageBackend {
         let delete_req = DeleteObjectRequest {
             bucket: uri.bucket.to_string(),
             key: uri.key.to_string(),
+            request_payer: self.options.request_payer(),
             ..Default::default()
         };
 
@@ -689,6 +1134,7 @@ impl StorageBackend for S3StorageBackend {
             let delete_req = DeleteObjectsRequest {
                 bucket: bucket.to_string(),
                 delete,
+                request_payer: self.options.request_payer(),
                 ..Default::default()
             };
             self.client.delete_objects(delete_req).await?;
@@ -751,6 +1197,15 @@ fn try_create_lock_client(
 
     match &options.locking_provider {
         Some(p) if p.to_lowercase() == "dynamodb" => {
+            // a default lock table would silently split writers with different defaults
+            let table_name = dynamodb_lock::dynamo_lock_options::DYNAMO_LOCK_TABLE_NAME;
+            if !options.extra_opts.contains_key(table_name) && std::env::var(table_name).is_err() {
+                return Err(StorageError::InvalidOptions(format!(
+                    "{} must be set when {} is dynamodb",
+                    table_name,
+                    s3_storage_options::AWS_S3_LOCKING_PROVIDER
+                )));
+            }
             let dynamodb_client = match options.use_web_identity {
                 true => rusoto_dynamodb::DynamoDbClient::new_with(
                     dispatcher,
@@ -765,7 +1220,13 @@ fn try_create_lock_client(
             );
             Ok(Some(Box::new(lock_client)))
         }
-        _ => Ok(None),
+        Some(p) if p.trim().is_empty() || p.trim().eq_ignore_ascii_case("none") => Ok(None),
+        Some(p) => Err(StorageError::InvalidOptions(format!(
+            "unsupported {} {:?}, only dynamodb is supported",
+            s3_storage_options::AWS_S3_LOCKING_PROVIDER,
+            p
+        ))),
+        None => Ok(None),
     }
 }
 
@@ -874,21 +1335,41 @@ impl dyn LockClient {
     }
 }
 
+/// Decodes an ETag that is the MD5 of the object, i.e. of an object not uploaded in parts, whose
+/// ETags have a `-<parts>` suffix.
+fn md5_etag(e_tag: &str) -> Option<Vec<u8>> {
+    let hex = e_tag.trim_matches('"');
+    if hex.len() != 32 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
 async fn get_object_with_retries(
     client: &S3Client,
     bucket: &str,
     key: &str,
+    range: Option<String>,
+    request_payer: Option<String>,
+    sse: Option<&ServerSideEncryption>,
     retries: usize,
 ) -> Result<GetObjectOutput, RusotoError<GetObjectError>> {
     let mut tries = 0;
     loop {
-        let result = client
-            .get_object(GetObjectRequest {
-                bucket: bucket.to_string(),
-                key: key.to_string(),
-                ..Default::default()
-            })
-            .await;
+        let mut get_req = GetObjectRequest {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+            range: range.clone(),
+            request_payer: request_payer.clone(),
+            ..Default::default()
+        };
+        if let Some(sse) = sse {
+            sse.apply_to_get(&mut get_req);
+        }
+        let result = client.get_object(get_req).await;
         match result {
             Err(RusotoError::Unknown(e)) if e.status.is_server_error() && tries < retries => {
                 log::warn!("Got {:?}, retrying", e);
@@ -906,8 +1387,13 @@ async fn get_object_with_retries(
 mod tests {
     use super::*;
 
+    use futures::TryStreamExt;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Method, Request, Response, Server};
     use maplit::hashmap;
     use serial_test::serial;
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn join_multiple_paths() {
@@ -944,6 +1430,11 @@ mod tests {
     fn storage_options_default_test() {
         std::env::set_var(s3_storage_options::AWS_ENDPOINT_URL, "http://localhost");
         std::env::set_var(s3_storage_options::AWS_REGION, "us-west-1");
+        // backends created by concurrent tests require a lock table with the provider
+        std::env::set_var(
+            dynamodb_lock::dynamo_lock_options::DYNAMO_LOCK_TABLE_NAME,
+            "test_table",
+        );
         std::env::set_var(s3_storage_options::AWS_S3_LOCKING_PROVIDER, "dynamodb");
         std::env::set_var(
             s3_storage_options::AWS_S3_ASSUME_ROLE_ARN,
@@ -974,6 +1465,11 @@ mod tests {
                 s3_pool_idle_timeout: Duration::from_secs(15),
                 sts_pool_idle_timeout: Duration::from_secs(10),
                 s3_get_internal_server_error_retries: 10,
+                skip_signature: false,
+                virtual_hosted_style_request: false,
+                requester_pays: false,
+                server_side_encryption: Ok(None),
+                checksum: Ok(None),
                 extra_opts: HashMap::new(),
             },
             options
@@ -1009,6 +1505,11 @@ mod tests {
                 s3_pool_idle_timeout: Duration::from_secs(1),
                 sts_pool_idle_timeout: Duration::from_secs(2),
                 s3_get_internal_server_error_retries: 3,
+                skip_signature: false,
+                virtual_hosted_style_request: false,
+                requester_pays: false,
+                server_side_encryption: Ok(None),
+                checksum: Ok(None),
                 extra_opts: HashMap::new(),
             },
             options
@@ -1020,6 +1521,11 @@ mod tests {
     fn storage_options_mixed_test() {
         std::env::set_var(s3_storage_options::AWS_ENDPOINT_URL, "http://localhost");
         std::env::set_var(s3_storage_options::AWS_REGION, "us-west-1");
+        // backends created by concurrent tests require a lock table with the provider
+        std::env::set_var(
+            dynamodb_lock::dynamo_lock_options::DYNAMO_LOCK_TABLE_NAME,
+            "test_table",
+        );
         std::env::set_var(s3_storage_options::AWS_S3_LOCKING_PROVIDER, "dynamodb");
         std::env::set_var(
             s3_storage_options::AWS_S3_ASSUME_ROLE_ARN,
@@ -1058,6 +1564,11 @@ mod tests {
                 s3_pool_idle_timeout: Duration::from_secs(1),
                 sts_pool_idle_timeout: Duration::from_secs(2),
                 s3_get_internal_server_error_retries: 3,
+                skip_signature: false,
+                virtual_hosted_style_request: false,
+                requester_pays: false,
+                server_side_encryption: Ok(None),
+                checksum: Ok(None),
                 extra_opts: hashmap! {
                     "DYNAMO_LOCK_PARTITION_KEY_VALUE".to_string() => "my_lock".to_string(),
                 },
@@ -1089,4 +1600,398 @@ mod tests {
             std::env::var(s3_storage_options::AWS_ROLE_SESSION_NAME).unwrap()
         );
     }
+
+    #[test]
+    #[serial]
+    fn storage_options_profile_test() {
+        let _options = S3StorageOptions::from_map(hashmap! {
+            s3_storage_options::AWS_PROFILE.to_string() => "sso_profile".to_string(),
+        });
+
+        assert_eq!(
+            "sso_profile",
+            std::env::var(s3_storage_options::AWS_PROFILE).unwrap()
+        );
+        std::env::remove_var(s3_storage_options::AWS_PROFILE);
+    }
+
+    #[test]
+    #[serial]
+    fn storage_options_skip_signature_test() {
+        std::env::remove_var(s3_storage_options::AWS_SKIP_SIGNATURE);
+        std::env::remove_var(s3_storage_options::ANONYMOUS);
+        assert!(!S3StorageOptions::from_map(HashMap::new()).skip_signature);
+
+        let options = S3StorageOptions::from_map(hashmap! {
+            s3_storage_options::AWS_SKIP_SIGNATURE.to_string() => "TRUE".to_string(),
+        });
+        assert!(options.skip_signature);
+
+        let options = S3StorageOptions::from_map(hashmap! {
+            s3_storage_options::ANONYMOUS.to_string() => "true".to_string(),
+        });
+        assert!(options.skip_signature);
+        assert!(options.extra_opts.is_empty());
+    }
+
+    #[test]
+    fn lock_client_configuration() {
+        let lock_client = |provider: &str| {
+            try_create_lock_client(&S3StorageOptions::from_map(hashmap! {
+                s3_storage_options::AWS_REGION.to_string() => "us-east-1".to_string(),
+                s3_storage_options::AWS_S3_LOCKING_PROVIDER.to_string() => provider.to_string(),
+                dynamodb_lock::dynamo_lock_options::DYNAMO_LOCK_TABLE_NAME.to_string() => "locks".to_string(),
+            }))
+        };
+        assert!(lock_client("dynamodb").unwrap().is_some());
+        assert!(lock_client("none").unwrap().is_none());
+        assert!(matches!(
+            lock_client("zookeeper"),
+            Err(StorageError::InvalidOptions(_))
+        ));
+    }
+
+    #[test]
+    fn storage_options_are_case_insensitive() {
+        let options = S3StorageOptions::from_map(hashmap! {
+            "aws_endpoint_url".to_string() => "http://localhost:9000".to_string(),
+            "Aws_Region".to_string() => "minio".to_string(),
+            "aws_virtual_hosted_style_request".to_string() => "false".to_string(),
+            "dynamo_lock_table_name".to_string() => "locks".to_string(),
+        });
+        assert_eq!(
+            options.region,
+            Region::Custom {
+                name: "minio".to_string(),
+                endpoint: "http://localhost:9000".to_string(),
+            }
+        );
+        assert_eq!(
+            options.extra_opts,
+            hashmap! {
+                dynamodb_lock::dynamo_lock_options::DYNAMO_LOCK_TABLE_NAME.to_string() => "locks".to_string(),
+            }
+        );
+        assert!(options.validate().is_ok());
+
+        let options = S3StorageOptions::from_map(hashmap! {
+            s3_storage_options::AWS_VIRTUAL_HOSTED_STYLE_REQUEST.to_string() => "true".to_string(),
+        });
+        assert!(matches!(
+            options.validate(),
+            Err(StorageError::InvalidOptions(_))
+        ));
+    }
+
+    #[test]
+    fn endpoint_validation() {
+        for endpoint in [
+            "http://localhost:9000",
+            "https://s3.example.com/",
+            "minio.local:9000",
+        ] {
+            assert!(validate_endpoint(endpoint).is_ok(), "{}", endpoint);
+        }
+        match validate_endpoint("ftp://localhost:9000") {
+            Err(StorageError::InvalidOptions(message)) => assert_eq!(
+                message,
+                "AWS_ENDPOINT_URL must be an http(s) URL, found scheme \"ftp\" and host \"localhost:9000\""
+            ),
+            other => panic!("expected invalid options, got {:?}", other),
+        }
+        assert!(validate_endpoint("http://").is_err());
+        assert!(validate_endpoint("http://:9000").is_err());
+    }
+
+    const CUSTOMER_KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+
+    #[test]
+    fn server_side_encryption_options() {
+        let sse = |entries: &[(&str, &str)]| {
+            let options = entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            S3StorageOptions::from_map(options).server_side_encryption
+        };
+        use s3_storage_options::*;
+
+        assert_eq!(sse(&[]), Ok(None));
+        assert_eq!(
+            sse(&[(AWS_SSE_TYPE, "AES256")]),
+            Ok(Some(ServerSideEncryption::Aes256))
+        );
+        assert_eq!(
+            sse(&[
+                (AWS_SSE_TYPE, "kms"),
+                (AWS_SSE_KMS_KEY_ID, "arn:aws:kms:key")
+            ]),
+            Ok(Some(ServerSideEncryption::Kms {
+                key_id: Some("arn:aws:kms:key".to_string())
+            }))
+        );
+        match sse(&[(AWS_SSE_TYPE, "c"), (AWS_SSE_CUSTOMER_KEY, CUSTOMER_KEY)]) {
+            Ok(Some(ServerSideEncryption::Customer(key))) => {
+                assert_eq!(key.key_md5, "hRasmdxgYDKV3nvbahU1MA==");
+                assert!(!format!("{:?}", key).contains(CUSTOMER_KEY));
+            }
+            other => panic!("expected a customer key, got {:?}", other),
+        }
+
+        for invalid in [
+            sse(&[(AWS_SSE_TYPE, "rot13")]),
+            sse(&[(AWS_SSE_TYPE, "c")]),
+            sse(&[(AWS_SSE_TYPE, "c"), (AWS_SSE_CUSTOMER_KEY, "c2hvcnQ=")]),
+            sse(&[(AWS_SSE_KMS_KEY_ID, "arn:aws:kms:key")]),
+            sse(&[(AWS_SSE_TYPE, "kms"), (AWS_SSE_CUSTOMER_KEY, CUSTOMER_KEY)]),
+        ] {
+            let message = invalid.unwrap_err();
+            assert!(!message.contains(CUSTOMER_KEY), "{}", message);
+        }
+    }
+
+    type Recorded = Arc<Mutex<Vec<(Method, String, hyper::HeaderMap)>>>;
+
+    /// The ETag of objects with the contents `data`, i.e. their MD5.
+    const DATA_ETAG: &str = "\"8d777f385d3dfec8815d20f7496026dc\"";
+
+    /// Answers S3 requests like a bucket with the object `src`, and records them.
+    async fn respond(
+        request: Request<Body>,
+        recorded: Recorded,
+    ) -> Result<Response<Body>, Infallible> {
+        let (method, path) = (request.method().clone(), request.uri().path().to_string());
+        let is_copy = request.headers().contains_key("x-amz-copy-source");
+        recorded
+            .lock()
+            .unwrap()
+            .push((method.clone(), path.clone(), request.headers().clone()));
+        let response = Response::builder()
+            .header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .header("Content-Length", "4");
+        Ok(match (method, path.as_str()) {
+            (Method::HEAD, "/bucket/dst") => {
+                Response::builder().status(404).body(Body::empty()).unwrap()
+            }
+            (Method::PUT, _) if is_copy => Response::builder()
+                .body(Body::from(
+                    "<CopyObjectResult><ETag>\"etag\"</ETag></CopyObjectResult>",
+                ))
+                .unwrap(),
+            (Method::GET, "/bucket") | (Method::GET, "/bucket/") => Response::builder()
+                .body(Body::from(
+                    "<ListBucketResult><Contents><Key>src</Key>\
+                     <LastModified>2015-10-21T07:28:00.000Z</LastModified>\
+                     <ETag>\"8d777f385d3dfec8815d20f7496026dc\"</ETag>\
+                     <Size>4</Size></Contents></ListBucketResult>",
+                ))
+                .unwrap(),
+            // contents corrupted in transit, not matching their ETag
+            (Method::GET, "/bucket/corrupt") => response
+                .header("ETag", DATA_ETAG)
+                .body(Body::from("dbta"))
+                .unwrap(),
+            (Method::GET, _) | (Method::HEAD, _) => response
+                .header("ETag", DATA_ETAG)
+                .header("x-amz-version-id", "v1")
+                .body(Body::from("data"))
+                .unwrap(),
+            _ => Response::new(Body::empty()),
+        })
+    }
+
+    /// Starts a server for [respond] and returns a backend configured by `options` sending
+    /// requests to it.
+    fn start_recording_backend(options: HashMap<String, String>) -> (S3StorageBackend, Recorded) {
+        let recorded = Recorded::default();
+        let server_recorded = recorded.clone();
+        let make_service = make_service_fn(move |_| {
+            let recorded = server_recorded.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    respond(request, recorded.clone())
+                }))
+            }
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let endpoint = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+
+        let mut options = options;
+        options.insert(s3_storage_options::AWS_ENDPOINT_URL.to_string(), endpoint);
+        options.insert(
+            s3_storage_options::AWS_REGION.to_string(),
+            "us-east-1".to_string(),
+        );
+        let options = S3StorageOptions::from_map(options);
+        let client = S3Client::new_with(
+            HttpClient::new().unwrap(),
+            StaticProvider::new_minimal("key".to_string(), "secret".to_string()),
+            options.region.clone(),
+        );
+        (S3StorageBackend::new_with(client, None, options), recorded)
+    }
+
+    fn header(headers: &hyper::HeaderMap, name: &str) -> Option<String> {
+        headers
+            .get(name)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn requester_pays_headers() {
+        let (backend, recorded) = start_recording_backend(hashmap! {
+            "Request_Payer".to_string() => "requester".to_string(),
+        });
+
+        backend.head_obj("s3://bucket/src").await.unwrap();
+        assert_eq!(backend.get_obj("s3://bucket/src").await.unwrap(), b"data");
+        let listed: Vec<_> = backend
+            .list_objs("s3://bucket/")
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].path, "s3://bucket/src");
+        backend.put_obj("s3://bucket/src", b"data").await.unwrap();
+        backend.delete_obj("s3://bucket/src").await.unwrap();
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(
+            recorded
+                .iter()
+                .map(|(method, _, _)| method.as_str())
+                .collect::<Vec<_>>(),
+            ["HEAD", "GET", "GET", "PUT", "DELETE"]
+        );
+        for (method, path, headers) in recorded.iter() {
+            assert_eq!(
+                header(headers, "x-amz-request-payer").as_deref(),
+                Some("requester"),
+                "{} {}",
+                method,
+                path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn server_side_encryption_headers() {
+        let (backend, recorded) = start_recording_backend(hashmap! {
+            s3_storage_options::AWS_SSE_TYPE.to_string() => "c".to_string(),
+            s3_storage_options::AWS_SSE_CUSTOMER_KEY.to_string() => CUSTOMER_KEY.to_string(),
+        });
+
+        backend.put_obj("s3://bucket/src", b"data").await.unwrap();
+        assert_eq!(backend.get_obj("s3://bucket/src").await.unwrap(), b"data");
+        backend.head_obj("s3://bucket/src").await.unwrap();
+        backend
+            .unsafe_rename_obj("s3://bucket/src", "s3://bucket/dst")
+            .await
+            .unwrap();
+
+        let recorded = recorded.lock().unwrap();
+        let customer_headers = [
+            ("x-amz-server-side-encryption-customer-algorithm", "AES256"),
+            ("x-amz-server-side-encryption-customer-key", CUSTOMER_KEY),
+            (
+                "x-amz-server-side-encryption-customer-key-MD5",
+                "hRasmdxgYDKV3nvbahU1MA==",
+            ),
+        ];
+        for (method, path, headers) in recorded.iter() {
+            if *method == Method::DELETE {
+                continue;
+            }
+            for (name, value) in customer_headers {
+                assert_eq!(
+                    header(headers, name).as_deref(),
+                    Some(value),
+                    "{} {} {}",
+                    method,
+                    path,
+                    name
+                );
+            }
+        }
+        let (_, _, copy_headers) = recorded
+            .iter()
+            .find(|(_, _, headers)| headers.contains_key("x-amz-copy-source"))
+            .unwrap();
+        for (name, value) in customer_headers {
+            let name = format!("x-amz-copy-source-{}", name.trim_start_matches("x-amz-"));
+            assert_eq!(header(copy_headers, &name).as_deref(), Some(value));
+        }
+        assert_eq!(
+            recorded
+                .iter()
+                .map(|(method, _, _)| method.as_str())
+                .collect::<Vec<_>>(),
+            ["PUT", "GET", "HEAD", "HEAD", "PUT", "DELETE"]
+        );
+    }
+
+    #[tokio::test]
+    async fn e_tags_and_versions() {
+        let (backend, _) = start_recording_backend(HashMap::new());
+
+        let obj = backend.head_obj("s3://bucket/src").await.unwrap();
+        assert_eq!(obj.e_tag.as_deref(), Some(DATA_ETAG));
+        assert_eq!(obj.version.as_deref(), Some("v1"));
+        // listings only report the versions of versioned listings
+        let listed: Vec<_> = backend
+            .list_objs("s3://bucket/")
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(listed[0].e_tag.as_deref(), Some(DATA_ETAG));
+        assert_eq!(listed[0].version, None);
+    }
+
+    #[tokio::test]
+    async fn md5_checksums() {
+        let (backend, recorded) = start_recording_backend(hashmap! {
+            s3_storage_options::CHECKSUM.to_string() => "md5".to_string(),
+        });
+
+        backend.put_obj("s3://bucket/src", b"data").await.unwrap();
+        let put_headers = recorded.lock().unwrap()[0].2.clone();
+        assert_eq!(
+            header(&put_headers, "Content-MD5").as_deref(),
+            Some("jXd/OF09/siBXSD3SWAm3A==")
+        );
+        assert_eq!(backend.get_obj("s3://bucket/src").await.unwrap(), b"data");
+        match backend.get_obj("s3://bucket/corrupt").await {
+            Err(StorageError::ChecksumMismatch {
+                path,
+                expected,
+                actual,
+                ..
+            }) => {
+                assert_eq!(path, "s3://bucket/corrupt");
+                assert_eq!(expected, "8d777f385d3dfec8815d20f7496026dc");
+                assert_ne!(actual, expected);
+            }
+            other => panic!("expected a checksum mismatch, got {:?}", other),
+        }
+
+        // corrupted objects are only detected if checksums are enabled
+        let (backend, _) = start_recording_backend(HashMap::new());
+        assert_eq!(
+            backend.get_obj("s3://bucket/corrupt").await.unwrap(),
+            b"dbta"
+        );
+
+        let crc32c = S3StorageOptions::from_map(hashmap! {
+            "checksum".to_string() => "crc32c".to_string(),
+        });
+        assert!(matches!(
+            crc32c.validate(),
+            Err(StorageError::InvalidOptions(_))
+        ));
+    }
 }
@@ -0,0 +1,438 @@
+//! The WebHDFS storage backend for tables stored on HDFS, addressed as
+//! `webhdfs://<namenode>:<http port>/<path>`, or `swebhdfs://` for clusters serving WebHDFS over
+//! HTTPS.
+//!
+//! This module is gated behind the "webhdfs" feature.
+//!
+//! Requests are authenticated as described in [webhdfs_options]: either with the user name of
+//! the pseudo authentication of unsecured clusters, or with a delegation token for clusters
+//! secured with Kerberos, e.g. obtained with `hdfs fetchdt`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+
+use chrono::{TimeZone, Utc};
+use futures::Stream;
+use log::debug;
+use reqwest::{Method, StatusCode, Url};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::client::ClientOptions;
+use super::{parse_uri, ObjectMeta, StorageBackend, StorageError, UriError};
+
+/// Storage option keys to use when creating a [WebHdfsBackend].
+/// The same key should be used whether passing a key in the hashmap or setting it as an environment variable.
+pub mod webhdfs_options {
+    /// The user name sent with every request on clusters using pseudo authentication.
+    pub const WEBHDFS_USER: &str = "WEBHDFS_USER";
+    /// A delegation token sent with every request on clusters secured with Kerberos.
+    pub const WEBHDFS_DELEGATION_TOKEN: &str = "WEBHDFS_DELEGATION_TOKEN";
+
+    /// The list of option keys owned by the WebHDFS module.
+    pub const WEBHDFS_OPTS: &[&str] = &[WEBHDFS_USER, WEBHDFS_DELEGATION_TOKEN];
+}
+
+/// A file on HDFS accessed through WebHDFS.
+#[derive(Debug, PartialEq)]
+pub struct WebHdfsObject<'a> {
+    /// Whether WebHDFS is served over HTTPS (`swebhdfs://`).
+    pub secure: bool,
+    /// The host and port of the namenode's HTTP server.
+    pub authority: &'a str,
+    /// The absolute path of the file on HDFS, without the leading separator.
+    pub path: &'a str,
+}
+
+impl<'a> fmt::Display for WebHdfsObject<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scheme = if self.secure { "swebhdfs" } else { "webhdfs" };
+        write!(f, "{}://{}/{}", scheme, self.authority, self.path)
+    }
+}
+
+impl<'a> WebHdfsObject<'a> {
+    /// Parses the part of a WebHDFS URI following the scheme.
+    pub(crate) fn parse(secure: bool, rest: &'a str) -> Result<Self, UriError> {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if authority.is_empty() {
+            return Err(UriError::MissingWebHdfsNamenode);
+        }
+        Ok(Self {
+            secure,
+            authority,
+            path,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileStatus {
+    path_suffix: String,
+    length: i64,
+    modification_time: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileStatusResponse {
+    file_status: FileStatus,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileStatuses {
+    file_status: Vec<FileStatus>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListStatusResponse {
+    file_statuses: FileStatuses,
+}
+
+#[derive(Deserialize)]
+struct BooleanResponse {
+    boolean: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LocationResponse {
+    location: String,
+}
+
+#[derive(Deserialize)]
+struct RemoteException {
+    exception: String,
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RemoteExceptionResponse {
+    remote_exception: RemoteException,
+}
+
+/// A storage backend for HDFS using the WebHDFS REST API.
+///
+/// Renames are atomic and fail if the destination exists, so it is safe for concurrent writers.
+#[derive(Debug)]
+pub struct WebHdfsBackend {
+    client: reqwest::Client,
+    user: Option<String>,
+    delegation_token: Option<String>,
+}
+
+impl WebHdfsBackend {
+    /// Creates a new [`WebHdfsBackend`] configured by the environment.
+    pub fn new() -> Result<Self, StorageError> {
+        Self::new_from_options(&HashMap::new())
+    }
+
+    /// Creates a new [`WebHdfsBackend`] configured by the [webhdfs_options] keys of `options`.
+    /// Options missing from the map are read from the environment.
+    pub fn new_from_options(options: &HashMap<String, String>) -> Result<Self, StorageError> {
+        let option = |key: &str| {
+            options
+                .get(key)
+                .cloned()
+                .or_else(|| std::env::var(key).ok())
+        };
+        let client = ClientOptions::from_map(options).reqwest_client()?;
+        Ok(Self {
+            client,
+            user: option(webhdfs_options::WEBHDFS_USER),
+            delegation_token: option(webhdfs_options::WEBHDFS_DELEGATION_TOKEN),
+        })
+    }
+
+    fn url(
+        &self,
+        obj: &WebHdfsObject,
+        op: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Url, StorageError> {
+        let scheme = if obj.secure { "https" } else { "http" };
+        let mut url = Url::parse(&format!(
+            "{}://{}/webhdfs/v1/{}",
+            scheme, obj.authority, obj.path
+        ))
+        .map_err(|err| StorageError::WebHdfs(format!("Invalid URL for {}: {}", obj, err)))?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("op", op);
+            if let Some(user) = &self.user {
+                query.append_pair("user.name", user);
+            }
+            if let Some(token) = &self.delegation_token {
+                query.append_pair("delegation", token);
+            }
+            for (key, value) in params {
+                query.append_pair(key, value);
+            }
+        }
+        Ok(url)
+    }
+
+    /// Sends the request and maps WebHDFS errors to storage errors.
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Vec<u8>>,
+    ) -> Result<reqwest::Response, StorageError> {
+        let mut request = self.client.request(method, url);
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.bytes().await?;
+        let (exception, message) = match serde_json::from_slice::<RemoteExceptionResponse>(&body) {
+            Ok(response) => (
+                response.remote_exception.exception,
+                response.remote_exception.message,
+            ),
+            Err(_) => (String::new(), String::from_utf8_lossy(&body).into_owned()),
+        };
+        Err(match (status, exception.as_str()) {
+            (StatusCode::NOT_FOUND, _) | (_, "FileNotFoundException") => StorageError::NotFound,
+            (_, "FileAlreadyExistsException") => StorageError::AlreadyExists(message),
+            (StatusCode::UNAUTHORIZED, _)
+            | (StatusCode::FORBIDDEN, _)
+            | (_, "AccessControlException")
+            | (_, "SecurityException") => StorageError::Io {
+                source: std::io::Error::new(std::io::ErrorKind::PermissionDenied, message),
+            },
+            _ => StorageError::WebHdfs(format!("{} {}: {}", status, exception, message)),
+        })
+    }
+
+    async fn send_json<T: serde::de::DeserializeOwned>(
+        &self,
+        method: Method,
+        url: Url,
+    ) -> Result<T, StorageError> {
+        let body = self.send(method, url, None).await?.bytes().await?;
+        serde_json::from_slice(&body)
+            .map_err(|err| StorageError::WebHdfs(format!("Invalid response: {}", err)))
+    }
+
+    async fn rename(
+        &self,
+        src: &WebHdfsObject<'_>,
+        dst: &WebHdfsObject<'_>,
+    ) -> Result<bool, StorageError> {
+        let destination = format!("/{}", dst.path);
+        let url = self.url(src, "RENAME", &[("destination", &destination)])?;
+        let response: BooleanResponse = self.send_json(Method::PUT, url).await?;
+        Ok(response.boolean)
+    }
+
+    async fn delete(&self, obj: &WebHdfsObject<'_>) -> Result<(), StorageError> {
+        let url = self.url(obj, "DELETE", &[])?;
+        let response: BooleanResponse = self.send_json(Method::DELETE, url).await?;
+        if response.boolean {
+            Ok(())
+        } else {
+            Err(StorageError::NotFound)
+        }
+    }
+}
+
+fn object_meta(path: String, status: &FileStatus) -> ObjectMeta {
+    ObjectMeta {
+        path,
+        modified: Utc.timestamp_millis(status.modification_time),
+        size: Some(status.length),
+        e_tag: None,
+        version: None,
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for WebHdfsBackend {
+    async fn head_obj(&self, path: &str) -> Result<ObjectMeta, StorageError> {
+        let obj = parse_uri(path)?.into_webhdfs_object()?;
+        let url = self.url(&obj, "GETFILESTATUS", &[])?;
+        let response: FileStatusResponse = self.send_json(Method::GET, url).await?;
+        Ok(object_meta(path.to_string(), &response.file_status))
+    }
+
+    async fn get_obj(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        debug!("getting WebHDFS object at: {}", path);
+        let obj = parse_uri(path)?.into_webhdfs_object()?;
+        // the namenode redirects to a datanode serving the content
+        let url = self.url(&obj, "OPEN", &[])?;
+        let response = self.send(Method::GET, url, None).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Lists the files and directories in the directory at `path`.
+    async fn list_objs<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ObjectMeta, StorageError>> + Send + 'a>>,
+        StorageError,
+    > {
+        let obj = parse_uri(path)?.into_webhdfs_object()?;
+        let url = self.url(&obj, "LISTSTATUS", &[])?;
+        let response: ListStatusResponse = self.send_json(Method::GET, url).await?;
+        let metas: Vec<_> = response
+            .file_statuses
+            .file_status
+            .iter()
+            .map(|status| {
+                Ok(object_meta(
+                    self.join_path(path, &status.path_suffix),
+                    status,
+                ))
+            })
+            .collect();
+        Ok(Box::pin(futures::stream::iter(metas)))
+    }
+
+    /// Writes the object to a temporary file that is renamed to `path`. An existing object is
+    /// deleted right before the rename, so it is briefly missing.
+    async fn put_obj(&self, path: &str, obj_bytes: &[u8]) -> Result<(), StorageError> {
+        let obj = parse_uri(path)?.into_webhdfs_object()?;
+        let tmp_path = format!("{}.{}.tmp", obj.path, Uuid::new_v4());
+        let tmp = WebHdfsObject {
+            path: &tmp_path,
+            ..obj
+        };
+
+        // ask the namenode for the datanode to upload to instead of following a redirect, which
+        // would send the content twice
+        let url = self.url(
+            &tmp,
+            "CREATE",
+            &[("overwrite", "true"), ("noredirect", "true")],
+        )?;
+        let location: LocationResponse = self.send_json(Method::PUT, url).await?;
+        let location = Url::parse(&location.location)
+            .map_err(|err| StorageError::WebHdfs(format!("Invalid datanode location: {}", err)))?;
+        self.send(Method::PUT, location, Some(obj_bytes.to_vec()))
+            .await?;
+
+        if !self.rename(&tmp, &obj).await? {
+            match self.delete(&obj).await {
+                Ok(()) | Err(StorageError::NotFound) => {}
+                Err(err) => return Err(err),
+            }
+            if !self.rename(&tmp, &obj).await? {
+                let _ = self.delete(&tmp).await;
+                return Err(StorageError::WebHdfs(format!("Failed to write {}", path)));
+            }
+        }
+        Ok(())
+    }
+
+    async fn rename_obj_noreplace(&self, src: &str, dst: &str) -> Result<(), StorageError> {
+        let src_obj = parse_uri(src)?.into_webhdfs_object()?;
+        let dst_obj = parse_uri(dst)?.into_webhdfs_object()?;
+        if self.rename(&src_obj, &dst_obj).await? {
+            return Ok(());
+        }
+        // HDFS only reports that the rename failed, because the destination exists or the
+        // source is missing
+        match self.head_obj(dst).await {
+            Ok(_) => Err(StorageError::AlreadyExists(dst.to_string())),
+            Err(StorageError::NotFound) => Err(StorageError::NotFound),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn delete_obj(&self, path: &str) -> Result<(), StorageError> {
+        let obj = parse_uri(path)?.into_webhdfs_object()?;
+        self.delete(&obj).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_webhdfs_uri() {
+        let uri = parse_uri("webhdfs://namenode:9870/warehouse/table").unwrap();
+        assert_eq!(uri.path(), "warehouse/table");
+        let obj = uri.into_webhdfs_object().unwrap();
+        assert_eq!(
+            obj,
+            WebHdfsObject {
+                secure: false,
+                authority: "namenode:9870",
+                path: "warehouse/table",
+            }
+        );
+        assert_eq!(obj.to_string(), "webhdfs://namenode:9870/warehouse/table");
+
+        let obj = parse_uri("swebhdfs://namenode:9871/t")
+            .unwrap()
+            .into_webhdfs_object()
+            .unwrap();
+        assert!(obj.secure);
+        assert!(parse_uri("webhdfs:///table").is_err());
+    }
+
+    #[test]
+    fn request_urls() {
+        let options = [
+            (
+                webhdfs_options::WEBHDFS_USER.to_string(),
+                "hive".to_string(),
+            ),
+            (
+                webhdfs_options::WEBHDFS_DELEGATION_TOKEN.to_string(),
+                "token".to_string(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let backend = WebHdfsBackend::new_from_options(&options).unwrap();
+        let obj = WebHdfsObject::parse(true, "namenode:9871/dir/my file").unwrap();
+        let url = backend
+            .url(&obj, "RENAME", &[("destination", "/dir/other")])
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://namenode:9871/webhdfs/v1/dir/my%20file?op=RENAME&user.name=hive&delegation=token&destination=%2Fdir%2Fother"
+        );
+    }
+
+    #[test]
+    fn parse_responses() {
+        let response: ListStatusResponse = serde_json::from_str(
+            r#"{"FileStatuses":{"FileStatus":[
+                {"accessTime":0,"blockSize":134217728,"group":"hive","length":512,
+                 "modificationTime":1587968626000,"owner":"hive","pathSuffix":"part-0.parquet",
+                 "permission":"644","replication":3,"type":"FILE"}
+            ]}}"#,
+        )
+        .unwrap();
+        let status = &response.file_statuses.file_status[0];
+        let meta = object_meta("webhdfs://nn/t/part-0.parquet".to_string(), status);
+        assert_eq!(meta.size, Some(512));
+        assert_eq!(meta.modified.timestamp(), 1587968626);
+        assert_eq!(status.path_suffix, "part-0.parquet");
+
+        let exception: RemoteExceptionResponse = serde_json::from_str(
+            r#"{"RemoteException":{"exception":"FileNotFoundException",
+                "javaClassName":"java.io.FileNotFoundException","message":"File does not exist"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            exception.remote_exception.exception,
+            "FileNotFoundException"
+        );
+    }
+}
@@ -16,7 +16,7 @@ use super::{
     DeltaTableError, DeltaTableMetaData,
 };
 use crate::action;
-use crate::delta_config;
+use crate::delta_config::TableProperties;
 
 /// State snapshot currently held by the Delta Table instance.
 #[derive(Default, Debug, Clone)]
@@ -110,7 +110,7 @@ impl DeltaTableState {
     }
 
     /// List of unexpired tombstones (remove actions) representing files removed from table state.
-    /// The retention period is set by `deletedFileRetentionDuration` with default value of 1 week.
+    /// The retention period is set by `delta.deletedFileRetentionDuration` with default value of 1 week.
     pub fn unexpired_tombstones(&self) -> impl Iterator<Item = &action::Remove> {
         let retention_timestamp = Utc::now().timestamp_millis() - self.tombstone_retention_millis;
         self.tombstones
@@ -215,14 +215,11 @@ impl DeltaTableState {
             }
             action::Action::metaData(v) => {
                 let md = DeltaTableMetaData::try_from(v)?;
-                self.tombstone_retention_millis = delta_config::TOMBSTONE_RETENTION
-                    .get_interval_from_metadata(&md)?
-                    .as_millis() as i64;
-                self.log_retention_millis = delta_config::LOG_RETENTION
-                    .get_interval_from_metadata(&md)?
-                    .as_millis() as i64;
-                self.enable_expired_log_cleanup =
-                    delta_config::ENABLE_EXPIRED_LOG_CLEANUP.get_boolean_from_metadata(&md)?;
+                let properties = TableProperties::from_metadata(&md);
+                self.tombstone_retention_millis =
+                    properties.deleted_file_retention_duration.as_millis() as i64;
+                self.log_retention_millis = properties.log_retention_duration.as_millis() as i64;
+                self.enable_expired_log_cleanup = properties.enable_expired_log_cleanup;
                 self.current_metadata = Some(md);
             }
             action::Action::txn(v) => {
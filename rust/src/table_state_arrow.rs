@@ -0,0 +1,405 @@
+//! Conversion of the add actions of a table state into an Arrow record batch, with the partition
+//! values and statistics of the files parsed into the types of their columns.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BooleanArray, Date32Array, DecimalBuilder, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, StringArray, StructArray, TimestampMicrosecondArray,
+    TimestampMillisecondArray,
+};
+use arrow::datatypes::{DataType, Field, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::Value;
+
+use super::action::{ColumnCountStat, ColumnValueStat, Stats};
+use super::schema::{SchemaDataType, SchemaField, COLUMN_MAPPING_PHYSICAL_NAME_KEY};
+use super::table_state::DeltaTableState;
+use super::writer::{decimal_utils, time_utils};
+use super::DeltaTableError;
+
+/// Columns nested in structs, as the path of their names and their values.
+type NestedColumns = Vec<(Vec<String>, ArrayRef)>;
+
+impl DeltaTableState {
+    /// Returns the add actions of the files of the table, one row per file, with the columns:
+    ///
+    /// * `path`, `size_bytes`, `modification_time` and `data_change`, from the action;
+    /// * `partition_values`, the partition values in the types of the partition columns;
+    /// * `num_records`, and `null_count`, `min` and `max` with the statistics of every column
+    ///   that is not a partition column, in the type of the column.
+    ///
+    /// `partition_values`, `null_count`, `min` and `max` are structs nesting a field per
+    /// column like the table schema, or if `flatten` is set separate columns named by the path
+    /// of the column, e.g. `min.address.city`. Statistics of the elements of arrays and maps,
+    /// and of binary columns, are left out. Missing statistics and statistics that cannot be
+    /// parsed, e.g. if they were truncated, are null.
+    pub fn add_actions_table(&self, flatten: bool) -> Result<RecordBatch, DeltaTableError> {
+        let metadata = self.current_metadata().ok_or(DeltaTableError::NoMetadata)?;
+        let files = self.files();
+        let stats: Vec<Option<Stats>> = files
+            .iter()
+            .map(|add| add.get_stats().ok().flatten())
+            .collect();
+
+        let mut columns: Vec<(String, ArrayRef)> = vec![
+            (
+                "path".to_string(),
+                Arc::new(StringArray::from(
+                    files
+                        .iter()
+                        .map(|add| add.path.as_str())
+                        .collect::<Vec<_>>(),
+                )),
+            ),
+            (
+                "size_bytes".to_string(),
+                Arc::new(Int64Array::from(
+                    files.iter().map(|add| add.size).collect::<Vec<_>>(),
+                )),
+            ),
+            (
+                "modification_time".to_string(),
+                Arc::new(TimestampMillisecondArray::from(
+                    files
+                        .iter()
+                        .map(|add| add.modification_time)
+                        .collect::<Vec<_>>(),
+                )),
+            ),
+            (
+                "data_change".to_string(),
+                Arc::new(BooleanArray::from(
+                    files.iter().map(|add| add.data_change).collect::<Vec<_>>(),
+                )),
+            ),
+        ];
+
+        let fields = metadata.schema.get_fields();
+        let mut partition_values = NestedColumns::new();
+        for name in &metadata.partition_columns {
+            let field = match fields.iter().find(|field| field.get_name() == name) {
+                Some(field) => field,
+                None => continue,
+            };
+            let key = stats_name(field);
+            let values: Vec<Option<Value>> = files
+                .iter()
+                .map(|add| {
+                    add.partition_values
+                        .get(key)
+                        .cloned()
+                        .flatten()
+                        .filter(|value| !value.is_empty())
+                        .map(Value::String)
+                })
+                .collect();
+            let values: Vec<Option<&Value>> = values.iter().map(Option::as_ref).collect();
+            if let Some(array) = typed_array(field.get_type(), &values, false) {
+                partition_values.push((vec![name.clone()], array));
+            }
+        }
+        push_nested(&mut columns, "partition_values", partition_values, flatten);
+
+        columns.push((
+            "num_records".to_string(),
+            Arc::new(Int64Array::from(
+                stats
+                    .iter()
+                    .map(|stats| stats.as_ref().map(|stats| stats.num_records))
+                    .collect::<Vec<_>>(),
+            )),
+        ));
+
+        let mut leaves = Vec::new();
+        let data_fields: Vec<&SchemaField> = fields
+            .iter()
+            .filter(|field| {
+                !metadata
+                    .partition_columns
+                    .iter()
+                    .any(|name| name == field.get_name())
+            })
+            .collect();
+        collect_leaves(&data_fields, &[], &[], &mut leaves);
+        let mut null_counts = NestedColumns::new();
+        let mut min_values = NestedColumns::new();
+        let mut max_values = NestedColumns::new();
+        for leaf in &leaves {
+            let path: Vec<String> = leaf.names.iter().map(|name| name.to_string()).collect();
+            null_counts.push((
+                path.clone(),
+                Arc::new(Int64Array::from(
+                    stats
+                        .iter()
+                        .map(|stats| count_stat(&stats.as_ref()?.null_count, &leaf.stats_names))
+                        .collect::<Vec<_>>(),
+                )),
+            ));
+            for (bounds, upper) in [(&mut min_values, false), (&mut max_values, true)] {
+                let values: Vec<Option<&Value>> = stats
+                    .iter()
+                    .map(|stats| {
+                        let stats = stats.as_ref()?;
+                        let values = if upper {
+                            &stats.max_values
+                        } else {
+                            &stats.min_values
+                        };
+                        value_stat(values, &leaf.stats_names)
+                    })
+                    .collect();
+                if let Some(array) = typed_array(leaf.data_type, &values, upper) {
+                    bounds.push((path.clone(), array));
+                }
+            }
+        }
+        push_nested(&mut columns, "null_count", null_counts, flatten);
+        push_nested(&mut columns, "min", min_values, flatten);
+        push_nested(&mut columns, "max", max_values, flatten);
+
+        RecordBatch::try_from_iter(columns).map_err(|source| DeltaTableError::ArrowError { source })
+    }
+}
+
+/// A column nested in structs, but not in arrays or maps, which has statistics.
+struct LeafColumn<'a> {
+    names: Vec<&'a str>,
+    stats_names: Vec<&'a str>,
+    data_type: &'a SchemaDataType,
+}
+
+fn collect_leaves<'a>(
+    fields: &[&'a SchemaField],
+    names: &[&'a str],
+    stats_names: &[&'a str],
+    leaves: &mut Vec<LeafColumn<'a>>,
+) {
+    for &field in fields {
+        let mut names = names.to_vec();
+        names.push(field.get_name());
+        let mut stats_names = stats_names.to_vec();
+        stats_names.push(stats_name(field));
+        match field.get_type() {
+            SchemaDataType::r#struct(nested) => {
+                let nested: Vec<&SchemaField> = nested.get_fields().iter().collect();
+                collect_leaves(&nested, &names, &stats_names, leaves);
+            }
+            SchemaDataType::primitive(_) => leaves.push(LeafColumn {
+                names,
+                stats_names,
+                data_type: field.get_type(),
+            }),
+            SchemaDataType::array(_) | SchemaDataType::map(_) => {}
+        }
+    }
+}
+
+/// The name of the column in statistics and partition values, which is its physical name if
+/// column mapping is enabled.
+pub(crate) fn stats_name(field: &SchemaField) -> &str {
+    field
+        .get_metadata()
+        .get(COLUMN_MAPPING_PHYSICAL_NAME_KEY)
+        .map(String::as_str)
+        .unwrap_or_else(|| field.get_name())
+}
+
+pub(crate) fn value_stat<'a>(
+    stats: &'a HashMap<String, ColumnValueStat>,
+    path: &[&str],
+) -> Option<&'a Value> {
+    let (name, parents) = path.split_last()?;
+    let mut stats = stats;
+    for parent in parents {
+        stats = stats.get(*parent)?.as_column()?;
+    }
+    stats.get(*name)?.as_value()
+}
+
+pub(crate) fn count_stat(stats: &HashMap<String, ColumnCountStat>, path: &[&str]) -> Option<i64> {
+    let (name, parents) = path.split_last()?;
+    let mut stats = stats;
+    for parent in parents {
+        stats = stats.get(*parent)?.as_column()?;
+    }
+    stats.get(*name)?.as_value()
+}
+
+/// Converts statistics or partition values to an array of the type of their column, with nulls
+/// for values not matching the type. Decimals with more digits than the scale of the column are
+/// rounded down, or up if `upper` is set, so that they remain bounds. Returns None for types
+/// without statistics.
+fn typed_array(
+    data_type: &SchemaDataType,
+    values: &[Option<&Value>],
+    upper: bool,
+) -> Option<ArrayRef> {
+    let integer = |value: &Value| {
+        value
+            .as_i64()
+            .or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+    };
+    let float = |value: &Value| {
+        value
+            .as_f64()
+            .or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+    };
+    let array: ArrayRef = match DataType::try_from(data_type).ok()? {
+        DataType::Utf8 => Arc::new(StringArray::from(
+            values
+                .iter()
+                .map(|value| value.and_then(Value::as_str))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Int64 => Arc::new(Int64Array::from(
+            values
+                .iter()
+                .map(|value| value.and_then(integer))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Int32 => Arc::new(Int32Array::from(
+            values
+                .iter()
+                .map(|value| value.and_then(integer).and_then(|v| i32::try_from(v).ok()))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Int16 => Arc::new(Int16Array::from(
+            values
+                .iter()
+                .map(|value| value.and_then(integer).and_then(|v| i16::try_from(v).ok()))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Int8 => Arc::new(Int8Array::from(
+            values
+                .iter()
+                .map(|value| value.and_then(integer).and_then(|v| i8::try_from(v).ok()))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Float64 => Arc::new(Float64Array::from(
+            values
+                .iter()
+                .map(|value| value.and_then(float))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Float32 => Arc::new(Float32Array::from(
+            values
+                .iter()
+                .map(|value| value.and_then(float).map(|v| v as f32))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Boolean => Arc::new(BooleanArray::from(
+            values
+                .iter()
+                .map(|value| {
+                    value.and_then(|value| {
+                        value
+                            .as_bool()
+                            .or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+                    })
+                })
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Date32 => Arc::new(Date32Array::from(
+            values
+                .iter()
+                .map(|value| value.and_then(Value::as_str).and_then(days_since_epoch))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Timestamp(TimeUnit::Microsecond, None) => {
+            Arc::new(TimestampMicrosecondArray::from(
+                values
+                    .iter()
+                    .map(|value| value.and_then(Value::as_str).and_then(timestamp_micros))
+                    .collect::<Vec<_>>(),
+            ))
+        }
+        DataType::Decimal(precision, scale) => {
+            let mut builder = DecimalBuilder::new(values.len(), precision, scale);
+            for value in values {
+                match value.and_then(|value| {
+                    decimal_utils::decimal_from_stats_value(value, precision, scale, upper)
+                }) {
+                    Some(value) => builder.append_value(value).ok()?,
+                    None => builder.append_null().ok()?,
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        _ => return None,
+    };
+    Some(array)
+}
+
+pub(crate) fn days_since_epoch(value: &str) -> Option<i32> {
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    i32::try_from((date - NaiveDate::from_ymd(1970, 1, 1)).num_days()).ok()
+}
+
+/// Parses timestamps of statistics, in RFC 3339, or of partition values, like
+/// `2021-01-01 12:00:00.123456`.
+pub(crate) fn timestamp_micros(value: &str) -> Option<i64> {
+    time_utils::timestamp_micros_from_stats_string(value)
+        .ok()
+        .or_else(|| {
+            let timestamp = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").ok()?;
+            Some(timestamp.timestamp() * 1_000_000 + timestamp.timestamp_subsec_micros() as i64)
+        })
+}
+
+/// Adds `nested` as a struct column named `name`, or if `flatten` is set as columns named by
+/// their path, e.g. `name.a.b`. Nothing is added if there are no nested columns.
+fn push_nested(
+    columns: &mut Vec<(String, ArrayRef)>,
+    name: &str,
+    nested: NestedColumns,
+    flatten: bool,
+) {
+    if nested.is_empty() {
+        return;
+    }
+    if flatten {
+        columns.extend(
+            nested
+                .into_iter()
+                .map(|(path, array)| (format!("{}.{}", name, path.join(".")), array)),
+        );
+    } else {
+        columns.push((name.to_string(), struct_array(nest(nested))));
+    }
+}
+
+/// Groups the columns by the first name of their paths into structs. The columns of a struct
+/// must be adjacent, as they are when collected in the order of the schema.
+fn nest(columns: NestedColumns) -> Vec<(String, ArrayRef)> {
+    let mut groups: Vec<(String, NestedColumns)> = Vec::new();
+    for (mut path, array) in columns {
+        let name = path.remove(0);
+        match groups.last_mut() {
+            Some((last, children)) if *last == name => children.push((path, array)),
+            _ => groups.push((name, vec![(path, array)])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(name, mut children)| {
+            if children.len() == 1 && children[0].0.is_empty() {
+                (name, children.remove(0).1)
+            } else {
+                (name, struct_array(nest(children)))
+            }
+        })
+        .collect()
+}
+
+fn struct_array(fields: Vec<(String, ArrayRef)>) -> ArrayRef {
+    Arc::new(StructArray::from(
+        fields
+            .into_iter()
+            .map(|(name, array)| (Field::new(&name, array.data_type().clone(), true), array))
+            .collect::<Vec<_>>(),
+    ))
+}
@@ -0,0 +1,564 @@
+//! A minimal writer and reader of Avro object container files, covering the schemas of Iceberg
+//! manifests and manifest lists. Fields carry the Iceberg `field-id` of their column.
+
+use serde_json::{json, Value};
+use std::io::{Cursor, Read};
+
+use super::UniformError;
+
+const MAGIC: &[u8] = b"Obj\x01";
+const SYNC_MARKER_SIZE: usize = 16;
+
+/// Schema of an Avro value
+#[derive(Clone, Debug, PartialEq)]
+pub enum AvroSchema {
+    /// A boolean
+    Boolean,
+    /// A 32 bit integer
+    Int,
+    /// A 64 bit integer
+    Long,
+    /// A single precision float
+    Float,
+    /// A double precision float
+    Double,
+    /// A sequence of bytes
+    Bytes,
+    /// A UTF-8 string
+    String,
+    /// Days since the Unix epoch, stored as an int
+    Date,
+    /// Microseconds since the Unix epoch, stored as a long
+    TimestampMicros {
+        /// Whether the timestamp is adjusted to UTC
+        adjust_to_utc: bool,
+    },
+    /// A union of null and the wrapped schema
+    Optional(Box<AvroSchema>),
+    /// A record with the given fields
+    Record {
+        /// Name of the record
+        name: String,
+        /// Fields of the record in order
+        fields: Vec<AvroField>,
+    },
+    /// An array
+    Array {
+        /// Field id of the elements
+        element_id: i32,
+        /// Schema of the elements
+        items: Box<AvroSchema>,
+    },
+    /// A map with non-string keys, stored as an array of key/value records
+    Map {
+        /// Field id of the keys
+        key_id: i32,
+        /// Schema of the keys
+        key: Box<AvroSchema>,
+        /// Field id of the values
+        value_id: i32,
+        /// Schema of the values
+        value: Box<AvroSchema>,
+    },
+}
+
+/// A field of an Avro record
+#[derive(Clone, Debug, PartialEq)]
+pub struct AvroField {
+    /// Name of the field
+    pub name: String,
+    /// Iceberg field id of the field
+    pub field_id: i32,
+    /// Schema of the field
+    pub schema: AvroSchema,
+}
+
+impl AvroField {
+    /// Creates a field
+    pub fn new(name: &str, field_id: i32, schema: AvroSchema) -> Self {
+        Self {
+            name: name.to_string(),
+            field_id,
+            schema,
+        }
+    }
+}
+
+/// An Avro value
+#[derive(Clone, Debug, PartialEq)]
+pub enum AvroValue {
+    /// The null of an optional value
+    Null,
+    /// A boolean
+    Boolean(bool),
+    /// An int or a date
+    Int(i32),
+    /// A long or a timestamp
+    Long(i64),
+    /// A float
+    Float(f32),
+    /// A double
+    Double(f64),
+    /// Bytes
+    Bytes(Vec<u8>),
+    /// A string
+    String(String),
+    /// The values of the fields of a record
+    Record(Vec<AvroValue>),
+    /// The elements of an array
+    Array(Vec<AvroValue>),
+    /// The entries of a map
+    Map(Vec<(AvroValue, AvroValue)>),
+}
+
+impl AvroValue {
+    /// Returns the long of a long value
+    pub fn as_long(&self) -> Option<i64> {
+        match self {
+            AvroValue::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the int of an int value
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            AvroValue::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the string of a string value
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AvroValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the field at `index` of a record value
+    pub fn field(&self, index: usize) -> Option<&AvroValue> {
+        match self {
+            AvroValue::Record(fields) => fields.get(index),
+            _ => None,
+        }
+    }
+}
+
+impl AvroSchema {
+    /// Returns the JSON representation of the schema, including the Iceberg field ids
+    pub fn to_json(&self) -> Value {
+        match self {
+            AvroSchema::Boolean => json!("boolean"),
+            AvroSchema::Int => json!("int"),
+            AvroSchema::Long => json!("long"),
+            AvroSchema::Float => json!("float"),
+            AvroSchema::Double => json!("double"),
+            AvroSchema::Bytes => json!("bytes"),
+            AvroSchema::String => json!("string"),
+            AvroSchema::Date => json!({"type": "int", "logicalType": "date"}),
+            AvroSchema::TimestampMicros { adjust_to_utc } => json!({
+                "type": "long",
+                "logicalType": "timestamp-micros",
+                "adjust-to-utc": adjust_to_utc,
+            }),
+            AvroSchema::Optional(schema) => json!(["null", schema.to_json()]),
+            AvroSchema::Record { name, fields } => json!({
+                "type": "record",
+                "name": name,
+                "fields": fields.iter().map(|field| {
+                    let mut json = json!({
+                        "name": field.name,
+                        "type": field.schema.to_json(),
+                        "field-id": field.field_id,
+                    });
+                    if matches!(field.schema, AvroSchema::Optional(_)) {
+                        json["default"] = Value::Null;
+                    }
+                    json
+                }).collect::<Vec<_>>(),
+            }),
+            AvroSchema::Array { element_id, items } => json!({
+                "type": "array",
+                "items": items.to_json(),
+                "element-id": element_id,
+            }),
+            AvroSchema::Map {
+                key_id,
+                key,
+                value_id,
+                value,
+            } => json!({
+                "type": "array",
+                "logicalType": "map",
+                "items": {
+                    "type": "record",
+                    "name": format!("k{}_v{}", key_id, value_id),
+                    "fields": [
+                        {"name": "key", "type": key.to_json(), "field-id": key_id},
+                        {"name": "value", "type": value.to_json(), "field-id": value_id},
+                    ],
+                },
+            }),
+        }
+    }
+}
+
+/// Writes the records as an uncompressed Avro object container file with the given metadata.
+pub fn write_container(
+    schema: &AvroSchema,
+    metadata: &[(&str, String)],
+    records: &[AvroValue],
+) -> Result<Vec<u8>, UniformError> {
+    let mut out = MAGIC.to_vec();
+
+    let mut entries = vec![
+        ("avro.schema", schema.to_json().to_string()),
+        ("avro.codec", "null".to_string()),
+    ];
+    entries.extend(metadata.iter().cloned());
+    encode_long(entries.len() as i64, &mut out);
+    for (key, value) in entries {
+        encode_bytes(key.as_bytes(), &mut out);
+        encode_bytes(value.as_bytes(), &mut out);
+    }
+    encode_long(0, &mut out);
+
+    let sync_marker = *uuid::Uuid::new_v4().as_bytes();
+    out.extend_from_slice(&sync_marker);
+
+    if !records.is_empty() {
+        let mut block = vec![];
+        for record in records {
+            encode(schema, record, &mut block)?;
+        }
+        encode_long(records.len() as i64, &mut out);
+        encode_long(block.len() as i64, &mut out);
+        out.extend(block);
+        out.extend_from_slice(&sync_marker);
+    }
+    Ok(out)
+}
+
+/// Reads the records of an uncompressed Avro object container file written with `schema`.
+pub fn read_container(schema: &AvroSchema, bytes: &[u8]) -> Result<Vec<AvroValue>, UniformError> {
+    let mut reader = Cursor::new(bytes);
+    let mut magic = [0u8; 4];
+    read_exact(&mut reader, &mut magic)?;
+    if magic != MAGIC {
+        return Err(invalid("not an Avro object container file"));
+    }
+
+    loop {
+        let count = decode_block_count(&mut reader)?;
+        if count == 0 {
+            break;
+        }
+        for _ in 0..count {
+            let key = String::from_utf8(decode_bytes(&mut reader)?)
+                .map_err(|_| invalid("metadata key is not UTF-8"))?;
+            let value = decode_bytes(&mut reader)?;
+            if key == "avro.codec" && value != b"null" {
+                return Err(invalid("only uncompressed files are supported"));
+            }
+        }
+    }
+    let mut sync_marker = [0u8; SYNC_MARKER_SIZE];
+    read_exact(&mut reader, &mut sync_marker)?;
+
+    let mut records = vec![];
+    while (reader.position() as usize) < bytes.len() {
+        let count = decode_long(&mut reader)?;
+        let _size = decode_long(&mut reader)?;
+        for _ in 0..count {
+            records.push(decode(schema, &mut reader)?);
+        }
+        let mut marker = [0u8; SYNC_MARKER_SIZE];
+        read_exact(&mut reader, &mut marker)?;
+        if marker != sync_marker {
+            return Err(invalid("sync marker does not match"));
+        }
+    }
+    Ok(records)
+}
+
+fn invalid(msg: &str) -> UniformError {
+    UniformError::InvalidAvro(msg.to_string())
+}
+
+fn encode(schema: &AvroSchema, value: &AvroValue, out: &mut Vec<u8>) -> Result<(), UniformError> {
+    match (schema, value) {
+        (AvroSchema::Boolean, AvroValue::Boolean(v)) => out.push(*v as u8),
+        (AvroSchema::Int, AvroValue::Int(v)) | (AvroSchema::Date, AvroValue::Int(v)) => {
+            encode_long(*v as i64, out)
+        }
+        (AvroSchema::Long, AvroValue::Long(v))
+        | (AvroSchema::TimestampMicros { .. }, AvroValue::Long(v)) => encode_long(*v, out),
+        (AvroSchema::Float, AvroValue::Float(v)) => out.extend_from_slice(&v.to_le_bytes()),
+        (AvroSchema::Double, AvroValue::Double(v)) => out.extend_from_slice(&v.to_le_bytes()),
+        (AvroSchema::Bytes, AvroValue::Bytes(v)) => encode_bytes(v, out),
+        (AvroSchema::String, AvroValue::String(v)) => encode_bytes(v.as_bytes(), out),
+        (AvroSchema::Optional(_), AvroValue::Null) => encode_long(0, out),
+        (AvroSchema::Optional(schema), value) => {
+            encode_long(1, out);
+            encode(schema, value, out)?;
+        }
+        (AvroSchema::Record { fields, .. }, AvroValue::Record(values))
+            if fields.len() == values.len() =>
+        {
+            for (field, value) in fields.iter().zip(values) {
+                encode(&field.schema, value, out)?;
+            }
+        }
+        (AvroSchema::Array { items, .. }, AvroValue::Array(values)) => {
+            if !values.is_empty() {
+                encode_long(values.len() as i64, out);
+                for value in values {
+                    encode(items, value, out)?;
+                }
+            }
+            encode_long(0, out);
+        }
+        (AvroSchema::Map { key, value, .. }, AvroValue::Map(entries)) => {
+            if !entries.is_empty() {
+                encode_long(entries.len() as i64, out);
+                for (k, v) in entries {
+                    encode(key, k, out)?;
+                    encode(value, v, out)?;
+                }
+            }
+            encode_long(0, out);
+        }
+        (schema, value) => {
+            return Err(UniformError::InvalidAvro(format!(
+                "value {:?} does not match the schema {}",
+                value,
+                schema.to_json()
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn decode(schema: &AvroSchema, reader: &mut Cursor<&[u8]>) -> Result<AvroValue, UniformError> {
+    Ok(match schema {
+        AvroSchema::Boolean => {
+            let mut byte = [0u8; 1];
+            read_exact(reader, &mut byte)?;
+            AvroValue::Boolean(byte[0] != 0)
+        }
+        AvroSchema::Int | AvroSchema::Date => AvroValue::Int(decode_long(reader)? as i32),
+        AvroSchema::Long | AvroSchema::TimestampMicros { .. } => {
+            AvroValue::Long(decode_long(reader)?)
+        }
+        AvroSchema::Float => {
+            let mut bytes = [0u8; 4];
+            read_exact(reader, &mut bytes)?;
+            AvroValue::Float(f32::from_le_bytes(bytes))
+        }
+        AvroSchema::Double => {
+            let mut bytes = [0u8; 8];
+            read_exact(reader, &mut bytes)?;
+            AvroValue::Double(f64::from_le_bytes(bytes))
+        }
+        AvroSchema::Bytes => AvroValue::Bytes(decode_bytes(reader)?),
+        AvroSchema::String => AvroValue::String(
+            String::from_utf8(decode_bytes(reader)?).map_err(|_| invalid("string is not UTF-8"))?,
+        ),
+        AvroSchema::Optional(schema) => match decode_long(reader)? {
+            0 => AvroValue::Null,
+            1 => decode(schema, reader)?,
+            _ => return Err(invalid("union branch out of range")),
+        },
+        AvroSchema::Record { fields, .. } => AvroValue::Record(
+            fields
+                .iter()
+                .map(|field| decode(&field.schema, reader))
+                .collect::<Result<_, _>>()?,
+        ),
+        AvroSchema::Array { items, .. } => {
+            let mut values = vec![];
+            loop {
+                let count = decode_block_count(reader)?;
+                if count == 0 {
+                    break;
+                }
+                for _ in 0..count {
+                    values.push(decode(items, reader)?);
+                }
+            }
+            AvroValue::Array(values)
+        }
+        AvroSchema::Map { key, value, .. } => {
+            let mut entries = vec![];
+            loop {
+                let count = decode_block_count(reader)?;
+                if count == 0 {
+                    break;
+                }
+                for _ in 0..count {
+                    entries.push((decode(key, reader)?, decode(value, reader)?));
+                }
+            }
+            AvroValue::Map(entries)
+        }
+    })
+}
+
+fn encode_long(value: i64, out: &mut Vec<u8>) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    encode_long(bytes.len() as i64, out);
+    out.extend_from_slice(bytes);
+}
+
+fn decode_long(reader: &mut Cursor<&[u8]>) -> Result<i64, UniformError> {
+    let mut n: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        read_exact(reader, &mut byte)?;
+        n |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+        if shift > 63 {
+            return Err(invalid("variable length integer is too long"));
+        }
+    }
+    Ok(((n >> 1) as i64) ^ -((n & 1) as i64))
+}
+
+/// Reads the item count of an array or map block. A negative count is followed by the size of
+/// the block in bytes.
+fn decode_block_count(reader: &mut Cursor<&[u8]>) -> Result<i64, UniformError> {
+    let count = decode_long(reader)?;
+    if count < 0 {
+        let _size = decode_long(reader)?;
+        return Ok(-count);
+    }
+    Ok(count)
+}
+
+fn decode_bytes(reader: &mut Cursor<&[u8]>) -> Result<Vec<u8>, UniformError> {
+    let len = decode_long(reader)?;
+    if len < 0 || len as u64 > reader.get_ref().len() as u64 - reader.position() {
+        return Err(invalid("invalid length of bytes"));
+    }
+    let mut bytes = vec![0u8; len as usize];
+    read_exact(reader, &mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_exact(reader: &mut Cursor<&[u8]>, buf: &mut [u8]) -> Result<(), UniformError> {
+    reader
+        .read_exact(buf)
+        .map_err(|_| invalid("unexpected end of file"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn container_roundtrip() {
+        let schema = AvroSchema::Record {
+            name: "r1".to_string(),
+            fields: vec![
+                AvroField::new("path", 1, AvroSchema::String),
+                AvroField::new("count", 2, AvroSchema::Long),
+                AvroField::new("day", 3, AvroSchema::Optional(Box::new(AvroSchema::Date))),
+                AvroField::new(
+                    "bounds",
+                    4,
+                    AvroSchema::Optional(Box::new(AvroSchema::Map {
+                        key_id: 5,
+                        key: Box::new(AvroSchema::Int),
+                        value_id: 6,
+                        value: Box::new(AvroSchema::Bytes),
+                    })),
+                ),
+                AvroField::new(
+                    "values",
+                    7,
+                    AvroSchema::Array {
+                        element_id: 8,
+                        items: Box::new(AvroSchema::Double),
+                    },
+                ),
+            ],
+        };
+        let records = vec![
+            AvroValue::Record(vec![
+                AvroValue::String("a.parquet".to_string()),
+                AvroValue::Long(-1234567890123),
+                AvroValue::Int(18628),
+                AvroValue::Map(vec![(AvroValue::Int(1), AvroValue::Bytes(vec![0, 255]))]),
+                AvroValue::Array(vec![AvroValue::Double(1.5), AvroValue::Double(-2.0)]),
+            ]),
+            AvroValue::Record(vec![
+                AvroValue::String("b.parquet".to_string()),
+                AvroValue::Long(0),
+                AvroValue::Null,
+                AvroValue::Null,
+                AvroValue::Array(vec![]),
+            ]),
+        ];
+
+        let bytes =
+            write_container(&schema, &[("format-version", "1".to_string())], &records).unwrap();
+        assert_eq!(read_container(&schema, &bytes).unwrap(), records);
+
+        let empty = write_container(&schema, &[], &[]).unwrap();
+        assert!(read_container(&schema, &empty).unwrap().is_empty());
+    }
+
+    #[test]
+    fn schema_json_has_field_ids() {
+        let schema = AvroSchema::Record {
+            name: "r2".to_string(),
+            fields: vec![AvroField::new(
+                "value_counts",
+                109,
+                AvroSchema::Optional(Box::new(AvroSchema::Map {
+                    key_id: 119,
+                    key: Box::new(AvroSchema::Int),
+                    value_id: 120,
+                    value: Box::new(AvroSchema::Long),
+                })),
+            )],
+        };
+        assert_eq!(
+            schema.to_json(),
+            json!({
+                "type": "record",
+                "name": "r2",
+                "fields": [{
+                    "name": "value_counts",
+                    "type": ["null", {
+                        "type": "array",
+                        "logicalType": "map",
+                        "items": {
+                            "type": "record",
+                            "name": "k119_v120",
+                            "fields": [
+                                {"name": "key", "type": "int", "field-id": 119},
+                                {"name": "value", "type": "long", "field-id": 120},
+                            ],
+                        },
+                    }],
+                    "field-id": 109,
+                    "default": null,
+                }],
+            })
+        );
+    }
+}
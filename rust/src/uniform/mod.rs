@@ -0,0 +1,1118 @@
+//! Experimental export of the current snapshot of a delta table as Apache Iceberg metadata, like
+//! Delta UniForm. Iceberg readers can then read the table from the exported metadata, which
+//! references the existing parquet files instead of copying them.
+//!
+//! The metadata is written in Iceberg format version 1. Every export adds a snapshot to the
+//! metadata of the previous export, keeping the Iceberg history of the table.
+
+mod avro;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+use self::avro::{read_container, write_container, AvroField, AvroSchema, AvroValue};
+use super::action::{Add, Stats};
+use super::schema::{SchemaDataType, SchemaField, COLUMN_MAPPING_PHYSICAL_NAME_KEY};
+use super::storage::{get_backend_for_uri, normalize_table_uri, StorageBackend, StorageError};
+use super::writer::time_utils;
+use super::{DeltaTable, DeltaTableError, DeltaTableMetaData};
+use crate::delta_config::TableProperties;
+
+/// Error returned when exporting Iceberg metadata fails.
+#[derive(thiserror::Error, Debug)]
+pub enum UniformError {
+    /// Error returned for tables with a column mapping mode Iceberg readers cannot resolve.
+    #[error("Column mapping mode '{0}' is not supported by the Iceberg export")]
+    UnsupportedColumnMapping(String),
+    /// Error returned for tables which may use deletion vectors.
+    #[error("Tables with reader version {0} may use deletion vectors, which are not supported by the Iceberg export")]
+    UnsupportedReaderVersion(i32),
+    /// Error returned for columns of a data type Iceberg does not have.
+    #[error("Data type {0} is not supported by the Iceberg export")]
+    UnsupportedType(String),
+    /// Error returned for partition columns of a type that cannot be an Iceberg partition.
+    #[error(
+        "Partition column {column} of type {data_type} is not supported by the Iceberg export"
+    )]
+    UnsupportedPartitionType {
+        /// Name of the partition column
+        column: String,
+        /// Iceberg type of the partition column
+        data_type: String,
+    },
+    /// Error returned when a partition value cannot be parsed as its type.
+    #[error("Invalid value '{value}' of partition column {column}")]
+    InvalidPartitionValue {
+        /// Name of the partition column
+        column: String,
+        /// The partition value
+        value: String,
+    },
+    /// Error returned for files without statistics, as Iceberg requires their row count.
+    #[error("The file {0} has no statistics, which are required for the Iceberg export")]
+    MissingStatistics(String),
+    /// Error returned when the metadata of a previous export cannot be read.
+    #[error("Invalid Iceberg metadata: {0}")]
+    InvalidMetadata(String),
+    /// Error returned when a manifest or manifest list cannot be written or read.
+    #[error("Invalid Avro file: {0}")]
+    InvalidAvro(String),
+    /// Passthrough error returned when calling DeltaTable.
+    #[error("DeltaTableError: {source}")]
+    DeltaTable {
+        /// The source DeltaTableError.
+        #[from]
+        source: DeltaTableError,
+    },
+    /// Passthrough error returned when calling StorageBackend.
+    #[error("StorageError: {source}")]
+    Storage {
+        /// The source StorageError.
+        #[from]
+        source: StorageError,
+    },
+    /// Passthrough error returned by serde_json.
+    #[error("serde_json::Error: {source}")]
+    JSONSerialization {
+        /// The source serde_json::Error.
+        #[from]
+        source: serde_json::Error,
+    },
+}
+
+/// Name of the file holding the version of the latest metadata file
+pub const VERSION_HINT_FILE: &str = "version-hint.text";
+/// Table property of the Iceberg metadata recording the exported delta version
+pub const DELTA_VERSION_PROPERTY: &str = "delta.version";
+/// Table property of the Iceberg metadata mapping the column names of the data files to ids
+pub const NAME_MAPPING_PROPERTY: &str = "schema.name-mapping.default";
+
+const FORMAT_VERSION: i32 = 1;
+const PARTITION_FIELD_ID_START: i32 = 1000;
+const DEFAULT_BLOCK_SIZE: i64 = 64 * 1024 * 1024;
+const STATUS_EXISTING: i32 = 0;
+const STATUS_ADDED: i32 = 1;
+const STATUS_DELETED: i32 = 2;
+// Delta truncates string statistics, longer strings are no exact bounds
+const MAX_STRING_BOUND_LENGTH: usize = 32;
+
+/// Exports the loaded snapshot of `table` as Iceberg metadata to `target_metadata_dir`, usually
+/// the `metadata` directory of the table. If the directory holds the metadata of a previous
+/// export, a new snapshot is added to it, keeping the files of earlier exports and marking the
+/// data files that were exported before as existing. Nothing is written if the loaded version was
+/// exported already. Returns the URI of the metadata file of the export.
+///
+/// Tables with column mapping in id mode, tables that may use deletion vectors and files without
+/// statistics are not supported.
+pub async fn export_iceberg_metadata(
+    table: &DeltaTable,
+    target_metadata_dir: &str,
+) -> Result<String, UniformError> {
+    let metadata = table.get_metadata()?;
+    check_supported(table, metadata)?;
+
+    let target_dir = normalize_table_uri(target_metadata_dir)?;
+    let storage = get_backend_for_uri(&target_dir)?;
+    let previous = PreviousExport::load(storage.as_ref(), &target_dir).await?;
+    if let Some(previous) = &previous {
+        if previous.metadata["properties"][DELTA_VERSION_PROPERTY].as_str()
+            == Some(table.version.to_string().as_str())
+        {
+            return Ok(previous.uri.clone());
+        }
+    }
+    let previous_metadata = previous.as_ref().map(|p| &p.metadata);
+
+    // schema and partition spec, reusing those of the previous export if they did not change
+    let mut builder = SchemaBuilder::new(previous_metadata);
+    let (schema_type, name_mapping) = builder.struct_type(metadata.schema.get_fields(), "")?;
+    let schemas = previous_metadata.map_or_else(Vec::new, |m| array(&m["schemas"]));
+    let schema_id = find_or_next_id(&schemas, "schema-id", |schema| {
+        schema["fields"] == schema_type["fields"]
+    });
+    let mut schema = schema_type;
+    schema["schema-id"] = json!(schema_id);
+
+    let spec_fields = partition_spec_fields(&metadata.partition_columns, &schema)?;
+    let specs = previous_metadata.map_or_else(Vec::new, |m| array(&m["partition-specs"]));
+    let spec_id = find_or_next_id(&specs, "spec-id", |spec| spec["fields"] == spec_fields);
+    let partition_schema = partition_avro_schema(&spec_fields, &schema)?;
+    let partition_types = partition_types(&spec_fields, &schema)?;
+
+    let snapshot_id = new_snapshot_id(previous_metadata);
+    let previous_files = previous
+        .as_ref()
+        .map_or_else(HashMap::new, |p| p.files.clone());
+    let stats_columns = stats_columns(metadata.schema.get_fields(), &schema);
+
+    // manifest of all current data files
+    let mut entries = vec![];
+    let mut summary = SnapshotSummary::default();
+    let mut partition_summaries = vec![PartitionSummary::default(); partition_types.len()];
+    let mut current_files = HashSet::new();
+    for (add, stats) in table.get_state().files().iter().zip(table.get_stats()) {
+        let stats = stats?.ok_or_else(|| UniformError::MissingStatistics(add.path.clone()))?;
+        let uri = table.storage.join_path(&table.table_uri, &add.path);
+
+        let partition = metadata
+            .partition_columns
+            .iter()
+            .zip(&partition_types)
+            .map(|(column, data_type)| {
+                partition_value(column, data_type, add.partition_values.get(column))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for (partition_summary, value) in partition_summaries.iter_mut().zip(&partition) {
+            partition_summary.add(value);
+        }
+
+        let (status, added_snapshot_id) = match previous_files.get(&uri) {
+            Some(id) => {
+                summary.existing_files += 1;
+                summary.existing_rows += stats.num_records;
+                (STATUS_EXISTING, *id)
+            }
+            None => {
+                summary.added_files += 1;
+                summary.added_rows += stats.num_records;
+                (STATUS_ADDED, snapshot_id)
+            }
+        };
+        entries.push(AvroValue::Record(vec![
+            AvroValue::Int(status),
+            AvroValue::Long(added_snapshot_id),
+            data_file(add, uri.clone(), partition, &stats, &stats_columns),
+        ]));
+        current_files.insert(uri);
+    }
+    summary.deleted_files = previous_files
+        .keys()
+        .filter(|uri| !current_files.contains(*uri))
+        .count() as i64;
+
+    let mut manifests = vec![];
+    if !entries.is_empty() {
+        let manifest_uri =
+            storage.join_path(&target_dir, &format!("{}-m0.avro", uuid::Uuid::new_v4()));
+        let manifest = write_container(
+            &manifest_entry_schema(partition_schema),
+            &[
+                ("schema", schema.to_string()),
+                ("schema-id", schema_id.to_string()),
+                (
+                    "partition-spec",
+                    Value::Array(spec_fields.clone()).to_string(),
+                ),
+                ("partition-spec-id", spec_id.to_string()),
+                ("format-version", FORMAT_VERSION.to_string()),
+                ("content", "data".to_string()),
+            ],
+            &entries,
+        )?;
+        storage.put_obj(&manifest_uri, &manifest).await?;
+        manifests.push(AvroValue::Record(vec![
+            AvroValue::String(manifest_uri),
+            AvroValue::Long(manifest.len() as i64),
+            AvroValue::Int(spec_id),
+            AvroValue::Long(snapshot_id),
+            AvroValue::Int(summary.added_files as i32),
+            AvroValue::Int(summary.existing_files as i32),
+            AvroValue::Int(0),
+            AvroValue::Array(
+                partition_summaries
+                    .iter()
+                    .map(PartitionSummary::to_avro)
+                    .collect(),
+            ),
+            AvroValue::Long(summary.added_rows),
+            AvroValue::Long(summary.existing_rows),
+            AvroValue::Long(0),
+        ]));
+    }
+
+    let parent_snapshot_id = previous_metadata
+        .and_then(|m| m["current-snapshot-id"].as_i64())
+        .filter(|id| *id >= 0);
+    let manifest_list_uri = storage.join_path(
+        &target_dir,
+        &format!("snap-{}-1-{}.avro", snapshot_id, uuid::Uuid::new_v4()),
+    );
+    let mut manifest_list_metadata = vec![
+        ("snapshot-id", snapshot_id.to_string()),
+        ("format-version", FORMAT_VERSION.to_string()),
+    ];
+    if let Some(parent_snapshot_id) = parent_snapshot_id {
+        manifest_list_metadata.push(("parent-snapshot-id", parent_snapshot_id.to_string()));
+    }
+    let manifest_list =
+        write_container(&manifest_file_schema(), &manifest_list_metadata, &manifests)?;
+    storage.put_obj(&manifest_list_uri, &manifest_list).await?;
+
+    let now = Utc::now().timestamp_millis();
+    let mut snapshot = json!({
+        "snapshot-id": snapshot_id,
+        "timestamp-ms": now,
+        "summary": summary.to_json(),
+        "manifest-list": manifest_list_uri,
+        "schema-id": schema_id,
+    });
+    if let Some(parent_snapshot_id) = parent_snapshot_id {
+        snapshot["parent-snapshot-id"] = json!(parent_snapshot_id);
+    }
+
+    let mut schemas = schemas;
+    if !schemas.iter().any(|s| s["schema-id"] == json!(schema_id)) {
+        schemas.push(schema.clone());
+    }
+    let spec = json!({"spec-id": spec_id, "fields": spec_fields});
+    let mut specs = specs;
+    if !specs.iter().any(|s| s["spec-id"] == json!(spec_id)) {
+        specs.push(spec);
+    }
+    let last_partition_id = specs
+        .iter()
+        .flat_map(|spec| array(&spec["fields"]))
+        .filter_map(|field| field["field-id"].as_i64())
+        .max()
+        .unwrap_or(PARTITION_FIELD_ID_START as i64 - 1);
+
+    let mut snapshots = previous_metadata.map_or_else(Vec::new, |m| array(&m["snapshots"]));
+    snapshots.push(snapshot);
+    let mut snapshot_log = previous_metadata.map_or_else(Vec::new, |m| array(&m["snapshot-log"]));
+    snapshot_log.push(json!({"timestamp-ms": now, "snapshot-id": snapshot_id}));
+    let mut metadata_log = previous_metadata.map_or_else(Vec::new, |m| array(&m["metadata-log"]));
+    if let Some(previous) = &previous {
+        metadata_log.push(json!({
+            "timestamp-ms": previous.metadata["last-updated-ms"],
+            "metadata-file": previous.uri,
+        }));
+    }
+
+    let iceberg_metadata = json!({
+        "format-version": FORMAT_VERSION,
+        "table-uuid": previous_metadata
+            .and_then(|m| m["table-uuid"].as_str().map(String::from))
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        "location": table.table_uri,
+        "last-updated-ms": now,
+        "last-column-id": builder.last_column_id,
+        "schema": schema,
+        "schemas": schemas,
+        "current-schema-id": schema_id,
+        "partition-spec": spec_fields,
+        "partition-specs": specs,
+        "default-spec-id": spec_id,
+        "last-partition-id": last_partition_id,
+        "properties": {
+            DELTA_VERSION_PROPERTY: table.version.to_string(),
+            "delta.table-id": metadata.id,
+            NAME_MAPPING_PROPERTY: Value::Array(name_mapping).to_string(),
+        },
+        "current-snapshot-id": snapshot_id,
+        "snapshots": snapshots,
+        "snapshot-log": snapshot_log,
+        "metadata-log": metadata_log,
+        "sort-orders": [{"order-id": 0, "fields": []}],
+        "default-sort-order-id": 0,
+    });
+
+    let version = previous.as_ref().map_or(1, |p| p.version + 1);
+    let metadata_uri = storage.join_path(&target_dir, &metadata_file_name(version));
+    storage
+        .put_obj(
+            &metadata_uri,
+            serde_json::to_string_pretty(&iceberg_metadata)?.as_bytes(),
+        )
+        .await?;
+    storage
+        .put_obj(
+            &storage.join_path(&target_dir, VERSION_HINT_FILE),
+            version.to_string().as_bytes(),
+        )
+        .await?;
+
+    Ok(metadata_uri)
+}
+
+fn check_supported(table: &DeltaTable, metadata: &DeltaTableMetaData) -> Result<(), UniformError> {
+    // deletion vectors require reader version 3
+    if table.get_min_reader_version() >= 3 {
+        return Err(UniformError::UnsupportedReaderVersion(
+            table.get_min_reader_version(),
+        ));
+    }
+    match TableProperties::from_metadata(metadata)
+        .column_mapping_mode
+        .as_str()
+    {
+        // the physical names of name mode are resolved with the name mapping
+        "none" | "name" => Ok(()),
+        mode => Err(UniformError::UnsupportedColumnMapping(mode.to_string())),
+    }
+}
+
+fn metadata_file_name(version: i64) -> String {
+    format!("v{}.metadata.json", version)
+}
+
+fn array(value: &Value) -> Vec<Value> {
+    value.as_array().cloned().unwrap_or_default()
+}
+
+fn invalid_metadata(msg: &str) -> UniformError {
+    UniformError::InvalidMetadata(msg.to_string())
+}
+
+/// Returns the id of the first item matching `matches` or the next free id.
+fn find_or_next_id<F>(items: &[Value], id_key: &str, matches: F) -> i32
+where
+    F: Fn(&Value) -> bool,
+{
+    items
+        .iter()
+        .find(|item| matches(item))
+        .and_then(|item| item[id_key].as_i64())
+        .or_else(|| {
+            items
+                .iter()
+                .filter_map(|item| item[id_key].as_i64())
+                .max()
+                .map(|id| id + 1)
+        })
+        .unwrap_or(0) as i32
+}
+
+fn new_snapshot_id(previous_metadata: Option<&Value>) -> i64 {
+    let existing = previous_metadata
+        .map(|m| array(&m["snapshots"]))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|snapshot| snapshot["snapshot-id"].as_i64())
+        .collect::<HashSet<_>>();
+    loop {
+        let id = (uuid::Uuid::new_v4().as_u128() as i64) & i64::MAX;
+        if !existing.contains(&id) {
+            return id;
+        }
+    }
+}
+
+/// The metadata written by a previous export
+struct PreviousExport {
+    version: i64,
+    uri: String,
+    metadata: Value,
+    /// URIs of the data files of the current snapshot and the snapshot that added them
+    files: HashMap<String, i64>,
+}
+
+impl PreviousExport {
+    async fn load(
+        storage: &dyn StorageBackend,
+        target_dir: &str,
+    ) -> Result<Option<Self>, UniformError> {
+        let hint = match storage
+            .get_obj(&storage.join_path(target_dir, VERSION_HINT_FILE))
+            .await
+        {
+            Ok(hint) => hint,
+            Err(StorageError::NotFound) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let version = String::from_utf8_lossy(&hint)
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| invalid_metadata("the version hint is not a number"))?;
+        let uri = storage.join_path(target_dir, &metadata_file_name(version));
+        let metadata: Value = serde_json::from_slice(&storage.get_obj(&uri).await?)?;
+        let files = live_files(storage, &metadata).await?;
+        Ok(Some(Self {
+            version,
+            uri,
+            metadata,
+            files,
+        }))
+    }
+}
+
+async fn live_files(
+    storage: &dyn StorageBackend,
+    metadata: &Value,
+) -> Result<HashMap<String, i64>, UniformError> {
+    let mut files = HashMap::new();
+    let snapshot_id = match metadata["current-snapshot-id"].as_i64() {
+        Some(id) if id >= 0 => id,
+        _ => return Ok(files),
+    };
+    let snapshot = array(&metadata["snapshots"])
+        .into_iter()
+        .find(|snapshot| snapshot["snapshot-id"].as_i64() == Some(snapshot_id))
+        .ok_or_else(|| invalid_metadata("the current snapshot is missing"))?;
+    let schema_id = snapshot["schema-id"]
+        .as_i64()
+        .or_else(|| metadata["current-schema-id"].as_i64());
+    let schema = array(&metadata["schemas"])
+        .into_iter()
+        .find(|schema| schema["schema-id"].as_i64() == schema_id)
+        .unwrap_or_else(|| metadata["schema"].clone());
+    let manifest_list_uri = snapshot["manifest-list"]
+        .as_str()
+        .ok_or_else(|| invalid_metadata("the current snapshot has no manifest list"))?;
+
+    let manifest_list = read_container(
+        &manifest_file_schema(),
+        &storage.get_obj(manifest_list_uri).await?,
+    )?;
+    for manifest in manifest_list {
+        let (manifest_uri, spec_id) = match (
+            manifest.field(0).and_then(AvroValue::as_str),
+            manifest.field(2).and_then(AvroValue::as_int),
+        ) {
+            (Some(uri), Some(spec_id)) => (uri, spec_id),
+            _ => return Err(invalid_metadata("invalid entry of the manifest list")),
+        };
+        let spec = array(&metadata["partition-specs"])
+            .into_iter()
+            .find(|spec| spec["spec-id"].as_i64() == Some(spec_id as i64))
+            .ok_or_else(|| invalid_metadata("the partition spec of a manifest is missing"))?;
+        let partition_schema = partition_avro_schema(&array(&spec["fields"]), &schema)?;
+
+        let entries = read_container(
+            &manifest_entry_schema(partition_schema),
+            &storage.get_obj(manifest_uri).await?,
+        )?;
+        for entry in entries {
+            let status = entry.field(0).and_then(AvroValue::as_int);
+            let added_snapshot_id = entry.field(1).and_then(AvroValue::as_long);
+            let path = entry
+                .field(2)
+                .and_then(|data_file| data_file.field(0))
+                .and_then(AvroValue::as_str);
+            match (status, added_snapshot_id, path) {
+                (Some(STATUS_DELETED), _, _) => {}
+                (Some(_), Some(id), Some(path)) => {
+                    files.insert(path.to_string(), id);
+                }
+                _ => return Err(invalid_metadata("invalid entry of a manifest")),
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Translates delta schemas to Iceberg schemas, keeping the field ids of the previous export
+struct SchemaBuilder {
+    previous_ids: HashMap<String, i32>,
+    last_column_id: i32,
+}
+
+impl SchemaBuilder {
+    fn new(previous_metadata: Option<&Value>) -> Self {
+        let mut previous_ids = HashMap::new();
+        let mut last_column_id = 0;
+        if let Some(metadata) = previous_metadata {
+            for schema in array(&metadata["schemas"]) {
+                collect_field_ids(&schema, "", &mut previous_ids);
+            }
+            collect_field_ids(&metadata["schema"], "", &mut previous_ids);
+            last_column_id = metadata["last-column-id"].as_i64().unwrap_or_default() as i32;
+        }
+        Self {
+            previous_ids,
+            last_column_id,
+        }
+    }
+
+    fn id(&mut self, path: &str) -> i32 {
+        if let Some(id) = self.previous_ids.get(path) {
+            return *id;
+        }
+        self.last_column_id += 1;
+        self.previous_ids
+            .insert(path.to_string(), self.last_column_id);
+        self.last_column_id
+    }
+
+    /// Returns the Iceberg struct type and the name mapping of its fields
+    fn struct_type(
+        &mut self,
+        fields: &[SchemaField],
+        prefix: &str,
+    ) -> Result<(Value, Vec<Value>), UniformError> {
+        let mut iceberg_fields = vec![];
+        let mut name_mapping = vec![];
+        for field in fields {
+            let path = format!("{}{}", prefix, field.get_name());
+            let id = self.id(&path);
+            let (data_type, nested_mapping) = self.data_type(field.get_type(), &path)?;
+            iceberg_fields.push(json!({
+                "id": id,
+                "name": field.get_name(),
+                "required": !field.is_nullable(),
+                "type": data_type,
+            }));
+
+            let mut names = vec![field.get_name().to_string()];
+            if let Some(physical_name) = field.get_metadata().get(COLUMN_MAPPING_PHYSICAL_NAME_KEY)
+            {
+                names.push(physical_name.clone());
+            }
+            name_mapping.push(mapped_field(id, names, nested_mapping));
+        }
+        Ok((
+            json!({"type": "struct", "fields": iceberg_fields}),
+            name_mapping,
+        ))
+    }
+
+    fn data_type(
+        &mut self,
+        data_type: &SchemaDataType,
+        path: &str,
+    ) -> Result<(Value, Vec<Value>), UniformError> {
+        match data_type {
+            SchemaDataType::primitive(name) => Ok((json!(iceberg_primitive(name)?), vec![])),
+            SchemaDataType::r#struct(nested) => {
+                self.struct_type(nested.get_fields(), &format!("{}.", path))
+            }
+            SchemaDataType::array(array) => {
+                let element_path = format!("{}.element", path);
+                let element_id = self.id(&element_path);
+                let (element, mapping) = self.data_type(array.get_element_type(), &element_path)?;
+                Ok((
+                    json!({
+                        "type": "list",
+                        "element-id": element_id,
+                        "element": element,
+                        "element-required": !array.contains_null(),
+                    }),
+                    vec![mapped_field(
+                        element_id,
+                        vec!["element".to_string(), "item".to_string()],
+                        mapping,
+                    )],
+                ))
+            }
+            SchemaDataType::map(map) => {
+                let key_path = format!("{}.key", path);
+                let value_path = format!("{}.value", path);
+                let key_id = self.id(&key_path);
+                let value_id = self.id(&value_path);
+                let (key, key_mapping) = self.data_type(map.get_key_type(), &key_path)?;
+                let (value, value_mapping) = self.data_type(map.get_value_type(), &value_path)?;
+                Ok((
+                    json!({
+                        "type": "map",
+                        "key-id": key_id,
+                        "key": key,
+                        "value-id": value_id,
+                        "value": value,
+                        "value-required": !map.get_value_contains_null(),
+                    }),
+                    vec![
+                        mapped_field(key_id, vec!["key".to_string()], key_mapping),
+                        mapped_field(value_id, vec!["value".to_string()], value_mapping),
+                    ],
+                ))
+            }
+        }
+    }
+}
+
+fn collect_field_ids(data_type: &Value, path: &str, ids: &mut HashMap<String, i32>) {
+    let join = |name: &str| {
+        if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", path, name)
+        }
+    };
+    let mut insert = |path: String, id: &Value, nested: &Value| {
+        if let Some(id) = id.as_i64() {
+            collect_field_ids(nested, &path, ids);
+            ids.insert(path, id as i32);
+        }
+    };
+    match data_type["type"].as_str() {
+        Some("struct") => {
+            for field in array(&data_type["fields"]) {
+                if let Some(name) = field["name"].as_str() {
+                    insert(join(name), &field["id"], &field["type"]);
+                }
+            }
+        }
+        Some("list") => insert(
+            join("element"),
+            &data_type["element-id"],
+            &data_type["element"],
+        ),
+        Some("map") => {
+            insert(join("key"), &data_type["key-id"], &data_type["key"]);
+            insert(join("value"), &data_type["value-id"], &data_type["value"]);
+        }
+        _ => {}
+    }
+}
+
+fn mapped_field(id: i32, names: Vec<String>, fields: Vec<Value>) -> Value {
+    let mut mapping = json!({"field-id": id, "names": names});
+    if !fields.is_empty() {
+        mapping["fields"] = Value::Array(fields);
+    }
+    mapping
+}
+
+fn iceberg_primitive(name: &str) -> Result<String, UniformError> {
+    Ok(match name {
+        "string" => "string".to_string(),
+        "long" => "long".to_string(),
+        "integer" | "short" | "byte" => "int".to_string(),
+        "float" => "float".to_string(),
+        "double" => "double".to_string(),
+        "boolean" => "boolean".to_string(),
+        "binary" => "binary".to_string(),
+        "date" => "date".to_string(),
+        // delta timestamps are adjusted to UTC
+        "timestamp" => "timestamptz".to_string(),
+        decimal if decimal.starts_with("decimal(") && decimal.ends_with(')') => {
+            let precision_and_scale = decimal["decimal(".len()..decimal.len() - 1]
+                .split(',')
+                .map(|n| n.trim().parse::<u32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| UniformError::UnsupportedType(name.to_string()))?;
+            match precision_and_scale.as_slice() {
+                [precision, scale] => format!("decimal({}, {})", precision, scale),
+                _ => return Err(UniformError::UnsupportedType(name.to_string())),
+            }
+        }
+        _ => return Err(UniformError::UnsupportedType(name.to_string())),
+    })
+}
+
+/// Returns the type of the top level column with the given id of an Iceberg schema
+fn column_type(schema: &Value, id: i64) -> Option<&Value> {
+    schema["fields"]
+        .as_array()?
+        .iter()
+        .find(|field| field["id"].as_i64() == Some(id))
+        .map(|field| &field["type"])
+}
+
+fn partition_spec_fields(
+    partition_columns: &[String],
+    schema: &Value,
+) -> Result<Vec<Value>, UniformError> {
+    partition_columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let source_id = array(&schema["fields"])
+                .iter()
+                .find(|field| field["name"].as_str() == Some(column.as_str()))
+                .and_then(|field| field["id"].as_i64())
+                .ok_or_else(|| invalid_metadata("a partition column is missing in the schema"))?;
+            Ok(json!({
+                "name": column,
+                "transform": "identity",
+                "source-id": source_id,
+                "field-id": PARTITION_FIELD_ID_START + i as i32,
+            }))
+        })
+        .collect()
+}
+
+/// Returns the Iceberg types of the identity partition fields of a spec
+fn partition_types(spec_fields: &[Value], schema: &Value) -> Result<Vec<String>, UniformError> {
+    spec_fields
+        .iter()
+        .map(|field| {
+            field["source-id"]
+                .as_i64()
+                .and_then(|id| column_type(schema, id))
+                .and_then(Value::as_str)
+                .map(String::from)
+                .ok_or_else(|| invalid_metadata("the source column of a partition is missing"))
+        })
+        .collect()
+}
+
+fn partition_avro_schema(
+    spec_fields: &[Value],
+    schema: &Value,
+) -> Result<AvroSchema, UniformError> {
+    let fields = spec_fields
+        .iter()
+        .zip(partition_types(spec_fields, schema)?)
+        .map(|(field, data_type)| {
+            let name = field["name"].as_str().unwrap_or_default();
+            let avro_type = match data_type.as_str() {
+                "boolean" => AvroSchema::Boolean,
+                "int" => AvroSchema::Int,
+                "long" => AvroSchema::Long,
+                "float" => AvroSchema::Float,
+                "double" => AvroSchema::Double,
+                "string" => AvroSchema::String,
+                "binary" => AvroSchema::Bytes,
+                "date" => AvroSchema::Date,
+                "timestamptz" => AvroSchema::TimestampMicros {
+                    adjust_to_utc: true,
+                },
+                _ => {
+                    return Err(UniformError::UnsupportedPartitionType {
+                        column: name.to_string(),
+                        data_type,
+                    })
+                }
+            };
+            Ok(AvroField::new(
+                &avro_name(name),
+                field["field-id"].as_i64().unwrap_or_default() as i32,
+                AvroSchema::Optional(Box::new(avro_type)),
+            ))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(AvroSchema::Record {
+        name: "r102".to_string(),
+        fields,
+    })
+}
+
+/// Makes a column name a valid Avro name like Iceberg does
+fn avro_name(name: &str) -> String {
+    let mut avro_name = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()) {
+            avro_name.push(c);
+        } else if c.is_ascii_digit() {
+            avro_name.push('_');
+            avro_name.push(c);
+        } else {
+            avro_name.push_str(&format!("_x{:X}", c as u32));
+        }
+    }
+    avro_name
+}
+
+fn days_since_epoch(value: &str) -> Option<i32> {
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    i32::try_from((date - NaiveDate::from_ymd(1970, 1, 1)).num_days()).ok()
+}
+
+fn partition_value(
+    column: &str,
+    data_type: &str,
+    value: Option<&Option<String>>,
+) -> Result<AvroValue, UniformError> {
+    let value = match value.cloned().flatten() {
+        Some(value) => value,
+        None => return Ok(AvroValue::Null),
+    };
+    let parsed = match data_type {
+        "boolean" => value.parse().ok().map(AvroValue::Boolean),
+        "int" => value.parse().ok().map(AvroValue::Int),
+        "long" => value.parse().ok().map(AvroValue::Long),
+        "float" => value.parse().ok().map(AvroValue::Float),
+        "double" => value.parse().ok().map(AvroValue::Double),
+        "string" => Some(AvroValue::String(value.clone())),
+        "binary" => Some(AvroValue::Bytes(value.as_bytes().to_vec())),
+        "date" => days_since_epoch(&value).map(AvroValue::Int),
+        "timestamptz" => NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S%.f")
+            .ok()
+            .map(|timestamp| AvroValue::Long(timestamp.timestamp_nanos() / 1000)),
+        _ => None,
+    };
+    parsed.ok_or_else(|| UniformError::InvalidPartitionValue {
+        column: column.to_string(),
+        value,
+    })
+}
+
+/// Aggregates the partition values of a manifest for the manifest list
+#[derive(Clone, Default)]
+struct PartitionSummary {
+    contains_null: bool,
+    lower: Option<AvroValue>,
+    upper: Option<AvroValue>,
+}
+
+impl PartitionSummary {
+    fn add(&mut self, value: &AvroValue) {
+        if *value == AvroValue::Null {
+            self.contains_null = true;
+            return;
+        }
+        if self
+            .lower
+            .as_ref()
+            .map_or(true, |lower| compare(value, lower) == Some(Ordering::Less))
+        {
+            self.lower = Some(value.clone());
+        }
+        if self.upper.as_ref().map_or(true, |upper| {
+            compare(value, upper) == Some(Ordering::Greater)
+        }) {
+            self.upper = Some(value.clone());
+        }
+    }
+
+    fn to_avro(&self) -> AvroValue {
+        let bound = |value: &Option<AvroValue>| {
+            value
+                .as_ref()
+                .and_then(single_value_bytes)
+                .map_or(AvroValue::Null, AvroValue::Bytes)
+        };
+        AvroValue::Record(vec![
+            AvroValue::Boolean(self.contains_null),
+            bound(&self.lower),
+            bound(&self.upper),
+        ])
+    }
+}
+
+fn compare(a: &AvroValue, b: &AvroValue) -> Option<Ordering> {
+    match (a, b) {
+        (AvroValue::Boolean(a), AvroValue::Boolean(b)) => a.partial_cmp(b),
+        (AvroValue::Int(a), AvroValue::Int(b)) => a.partial_cmp(b),
+        (AvroValue::Long(a), AvroValue::Long(b)) => a.partial_cmp(b),
+        (AvroValue::Float(a), AvroValue::Float(b)) => a.partial_cmp(b),
+        (AvroValue::Double(a), AvroValue::Double(b)) => a.partial_cmp(b),
+        (AvroValue::String(a), AvroValue::String(b)) => a.partial_cmp(b),
+        (AvroValue::Bytes(a), AvroValue::Bytes(b)) => a.partial_cmp(b),
+        _ => None,
+    }
+}
+
+/// Serializes a value for the bounds of manifests and manifest lists
+fn single_value_bytes(value: &AvroValue) -> Option<Vec<u8>> {
+    match value {
+        AvroValue::Boolean(v) => Some(vec![*v as u8]),
+        AvroValue::Int(v) => Some(v.to_le_bytes().to_vec()),
+        AvroValue::Long(v) => Some(v.to_le_bytes().to_vec()),
+        AvroValue::Float(v) => Some(v.to_le_bytes().to_vec()),
+        AvroValue::Double(v) => Some(v.to_le_bytes().to_vec()),
+        AvroValue::String(v) => Some(v.as_bytes().to_vec()),
+        AvroValue::Bytes(v) => Some(v.clone()),
+        _ => None,
+    }
+}
+
+/// A top level primitive column, for which delta statistics are translated
+struct StatsColumn {
+    field_id: i32,
+    data_type: String,
+    stats_name: String,
+}
+
+fn stats_columns(fields: &[SchemaField], schema: &Value) -> Vec<StatsColumn> {
+    fields
+        .iter()
+        .zip(array(&schema["fields"]))
+        .filter_map(|(field, iceberg_field)| {
+            let data_type = iceberg_field["type"].as_str()?.to_string();
+            let stats_name = field
+                .get_metadata()
+                .get(COLUMN_MAPPING_PHYSICAL_NAME_KEY)
+                .cloned()
+                .unwrap_or_else(|| field.get_name().to_string());
+            Some(StatsColumn {
+                field_id: iceberg_field["id"].as_i64()? as i32,
+                data_type,
+                stats_name,
+            })
+        })
+        .collect()
+}
+
+/// Translates a delta statistic to an Iceberg bound, if it is exact enough to be one
+fn bound(data_type: &str, value: &Value, upper: bool) -> Option<Vec<u8>> {
+    match data_type {
+        "int" => value
+            .as_i64()
+            .and_then(|v| i32::try_from(v).ok())
+            .map(|v| v.to_le_bytes().to_vec()),
+        "long" => value.as_i64().map(|v| v.to_le_bytes().to_vec()),
+        "double" => value
+            .as_f64()
+            .filter(|v| v.is_finite())
+            .map(|v| v.to_le_bytes().to_vec()),
+        "date" => value
+            .as_str()
+            .and_then(days_since_epoch)
+            .map(|v| v.to_le_bytes().to_vec()),
+        "timestamptz" => value
+            .as_str()
+            .and_then(|v| time_utils::timestamp_micros_from_stats_string(v).ok())
+            // delta statistics of timestamps are truncated to milliseconds
+            .map(|v| if upper { v + 999 } else { v })
+            .map(|v| v.to_le_bytes().to_vec()),
+        "string" => value
+            .as_str()
+            .filter(|v| v.chars().count() < MAX_STRING_BOUND_LENGTH)
+            .map(|v| v.as_bytes().to_vec()),
+        _ => None,
+    }
+}
+
+fn data_file(
+    add: &Add,
+    uri: String,
+    partition: Vec<AvroValue>,
+    stats: &Stats,
+    columns: &[StatsColumn],
+) -> AvroValue {
+    let mut value_counts = vec![];
+    let mut null_value_counts = vec![];
+    let mut lower_bounds = vec![];
+    let mut upper_bounds = vec![];
+    for column in columns {
+        let id = AvroValue::Int(column.field_id);
+        value_counts.push((id.clone(), AvroValue::Long(stats.num_records)));
+        if let Some(null_count) = stats
+            .null_count
+            .get(&column.stats_name)
+            .and_then(|count| count.as_value())
+        {
+            null_value_counts.push((id.clone(), AvroValue::Long(null_count)));
+        }
+        let bounds = [
+            (&stats.min_values, false, &mut lower_bounds),
+            (&stats.max_values, true, &mut upper_bounds),
+        ];
+        for (values, upper, bounds) in bounds {
+            if let Some(bound) = values
+                .get(&column.stats_name)
+                .and_then(|value| value.as_value())
+                .and_then(|value| bound(&column.data_type, value, upper))
+            {
+                bounds.push((id.clone(), AvroValue::Bytes(bound)));
+            }
+        }
+    }
+    let map = |entries: Vec<(AvroValue, AvroValue)>| {
+        if entries.is_empty() {
+            AvroValue::Null
+        } else {
+            AvroValue::Map(entries)
+        }
+    };
+
+    AvroValue::Record(vec![
+        AvroValue::String(uri),
+        AvroValue::String("PARQUET".to_string()),
+        AvroValue::Record(partition),
+        AvroValue::Long(stats.num_records),
+        AvroValue::Long(add.size),
+        AvroValue::Long(DEFAULT_BLOCK_SIZE),
+        AvroValue::Null,
+        map(value_counts),
+        map(null_value_counts),
+        map(lower_bounds),
+        map(upper_bounds),
+    ])
+}
+
+fn optional(schema: AvroSchema) -> AvroSchema {
+    AvroSchema::Optional(Box::new(schema))
+}
+
+fn id_map(key_id: i32, value_id: i32, value: AvroSchema) -> AvroSchema {
+    optional(AvroSchema::Map {
+        key_id,
+        key: Box::new(AvroSchema::Int),
+        value_id,
+        value: Box::new(value),
+    })
+}
+
+fn manifest_entry_schema(partition: AvroSchema) -> AvroSchema {
+    let data_file = AvroSchema::Record {
+        name: "r2".to_string(),
+        fields: vec![
+            AvroField::new("file_path", 100, AvroSchema::String),
+            AvroField::new("file_format", 101, AvroSchema::String),
+            AvroField::new("partition", 102, partition),
+            AvroField::new("record_count", 103, AvroSchema::Long),
+            AvroField::new("file_size_in_bytes", 104, AvroSchema::Long),
+            AvroField::new("block_size_in_bytes", 105, AvroSchema::Long),
+            AvroField::new("column_sizes", 108, id_map(117, 118, AvroSchema::Long)),
+            AvroField::new("value_counts", 109, id_map(119, 120, AvroSchema::Long)),
+            AvroField::new("null_value_counts", 110, id_map(121, 122, AvroSchema::Long)),
+            AvroField::new("lower_bounds", 125, id_map(126, 127, AvroSchema::Bytes)),
+            AvroField::new("upper_bounds", 128, id_map(129, 130, AvroSchema::Bytes)),
+        ],
+    };
+    AvroSchema::Record {
+        name: "manifest_entry".to_string(),
+        fields: vec![
+            AvroField::new("status", 0, AvroSchema::Int),
+            AvroField::new("snapshot_id", 1, AvroSchema::Long),
+            AvroField::new("data_file", 2, data_file),
+        ],
+    }
+}
+
+fn manifest_file_schema() -> AvroSchema {
+    let field_summary = AvroSchema::Record {
+        name: "r508".to_string(),
+        fields: vec![
+            AvroField::new("contains_null", 509, AvroSchema::Boolean),
+            AvroField::new("lower_bound", 510, optional(AvroSchema::Bytes)),
+            AvroField::new("upper_bound", 511, optional(AvroSchema::Bytes)),
+        ],
+    };
+    AvroSchema::Record {
+        name: "manifest_file".to_string(),
+        fields: vec![
+            AvroField::new("manifest_path", 500, AvroSchema::String),
+            AvroField::new("manifest_length", 501, AvroSchema::Long),
+            AvroField::new("partition_spec_id", 502, AvroSchema::Int),
+            AvroField::new("added_snapshot_id", 503, AvroSchema::Long),
+            AvroField::new("added_data_files_count", 504, optional(AvroSchema::Int)),
+            AvroField::new("existing_data_files_count", 505, optional(AvroSchema::Int)),
+            AvroField::new("deleted_data_files_count", 506, optional(AvroSchema::Int)),
+            AvroField::new(
+                "partitions",
+                507,
+                optional(AvroSchema::Array {
+                    element_id: 508,
+                    items: Box::new(field_summary),
+                }),
+            ),
+            AvroField::new("added_rows_count", 512, optional(AvroSchema::Long)),
+            AvroField::new("existing_rows_count", 513, optional(AvroSchema::Long)),
+            AvroField::new("deleted_rows_count", 514, optional(AvroSchema::Long)),
+        ],
+    }
+}
+
+/// The counts of the summary of a snapshot
+#[derive(Default)]
+struct SnapshotSummary {
+    added_files: i64,
+    existing_files: i64,
+    deleted_files: i64,
+    added_rows: i64,
+    existing_rows: i64,
+}
+
+impl SnapshotSummary {
+    fn to_json(&self) -> Value {
+        let operation = if self.deleted_files > 0 {
+            "overwrite"
+        } else {
+            "append"
+        };
+        json!({
+            "operation": operation,
+            "added-data-files": self.added_files.to_string(),
+            "deleted-data-files": self.deleted_files.to_string(),
+            "total-data-files": (self.added_files + self.existing_files).to_string(),
+            "added-records": self.added_rows.to_string(),
+            "total-records": (self.added_rows + self.existing_rows).to_string(),
+        })
+    }
+}
@@ -4,7 +4,12 @@
 //! Unlike the transaction API on DeltaTable, this higher level writer will also write out the
 //! parquet files
 
-use crate::action::Txn;
+use crate::action::{
+    Action, Add, ColumnCountStat, ColumnValueStat, DeltaOperation, MetaData, Protocol, Remove,
+    SaveMode, Stats, Txn,
+};
+use crate::delta_config::TableProperties;
+use crate::predicate::Predicate;
 use crate::DeltaTableError;
 use arrow::record_batch::RecordBatch;
 use log::*;
@@ -13,9 +18,11 @@ use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
 use parquet::file::writer::InMemoryWriteableCursor;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
+use std::io::{Seek, SeekFrom};
 use std::sync::Arc;
+use std::time::Instant;
 
 /// BufferedJsonWriter allows for buffering serde_json::Value rows before flushing to parquet files
 /// and a Delta transaction
@@ -32,6 +39,7 @@ impl BufferedJsonWriter {
     /// present
     pub fn try_new(table: crate::DeltaTable) -> Result<Self, DeltaTableError> {
         let metadata = table.get_metadata()?.clone();
+        check_column_mapping(&metadata)?;
         let schema = metadata.schema;
         let arrow_schema =
             <arrow::datatypes::Schema as TryFrom<&crate::Schema>>::try_from(&schema).unwrap();
@@ -169,6 +177,15 @@ impl ParquetBuffer {
         self.cursor.data()
     }
 
+    /// The number of bytes written so far, each written batch is encoded as a row group right
+    /// away
+    fn size(&self) -> Result<u64, DeltaTableError> {
+        let mut cursor = self.cursor.clone();
+        cursor
+            .seek(SeekFrom::Current(0))
+            .map_err(|err| DeltaTableError::Generic(err.to_string()))
+    }
+
     fn close(&mut self) -> Result<parquet_format::FileMetaData, DeltaTableError> {
         self.writer
             .close()
@@ -212,6 +229,1071 @@ pub enum WriterPartition {
     },
 }
 
+/// Partition values of a set of rows, ordered like the partition columns of the table
+type PartitionValues = Vec<(String, Option<String>)>;
+
+/// The writers name the parquet columns by their logical names, so tables with column mapping,
+/// whose readers look the columns up by their physical names, are rejected.
+fn check_column_mapping(metadata: &crate::DeltaTableMetaData) -> Result<(), DeltaTableError> {
+    let properties = TableProperties::from_metadata(metadata);
+    if properties.column_mapping_mode != "none" {
+        return Err(DeltaTableError::ColumnMappingNotSupported {
+            mode: properties.column_mapping_mode,
+        });
+    }
+    Ok(())
+}
+
+/// Names parquet cannot store are rejected by the commit, so they are checked before any file of
+/// a new schema is written.
+fn check_column_names(metadata: &crate::DeltaTableMetaData) -> Result<(), DeltaTableError> {
+    let names = metadata.schema.invalid_column_names();
+    if !names.is_empty() {
+        return Err(DeltaTableError::InvalidColumnNames { names });
+    }
+    Ok(())
+}
+
+/// The remove action of a file replaced by an overwrite
+fn remove_action(add: &Add, deletion_timestamp: i64) -> Action {
+    Action::remove(Remove {
+        path: add.path.clone(),
+        deletion_timestamp: Some(deletion_timestamp),
+        data_change: true,
+        extended_file_metadata: Some(true),
+        partition_values: Some(add.partition_values.clone()),
+        size: Some(add.size),
+        tags: add.tags.clone(),
+    })
+}
+
+/// RecordBatchWriter buffers arrow record batches split by the partition columns of a table and
+/// writes one parquet file with statistics per partition to a Delta transaction
+pub struct RecordBatchWriter {
+    schema: arrow::datatypes::SchemaRef,
+    file_schema: arrow::datatypes::SchemaRef,
+    partition_columns: Vec<String>,
+    buffer: HashMap<PartitionValues, Vec<RecordBatch>>,
+    control: crate::OperationControl,
+}
+
+impl RecordBatchWriter {
+    /// Attempt to construct the RecordBatchWriter for the schema and partition columns of the
+    /// table, will fail if the table's metadata is not present
+    pub fn for_table(table: &crate::DeltaTable) -> Result<Self, DeltaTableError> {
+        Self::for_metadata(table.get_metadata()?)
+    }
+
+    /// Construct the RecordBatchWriter for the schema and partition columns of `metadata`, e.g.
+    /// of a table that is yet to be created
+    pub fn for_metadata(metadata: &crate::DeltaTableMetaData) -> Result<Self, DeltaTableError> {
+        check_column_mapping(metadata)?;
+        let schema =
+            <arrow::datatypes::Schema as TryFrom<&crate::Schema>>::try_from(&metadata.schema)
+                .map_err(|source| DeltaTableError::ArrowError { source })?;
+        let file_schema = arrow::datatypes::Schema::new(
+            schema
+                .fields()
+                .iter()
+                .filter(|field| !metadata.partition_columns.contains(field.name()))
+                .cloned()
+                .collect(),
+        );
+
+        Ok(Self {
+            schema: Arc::new(schema),
+            file_schema: Arc::new(file_schema),
+            partition_columns: metadata.partition_columns.clone(),
+            buffer: HashMap::new(),
+            control: crate::OperationControl::default(),
+        })
+    }
+
+    /// Report the written files to the progress callback of `control` and stop writing once its
+    /// cancellation token is cancelled
+    pub fn with_control(mut self, control: crate::OperationControl) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// Return the number of rows pending in the buffer
+    pub fn count(&self) -> usize {
+        self.buffer
+            .values()
+            .flat_map(|batches| batches.iter())
+            .map(|batch| batch.num_rows())
+            .sum()
+    }
+
+    /// Write a new RecordBatch into the buffer
+    ///
+    /// Columns are matched by name and cast to the types of the table schema, a column that is
+    /// missing, not part of the table or cannot be cast results in a `SchemaMismatch` error
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<(), DeltaTableError> {
+        self.control.check_cancelled()?;
+        for (values, batch) in self.partition_batch(batch)? {
+            self.buffer.entry(values).or_default().push(batch);
+        }
+        Ok(())
+    }
+
+    /// Split a batch conformed to the table schema into the rows of each partition, without the
+    /// partition columns
+    fn partition_batch(
+        &self,
+        batch: &RecordBatch,
+    ) -> Result<Vec<(PartitionValues, RecordBatch)>, DeltaTableError> {
+        let batch = self.conform_to_schema(batch)?;
+
+        if self.partition_columns.is_empty() {
+            return Ok(vec![(vec![], batch)]);
+        }
+
+        let partition_arrays = self
+            .partition_columns
+            .iter()
+            .map(|name| Ok((name, batch.column(self.schema.index_of(name)?))))
+            .collect::<Result<Vec<_>, arrow::error::ArrowError>>()
+            .map_err(|source| DeltaTableError::ArrowError { source })?;
+
+        let mut rows: HashMap<PartitionValues, Vec<u32>> = HashMap::new();
+        for row in 0..batch.num_rows() {
+            let values = partition_arrays
+                .iter()
+                .map(|(name, array)| {
+                    Ok(((*name).clone(), stringified_partition_value(array, row)?))
+                })
+                .collect::<Result<PartitionValues, arrow::error::ArrowError>>()
+                .map_err(|source| DeltaTableError::ArrowError { source })?;
+            rows.entry(values).or_default().push(row as u32);
+        }
+
+        let mut partitions = Vec::with_capacity(rows.len());
+        for (values, indices) in rows {
+            let indices = arrow::array::UInt32Array::from(indices);
+            let columns = self
+                .file_schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    let column = batch.column(self.schema.index_of(field.name())?);
+                    arrow::compute::take(column.as_ref(), &indices, None)
+                })
+                .collect::<Result<Vec<_>, arrow::error::ArrowError>>()
+                .and_then(|columns| RecordBatch::try_new(self.file_schema.clone(), columns))
+                .map_err(|source| DeltaTableError::ArrowError { source })?;
+            partitions.push((values, columns));
+        }
+
+        Ok(partitions)
+    }
+
+    /// Flush the buffer, writing a parquet file for each partition and adding it to the given
+    /// transaction together with the statistics of its rows
+    ///
+    /// The transaction is not committed, this is left to the caller
+    pub async fn flush(
+        &mut self,
+        transaction: &mut crate::DeltaTransaction<'_>,
+    ) -> Result<FlushMetrics, DeltaTableError> {
+        let mut metrics = FlushMetrics::default();
+        let total = self.buffer.len();
+        for (partition_values, batches) in self.buffer.drain() {
+            self.control.check_cancelled()?;
+            let mut pb = ParquetBuffer::try_new(self.file_schema.clone())?;
+            let mut stats = FileStats::default();
+            for batch in &batches {
+                pb.write_batch(batch)?;
+                stats.add_batch(batch);
+            }
+            let _metadata = pb.close()?;
+
+            let data = pb.data();
+            metrics.num_files += 1;
+            metrics.num_rows += stats.num_records;
+            metrics.num_bytes += data.len() as i64;
+
+            let stats = serde_json::to_string(&stats.into_stats())?;
+            transaction
+                .add_file_with_stats(&data, partition_values, Some(stats))
+                .await?;
+            self.control.report(crate::ProgressEvent {
+                phase: crate::ProgressPhase::WriteFiles,
+                done: metrics.num_files as usize,
+                total,
+                bytes: Some(metrics.num_bytes),
+            });
+        }
+
+        Ok(metrics)
+    }
+
+    fn conform_to_schema(&self, batch: &RecordBatch) -> Result<RecordBatch, DeltaTableError> {
+        let batch_schema = batch.schema();
+        if let Some(field) = batch_schema
+            .fields()
+            .iter()
+            .find(|field| self.schema.index_of(field.name()).is_err())
+        {
+            return Err(DeltaTableError::SchemaMismatch {
+                msg: format!("Column {} is not part of the table schema", field.name()),
+            });
+        }
+
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let column = batch_schema
+                    .index_of(field.name())
+                    .map(|i| batch.column(i).clone())
+                    .map_err(|_| DeltaTableError::SchemaMismatch {
+                        msg: format!("Column {} of the table schema is missing", field.name()),
+                    })?;
+                if column.data_type() == field.data_type() {
+                    return Ok(column);
+                }
+                if !arrow::compute::can_cast_types(column.data_type(), field.data_type()) {
+                    return Err(DeltaTableError::SchemaMismatch {
+                        msg: format!(
+                            "Column {} of type {:?} cannot be written as {:?}",
+                            field.name(),
+                            column.data_type(),
+                            field.data_type()
+                        ),
+                    });
+                }
+                arrow::compute::cast(&column, field.data_type())
+                    .map_err(|source| DeltaTableError::ArrowError { source })
+            })
+            .collect::<Result<Vec<_>, DeltaTableError>>()?;
+
+        RecordBatch::try_new(self.schema.clone(), columns)
+            .map_err(|source| DeltaTableError::ArrowError { source })
+    }
+}
+
+/// The files, rows and bytes written by [`RecordBatchWriter::flush`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FlushMetrics {
+    /// Number of parquet files written
+    pub num_files: i64,
+    /// Number of rows written
+    pub num_rows: i64,
+    /// Size of the written parquet files in bytes
+    pub num_bytes: i64,
+}
+
+/// The metrics of a write, which are also stored as the operationMetrics of its commitInfo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteMetrics {
+    /// The version committed by the write
+    pub version: crate::DeltaDataTypeVersion,
+    /// Number of parquet files added
+    pub num_added_files: i64,
+    /// Number of files removed, i.e. all files of the table when overwriting
+    pub num_removed_files: i64,
+    /// Size of the added files in bytes
+    pub num_added_bytes: i64,
+    /// Size of the removed files in bytes
+    pub num_removed_bytes: i64,
+    /// Number of rows written
+    pub num_output_rows: i64,
+    /// Number of partitions written to, 0 for an unpartitioned table
+    pub num_partitions: i64,
+    /// Time spent writing the files until the commit in milliseconds
+    pub execution_time_ms: i64,
+}
+
+impl WriteMetrics {
+    /// The operationMetrics of the commitInfo. Like other writers, the values are stored as
+    /// strings.
+    pub fn operation_metrics(&self) -> Value {
+        json!({
+            "numFiles": self.num_added_files.to_string(),
+            "numRemovedFiles": self.num_removed_files.to_string(),
+            "numOutputBytes": self.num_added_bytes.to_string(),
+            "numRemovedBytes": self.num_removed_bytes.to_string(),
+            "numOutputRows": self.num_output_rows.to_string(),
+            "numPartitions": self.num_partitions.to_string(),
+            "executionTimeMs": self.execution_time_ms.to_string(),
+        })
+    }
+}
+
+/// Write the record batches to the table in a single commit according to the save mode and
+/// return the metrics of the write, or None if nothing was written
+///
+/// With `SaveMode::Overwrite` all existing files are removed in the same commit, with
+/// `SaveMode::ErrorIfExists` and `SaveMode::Ignore` nothing is written since the table exists.
+/// The entries of `commit_info` are added to the commitInfo action of the commit, the
+/// transaction options e.g. register post-commit hooks. With a `control` the written files are
+/// reported and a cancelled write stops before committing, leaving the table unchanged.
+pub async fn write_batches(
+    table: &mut crate::DeltaTable,
+    batches: &[RecordBatch],
+    mode: SaveMode,
+    commit_info: Option<serde_json::Map<String, Value>>,
+    transaction_options: Option<crate::DeltaTransactionOptions>,
+    control: Option<crate::OperationControl>,
+) -> Result<Option<WriteMetrics>, DeltaTableError> {
+    let start = Instant::now();
+    match mode {
+        SaveMode::ErrorIfExists => {
+            return Err(DeltaTableError::Generic(format!(
+                "A Delta table already exists at {}",
+                table.table_uri
+            )));
+        }
+        SaveMode::Ignore => return Ok(None),
+        SaveMode::Append | SaveMode::Overwrite => {}
+    }
+
+    let control = control.unwrap_or_default();
+    let mut writer = RecordBatchWriter::for_table(table)?.with_control(control.clone());
+    for batch in batches {
+        writer.write(batch)?;
+    }
+
+    let mut actions = vec![];
+    let mut num_removed_bytes = 0;
+    if mode == SaveMode::Overwrite {
+        num_removed_bytes = table.get_state().files().iter().map(|add| add.size).sum();
+        let deletion_timestamp = chrono::Utc::now().timestamp_millis();
+        actions.extend(
+            table
+                .get_state()
+                .files()
+                .iter()
+                .map(|add| remove_action(add, deletion_timestamp)),
+        );
+    }
+
+    let metrics = {
+        let mut transaction = table.create_transaction(transaction_options);
+        let flushed = writer.flush(&mut transaction).await?;
+        let mut metrics = WriteMetrics {
+            // set once committed
+            version: -1,
+            num_added_files: flushed.num_files,
+            num_removed_files: actions.len() as i64,
+            num_added_bytes: flushed.num_bytes,
+            num_removed_bytes,
+            num_output_rows: flushed.num_rows,
+            num_partitions: if writer.partition_columns.is_empty() {
+                0
+            } else {
+                flushed.num_files
+            },
+            execution_time_ms: start.elapsed().as_millis() as i64,
+        };
+
+        actions.push(write_commit_info(
+            commit_info,
+            mode,
+            &writer.partition_columns,
+            None,
+            &metrics,
+        )?);
+
+        transaction.add_actions(actions);
+        control.check_cancelled()?;
+        metrics.version = transaction
+            .commit(Some(DeltaOperation::Write {
+                mode,
+                partitionBy: Some(writer.partition_columns.clone()),
+                predicate: None,
+            }))
+            .await?;
+        metrics
+    };
+    table.update().await?;
+
+    Ok(Some(metrics))
+}
+
+/// Create a table with `metadata` and `protocol` and write the record batches to it, all in the
+/// single commit of version 0, so that the table is never observed without its data
+///
+/// If another writer creates the table first with the same schema and partition columns, the
+/// written files are appended to that table instead. Otherwise they are deleted again and a
+/// [`DeltaTableError::SchemaMismatch`] is returned. `table` is loaded at the committed version.
+pub async fn write_batches_to_new_table(
+    table: &mut crate::DeltaTable,
+    metadata: crate::DeltaTableMetaData,
+    protocol: Protocol,
+    batches: &[RecordBatch],
+    commit_info: Option<serde_json::Map<String, Value>>,
+    transaction_options: Option<crate::DeltaTransactionOptions>,
+    control: Option<crate::OperationControl>,
+) -> Result<WriteMetrics, DeltaTableError> {
+    let start = Instant::now();
+    check_column_names(&metadata)?;
+    let control = control.unwrap_or_default();
+    let mut writer = RecordBatchWriter::for_metadata(&metadata)?.with_control(control.clone());
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    let partition_columns = writer.partition_columns.clone();
+
+    let mut transaction = table.create_transaction(transaction_options.clone());
+    let flushed = writer.flush(&mut transaction).await?;
+    let mut metrics = WriteMetrics {
+        version: 0,
+        num_added_files: flushed.num_files,
+        num_removed_files: 0,
+        num_added_bytes: flushed.num_bytes,
+        num_removed_bytes: 0,
+        num_output_rows: flushed.num_rows,
+        num_partitions: if partition_columns.is_empty() {
+            0
+        } else {
+            flushed.num_files
+        },
+        execution_time_ms: start.elapsed().as_millis() as i64,
+    };
+    let adds = transaction.actions().to_vec();
+
+    transaction.add_actions(vec![
+        Action::protocol(protocol),
+        Action::metaData(MetaData::try_from(metadata.clone())?),
+        write_commit_info(
+            commit_info.clone(),
+            SaveMode::ErrorIfExists,
+            &partition_columns,
+            None,
+            &metrics,
+        )?,
+    ]);
+    control.check_cancelled()?;
+    let result = transaction
+        .commit_version(
+            0,
+            Some(DeltaOperation::Write {
+                mode: SaveMode::ErrorIfExists,
+                partitionBy: Some(partition_columns.clone()),
+                predicate: None,
+            }),
+        )
+        .await;
+    match result {
+        Ok(_) => return Ok(metrics),
+        Err(DeltaTableError::VersionAlreadyExists(_)) => {}
+        Err(err) => return Err(err),
+    }
+
+    debug!(
+        "The table {} was created concurrently, appending the files instead.",
+        table.table_uri
+    );
+    table.load().await?;
+    let existing = table.get_metadata()?;
+    if existing.schema != metadata.schema || existing.partition_columns != partition_columns {
+        let paths = adds
+            .iter()
+            .filter_map(|action| match action {
+                Action::add(add) => Some(table.storage.join_path(&table.table_uri, &add.path)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        table.storage.delete_objs(&paths).await?;
+        return Err(DeltaTableError::SchemaMismatch {
+            msg: "The table was created concurrently with a different schema or partition columns"
+                .to_string(),
+        });
+    }
+
+    let mut transaction = table.create_transaction(transaction_options);
+    transaction.add_actions(adds);
+    transaction.add_action(write_commit_info(
+        commit_info,
+        SaveMode::Append,
+        &partition_columns,
+        None,
+        &metrics,
+    )?);
+    metrics.version = transaction
+        .commit(Some(DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partitionBy: Some(partition_columns),
+            predicate: None,
+        }))
+        .await?;
+    table.update().await?;
+
+    Ok(metrics)
+}
+
+/// The commitInfo action of a write with the given entries added
+fn write_commit_info(
+    commit_info: Option<serde_json::Map<String, Value>>,
+    mode: SaveMode,
+    partition_columns: &[String],
+    predicate: Option<&Predicate>,
+    metrics: &WriteMetrics,
+) -> Result<Action, DeltaTableError> {
+    let mut commit_info = commit_info.unwrap_or_default();
+    commit_info.insert("operation".to_string(), json!("WRITE"));
+    let mut parameters = json!({
+        "mode": mode,
+        "partitionBy": serde_json::to_string(partition_columns)?,
+    });
+    if let Some(predicate) = predicate {
+        parameters["predicate"] = json!(predicate.to_string());
+    }
+    commit_info.insert("operationParameters".to_string(), parameters);
+    commit_info.insert("operationMetrics".to_string(), metrics.operation_metrics());
+    Ok(Action::commitInfo(commit_info))
+}
+
+/// A parquet file of a [`StreamingRecordBatchWriter`] that is still being written
+struct OpenFile {
+    buffer: ParquetBuffer,
+    stats: FileStats,
+}
+
+/// StreamingRecordBatchWriter writes arrow record batches to parquet files of a table as they
+/// arrive, instead of buffering all of them like [`RecordBatchWriter`]
+///
+/// One file is open per partition and uploaded once it reaches the target file size. The
+/// uploaded files are committed in a single transaction by
+/// [`StreamingRecordBatchWriter::close`], until then they are not part of the table and are
+/// deleted again by [`StreamingRecordBatchWriter::abort`].
+pub struct StreamingRecordBatchWriter {
+    inner: RecordBatchWriter,
+    target_file_size: u64,
+    mode: SaveMode,
+    /// The partitions replaced by an overwrite, all of them if None
+    predicate: Option<Predicate>,
+    /// The metadata committed with the files, for a new table or a schema change
+    metadata: Option<crate::DeltaTableMetaData>,
+    /// The protocol of a new table, which is created by the commit
+    protocol: Option<Protocol>,
+    open_files: HashMap<PartitionValues, OpenFile>,
+    uploaded: Vec<Add>,
+    partitions: HashSet<PartitionValues>,
+    metrics: FlushMetrics,
+    start: Instant,
+}
+
+impl StreamingRecordBatchWriter {
+    /// Attempt to construct the writer for the schema and partition columns of the table. Files
+    /// are rolled at the `delta.targetFileSize` of the table.
+    pub fn for_table(table: &crate::DeltaTable) -> Result<Self, DeltaTableError> {
+        Self::for_metadata(table.get_metadata()?)
+    }
+
+    /// Construct the writer for a table that is yet to be created with `metadata` and
+    /// `protocol`. The commit of [`StreamingRecordBatchWriter::close`] creates the table with the
+    /// written files as version 0. If another writer creates the table first with the same
+    /// schema and partition columns, the files are appended to that table instead.
+    pub fn for_new_table(
+        metadata: crate::DeltaTableMetaData,
+        protocol: Protocol,
+    ) -> Result<Self, DeltaTableError> {
+        check_column_names(&metadata)?;
+        let mut writer = Self::for_metadata(&metadata)?;
+        writer.mode = SaveMode::ErrorIfExists;
+        writer.metadata = Some(metadata);
+        writer.protocol = Some(protocol);
+        Ok(writer)
+    }
+
+    fn for_metadata(metadata: &crate::DeltaTableMetaData) -> Result<Self, DeltaTableError> {
+        let properties = TableProperties::from_metadata(metadata);
+        Ok(Self {
+            inner: RecordBatchWriter::for_metadata(metadata)?,
+            target_file_size: properties.target_file_size.max(1) as u64,
+            mode: SaveMode::Append,
+            predicate: None,
+            metadata: None,
+            protocol: None,
+            open_files: HashMap::new(),
+            uploaded: Vec::new(),
+            partitions: HashSet::new(),
+            metrics: FlushMetrics::default(),
+            start: Instant::now(),
+        })
+    }
+
+    /// Commit `metadata` together with the files, e.g. to change the schema of the table. The
+    /// batches are written with the schema and partition columns of `metadata`, so this has to be
+    /// set before the first batch is written.
+    pub fn with_metadata(
+        mut self,
+        metadata: crate::DeltaTableMetaData,
+    ) -> Result<Self, DeltaTableError> {
+        check_column_names(&metadata)?;
+        let control = self.inner.control.clone();
+        self.inner = RecordBatchWriter::for_metadata(&metadata)?.with_control(control);
+        self.metadata = Some(metadata);
+        Ok(self)
+    }
+
+    /// Replace the data of the table in the commit: all of its files are removed, or with a
+    /// `predicate` only the files of the partitions matching it. The predicate may only refer to
+    /// partition columns and all written rows have to match it. A new table has no files to
+    /// remove.
+    pub fn with_overwrite(mut self, predicate: Option<Predicate>) -> Self {
+        self.mode = SaveMode::Overwrite;
+        self.predicate = predicate;
+        self
+    }
+
+    /// Upload a file once it reaches `target_file_size` bytes and continue with a new one
+    pub fn with_target_file_size(mut self, target_file_size: u64) -> Self {
+        self.target_file_size = target_file_size.max(1);
+        self
+    }
+
+    /// Report the uploaded files to the progress callback of `control` and stop writing once
+    /// its cancellation token is cancelled
+    pub fn with_control(mut self, control: crate::OperationControl) -> Self {
+        self.inner = self.inner.with_control(control);
+        self
+    }
+
+    /// Return the number of rows written so far
+    pub fn count(&self) -> usize {
+        self.metrics.num_rows as usize
+            + self
+                .open_files
+                .values()
+                .map(|file| file.stats.num_records as usize)
+                .sum::<usize>()
+    }
+
+    /// Write a RecordBatch to the open files of its partitions, uploading the files that reach
+    /// the target file size to the storage of `table`
+    ///
+    /// The columns are matched like [`RecordBatchWriter::write`] does. After an error the writer
+    /// should be aborted.
+    pub async fn write(
+        &mut self,
+        table: &crate::DeltaTable,
+        batch: &RecordBatch,
+    ) -> Result<(), DeltaTableError> {
+        self.inner.control.check_cancelled()?;
+        for (values, batch) in self.inner.partition_batch(batch)? {
+            if !self.open_files.contains_key(&values) {
+                let file = OpenFile {
+                    buffer: ParquetBuffer::try_new(self.inner.file_schema.clone())?,
+                    stats: FileStats::default(),
+                };
+                self.open_files.insert(values.clone(), file);
+            }
+            let file = self.open_files.get_mut(&values).unwrap();
+            file.buffer.write_batch(&batch)?;
+            file.stats.add_batch(&batch);
+
+            if file.buffer.size()? >= self.target_file_size {
+                let file = self.open_files.remove(&values).unwrap();
+                self.upload(table, values, file).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn upload(
+        &mut self,
+        table: &crate::DeltaTable,
+        partition_values: PartitionValues,
+        mut file: OpenFile,
+    ) -> Result<(), DeltaTableError> {
+        self.inner.control.check_cancelled()?;
+        file.buffer.close()?;
+        let data = file.buffer.data();
+        self.metrics.num_files += 1;
+        self.metrics.num_rows += file.stats.num_records;
+        self.metrics.num_bytes += data.len() as i64;
+
+        let stats = serde_json::to_string(&file.stats.into_stats())?;
+        let add = table
+            .write_data_file(&data, partition_values.clone(), Some(stats))
+            .await?;
+        self.uploaded.push(add);
+        self.partitions.insert(partition_values);
+        self.inner.control.report(crate::ProgressEvent {
+            phase: crate::ProgressPhase::WriteFiles,
+            done: self.uploaded.len(),
+            total: self.uploaded.len() + self.open_files.len(),
+            bytes: Some(self.metrics.num_bytes),
+        });
+        Ok(())
+    }
+
+    /// Upload the remaining open files and commit all files in a single transaction, appending
+    /// them to `table` or replacing its data with [`StreamingRecordBatchWriter::with_overwrite`].
+    /// `table` is updated to the committed version.
+    ///
+    /// The entries of `commit_info` are added to the commitInfo action of the commit. If
+    /// anything fails before the commit, the uploaded files are deleted again.
+    pub async fn close(
+        mut self,
+        table: &mut crate::DeltaTable,
+        commit_info: Option<serde_json::Map<String, Value>>,
+        transaction_options: Option<crate::DeltaTransactionOptions>,
+    ) -> Result<WriteMetrics, DeltaTableError> {
+        let result = self.commit(table, commit_info, transaction_options).await;
+        match result {
+            Ok(metrics) => {
+                table.update().await?;
+                Ok(metrics)
+            }
+            // the files were committed, only the post-commit hooks failed
+            Err(err @ DeltaTableError::PostCommitHookFailed { .. }) => Err(err),
+            Err(err) => {
+                if let Err(abort_err) = self.abort(table).await {
+                    warn!("Deleting the uncommitted files failed: {}", abort_err);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Upload the remaining open files without committing them
+    pub async fn flush(&mut self, table: &crate::DeltaTable) -> Result<(), DeltaTableError> {
+        // the files are taken out one by one, so that the progress reports the remaining ones
+        let partitions = self.open_files.keys().cloned().collect::<Vec<_>>();
+        for values in partitions {
+            let file = self.open_files.remove(&values).unwrap();
+            self.upload(table, values, file).await?;
+        }
+        Ok(())
+    }
+
+    /// Like [`StreamingRecordBatchWriter::close`], but leaves deleting the files on failure to
+    /// [`StreamingRecordBatchWriter::abort`], e.g. for callers that may drop the commit midway.
+    /// `table` is not updated to the committed version, so a clone of the table can be passed to
+    /// commit while the original stays readable.
+    pub async fn commit(
+        &mut self,
+        table: &mut crate::DeltaTable,
+        commit_info: Option<serde_json::Map<String, Value>>,
+        transaction_options: Option<crate::DeltaTransactionOptions>,
+    ) -> Result<WriteMetrics, DeltaTableError> {
+        self.flush(table).await?;
+
+        let removes = if self.mode == SaveMode::Overwrite && self.protocol.is_none() {
+            self.overwritten_files(table)?
+        } else {
+            vec![]
+        };
+        let partition_columns = self.inner.partition_columns.clone();
+        let mut metrics = WriteMetrics {
+            // set once committed
+            version: -1,
+            num_added_files: self.metrics.num_files,
+            num_removed_files: removes.len() as i64,
+            num_added_bytes: self.metrics.num_bytes,
+            num_removed_bytes: removes.iter().map(|add| add.size).sum(),
+            num_output_rows: self.metrics.num_rows,
+            num_partitions: if partition_columns.is_empty() {
+                0
+            } else {
+                self.partitions.len() as i64
+            },
+            execution_time_ms: self.start.elapsed().as_millis() as i64,
+        };
+
+        let adds = self
+            .uploaded
+            .iter()
+            .map(|add| Action::add(add.clone()))
+            .collect::<Vec<_>>();
+        let mut actions = adds.clone();
+        let deletion_timestamp = chrono::Utc::now().timestamp_millis();
+        actions.extend(
+            removes
+                .iter()
+                .map(|add| remove_action(add, deletion_timestamp)),
+        );
+        if let Some(protocol) = &self.protocol {
+            actions.push(Action::protocol(protocol.clone()));
+        }
+        if let Some(metadata) = &self.metadata {
+            actions.push(Action::metaData(MetaData::try_from(metadata.clone())?));
+        }
+        actions.push(write_commit_info(
+            commit_info.clone(),
+            self.mode,
+            &partition_columns,
+            self.predicate.as_ref(),
+            &metrics,
+        )?);
+        let operation = DeltaOperation::Write {
+            mode: self.mode,
+            partitionBy: Some(partition_columns.clone()),
+            predicate: self.predicate.as_ref().map(Predicate::to_string),
+        };
+
+        self.inner.control.check_cancelled()?;
+        let mut transaction = table.create_transaction(transaction_options.clone());
+        transaction.add_actions(actions);
+        if self.protocol.is_none() {
+            metrics.version = transaction.commit(Some(operation)).await?;
+            return Ok(metrics);
+        }
+        match transaction.commit_version(0, Some(operation)).await {
+            Ok(version) => {
+                metrics.version = version;
+                return Ok(metrics);
+            }
+            Err(DeltaTableError::VersionAlreadyExists(_)) => {}
+            Err(err) => return Err(err),
+        }
+
+        debug!(
+            "The table {} was created concurrently, appending the files instead.",
+            table.table_uri
+        );
+        table.load().await?;
+        let existing = table.get_metadata()?;
+        let metadata = self.metadata.as_ref().unwrap();
+        if existing.schema != metadata.schema || existing.partition_columns != partition_columns {
+            return Err(DeltaTableError::SchemaMismatch {
+                msg: "The table was created concurrently with a different schema or partition columns"
+                    .to_string(),
+            });
+        }
+        let mut transaction = table.create_transaction(transaction_options);
+        transaction.add_actions(adds);
+        transaction.add_action(write_commit_info(
+            commit_info,
+            SaveMode::Append,
+            &partition_columns,
+            None,
+            &metrics,
+        )?);
+        metrics.version = transaction
+            .commit(Some(DeltaOperation::Write {
+                mode: SaveMode::Append,
+                partitionBy: Some(partition_columns),
+                predicate: None,
+            }))
+            .await?;
+        Ok(metrics)
+    }
+
+    /// The files removed by an overwrite, after checking that the written partitions match the
+    /// predicate of the overwrite
+    fn overwritten_files(&self, table: &crate::DeltaTable) -> Result<Vec<Add>, DeltaTableError> {
+        let state = table.get_state();
+        let column_resolution = table.config.column_resolution;
+        let files = match &self.predicate {
+            Some(predicate) => {
+                for values in &self.partitions {
+                    let values = values.iter().cloned().collect();
+                    if !predicate.matches_partition_values(state, column_resolution, &values)? {
+                        return Err(DeltaTableError::InvalidPredicate {
+                            predicate: predicate.to_string(),
+                            msg: format!(
+                                "the written partition {:?} does not match the predicate of the overwrite",
+                                values
+                            ),
+                        });
+                    }
+                }
+                predicate.partition_files(state, column_resolution)?
+            }
+            None => state.files().iter().collect(),
+        };
+        Ok(files.into_iter().cloned().collect())
+    }
+
+    /// Discard the open files and delete the files uploaded so far, leaving the table unchanged
+    pub async fn abort(&mut self, table: &crate::DeltaTable) -> Result<(), DeltaTableError> {
+        self.open_files.clear();
+        let paths = self
+            .uploaded
+            .drain(..)
+            .map(|add| table.storage.join_path(&table.table_uri, &add.path))
+            .collect::<Vec<_>>();
+        if !paths.is_empty() {
+            debug!("Deleting {} uncommitted files.", paths.len());
+            table.storage.delete_objs(&paths).await?;
+        }
+        Ok(())
+    }
+}
+
+fn stringified_partition_value(
+    array: &arrow::array::ArrayRef,
+    row: usize,
+) -> Result<Option<String>, arrow::error::ArrowError> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    let value = arrow::util::display::array_value_to_string(array, row)?;
+    // an empty string is stored as a null partition value
+    Ok(Some(value).filter(|v| !v.is_empty()))
+}
+
+/// Statistics of the rows written to a single parquet file
+#[derive(Default)]
+struct FileStats {
+    num_records: i64,
+    min_values: HashMap<String, Value>,
+    max_values: HashMap<String, Value>,
+    null_count: HashMap<String, i64>,
+    // columns with values that cannot be bounded, e.g. NaN
+    unbounded: HashSet<String>,
+    // unscaled minimum, maximum and scale of decimal columns, which are compared exactly and only
+    // formatted once the file is complete
+    decimal_bounds: HashMap<String, (i128, i128, usize)>,
+}
+
+impl FileStats {
+    fn add_batch(&mut self, batch: &RecordBatch) {
+        self.num_records += batch.num_rows() as i64;
+
+        for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+            let name = field.name();
+            *self.null_count.entry(name.clone()).or_default() += column.null_count() as i64;
+
+            if column.null_count() == column.len() || self.unbounded.contains(name) {
+                continue;
+            }
+            if let arrow::datatypes::DataType::Decimal(_, scale) = field.data_type() {
+                if let Some((lo, hi)) = decimal_min_max(column) {
+                    let bounds = self
+                        .decimal_bounds
+                        .entry(name.clone())
+                        .or_insert((lo, hi, *scale));
+                    bounds.0 = bounds.0.min(lo);
+                    bounds.1 = bounds.1.max(hi);
+                }
+                continue;
+            }
+            match min_max_values(column) {
+                Some((min, max)) => {
+                    match self.min_values.get(name) {
+                        Some(current) if !json_value_lt(&min, current) => {}
+                        _ => {
+                            self.min_values.insert(name.clone(), min);
+                        }
+                    }
+                    match self.max_values.get(name) {
+                        Some(current) if !json_value_lt(current, &max) => {}
+                        _ => {
+                            self.max_values.insert(name.clone(), max);
+                        }
+                    }
+                }
+                None => {
+                    self.min_values.remove(name);
+                    self.max_values.remove(name);
+                    self.unbounded.insert(name.clone());
+                }
+            }
+        }
+    }
+
+    fn into_stats(mut self) -> Stats {
+        for (name, (min, max, scale)) in self.decimal_bounds {
+            // decimals are written as strings, JSON numbers would be read back as f64
+            let as_value =
+                |unscaled| Value::String(decimal_utils::decimal_to_stats_string(unscaled, scale));
+            self.min_values.insert(name.clone(), as_value(min));
+            self.max_values.insert(name, as_value(max));
+        }
+
+        let as_value_stats = |values: HashMap<String, Value>| {
+            values
+                .into_iter()
+                .map(|(name, value)| (name, ColumnValueStat::Value(value)))
+                .collect()
+        };
+
+        Stats {
+            num_records: self.num_records,
+            min_values: as_value_stats(self.min_values),
+            max_values: as_value_stats(self.max_values),
+            null_count: self
+                .null_count
+                .into_iter()
+                .map(|(name, count)| (name, ColumnCountStat::Value(count)))
+                .collect(),
+        }
+    }
+}
+
+/// Returns the minimum and maximum of the non-null values of the array as stats values, or None
+/// if the type is not supported or the values cannot be bounded
+fn min_max_values(array: &arrow::array::ArrayRef) -> Option<(Value, Value)> {
+    use arrow::array::*;
+    use arrow::compute::{max, max_string, min, min_string};
+    use arrow::datatypes::DataType;
+
+    macro_rules! primitive_min_max {
+        ($array_type:ty) => {{
+            let array = array.as_any().downcast_ref::<$array_type>()?;
+            Some((json!(min(array)?), json!(max(array)?)))
+        }};
+    }
+
+    macro_rules! float_min_max {
+        ($array_type:ty) => {{
+            let array = array.as_any().downcast_ref::<$array_type>()?;
+            let (lo, hi) = (min(array)?, max(array)?);
+            if !lo.is_finite() || !hi.is_finite() {
+                return None;
+            }
+            Some((json!(lo), json!(hi)))
+        }};
+    }
+
+    match array.data_type() {
+        DataType::Int8 => primitive_min_max!(Int8Array),
+        DataType::Int16 => primitive_min_max!(Int16Array),
+        DataType::Int32 => primitive_min_max!(Int32Array),
+        DataType::Int64 => primitive_min_max!(Int64Array),
+        DataType::Float32 => float_min_max!(Float32Array),
+        DataType::Float64 => float_min_max!(Float64Array),
+        DataType::Utf8 => {
+            let array = array.as_any().downcast_ref::<StringArray>()?;
+            Some((json!(min_string(array)?), json!(max_string(array)?)))
+        }
+        DataType::Date32 => {
+            let array = array.as_any().downcast_ref::<Date32Array>()?;
+            let as_date = |days: i32| {
+                json!(arrow::temporal_conversions::date32_to_datetime(days)
+                    .date()
+                    .format("%Y-%m-%d")
+                    .to_string())
+            };
+            Some((as_date(min(array)?), as_date(max(array)?)))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the unscaled minimum and maximum of the non-null values of a decimal array
+fn decimal_min_max(array: &arrow::array::ArrayRef) -> Option<(i128, i128)> {
+    let array = array
+        .as_any()
+        .downcast_ref::<arrow::array::DecimalArray>()?;
+    (0..array.len())
+        .filter(|i| array.is_valid(*i))
+        .map(|i| array.value(i))
+        .fold(None, |bounds, value| match bounds {
+            None => Some((value, value)),
+            Some((lo, hi)) => Some((lo.min(value), hi.max(value))),
+        })
+}
+
+fn json_value_lt(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a < b,
+            _ => a.as_f64() < b.as_f64(),
+        },
+        (Value::String(a), Value::String(b)) => a < b,
+        _ => false,
+    }
+}
+
 /// Utility functions for handling timestamps
 pub mod time_utils {
     use arrow::temporal_conversions;
@@ -235,6 +1317,114 @@ pub mod time_utils {
     }
 }
 
+/// Utility functions for handling decimals
+pub mod decimal_utils {
+    use serde_json::Value;
+
+    /// Parse a decimal min or max value from JSON statistics into its unscaled representation at
+    /// the given scale. Writers store decimals as JSON numbers or as strings, both are parsed
+    /// from their textual form so no precision is lost to f64. Digits beyond `scale` are rounded
+    /// down, or up if `round_up` is set, so the result still bounds the column. Returns `None`
+    /// if the value is not a decimal or needs more than `precision` digits.
+    pub fn decimal_from_stats_value(
+        value: &Value,
+        precision: usize,
+        scale: usize,
+        round_up: bool,
+    ) -> Option<i128> {
+        match value {
+            Value::Number(n) => {
+                decimal_from_stats_string(&n.to_string(), precision, scale, round_up)
+            }
+            Value::String(s) => decimal_from_stats_string(s.trim(), precision, scale, round_up),
+            _ => None,
+        }
+    }
+
+    /// Parse a decimal literal such as `-12.345` or `1.2345E+3` into its unscaled
+    /// representation at the given scale, see [`decimal_from_stats_value`].
+    pub fn decimal_from_stats_string(
+        s: &str,
+        precision: usize,
+        scale: usize,
+        round_up: bool,
+    ) -> Option<i128> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (mantissa, exponent) = match s.find(|c| c == 'e' || c == 'E') {
+            Some(i) => (&s[..i], s[i + 1..].parse::<i64>().ok()?),
+            None => (s, 0),
+        };
+        let (int_part, frac_part) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+            None => (mantissa, ""),
+        };
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !int_part
+                .chars()
+                .chain(frac_part.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        // The value is `digits * 10^shift` in units of the scale.
+        let digits = [int_part, frac_part].concat();
+        let digits = digits.trim_start_matches('0');
+        if digits.is_empty() {
+            // zero, whatever the exponent
+            return Some(0);
+        }
+        let shift = exponent
+            .checked_add(scale as i64)?
+            .checked_sub(frac_part.len() as i64)?;
+
+        let (kept, dropped) = if shift >= 0 {
+            if digits.len() as i64 + shift > precision as i64 {
+                return None;
+            }
+            (format!("{}{}", digits, "0".repeat(shift as usize)), "")
+        } else {
+            let split = (digits.len() as i64 + shift).max(0) as usize;
+            (digits[..split].to_string(), &digits[split..])
+        };
+        if kept.len() > precision {
+            return None;
+        }
+
+        let mut unscaled = if kept.is_empty() {
+            0
+        } else {
+            kept.parse::<i128>().ok()?
+        };
+        // Dropping digits rounded the magnitude towards zero, step away from zero when the
+        // requested direction requires it.
+        if dropped.chars().any(|c| c != '0') && round_up != negative {
+            unscaled += 1;
+            if unscaled >= 10i128.checked_pow(precision as u32)? {
+                return None;
+            }
+        }
+
+        Some(if negative { -unscaled } else { unscaled })
+    }
+
+    /// Format an unscaled decimal value at the given scale as a string for JSON statistics.
+    pub fn decimal_to_stats_string(unscaled: i128, scale: usize) -> String {
+        let sign = if unscaled < 0 { "-" } else { "" };
+        let digits = unscaled.unsigned_abs().to_string();
+        if scale == 0 {
+            return format!("{}{}", sign, digits);
+        }
+
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+        format!("{}{}.{}", sign, int_part, frac_part)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +1456,151 @@ mod tests {
         assert_eq!(1628685199541000i64, us);
     }
 
+    #[test]
+    fn test_decimal_stats_round_trip() {
+        let cases = vec![
+            (
+                "9999999999999999999999999999.9999999999",
+                99999999999999999999999999999999999999i128,
+            ),
+            (
+                "-9999999999999999999999999999.9999999999",
+                -99999999999999999999999999999999999999i128,
+            ),
+            (
+                "12345678901234567890.0000000001",
+                123456789012345678900000000001,
+            ),
+            ("0.0000000001", 1),
+            ("0.0000000000", 0),
+            ("-1.5000000000", -15000000000),
+        ];
+        for (s, unscaled) in cases {
+            let parsed = decimal_utils::decimal_from_stats_value(&json!(s), 38, 10, false);
+            assert_eq!(Some(unscaled), parsed);
+            assert_eq!(s, decimal_utils::decimal_to_stats_string(unscaled, 10));
+        }
+
+        assert_eq!("-12", decimal_utils::decimal_to_stats_string(-12, 0));
+        assert_eq!("0.05", decimal_utils::decimal_to_stats_string(5, 2));
+    }
+
+    #[test]
+    fn test_decimal_from_stats_value() {
+        use decimal_utils::decimal_from_stats_value;
+
+        // JSON numbers and exponent notation
+        assert_eq!(
+            Some(15000000000000),
+            decimal_from_stats_value(&json!(1500), 38, 10, false)
+        );
+        assert_eq!(
+            Some(15000000000000),
+            decimal_from_stats_value(&json!(1.5e3), 38, 10, false)
+        );
+        assert_eq!(
+            Some(-1230),
+            decimal_from_stats_value(&json!("-1.23E-7"), 38, 10, false)
+        );
+
+        // Digits beyond the scale are rounded in the requested direction
+        assert_eq!(
+            Some(0),
+            decimal_from_stats_value(&json!("0.00000000005"), 38, 10, false)
+        );
+        assert_eq!(
+            Some(1),
+            decimal_from_stats_value(&json!("0.00000000005"), 38, 10, true)
+        );
+        assert_eq!(
+            Some(-1),
+            decimal_from_stats_value(&json!("-0.00000000005"), 38, 10, false)
+        );
+        assert_eq!(
+            Some(0),
+            decimal_from_stats_value(&json!("-0.00000000005"), 38, 10, true)
+        );
+
+        // Values exceeding the precision or not being decimals
+        assert_eq!(
+            None,
+            decimal_from_stats_value(&json!("100000.00"), 7, 2, false)
+        );
+        assert_eq!(
+            None,
+            decimal_from_stats_value(&json!("99999.995"), 7, 2, true)
+        );
+        assert_eq!(
+            None,
+            decimal_from_stats_value(&json!("1e400"), 38, 10, false)
+        );
+        assert_eq!(None, decimal_from_stats_value(&json!("NaN"), 38, 10, false));
+        assert_eq!(None, decimal_from_stats_value(&json!(true), 38, 10, false));
+
+        // Huge exponents are bounded by the precision instead of being expanded
+        assert_eq!(
+            Some(0),
+            decimal_from_stats_value(&json!("0e999999999999999"), 38, 10, false)
+        );
+        assert_eq!(
+            Some(0),
+            decimal_from_stats_value(&json!("-0.000e-999999999999999"), 38, 10, true)
+        );
+        assert_eq!(
+            None,
+            decimal_from_stats_value(&json!("1e999999999999999"), 38, 10, false)
+        );
+        assert_eq!(
+            Some(0),
+            decimal_from_stats_value(&json!("1e-999999999999999"), 38, 10, false)
+        );
+        assert_eq!(
+            Some(1),
+            decimal_from_stats_value(&json!("1e-999999999999999"), 38, 10, true)
+        );
+    }
+
+    #[test]
+    fn test_file_stats_of_decimal_columns() {
+        use arrow::array::DecimalBuilder;
+        use arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "d",
+            DataType::Decimal(38, 10),
+            true,
+        )]));
+        let batch = |values: &[Option<i128>]| {
+            let mut builder = DecimalBuilder::new(values.len(), 38, 10);
+            for value in values {
+                match value {
+                    Some(value) => builder.append_value(*value).unwrap(),
+                    None => builder.append_null().unwrap(),
+                }
+            }
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(builder.finish())]).unwrap()
+        };
+
+        // bounds differing beyond f64 precision, compared across batches
+        let mut stats = FileStats::default();
+        stats.add_batch(&batch(&[Some(123456789012345678900000000001), None]));
+        stats.add_batch(&batch(&[
+            Some(-15000000000),
+            Some(123456789012345678900000000002),
+        ]));
+        let stats = stats.into_stats();
+
+        assert_eq!(
+            stats.min_values["d"].as_value(),
+            Some(&json!("-1.5000000000"))
+        );
+        assert_eq!(
+            stats.max_values["d"].as_value(),
+            Some(&json!("12345678901234567890.0000000002"))
+        );
+        assert_eq!(stats.null_count["d"].as_value(), Some(1));
+    }
+
     #[tokio::test]
     async fn test_writer_buffer_nopartition() {
         let table = crate::open_table("./tests/data/delta-0.8.0").await.unwrap();
@@ -0,0 +1,166 @@
+extern crate deltalake;
+
+use deltalake::arrow::array::{
+    Array, Date32Array, DecimalArray, Int32Array, Int64Array, StringArray, StructArray,
+    TimestampMicrosecondArray,
+};
+use deltalake::arrow::datatypes::{DataType, TimeUnit};
+use deltalake::arrow::record_batch::RecordBatch;
+
+fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> &'a T {
+    let index = batch.schema().index_of(name).unwrap();
+    batch.column(index).as_any().downcast_ref::<T>().unwrap()
+}
+
+#[tokio::test]
+async fn add_actions_table_flattened() {
+    let table = deltalake::open_table("./tests/data/table_with_typed_stats")
+        .await
+        .unwrap();
+    let batch = table.get_state().add_actions_table(true).unwrap();
+
+    let names: Vec<String> = batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "path",
+            "size_bytes",
+            "modification_time",
+            "data_change",
+            "partition_values.date",
+            "num_records",
+            "null_count.id",
+            "null_count.ts",
+            "null_count.amount",
+            "null_count.address.city",
+            "null_count.address.zip",
+            "min.id",
+            "min.ts",
+            "min.amount",
+            "min.address.city",
+            "min.address.zip",
+            "max.id",
+            "max.ts",
+            "max.amount",
+            "max.address.city",
+            "max.address.zip",
+        ]
+    );
+    assert_eq!(batch.num_rows(), 3);
+
+    let dates = column::<Date32Array>(&batch, "partition_values.date");
+    assert_eq!((dates.value(0), dates.value(1)), (18628, 18629));
+    assert!(dates.is_null(2));
+    let sizes = column::<Int64Array>(&batch, "size_bytes");
+    assert_eq!(sizes.values(), &[1200, 1300, 1400]);
+
+    // the second file has truncated statistics and the third none
+    let num_records = column::<Int64Array>(&batch, "num_records");
+    assert_eq!(num_records.value(0), 2);
+    assert!(num_records.is_null(1) && num_records.is_null(2));
+    let null_counts = column::<Int64Array>(&batch, "null_count.address.zip");
+    assert_eq!(null_counts.value(0), 1);
+    assert!(null_counts.is_null(1) && null_counts.is_null(2));
+
+    let min_ts = column::<TimestampMicrosecondArray>(&batch, "min.ts");
+    assert_eq!(min_ts.value(0), 1609459200000000);
+    let max_ts = column::<TimestampMicrosecondArray>(&batch, "max.ts");
+    assert_eq!(max_ts.value(0), 1609590600123000);
+    // decimals with more digits than the scale are rounded outwards
+    let min_amount = column::<DecimalArray>(&batch, "min.amount");
+    assert_eq!(min_amount.value(0), 100);
+    let max_amount = column::<DecimalArray>(&batch, "max.amount");
+    assert_eq!(max_amount.value(0), 1235);
+    assert!(max_amount.is_null(1));
+    let min_city = column::<StringArray>(&batch, "min.address.city");
+    assert_eq!(min_city.value(0), "Berlin");
+    let max_zip = column::<Int32Array>(&batch, "max.address.zip");
+    assert_eq!(max_zip.value(0), 75001);
+}
+
+#[tokio::test]
+async fn add_actions_table_nested() {
+    let table = deltalake::open_table("./tests/data/table_with_typed_stats")
+        .await
+        .unwrap();
+    let batch = table.get_state().add_actions_table(false).unwrap();
+
+    let names: Vec<String> = batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "path",
+            "size_bytes",
+            "modification_time",
+            "data_change",
+            "partition_values",
+            "num_records",
+            "null_count",
+            "min",
+            "max",
+        ]
+    );
+
+    let min = column::<StructArray>(&batch, "min");
+    let field_types: Vec<(String, DataType)> = match min.data_type() {
+        DataType::Struct(fields) => fields
+            .iter()
+            .map(|field| (field.name().clone(), field.data_type().clone()))
+            .collect(),
+        other => panic!("expected a struct, got {:?}", other),
+    };
+    assert_eq!(
+        field_types[..3],
+        [
+            ("id".to_string(), DataType::Int64),
+            (
+                "ts".to_string(),
+                DataType::Timestamp(TimeUnit::Microsecond, None)
+            ),
+            ("amount".to_string(), DataType::Decimal(10, 2)),
+        ]
+    );
+    let address = min
+        .column_by_name("address")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    let zip = address
+        .column_by_name("zip")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(zip.value(0), 10115);
+    assert!(zip.is_null(1));
+}
+
+#[tokio::test]
+async fn add_actions_table_without_partitions() {
+    let table = deltalake::open_table("./tests/data/delta-0.8.0")
+        .await
+        .unwrap();
+    let batch = table.get_state().add_actions_table(false).unwrap();
+
+    // unpartitioned tables have no partition values column
+    assert!(batch.schema().index_of("partition_values").is_err());
+    let max = column::<StructArray>(&batch, "max");
+    let values = max
+        .column_by_name("value")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(values.len(), table.get_files().len());
+}
@@ -0,0 +1,197 @@
+extern crate deltalake;
+
+use arrow::array::{Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use deltalake::action::{Action, SaveMode};
+use deltalake::audit::{AuditCheck, AuditOptions, AuditReport, AuditStatus};
+use deltalake::checkpoints;
+use deltalake::writer::write_batches;
+use deltalake::DeltaTable;
+use serde_json::json;
+use std::path::Path;
+use std::sync::Arc;
+
+#[allow(dead_code)]
+mod fs_common;
+
+fn batch(ids: Vec<i64>, modified: Vec<&str>) -> RecordBatch {
+    let schema = ArrowSchema::new(vec![
+        Field::new("id", DataType::Int64, true),
+        Field::new("modified", DataType::Utf8, true),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int64Array::from(ids)),
+            Arc::new(StringArray::from(modified)),
+        ],
+    )
+    .unwrap()
+}
+
+async fn create_table(path: &str) -> DeltaTable {
+    let mut table = fs_common::create_table_from_json(
+        path,
+        json!({
+            "type": "struct",
+            "fields": [
+                {"name":"id","type":"long","metadata":{},"nullable":true},
+                {"name":"modified","type":"string","metadata":{},"nullable":true},
+            ]
+        }),
+        vec!["modified"],
+        json!({}),
+    )
+    .await;
+    write_batches(
+        &mut table,
+        &[batch(
+            vec![1, 2, 3],
+            vec!["2021-02-01", "2021-02-02", "2021-02-03"],
+        )],
+        SaveMode::Append,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    table
+}
+
+async fn audit(table: &DeltaTable) -> AuditReport {
+    table.audit(&AuditOptions::default()).await.unwrap()
+}
+
+fn assert_failed(report: &AuditReport, check: AuditCheck, paths: &[&str]) {
+    let result = report.get(check).unwrap();
+    assert_eq!(result.status, AuditStatus::Failed, "{:?}", result);
+    assert_eq!(result.num_failures, paths.len());
+    assert_eq!(result.sample, paths);
+    assert!(!report.is_healthy());
+}
+
+#[tokio::test]
+async fn audit_healthy_table() {
+    let table = create_table("./tests/data/audit_healthy").await;
+    let report = audit(&table).await;
+
+    assert!(report.is_healthy(), "{:?}", report);
+    assert_eq!(report.version, 1);
+    assert_eq!(report.checks.len(), 6);
+    assert_eq!(
+        report.get(AuditCheck::CheckpointMatchesLog).unwrap().status,
+        AuditStatus::Skipped
+    );
+    assert_eq!(
+        report.get(AuditCheck::FileSizesMatch).unwrap().status,
+        AuditStatus::Passed
+    );
+}
+
+#[tokio::test]
+async fn audit_reports_missing_file() {
+    let path = "./tests/data/audit_missing_file";
+    let table = create_table(path).await;
+    let removed = table.get_files()[0].to_string();
+    std::fs::remove_file(Path::new(path).join(&removed)).unwrap();
+
+    let report = audit(&table).await;
+    assert_failed(&report, AuditCheck::FilesExist, &[&removed]);
+    assert_eq!(
+        report.get(AuditCheck::FileSizesMatch).unwrap().status,
+        AuditStatus::Passed
+    );
+}
+
+#[tokio::test]
+async fn audit_reports_tampered_size() {
+    let path = "./tests/data/audit_tampered_size";
+    let table = create_table(path).await;
+    let tampered = table.get_files()[1].to_string();
+    let file_path = Path::new(path).join(&tampered);
+    let mut content = std::fs::read(&file_path).unwrap();
+    content.extend_from_slice(b"garbage");
+    std::fs::write(&file_path, content).unwrap();
+
+    let report = audit(&table).await;
+    assert_failed(&report, AuditCheck::FileSizesMatch, &[&tampered]);
+    assert_eq!(
+        report.get(AuditCheck::FilesExist).unwrap().status,
+        AuditStatus::Passed
+    );
+}
+
+#[tokio::test]
+async fn audit_reports_duplicate_add() {
+    let path = "./tests/data/audit_duplicate_add";
+    let mut table = create_table(path).await;
+    let duplicate = table.get_state().files()[0].clone();
+    fs_common::commit_actions(&mut table, vec![Action::add(duplicate.clone())]).await;
+
+    let report = audit(&table).await;
+    assert_failed(&report, AuditCheck::UniqueAddPaths, &[&duplicate.path]);
+}
+
+#[tokio::test]
+async fn audit_reports_checkpoint_differing_from_log() {
+    let path = "./tests/data/audit_checkpoint";
+    let table = create_table(path).await;
+    checkpoints::create_checkpoint(&table).await.unwrap();
+    let report = audit(&table).await;
+    assert!(report.is_healthy(), "{:?}", report);
+    assert_eq!(
+        report.get(AuditCheck::CheckpointMatchesLog).unwrap().status,
+        AuditStatus::Passed
+    );
+
+    // rewrite the size of one add in the commit covered by the checkpoint
+    let tampered = table.get_state().files()[0].clone();
+    let commit_path = Path::new(path).join("_delta_log/00000000000000000001.json");
+    let commit = std::fs::read_to_string(&commit_path)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            if line.contains(&tampered.path) {
+                line.replace(
+                    &format!("\"size\":{}", tampered.size),
+                    &format!("\"size\":{}", tampered.size + 1),
+                )
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(&commit_path, commit).unwrap();
+
+    let report = audit(&table).await;
+    assert_failed(&report, AuditCheck::CheckpointMatchesLog, &[&tampered.path]);
+    assert!(report
+        .get(AuditCheck::CheckpointMatchesLog)
+        .unwrap()
+        .message
+        .as_ref()
+        .unwrap()
+        .contains("files"));
+}
+
+#[tokio::test]
+async fn audit_sample_is_bounded() {
+    let path = "./tests/data/audit_sample";
+    let table = create_table(path).await;
+    for file in table.get_files() {
+        std::fs::remove_file(Path::new(path).join(file)).unwrap();
+    }
+
+    let options = AuditOptions {
+        max_concurrent_requests: 2,
+        max_sample_size: 1,
+    };
+    let report = table.audit(&options).await.unwrap();
+    let result = report.get(AuditCheck::FilesExist).unwrap();
+    assert_eq!(result.status, AuditStatus::Failed);
+    assert_eq!(result.num_failures, 3);
+    assert_eq!(result.sample.len(), 1);
+}
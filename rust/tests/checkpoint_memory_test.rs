@@ -0,0 +1,128 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use deltalake::checkpoints;
+
+/// Allocator tracking the current and peak number of allocated bytes of the test binary.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Number of files of the synthetic table, override with `CHECKPOINT_MEMORY_TEST_FILES`
+const NUM_FILES: usize = 20_000;
+
+fn num_files() -> usize {
+    std::env::var("CHECKPOINT_MEMORY_TEST_FILES")
+        .map(|files| files.parse().unwrap())
+        .unwrap_or(NUM_FILES)
+}
+
+fn write_synthetic_table(path: &Path, num_files: usize) {
+    let log_path = path.join("_delta_log");
+    fs::create_dir_all(&log_path).unwrap();
+
+    let mut writer =
+        BufWriter::new(File::create(log_path.join("00000000000000000000.json")).unwrap());
+    writeln!(
+        writer,
+        r#"{{"protocol":{{"minReaderVersion":1,"minWriterVersion":2}}}}"#
+    )
+    .unwrap();
+    writeln!(
+        writer,
+        r#"{{"metaData":{{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{{\"name\":\"id\",\"type\":\"long\",\"nullable\":true,\"metadata\":{{}}}}]}}","partitionColumns":[],"configuration":{{}},"createdTime":1587968585495}}}}"#
+    )
+    .unwrap();
+    for i in 0..num_files {
+        writeln!(
+            writer,
+            r#"{{"add":{{"path":"part-{:08}.snappy.parquet","partitionValues":{{}},"size":1024,"modificationTime":1587968586000,"dataChange":true,"stats":"{{\"numRecords\":10,\"minValues\":{{\"id\":{}}},\"maxValues\":{{\"id\":{}}},\"nullCount\":{{\"id\":0}}}}"}}}}"#,
+            i,
+            i * 10,
+            i * 10 + 9
+        )
+        .unwrap();
+    }
+    writer.flush().unwrap();
+}
+
+// Generating and replaying a million actions is slow in debug builds, run the full size with
+// `CHECKPOINT_MEMORY_TEST_FILES=1000000 cargo test --release --test checkpoint_memory_test`.
+#[tokio::test]
+async fn checkpoint_large_table_with_bounded_memory() {
+    let tmp_dir = tempdir::TempDir::new("checkpoint_memory_test").unwrap();
+    let path = tmp_dir.path();
+    let num_files = num_files();
+    write_synthetic_table(path, num_files);
+
+    let table = deltalake::open_table(path.to_str().unwrap()).await.unwrap();
+    assert_eq!(num_files, table.get_files().len());
+
+    let config = checkpoints::CheckpointConfig {
+        record_batch_size: 1000,
+        max_part_size: 1024 * 1024,
+        ..Default::default()
+    };
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    checkpoints::create_checkpoint_with_config(&table, &config)
+        .await
+        .unwrap();
+    let peak_delta = PEAK.load(Ordering::SeqCst) - baseline;
+
+    // Smoke level bound: only a single part of at most 1 MiB of actions is buffered at a time,
+    // independent of the number of files.
+    assert!(
+        peak_delta < 32 * 1024 * 1024,
+        "checkpoint used {} bytes at peak",
+        peak_delta
+    );
+
+    let log_path = path.join("_delta_log");
+    let last_checkpoint: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(log_path.join("_last_checkpoint")).unwrap())
+            .unwrap();
+    let parts = last_checkpoint["parts"].as_i64().unwrap();
+    assert!(parts > 1);
+    for part in 1..=parts {
+        assert!(log_path
+            .join(format!(
+                "00000000000000000000.checkpoint.{:010}.{:010}.parquet",
+                part, parts
+            ))
+            .exists());
+    }
+
+    // Drop the commit so that the table can only be restored from the checkpoint.
+    fs::remove_file(log_path.join("00000000000000000000.json")).unwrap();
+    let reloaded = deltalake::open_table(path.to_str().unwrap()).await.unwrap();
+    assert_eq!(0, reloaded.version);
+    assert_eq!(table.get_files(), reloaded.get_files());
+    assert_eq!(
+        table.get_state().current_metadata().unwrap().schema,
+        reloaded.get_state().current_metadata().unwrap().schema
+    );
+}
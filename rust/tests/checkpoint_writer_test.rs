@@ -96,6 +96,126 @@ mod simple_checkpoint {
     }
 }
 
+mod multi_part_checkpoint {
+    use super::*;
+
+    #[tokio::test]
+    async fn multi_part_checkpoint_test() {
+        let mut table = fs_common::create_table("./tests/data/checkpoints_multi_part", None).await;
+        let log_path = Path::new(&table.table_uri).join("_delta_log");
+
+        let adds: Vec<Add> = (0..10).map(|_| fs_common::add(0)).collect();
+        let actions = adds.iter().map(|a| Action::add(a.clone())).collect();
+        fs_common::commit_actions(&mut table, actions).await;
+        let removes: Vec<Remove> = adds[..2]
+            .iter()
+            .map(|a| Remove {
+                path: a.path.clone(),
+                deletion_timestamp: Some(Utc::now().timestamp_millis()),
+                data_change: true,
+                ..Default::default()
+            })
+            .collect();
+        fs_common::commit_removes(&mut table, removes.iter().collect()).await;
+
+        // protocol + metaData + 2 removes + 8 adds = 12 actions, written in parts of 3 actions
+        let config = checkpoints::CheckpointConfig {
+            record_batch_size: 2,
+            max_actions_per_part: 3,
+            ..Default::default()
+        };
+        checkpoints::create_checkpoint_with_config(&table, &config)
+            .await
+            .unwrap();
+
+        let last_checkpoint: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(log_path.join("_last_checkpoint")).unwrap())
+                .unwrap();
+        assert_eq!(Some(2), last_checkpoint["version"].as_i64());
+        assert_eq!(Some(4), last_checkpoint["parts"].as_i64());
+        assert!(!log_path
+            .join("00000000000000000002.checkpoint.parquet")
+            .exists());
+
+        let mut num_actions = 0;
+        for part in 1..=4 {
+            let part_path = log_path.join(format!(
+                "00000000000000000002.checkpoint.{:010}.0000000004.parquet",
+                part
+            ));
+            let (_, actions) = fs_common::read_checkpoint(part_path.to_str().unwrap()).await;
+            assert_eq!(3, actions.len());
+            num_actions += actions.len();
+        }
+        assert_eq!(12, num_actions);
+
+        // the multi-part checkpoint parses back to the same snapshot
+        let reloaded = deltalake::open_table(&table.table_uri).await.unwrap();
+        assert_eq!(2, reloaded.version);
+        assert_eq!(
+            table.get_files_iter().collect::<HashSet<_>>(),
+            reloaded.get_files_iter().collect::<HashSet<_>>()
+        );
+        let tombstone_paths = |t: &DeltaTable| {
+            t.get_state()
+                .all_tombstones()
+                .iter()
+                .map(|r| r.path.clone())
+                .collect::<HashSet<_>>()
+        };
+        assert_eq!(tombstone_paths(&table), tombstone_paths(&reloaded));
+    }
+}
+
+mod checkpoint_with_non_finite_stats {
+    use super::*;
+
+    #[tokio::test]
+    async fn checkpoint_with_nan_stats_test() {
+        let table_location = "./tests/data/checkpoints_nan_stats";
+        let log_path = Path::new(table_location).join("_delta_log");
+        fs::create_dir_all(&log_path).unwrap();
+        fs_common::cleanup_dir_except(&log_path, vec![]);
+        fs::copy(
+            "./tests/data/table_with_nan_stats/_delta_log/00000000000000000000.json",
+            log_path.join("00000000000000000000.json"),
+        )
+        .unwrap();
+
+        let table = deltalake::open_table(table_location).await.unwrap();
+        checkpoints::create_checkpoint(&table).await.unwrap();
+
+        let (_, actions) = fs_common::read_checkpoint(
+            log_path
+                .join("00000000000000000000.checkpoint.parquet")
+                .to_str()
+                .unwrap(),
+        )
+        .await;
+        let adds: Vec<&Add> = actions
+            .iter()
+            .filter_map(|a| match a {
+                Action::add(add) => Some(add),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(3, adds.len());
+
+        // the raw stats are kept as written
+        for add in adds {
+            let original = table
+                .get_active_add_actions()
+                .iter()
+                .find(|a| a.path == add.path)
+                .unwrap();
+            assert_eq!(original.stats, add.stats);
+        }
+
+        let reloaded = deltalake::open_table(table_location).await.unwrap();
+        assert_eq!(table.get_files(), reloaded.get_files());
+    }
+}
+
 mod delete_expired_delta_log_in_checkpoint {
     use super::*;
 
@@ -385,3 +505,34 @@ mod checkpoints_with_tombstones {
         }
     }
 }
+
+mod load_from_checkpoint {
+    use super::*;
+    use deltalake::storage::metrics::{MetricsStorageBackend, StorageOperation};
+
+    #[tokio::test]
+    async fn load_skips_commits_before_checkpoint() {
+        let tmp_dir = tempdir::TempDir::new("load_from_checkpoint").unwrap();
+        let table_uri = tmp_dir.path().to_str().unwrap();
+        let mut table = fs_common::create_table(table_uri, None).await;
+        for _ in 0..50 {
+            fs_common::commit_add(&mut table, &fs_common::add(0)).await;
+        }
+
+        // without a checkpoint no commit may be deleted
+        assert_eq!(checkpoints::cleanup_metadata(&table).await.unwrap(), 0);
+        checkpoints::create_checkpoint(&table).await.unwrap();
+
+        let backend = MetricsStorageBackend::new(storage::get_backend_for_uri(table_uri).unwrap());
+        let metrics = backend.metrics();
+        let mut reloaded =
+            DeltaTable::new(table_uri, Box::new(backend), DeltaTableConfig::default()).unwrap();
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.version, 50);
+        assert_eq!(reloaded.get_files().len(), 50);
+
+        // at most _last_checkpoint, the checkpoint and the probe for version 51 are read
+        assert!(metrics.get(StorageOperation::Get).calls <= 3);
+        assert_eq!(metrics.get(StorageOperation::List).calls, 0);
+    }
+}
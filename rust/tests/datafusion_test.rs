@@ -121,4 +121,61 @@ mod datafusion {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_datafusion_stats_with_nan_bounds() -> Result<()> {
+        let table = deltalake::open_table("./tests/data/table_with_nan_stats")
+            .await
+            .unwrap();
+        let statistics = table.datafusion_table_statistics();
+        let column_statistics = statistics.column_statistics.unwrap();
+
+        assert_eq!(statistics.num_rows, Some(6));
+        // `id` is bounded in every file
+        assert_eq!(
+            column_statistics[0].min_value,
+            Some(ScalarValue::from(0i64))
+        );
+        assert_eq!(
+            column_statistics[0].max_value,
+            Some(ScalarValue::from(5i64))
+        );
+        // NaN and infinite bounds must not be used for pruning
+        assert_eq!(column_statistics[1].min_value, None);
+        assert_eq!(column_statistics[1].max_value, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_datafusion_stats_with_decimal_bounds() -> Result<()> {
+        let table = deltalake::open_table("./tests/data/table_with_decimal_stats")
+            .await
+            .unwrap();
+        let statistics = table.datafusion_table_statistics();
+        let column_statistics = statistics.column_statistics.unwrap();
+
+        // Bounds are exact at the column's scale, the max of the second file is rounded up.
+        let min = 99999999999999990000000001i128;
+        let max = 99999999999999990000000010i128;
+        assert_eq!(
+            column_statistics[1].min_value,
+            Some(ScalarValue::Decimal128(Some(min), 38, 10))
+        );
+        assert_eq!(
+            column_statistics[1].max_value,
+            Some(ScalarValue::Decimal128(Some(max), 38, 10))
+        );
+
+        // As doubles the min and the literal both round to 1e16, so `amount < 9999999999999999.5`
+        // would wrongly skip the table. The exact bound keeps it.
+        let literal = 99999999999999995000000000i128;
+        assert!(min < literal);
+        assert!(
+            "9999999999999999.0000000001".parse::<f64>().unwrap()
+                >= "9999999999999999.5".parse::<f64>().unwrap()
+        );
+
+        Ok(())
+    }
 }
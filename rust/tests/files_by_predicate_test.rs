@@ -0,0 +1,164 @@
+extern crate deltalake;
+
+use deltalake::predicate::Predicate;
+use deltalake::DeltaTableError;
+use serde_json::json;
+
+const FULL_STATS: &str =
+    "date=2021-01-01/part-00000-0a1b2c3d-0000-4000-8000-000000000000.c000.snappy.parquet";
+const TRUNCATED_STATS: &str =
+    "date=2021-01-02/part-00000-0a1b2c3d-0000-4000-8000-000000000001.c000.snappy.parquet";
+const NO_STATS: &str =
+    "date=__HIVE_DEFAULT_PARTITION__/part-00000-0a1b2c3d-0000-4000-8000-000000000002.c000.snappy.parquet";
+
+fn files_by_predicate(
+    table: &deltalake::DeltaTable,
+    predicate: &str,
+) -> Result<Vec<String>, DeltaTableError> {
+    table.get_files_by_predicate(&predicate.parse::<Predicate>()?)
+}
+
+#[tokio::test]
+async fn prune_by_partition_values() {
+    let table = deltalake::open_table("./tests/data/table_with_typed_stats")
+        .await
+        .unwrap();
+
+    for (predicate, expected) in [
+        ("date = '2021-01-01'", vec![FULL_STATS]),
+        ("date IS NULL", vec![NO_STATS]),
+        ("date > '2021-01-01'", vec![TRUNCATED_STATS]),
+        (
+            "date >= '2021-01-02' OR date IS NULL",
+            vec![TRUNCATED_STATS, NO_STATS],
+        ),
+        ("date NOT IN ('2021-01-02')", vec![FULL_STATS]),
+    ] {
+        assert_eq!(
+            files_by_predicate(&table, predicate).unwrap(),
+            expected,
+            "{}",
+            predicate
+        );
+    }
+}
+
+#[tokio::test]
+async fn prune_by_stats() {
+    let table = deltalake::open_table("./tests/data/table_with_typed_stats")
+        .await
+        .unwrap();
+
+    // files with truncated or without statistics cannot be pruned
+    let all = vec![FULL_STATS, TRUNCATED_STATS, NO_STATS];
+    let pruned = vec![TRUNCATED_STATS, NO_STATS];
+    for (predicate, expected) in [
+        ("id > 2", &pruned),
+        ("id >= 2", &all),
+        ("NOT id <= 2", &pruned),
+        ("id IN (5, 7)", &pruned),
+        ("id NOT IN (1, 2)", &all),
+        ("3 <= id", &pruned),
+        ("address.city = 'Amsterdam'", &pruned),
+        ("ADDRESS.City < 'C'", &all),
+        ("address.zip IS NULL", &all),
+        ("id IS NULL", &pruned),
+        // decimal statistics with more digits than the scale are compared exactly
+        ("amount > 12.345", &pruned),
+        ("amount > 12.34", &all),
+        ("amount < 1.005", &pruned),
+        // timestamp statistics are truncated to milliseconds
+        ("ts > '2021-01-02 12:30:00.1235'", &all),
+        ("ts > '2021-01-02T12:30:01Z'", &pruned),
+        // arrays have no statistics
+        ("tags = 'a'", &all),
+        ("id > 2 AND date = '2021-01-01'", &vec![]),
+        ("id > 2 OR date = '2021-01-01'", &all),
+    ] {
+        assert_eq!(
+            &files_by_predicate(&table, predicate).unwrap(),
+            expected,
+            "{}",
+            predicate
+        );
+    }
+}
+
+#[tokio::test]
+async fn invalid_predicates() {
+    let table = deltalake::open_table("./tests/data/table_with_typed_stats")
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        files_by_predicate(&table, "missing = 1"),
+        Err(DeltaTableError::ColumnNotFound { .. })
+    ));
+    assert!(matches!(
+        files_by_predicate(&table, "id.nested = 1"),
+        Err(DeltaTableError::ColumnNotFound { .. })
+    ));
+    assert!(matches!(
+        files_by_predicate(&table, "id = 'one'"),
+        Err(DeltaTableError::InvalidPredicate { .. })
+    ));
+    assert!(matches!(
+        files_by_predicate(&table, "id = "),
+        Err(DeltaTableError::InvalidPredicate { .. })
+    ));
+}
+
+#[tokio::test]
+async fn resolve_predicate() {
+    let table = deltalake::open_table("./tests/data/table_with_typed_stats")
+        .await
+        .unwrap();
+    let predicate = "NOT ID > 2 AND (Address.CITY IN ('Berlin') OR amount <= 1.5) \
+        AND date = '2021-01-02' AND ts < '2021-01-02T12:30:01Z' AND tags IS NOT NULL"
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        table.resolve_predicate(&predicate).unwrap(),
+        json!({"op": "and", "args": [
+            {"op": "and", "args": [
+                {"op": "and", "args": [
+                    {"op": "and", "args": [
+                        {"op": "not", "args": [{"op": ">", "column": ["id"], "value": "2"}]},
+                        {"op": "or", "args": [
+                            {"op": "in", "column": ["address", "city"], "values": ["Berlin"]},
+                            {"op": "<=", "column": ["amount"], "value": "1.50"},
+                        ]},
+                    ]},
+                    {"op": "=", "column": ["date"], "value": 18629},
+                ]},
+                {"op": "<", "column": ["ts"], "value": 1609590601000000i64},
+            ]},
+            {"op": "is not null", "column": ["tags"]},
+        ]})
+    );
+    assert!(matches!(
+        table.resolve_predicate(&"missing = 1".parse().unwrap()),
+        Err(DeltaTableError::ColumnNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn file_uris_by_predicate() {
+    let table = deltalake::open_table("./tests/data/delta-0.8.0-partitioned")
+        .await
+        .unwrap();
+    let predicate = "year = '2021' AND month = '12'".parse().unwrap();
+
+    let files = table.get_files_by_predicate(&predicate).unwrap();
+    assert!(!files.is_empty());
+    assert!(files
+        .iter()
+        .all(|file| file.starts_with("year=2021/month=12/")));
+    let uris = table.get_file_uris_by_predicate(&predicate).unwrap();
+    assert_eq!(uris.len(), files.len());
+    assert!(uris
+        .iter()
+        .zip(&files)
+        .all(|(uri, file)| uri.ends_with(file.as_str())));
+}
@@ -0,0 +1,174 @@
+extern crate deltalake;
+
+use arrow::array::{Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use deltalake::action::{Action, Remove, SaveMode};
+use deltalake::writer::{write_batches, RecordBatchWriter};
+use deltalake::DeltaTable;
+use serde_json::json;
+use std::path::Path;
+use std::sync::Arc;
+
+#[allow(dead_code)]
+mod fs_common;
+
+fn batch(ids: Vec<i64>, modified: Vec<&str>) -> RecordBatch {
+    let schema = ArrowSchema::new(vec![
+        Field::new("id", DataType::Int64, true),
+        Field::new("modified", DataType::Utf8, true),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int64Array::from(ids)),
+            Arc::new(StringArray::from(modified)),
+        ],
+    )
+    .unwrap()
+}
+
+fn manifest_path(table_path: &str, partition: &str) -> String {
+    format!(
+        "{}/_symlink_format_manifest/modified={}/manifest",
+        table_path, partition
+    )
+}
+
+fn read_manifest(table_path: &str, partition: &str) -> Vec<String> {
+    let mut files = std::fs::read_to_string(manifest_path(table_path, partition))
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+fn partition_file_uris(table: &DeltaTable, partition: &str) -> Vec<String> {
+    let mut uris = table
+        .get_file_uris()
+        .into_iter()
+        .filter(|uri| uri.contains(&format!("modified={}/", partition)))
+        .collect::<Vec<_>>();
+    uris.sort();
+    uris
+}
+
+fn removes_of_partition(table: &DeltaTable, partition: &str) -> Vec<Action> {
+    table
+        .get_state()
+        .files()
+        .iter()
+        .filter(|add| add.partition_values["modified"].as_deref() == Some(partition))
+        .map(|add| {
+            Action::remove(Remove {
+                path: add.path.clone(),
+                deletion_timestamp: Some(chrono::Utc::now().timestamp_millis()),
+                data_change: true,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+async fn create_partitioned_table(path: &str, config: serde_json::Value) -> DeltaTable {
+    fs_common::create_table_from_json(
+        path,
+        json!({
+            "type": "struct",
+            "fields": [
+                {"name":"id","type":"long","metadata":{},"nullable":true},
+                {"name":"modified","type":"string","metadata":{},"nullable":true},
+            ]
+        }),
+        vec!["modified"],
+        config,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn generate_manifest_of_partitioned_table() {
+    let path = "./tests/data/manifest_generate";
+    let mut table = create_partitioned_table(path, json!({})).await;
+    write_batches(
+        &mut table,
+        &[batch(
+            vec![1, 2, 3],
+            vec!["2021-02-01", "2021-02-01", "2021-02-02"],
+        )],
+        SaveMode::Append,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // manifests are only written on request
+    assert!(!Path::new(path).join("_symlink_format_manifest").exists());
+
+    table.generate_symlink_format_manifest().await.unwrap();
+    for partition in &["2021-02-01", "2021-02-02"] {
+        assert_eq!(
+            read_manifest(path, partition),
+            partition_file_uris(&table, partition)
+        );
+    }
+}
+
+#[tokio::test]
+async fn manifest_is_updated_after_each_commit() {
+    let path = "./tests/data/manifest_auto_update";
+    let mut table = create_partitioned_table(
+        path,
+        json!({"delta.compatibility.symlinkFormatManifest.enabled": "true"}),
+    )
+    .await;
+    write_batches(
+        &mut table,
+        &[batch(
+            vec![1, 2, 3],
+            vec!["2021-02-01", "2021-02-02", "2021-02-03"],
+        )],
+        SaveMode::Append,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    for partition in &["2021-02-01", "2021-02-02", "2021-02-03"] {
+        assert_eq!(read_manifest(path, partition).len(), 1);
+        assert_eq!(
+            read_manifest(path, partition),
+            partition_file_uris(&table, partition)
+        );
+    }
+    let unchanged = read_manifest(path, "2021-02-02");
+
+    // overwrite one partition
+    let removes = removes_of_partition(&table, "2021-02-01");
+    let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+    writer
+        .write(&batch(vec![4, 5], vec!["2021-02-01", "2021-02-01"]))
+        .unwrap();
+    let mut transaction = table.create_transaction(None);
+    writer.flush(&mut transaction).await.unwrap();
+    transaction.add_actions(removes);
+    transaction.commit(None).await.unwrap();
+
+    let overwritten = read_manifest(path, "2021-02-01");
+    assert_eq!(overwritten, partition_file_uris(&table, "2021-02-01"));
+    assert_eq!(overwritten.len(), 1);
+    assert_eq!(read_manifest(path, "2021-02-02"), unchanged);
+
+    // remove all data of a partition
+    let removes = removes_of_partition(&table, "2021-02-03");
+    fs_common::commit_actions(&mut table, removes).await;
+
+    assert!(!Path::new(&manifest_path(path, "2021-02-03")).exists());
+    assert_eq!(read_manifest(path, "2021-02-01"), overwritten);
+    assert_eq!(read_manifest(path, "2021-02-02"), unchanged);
+}
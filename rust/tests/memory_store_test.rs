@@ -0,0 +1,31 @@
+extern crate deltalake;
+
+use deltalake::storage::memory;
+use deltalake::{Schema, SchemaDataType, SchemaField};
+use std::collections::HashMap;
+
+#[allow(dead_code)]
+mod fs_common;
+
+#[tokio::test]
+async fn create_and_commit_in_memory() {
+    let path = "memory://memory_test/table";
+    let schema = Schema::new(vec![SchemaField::new(
+        "id".to_string(),
+        SchemaDataType::primitive("integer".to_string()),
+        true,
+        HashMap::new(),
+    )]);
+    let mut table = fs_common::create_test_table(path, schema, vec![], HashMap::new()).await;
+    let add = fs_common::add(0);
+    assert_eq!(fs_common::commit_add(&mut table, &add).await, 1);
+
+    let table = deltalake::open_table(path).await.unwrap();
+    assert_eq!(table.version, 1);
+    assert_eq!(table.get_files(), vec![add.path.as_str()]);
+
+    let contents = memory::snapshot("memory_test");
+    assert!(contents.contains_key("table/_delta_log/00000000000000000000.json"));
+    assert!(contents.contains_key("table/_delta_log/00000000000000000001.json"));
+    memory::clear("memory_test");
+}
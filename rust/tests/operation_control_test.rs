@@ -0,0 +1,143 @@
+extern crate deltalake;
+
+use arrow::array::{Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use deltalake::action::SaveMode;
+use deltalake::writer::write_batches;
+use deltalake::{
+    CancellationToken, DeltaTableError, OperationControl, ProgressCallback, ProgressEvent,
+    ProgressPhase,
+};
+use serde_json::json;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[allow(dead_code)]
+mod fs_common;
+
+/// Records the events and cancels the operation after the first one
+struct CancelAfterFirstEvent {
+    events: Mutex<Vec<ProgressEvent>>,
+    token: Arc<AtomicBool>,
+}
+
+impl ProgressCallback for CancelAfterFirstEvent {
+    fn on_progress(&self, event: &ProgressEvent) {
+        self.events.lock().unwrap().push(*event);
+        self.token.store(true, Ordering::SeqCst);
+    }
+}
+
+fn sample_batch() -> RecordBatch {
+    let schema = ArrowSchema::new(vec![
+        Field::new("id", DataType::Int64, true),
+        Field::new("modified", DataType::Utf8, true),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec![
+                "2021-02-01",
+                "2021-02-02",
+                "2021-02-03",
+            ])),
+        ],
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn cancelled_write_does_not_commit() {
+    let path = "./tests/data/operation_control_write";
+    let mut table = fs_common::create_table_from_json(
+        path,
+        json!({
+            "type": "struct",
+            "fields": [
+                {"name":"id","type":"long","metadata":{},"nullable":true},
+                {"name":"modified","type":"string","metadata":{},"nullable":true},
+            ]
+        }),
+        vec!["modified"],
+        json!({}),
+    )
+    .await;
+
+    let token = Arc::new(AtomicBool::new(false));
+    let callback = Arc::new(CancelAfterFirstEvent {
+        events: Mutex::new(vec![]),
+        token: token.clone(),
+    });
+    let control = OperationControl::default()
+        .with_progress_callback(callback.clone())
+        .with_cancellation_token(token.clone());
+
+    let result = write_batches(
+        &mut table,
+        &[sample_batch()],
+        SaveMode::Append,
+        None,
+        None,
+        Some(control),
+    )
+    .await;
+
+    assert!(matches!(result, Err(DeltaTableError::Cancelled)));
+    assert!(token.is_cancelled());
+    let events = callback.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].phase, ProgressPhase::WriteFiles);
+    assert_eq!((events[0].done, events[0].total), (1, 3));
+    assert!(events[0].bytes.unwrap() > 0);
+
+    assert_eq!(table.version, 0);
+    assert!(!Path::new(path)
+        .join("_delta_log/00000000000000000001.json")
+        .exists());
+}
+
+#[tokio::test]
+async fn write_reports_progress() {
+    let mut table = fs_common::create_table_from_json(
+        "./tests/data/operation_control_progress",
+        json!({
+            "type": "struct",
+            "fields": [
+                {"name":"id","type":"long","metadata":{},"nullable":true},
+                {"name":"modified","type":"string","metadata":{},"nullable":true},
+            ]
+        }),
+        vec!["modified"],
+        json!({}),
+    )
+    .await;
+
+    // the token is never cancelled, the callback only records the events
+    let callback = Arc::new(CancelAfterFirstEvent {
+        events: Mutex::new(vec![]),
+        token: Arc::new(AtomicBool::new(false)),
+    });
+    let control = OperationControl::default().with_progress_callback(callback.clone());
+    let metrics = write_batches(
+        &mut table,
+        &[sample_batch()],
+        SaveMode::Append,
+        None,
+        None,
+        Some(control),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    let events = callback.events.lock().unwrap();
+    assert_eq!(
+        events.iter().map(|e| (e.done, e.total)).collect::<Vec<_>>(),
+        vec![(1, 3), (2, 3), (3, 3)]
+    );
+    assert_eq!(events[2].bytes, Some(metrics.num_added_bytes));
+    assert_eq!(table.version, 1);
+}
@@ -0,0 +1,130 @@
+extern crate deltalake;
+
+use deltalake::action::{Action, DeltaOperation, SaveMode};
+use deltalake::{DeltaDataTypeVersion, DeltaTableError, DeltaTransactionOptions, PostCommitHook};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+#[allow(dead_code)]
+mod fs_common;
+
+/// Records the invocations of all hooks sharing the log as (hook name, version, number of actions)
+struct RecordingHook {
+    name: &'static str,
+    log: Arc<Mutex<Vec<(&'static str, DeltaDataTypeVersion, usize)>>>,
+    fail: bool,
+}
+
+impl PostCommitHook for RecordingHook {
+    fn after_commit(
+        &self,
+        version: DeltaDataTypeVersion,
+        operation: Option<&DeltaOperation>,
+        actions: &[Action],
+    ) -> Result<(), DeltaTableError> {
+        assert!(matches!(
+            operation,
+            Some(DeltaOperation::Write {
+                mode: SaveMode::Append,
+                ..
+            })
+        ));
+        self.log
+            .lock()
+            .unwrap()
+            .push((self.name, version, actions.len()));
+        if self.fail {
+            return Err(DeltaTableError::Generic(format!("{} failed", self.name)));
+        }
+        Ok(())
+    }
+}
+
+fn hook(
+    name: &'static str,
+    log: &Arc<Mutex<Vec<(&'static str, DeltaDataTypeVersion, usize)>>>,
+    fail: bool,
+) -> Arc<dyn PostCommitHook> {
+    Arc::new(RecordingHook {
+        name,
+        log: log.clone(),
+        fail,
+    })
+}
+
+fn write_operation() -> DeltaOperation {
+    DeltaOperation::Write {
+        mode: SaveMode::Append,
+        partitionBy: None,
+        predicate: None,
+    }
+}
+
+async fn create_table(path: &str) -> deltalake::DeltaTable {
+    fs_common::create_table_from_json(
+        path,
+        json!({
+            "type": "struct",
+            "fields": [{"name":"id","type":"integer","metadata":{},"nullable":true}]
+        }),
+        vec![],
+        json!({}),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn post_commit_hooks_run_in_order() {
+    let mut table = create_table("./tests/data/post_commit_hooks").await;
+    let log = Arc::new(Mutex::new(vec![]));
+    let options = DeltaTransactionOptions::default()
+        .with_post_commit_hook(hook("first", &log, false))
+        .with_post_commit_hook(hook("second", &log, false));
+
+    let mut tx = table.create_transaction(Some(options));
+    tx.add_actions(vec![
+        Action::add(fs_common::add(0)),
+        Action::add(fs_common::add(1)),
+    ]);
+    let version = tx.commit(Some(write_operation())).await.unwrap();
+
+    assert_eq!(version, 1);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![("first", 1, 2), ("second", 1, 2)]
+    );
+}
+
+#[tokio::test]
+async fn failing_post_commit_hook_keeps_commit() {
+    let mut table = create_table("./tests/data/post_commit_hooks_failing").await;
+    let log = Arc::new(Mutex::new(vec![]));
+
+    // by default the failure is only logged and the following hooks still run
+    let options = DeltaTransactionOptions::default()
+        .with_post_commit_hook(hook("failing", &log, true))
+        .with_post_commit_hook(hook("next", &log, false));
+    let mut tx = table.create_transaction(Some(options));
+    tx.add_action(Action::add(fs_common::add(0)));
+    assert_eq!(tx.commit(Some(write_operation())).await.unwrap(), 1);
+
+    // strict hooks return the failure, but the version is committed
+    let options = DeltaTransactionOptions::default()
+        .with_post_commit_hook(hook("failing", &log, true))
+        .with_post_commit_hook(hook("next", &log, false))
+        .with_strict_post_commit_hooks();
+    let mut tx = table.create_transaction(Some(options));
+    tx.add_action(Action::add(fs_common::add(1)));
+    let result = tx.commit(Some(write_operation())).await;
+    assert!(matches!(
+        result,
+        Err(DeltaTableError::PostCommitHookFailed { version: 2, msg }) if msg.contains("failing failed")
+    ));
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![("failing", 1, 1), ("next", 1, 1), ("failing", 2, 1)]
+    );
+    assert_eq!(table.version, 2);
+    assert_eq!(table.get_files().len(), 2);
+}
@@ -167,3 +167,80 @@ async fn read_null_partitions_from_checkpoint() {
     let table = deltalake::open_table(&table.table_uri).await.unwrap();
     assert_eq!(table.version, 2);
 }
+
+#[tokio::test]
+async fn read_partitions_with_mixed_case_column_names() {
+    let mut table = fs_common::create_table_from_json(
+        "./tests/data/mixed_case_partitions",
+        json!({
+            "type": "struct",
+            "fields": [
+                {"name":"Id","type":"integer","metadata":{},"nullable":true},
+                {"name":"ID","type":"integer","metadata":{},"nullable":true},
+                {"name":"Date","type":"string","metadata":{},"nullable":true},
+            ]
+        }),
+        vec!["Date"],
+        json!({}),
+    )
+    .await;
+
+    let add = |date: &str| Add {
+        partition_values: hashmap! {
+            "Date".to_string() => Some(date.to_string())
+        },
+        ..fs_common::add(0)
+    };
+    let first = add("2021-12-01");
+    fs_common::commit_add(&mut table, &first).await;
+    fs_common::commit_add(&mut table, &add("2021-12-02")).await;
+
+    // Case-insensitive resolution is the default
+    let filters = vec![deltalake::PartitionFilter::try_from(("date", "=", "2021-12-01")).unwrap()];
+    assert_eq!(
+        table.get_files_by_partitions(&filters).unwrap(),
+        vec![first.path.clone()]
+    );
+    assert_eq!(table.resolve_column_name("DATE").unwrap(), "Date");
+
+    // Columns differing only by case cannot be resolved case-insensitively
+    assert!(matches!(
+        table.resolve_column_name("id"),
+        Err(deltalake::DeltaTableError::AmbiguousColumn { name, candidates })
+            if name == "id" && candidates == vec!["Id".to_string(), "ID".to_string()]
+    ));
+
+    // With case-sensitive resolution only exact names match
+    let table = deltalake::DeltaTableBuilder::from_uri(&table.table_uri)
+        .unwrap()
+        .with_column_resolution(deltalake::ColumnResolution::CaseSensitive)
+        .load()
+        .await
+        .unwrap();
+    assert!(matches!(
+        table.get_files_by_partitions(&filters),
+        Err(deltalake::DeltaTableError::InvalidPartitionFilter { .. })
+    ));
+    assert_eq!(table.resolve_column_name("ID").unwrap(), "ID");
+    assert!(matches!(
+        table.resolve_column_name("date"),
+        Err(deltalake::DeltaTableError::ColumnNotFound { .. })
+    ));
+}
+
+#[test]
+fn resolve_ambiguous_partition_column() {
+    let columns = vec!["Year".to_string(), "year".to_string()];
+    let resolution = deltalake::ColumnResolution::CaseInsensitive;
+
+    assert!(matches!(
+        resolution.resolve("YEAR", columns.iter().map(String::as_str)),
+        Err(deltalake::DeltaTableError::AmbiguousColumn { .. })
+    ));
+    assert_eq!(
+        deltalake::ColumnResolution::CaseSensitive
+            .resolve("year", columns.iter().map(String::as_str))
+            .unwrap(),
+        Some("year")
+    );
+}
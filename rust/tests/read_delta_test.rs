@@ -2,8 +2,8 @@ extern crate deltalake;
 
 use chrono::Utc;
 use deltalake::storage::file::FileStorageBackend;
-use deltalake::DeltaTableBuilder;
 use deltalake::StorageBackend;
+use deltalake::{DeltaTableBuilder, DeltaTableHealthWarning};
 use pretty_assertions::assert_eq;
 use std::collections::HashMap;
 use std::time::SystemTime;
@@ -54,6 +54,154 @@ async fn read_delta_table_with_update() {
     );
 }
 
+#[tokio::test]
+async fn read_delta_table_with_prepared_update() {
+    let path = "./tests/data/simple_table_with_checkpoint/";
+    let table_newest_version = deltalake::open_table(path).await.unwrap();
+    let mut table = deltalake::open_table_with_version(path, 5).await.unwrap();
+    let files_at_version_5 = table.get_files_iter().map(String::from).collect::<Vec<_>>();
+
+    // preparing an update does not touch the loaded state
+    let update = table.prepare_update_incremental().await.unwrap();
+    assert_eq!(update.version(), 10);
+    assert_eq!(table.version, 5);
+    assert_eq!(table.get_files(), files_at_version_5);
+
+    let stale_update = table.prepare_update_incremental().await.unwrap();
+    table.apply_update(update).unwrap();
+    assert_eq!(table.version, 10);
+    assert_eq!(table.get_files(), table_newest_version.get_files());
+
+    // an incremental update prepared for an older version is rejected
+    assert!(table.apply_update(stale_update).is_err());
+    assert_eq!(table.version, 10);
+
+    let update = table.prepare_load_version(2).await.unwrap();
+    assert_eq!(table.version, 10);
+    table.apply_update(update).unwrap();
+    assert_eq!(table.version, 2);
+}
+
+#[tokio::test]
+async fn read_delta_table_with_junk_log_files() {
+    let path = "./tests/data/simple_table_with_junk_log_files/";
+    let table = deltalake::open_table(path).await.unwrap();
+    assert_eq!(table.version, 10);
+
+    // loading a version forces a listing of _delta_log to find the closest checkpoint
+    let mut table_at_version = deltalake::open_table_with_version(path, 10).await.unwrap();
+    assert_eq!(table_at_version.version, 10);
+    assert_eq!(table.get_files(), table_at_version.get_files());
+    table_at_version.load_version(9).await.unwrap();
+    assert_eq!(table_at_version.version, 9);
+
+    let mut table_to_update = deltalake::open_table_with_version(path, 5).await.unwrap();
+    table_to_update.update_incremental().await.unwrap();
+    assert_eq!(table_to_update.version, 10);
+    assert_eq!(table.get_files(), table_to_update.get_files());
+
+    let reference = deltalake::open_table("./tests/data/simple_table_with_checkpoint/")
+        .await
+        .unwrap();
+    assert_eq!(reference.get_files(), table.get_files());
+}
+
+#[tokio::test]
+async fn read_delta_table_with_corrupt_last_checkpoint() {
+    let path = "./tests/data/simple_table_with_corrupt_last_checkpoint";
+    let reference = deltalake::open_table("./tests/data/simple_table_with_checkpoint")
+        .await
+        .unwrap();
+
+    let mut table = deltalake::open_table(path).await.unwrap();
+    assert_eq!(table.version, 10);
+    assert_eq!(table.get_files(), reference.get_files());
+    assert!(matches!(
+        table.get_health_warnings(),
+        [DeltaTableHealthWarning::CorruptLastCheckpoint { .. }]
+    ));
+
+    table.update().await.unwrap();
+    assert_eq!(table.version, 10);
+    assert_eq!(table.get_health_warnings().len(), 1);
+
+    assert!(DeltaTableBuilder::from_uri(path)
+        .unwrap()
+        .with_strict_checkpoints()
+        .load()
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn read_delta_table_with_corrupt_checkpoint() {
+    let path = "./tests/data/simple_table_with_corrupt_checkpoint";
+    let reference = deltalake::open_table("./tests/data/simple_table_with_checkpoint")
+        .await
+        .unwrap();
+
+    let mut table = deltalake::open_table(path).await.unwrap();
+    assert_eq!(table.version, 10);
+    assert_eq!(table.get_files(), reference.get_files());
+    assert!(matches!(
+        table.get_health_warnings(),
+        [DeltaTableHealthWarning::CorruptCheckpoint { version: 10, .. }]
+    ));
+
+    table.load_version(10).await.unwrap();
+    assert_eq!(table.get_files(), reference.get_files());
+    assert_eq!(table.get_health_warnings().len(), 1);
+
+    assert!(DeltaTableBuilder::from_uri(path)
+        .unwrap()
+        .with_strict_checkpoints()
+        .load()
+        .await
+        .is_err());
+    assert!(DeltaTableBuilder::from_uri(path)
+        .unwrap()
+        .with_strict_checkpoints()
+        .with_version(10)
+        .load()
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn read_delta_table_with_nan_stats() {
+    let table = deltalake::open_table("./tests/data/table_with_nan_stats")
+        .await
+        .unwrap();
+    assert_eq!(table.get_files().len(), 3);
+
+    let stats = table
+        .get_stats()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let values = stats
+        .iter()
+        .map(|s| {
+            let s = s.as_ref().unwrap();
+            (
+                s.min_values["value"].as_value().unwrap().clone(),
+                s.max_values["value"].as_value().unwrap().clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            (serde_json::json!(1.5), serde_json::json!(2.5)),
+            (serde_json::json!("NaN"), serde_json::json!("NaN")),
+            (
+                serde_json::json!("-Infinity"),
+                serde_json::json!("Infinity")
+            ),
+        ]
+    );
+}
+
 #[tokio::test]
 async fn read_delta_table_ignoring_tombstones() {
     let table = DeltaTableBuilder::from_uri("./tests/data/delta-0.8.0")
@@ -222,20 +370,16 @@ async fn read_delta_8_0_table_with_partitions() {
         ]
     );
 
-    #[cfg(unix)]
-    assert_eq!(
-        table.get_file_uris_by_partitions(&filters).unwrap(),
-        vec![
-            "./tests/data/delta-0.8.0-partitioned/year=2020/month=2/day=3/part-00000-94d16827-f2fd-42cd-a060-f67ccc63ced9.c000.snappy.parquet".to_string(),
-            "./tests/data/delta-0.8.0-partitioned/year=2020/month=2/day=5/part-00000-89cdd4c8-2af7-4add-8ea3-3990b2f027b5.c000.snappy.parquet".to_string()
-        ]
-    );
-    #[cfg(windows)]
+    let table_root = std::env::current_dir()
+        .unwrap()
+        .join("tests")
+        .join("data")
+        .join("delta-0.8.0-partitioned");
     assert_eq!(
         table.get_file_uris_by_partitions(&filters).unwrap(),
         vec![
-            "./tests/data/delta-0.8.0-partitioned\\year=2020/month=2/day=3/part-00000-94d16827-f2fd-42cd-a060-f67ccc63ced9.c000.snappy.parquet".to_string(),
-            "./tests/data/delta-0.8.0-partitioned\\year=2020/month=2/day=5/part-00000-89cdd4c8-2af7-4add-8ea3-3990b2f027b5.c000.snappy.parquet".to_string()
+            table_root.join("year=2020/month=2/day=3/part-00000-94d16827-f2fd-42cd-a060-f67ccc63ced9.c000.snappy.parquet").to_str().unwrap().to_string(),
+            table_root.join("year=2020/month=2/day=5/part-00000-89cdd4c8-2af7-4add-8ea3-3990b2f027b5.c000.snappy.parquet").to_str().unwrap().to_string(),
         ]
     );
 
@@ -389,12 +533,17 @@ async fn vacuum_delta_8_0_table() {
 
     assert_eq!(
         table.vacuum(Some(retention_hours), dry_run).await.unwrap(),
-        vec![backend.join_paths(&[
-            "tests",
-            "data",
-            "delta-0.8.0",
-            "part-00001-911a94a2-43f6-4acb-8620-5e68c2654989-c000.snappy.parquet",
-        ])]
+        vec![std::env::current_dir()
+            .unwrap()
+            .join(backend.join_paths(&[
+                "tests",
+                "data",
+                "delta-0.8.0",
+                "part-00001-911a94a2-43f6-4acb-8620-5e68c2654989-c000.snappy.parquet",
+            ]))
+            .to_str()
+            .unwrap()
+            .to_string()]
     );
 
     let retention_hours = SystemTime::now()
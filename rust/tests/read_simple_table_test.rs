@@ -2,6 +2,7 @@ extern crate chrono;
 extern crate deltalake;
 extern crate utime;
 
+use deltalake::action::Action;
 use std::path::Path;
 
 use self::chrono::{DateTime, FixedOffset, Utc};
@@ -35,31 +36,96 @@ async fn read_simple_table() {
         extended_file_metadata: None,
         ..Default::default()
     }));
+    let table_root = std::env::current_dir()
+        .unwrap()
+        .join("tests")
+        .join("data")
+        .join("simple_table");
+    let mut paths = table.get_file_uris();
+    paths.sort();
+    let expected_paths: Vec<String> = vec![
+        "part-00000-2befed33-c358-4768-a43c-3eda0d2a499d-c000.snappy.parquet",
+        "part-00000-c1777d7d-89d9-4790-b38a-6ee7e24456b1-c000.snappy.parquet",
+        "part-00001-7891c33d-cedc-47c3-88a6-abcfb049d3b4-c000.snappy.parquet",
+        "part-00004-315835fe-fb44-4562-98f6-5e6cfa3ae45d-c000.snappy.parquet",
+        "part-00007-3a0e4727-de0d-41b6-81ef-5223cf40f025-c000.snappy.parquet",
+    ]
+    .into_iter()
+    .map(|f| table_root.join(f).to_str().unwrap().to_string())
+    .collect();
+    assert_eq!(paths, expected_paths);
+}
+
+#[tokio::test]
+async fn simple_table_detail() {
+    let table = deltalake::open_table("./tests/data/simple_table")
+        .await
+        .unwrap();
+    let detail = table.get_detail().unwrap();
+
+    assert_eq!(detail.format, "delta");
+    assert_eq!(detail.id, "5fba94ed-9794-4965-ba6e-6ee3c0d22af9");
+    assert_eq!(detail.name, None);
+    assert_eq!(detail.location, table.table_uri);
+    assert_eq!(detail.created_at, Some(1587968585495));
+    assert!(detail.last_modified.is_some());
+    assert!(detail.partition_columns.is_empty());
+    assert_eq!(detail.num_files, 5);
+    assert_eq!(detail.size_in_bytes, 1811);
+    assert!(detail.properties.is_empty());
+    assert_eq!(detail.min_reader_version, 1);
+    assert_eq!(detail.min_writer_version, 2);
+    assert!(detail.reader_features.is_empty());
+    assert_eq!(detail.writer_features, vec!["appendOnly", "invariants"]);
+}
+
+#[tokio::test]
+async fn read_simple_table_normalized_uri() {
+    let table_root = std::env::current_dir()
+        .unwrap()
+        .join("tests")
+        .join("data")
+        .join("simple_table");
+    let table_root = table_root.to_str().unwrap();
+    let trailing_slash = format!("{}{}", table_root, std::path::MAIN_SEPARATOR);
+
+    for table_uri in [
+        "./tests/data/simple_table",
+        "tests/data/simple_table/",
+        "./tests/data/../data/simple_table",
+        table_root,
+        trailing_slash.as_str(),
+    ] {
+        let table = deltalake::open_table(table_uri).await.unwrap();
+        assert_eq!(table.table_uri, table_root);
+        assert_eq!(table.version, 4);
+    }
+
     #[cfg(unix)]
     {
-        let mut paths = table.get_file_uris();
-        paths.sort();
-        let expected_paths: Vec<String> = vec![
-                "./tests/data/simple_table/part-00000-2befed33-c358-4768-a43c-3eda0d2a499d-c000.snappy.parquet".to_string(),
-                "./tests/data/simple_table/part-00000-c1777d7d-89d9-4790-b38a-6ee7e24456b1-c000.snappy.parquet".to_string(),
-                "./tests/data/simple_table/part-00001-7891c33d-cedc-47c3-88a6-abcfb049d3b4-c000.snappy.parquet".to_string(),
-                "./tests/data/simple_table/part-00004-315835fe-fb44-4562-98f6-5e6cfa3ae45d-c000.snappy.parquet".to_string(),
-                "./tests/data/simple_table/part-00007-3a0e4727-de0d-41b6-81ef-5223cf40f025-c000.snappy.parquet".to_string()
-            ];
-        assert_eq!(paths, expected_paths);
+        let table = deltalake::open_table(&format!("file://{}", table_root))
+            .await
+            .unwrap();
+        assert_eq!(table.table_uri, table_root);
     }
-    #[cfg(windows)]
-    {
-        let mut paths = table.get_file_uris();
-        paths.sort();
-        let mut expected_paths: Vec<String> = vec![
-                "./tests/data/simple_table\\part-00000-2befed33-c358-4768-a43c-3eda0d2a499d-c000.snappy.parquet".to_string(),
-                "./tests/data/simple_table\\part-00000-c1777d7d-89d9-4790-b38a-6ee7e24456b1-c000.snappy.parquet".to_string(),
-                "./tests/data/simple_table\\part-00001-7891c33d-cedc-47c3-88a6-abcfb049d3b4-c000.snappy.parquet".to_string(),
-                "./tests/data/simple_table\\part-00004-315835fe-fb44-4562-98f6-5e6cfa3ae45d-c000.snappy.parquet".to_string(),
-                "./tests/data/simple_table\\part-00007-3a0e4727-de0d-41b6-81ef-5223cf40f025-c000.snappy.parquet".to_string()
-            ];
-        assert_eq!(paths, expected_paths);
+}
+
+#[tokio::test]
+async fn new_table_normalizes_uri_like_builder() {
+    for table_uri in ["./tests/data/simple_table", "tests/data/simple_table/"] {
+        let built = deltalake::DeltaTableBuilder::from_uri(table_uri)
+            .unwrap()
+            .load()
+            .await
+            .unwrap();
+        let backend = deltalake::get_backend_for_uri(table_uri).unwrap();
+        let mut table =
+            deltalake::DeltaTable::new(table_uri, backend, deltalake::DeltaTableConfig::default())
+                .unwrap();
+        assert_eq!(table.table_uri, built.table_uri);
+
+        table.load().await.unwrap();
+        assert_eq!(table.get_file_uris(), built.get_file_uris());
     }
 }
 
@@ -147,12 +213,35 @@ async fn time_travel_by_ds() {
         utime::set_file_times(Path::new(log_dir).join(fname), ts, ts).unwrap();
     }
 
-    let mut table =
+    let result =
         deltalake::open_table_with_ds("./tests/data/simple_table", "2020-05-01T00:47:31-07:00")
+            .await;
+    match result.unwrap_err() {
+        deltalake::DeltaTableError::TimestampBeforeFirstCommit {
+            earliest_version,
+            earliest_timestamp,
+            ..
+        } => {
+            assert_eq!(earliest_version, 0);
+            assert_eq!(
+                earliest_timestamp.timestamp(),
+                ds_to_ts("2020-05-01T22:47:31-07:00")
+            );
+        }
+        other => panic!("expected TimestampBeforeFirstCommit, got {:?}", other),
+    }
+
+    let mut table =
+        deltalake::open_table_with_ds("./tests/data/simple_table", "2020-05-01T22:47:31-07:00")
             .await
             .unwrap();
     assert_eq!(table.version, 0);
 
+    table = deltalake::open_table_with_ds("./tests/data/simple_table", "2020-05-02T22:47:30-07:00")
+        .await
+        .unwrap();
+    assert_eq!(table.version, 0);
+
     table = deltalake::open_table_with_ds("./tests/data/simple_table", "2020-05-02T22:47:31-07:00")
         .await
         .unwrap();
@@ -188,3 +277,113 @@ async fn time_travel_by_ds() {
         .unwrap();
     assert_eq!(table.version, 4);
 }
+
+#[tokio::test]
+async fn time_travel_by_ds_after_log_cleanup() {
+    // a log whose commits before version 5 were cleaned up after the checkpoint of version 10
+    let tmp_dir = tempdir::TempDir::new("time_travel_test").unwrap();
+    let log_dir = tmp_dir.path().join("_delta_log");
+    std::fs::create_dir(&log_dir).unwrap();
+    for entry in std::fs::read_dir("./tests/data/simple_table_with_checkpoint/_delta_log").unwrap()
+    {
+        let entry = entry.unwrap();
+        std::fs::copy(entry.path(), log_dir.join(entry.file_name())).unwrap();
+    }
+    for version in 0..5 {
+        std::fs::remove_file(log_dir.join(format!("{:020}.json", version))).unwrap();
+    }
+    for version in 5..=10 {
+        let ts = ds_to_ts(&format!("2020-05-{:02}T00:00:00Z", version));
+        utime::set_file_times(log_dir.join(format!("{:020}.json", version)), ts, ts).unwrap();
+    }
+    let table_uri = tmp_dir.path().to_str().unwrap();
+
+    match deltalake::open_table_with_ds(table_uri, "2020-05-04T23:59:59Z")
+        .await
+        .unwrap_err()
+    {
+        deltalake::DeltaTableError::TimestampBeforeFirstCommit {
+            earliest_version,
+            earliest_timestamp,
+            ..
+        } => {
+            assert_eq!(earliest_version, 5);
+            assert_eq!(
+                earliest_timestamp.timestamp(),
+                ds_to_ts("2020-05-05T00:00:00Z")
+            );
+        }
+        other => panic!("expected TimestampBeforeFirstCommit, got {:?}", other),
+    }
+
+    let table = deltalake::open_table_with_ds(table_uri, "2020-05-10T12:00:00+02:00")
+        .await
+        .unwrap();
+    assert_eq!(table.version, 10);
+}
+
+#[tokio::test]
+async fn get_actions_between() {
+    let table = deltalake::open_table("./tests/data/simple_table")
+        .await
+        .unwrap();
+    let commits = table.get_actions_between(1, 4).await.unwrap();
+    assert_eq!(
+        commits.iter().map(|c| c.version).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+
+    let count =
+        |actions: &Vec<Action>, f: fn(&Action) -> bool| actions.iter().filter(|a| f(a)).count();
+    let counts: Vec<_> = commits
+        .iter()
+        .map(|c| {
+            (
+                count(&c.actions, |a| matches!(a, Action::add(_))),
+                count(&c.actions, |a| matches!(a, Action::remove(_))),
+            )
+        })
+        .collect();
+    assert_eq!(counts, vec![(21, 5), (6, 22), (2, 2), (1, 2)]);
+
+    match &commits[2].actions[0] {
+        Action::commitInfo(info) => assert_eq!(info["operation"], "UPDATE"),
+        other => panic!("expected commitInfo, got {:?}", other),
+    }
+
+    assert!(matches!(
+        table.get_actions_between(4, 5).await,
+        Err(deltalake::DeltaTableError::InvalidVersion(5))
+    ));
+    assert!(table.get_actions_between(4, 3).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn get_actions_between_after_log_cleanup() {
+    let tmp_dir = tempdir::TempDir::new("get_actions_test").unwrap();
+    let log_dir = tmp_dir.path().join("_delta_log");
+    std::fs::create_dir(&log_dir).unwrap();
+    for entry in std::fs::read_dir("./tests/data/simple_table_with_checkpoint/_delta_log").unwrap()
+    {
+        let entry = entry.unwrap();
+        std::fs::copy(entry.path(), log_dir.join(entry.file_name())).unwrap();
+    }
+    for version in 0..5 {
+        std::fs::remove_file(log_dir.join(format!("{:020}.json", version))).unwrap();
+    }
+    let table = deltalake::open_table(tmp_dir.path().to_str().unwrap())
+        .await
+        .unwrap();
+
+    match table.get_actions_between(3, 10).await.unwrap_err() {
+        deltalake::DeltaTableError::VersionNotInLog {
+            version,
+            earliest_version,
+        } => {
+            assert_eq!(version, 3);
+            assert_eq!(earliest_version, 5);
+        }
+        other => panic!("expected VersionNotInLog, got {:?}", other),
+    }
+    assert_eq!(table.get_actions_between(5, 10).await.unwrap().len(), 6);
+}
@@ -0,0 +1,122 @@
+extern crate deltalake;
+
+use deltalake::action::{Action, Remove};
+use deltalake::delta_config::{DeltaConfigError, TableProperties};
+use deltalake::{DeltaTableBuilder, DeltaTableError};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[allow(dead_code)]
+mod fs_common;
+
+async fn create_table(path: &str, config: serde_json::Value) {
+    fs_common::create_table_from_json(
+        path,
+        json!({
+            "type": "struct",
+            "fields": [{"name":"id","type":"long","metadata":{},"nullable":true}]
+        }),
+        vec![],
+        config,
+    )
+    .await;
+}
+
+fn properties(entries: &[(&str, &str)]) -> HashMap<String, Option<String>> {
+    entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), Some(v.to_string())))
+        .collect()
+}
+
+#[tokio::test]
+async fn set_tbl_properties_updates_table_properties() {
+    let path = "./tests/data/table_properties_set";
+    create_table(path, json!({})).await;
+    let mut table = deltalake::open_table(path).await.unwrap();
+
+    let version = table
+        .set_tbl_properties(properties(&[
+            ("delta.deletedFileRetentionDuration", "interval 2 days"),
+            ("delta.checkpointInterval", "20"),
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(version, 1);
+    assert_eq!(table.version, 1);
+
+    let table_properties = TableProperties::from_metadata(table.get_metadata().unwrap());
+    assert_eq!(table_properties.checkpoint_interval, 20);
+    assert_eq!(
+        table_properties.deleted_file_retention_duration,
+        Duration::from_secs(2 * 24 * 3600)
+    );
+    assert_eq!(
+        table.get_state().tombstone_retention_millis(),
+        2 * 24 * 3600 * 1000
+    );
+}
+
+#[tokio::test]
+async fn set_tbl_properties_rejects_malformed_value_in_strict_mode() {
+    let path = "./tests/data/table_properties_strict";
+    create_table(path, json!({})).await;
+
+    // without strict mode the value is committed and its default used
+    let mut table = deltalake::open_table(path).await.unwrap();
+    table
+        .set_tbl_properties(properties(&[("delta.logRetentionDuration", "30 days")]))
+        .await
+        .unwrap();
+    assert_eq!(
+        TableProperties::from_metadata(table.get_metadata().unwrap()).log_retention_duration,
+        Duration::from_secs(30 * 24 * 3600)
+    );
+
+    let mut table = DeltaTableBuilder::from_uri(path)
+        .unwrap()
+        .with_strict_table_properties()
+        .load()
+        .await
+        .unwrap();
+    let result = table
+        .set_tbl_properties(properties(&[("delta.checkpointInterval", "often")]))
+        .await;
+    match result {
+        Err(DeltaTableError::Config {
+            source: DeltaConfigError::InvalidProperty { key, .. },
+        }) => assert_eq!(key, "delta.checkpointInterval"),
+        other => panic!("expected an invalid property error, got {:?}", other),
+    }
+    assert_eq!(table.version, 1);
+
+    // malformed values committed before do not fail unrelated changes
+    table
+        .set_tbl_properties(properties(&[("delta.enableChangeDataFeed", "true")]))
+        .await
+        .unwrap();
+    assert!(TableProperties::from_metadata(table.get_metadata().unwrap()).enable_change_data_feed);
+}
+
+#[tokio::test]
+async fn append_only_table_rejects_removes() {
+    let path = "./tests/data/table_properties_append_only";
+    create_table(path, json!({"delta.appendOnly": "true"})).await;
+    let mut table = deltalake::open_table(path).await.unwrap();
+
+    let add = fs_common::add(0);
+    fs_common::commit_add(&mut table, &add).await;
+
+    let mut transaction = table.create_transaction(None);
+    transaction.add_action(Action::remove(Remove {
+        path: add.path.clone(),
+        deletion_timestamp: Some(add.modification_time),
+        data_change: true,
+        ..Default::default()
+    }));
+    assert!(matches!(
+        transaction.commit(None).await,
+        Err(DeltaTableError::AppendOnly(_))
+    ));
+}
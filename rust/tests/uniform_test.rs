@@ -0,0 +1,145 @@
+extern crate deltalake;
+
+use arrow::array::{Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use deltalake::action::SaveMode;
+use deltalake::uniform::{export_iceberg_metadata, UniformError, VERSION_HINT_FILE};
+use deltalake::writer::write_batches;
+use deltalake::DeltaTable;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+
+#[allow(dead_code)]
+mod fs_common;
+
+fn batch(ids: Vec<i64>, modified: Vec<&str>) -> RecordBatch {
+    let schema = ArrowSchema::new(vec![
+        Field::new("id", DataType::Int64, true),
+        Field::new("modified", DataType::Utf8, true),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int64Array::from(ids)),
+            Arc::new(StringArray::from(modified)),
+        ],
+    )
+    .unwrap()
+}
+
+async fn create_table(path: &str, config: Value) -> DeltaTable {
+    fs_common::create_table_from_json(
+        path,
+        json!({
+            "type": "struct",
+            "fields": [
+                {"name":"id","type":"long","metadata":{},"nullable":true},
+                {"name":"modified","type":"string","metadata":{},"nullable":true},
+            ]
+        }),
+        vec!["modified"],
+        config,
+    )
+    .await
+}
+
+async fn append(table: &mut DeltaTable, batch: RecordBatch) {
+    write_batches(table, &[batch], SaveMode::Append, None, None, None)
+        .await
+        .unwrap();
+}
+
+fn read_metadata(uri: &str) -> Value {
+    serde_json::from_slice(&std::fs::read(uri).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn export_iceberg_metadata_of_partitioned_table() {
+    let path = "./tests/data/uniform_export";
+    let mut table = create_table(path, json!({})).await;
+    append(
+        &mut table,
+        batch(
+            vec![1, 2, 3],
+            vec!["2021-02-01", "2021-02-01", "2021-02-02"],
+        ),
+    )
+    .await;
+
+    let metadata_dir = format!("{}/metadata", path);
+    let metadata_uri = export_iceberg_metadata(&table, &metadata_dir)
+        .await
+        .unwrap();
+    assert!(metadata_uri.ends_with("v1.metadata.json"));
+    assert_eq!(
+        std::fs::read_to_string(Path::new(&metadata_dir).join(VERSION_HINT_FILE)).unwrap(),
+        "1"
+    );
+
+    let metadata = read_metadata(&metadata_uri);
+    assert_eq!(metadata["format-version"], json!(1));
+    assert_eq!(metadata["properties"]["delta.version"], json!("1"));
+    assert_eq!(
+        metadata["properties"]["delta.table-id"],
+        json!(table.get_metadata().unwrap().id)
+    );
+    assert_eq!(
+        metadata["schema"]["fields"],
+        json!([
+            {"id": 1, "name": "id", "required": false, "type": "long"},
+            {"id": 2, "name": "modified", "required": false, "type": "string"},
+        ])
+    );
+    assert_eq!(
+        metadata["partition-spec"],
+        json!([{"name": "modified", "transform": "identity", "source-id": 2, "field-id": 1000}])
+    );
+    let snapshot = &metadata["snapshots"][0];
+    assert_eq!(snapshot["snapshot-id"], metadata["current-snapshot-id"]);
+    assert_eq!(snapshot["summary"]["added-data-files"], json!("2"));
+    assert_eq!(snapshot["summary"]["total-records"], json!("3"));
+    assert!(Path::new(snapshot["manifest-list"].as_str().unwrap()).exists());
+
+    // exporting the same version again writes nothing
+    assert_eq!(
+        export_iceberg_metadata(&table, &metadata_dir)
+            .await
+            .unwrap(),
+        metadata_uri
+    );
+
+    append(&mut table, batch(vec![4], vec!["2021-02-03"])).await;
+    let metadata_uri = export_iceberg_metadata(&table, &metadata_dir)
+        .await
+        .unwrap();
+    assert!(metadata_uri.ends_with("v2.metadata.json"));
+
+    let metadata = read_metadata(&metadata_uri);
+    assert_eq!(metadata["properties"]["delta.version"], json!("2"));
+    let snapshots = metadata["snapshots"].as_array().unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(
+        snapshots[1]["parent-snapshot-id"],
+        snapshots[0]["snapshot-id"]
+    );
+    assert_eq!(snapshots[1]["summary"]["added-data-files"], json!("1"));
+    assert_eq!(snapshots[1]["summary"]["total-data-files"], json!("3"));
+    assert_eq!(snapshots[1]["summary"]["total-records"], json!("4"));
+    assert_eq!(metadata["metadata-log"].as_array().unwrap().len(), 1);
+    assert_eq!(metadata["schemas"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn export_iceberg_metadata_rejects_column_mapping_id_mode() {
+    let path = "./tests/data/uniform_column_mapping_id";
+    let table = create_table(path, json!({"delta.columnMapping.mode": "id"})).await;
+
+    let result = export_iceberg_metadata(&table, &format!("{}/metadata", path)).await;
+    assert!(matches!(
+        result,
+        Err(UniformError::UnsupportedColumnMapping(mode)) if mode == "id"
+    ));
+    assert!(!Path::new(path).join("metadata").exists());
+}
@@ -0,0 +1,141 @@
+extern crate deltalake;
+
+use deltalake::action::{Action, Remove};
+use deltalake::storage::metrics::{MetricsStorageBackend, StorageMetrics, StorageOperation};
+use deltalake::{storage, DeltaTable, DeltaTableConfig, DeltaTableError};
+use std::sync::Arc;
+
+#[allow(dead_code)]
+mod fs_common;
+
+async fn open_with_metrics(path: &str) -> (DeltaTable, Arc<StorageMetrics>) {
+    let backend = MetricsStorageBackend::new(storage::get_backend_for_uri(path).unwrap());
+    let metrics = backend.metrics();
+    let mut table = DeltaTable::new(path, Box::new(backend), DeltaTableConfig::default()).unwrap();
+    table.load().await.unwrap();
+    (table, metrics)
+}
+
+fn sorted_files(table: &DeltaTable) -> Vec<String> {
+    let mut files: Vec<String> = table.get_files().iter().map(|f| f.to_string()).collect();
+    files.sort();
+    files
+}
+
+#[tokio::test]
+async fn update_incremental_with_concurrent_appends() {
+    let tmp_dir = tempdir::TempDir::new("update_incremental_test").unwrap();
+    let path = tmp_dir.path().to_str().unwrap();
+    let mut writer = fs_common::create_table(path, None).await;
+    let (mut reader, metrics) = open_with_metrics(path).await;
+    metrics.reset();
+
+    let mut commits = 0;
+    let polls = 5;
+    for round in 1..=polls {
+        for _ in 0..round {
+            fs_common::commit_add(&mut writer, &fs_common::add(0)).await;
+        }
+        commits += round;
+
+        assert_eq!(reader.update_incremental_to(None).await.unwrap(), round);
+        assert_eq!(reader.version, writer.version);
+        assert_eq!(sorted_files(&reader), sorted_files(&writer));
+    }
+    assert_eq!(reader.update_incremental_to(None).await.unwrap(), 0);
+    assert_eq!(reader.version, commits);
+
+    // each poll reads only the new commits and probes for the next one, the log is never listed
+    assert_eq!(metrics.get(StorageOperation::List).calls, 0);
+    assert!(metrics.get(StorageOperation::Get).calls <= (commits + 2 * (polls + 1)) as u64);
+}
+
+#[tokio::test]
+async fn update_incremental_to_max_version() {
+    let tmp_dir = tempdir::TempDir::new("update_incremental_test").unwrap();
+    let path = tmp_dir.path().to_str().unwrap();
+    let mut writer = fs_common::create_table(path, None).await;
+    let mut reader = deltalake::open_table(path).await.unwrap();
+    for _ in 0..3 {
+        fs_common::commit_add(&mut writer, &fs_common::add(0)).await;
+    }
+
+    assert_eq!(reader.update_incremental_to(Some(1)).await.unwrap(), 1);
+    assert_eq!(reader.version, 1);
+    assert_eq!(reader.get_files().len(), 1);
+    assert_eq!(reader.update_incremental_to(Some(1)).await.unwrap(), 0);
+    assert_eq!(reader.update_incremental_to(Some(0)).await.unwrap(), 0);
+    assert_eq!(reader.version, 1);
+    assert_eq!(reader.update_incremental_to(Some(5)).await.unwrap(), 2);
+    assert_eq!(reader.version, 3);
+    assert_eq!(sorted_files(&reader), sorted_files(&writer));
+}
+
+#[tokio::test]
+async fn update_incremental_after_log_cleanup() {
+    let tmp_dir = tempdir::TempDir::new("update_incremental_test").unwrap();
+    let path = tmp_dir.path().to_str().unwrap();
+    let mut writer = fs_common::create_table(path, None).await;
+    let mut reader = deltalake::open_table(path).await.unwrap();
+    let mut bounded_reader = deltalake::open_table(path).await.unwrap();
+
+    for _ in 0..3 {
+        fs_common::commit_add(&mut writer, &fs_common::add(0)).await;
+    }
+    deltalake::checkpoints::create_checkpoint(&writer)
+        .await
+        .unwrap();
+    fs_common::commit_add(&mut writer, &fs_common::add(0)).await;
+    for version in 1..=2 {
+        let commit = tmp_dir
+            .path()
+            .join("_delta_log")
+            .join(format!("{:020}.json", version));
+        std::fs::remove_file(commit).unwrap();
+    }
+
+    // the missing commits are skipped by loading the checkpoint of version 3
+    assert_eq!(reader.update_incremental_to(None).await.unwrap(), 4);
+    assert_eq!(reader.version, 4);
+    assert_eq!(sorted_files(&reader), sorted_files(&writer));
+
+    // versions before the checkpoint cannot be reached anymore
+    assert!(matches!(
+        bounded_reader.update_incremental_to(Some(2)).await,
+        Err(DeltaTableError::InvalidVersion(1))
+    ));
+    assert_eq!(bounded_reader.version, 0);
+    assert_eq!(
+        bounded_reader.update_incremental_to(Some(3)).await.unwrap(),
+        3
+    );
+    assert_eq!(bounded_reader.get_files().len(), 3);
+}
+
+#[tokio::test]
+async fn update_incremental_with_file_removed_and_added_again() {
+    let tmp_dir = tempdir::TempDir::new("update_incremental_test").unwrap();
+    let path = tmp_dir.path().to_str().unwrap();
+    let mut writer = fs_common::create_table(path, None).await;
+    let add = fs_common::add(0);
+    fs_common::commit_add(&mut writer, &add).await;
+    let mut reader = deltalake::open_table(path).await.unwrap();
+
+    let remove = Remove {
+        path: add.path.clone(),
+        deletion_timestamp: Some(0),
+        data_change: true,
+        extended_file_metadata: None,
+        partition_values: None,
+        size: None,
+        tags: None,
+    };
+    fs_common::commit_actions(&mut writer, vec![Action::remove(remove)]).await;
+    fs_common::commit_add(&mut writer, &add).await;
+
+    // both commits are read by a single update and the file is still only listed once
+    assert_eq!(reader.update_incremental_to(None).await.unwrap(), 2);
+    assert_eq!(reader.version, 3);
+    assert_eq!(sorted_files(&reader), vec![add.path.clone()]);
+    assert_eq!(sorted_files(&reader), sorted_files(&writer));
+}
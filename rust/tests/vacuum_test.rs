@@ -0,0 +1,138 @@
+extern crate deltalake;
+
+use deltalake::action::{Action, Remove};
+use deltalake::{
+    DeltaTableError, OperationControl, ProgressCallback, ProgressEvent, ProgressPhase,
+    VacuumOptions,
+};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+#[allow(dead_code)]
+mod fs_common;
+
+#[derive(Default)]
+struct RecordingCallback {
+    events: Mutex<Vec<ProgressEvent>>,
+}
+
+impl ProgressCallback for RecordingCallback {
+    fn on_progress(&self, event: &ProgressEvent) {
+        self.events.lock().unwrap().push(*event);
+    }
+}
+
+fn write_file(dir: &Path, path: &str, size: usize) {
+    let path = dir.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, vec![0u8; size]).unwrap();
+}
+
+fn remove(path: &str) -> Action {
+    Action::remove(Remove {
+        path: path.to_string(),
+        deletion_timestamp: Some(0),
+        data_change: true,
+        extended_file_metadata: None,
+        partition_values: None,
+        size: None,
+        tags: None,
+    })
+}
+
+#[tokio::test]
+async fn vacuum_with_options() {
+    let tmp_dir = tempdir::TempDir::new("vacuum_test").unwrap();
+    let dir = tmp_dir.path();
+    let mut table = fs_common::create_table(dir.to_str().unwrap(), None).await;
+
+    let files = [
+        ("removed-1.parquet", 10),
+        ("removed-2.parquet", 20),
+        ("active.parquet", 40),
+        ("_change_data/cdc.parquet", 80),
+    ];
+    for (path, size) in files {
+        write_file(dir, path, size);
+        let mut add = fs_common::add(0);
+        add.path = path.to_string();
+        add.size = size as i64;
+        fs_common::commit_add(&mut table, &add).await;
+    }
+    // tombstones deleted long ago, including one of the change data directory
+    fs_common::commit_actions(
+        &mut table,
+        vec![
+            remove("removed-1.parquet"),
+            remove("removed-2.parquet"),
+            remove("_change_data/cdc.parquet"),
+        ],
+    )
+    .await;
+
+    let options = VacuumOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+    let control = OperationControl::default();
+    let mut metrics = table.vacuum_with_options(&options, &control).await.unwrap();
+    metrics.files_deleted.sort();
+    let expected: Vec<String> = ["removed-1.parquet", "removed-2.parquet"]
+        .iter()
+        .map(|path| dir.join(path).to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(metrics.files_deleted, expected);
+    assert_eq!(metrics.bytes_reclaimed, 30);
+    assert_eq!(metrics.referenced_files_skipped, 1);
+    assert!(dir.join("removed-1.parquet").exists());
+
+    assert!(matches!(
+        table
+            .vacuum_with_options(
+                &VacuumOptions {
+                    retention_hours: Some(0),
+                    ..options.clone()
+                },
+                &control,
+            )
+            .await,
+        Err(DeltaTableError::InvalidVacuumRetentionPeriod)
+    ));
+    let options = VacuumOptions {
+        retention_hours: Some(0),
+        enforce_retention_duration: false,
+        ..options
+    };
+    assert_eq!(
+        table
+            .vacuum_with_options(&options, &control)
+            .await
+            .unwrap()
+            .files_deleted
+            .len(),
+        2
+    );
+
+    let callback = Arc::new(RecordingCallback::default());
+    let control = OperationControl::default().with_progress_callback(callback.clone());
+    let options = VacuumOptions {
+        dry_run: false,
+        max_concurrent_deletes: 2,
+        ..options
+    };
+    let metrics = table.vacuum_with_options(&options, &control).await.unwrap();
+    assert_eq!(metrics.files_deleted.len(), 2);
+    assert_eq!(metrics.bytes_reclaimed, 30);
+    assert!(!dir.join("removed-1.parquet").exists());
+    assert!(!dir.join("removed-2.parquet").exists());
+    assert!(dir.join("active.parquet").exists());
+    assert!(dir.join("_change_data/cdc.parquet").exists());
+
+    let events = callback.events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events
+        .iter()
+        .all(|event| event.phase == ProgressPhase::DeleteFiles && event.total == 2));
+    assert_eq!(events[1].done, 2);
+    assert_eq!(events[1].bytes, Some(30));
+}
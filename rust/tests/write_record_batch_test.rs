@@ -0,0 +1,583 @@
+extern crate deltalake;
+
+use arrow::array::{Float64Array, Int32Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use deltalake::action::{Protocol, SaveMode};
+use deltalake::writer::{write_batches, write_batches_to_new_table, StreamingRecordBatchWriter};
+use serde_json::json;
+use std::sync::Arc;
+
+#[allow(dead_code)]
+mod fs_common;
+
+async fn create_partitioned_table(path: &str) -> deltalake::DeltaTable {
+    fs_common::create_table_from_json(
+        path,
+        json!({
+            "type": "struct",
+            "fields": [
+                {"name":"id","type":"long","metadata":{},"nullable":true},
+                {"name":"value","type":"double","metadata":{},"nullable":true},
+                {"name":"modified","type":"string","metadata":{},"nullable":true},
+            ]
+        }),
+        vec!["modified"],
+        json!({}),
+    )
+    .await
+}
+
+/// Paths of the parquet data files below `dir`, committed or not
+fn data_files(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() && !path.ends_with("_delta_log") {
+            files.extend(data_files(&path));
+        } else if path.extension().map_or(false, |ext| ext == "parquet") {
+            files.push(path);
+        }
+    }
+    files
+}
+
+fn sample_batch(
+    ids: Vec<i32>,
+    values: Vec<Option<f64>>,
+    modified: Vec<Option<&str>>,
+) -> RecordBatch {
+    // id is written as int32 to check that columns are cast to the table schema
+    let schema = ArrowSchema::new(vec![
+        Field::new("id", DataType::Int32, true),
+        Field::new("value", DataType::Float64, true),
+        Field::new("modified", DataType::Utf8, true),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int32Array::from(ids)),
+            Arc::new(Float64Array::from(values)),
+            Arc::new(StringArray::from(modified)),
+        ],
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn write_partitioned_batches_with_stats() {
+    let mut table = create_partitioned_table("./tests/data/write_record_batches").await;
+    let batch = sample_batch(
+        vec![1, 2, 3, 4],
+        vec![Some(1.5), None, Some(-2.0), Some(f64::NAN)],
+        vec![
+            Some("2021-02-01"),
+            Some("2021-02-01"),
+            Some("2021-02-02"),
+            None,
+        ],
+    );
+
+    let mut commit_info = serde_json::Map::new();
+    commit_info.insert("userName".to_string(), json!("test"));
+    let metrics = write_batches(
+        &mut table,
+        &[batch],
+        SaveMode::Append,
+        Some(commit_info),
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(metrics.version, 1);
+    assert_eq!(table.version, 1);
+
+    let mut files = table.get_state().files().clone();
+    files.sort_by_key(|add| add.partition_values["modified"].clone());
+    assert_eq!(files.len(), 3);
+
+    assert_eq!(files[0].partition_values["modified"], None);
+    assert!(files[0]
+        .path
+        .starts_with("modified=__HIVE_DEFAULT_PARTITION__/"));
+    let stats = files[0].get_stats().unwrap().unwrap();
+    assert_eq!(stats.num_records, 1);
+    // NaN cannot be used as a bound
+    assert!(!stats.min_values.contains_key("value"));
+
+    assert_eq!(
+        files[1].partition_values["modified"],
+        Some("2021-02-01".to_string())
+    );
+    let stats = files[1].get_stats().unwrap().unwrap();
+    assert_eq!(stats.num_records, 2);
+    assert_eq!(stats.min_values["id"].as_value(), Some(&json!(1)));
+    assert_eq!(stats.max_values["id"].as_value(), Some(&json!(2)));
+    assert_eq!(stats.min_values["value"].as_value(), Some(&json!(1.5)));
+    assert_eq!(stats.null_count["value"].as_value(), Some(1));
+    // partition columns are not stored in the data files
+    assert!(!stats.null_count.contains_key("modified"));
+
+    assert_eq!(metrics.num_added_files, 3);
+    assert_eq!(metrics.num_removed_files, 0);
+    assert_eq!(
+        metrics.num_added_bytes,
+        files.iter().map(|add| add.size).sum::<i64>()
+    );
+    assert_eq!(metrics.num_output_rows, 4);
+    assert_eq!(metrics.num_partitions, 3);
+
+    let history = table.history(Some(1)).unwrap();
+    assert_eq!(history[0]["operation"], json!("WRITE"));
+    assert_eq!(history[0]["userName"], json!("test"));
+    assert_eq!(history[0]["operationMetrics"], metrics.operation_metrics());
+}
+
+#[tokio::test]
+async fn write_batches_with_save_modes() {
+    let mut table = create_partitioned_table("./tests/data/write_record_batches_modes").await;
+    let batch = sample_batch(vec![1], vec![Some(1.0)], vec![Some("2021-02-01")]);
+
+    write_batches(
+        &mut table,
+        &[batch.clone()],
+        SaveMode::Append,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    write_batches(
+        &mut table,
+        &[batch.clone()],
+        SaveMode::Append,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(table.get_files().len(), 2);
+
+    assert!(matches!(
+        write_batches(
+            &mut table,
+            &[batch.clone()],
+            SaveMode::ErrorIfExists,
+            None,
+            None,
+            None
+        )
+        .await,
+        Err(deltalake::DeltaTableError::Generic(_))
+    ));
+    assert_eq!(
+        write_batches(
+            &mut table,
+            &[batch.clone()],
+            SaveMode::Ignore,
+            None,
+            None,
+            None
+        )
+        .await
+        .unwrap(),
+        None
+    );
+    assert_eq!(table.version, 2);
+
+    let removed_bytes: i64 = table.get_state().files().iter().map(|add| add.size).sum();
+    let metrics = write_batches(&mut table, &[batch], SaveMode::Overwrite, None, None, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(table.version, 3);
+    assert_eq!(table.get_files().len(), 1);
+    assert_eq!(table.get_tombstones().count(), 2);
+    assert_eq!(metrics.num_added_files, 1);
+    assert_eq!(metrics.num_removed_files, 2);
+    assert_eq!(metrics.num_removed_bytes, removed_bytes);
+}
+
+#[tokio::test]
+async fn write_batch_with_mismatched_schema() {
+    let mut table = create_partitioned_table("./tests/data/write_record_batches_mismatch").await;
+    let schema = ArrowSchema::new(vec![
+        Field::new("id", DataType::Int32, true),
+        Field::new("other", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int32Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["a"])),
+        ],
+    )
+    .unwrap();
+
+    let result = write_batches(&mut table, &[batch], SaveMode::Append, None, None, None).await;
+    assert!(matches!(
+        result,
+        Err(deltalake::DeltaTableError::SchemaMismatch { msg }) if msg.contains("other")
+    ));
+    assert_eq!(table.version, 0);
+}
+
+fn new_table_metadata(partition_columns: Vec<&str>) -> deltalake::DeltaTableMetaData {
+    let schema = serde_json::from_value(json!({
+        "type": "struct",
+        "fields": [
+            {"name":"id","type":"long","metadata":{},"nullable":true},
+            {"name":"value","type":"double","metadata":{},"nullable":true},
+            {"name":"modified","type":"string","metadata":{},"nullable":true},
+        ]
+    }))
+    .unwrap();
+    deltalake::DeltaTableMetaData::new(
+        None,
+        None,
+        None,
+        schema,
+        partition_columns.into_iter().map(String::from).collect(),
+        std::collections::HashMap::new(),
+    )
+}
+
+fn unloaded_table(path: &str) -> deltalake::DeltaTable {
+    deltalake::DeltaTable::new(
+        path,
+        deltalake::storage::get_backend_for_uri(path).unwrap(),
+        deltalake::DeltaTableConfig::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn write_batches_to_new_table_in_single_commit() {
+    let tmp_dir = tempdir::TempDir::new("write_record_batch_test").unwrap();
+    let path = tmp_dir.path().to_str().unwrap();
+    let protocol = Protocol {
+        min_reader_version: 1,
+        min_writer_version: 2,
+    };
+    let batch = sample_batch(
+        vec![1, 2],
+        vec![Some(1.0), None],
+        vec![Some("2021-02-01"), Some("2021-02-02")],
+    );
+
+    let mut table = unloaded_table(path);
+    let metrics = write_batches_to_new_table(
+        &mut table,
+        new_table_metadata(vec!["modified"]),
+        protocol.clone(),
+        &[batch.clone()],
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(metrics.version, 0);
+    assert_eq!(metrics.num_added_files, 2);
+    assert_eq!(table.version, 0);
+    assert_eq!(table.get_files().len(), 2);
+    assert_eq!(
+        table.get_metadata().unwrap().partition_columns,
+        ["modified"]
+    );
+
+    // a writer that lost the race to create the table appends to it
+    let mut concurrent = unloaded_table(path);
+    let metrics = write_batches_to_new_table(
+        &mut concurrent,
+        new_table_metadata(vec!["modified"]),
+        protocol.clone(),
+        &[batch.clone()],
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(metrics.version, 1);
+    assert_eq!(concurrent.get_files().len(), 4);
+
+    // unless the table was created with another schema, then its files are deleted again
+    let mut concurrent = unloaded_table(path);
+    let result = write_batches_to_new_table(
+        &mut concurrent,
+        new_table_metadata(vec![]),
+        protocol,
+        &[batch],
+        None,
+        None,
+        None,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(deltalake::DeltaTableError::SchemaMismatch { .. })
+    ));
+    assert_eq!(data_files(tmp_dir.path()).len(), 4);
+    assert_eq!(
+        std::fs::read_dir(tmp_dir.path().join("_delta_log"))
+            .unwrap()
+            .count(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn write_to_table_with_column_mapping() {
+    let mut config = std::collections::HashMap::new();
+    config.insert(
+        "delta.columnMapping.mode".to_string(),
+        Some("name".to_string()),
+    );
+    let mut table = fs_common::create_table(
+        "./tests/data/write_record_batches_column_mapping",
+        Some(config),
+    )
+    .await;
+    let schema = ArrowSchema::new(vec![Field::new("id", DataType::Int32, true)]);
+    let batch =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Int32Array::from(vec![1]))]).unwrap();
+
+    // the parquet columns would have to be named by their physical names
+    let result = write_batches(&mut table, &[batch], SaveMode::Append, None, None, None).await;
+    assert!(matches!(
+        result,
+        Err(deltalake::DeltaTableError::ColumnMappingNotSupported { mode }) if mode == "name"
+    ));
+    assert!(matches!(
+        StreamingRecordBatchWriter::for_table(&table),
+        Err(deltalake::DeltaTableError::ColumnMappingNotSupported { .. })
+    ));
+    assert_eq!(table.version, 0);
+}
+
+#[tokio::test]
+async fn streaming_writer_rolls_files_per_partition() {
+    let path = "./tests/data/write_streaming_batches";
+    let mut table = create_partitioned_table(path).await;
+    let batch = sample_batch(
+        vec![1, 2, 3],
+        vec![Some(1.0), Some(2.0), Some(3.0)],
+        vec![Some("2021-02-01"), Some("2021-02-02"), None],
+    );
+
+    // every file is uploaded as soon as a batch was written to it
+    let mut writer = StreamingRecordBatchWriter::for_table(&table)
+        .unwrap()
+        .with_target_file_size(1);
+    writer.write(&table, &batch).await.unwrap();
+    writer.write(&table, &batch).await.unwrap();
+    assert_eq!(writer.count(), 6);
+    assert_eq!(data_files(std::path::Path::new(path)).len(), 6);
+    assert_eq!(table.get_files().len(), 0);
+
+    let metrics = writer.close(&mut table, None, None).await.unwrap();
+    assert_eq!(metrics.version, 1);
+    assert_eq!(table.version, 1);
+    assert_eq!(table.get_files().len(), 6);
+    assert_eq!(metrics.num_added_files, 6);
+    assert_eq!(metrics.num_output_rows, 6);
+    assert_eq!(metrics.num_partitions, 3);
+    let history = table.history(None).unwrap();
+    assert_eq!(history[0]["operationMetrics"], metrics.operation_metrics());
+
+    // with the default target file size, one file is written per partition
+    let mut writer = StreamingRecordBatchWriter::for_table(&table).unwrap();
+    writer.write(&table, &batch).await.unwrap();
+    writer.write(&table, &batch).await.unwrap();
+    let metrics = writer.close(&mut table, None, None).await.unwrap();
+    assert_eq!(metrics.version, 2);
+    assert_eq!(metrics.num_added_files, 3);
+    assert_eq!(metrics.num_output_rows, 6);
+    assert_eq!(table.get_files().len(), 9);
+}
+
+#[tokio::test]
+async fn streaming_writer_abort_deletes_uploaded_files() {
+    let path = "./tests/data/write_streaming_batches_abort";
+    let mut table = create_partitioned_table(path).await;
+    let batch = sample_batch(
+        vec![1, 2],
+        vec![Some(1.0), None],
+        vec![Some("a"), Some("b")],
+    );
+
+    let mut writer = StreamingRecordBatchWriter::for_table(&table)
+        .unwrap()
+        .with_target_file_size(1);
+    writer.write(&table, &batch).await.unwrap();
+    assert_eq!(data_files(std::path::Path::new(path)).len(), 2);
+
+    let mismatched = RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(vec![Field::new(
+            "other",
+            DataType::Utf8,
+            true,
+        )])),
+        vec![Arc::new(StringArray::from(vec!["a"]))],
+    )
+    .unwrap();
+    assert!(matches!(
+        writer.write(&table, &mismatched).await,
+        Err(deltalake::DeltaTableError::SchemaMismatch { .. })
+    ));
+    writer.abort(&table).await.unwrap();
+    assert!(data_files(std::path::Path::new(path)).is_empty());
+
+    table.update().await.unwrap();
+    assert_eq!(table.version, 0);
+}
+
+#[tokio::test]
+async fn streaming_writer_creates_new_table() {
+    let tmp_dir = tempdir::TempDir::new("streaming_writer_new_table").unwrap();
+    let path = tmp_dir.path().to_str().unwrap();
+    let protocol = Protocol {
+        min_reader_version: 1,
+        min_writer_version: 2,
+    };
+    let batch = sample_batch(
+        vec![1, 2],
+        vec![Some(1.0), None],
+        vec![Some("2021-02-01"), Some("2021-02-02")],
+    );
+
+    let mut table = unloaded_table(path);
+    let mut writer =
+        StreamingRecordBatchWriter::for_new_table(new_table_metadata(vec!["modified"]), protocol)
+            .unwrap();
+    writer.write(&table, &batch).await.unwrap();
+    writer.write(&table, &batch).await.unwrap();
+    let metrics = writer.close(&mut table, None, None).await.unwrap();
+    assert_eq!(metrics.version, 0);
+    assert_eq!(metrics.num_added_files, 2);
+    assert_eq!(metrics.num_output_rows, 4);
+    assert_eq!(table.version, 0);
+    assert_eq!(table.get_files().len(), 2);
+    assert_eq!(
+        table.get_metadata().unwrap().partition_columns,
+        ["modified"]
+    );
+}
+
+#[tokio::test]
+async fn streaming_writer_overwrites_partitions_matching_predicate() {
+    let path = "./tests/data/write_streaming_overwrite";
+    let mut table = create_partitioned_table(path).await;
+    let batch = sample_batch(
+        vec![1, 2, 3],
+        vec![Some(1.0), Some(2.0), Some(3.0)],
+        vec![Some("a"), Some("b"), None],
+    );
+    let mut writer = StreamingRecordBatchWriter::for_table(&table).unwrap();
+    writer.write(&table, &batch).await.unwrap();
+    writer.close(&mut table, None, None).await.unwrap();
+    assert_eq!(table.get_files().len(), 3);
+
+    // the written rows have to match the predicate
+    let mut writer = StreamingRecordBatchWriter::for_table(&table)
+        .unwrap()
+        .with_overwrite(Some("modified = 'a'".parse().unwrap()));
+    let other_partition = sample_batch(vec![4], vec![None], vec![Some("b")]);
+    writer.write(&table, &other_partition).await.unwrap();
+    assert!(matches!(
+        writer.close(&mut table, None, None).await,
+        Err(deltalake::DeltaTableError::InvalidPredicate { .. })
+    ));
+    assert_eq!(table.version, 1);
+    assert_eq!(data_files(std::path::Path::new(path)).len(), 3);
+
+    // only partition columns can be used
+    let writer = StreamingRecordBatchWriter::for_table(&table)
+        .unwrap()
+        .with_overwrite(Some("id > 1".parse().unwrap()));
+    assert!(matches!(
+        writer.close(&mut table, None, None).await,
+        Err(deltalake::DeltaTableError::InvalidPredicate { .. })
+    ));
+
+    let mut writer = StreamingRecordBatchWriter::for_table(&table)
+        .unwrap()
+        .with_overwrite(Some("MODIFIED = 'a' OR modified IS NULL".parse().unwrap()));
+    let replacement = sample_batch(vec![4, 5], vec![Some(4.0), None], vec![Some("a"), None]);
+    writer.write(&table, &replacement).await.unwrap();
+    let metrics = writer.close(&mut table, None, None).await.unwrap();
+    assert_eq!(metrics.version, 2);
+    assert_eq!(metrics.num_added_files, 2);
+    assert_eq!(metrics.num_removed_files, 2);
+    let files = table.get_files();
+    assert_eq!(files.len(), 3);
+    assert_eq!(
+        files
+            .iter()
+            .filter(|file| file.starts_with("modified=b/"))
+            .count(),
+        1
+    );
+    let history = table.history(Some(1)).unwrap();
+    assert_eq!(
+        history[0]["operationParameters"]["predicate"],
+        json!("(MODIFIED = 'a' OR modified IS NULL)")
+    );
+
+    // without a predicate all files are replaced
+    let mut writer = StreamingRecordBatchWriter::for_table(&table)
+        .unwrap()
+        .with_overwrite(None);
+    writer.write(&table, &other_partition).await.unwrap();
+    let metrics = writer.close(&mut table, None, None).await.unwrap();
+    assert_eq!(metrics.num_removed_files, 3);
+    assert_eq!(table.get_files().len(), 1);
+}
+
+#[tokio::test]
+async fn streaming_writer_changes_schema() {
+    let path = "./tests/data/write_streaming_schema_change";
+    let mut table = create_partitioned_table(path).await;
+
+    let mut metadata = table.get_metadata().unwrap().clone();
+    let mut fields = metadata.schema.get_fields().clone();
+    fields.push(deltalake::SchemaField::new(
+        "comment".to_string(),
+        deltalake::SchemaDataType::primitive("string".to_string()),
+        true,
+        std::collections::HashMap::new(),
+    ));
+    metadata.schema = deltalake::Schema::new(fields);
+
+    let schema = ArrowSchema::new(vec![
+        Field::new("id", DataType::Int32, true),
+        Field::new("value", DataType::Float64, true),
+        Field::new("modified", DataType::Utf8, true),
+        Field::new("comment", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int32Array::from(vec![1])),
+            Arc::new(Float64Array::from(vec![Some(1.0)])),
+            Arc::new(StringArray::from(vec![Some("a")])),
+            Arc::new(StringArray::from(vec![Some("new")])),
+        ],
+    )
+    .unwrap();
+
+    let mut writer = StreamingRecordBatchWriter::for_table(&table)
+        .unwrap()
+        .with_metadata(metadata.clone())
+        .unwrap();
+    writer.write(&table, &batch).await.unwrap();
+    writer.close(&mut table, None, None).await.unwrap();
+    assert_eq!(table.version, 1);
+    assert_eq!(table.get_metadata().unwrap().schema, metadata.schema);
+    assert_eq!(table.get_files().len(), 1);
+}